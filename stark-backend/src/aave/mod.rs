@@ -0,0 +1,389 @@
+//! Aave V3 positions
+//!
+//! Reads account totals (`getUserAccountData`) and per-reserve supplied and
+//! borrowed balances from the Aave V3 Pool on each supported network, priced
+//! with the Aave oracle. The `aave` tool reports them, `aave_health_factor`
+//! event triggers watch the health factor, and a fired watcher can queue a
+//! repay within [`RepayPolicy`] (see `repay`).
+
+mod repay;
+
+pub use repay::{queue_auto_repay, AutoRepay, RepayPolicy};
+
+use crate::approvals::decode_symbol;
+use crate::swap;
+use crate::web3::multicall::{self, Call3};
+use crate::x402::X402EvmRpc;
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::id;
+use serde::Serialize;
+
+/// Aave V3 Pool per network
+pub const AAVE_V3_POOLS: &[(&str, &str)] = &[
+    ("base", "0xA238Dd80C259a72e81d7e4664a9801593F98d1c5"),
+    ("mainnet", "0x87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2"),
+    ("polygon", "0x794a61358D6845594F94dc1DB02A252b5b4814aD"),
+];
+
+/// Account totals are in the oracle base currency (USD, 8 decimals)
+const BASE_CURRENCY_DECIMALS: u8 = 8;
+/// Health factor is a WAD (18 decimals)
+const HEALTH_FACTOR_DECIMALS: u8 = 18;
+/// Interest rates are RAYs (27 decimals)
+const RAY_DECIMALS: u8 = 27;
+/// Static words returned by `getReserveData` (ReserveDataLegacy)
+const RESERVE_DATA_WORDS: usize = 15;
+
+pub fn pool_address(network: &str) -> Option<Address> {
+    AAVE_V3_POOLS
+        .iter()
+        .find(|(n, _)| *n == network)
+        .and_then(|(_, address)| address.parse().ok())
+}
+
+/// Networks with an Aave V3 Pool
+pub fn networks() -> impl Iterator<Item = &'static str> {
+    AAVE_V3_POOLS.iter().map(|(network, _)| *network)
+}
+
+/// Raw amount as f64 in whole units (for display and USD maths only)
+fn to_f64(amount: U256, decimals: u8) -> f64 {
+    swap::format_units(amount, decimals).parse().unwrap_or(0.0)
+}
+
+/// Account totals from `getUserAccountData`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountData {
+    pub collateral_usd: f64,
+    pub debt_usd: f64,
+    pub available_borrows_usd: f64,
+    /// Weighted liquidation threshold of the collateral, in basis points
+    pub liquidation_threshold_bps: u64,
+    pub ltv_bps: u64,
+    /// None when there is no debt (Aave reports max uint256)
+    pub health_factor: Option<f64>,
+}
+
+impl AccountData {
+    pub fn has_position(&self) -> bool {
+        self.collateral_usd > 0.0 || self.debt_usd > 0.0
+    }
+
+    /// Health factor after `repaid_usd` of the debt is repaid (None = no debt left)
+    pub fn health_factor_after_repay(&self, repaid_usd: f64) -> Option<f64> {
+        let debt = self.debt_usd - repaid_usd;
+        (debt > 0.0).then(|| self.collateral_usd * self.liquidation_threshold_bps as f64 / 10_000.0 / debt)
+    }
+}
+
+fn decode_account_data(data: &[u8]) -> Result<AccountData, String> {
+    let words: Vec<U256> = abi::decode(&vec![ParamType::Uint(256); 6], data)
+        .map_err(|e| format!("Invalid getUserAccountData response: {}", e))?
+        .into_iter()
+        .filter_map(|t| t.into_uint())
+        .collect();
+    if words.len() != 6 {
+        return Err("Invalid getUserAccountData response".to_string());
+    }
+    let debt = words[1];
+    Ok(AccountData {
+        collateral_usd: to_f64(words[0], BASE_CURRENCY_DECIMALS),
+        debt_usd: to_f64(debt, BASE_CURRENCY_DECIMALS),
+        available_borrows_usd: to_f64(words[2], BASE_CURRENCY_DECIMALS),
+        liquidation_threshold_bps: words[3].low_u64(),
+        ltv_bps: words[4].low_u64(),
+        health_factor: (!debt.is_zero()).then(|| to_f64(words[5], HEALTH_FACTOR_DECIMALS)),
+    })
+}
+
+fn account_data_call(pool: Address, user: Address) -> Call3 {
+    let mut call_data = id("getUserAccountData(address)").to_vec();
+    call_data.extend(abi::encode(&[Token::Address(user)]));
+    Call3 {
+        target: pool,
+        allow_failure: false,
+        call_data,
+    }
+}
+
+/// Read the account totals of `user` from `pool`
+pub async fn get_account_data(rpc: &X402EvmRpc, pool: Address, user: Address) -> Result<AccountData, String> {
+    let data = rpc.call(pool, &account_data_call(pool, user).call_data).await?;
+    decode_account_data(&data)
+}
+
+/// Token addresses and rates of one reserve (from `getReserveData`)
+#[derive(Debug, Clone, PartialEq)]
+struct ReserveInfo {
+    asset: Address,
+    decimals: u8,
+    a_token: Address,
+    variable_debt_token: Address,
+    liquidity_rate: U256,
+    variable_borrow_rate: U256,
+}
+
+fn decode_reserve_data(asset: Address, data: &[u8]) -> Option<ReserveInfo> {
+    if data.len() < RESERVE_DATA_WORDS * 32 {
+        return None;
+    }
+    let word = |i: usize| &data[i * 32..(i + 1) * 32];
+    let configuration = U256::from_big_endian(word(0));
+    Some(ReserveInfo {
+        asset,
+        // Reserve configuration bits 48-55
+        decimals: ((configuration >> 48) & U256::from(0xff)).low_u32() as u8,
+        liquidity_rate: U256::from_big_endian(word(2)),
+        variable_borrow_rate: U256::from_big_endian(word(4)),
+        a_token: Address::from_slice(&word(8)[12..]),
+        variable_debt_token: Address::from_slice(&word(10)[12..]),
+    })
+}
+
+/// Supplied and borrowed balance of one reserve
+#[derive(Debug, Clone, Serialize)]
+pub struct ReservePosition {
+    pub asset: String,
+    pub symbol: Option<String>,
+    pub decimals: u8,
+    pub supplied: String,
+    pub borrowed: String,
+    pub price_usd: f64,
+    pub supplied_usd: f64,
+    pub borrowed_usd: f64,
+    pub supply_apr_pct: f64,
+    pub variable_borrow_apr_pct: f64,
+    #[serde(skip)]
+    pub borrowed_raw: U256,
+}
+
+impl ReservePosition {
+    pub fn name(&self) -> &str {
+        self.symbol.as_deref().unwrap_or(&self.asset)
+    }
+}
+
+/// Aave position of one wallet on one network
+#[derive(Debug, Clone, Serialize)]
+pub struct Position {
+    pub network: String,
+    pub user: String,
+    pub account: AccountData,
+    pub reserves: Vec<ReservePosition>,
+}
+
+async fn call_address(rpc: &X402EvmRpc, to: Address, signature: &str) -> Result<Address, String> {
+    let data = rpc.call(to, &id(signature)).await?;
+    abi::decode(&[ParamType::Address], &data)
+        .ok()
+        .and_then(|t| t.into_iter().next())
+        .and_then(|t| t.into_address())
+        .ok_or_else(|| format!("Invalid {} response", signature))
+}
+
+async fn reserves_list(rpc: &X402EvmRpc, pool: Address) -> Result<Vec<Address>, String> {
+    let data = rpc.call(pool, &id("getReservesList()")).await?;
+    abi::decode(&[ParamType::Array(Box::new(ParamType::Address))], &data)
+        .ok()
+        .and_then(|t| t.into_iter().next())
+        .and_then(|t| t.into_array())
+        .map(|assets| assets.into_iter().filter_map(|a| a.into_address()).collect())
+        .ok_or_else(|| "Invalid getReservesList response".to_string())
+}
+
+/// USD prices (8 decimals) from the Aave oracle of `pool`, in the order of `assets`
+async fn oracle_prices(rpc: &X402EvmRpc, pool: Address, assets: &[Address]) -> Result<Vec<U256>, String> {
+    let provider = call_address(rpc, pool, "ADDRESSES_PROVIDER()").await?;
+    let oracle = call_address(rpc, provider, "getPriceOracle()").await?;
+    let mut call_data = id("getAssetsPrices(address[])").to_vec();
+    call_data.extend(abi::encode(&[Token::Array(
+        assets.iter().map(|a| Token::Address(*a)).collect(),
+    )]));
+    let data = rpc.call(oracle, &call_data).await?;
+    abi::decode(&[ParamType::Array(Box::new(ParamType::Uint(256)))], &data)
+        .ok()
+        .and_then(|t| t.into_iter().next())
+        .and_then(|t| t.into_array())
+        .map(|prices| prices.into_iter().filter_map(|p| p.into_uint()).collect::<Vec<_>>())
+        .filter(|prices| prices.len() == assets.len())
+        .ok_or_else(|| "Invalid getAssetsPrices response".to_string())
+}
+
+/// Read the account totals and every non-empty reserve position of `user` on `network`
+pub async fn get_position(rpc: &X402EvmRpc, network: &str, user: Address) -> Result<Position, String> {
+    let pool = pool_address(network).ok_or_else(|| format!("Aave V3 is not available on {}", network))?;
+    let assets = reserves_list(rpc, pool).await?;
+
+    let mut calls = vec![account_data_call(pool, user)];
+    calls.extend(assets.iter().map(|asset| {
+        let mut call_data = id("getReserveData(address)").to_vec();
+        call_data.extend(abi::encode(&[Token::Address(*asset)]));
+        Call3 {
+            target: pool,
+            allow_failure: true,
+            call_data,
+        }
+    }));
+    let results = multicall::aggregate3(rpc, &calls).await?;
+    let account = decode_account_data(&results[0].return_data)?;
+
+    let mut position = Position {
+        network: network.to_string(),
+        user: format!("{:?}", user),
+        account,
+        reserves: Vec::new(),
+    };
+    if !position.account.has_position() {
+        return Ok(position);
+    }
+
+    let reserves: Vec<ReserveInfo> = assets
+        .iter()
+        .zip(&results[1..])
+        .filter(|(_, r)| r.success)
+        .filter_map(|(asset, r)| decode_reserve_data(*asset, &r.return_data))
+        .collect();
+    let balance_calls: Vec<Call3> = reserves
+        .iter()
+        .flat_map(|r| {
+            [
+                multicall::erc20_balance_call(r.a_token, user),
+                multicall::erc20_balance_call(r.variable_debt_token, user),
+            ]
+        })
+        .collect();
+    let balances = multicall::aggregate3(rpc, &balance_calls).await?;
+
+    let held: Vec<(&ReserveInfo, U256, U256)> = reserves
+        .iter()
+        .zip(balances.chunks(2))
+        .map(|(r, b)| {
+            let supplied = b[0].as_u256().unwrap_or_default();
+            let borrowed = b.get(1).and_then(|b| b.as_u256()).unwrap_or_default();
+            (r, supplied, borrowed)
+        })
+        .filter(|(_, supplied, borrowed)| !supplied.is_zero() || !borrowed.is_zero())
+        .collect();
+    if held.is_empty() {
+        return Ok(position);
+    }
+
+    let held_assets: Vec<Address> = held.iter().map(|(r, _, _)| r.asset).collect();
+    let symbol_calls: Vec<Call3> = held_assets
+        .iter()
+        .map(|a| multicall::erc20_getter_call(*a, "symbol()"))
+        .collect();
+    let symbols = multicall::aggregate3(rpc, &symbol_calls).await?;
+    let prices = match oracle_prices(rpc, pool, &held_assets).await {
+        Ok(prices) => prices,
+        Err(e) => {
+            log::warn!("[aave] Oracle prices unavailable on {}: {}", network, e);
+            vec![U256::zero(); held_assets.len()]
+        }
+    };
+
+    for (((reserve, supplied, borrowed), symbol), price) in held.into_iter().zip(&symbols).zip(prices) {
+        let price_usd = to_f64(price, BASE_CURRENCY_DECIMALS);
+        let supplied_units = to_f64(supplied, reserve.decimals);
+        let borrowed_units = to_f64(borrowed, reserve.decimals);
+        position.reserves.push(ReservePosition {
+            asset: format!("{:?}", reserve.asset),
+            symbol: decode_symbol(symbol),
+            decimals: reserve.decimals,
+            supplied: swap::format_units(supplied, reserve.decimals),
+            borrowed: swap::format_units(borrowed, reserve.decimals),
+            price_usd,
+            supplied_usd: supplied_units * price_usd,
+            borrowed_usd: borrowed_units * price_usd,
+            supply_apr_pct: to_f64(reserve.liquidity_rate, RAY_DECIMALS) * 100.0,
+            variable_borrow_apr_pct: to_f64(reserve.variable_borrow_rate, RAY_DECIMALS) * 100.0,
+            borrowed_raw: borrowed,
+        });
+    }
+    position
+        .reserves
+        .sort_by(|a, b| (b.supplied_usd + b.borrowed_usd).total_cmp(&(a.supplied_usd + a.borrowed_usd)));
+    Ok(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(value: U256) -> Vec<u8> {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        bytes.to_vec()
+    }
+
+    #[test]
+    fn test_decode_account_data() {
+        let data = abi::encode(&[
+            Token::Uint(U256::from(1_000_000_000_000u64)), // $10,000 collateral
+            Token::Uint(U256::from(500_000_000_000u64)),   // $5,000 debt
+            Token::Uint(U256::from(250_000_000_000u64)),
+            Token::Uint(U256::from(8_250)),
+            Token::Uint(U256::from(8_000)),
+            Token::Uint(U256::exp10(18) * 165 / 100),
+        ]);
+        let account = decode_account_data(&data).unwrap();
+        assert_eq!(account.collateral_usd, 10_000.0);
+        assert_eq!(account.debt_usd, 5_000.0);
+        assert_eq!(account.liquidation_threshold_bps, 8_250);
+        assert_eq!(account.health_factor, Some(1.65));
+        assert_eq!(account.health_factor_after_repay(875.0), Some(2.0));
+        assert_eq!(account.health_factor_after_repay(5_000.0), None);
+
+        // No debt: Aave reports max uint256
+        let data = abi::encode(&[
+            Token::Uint(U256::from(1_000_000_000_000u64)),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::from(8_250)),
+            Token::Uint(U256::from(8_000)),
+            Token::Uint(U256::MAX),
+        ]);
+        assert_eq!(decode_account_data(&data).unwrap().health_factor, None);
+        assert!(decode_account_data(&data[..64]).is_err());
+    }
+
+    #[test]
+    fn test_decode_reserve_data() {
+        let asset = Address::repeat_byte(0x01);
+        let a_token = Address::repeat_byte(0xaa);
+        let debt_token = Address::repeat_byte(0xdd);
+        // decimals 6 in bits 48-55, LTV/threshold bits below
+        let configuration = (U256::from(6) << 48) | U256::from(0x2008_1f40u64);
+        let mut data = word(configuration);
+        data.extend(word(U256::exp10(27)));
+        data.extend(word(U256::exp10(27) * 3 / 100)); // 3% supply
+        data.extend(word(U256::exp10(27)));
+        data.extend(word(U256::exp10(27) * 5 / 100)); // 5% variable borrow
+        data.extend(word(U256::zero()));
+        data.extend(word(U256::zero()));
+        data.extend(word(U256::zero()));
+        data.extend(abi::encode(&[Token::Address(a_token)]));
+        data.extend(word(U256::zero()));
+        data.extend(abi::encode(&[Token::Address(debt_token)]));
+        for _ in 11..RESERVE_DATA_WORDS {
+            data.extend(word(U256::zero()));
+        }
+
+        let reserve = decode_reserve_data(asset, &data).unwrap();
+        assert_eq!(reserve.decimals, 6);
+        assert_eq!(reserve.a_token, a_token);
+        assert_eq!(reserve.variable_debt_token, debt_token);
+        assert_eq!(to_f64(reserve.variable_borrow_rate, RAY_DECIMALS), 0.05);
+        assert!(decode_reserve_data(asset, &data[..320]).is_none());
+    }
+
+    #[test]
+    fn test_pool_addresses() {
+        assert_eq!(networks().count(), 3);
+        for network in networks() {
+            assert!(pool_address(network).is_some(), "{}", network);
+        }
+        assert!(pool_address("solana").is_none());
+    }
+}
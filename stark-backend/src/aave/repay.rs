@@ -0,0 +1,321 @@
+//! Automatic repays queued by Aave health-factor watchers.
+
+use super::{get_position, pool_address, to_f64, AccountData, ReservePosition};
+use crate::config;
+use crate::db::Database;
+use crate::tools::rpc_config::{resolve_rpc_config, ResolvedRpcConfig};
+use crate::tx_queue::TxQueueManager;
+use crate::wallet::WalletProvider;
+use crate::web3::multicall;
+use crate::web3::queue_signed;
+use crate::x402::X402EvmRpc;
+use chrono::{Duration, Utc};
+use ethers::abi::{self, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::id;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Preset the queued transaction is labelled with
+const REPAY_PRESET: &str = "aave_repay";
+/// Aave interest rate mode for variable-rate debt
+const VARIABLE_RATE_MODE: u64 = 2;
+
+/// Limits on repays the scheduler queues without a human in the loop
+#[derive(Debug, Clone)]
+pub struct RepayPolicy {
+    /// Maximum USD value of one repay
+    pub max_repay_usd: f64,
+    /// Maximum repays per rolling 24 hours
+    pub max_repays_per_day: i64,
+    /// Health factor a repay aims to restore
+    pub target_health_factor: f64,
+}
+
+impl RepayPolicy {
+    pub fn from_config() -> Self {
+        RepayPolicy {
+            max_repay_usd: config::aave_max_auto_repay_usd(),
+            max_repays_per_day: config::aave_max_auto_repays_per_day(),
+            target_health_factor: config::aave_target_health_factor(),
+        }
+    }
+
+    /// Check the daily limit given the repays already queued today
+    pub fn check(&self, repays_last_24h: i64) -> Result<(), String> {
+        if repays_last_24h >= self.max_repays_per_day {
+            return Err(format!(
+                "Daily automatic repay limit reached ({} per 24h)",
+                self.max_repays_per_day
+            ));
+        }
+        Ok(())
+    }
+
+    /// USD of debt to repay to bring the health factor back to the target
+    pub fn repay_needed_usd(&self, account: &AccountData) -> f64 {
+        let threshold = account.liquidation_threshold_bps as f64 / 10_000.0;
+        let max_debt = account.collateral_usd * threshold / self.target_health_factor;
+        (account.debt_usd - max_debt).max(0.0)
+    }
+}
+
+/// The repay chosen for a position
+#[derive(Debug, Clone, PartialEq)]
+struct RepayPlan {
+    asset: Address,
+    symbol: String,
+    decimals: u8,
+    amount: U256,
+    amount_usd: f64,
+}
+
+/// Pick the largest debt the wallet can repay and size the repay to
+/// `needed_usd`, capped by `max_usd`, the debt itself and what the wallet
+/// holds and has approved (`spendable`, keyed by asset).
+fn plan_repay(
+    reserves: &[ReservePosition],
+    spendable: &HashMap<String, U256>,
+    needed_usd: f64,
+    max_usd: f64,
+) -> Option<RepayPlan> {
+    let reserve = reserves
+        .iter()
+        .filter(|r| !r.borrowed_raw.is_zero() && r.price_usd > 0.0)
+        .filter(|r| spendable.get(&r.asset).is_some_and(|s| !s.is_zero()))
+        .max_by(|a, b| a.borrowed_usd.total_cmp(&b.borrowed_usd))?;
+
+    let usd = needed_usd.min(max_usd);
+    let units = format!("{:.*}", reserve.decimals as usize, usd / reserve.price_usd);
+    let wanted: U256 = ethers::utils::parse_units(units, reserve.decimals as u32).ok()?.into();
+    let amount = wanted.min(reserve.borrowed_raw).min(spendable[&reserve.asset]);
+    if amount.is_zero() {
+        return None;
+    }
+    Some(RepayPlan {
+        asset: reserve.asset.parse().ok()?,
+        symbol: reserve.name().to_string(),
+        decimals: reserve.decimals,
+        amount,
+        amount_usd: to_f64(amount, reserve.decimals) * reserve.price_usd,
+    })
+}
+
+/// Calldata for `repay(asset, amount, interestRateMode, onBehalfOf)` of variable-rate debt
+pub fn encode_repay(asset: Address, amount: U256, on_behalf_of: Address) -> Vec<u8> {
+    let mut data = id("repay(address,uint256,uint256,address)").to_vec();
+    data.extend(abi::encode(&[
+        Token::Address(asset),
+        Token::Uint(amount),
+        Token::Uint(U256::from(VARIABLE_RATE_MODE)),
+        Token::Address(on_behalf_of),
+    ]));
+    data
+}
+
+/// A repay queued for a fired watcher
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoRepay {
+    pub uuid: String,
+    pub network: String,
+    pub asset: String,
+    pub symbol: String,
+    pub amount: String,
+    pub amount_usd: f64,
+    pub health_factor_before: Option<f64>,
+    pub expected_health_factor: Option<f64>,
+    /// True when the policy, the wallet balance or the allowance kept the
+    /// repay below what the target health factor needs
+    pub partial: bool,
+}
+
+/// Queue a repay of the bot wallet's Aave debt on `network` that brings the
+/// health factor back towards the policy target
+///
+/// The repay uses a borrowed asset the wallet already holds and has approved
+/// for the Pool. Nothing is queued while the wallet has other pending
+/// transactions (see [`TxQueueManager::has_pending_from`]). The transaction is
+/// only queued; broadcasting it is left to the user (or `broadcast_web3_tx`).
+pub async fn queue_auto_repay(
+    db: &Arc<Database>,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    tx_queue: &Arc<TxQueueManager>,
+    policy: &RepayPolicy,
+    network: &str,
+) -> Result<AutoRepay, String> {
+    let wallet = wallet_provider.get_address();
+    if tx_queue.has_pending_from(&wallet) {
+        return Err("The wallet already has a pending transaction; broadcast or cancel it first".to_string());
+    }
+
+    let (repays_last_24h, _) = db
+        .aave_auto_repay_totals_since(Utc::now() - Duration::hours(24))
+        .map_err(|e| format!("Failed to read repay history: {}", e))?;
+    policy.check(repays_last_24h)?;
+
+    let pool = pool_address(network).ok_or_else(|| format!("Aave V3 is not available on {}", network))?;
+    let settings = db
+        .get_bot_settings()
+        .map_err(|e| format!("Failed to load bot settings: {}", e))?;
+    let (url, use_x402) = resolve_rpc_config(&settings.rpc_provider, settings.custom_rpc_endpoints.as_ref(), network)
+        .ok_or_else(|| format!("No RPC endpoint configured for {}", network))?;
+    let rpc_config = ResolvedRpcConfig { url, use_x402 };
+    let rpc = X402EvmRpc::new_with_wallet_provider(
+        wallet_provider.clone(),
+        network,
+        Some(rpc_config.url.clone()),
+        rpc_config.use_x402,
+    )?;
+    let owner: Address = wallet.parse().map_err(|_| "Invalid wallet address".to_string())?;
+
+    let position = get_position(&rpc, network, owner).await?;
+    let needed_usd = policy.repay_needed_usd(&position.account);
+    if needed_usd <= 0.0 {
+        return Err(format!(
+            "Health factor is already at or above the target of {}",
+            policy.target_health_factor
+        ));
+    }
+
+    // What the wallet can repay with: balance, limited by the Pool allowance
+    let debts: Vec<&ReservePosition> = position.reserves.iter().filter(|r| !r.borrowed_raw.is_zero()).collect();
+    let mut calls = Vec::new();
+    for reserve in &debts {
+        let asset: Address = reserve.asset.parse().map_err(|_| format!("Invalid asset {}", reserve.asset))?;
+        calls.push(multicall::erc20_balance_call(asset, owner));
+        calls.push(multicall::erc20_allowance_call(asset, owner, pool));
+    }
+    let results = multicall::aggregate3(&rpc, &calls).await?;
+    let spendable: HashMap<String, U256> = debts
+        .iter()
+        .zip(results.chunks(2))
+        .map(|(reserve, r)| {
+            let balance = r[0].as_u256().unwrap_or_default();
+            let allowance = r.get(1).and_then(|a| a.as_u256()).unwrap_or_default();
+            (reserve.asset.clone(), balance.min(allowance))
+        })
+        .collect();
+
+    let plan = plan_repay(&position.reserves, &spendable, needed_usd, policy.max_repay_usd).ok_or_else(|| {
+        "The wallet holds none of the borrowed assets with a Pool allowance (approve with aave_approve_pool or repay manually)"
+            .to_string()
+    })?;
+
+    let calldata = encode_repay(plan.asset, plan.amount, owner);
+    let uuid = queue_signed(
+        network,
+        pool,
+        calldata,
+        U256::zero(),
+        REPAY_PRESET,
+        &rpc_config,
+        wallet_provider,
+        tx_queue,
+    )
+    .await?;
+
+    let asset = format!("{:?}", plan.asset);
+    if let Err(e) = db.record_aave_auto_repay(network, &wallet, &asset, &plan.amount.to_string(), plan.amount_usd, &uuid) {
+        log::error!("[aave] Failed to record repay {}: {}", uuid, e);
+    }
+    log::info!(
+        "[aave] Queued repay {} of {} {} on {}",
+        uuid,
+        crate::swap::format_units(plan.amount, plan.decimals),
+        plan.symbol,
+        network
+    );
+
+    Ok(AutoRepay {
+        uuid,
+        network: network.to_string(),
+        asset,
+        symbol: plan.symbol,
+        amount: crate::swap::format_units(plan.amount, plan.decimals),
+        amount_usd: plan.amount_usd,
+        health_factor_before: position.account.health_factor,
+        expected_health_factor: position.account.health_factor_after_repay(plan.amount_usd),
+        // Allow a cent of rounding
+        partial: plan.amount_usd + 0.01 < needed_usd,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(collateral_usd: f64, debt_usd: f64) -> AccountData {
+        AccountData {
+            collateral_usd,
+            debt_usd,
+            available_borrows_usd: 0.0,
+            liquidation_threshold_bps: 8_000,
+            ltv_bps: 7_500,
+            health_factor: Some(collateral_usd * 0.8 / debt_usd),
+        }
+    }
+
+    fn reserve(asset: &str, decimals: u8, price_usd: f64, borrowed_raw: U256) -> ReservePosition {
+        ReservePosition {
+            asset: asset.to_string(),
+            symbol: Some("USDC".to_string()),
+            decimals,
+            supplied: "0".to_string(),
+            borrowed: crate::swap::format_units(borrowed_raw, decimals),
+            price_usd,
+            supplied_usd: 0.0,
+            borrowed_usd: to_f64(borrowed_raw, decimals) * price_usd,
+            supply_apr_pct: 0.0,
+            variable_borrow_apr_pct: 0.0,
+            borrowed_raw,
+        }
+    }
+
+    fn policy() -> RepayPolicy {
+        RepayPolicy {
+            max_repay_usd: 500.0,
+            max_repays_per_day: 2,
+            target_health_factor: 1.6,
+        }
+    }
+
+    #[test]
+    fn test_policy() {
+        let policy = policy();
+        assert!(policy.check(1).is_ok());
+        assert!(policy.check(2).is_err());
+
+        // HF 1.2 -> debt must fall to 10_000 * 0.8 / 1.6 = 5_000
+        assert_eq!(policy.repay_needed_usd(&account(10_000.0, 6_666.0)), 1_666.0);
+        assert_eq!(policy.repay_needed_usd(&account(10_000.0, 4_000.0)), 0.0);
+    }
+
+    #[test]
+    fn test_plan_repay_caps() {
+        let usdc = "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913";
+        let reserves = vec![reserve(usdc, 6, 1.0, U256::from(2_000_000_000u64))]; // 2,000 USDC debt
+
+        // Capped by the policy maximum
+        let spendable = HashMap::from([(usdc.to_string(), U256::from(10_000_000_000u64))]);
+        let plan = plan_repay(&reserves, &spendable, 1_000.0, 500.0).unwrap();
+        assert_eq!(plan.amount, U256::from(500_000_000u64));
+        assert_eq!(plan.amount_usd, 500.0);
+
+        // Capped by what the wallet can spend
+        let spendable = HashMap::from([(usdc.to_string(), U256::from(120_000_000u64))]);
+        let plan = plan_repay(&reserves, &spendable, 300.0, 500.0).unwrap();
+        assert_eq!(plan.amount, U256::from(120_000_000u64));
+
+        // Nothing spendable
+        assert!(plan_repay(&reserves, &HashMap::new(), 300.0, 500.0).is_none());
+    }
+
+    #[test]
+    fn test_encode_repay() {
+        let data = encode_repay(Address::repeat_byte(0x11), U256::from(1), Address::repeat_byte(0x22));
+        assert_eq!(hex::encode(&data[..4]), "573ade81");
+        assert_eq!(data.len(), 4 + 4 * 32);
+        assert_eq!(U256::from_big_endian(&data[4 + 64..4 + 96]), U256::from(VARIABLE_RATE_MODE));
+    }
+}
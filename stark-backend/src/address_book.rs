@@ -0,0 +1,237 @@
+//! Address book: labelled contacts the agent may send funds to
+//!
+//! Each contact has a label, an address (optionally tied to an ENS name), the networks
+//! it receives on, notes and a trust level. The trust level decides how `verify_intent`
+//! treats the address as a transaction recipient:
+//! - `trusted`: always accepted, even if the user did not mention it
+//! - `known`: accepted when the user referred to it (label, ENS name or address)
+//! - `blocked`: transactions to it are always rejected
+//!
+//! The agent can only add `known` or `blocked` contacts; promoting a contact to
+//! `trusted` is done by an operator through the API.
+
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::web3::ens;
+use crate::x402::X402EvmRpc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustLevel {
+    Trusted,
+    #[default]
+    Known,
+    Blocked,
+}
+
+impl TrustLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrustLevel::Trusted => "trusted",
+            TrustLevel::Known => "known",
+            TrustLevel::Blocked => "blocked",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "trusted" => Some(TrustLevel::Trusted),
+            "known" => Some(TrustLevel::Known),
+            "blocked" => Some(TrustLevel::Blocked),
+            _ => None,
+        }
+    }
+}
+
+/// A stored contact
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contact {
+    pub id: i64,
+    pub label: String,
+    /// Lowercase 0x address
+    pub address: String,
+    pub ens_name: Option<String>,
+    /// Networks the contact receives on (empty = any network)
+    pub networks: Vec<String>,
+    pub notes: Option<String>,
+    pub trust_level: TrustLevel,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Contact {
+    /// Whether the contact accepts transactions on `network`
+    pub fn allows_network(&self, network: &str) -> bool {
+        self.networks.is_empty() || self.networks.iter().any(|n| n.eq_ignore_ascii_case(network))
+    }
+}
+
+/// Fields of a contact being added or updated
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContactInput {
+    pub label: String,
+    /// Required unless `ens_name` is given (it is then resolved on mainnet)
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub ens_name: Option<String>,
+    #[serde(default)]
+    pub networks: Vec<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub trust_level: TrustLevel,
+}
+
+const MAX_LABEL_LEN: usize = 64;
+
+impl ContactInput {
+    /// Trim and lowercase fields and check them (the address may still be missing when an
+    /// ENS name is given)
+    pub fn normalize(mut self) -> Result<Self, String> {
+        self.label = self.label.trim().to_string();
+        if self.label.is_empty() {
+            return Err("label is required".to_string());
+        }
+        if self.label.len() > MAX_LABEL_LEN {
+            return Err(format!("label must be at most {} characters", MAX_LABEL_LEN));
+        }
+        if self.label.starts_with("0x") || ens::is_ens_name(&self.label) {
+            return Err("label must be a name, not an address or ENS name".to_string());
+        }
+
+        self.address = self.address.map(|a| a.trim().to_lowercase()).filter(|a| !a.is_empty());
+        if let Some(ref address) = self.address {
+            let parsed: Address = address
+                .parse()
+                .map_err(|_| format!("Invalid address '{}'", address))?;
+            if parsed.is_zero() {
+                return Err("The zero address cannot be a contact".to_string());
+            }
+        }
+
+        self.ens_name = self.ens_name.map(|n| ens::normalize_name(&n)).filter(|n| !n.is_empty());
+        if let Some(ref name) = self.ens_name.as_ref().filter(|n| !ens::is_ens_name(n)) {
+            return Err(format!("Invalid ENS name '{}'", name));
+        }
+        if self.address.is_none() && self.ens_name.is_none() {
+            return Err("address or ens_name is required".to_string());
+        }
+
+        let mut networks: Vec<String> = self
+            .networks
+            .iter()
+            .map(|n| n.trim().to_lowercase())
+            .filter(|n| !n.is_empty())
+            .collect();
+        networks.sort();
+        networks.dedup();
+        self.networks = networks;
+
+        self.notes = self.notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        Ok(self)
+    }
+
+    /// Fill in the address from the ENS name, or check that both agree
+    pub async fn resolve_ens(&mut self, rpc: &X402EvmRpc) -> Result<(), String> {
+        let Some(ref name) = self.ens_name else {
+            return Ok(());
+        };
+        let resolved = ens::resolve_name(rpc, name)
+            .await?
+            .ok_or_else(|| format!("ENS name '{}' does not resolve to an address", name))?;
+        let resolved = format!("{:?}", resolved);
+        match self.address {
+            Some(ref address) if *address != resolved => Err(format!(
+                "ENS name '{}' resolves to {}, not {}",
+                name, resolved, address
+            )),
+            _ => {
+                self.address = Some(resolved);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Find the contact a user-supplied reference points to: an address, ENS name or label
+pub fn find_contact<'a>(contacts: &'a [Contact], reference: &str) -> Option<&'a Contact> {
+    let reference = reference.trim();
+    let lower = reference.to_lowercase();
+    contacts
+        .iter()
+        .find(|c| c.address == lower || c.ens_name.as_deref() == Some(lower.as_str()))
+        .or_else(|| contacts.iter().find(|c| c.label.eq_ignore_ascii_case(reference)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(label: &str, address: &str, ens_name: Option<&str>) -> Contact {
+        Contact {
+            id: 1,
+            label: label.to_string(),
+            address: address.to_string(),
+            ens_name: ens_name.map(str::to_string),
+            networks: vec![],
+            notes: None,
+            trust_level: TrustLevel::Known,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let input = ContactInput {
+            label: "  Alice ".to_string(),
+            address: Some("0x742D35Cc6634C0532925a3b844Bc9e7595f8FdF0".to_string()),
+            networks: vec!["Base".to_string(), "base".to_string(), " ".to_string()],
+            notes: Some("  ".to_string()),
+            ..Default::default()
+        }
+        .normalize()
+        .unwrap();
+        assert_eq!(input.label, "Alice");
+        assert_eq!(input.address.as_deref(), Some("0x742d35cc6634c0532925a3b844bc9e7595f8fdf0"));
+        assert_eq!(input.networks, vec!["base"]);
+        assert!(input.notes.is_none());
+
+        let missing = ContactInput { label: "Bob".to_string(), ..Default::default() };
+        assert!(missing.normalize().is_err());
+
+        let zero = ContactInput {
+            label: "Burn".to_string(),
+            address: Some("0x0000000000000000000000000000000000000000".to_string()),
+            ..Default::default()
+        };
+        assert!(zero.normalize().is_err());
+
+        let ens_label = ContactInput {
+            label: "vitalik.eth".to_string(),
+            ens_name: Some("vitalik.eth".to_string()),
+            ..Default::default()
+        };
+        assert!(ens_label.normalize().is_err());
+    }
+
+    #[test]
+    fn test_find_contact() {
+        let contacts = vec![
+            contact("Alice", "0x742d35cc6634c0532925a3b844bc9e7595f8fdf0", None),
+            contact("Treasury", "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", Some("treasury.eth")),
+        ];
+        assert_eq!(find_contact(&contacts, "alice").unwrap().label, "Alice");
+        assert_eq!(
+            find_contact(&contacts, "Treasury.ETH").unwrap().address,
+            "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+        );
+        assert_eq!(
+            find_contact(&contacts, "0x742D35Cc6634C0532925a3b844Bc9e7595f8FdF0").unwrap().label,
+            "Alice"
+        );
+        assert!(find_contact(&contacts, "bob").is_none());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::ai::types::{
     AiError, AiResponse, ClaudeContentBlock, ClaudeMessage as TypedClaudeMessage,
-    ClaudeMessageContent, ClaudeTool, ThinkingLevel, ToolCall, ToolResponse,
+    ClaudeMessageContent, ClaudeTool, ThinkingLevel, TokenUsage, ToolCall, ToolResponse,
 };
 use crate::ai::{Message, MessageRole};
 use crate::gateway::events::EventBroadcaster;
@@ -17,6 +17,9 @@ pub struct ClaudeClient {
     client: Client,
     endpoint: String,
     model: String,
+    max_tokens: u32,
+    temperature: Option<f32>,
+    top_p: Option<f32>,
     /// Thinking budget in tokens (0 = disabled)
     thinking_budget: AtomicU32,
     /// Optional broadcaster for emitting retry events
@@ -31,6 +34,9 @@ impl Clone for ClaudeClient {
             client: self.client.clone(),
             endpoint: self.endpoint.clone(),
             model: self.model.clone(),
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
             thinking_budget: AtomicU32::new(self.thinking_budget.load(Ordering::SeqCst)),
             broadcaster: self.broadcaster.clone(),
             channel_id: self.channel_id,
@@ -52,6 +58,10 @@ struct ClaudeCompletionRequest {
     messages: Vec<SimpleClaudeMessage>,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     thinking: Option<ThinkingConfig>,
@@ -83,6 +93,10 @@ struct ClaudeToolRequest {
     messages: Vec<TypedClaudeMessage>,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<ClaudeTool>>,
@@ -97,6 +111,25 @@ struct ClaudeCompletionResponse {
     content: Vec<ClaudeResponseContent>,
     #[serde(default)]
     stop_reason: Option<String>,
+    #[serde(default)]
+    usage: Option<ClaudeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClaudeUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+impl From<ClaudeUsage> for TokenUsage {
+    fn from(usage: ClaudeUsage) -> Self {
+        TokenUsage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -151,12 +184,30 @@ impl ClaudeClient {
                 .unwrap_or("https://api.anthropic.com/v1/messages")
                 .to_string(),
             model: model.unwrap_or("claude-sonnet-4-20250514").to_string(),
+            max_tokens: 4096,
+            temperature: None,
+            top_p: None,
             thinking_budget: AtomicU32::new(0),
             broadcaster: None,
             channel_id: None,
         })
     }
 
+    /// Model name sent with requests
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Override the output token limit and sampling parameters
+    pub fn with_params(mut self, max_tokens: Option<u32>, temperature: Option<f32>, top_p: Option<f32>) -> Self {
+        if let Some(max_tokens) = max_tokens {
+            self.max_tokens = max_tokens;
+        }
+        self.temperature = temperature;
+        self.top_p = top_p;
+        self
+    }
+
     /// Set the broadcaster for emitting retry events
     pub fn with_broadcaster(mut self, broadcaster: Arc<EventBroadcaster>, channel_id: i64) -> Self {
         self.broadcaster = Some(broadcaster);
@@ -203,7 +254,8 @@ impl ClaudeClient {
         }
     }
 
-    pub async fn generate_text(&self, messages: Vec<Message>) -> Result<String, String> {
+    /// Generate text, keeping the provider-reported token usage
+    pub async fn generate_text_response(&self, messages: Vec<Message>) -> Result<AiResponse, String> {
         // Extract system message if present
         let mut system_message = None;
         let filtered_messages: Vec<Message> = messages
@@ -230,7 +282,10 @@ impl ClaudeClient {
         let request = ClaudeCompletionRequest {
             model: self.model.clone(),
             messages: api_messages,
-            max_tokens: 4096,
+            max_tokens: self.max_tokens,
+            // Extended thinking rejects custom sampling parameters
+            temperature: self.temperature.filter(|_| thinking.is_none()),
+            top_p: self.top_p.filter(|_| thinking.is_none()),
             system: system_message,
             thinking,
         };
@@ -344,7 +399,7 @@ impl ClaudeClient {
             return Err("Claude API returned no content".to_string());
         }
 
-        Ok(content)
+        Ok(AiResponse::text(content).with_usage(response_data.usage.map(TokenUsage::from)))
     }
 
     /// Generate a response with tool support
@@ -395,7 +450,10 @@ impl ClaudeClient {
         let request = ClaudeToolRequest {
             model: self.model.clone(),
             messages: api_messages,
-            max_tokens: 4096,
+            max_tokens: self.max_tokens,
+            // Extended thinking rejects custom sampling parameters
+            temperature: self.temperature.filter(|_| thinking.is_none()),
+            top_p: self.top_p.filter(|_| thinking.is_none()),
             system: system_message,
             tools: if has_tools {
                 Some(claude_tools)
@@ -549,6 +607,7 @@ impl ClaudeClient {
             tool_calls,
             stop_reason: response_data.stop_reason,
             x402_payment: None, // Claude doesn't use x402
+            usage: response_data.usage.map(TokenUsage::from),
         })
     }
 
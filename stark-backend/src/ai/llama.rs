@@ -1,4 +1,4 @@
-use crate::ai::types::{AiResponse, ToolCall};
+use crate::ai::types::{AiResponse, TokenUsage, ToolCall};
 use crate::ai::Message;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
@@ -15,6 +15,7 @@ pub struct LlamaClient {
     client: Client,
     endpoint: String,
     model: String,
+    options: Option<OllamaOptions>,
     /// Optional broadcaster for emitting retry events
     broadcaster: Option<Arc<EventBroadcaster>>,
     /// Channel ID for events
@@ -28,6 +29,19 @@ struct OllamaChatRequest {
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<OllamaTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<OllamaOptions>,
+}
+
+/// Ollama model options (only the ones we expose)
+#[derive(Debug, Clone, Serialize)]
+struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +84,24 @@ struct OllamaChatResponse {
     message: OllamaResponseMessage,
     #[serde(default)]
     done_reason: Option<String>,
+    /// Prompt tokens evaluated
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    /// Tokens generated
+    #[serde(default)]
+    eval_count: Option<u64>,
+}
+
+impl OllamaChatResponse {
+    fn usage(&self) -> Option<TokenUsage> {
+        if self.prompt_eval_count.is_none() && self.eval_count.is_none() {
+            return None;
+        }
+        Some(TokenUsage {
+            input_tokens: self.prompt_eval_count.unwrap_or(0),
+            output_tokens: self.eval_count.unwrap_or(0),
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -105,11 +137,31 @@ impl LlamaClient {
                 .unwrap_or("http://localhost:11434/api/chat")
                 .to_string(),
             model: model.unwrap_or("llama3.3").to_string(),
+            options: None,
             broadcaster: None,
             channel_id: None,
         })
     }
 
+    /// Model name sent with requests
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Set the output token limit (`num_predict`) and sampling parameters
+    pub fn with_params(mut self, max_tokens: Option<u32>, temperature: Option<f32>, top_p: Option<f32>) -> Self {
+        self.options = if max_tokens.is_none() && temperature.is_none() && top_p.is_none() {
+            None
+        } else {
+            Some(OllamaOptions {
+                temperature,
+                top_p,
+                num_predict: max_tokens,
+            })
+        };
+        self
+    }
+
     /// Set the broadcaster for emitting retry events
     pub fn with_broadcaster(mut self, broadcaster: Arc<EventBroadcaster>, channel_id: i64) -> Self {
         self.broadcaster = Some(broadcaster);
@@ -131,7 +183,8 @@ impl LlamaClient {
         }
     }
 
-    pub async fn generate_text(&self, messages: Vec<Message>) -> Result<String, String> {
+    /// Generate text, keeping the token counts reported by Ollama
+    pub async fn generate_text_response(&self, messages: Vec<Message>) -> Result<AiResponse, String> {
         let api_messages: Vec<OllamaMessage> = messages
             .into_iter()
             .map(|m| OllamaMessage {
@@ -146,6 +199,7 @@ impl LlamaClient {
             messages: api_messages,
             stream: false,
             tools: None,
+            options: self.options.clone(),
         };
 
         log::debug!("Sending request to Ollama API: {:?}", request);
@@ -249,7 +303,8 @@ impl LlamaClient {
             return Err("Ollama API returned no content".to_string());
         }
 
-        Ok(response_data.message.content)
+        let usage = response_data.usage();
+        Ok(AiResponse::text(response_data.message.content).with_usage(usage))
     }
 
     /// Generate a response with tool support (Llama 3.1+ with Ollama)
@@ -294,6 +349,7 @@ impl LlamaClient {
             } else {
                 Some(ollama_tools)
             },
+            options: self.options.clone(),
         };
 
         log::debug!(
@@ -396,6 +452,8 @@ impl LlamaClient {
             last_error.unwrap_or_else(|| "Max retries exceeded".to_string())
         })?;
 
+        let usage = response_data.usage();
+
         // Parse tool calls from response
         let mut tool_calls = Vec::new();
         if let Some(calls) = response_data.message.tool_calls {
@@ -420,6 +478,7 @@ impl LlamaClient {
             tool_calls,
             stop_reason,
             x402_payment: None, // Llama doesn't use x402 directly (handled by OpenAI-compatible wrapper)
+            usage,
         })
     }
 
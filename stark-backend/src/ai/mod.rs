@@ -5,21 +5,22 @@ pub mod multi_agent;
 pub mod openai;
 pub mod streaming;
 pub mod types;
+pub mod usage;
 
 pub use claude::ClaudeClient;
 pub use llama::{LlamaClient, LlamaMessage};
 pub use openai::OpenAIClient;
 pub use archetypes::{ArchetypeId, ArchetypeRegistry, ModelArchetype};
 pub use types::{
-    AiError, AiResponse, ClaudeMessage as TypedClaudeMessage, ThinkingLevel, ToolCall,
+    AiError, AiResponse, ClaudeMessage as TypedClaudeMessage, ThinkingLevel, TokenUsage, ToolCall,
     ToolHistoryEntry, ToolResponse,
 };
+pub use usage::UsageScope;
 
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::AgentSettings;
+use crate::models::{AgentSettings, ModelOptions, ProviderKind};
 use crate::tools::ToolDefinition;
-use crate::x402::X402PaymentInfo;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
@@ -138,46 +139,21 @@ impl AiClient {
 
     /// Create an AI client from agent settings with optional burner wallet for x402
     ///
-    /// Uses ClaudeClient for Claude archetype (requires x-api-key auth),
-    /// OpenAI-compatible client for all other archetypes.
+    /// The provider comes from `settings.provider` (Auto = ClaudeClient for the Claude
+    /// archetype, OpenAI-compatible client for all other archetypes).
     pub fn from_settings_with_wallet(
         settings: &AgentSettings,
         burner_private_key: Option<&str>,
     ) -> Result<Self, String> {
-        use crate::x402::is_x402_endpoint;
-
-        // Get archetype to determine client type and default model
-        let archetype_id = Self::infer_archetype(settings);
-        let registry = ArchetypeRegistry::new();
-        let archetype = registry.get(archetype_id).unwrap_or_else(|| registry.default_archetype());
-        let model = archetype.default_model();
-
-        // Determine API key: x402 endpoints don't need one, others use secret_key
-        let api_key = if is_x402_endpoint(&settings.endpoint) {
-            ""  // x402 endpoints use crypto signatures, no API key needed
-        } else {
-            settings.secret_key.as_deref().unwrap_or("")
-        };
-
-        // Use ClaudeClient for Claude archetype (native Anthropic API with x-api-key header)
-        if archetype_id == ArchetypeId::Claude {
-            let client = ClaudeClient::new(
+        Self::build(settings, |api_key, model| {
+            OpenAIClient::new_with_x402_and_tokens(
                 api_key,
                 Some(&settings.endpoint),
                 Some(model),
-            )?;
-            return Ok(AiClient::Claude(client));
-        }
-
-        // All other archetypes use OpenAI-compatible client
-        let client = OpenAIClient::new_with_x402_and_tokens(
-            api_key,
-            Some(&settings.endpoint),
-            Some(model),
-            burner_private_key,
-            Some(settings.max_response_tokens as u32),
-        )?;
-        Ok(AiClient::OpenAI(client))
+                burner_private_key,
+                Some(settings.max_response_tokens as u32),
+            )
+        })
     }
 
     /// Create an AI client from agent settings with WalletProvider for x402
@@ -185,14 +161,36 @@ impl AiClient {
     pub fn from_settings_with_wallet_provider(
         settings: &AgentSettings,
         wallet_provider: Option<std::sync::Arc<dyn crate::wallet::WalletProvider>>,
+    ) -> Result<Self, String> {
+        Self::build(settings, |api_key, model| {
+            OpenAIClient::new_with_wallet_provider(
+                api_key,
+                Some(&settings.endpoint),
+                Some(model),
+                wallet_provider,
+                Some(settings.max_response_tokens as u32),
+            )
+        })
+    }
+
+    /// Shared client construction; `new_openai` builds the OpenAI-compatible client
+    /// (the two factories differ only in how x402 payments are signed)
+    fn build(
+        settings: &AgentSettings,
+        new_openai: impl FnOnce(&str, &str) -> Result<OpenAIClient, String>,
     ) -> Result<Self, String> {
         use crate::x402::is_x402_endpoint;
 
-        // Get archetype to determine client type and default model
+        // Explicit model wins over the archetype's default model
         let archetype_id = Self::infer_archetype(settings);
         let registry = ArchetypeRegistry::new();
         let archetype = registry.get(archetype_id).unwrap_or_else(|| registry.default_archetype());
-        let model = archetype.default_model();
+        let model = settings
+            .model
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| archetype.default_model());
 
         // Determine API key: x402 endpoints don't need one, others use secret_key
         let api_key = if is_x402_endpoint(&settings.endpoint) {
@@ -201,25 +199,98 @@ impl AiClient {
             settings.secret_key.as_deref().unwrap_or("")
         };
 
-        // Use ClaudeClient for Claude archetype (native Anthropic API with x-api-key header)
-        if archetype_id == ArchetypeId::Claude {
-            let client = ClaudeClient::new(
-                api_key,
-                Some(&settings.endpoint),
-                Some(model),
-            )?;
-            return Ok(AiClient::Claude(client));
+        let max_tokens = Some(settings.max_response_tokens as u32);
+        match Self::resolve_provider(settings) {
+            // Native Anthropic API with x-api-key header
+            ProviderKind::Anthropic => {
+                let client = ClaudeClient::new(api_key, Some(&settings.endpoint), Some(model))?
+                    .with_params(max_tokens, settings.temperature, settings.top_p);
+                Ok(AiClient::Claude(client))
+            }
+            // Native Ollama /api/chat
+            ProviderKind::Ollama => {
+                let client = LlamaClient::new(Some(&settings.endpoint), Some(model))?
+                    .with_params(max_tokens, settings.temperature, settings.top_p);
+                Ok(AiClient::Llama(client))
+            }
+            ProviderKind::OpenAI | ProviderKind::Auto => {
+                let client = new_openai(api_key, model)?
+                    .with_sampling(settings.temperature, settings.top_p);
+                Ok(AiClient::OpenAI(client))
+            }
+        }
+    }
+
+    /// Provider the settings resolve to (Auto picks Anthropic for the Claude archetype)
+    pub fn resolve_provider(settings: &AgentSettings) -> ProviderKind {
+        Self::effective_provider(settings.provider, Self::infer_archetype(settings))
+    }
+
+    fn effective_provider(provider: ProviderKind, archetype_id: ArchetypeId) -> ProviderKind {
+        match provider {
+            ProviderKind::Auto if archetype_id == ArchetypeId::Claude => ProviderKind::Anthropic,
+            ProviderKind::Auto => ProviderKind::OpenAI,
+            provider => provider,
+        }
+    }
+
+    /// Check that model options make sense for the archetype before they are saved
+    pub fn validate_model_options(
+        archetype_id: ArchetypeId,
+        options: &ModelOptions,
+    ) -> Result<(), String> {
+        if options.model.as_deref().is_some_and(|m| m.trim().is_empty()) {
+            return Err("Model name must not be empty (omit it to use the archetype default)".to_string());
+        }
+
+        let provider = Self::effective_provider(options.provider, archetype_id);
+
+        let registry = ArchetypeRegistry::new();
+        let archetype = registry.get(archetype_id).unwrap_or_else(|| registry.default_archetype());
+        if provider == ProviderKind::Anthropic && !archetype.uses_native_tool_calling() {
+            return Err(format!(
+                "The {} archetype parses tool calls from text and cannot be used with the anthropic provider",
+                archetype_id
+            ));
+        }
+
+        let max_temperature = if provider == ProviderKind::Anthropic { 1.0 } else { 2.0 };
+        if options.temperature.is_some_and(|t| !(0.0..=max_temperature).contains(&t)) {
+            return Err(format!(
+                "Temperature must be between 0 and {} for the {} provider",
+                max_temperature,
+                provider.as_str()
+            ));
+        }
+        if options.top_p.is_some_and(|p| !(p > 0.0 && p <= 1.0)) {
+            return Err("top_p must be greater than 0 and at most 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Model name the client sends requests for
+    pub fn model(&self) -> &str {
+        match self {
+            AiClient::Claude(client) => client.model(),
+            AiClient::OpenAI(client) => client.model(),
+            AiClient::Llama(client) => client.model(),
+            AiClient::Mock(_) => "mock",
+        }
+    }
+
+    /// Endpoint URL if this client pays for calls via x402
+    pub fn x402_endpoint(&self) -> Option<&str> {
+        match self {
+            AiClient::OpenAI(client) => client.x402_endpoint(),
+            _ => None,
         }
+    }
 
-        // All other archetypes use OpenAI-compatible client
-        let client = OpenAIClient::new_with_wallet_provider(
-            api_key,
-            Some(&settings.endpoint),
-            Some(model),
-            wallet_provider,
-            Some(settings.max_response_tokens as u32),
-        )?;
-        Ok(AiClient::OpenAI(client))
+    /// Cap what the client may pay per x402 call (None = no cap)
+    pub fn set_x402_spend_limit(&self, limit: Option<u64>) {
+        if let AiClient::OpenAI(client) = self {
+            client.set_x402_spend_limit(limit);
+        }
     }
 
     /// Get the archetype ID from agent settings
@@ -227,31 +298,29 @@ impl AiClient {
         ArchetypeId::from_str(&settings.model_archetype).unwrap_or(ArchetypeId::Kimi)
     }
 
-    /// Generate text using the configured provider
-    pub async fn generate_text(&self, messages: Vec<Message>) -> Result<String, String> {
+    /// Generate text, keeping token usage and x402 payment info
+    pub async fn generate_text_response(&self, messages: Vec<Message>) -> Result<AiResponse, String> {
         match self {
-            AiClient::Claude(client) => client.generate_text(messages).await,
-            AiClient::OpenAI(client) => client.generate_text(messages).await,
-            AiClient::Llama(client) => client.generate_text(messages).await,
-            AiClient::Mock(client) => client.next_response()
-                .map(|r| r.content)
-                .map_err(|e| e.message),
+            AiClient::Claude(client) => client.generate_text_response(messages).await,
+            AiClient::OpenAI(client) => client.generate_text_response(messages).await,
+            AiClient::Llama(client) => client.generate_text_response(messages).await,
+            AiClient::Mock(client) => client.next_response().map_err(|e| e.message),
         }
     }
 
     /// Generate text and emit x402 payment event if applicable
-    /// Returns (content, optional payment info) so caller can persist the payment
+    /// Returns the full response so caller can persist the payment and token usage
     pub async fn generate_text_with_events(
         &self,
         messages: Vec<Message>,
         broadcaster: &Arc<EventBroadcaster>,
         channel_id: i64,
-    ) -> Result<(String, Option<X402PaymentInfo>), String> {
+    ) -> Result<AiResponse, String> {
         match self {
             AiClient::OpenAI(client) => {
-                let (content, payment) = client.generate_text_with_payment_info(messages).await?;
+                let response = client.generate_text_response(messages).await?;
                 // Emit x402 payment event if payment was made
-                if let Some(ref payment_info) = payment {
+                if let Some(ref payment_info) = response.x402_payment {
                     broadcaster.broadcast(GatewayEvent::x402_payment(
                         channel_id,
                         &payment_info.amount,
@@ -261,14 +330,12 @@ impl AiClient {
                         payment_info.resource.as_deref(),
                     ));
                 }
-                Ok((content, payment))
+                Ok(response)
             }
             // Other providers don't support x402
-            AiClient::Claude(client) => Ok((client.generate_text(messages).await?, None)),
-            AiClient::Llama(client) => Ok((client.generate_text(messages).await?, None)),
-            AiClient::Mock(client) => client.next_response()
-                .map(|r| (r.content, None))
-                .map_err(|e| e.message),
+            AiClient::Claude(client) => client.generate_text_response(messages).await,
+            AiClient::Llama(client) => client.generate_text_response(messages).await,
+            AiClient::Mock(client) => client.next_response().map_err(|e| e.message),
         }
     }
 
@@ -410,3 +477,55 @@ impl AiClient {
         messages
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(provider: ProviderKind, temperature: Option<f32>) -> ModelOptions {
+        ModelOptions {
+            model: Some("gpt-4o-mini".to_string()),
+            provider,
+            temperature,
+            top_p: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_provider_auto() {
+        let mut settings = AgentSettings::default();
+        assert_eq!(AiClient::resolve_provider(&settings), ProviderKind::OpenAI);
+        settings.model_archetype = "claude".to_string();
+        assert_eq!(AiClient::resolve_provider(&settings), ProviderKind::Anthropic);
+        settings.provider = ProviderKind::Ollama;
+        assert_eq!(AiClient::resolve_provider(&settings), ProviderKind::Ollama);
+    }
+
+    #[test]
+    fn test_validate_model_options() {
+        assert!(AiClient::validate_model_options(ArchetypeId::Kimi, &options(ProviderKind::OpenAI, Some(1.5))).is_ok());
+        assert!(AiClient::validate_model_options(ArchetypeId::Claude, &options(ProviderKind::Auto, Some(1.5))).is_err());
+        assert!(AiClient::validate_model_options(ArchetypeId::Llama, &options(ProviderKind::Anthropic, None)).is_err());
+        assert!(AiClient::validate_model_options(ArchetypeId::Llama, &options(ProviderKind::Ollama, Some(0.2))).is_ok());
+
+        let mut bad_top_p = options(ProviderKind::OpenAI, None);
+        bad_top_p.top_p = Some(0.0);
+        assert!(AiClient::validate_model_options(ArchetypeId::Kimi, &bad_top_p).is_err());
+
+        let mut blank_model = options(ProviderKind::OpenAI, None);
+        blank_model.model = Some("  ".to_string());
+        assert!(AiClient::validate_model_options(ArchetypeId::Kimi, &blank_model).is_err());
+    }
+
+    #[test]
+    fn test_explicit_model_and_provider_used() {
+        let settings = AgentSettings {
+            endpoint: "http://localhost:11434/api/chat".to_string(),
+            model_archetype: "llama".to_string(),
+            model: Some("qwen2.5:14b".to_string()),
+            provider: ProviderKind::Ollama,
+            ..AgentSettings::default()
+        };
+        assert!(matches!(AiClient::from_settings(&settings), Ok(AiClient::Llama(_))));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::ai::streaming::{StreamEvent, StreamSender};
-use crate::ai::types::{AiError, AiResponse, ToolCall};
+use crate::ai::types::{AiError, AiResponse, TokenUsage, ToolCall};
 use crate::ai::Message;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
@@ -19,6 +19,8 @@ pub struct OpenAIClient {
     endpoint: String,
     model: String,
     max_tokens: u32,
+    temperature: Option<f32>,
+    top_p: Option<f32>,
     x402_client: Option<Arc<X402Client>>,
     /// Optional broadcaster for emitting retry events
     broadcaster: Option<Arc<EventBroadcaster>>,
@@ -32,6 +34,10 @@ struct OpenAICompletionRequest {
     messages: Vec<OpenAIMessage>,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<OpenAITool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
@@ -123,6 +129,16 @@ pub struct OpenAIFunctionCall {
 #[derive(Debug, Deserialize)]
 struct OpenAICompletionResponse {
     choices: Vec<OpenAIChoice>,
+    #[serde(default)]
+    usage: Option<OpenAIUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -233,6 +249,8 @@ impl OpenAIClient {
             endpoint: endpoint_url,
             model: effective_model,
             max_tokens: max_tokens.unwrap_or(40096),
+            temperature: None,
+            top_p: None,
             x402_client,
             broadcaster: None,
             channel_id: None,
@@ -314,12 +332,38 @@ impl OpenAIClient {
             endpoint: endpoint_url,
             model: model_name,
             max_tokens: max_tokens.unwrap_or(40000),
+            temperature: None,
+            top_p: None,
             x402_client,
             broadcaster: None,
             channel_id: None,
         })
     }
 
+    /// Model name sent with requests
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Endpoint URL when requests to it are paid via x402
+    pub fn x402_endpoint(&self) -> Option<&str> {
+        self.x402_client.as_ref().map(|_| self.endpoint.as_str())
+    }
+
+    /// Cap the next x402 payments to the remaining AI spend budget (None = no cap)
+    pub fn set_x402_spend_limit(&self, limit: Option<u64>) {
+        if let Some(ref x402) = self.x402_client {
+            x402.set_spend_limit(limit);
+        }
+    }
+
+    /// Set sampling parameters sent with every request
+    pub fn with_sampling(mut self, temperature: Option<f32>, top_p: Option<f32>) -> Self {
+        self.temperature = temperature;
+        self.top_p = top_p;
+        self
+    }
+
     /// Set the broadcaster for emitting retry events
     pub fn with_broadcaster(mut self, broadcaster: Arc<EventBroadcaster>, channel_id: i64) -> Self {
         self.broadcaster = Some(broadcaster);
@@ -341,17 +385,10 @@ impl OpenAIClient {
         }
     }
 
-    pub async fn generate_text(&self, messages: Vec<Message>) -> Result<String, String> {
-        let response = self.generate_with_tools_internal(messages, vec![], vec![]).await
-            .map_err(|e| e.to_string())?;
-        Ok(response.content)
-    }
-
-    /// Generate text and return payment info if x402 payment was made
-    pub async fn generate_text_with_payment_info(&self, messages: Vec<Message>) -> Result<(String, Option<X402PaymentInfo>), String> {
-        let response = self.generate_with_tools_internal(messages, vec![], vec![]).await
-            .map_err(|e| e.to_string())?;
-        Ok((response.content, response.x402_payment))
+    /// Generate text, keeping x402 payment info and token usage
+    pub async fn generate_text_response(&self, messages: Vec<Message>) -> Result<AiResponse, String> {
+        self.generate_with_tools_internal(messages, vec![], vec![]).await
+            .map_err(|e| e.to_string())
     }
 
     pub async fn generate_with_tools(
@@ -427,6 +464,8 @@ impl OpenAIClient {
             model: self.model.clone(),
             messages: api_messages,
             max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
             tools: openai_tools.clone(),
             tool_choice: if tools.is_empty() { None } else { Some("required".to_string()) },
             stream: None,
@@ -591,6 +630,11 @@ impl OpenAIClient {
         let response_data: OpenAICompletionResponse = serde_json::from_str(&response_text)
             .map_err(|e| AiError::new(format!("Failed to parse OpenAI response: {} - body: {}", e, response_text)))?;
 
+        let usage = response_data.usage.as_ref().map(|u| TokenUsage {
+            input_tokens: u.prompt_tokens,
+            output_tokens: u.completion_tokens,
+        });
+
         let choice = response_data
             .choices
             .first()
@@ -639,6 +683,7 @@ impl OpenAIClient {
                 Some("end_turn".to_string())
             },
             x402_payment,
+            usage,
         })
     }
 
@@ -751,6 +796,8 @@ impl OpenAIClient {
             model: self.model.clone(),
             messages: api_messages,
             max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            top_p: self.top_p,
             tools: openai_tools.clone(),
             tool_choice: if tools.is_empty() { None } else { Some("required".to_string()) },
             stream: Some(true),
@@ -992,6 +1039,10 @@ impl OpenAIClient {
                 Some("end_turn".to_string())
             },
             x402_payment: None, // Streaming doesn't support x402 yet
+            usage: usage.map(|(input, output)| TokenUsage {
+                input_tokens: input as u64,
+                output_tokens: output as u64,
+            }),
         })
     }
 }
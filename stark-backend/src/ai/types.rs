@@ -282,6 +282,16 @@ pub struct AiResponse {
     /// x402 payment info if a payment was made for this request
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x402_payment: Option<X402PaymentInfo>,
+    /// Token usage as reported by the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+}
+
+/// Provider-reported token counts for one AI call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
 }
 
 impl AiResponse {
@@ -291,6 +301,7 @@ impl AiResponse {
             tool_calls: vec![],
             stop_reason: Some("end_turn".to_string()),
             x402_payment: None,
+            usage: None,
         }
     }
 
@@ -300,6 +311,7 @@ impl AiResponse {
             tool_calls,
             stop_reason: Some("tool_use".to_string()),
             x402_payment: None,
+            usage: None,
         }
     }
 
@@ -309,6 +321,12 @@ impl AiResponse {
         self
     }
 
+    /// Add provider-reported token usage to the response
+    pub fn with_usage(mut self, usage: Option<TokenUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
+
     /// Check if the response contains tool calls
     pub fn has_tool_calls(&self) -> bool {
         !self.tool_calls.is_empty()
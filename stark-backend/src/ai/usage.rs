@@ -0,0 +1,152 @@
+//! Recording token usage and x402 payments of AI calls
+//!
+//! The dispatcher records the calls of its main and tool loops. Calls made
+//! outside of them (context compaction and memory flushes, session summaries,
+//! memory consolidation, transaction verification) go through
+//! [`AiClient::generate_sidecar_text`], which records them under the `sidecar`
+//! archetype so they show up in usage reports and count against AI budgets.
+
+use super::{AiClient, AiResponse, Message, TokenUsage};
+use crate::context::estimate_tokens;
+use crate::db::tables::ai_usage::AiUsageRecord;
+use crate::db::Database;
+use crate::x402::budget::AI_PAYMENT_KIND;
+
+/// Archetype recorded for AI calls made outside the dispatcher loops
+pub const SIDECAR_ARCHETYPE: &str = "sidecar";
+
+/// What an AI call is attributed to in usage and payment records
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UsageScope<'a> {
+    pub channel_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub cron_job_id: Option<&'a str>,
+}
+
+/// Local token estimate for a set of messages (used when a provider reports no usage)
+pub fn estimate_messages_tokens(messages: &[Message]) -> u64 {
+    let tokens: i64 = messages.iter().map(|m| estimate_tokens(&m.content) as i64).sum();
+    tokens.max(0) as u64
+}
+
+/// Record the token usage and cost of one AI response. Token counts are estimated
+/// locally when the provider reported none; an x402 USDC payment is the exact cost.
+pub fn record_usage(
+    db: &Database,
+    model: &str,
+    scope: UsageScope<'_>,
+    archetype: &str,
+    response: &AiResponse,
+    estimated_input_tokens: u64,
+) {
+    let (usage, estimated) = match response.usage {
+        Some(usage) => (usage, false),
+        None => {
+            let output_tokens: i64 = estimate_tokens(&response.content) as i64
+                + response.tool_calls.iter()
+                    .map(|c| estimate_tokens(&c.arguments.to_string()) as i64)
+                    .sum::<i64>();
+            let usage = TokenUsage {
+                input_tokens: estimated_input_tokens,
+                output_tokens: output_tokens.max(0) as u64,
+            };
+            (usage, true)
+        }
+    };
+    let cost_usd = match response.x402_payment {
+        Some(ref payment) if payment.asset == "USDC" => {
+            payment.amount.parse::<u64>().map(|micro| micro as f64 / 1_000_000.0).unwrap_or(0.0)
+        }
+        _ => crate::ai_pricing::estimate_cost(model, &usage),
+    };
+
+    let record = AiUsageRecord {
+        channel_id: scope.channel_id,
+        session_id: scope.session_id,
+        cron_job_id: scope.cron_job_id,
+        archetype,
+        model,
+        input_tokens: usage.input_tokens,
+        output_tokens: usage.output_tokens,
+        estimated,
+        cost_usd,
+    };
+    if let Err(e) = db.record_ai_usage(&record) {
+        log::error!("[AI_USAGE] Failed to record AI usage: {}", e);
+    }
+}
+
+/// Record the x402 payment of an AI response, if one was made
+pub fn record_payment(db: &Database, scope: UsageScope<'_>, response: &AiResponse) {
+    let Some(ref payment_info) = response.x402_payment else {
+        return;
+    };
+    if let Err(e) = db.record_x402_payment(
+        scope.channel_id,
+        scope.session_id,
+        scope.cron_job_id,
+        AI_PAYMENT_KIND,
+        None,
+        payment_info.resource.as_deref(),
+        &payment_info.amount,
+        &payment_info.amount_formatted,
+        &payment_info.asset,
+        &payment_info.pay_to,
+        payment_info.tx_hash.as_deref(),
+        &payment_info.status.to_string(),
+    ) {
+        log::error!("[AI_USAGE] Failed to record x402 payment: {}", e);
+    }
+}
+
+impl AiClient {
+    /// Generate text for a call made outside the dispatcher loops and record its
+    /// usage (archetype `sidecar`) and x402 payment. Nothing is recorded without a database.
+    pub async fn generate_sidecar_text(
+        &self,
+        messages: Vec<Message>,
+        db: Option<&Database>,
+        scope: UsageScope<'_>,
+    ) -> Result<String, String> {
+        let estimated_input_tokens = estimate_messages_tokens(&messages);
+        let response = self.generate_text_response(messages).await?;
+        if let Some(db) = db {
+            record_usage(db, self.model(), scope, SIDECAR_ARCHETYPE, &response, estimated_input_tokens);
+            record_payment(db, scope, &response);
+        }
+        Ok(response.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{MessageRole, MockAiClient};
+    use crate::db::tables::ai_usage::AiUsageGroupBy;
+
+    #[tokio::test]
+    async fn test_sidecar_call_is_recorded() {
+        let db = Database::new(":memory:").unwrap();
+        let client = AiClient::Mock(MockAiClient::new(vec![Ok(AiResponse::text("summary".to_string()))]));
+        let messages = vec![Message {
+            role: MessageRole::User,
+            content: "Summarize this conversation".to_string(),
+        }];
+
+        let text = client
+            .generate_sidecar_text(messages, Some(&db), UsageScope { channel_id: Some(0), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(text, "summary");
+
+        let usage = db.list_ai_usage_grouped(AiUsageGroupBy::Archetype, None, 10).unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].key.as_deref(), Some(SIDECAR_ARCHETYPE));
+        assert_eq!(usage[0].totals.calls, 1);
+        assert_eq!(usage[0].totals.estimated_calls, 1);
+
+        // Without a database the call still works, it just isn't recorded
+        let client = AiClient::Mock(MockAiClient::new(vec![Ok(AiResponse::text("ok".to_string()))]));
+        assert_eq!(client.generate_sidecar_text(vec![], None, UsageScope::default()).await.unwrap(), "ok");
+    }
+}
@@ -12,6 +12,9 @@ pub struct AiEndpointPreset {
     pub model_archetype: String,
     #[serde(default)]
     pub x402_cost: Option<u64>,
+    /// Model name sent with requests (used by embedding presets)
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 pub fn load_ai_endpoints(config_dir: &Path) {
@@ -57,6 +60,7 @@ fn default_endpoints() -> HashMap<String, AiEndpointPreset> {
             endpoint: "https://kimi.defirelay.com/api/v1/chat/completions".to_string(),
             model_archetype: "kimi".to_string(),
             x402_cost: None,
+            model: None,
         },
     );
     endpoints.insert(
@@ -66,6 +70,7 @@ fn default_endpoints() -> HashMap<String, AiEndpointPreset> {
             endpoint: "https://llama.defirelay.com/api/v1/chat/completions".to_string(),
             model_archetype: "llama".to_string(),
             x402_cost: None,
+            model: None,
         },
     );
     endpoints
@@ -0,0 +1,120 @@
+//! Per-model token prices used to estimate the cost of AI calls
+//!
+//! Prices are in USD per million tokens, keyed by model name. A key ending in `*` matches any
+//! model starting with the prefix (the longest prefix wins) and `*` alone is the fallback.
+//! Models without a matching entry are recorded with a cost of zero.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::ai::TokenUsage;
+
+static AI_PRICING: OnceLock<HashMap<String, ModelPrice>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// USD per million input (prompt) tokens
+    pub input_per_mtok: f64,
+    /// USD per million output (completion) tokens
+    pub output_per_mtok: f64,
+}
+
+impl ModelPrice {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input_per_mtok + usage.output_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+pub fn load_ai_pricing(config_dir: &Path) {
+    let config_path = config_dir.join("ai_pricing.ron");
+
+    let pricing = if config_path.exists() {
+        match std::fs::read_to_string(&config_path) {
+            Ok(content) => match ron::from_str::<HashMap<String, ModelPrice>>(&content) {
+                Ok(pricing) => {
+                    log::info!("Loaded prices for {} AI models from config", pricing.len());
+                    pricing
+                }
+                Err(e) => {
+                    log::error!("Failed to parse ai_pricing.ron: {}", e);
+                    default_pricing()
+                }
+            },
+            Err(e) => {
+                log::error!("Failed to read ai_pricing.ron: {}", e);
+                default_pricing()
+            }
+        }
+    } else {
+        log::info!("No ai_pricing.ron found, using defaults");
+        default_pricing()
+    };
+
+    if AI_PRICING.set(pricing).is_err() {
+        log::warn!("AI pricing already initialized");
+    }
+}
+
+fn default_pricing() -> HashMap<String, ModelPrice> {
+    let price = |input_per_mtok, output_per_mtok| ModelPrice { input_per_mtok, output_per_mtok };
+    HashMap::from([
+        ("claude-opus-4*".to_string(), price(15.0, 75.0)),
+        ("claude-sonnet-4*".to_string(), price(3.0, 15.0)),
+        ("claude-3-5-haiku*".to_string(), price(0.8, 4.0)),
+        ("gpt-4o-mini*".to_string(), price(0.15, 0.6)),
+        ("gpt-4o*".to_string(), price(2.5, 10.0)),
+    ])
+}
+
+/// Find the price entry for a model: exact name first, then the longest matching prefix
+/// pattern, then `*`
+pub fn find_price(pricing: &HashMap<String, ModelPrice>, model: &str) -> Option<ModelPrice> {
+    if let Some(price) = pricing.get(model) {
+        return Some(*price);
+    }
+    pricing
+        .iter()
+        .filter_map(|(pattern, price)| {
+            let prefix = pattern.strip_suffix('*')?;
+            model.starts_with(prefix).then_some((prefix.len(), *price))
+        })
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, price)| price)
+}
+
+/// Estimated USD cost of a call to `model` (0 for unpriced models)
+pub fn estimate_cost(model: &str, usage: &TokenUsage) -> f64 {
+    AI_PRICING
+        .get()
+        .and_then(|pricing| find_price(pricing, model))
+        .map(|price| price.cost(usage))
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_price_prefers_exact_then_longest_prefix() {
+        let mut pricing = default_pricing();
+        pricing.insert("*".to_string(), ModelPrice { input_per_mtok: 1.0, output_per_mtok: 1.0 });
+        pricing.insert("gpt-4o-2024-08-06".to_string(), ModelPrice { input_per_mtok: 5.0, output_per_mtok: 5.0 });
+
+        assert_eq!(find_price(&pricing, "gpt-4o-2024-08-06").unwrap().input_per_mtok, 5.0);
+        assert_eq!(find_price(&pricing, "gpt-4o-mini-2024-07-18").unwrap().input_per_mtok, 0.15);
+        assert_eq!(find_price(&pricing, "gpt-4o").unwrap().input_per_mtok, 2.5);
+        assert_eq!(find_price(&pricing, "llama3.3").unwrap().input_per_mtok, 1.0);
+        assert!(find_price(&default_pricing(), "llama3.3").is_none());
+    }
+
+    #[test]
+    fn test_cost() {
+        let price = ModelPrice { input_per_mtok: 3.0, output_per_mtok: 15.0 };
+        let usage = TokenUsage { input_tokens: 10_000, output_tokens: 2_000 };
+        assert!((price.cost(&usage) - 0.06).abs() < 1e-9);
+    }
+}
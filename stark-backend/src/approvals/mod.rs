@@ -0,0 +1,262 @@
+//! Token approval auditor
+//!
+//! Scans ERC-20 `Approval` events emitted for the bot wallet and tracked Safes
+//! on every configured network, re-reads the live allowance of each
+//! (token, spender) pair seen and rates it. Unlimited allowances and spenders
+//! that are neither a preset contract nor an address book contact are flagged.
+//! The `token_approvals` tool lists them and queues revocations
+//! (`approve(spender, 0)`).
+
+mod scan;
+
+pub use scan::{decode_symbol, scan_approvals, scan_due};
+
+use crate::address_book::{Contact, TrustLevel};
+use crate::db::tables::approvals::TokenApproval;
+use crate::swap;
+use crate::tools::presets::{get_web3_preset, list_web3_presets};
+use ethers::abi::{self, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::id;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Spenders granted allowances by the built-in approve presets
+const KNOWN_SPENDERS: &[(&str, &str)] = &[
+    ("0x000000000022d473030f116ddee9f6b43ac78ba3", "Uniswap Permit2"),
+    ("0x0000000000001ff3684f28c67538d4d072c22734", "0x AllowanceHolder"),
+    ("0xa238dd80c259a72e81d7e4664a9801593f98d1c5", "Aave V3 Pool (Base)"),
+    ("0x87870bca3f3fd6335c3f4ce8392d69350b4fa4e2", "Aave V3 Pool (Ethereum)"),
+    ("0x794a61358d6845594f94dc1db02a252b5b4814ad", "Aave V3 Pool (Polygon)"),
+    ("0xa23a42d266653846e05d8f356a52298844537472", "StarkLicense registry"),
+];
+
+/// Allowances at or above 2^128 are treated as unlimited (max uint256 and the
+/// other "infinite" values wallets use are all far above any real balance)
+fn unlimited_threshold() -> U256 {
+    U256::one() << 128
+}
+
+pub fn is_unlimited(allowance: U256) -> bool {
+    allowance >= unlimited_threshold()
+}
+
+/// Who a spender is
+#[derive(Debug, Clone, Serialize)]
+pub struct SpenderLabel {
+    pub label: String,
+    /// "builtin", "preset" or "contact"
+    pub source: String,
+    pub blocked: bool,
+}
+
+/// Spender labels keyed by lowercase address: the built-in table, then the
+/// spender of every `approve` preset (including skill presets), then address
+/// book contacts
+pub fn spender_labels(contacts: &[Contact]) -> HashMap<String, SpenderLabel> {
+    let mut labels: HashMap<String, SpenderLabel> = KNOWN_SPENDERS
+        .iter()
+        .map(|(address, label)| {
+            (
+                address.to_string(),
+                SpenderLabel {
+                    label: label.to_string(),
+                    source: "builtin".to_string(),
+                    blocked: false,
+                },
+            )
+        })
+        .collect();
+
+    for name in list_web3_presets() {
+        let Some(preset) = get_web3_preset(&name) else {
+            continue;
+        };
+        if preset.function != "approve" {
+            continue;
+        }
+        if let Some(spender) = preset.static_params.first().and_then(|s| s.parse::<Address>().ok()) {
+            labels
+                .entry(format!("{:?}", spender))
+                .or_insert_with(|| SpenderLabel {
+                    label: format!("{} preset", name),
+                    source: "preset".to_string(),
+                    blocked: false,
+                });
+        }
+    }
+
+    for contact in contacts {
+        let blocked = contact.trust_level == TrustLevel::Blocked;
+        match labels.get_mut(&contact.address) {
+            // A blocked contact overrides any other label
+            Some(existing) if blocked => existing.blocked = true,
+            Some(_) => {}
+            None => {
+                labels.insert(
+                    contact.address.clone(),
+                    SpenderLabel {
+                        label: contact.label.clone(),
+                        source: "contact".to_string(),
+                        blocked,
+                    },
+                );
+            }
+        }
+    }
+
+    labels
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Risk {
+    Low,
+    Medium,
+    High,
+}
+
+impl Risk {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Risk::Low => "low",
+            Risk::Medium => "medium",
+            Risk::High => "high",
+        }
+    }
+}
+
+/// Rate an allowance: unknown or blocked spenders and unlimited amounts raise the risk
+pub fn assess_risk(allowance: U256, spender: Option<&SpenderLabel>) -> (Risk, Vec<String>) {
+    let mut reasons = Vec::new();
+    let unlimited = is_unlimited(allowance);
+    if unlimited {
+        reasons.push("unlimited approval".to_string());
+    }
+    let risk = match spender {
+        Some(s) if s.blocked => {
+            reasons.push("spender is blocked in the address book".to_string());
+            Risk::High
+        }
+        Some(_) if unlimited => Risk::Medium,
+        Some(_) => Risk::Low,
+        None => {
+            reasons.push("unknown spender".to_string());
+            if unlimited { Risk::High } else { Risk::Medium }
+        }
+    };
+    (risk, reasons)
+}
+
+/// A live allowance with its spender label and risk
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditedApproval {
+    #[serde(flatten)]
+    pub approval: TokenApproval,
+    pub spender_label: Option<String>,
+    /// Human-readable allowance ("unlimited" or the amount in token units)
+    pub amount: String,
+    pub risk: Risk,
+    pub reasons: Vec<String>,
+}
+
+/// Label and rate approvals, highest risk first
+pub fn audit(approvals: Vec<TokenApproval>, labels: &HashMap<String, SpenderLabel>) -> Vec<AuditedApproval> {
+    let mut audited: Vec<AuditedApproval> = approvals
+        .into_iter()
+        .map(|approval| {
+            let allowance = U256::from_dec_str(&approval.allowance).unwrap_or_default();
+            let label = labels.get(&approval.spender);
+            let (risk, reasons) = assess_risk(allowance, label);
+            let amount = if is_unlimited(allowance) {
+                "unlimited".to_string()
+            } else {
+                match approval.decimals {
+                    Some(decimals) => swap::format_units(allowance, decimals),
+                    None => format!("{} (raw)", allowance),
+                }
+            };
+            AuditedApproval {
+                spender_label: label.map(|l| l.label.clone()),
+                amount,
+                risk,
+                reasons,
+                approval,
+            }
+        })
+        .collect();
+    audited.sort_by(|a, b| b.risk.cmp(&a.risk).then(a.approval.id.cmp(&b.approval.id)));
+    audited
+}
+
+/// Calldata for ERC-20 `approve(spender, amount)` (amount 0 revokes)
+pub fn encode_approve(spender: Address, amount: U256) -> Vec<u8> {
+    let mut data = id("approve(address,uint256)").to_vec();
+    data.extend(abi::encode(&[Token::Address(spender), Token::Uint(amount)]));
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(blocked: bool) -> SpenderLabel {
+        SpenderLabel {
+            label: "Uniswap Permit2".to_string(),
+            source: "builtin".to_string(),
+            blocked,
+        }
+    }
+
+    #[test]
+    fn test_assess_risk() {
+        let known = label(false);
+        assert_eq!(assess_risk(U256::from(1000), Some(&known)).0, Risk::Low);
+        assert_eq!(assess_risk(U256::MAX, Some(&known)).0, Risk::Medium);
+        assert_eq!(assess_risk(U256::from(1000), None).0, Risk::Medium);
+
+        let (risk, reasons) = assess_risk(U256::MAX, None);
+        assert_eq!(risk, Risk::High);
+        assert_eq!(reasons, vec!["unlimited approval", "unknown spender"]);
+
+        assert_eq!(assess_risk(U256::from(1), Some(&label(true))).0, Risk::High);
+    }
+
+    #[test]
+    fn test_is_unlimited() {
+        assert!(is_unlimited(U256::MAX));
+        assert!(is_unlimited(U256::from(u128::MAX) + 1));
+        assert!(!is_unlimited(U256::from(u128::MAX)));
+        assert!(!is_unlimited(U256::exp10(30)));
+    }
+
+    #[test]
+    fn test_spender_labels_contacts() {
+        let contact = |label: &str, address: &str, trust_level| Contact {
+            id: 1,
+            label: label.to_string(),
+            address: address.to_string(),
+            ens_name: None,
+            networks: vec![],
+            notes: None,
+            trust_level,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let contacts = vec![
+            contact("Router", "0x1111111111111111111111111111111111111111", TrustLevel::Known),
+            contact("Bad", KNOWN_SPENDERS[0].0, TrustLevel::Blocked),
+        ];
+        let labels = spender_labels(&contacts);
+        assert_eq!(labels["0x1111111111111111111111111111111111111111"].label, "Router");
+        assert_eq!(labels[KNOWN_SPENDERS[0].0].label, "Uniswap Permit2");
+        assert!(labels[KNOWN_SPENDERS[0].0].blocked);
+    }
+
+    #[test]
+    fn test_encode_approve() {
+        let data = encode_approve(Address::repeat_byte(0x11), U256::zero());
+        assert_eq!(hex::encode(&data[..4]), "095ea7b3");
+        assert_eq!(data.len(), 4 + 64);
+    }
+}
@@ -0,0 +1,375 @@
+//! Scanning Approval events and refreshing live allowances.
+
+use crate::config;
+use crate::db::tables::approvals::TokenApproval;
+use crate::db::Database;
+use crate::tools::builtin::cryptocurrency::token_lookup::{get_network_tokens, get_token_networks};
+use crate::tools::rpc_config::resolve_rpc_config;
+use crate::wallet::WalletProvider;
+use crate::web3::multicall::{self, Call3, Call3Result};
+use crate::x402::X402EvmRpc;
+use chrono::{Duration, Utc};
+use ethers::abi::{self, ParamType};
+use ethers::types::{Address, H256};
+use ethers::utils::keccak256;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Blocks per eth_getLogs request (most providers cap the range at 10k)
+const LOG_CHUNK_BLOCKS: u64 = 10_000;
+
+/// Prevents overlapping scans (scheduler tick + manual trigger)
+static SCAN_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+struct InProgressGuard;
+
+impl InProgressGuard {
+    fn acquire() -> Option<Self> {
+        SCAN_IN_PROGRESS
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| InProgressGuard)
+    }
+}
+
+impl Drop for InProgressGuard {
+    fn drop(&mut self) {
+        SCAN_IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Result of one scan across all networks
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanSummary {
+    pub networks: Vec<String>,
+    pub wallets: usize,
+    /// ERC-20 Approval events found since the previous scan
+    pub new_events: usize,
+    /// Non-zero allowances after the refresh
+    pub live_approvals: usize,
+    /// Networks or wallets that could not be fully scanned
+    pub errors: Vec<String>,
+}
+
+/// Returns true when no scan has been attempted yet or the latest attempt is older
+/// than `interval_hours`. Failed scans count too, so they are retried on the next
+/// interval rather than on every scheduler tick.
+pub fn scan_due(db: &Database, interval_hours: u64) -> bool {
+    if interval_hours == 0 || SCAN_IN_PROGRESS.load(Ordering::SeqCst) {
+        return false;
+    }
+    match db.last_approval_scan_attempt_at() {
+        Ok(Some(latest)) => Utc::now() - latest >= Duration::hours(interval_hours as i64),
+        Ok(None) => true,
+        Err(e) => {
+            log::error!("[approvals] Failed to read last scan attempt: {}", e);
+            false
+        }
+    }
+}
+
+/// `keccak256("Approval(address,address,uint256)")`
+fn approval_topic() -> H256 {
+    H256::from(keccak256("Approval(address,address,uint256)"))
+}
+
+/// The bot wallet plus every enabled Safe tracked in the portfolio
+fn audited_owners(db: &Database, wallet_provider: &Arc<dyn WalletProvider>) -> Result<Vec<Address>, String> {
+    let bot: Address = wallet_provider
+        .get_address()
+        .parse()
+        .map_err(|_| "Invalid bot wallet address".to_string())?;
+    let mut owners = vec![bot];
+
+    let tracked = db
+        .list_portfolio_wallets()
+        .map_err(|e| format!("Failed to list tracked wallets: {}", e))?;
+    for wallet in tracked.into_iter().filter(|w| w.enabled && w.kind == "safe") {
+        match wallet.address.parse::<Address>() {
+            Ok(address) if !owners.contains(&address) => owners.push(address),
+            Ok(_) => {}
+            Err(_) => log::warn!("[approvals] Skipping invalid Safe address {}", wallet.address),
+        }
+    }
+    Ok(owners)
+}
+
+/// Scan new Approval events for all audited wallets on every configured network,
+/// then refresh every known allowance.
+///
+/// The first scan of a wallet starts `STARK_APPROVAL_SCAN_LOOKBACK_BLOCKS` back;
+/// later scans resume after the last block scanned. Networks without an RPC
+/// endpoint are skipped and per-network failures end up in `errors`.
+pub async fn scan_approvals(
+    db: &Arc<Database>,
+    wallet_provider: &Arc<dyn WalletProvider>,
+) -> Result<ScanSummary, String> {
+    let _guard = InProgressGuard::acquire()
+        .ok_or_else(|| "An approval scan is already in progress".to_string())?;
+    if let Err(e) = db.record_approval_scan_attempt() {
+        log::error!("[approvals] Failed to record scan attempt: {}", e);
+    }
+
+    let owners = audited_owners(db, wallet_provider)?;
+    let settings = db
+        .get_bot_settings()
+        .map_err(|e| format!("Failed to load bot settings: {}", e))?;
+
+    let mut summary = ScanSummary {
+        networks: Vec::new(),
+        wallets: owners.len(),
+        new_events: 0,
+        live_approvals: 0,
+        errors: Vec::new(),
+    };
+
+    for network in get_token_networks() {
+        let (url, use_x402) = match resolve_rpc_config(
+            &settings.rpc_provider,
+            settings.custom_rpc_endpoints.as_ref(),
+            &network,
+        ) {
+            Some(cfg) => cfg,
+            None => {
+                log::debug!("[approvals] No RPC endpoint for {}, skipping", network);
+                continue;
+            }
+        };
+        let rpc = match X402EvmRpc::new_with_wallet_provider(wallet_provider.clone(), &network, Some(url), use_x402) {
+            Ok(rpc) => rpc,
+            Err(e) => {
+                summary.errors.push(format!("{}: {}", network, e));
+                continue;
+            }
+        };
+
+        match scan_network(db, &rpc, &network, &owners, &mut summary.errors).await {
+            Ok(events) => summary.new_events += events,
+            Err(e) => {
+                log::warn!("[approvals] Failed to scan {}: {}", network, e);
+                summary.errors.push(format!("{}: {}", network, e));
+                continue;
+            }
+        }
+        match refresh_allowances(db, &rpc, &network, &owners).await {
+            Ok(live) => summary.live_approvals += live,
+            Err(e) => summary.errors.push(format!("{}: failed to read allowances: {}", network, e)),
+        }
+        summary.networks.push(network);
+    }
+
+    log::info!(
+        "[approvals] Scan done: {} networks, {} wallets, {} new events, {} live approvals, {} errors",
+        summary.networks.len(),
+        summary.wallets,
+        summary.new_events,
+        summary.live_approvals,
+        summary.errors.len()
+    );
+    Ok(summary)
+}
+
+/// Record Approval events since each owner's cursor. Returns the number of events found.
+async fn scan_network(
+    db: &Database,
+    rpc: &X402EvmRpc,
+    network: &str,
+    owners: &[Address],
+    errors: &mut Vec<String>,
+) -> Result<usize, String> {
+    let head = rpc.block_number().await?;
+    let lookback = config::approval_scan_lookback_blocks();
+    let mut events = 0;
+
+    for owner in owners {
+        let owner_hex = format!("{:?}", owner);
+        let cursor = db
+            .get_approval_scan_cursor(network, &owner_hex)
+            .map_err(|e| format!("Failed to read scan cursor: {}", e))?;
+        let mut from = cursor.map(|b| b + 1).unwrap_or_else(|| head.saturating_sub(lookback));
+
+        while from <= head {
+            let to = (from + LOG_CHUNK_BLOCKS - 1).min(head);
+            let filter = json!({
+                "fromBlock": format!("0x{:x}", from),
+                "toBlock": format!("0x{:x}", to),
+                "topics": [format!("{:?}", approval_topic()), format!("{:?}", H256::from(*owner))],
+            });
+            let logs = match rpc.get_logs(filter).await {
+                Ok(logs) => logs,
+                Err(e) => {
+                    // Keep the cursor at the last complete chunk; the next scan resumes here
+                    errors.push(format!("{} {}: blocks {}-{}: {}", network, owner_hex, from, to, e));
+                    break;
+                }
+            };
+
+            // ERC-721 Approval has the same signature but an indexed token ID (4 topics)
+            for log in logs.iter().filter(|l| l.topics.len() == 3) {
+                let spender = Address::from(log.topics[2]);
+                let block = log.block_number.map(|b| b.as_u64()).unwrap_or(to);
+                db.record_approval_event(network, &owner_hex, &format!("{:?}", log.address), &format!("{:?}", spender), block)
+                    .map_err(|e| format!("Failed to record approval: {}", e))?;
+                events += 1;
+            }
+
+            db.set_approval_scan_cursor(network, &owner_hex, to)
+                .map_err(|e| format!("Failed to save scan cursor: {}", e))?;
+            from = to + 1;
+        }
+    }
+
+    Ok(events)
+}
+
+/// Re-read every recorded allowance on `network` (plus symbol/decimals for tokens
+/// not in tokens.ron). Returns the number of non-zero allowances.
+async fn refresh_allowances(
+    db: &Database,
+    rpc: &X402EvmRpc,
+    network: &str,
+    owners: &[Address],
+) -> Result<usize, String> {
+    let owner_set: HashSet<String> = owners.iter().map(|o| format!("{:?}", o)).collect();
+    let approvals: Vec<TokenApproval> = db
+        .list_token_approvals(Some(network), None, false)
+        .map_err(|e| format!("Failed to list approvals: {}", e))?
+        .into_iter()
+        .filter(|a| owner_set.contains(&a.owner))
+        .collect();
+    if approvals.is_empty() {
+        return Ok(0);
+    }
+
+    let known_tokens: HashMap<String, (String, u8)> = get_network_tokens(network)
+        .into_iter()
+        .map(|(symbol, info)| (info.address.to_lowercase(), (symbol, info.decimals)))
+        .collect();
+
+    // Allowance calls first, then metadata calls for unknown tokens without metadata
+    let mut calls: Vec<Call3> = Vec::new();
+    for approval in &approvals {
+        let (Ok(token), Ok(owner), Ok(spender)) = (
+            approval.token.parse::<Address>(),
+            approval.owner.parse::<Address>(),
+            approval.spender.parse::<Address>(),
+        ) else {
+            return Err(format!("Invalid stored approval {}", approval.id));
+        };
+        calls.push(multicall::erc20_allowance_call(token, owner, spender));
+    }
+    let mut metadata_tokens: Vec<String> = approvals
+        .iter()
+        .filter(|a| a.token_symbol.is_none() && !known_tokens.contains_key(&a.token))
+        .map(|a| a.token.clone())
+        .collect();
+    metadata_tokens.sort();
+    metadata_tokens.dedup();
+    for token in &metadata_tokens {
+        let address: Address = token.parse().map_err(|_| format!("Invalid token {}", token))?;
+        calls.push(multicall::erc20_getter_call(address, "symbol()"));
+        calls.push(multicall::erc20_getter_call(address, "decimals()"));
+    }
+
+    let results = multicall::aggregate3(rpc, &calls).await?;
+    let (allowances, metadata) = results.split_at(approvals.len());
+    let fetched: HashMap<&String, (Option<String>, Option<u8>)> = metadata_tokens
+        .iter()
+        .zip(metadata.chunks(2))
+        .map(|(token, pair)| (token, (decode_symbol(&pair[0]), decode_decimals(&pair[1]))))
+        .collect();
+
+    let mut live = 0;
+    for (approval, result) in approvals.iter().zip(allowances) {
+        // A failed read leaves the previous allowance in place
+        let Some(allowance) = result.as_u256() else {
+            log::debug!("[approvals] allowance() failed for {} on {}", approval.token, network);
+            continue;
+        };
+        if !allowance.is_zero() {
+            live += 1;
+        }
+        let (symbol, decimals) = match known_tokens.get(&approval.token) {
+            Some((symbol, decimals)) => (Some(symbol.clone()), Some(*decimals)),
+            None => fetched.get(&approval.token).cloned().unwrap_or((None, None)),
+        };
+        db.update_approval_allowance(approval.id, &allowance.to_string(), symbol.as_deref(), decimals)
+            .map_err(|e| format!("Failed to update approval: {}", e))?;
+    }
+
+    Ok(live)
+}
+
+/// Decode `symbol()`: a string, or a bytes32 for older tokens (MKR-style)
+pub fn decode_symbol(result: &Call3Result) -> Option<String> {
+    if !result.success {
+        return None;
+    }
+    if let Ok(tokens) = abi::decode(&[ParamType::String], &result.return_data) {
+        return tokens.into_iter().next().and_then(|t| t.into_string()).filter(|s| !s.is_empty());
+    }
+    let bytes = result.return_data.get(..32)?;
+    let symbol: String = bytes.iter().take_while(|b| **b != 0).map(|b| *b as char).collect();
+    (!symbol.is_empty() && symbol.chars().all(|c| c.is_ascii_graphic())).then_some(symbol)
+}
+
+fn decode_decimals(result: &Call3Result) -> Option<u8> {
+    result.as_u256().filter(|d| *d <= 255.into()).map(|d| d.as_u32() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::Token;
+
+    #[test]
+    fn test_scan_due_after_failed_attempt() {
+        let db = Database::new(":memory:").unwrap();
+        assert!(scan_due(&db, 24));
+        assert!(!scan_due(&db, 0));
+        // An attempt that wrote no cursor still holds off the next scan
+        db.record_approval_scan_attempt().unwrap();
+        assert!(!scan_due(&db, 24));
+    }
+
+    #[test]
+    fn test_approval_topic() {
+        assert_eq!(
+            format!("{:?}", approval_topic()),
+            "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"
+        );
+    }
+
+    #[test]
+    fn test_decode_metadata() {
+        let string_result = Call3Result {
+            success: true,
+            return_data: abi::encode(&[Token::String("USDC".to_string())]),
+        };
+        assert_eq!(decode_symbol(&string_result).as_deref(), Some("USDC"));
+
+        let mut bytes32 = [0u8; 32];
+        bytes32[..3].copy_from_slice(b"MKR");
+        let bytes_result = Call3Result {
+            success: true,
+            return_data: bytes32.to_vec(),
+        };
+        assert_eq!(decode_symbol(&bytes_result).as_deref(), Some("MKR"));
+
+        let failed = Call3Result {
+            success: false,
+            return_data: vec![],
+        };
+        assert_eq!(decode_symbol(&failed), None);
+        assert_eq!(decode_decimals(&failed), None);
+
+        let decimals = Call3Result {
+            success: true,
+            return_data: abi::encode(&[Token::Uint(6.into())]),
+        };
+        assert_eq!(decode_decimals(&decimals), Some(6));
+    }
+}
@@ -294,6 +294,10 @@ pub struct AgentSettingsEntry {
     /// Secret key is included so the user doesn't have to re-enter API keys after restore.
     /// The entire backup payload is already encrypted with ECIES — this is not stored in plaintext.
     pub secret_key: Option<String>,
+    pub model: Option<String>,
+    pub provider: crate::models::ProviderKind,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
 }
 
 /// On-chain agent identity registration entry in backup (minimal — everything else fetched from chain)
@@ -0,0 +1,984 @@
+//! starkctl - command-line client for a running StarkBot
+//!
+//! Operates a headless deployment over its HTTP API and WebSocket gateway:
+//! sign in with a wallet key (SIWE), chat with the agent with live tool/progress
+//! output, approve queued transactions, manage channels, cron jobs, skills and
+//! API keys, and tail gateway events.
+//!
+//! Usage:
+//!   starkctl login                       (key from STARKCTL_PRIVATE_KEY, or prompted for)
+//!   starkctl chat "what's my ETH balance on base?"
+//!   starkctl tx list
+//!   starkctl events agent. tool.
+//!
+//! Global options (before or after the command):
+//!   --url URL      Bot URL (default: STARKCTL_URL, the saved session, or http://localhost:8080)
+//!   --token TOKEN  Session token (default: STARKCTL_TOKEN, or the saved session if --url is the
+//!                  bot it was issued by)
+//!   --json         Print raw JSON instead of tables
+//!
+//! The session from `login` is saved to ~/.starkctl/session.json.
+
+use ethers::signers::{LocalWallet, Signer};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+const DEFAULT_URL: &str = "http://localhost:8080";
+
+const USAGE: &str = "\
+starkctl - operate a running StarkBot
+
+USAGE:
+    starkctl [--url URL] [--token TOKEN] [--json] <command> [args]
+
+COMMANDS:
+    login                             Sign in with a wallet key (SIWE) from STARKCTL_PRIVATE_KEY,
+                                      or entered at a hidden prompt (read from stdin when piped)
+    logout                            End the session and forget it
+    whoami                            Show the signed-in operator and role
+    chat [MESSAGE] [--chat ID] [--channel N] [--network NET]
+                                      Send one message, or start an interactive chat without MESSAGE
+    events [PREFIX...]                Tail gateway events, optionally only names starting with PREFIX
+    tx list                           Queued transactions awaiting confirmation
+    tx confirm|deny UUID [--channel N]
+    channels list
+    channels start|stop|restart ID
+    cron list
+    cron runs|run|pause|resume|delete ID
+    cron create --name NAME --schedule-type at|every|cron|event --schedule VALUE
+                [--message TEXT] [--mode main|isolated] [--channel N]
+    skills list
+    skills enable|disable NAME
+    skills reload
+    keys list
+    keys set NAME VALUE
+    keys delete NAME
+    schema                            Print the gateway RPC schema (rpc.discover)
+    rpc METHOD [PARAMS_JSON]          Call any gateway RPC method
+";
+
+// ============================================================================
+// Saved session
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedSession {
+    url: String,
+    token: String,
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(default)]
+    expires_at: Option<i64>,
+}
+
+fn session_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".starkctl").join("session.json")
+}
+
+fn load_session() -> Option<SavedSession> {
+    let raw = std::fs::read_to_string(session_path()).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn save_session(session: &SavedSession) -> Result<(), String> {
+    write_session(&session_path(), session)
+}
+
+fn write_session(path: &Path, session: &SavedSession) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let raw = serde_json::to_string_pretty(session).map_err(|e| e.to_string())?;
+    // The file holds a bearer token: create it owner-only rather than fixing the mode after writing
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // `mode` only applies to new files; tighten one left by an older version
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+        }
+    }
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    file.write_all(raw.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+// ============================================================================
+// Argument parsing
+// ============================================================================
+
+struct Options {
+    url: String,
+    token: Option<String>,
+    json: bool,
+}
+
+/// Remove `--name VALUE` from args and return VALUE
+fn take_flag(args: &mut Vec<String>, name: &str) -> Option<String> {
+    let pos = args.iter().position(|a| a == name)?;
+    if pos + 1 >= args.len() {
+        args.remove(pos);
+        return None;
+    }
+    let value = args.remove(pos + 1);
+    args.remove(pos);
+    Some(value)
+}
+
+/// Remove a boolean `--name` switch from args
+fn take_switch(args: &mut Vec<String>, name: &str) -> bool {
+    match args.iter().position(|a| a == name) {
+        Some(pos) => {
+            args.remove(pos);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The saved token, only if it was issued by the bot at `url`
+fn saved_token_for(saved: Option<SavedSession>, url: &str) -> Option<String> {
+    saved
+        .filter(|s| s.url.trim_end_matches('/') == url)
+        .map(|s| s.token)
+}
+
+fn parse_options(args: &mut Vec<String>) -> Options {
+    let saved = load_session();
+    let url = take_flag(args, "--url")
+        .or_else(|| std::env::var("STARKCTL_URL").ok())
+        .or_else(|| saved.as_ref().map(|s| s.url.clone()))
+        .unwrap_or_else(|| DEFAULT_URL.to_string());
+    let url = url.trim_end_matches('/').to_string();
+    let token = take_flag(args, "--token")
+        .or_else(|| std::env::var("STARKCTL_TOKEN").ok())
+        .or_else(|| saved_token_for(saved, &url));
+    let json = take_switch(args, "--json");
+    Options { url, token, json }
+}
+
+/// Gateway WebSocket URL for a bot URL (http -> ws, https -> wss)
+fn ws_url(url: &str) -> String {
+    let base = url.trim_end_matches('/');
+    let base = if let Some(rest) = base.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = base.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        base.to_string()
+    };
+    format!("{}/ws", base)
+}
+
+fn parse_id(value: Option<&String>, what: &str) -> Result<i64, String> {
+    value
+        .ok_or_else(|| format!("Missing {}", what))?
+        .parse::<i64>()
+        .map_err(|_| format!("{} must be a number", what))
+}
+
+fn required<'a>(value: Option<&'a String>, what: &str) -> Result<&'a str, String> {
+    value.map(|s| s.as_str()).ok_or_else(|| format!("Missing {}", what))
+}
+
+// ============================================================================
+// HTTP API
+// ============================================================================
+
+struct Api {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl Api {
+    fn new(opts: &Options) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: opts.url.clone(),
+            token: opts.token.clone(),
+        }
+    }
+
+    async fn request(&self, method: reqwest::Method, path: &str, body: Option<Value>) -> Result<Value, String> {
+        let mut req = self.client.request(method, format!("{}{}", self.url, path));
+        if let Some(ref token) = self.token {
+            req = req.bearer_auth(token);
+        }
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+        let resp = req.send().await.map_err(|e| format!("Request to {} failed: {}", self.url, e))?;
+        let status = resp.status();
+        let value: Value = resp.json().await.unwrap_or(Value::Null);
+        if !status.is_success() || value.get("success") == Some(&Value::Bool(false)) {
+            let error = value
+                .get("error")
+                .and_then(|e| e.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| format!("HTTP {}", status));
+            return Err(error);
+        }
+        Ok(value)
+    }
+
+    async fn get(&self, path: &str) -> Result<Value, String> {
+        self.request(reqwest::Method::GET, path, None).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value, String> {
+        self.request(reqwest::Method::POST, path, Some(body)).await
+    }
+
+    async fn put(&self, path: &str, body: Value) -> Result<Value, String> {
+        self.request(reqwest::Method::PUT, path, Some(body)).await
+    }
+
+    async fn delete(&self, path: &str, body: Value) -> Result<Value, String> {
+        self.request(reqwest::Method::DELETE, path, Some(body)).await
+    }
+}
+
+// ============================================================================
+// Gateway client
+// ============================================================================
+
+struct Gateway {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+    /// Events received while waiting for an RPC response
+    pending_events: VecDeque<Value>,
+}
+
+impl Gateway {
+    async fn connect(opts: &Options) -> Result<Self, String> {
+        let token = opts
+            .token
+            .clone()
+            .ok_or("Not signed in. Run `starkctl login` or pass --token")?;
+        let url = ws_url(&opts.url);
+        let (ws, _) = tokio_tungstenite::connect_async(url.as_str())
+            .await
+            .map_err(|e| format!("Failed to connect to {}: {}", url, e))?;
+        let mut gateway = Self {
+            ws,
+            next_id: 0,
+            pending_events: VecDeque::new(),
+        };
+        gateway.call("auth", json!({ "token": token })).await?;
+        Ok(gateway)
+    }
+
+    /// Call an RPC method and wait for its response
+    async fn call(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = format!("starkctl-{}", self.next_id);
+        let request = json!({ "id": id, "method": method, "params": params });
+        self.ws
+            .send(Message::Text(request.to_string()))
+            .await
+            .map_err(|e| format!("Gateway send failed: {}", e))?;
+
+        loop {
+            let frame = self.read_frame().await?.ok_or("Gateway closed the connection")?;
+            if frame.get("type").and_then(|t| t.as_str()) == Some("event") {
+                self.pending_events.push_back(frame);
+                continue;
+            }
+            if frame.get("id").and_then(|v| v.as_str()) != Some(id.as_str()) {
+                continue;
+            }
+            if let Some(error) = frame.get("error") {
+                let message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
+                return Err(format!("{} failed: {}", method, message));
+            }
+            return Ok(frame.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    /// Next server-push event, or None when the connection closes
+    async fn next_event(&mut self) -> Result<Option<Value>, String> {
+        if let Some(event) = self.pending_events.pop_front() {
+            return Ok(Some(event));
+        }
+        loop {
+            let Some(frame) = self.read_frame().await? else {
+                return Ok(None);
+            };
+            if frame.get("type").and_then(|t| t.as_str()) == Some("event") {
+                return Ok(Some(frame));
+            }
+        }
+    }
+
+    async fn read_frame(&mut self) -> Result<Option<Value>, String> {
+        while let Some(msg) = self.ws.next().await {
+            match msg.map_err(|e| format!("Gateway error: {}", e))? {
+                Message::Text(text) => {
+                    return serde_json::from_str(&text)
+                        .map(Some)
+                        .map_err(|e| format!("Invalid gateway frame: {}", e));
+                }
+                Message::Ping(data) => {
+                    let _ = self.ws.send(Message::Pong(data)).await;
+                }
+                Message::Close(_) => return Ok(None),
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+}
+
+// ============================================================================
+// Output
+// ============================================================================
+
+fn print_json(value: &Value) {
+    println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+}
+
+/// Print rows of JSON objects as an aligned table of the given fields
+fn print_table(rows: &[Value], columns: &[(&str, &str)]) {
+    if rows.is_empty() {
+        println!("(none)");
+        return;
+    }
+    let cell = |row: &Value, field: &str| match row.get(field) {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+    let table: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|(field, _)| truncate(&cell(row, field), 60)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, (_, header))| table.iter().map(|r| r[i].chars().count()).max().unwrap_or(0).max(header.len()))
+        .collect();
+
+    let line = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{:<width$}", c, width = w))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", line(columns.iter().map(|(_, h)| h.to_string()).collect()));
+    for row in table {
+        println!("{}", line(row));
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    let single_line = text.replace('\n', " ");
+    if single_line.chars().count() <= max {
+        return single_line;
+    }
+    format!("{}...", single_line.chars().take(max - 3).collect::<String>())
+}
+
+fn output(opts: &Options, value: &Value, columns: &[(&str, &str)]) {
+    match value.as_array() {
+        Some(rows) if !opts.json => print_table(rows, columns),
+        _ => print_json(value),
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Read a secret line from stdin, without echo when stdin is a terminal
+fn read_secret(prompt: &str) -> Result<String, String> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        let mut line = String::new();
+        stdin.read_line(&mut line).map_err(|e| format!("Failed to read stdin: {}", e))?;
+        return Ok(line.trim().to_string());
+    }
+
+    eprint!("{}", prompt);
+    let _ = std::io::stderr().flush();
+    let stty = |arg: &str| {
+        std::process::Command::new("stty")
+            .arg(arg)
+            .stdin(std::process::Stdio::inherit())
+            .status()
+            .is_ok_and(|s| s.success())
+    };
+    if !stty("-echo") {
+        return Err("Could not hide terminal input; set STARKCTL_PRIVATE_KEY instead".to_string());
+    }
+    let mut line = String::new();
+    let read = stdin.read_line(&mut line);
+    stty("echo");
+    eprintln!();
+    read.map_err(|e| format!("Failed to read stdin: {}", e))?;
+    Ok(line.trim().to_string())
+}
+
+async fn cmd_login(opts: &Options, args: &mut Vec<String>) -> Result<(), String> {
+    // Kept for scripts; a key on the command line shows up in `ps` and shell history
+    let flag_key = take_flag(args, "--key");
+    if flag_key.is_some() {
+        eprintln!("warning: --key exposes the private key to other users and shell history; use STARKCTL_PRIVATE_KEY or the prompt");
+    }
+    let key = match flag_key.or_else(|| std::env::var("STARKCTL_PRIVATE_KEY").ok()) {
+        Some(key) => key,
+        None => read_secret("Wallet private key: ")?,
+    };
+    if key.trim().is_empty() {
+        return Err("No private key given".to_string());
+    }
+    let wallet: LocalWallet = key
+        .trim()
+        .trim_start_matches("0x")
+        .parse()
+        .map_err(|e| format!("Invalid private key: {}", e))?;
+    let address = format!("{:?}", wallet.address()).to_lowercase();
+
+    let api = Api { token: None, ..Api::new(opts) };
+    let challenge = api
+        .post("/api/auth/generate_challenge", json!({ "public_address": address }))
+        .await?;
+    let challenge = challenge
+        .get("challenge")
+        .and_then(|c| c.as_str())
+        .ok_or("Server returned no challenge")?
+        .to_string();
+
+    let signature = wallet
+        .sign_message(&challenge)
+        .await
+        .map_err(|e| format!("Failed to sign challenge: {}", e))?;
+    let login = api
+        .post(
+            "/api/auth/validate_auth",
+            json!({
+                "public_address": address,
+                "challenge": challenge,
+                "signature": format!("0x{}", hex::encode(signature.to_vec())),
+            }),
+        )
+        .await?;
+    let token = login
+        .get("token")
+        .and_then(|t| t.as_str())
+        .ok_or("Server returned no token")?
+        .to_string();
+
+    save_session(&SavedSession {
+        url: opts.url.clone(),
+        token,
+        address: Some(address.clone()),
+        expires_at: login.get("expires_at").and_then(|e| e.as_i64()),
+    })?;
+    println!("Signed in to {} as {}", opts.url, address);
+    Ok(())
+}
+
+async fn cmd_logout(opts: &Options) -> Result<(), String> {
+    if let Some(ref token) = opts.token {
+        Api::new(opts).post("/api/auth/logout", json!({ "token": token })).await?;
+    }
+    let path = session_path();
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+    }
+    println!("Signed out");
+    Ok(())
+}
+
+async fn cmd_whoami(opts: &Options) -> Result<(), String> {
+    let me = Api::new(opts).get("/api/auth/me").await?;
+    if opts.json {
+        print_json(&me);
+    } else {
+        println!(
+            "{} ({}) on {}",
+            me.get("public_address").and_then(|v| v.as_str()).unwrap_or("?"),
+            me.get("role").and_then(|v| v.as_str()).unwrap_or("?"),
+            opts.url
+        );
+    }
+    Ok(())
+}
+
+/// Where a chat goes and which events belong to it
+struct ChatTarget {
+    channel_id: i64,
+    chat_id: Option<String>,
+    network: Option<String>,
+}
+
+impl ChatTarget {
+    fn matches(&self, data: &Value) -> bool {
+        let channel_ok = data.get("channel_id").and_then(|c| c.as_i64()).is_none_or(|c| c == self.channel_id);
+        let chat_ok = match (data.get("chat_id").and_then(|c| c.as_str()), self.chat_id.as_deref()) {
+            (Some(event_chat), Some(chat)) => event_chat == chat,
+            _ => true,
+        };
+        channel_ok && chat_ok
+    }
+}
+
+async fn cmd_chat(opts: &Options, args: &mut Vec<String>) -> Result<(), String> {
+    let mut target = ChatTarget {
+        chat_id: take_flag(args, "--chat"),
+        channel_id: take_flag(args, "--channel").map(|c| parse_id(Some(&c), "channel")).transpose()?.unwrap_or(0),
+        network: take_flag(args, "--network"),
+    };
+    let mut gateway = Gateway::connect(opts).await?;
+
+    let message = args.join(" ");
+    if !message.trim().is_empty() {
+        return send_and_stream(&mut gateway, &mut target, &message).await;
+    }
+
+    println!("Chatting with {} (channel {}). Ctrl-C stops a running reply, Ctrl-D exits.", opts.url, target.channel_id);
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        eprint!("> ");
+        let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? else {
+            break;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Err(e) = send_and_stream(&mut gateway, &mut target, line).await {
+            eprintln!("error: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Send one message and print progress events until the reply completes
+async fn send_and_stream(gateway: &mut Gateway, target: &mut ChatTarget, text: &str) -> Result<(), String> {
+    let mut params = json!({ "text": text, "channel_id": target.channel_id });
+    if let Some(ref chat_id) = target.chat_id {
+        params["chat_id"] = json!(chat_id);
+    }
+    if let Some(ref network) = target.network {
+        params["network"] = json!(network);
+    }
+    let accepted = gateway.call("chat.send", params).await?;
+    let request_id = accepted.get("request_id").and_then(|r| r.as_str()).unwrap_or_default().to_string();
+    // Stick to the conversation the server picked so follow-ups continue it
+    if target.chat_id.is_none() {
+        target.chat_id = accepted.get("chat_id").and_then(|c| c.as_str()).map(String::from);
+    }
+
+    let mut streamed_text = false;
+    loop {
+        let event = tokio::select! {
+            event = gateway.next_event() => event?,
+            _ = tokio::signal::ctrl_c() => {
+                gateway.call("chat.stop", json!({ "channel_id": target.channel_id })).await?;
+                eprintln!("\n[stopped]");
+                continue;
+            }
+        };
+        let Some(event) = event else {
+            return Err("Gateway closed the connection".to_string());
+        };
+        let name = event.get("event").and_then(|e| e.as_str()).unwrap_or_default();
+        let data = event.get("data").cloned().unwrap_or(Value::Null);
+        let str_field = |field: &str| data.get(field).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+        if name == "chat.completed" {
+            if str_field("request_id") != request_id {
+                continue;
+            }
+            if streamed_text {
+                println!();
+            }
+            if let Some(error) = data.get("error").and_then(|e| e.as_str()) {
+                return Err(error.to_string());
+            }
+            let response = str_field("response");
+            if !response.trim().is_empty() {
+                println!("{}", response.trim_end());
+            }
+            return Ok(());
+        }
+
+        if !target.matches(&data) {
+            continue;
+        }
+        match name {
+            "stream.content_delta" => {
+                print!("{}", str_field("content"));
+                streamed_text = true;
+            }
+            "agent.tool_call" => eprintln!("  → {}", str_field("tool_name")),
+            "tool.result" => {
+                let tool = str_field("tool_name");
+                if tool == "say_to_user" {
+                    println!("{}", str_field("content").trim_end());
+                } else {
+                    let ok = data.get("success").and_then(|s| s.as_bool()).unwrap_or(false);
+                    let ms = data.get("duration_ms").and_then(|d| d.as_i64()).unwrap_or(0);
+                    eprintln!("  {} {} ({} ms)", if ok { "✓" } else { "✗" }, tool, ms);
+                }
+            }
+            "agent.thinking" | "execution.thinking" => {
+                let text = data.get("message").or_else(|| data.get("text")).and_then(|t| t.as_str()).unwrap_or_default();
+                if !text.is_empty() {
+                    eprintln!("  … {}", truncate(text, 100));
+                }
+            }
+            "agent.question" => {
+                println!("? {}", str_field("question"));
+                if let Some(options) = data.get("options").and_then(|o| o.as_array()) {
+                    for (i, option) in options.iter().enumerate() {
+                        println!("  {}. {}", i + 1, option.as_str().unwrap_or_default());
+                    }
+                }
+            }
+            "tx_queue.confirmation_required" => {
+                eprintln!("  ! transaction {} awaits confirmation (starkctl tx confirm {})", str_field("uuid"), str_field("uuid"));
+            }
+            "agent.error" => eprintln!("  ! {}", str_field("error")),
+            _ => {}
+        }
+    }
+}
+
+async fn cmd_events(opts: &Options, args: &[String]) -> Result<(), String> {
+    let mut gateway = Gateway::connect(opts).await?;
+    eprintln!("Tailing gateway events from {} (Ctrl-C to exit)", opts.url);
+    while let Some(event) = gateway.next_event().await? {
+        let name = event.get("event").and_then(|e| e.as_str()).unwrap_or_default();
+        if !args.is_empty() && !args.iter().any(|prefix| name.starts_with(prefix.as_str())) {
+            continue;
+        }
+        if opts.json {
+            println!("{}", event);
+        } else {
+            let data = event.get("data").cloned().unwrap_or(Value::Null);
+            println!("{:<32} {}", name, truncate(&data.to_string(), 160));
+        }
+    }
+    Ok(())
+}
+
+async fn cmd_tx(opts: &Options, args: &mut Vec<String>) -> Result<(), String> {
+    let channel_id = take_flag(args, "--channel").map(|c| parse_id(Some(&c), "channel")).transpose()?.unwrap_or(0);
+    let mut gateway = Gateway::connect(opts).await?;
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            let pending = gateway.call("tx_queue.pending", json!({})).await?;
+            output(opts, &pending, &[
+                ("uuid", "UUID"),
+                ("network", "NETWORK"),
+                ("to", "TO"),
+                ("value_formatted", "VALUE"),
+                ("created_at", "QUEUED"),
+            ]);
+        }
+        Some(action @ ("confirm" | "deny")) => {
+            let uuid = required(args.get(1), "transaction UUID")?;
+            let result = gateway
+                .call(&format!("tx_queue.{}", action), json!({ "uuid": uuid, "channel_id": channel_id }))
+                .await?;
+            if opts.json {
+                print_json(&result);
+            } else if action == "confirm" {
+                println!(
+                    "Broadcast {}",
+                    result.get("explorer_url").or_else(|| result.get("tx_hash")).and_then(|v| v.as_str()).unwrap_or(uuid)
+                );
+            } else {
+                println!("Denied {}", uuid);
+            }
+        }
+        Some(other) => return Err(format!("Unknown tx command '{}'", other)),
+    }
+    Ok(())
+}
+
+async fn cmd_channels(opts: &Options, args: &[String]) -> Result<(), String> {
+    let mut gateway = Gateway::connect(opts).await?;
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            let channels = gateway.call("channels.status", json!({})).await?;
+            output(opts, &channels, &[
+                ("id", "ID"),
+                ("channel_type", "TYPE"),
+                ("name", "NAME"),
+                ("enabled", "ENABLED"),
+                ("running", "RUNNING"),
+            ]);
+        }
+        Some(action @ ("start" | "stop" | "restart")) => {
+            let id = parse_id(args.get(1), "channel ID")?;
+            gateway.call(&format!("channels.{}", action), json!({ "id": id })).await?;
+            println!("Channel {}: {}", id, action);
+        }
+        Some(other) => return Err(format!("Unknown channels command '{}'", other)),
+    }
+    Ok(())
+}
+
+async fn cmd_cron(opts: &Options, args: &mut Vec<String>) -> Result<(), String> {
+    let mut gateway = Gateway::connect(opts).await?;
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            let jobs = gateway.call("cron.list", json!({})).await?;
+            output(opts, &jobs, &[
+                ("id", "ID"),
+                ("name", "NAME"),
+                ("schedule_type", "TYPE"),
+                ("schedule_value", "SCHEDULE"),
+                ("status", "STATUS"),
+                ("next_run_at", "NEXT RUN"),
+            ]);
+        }
+        Some("runs") => {
+            let id = parse_id(args.get(1), "job ID")?;
+            let runs = gateway.call("cron.runs", json!({ "id": id })).await?;
+            output(opts, &runs, &[
+                ("id", "RUN"),
+                ("started_at", "STARTED"),
+                ("success", "SUCCESS"),
+                ("error", "ERROR"),
+            ]);
+        }
+        Some("create") => {
+            let mut params = json!({
+                "name": take_flag(args, "--name").ok_or("Missing --name")?,
+                "schedule_type": take_flag(args, "--schedule-type").ok_or("Missing --schedule-type")?,
+                "schedule_value": take_flag(args, "--schedule").ok_or("Missing --schedule")?,
+            });
+            if let Some(message) = take_flag(args, "--message") {
+                params["message"] = json!(message);
+            }
+            if let Some(mode) = take_flag(args, "--mode") {
+                params["session_mode"] = json!(mode);
+            }
+            if let Some(channel) = take_flag(args, "--channel") {
+                params["channel_id"] = json!(parse_id(Some(&channel), "channel")?);
+            }
+            let result = gateway.call("cron.create", params).await?;
+            match result.get("job") {
+                Some(job) if !opts.json => println!(
+                    "Created job {} ({})",
+                    job.get("id").cloned().unwrap_or(Value::Null),
+                    job.get("name").and_then(|n| n.as_str()).unwrap_or_default()
+                ),
+                _ => print_json(&result),
+            }
+        }
+        Some(action @ ("run" | "pause" | "resume" | "delete")) => {
+            let id = parse_id(args.get(1), "job ID")?;
+            gateway.call(&format!("cron.{}", action), json!({ "id": id })).await?;
+            println!("Job {}: {}", id, action);
+        }
+        Some(other) => return Err(format!("Unknown cron command '{}'", other)),
+    }
+    Ok(())
+}
+
+async fn cmd_skills(opts: &Options, args: &[String]) -> Result<(), String> {
+    let api = Api::new(opts);
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            let skills = api.get("/api/skills").await?;
+            output(opts, &skills, &[
+                ("name", "NAME"),
+                ("version", "VERSION"),
+                ("enabled", "ENABLED"),
+                ("source", "SOURCE"),
+                ("description", "DESCRIPTION"),
+            ]);
+        }
+        Some(action @ ("enable" | "disable")) => {
+            let name = required(args.get(1), "skill name")?;
+            api.put(
+                &format!("/api/skills/{}/enabled", urlencoding::encode(name)),
+                json!({ "enabled": action == "enable" }),
+            )
+            .await?;
+            println!("Skill {}: {}d", name, action);
+        }
+        Some("reload") => {
+            api.post("/api/skills/reload", json!({})).await?;
+            println!("Skills reloaded");
+        }
+        Some(other) => return Err(format!("Unknown skills command '{}'", other)),
+    }
+    Ok(())
+}
+
+async fn cmd_keys(opts: &Options, args: &[String]) -> Result<(), String> {
+    let api = Api::new(opts);
+    match args.first().map(String::as_str) {
+        Some("list") | None => {
+            let keys = api.get("/api/keys").await?;
+            let keys = keys.get("keys").cloned().unwrap_or(json!([]));
+            output(opts, &keys, &[("key_name", "NAME"), ("key_preview", "VALUE"), ("updated_at", "UPDATED")]);
+        }
+        Some("set") => {
+            let name = required(args.get(1), "key name")?;
+            let value = required(args.get(2), "key value")?;
+            api.post("/api/keys", json!({ "key_name": name, "api_key": value })).await?;
+            println!("Saved {}", name);
+        }
+        Some("delete") => {
+            let name = required(args.get(1), "key name")?;
+            api.delete("/api/keys", json!({ "key_name": name })).await?;
+            println!("Deleted {}", name);
+        }
+        Some(other) => return Err(format!("Unknown keys command '{}'", other)),
+    }
+    Ok(())
+}
+
+async fn cmd_rpc(opts: &Options, args: &[String]) -> Result<(), String> {
+    let method = required(args.first(), "method")?;
+    let params = match args.get(1) {
+        Some(raw) => serde_json::from_str(raw).map_err(|e| format!("PARAMS_JSON is not valid JSON: {}", e))?,
+        None => json!({}),
+    };
+    let result = Gateway::connect(opts).await?.call(method, params).await?;
+    print_json(&result);
+    Ok(())
+}
+
+async fn run(mut args: Vec<String>) -> Result<(), String> {
+    if args.is_empty() || take_switch(&mut args, "--help") || take_switch(&mut args, "-h") {
+        print!("{}", USAGE);
+        return Ok(());
+    }
+    let opts = parse_options(&mut args);
+    let command = args.remove(0);
+
+    match command.as_str() {
+        "login" => cmd_login(&opts, &mut args).await,
+        "logout" => cmd_logout(&opts).await,
+        "whoami" => cmd_whoami(&opts).await,
+        "chat" => cmd_chat(&opts, &mut args).await,
+        "events" => cmd_events(&opts, &args).await,
+        "tx" => cmd_tx(&opts, &mut args).await,
+        "channels" => cmd_channels(&opts, &args).await,
+        "cron" => cmd_cron(&opts, &mut args).await,
+        "skills" => cmd_skills(&opts, &args).await,
+        "keys" => cmd_keys(&opts, &args).await,
+        "schema" => cmd_rpc(&opts, &["rpc.discover".to_string()]).await,
+        "rpc" => cmd_rpc(&opts, &args).await,
+        "help" => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command '{}'. Run `starkctl --help`.", other)),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(args).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ws_url() {
+        assert_eq!(ws_url("http://localhost:8080"), "ws://localhost:8080/ws");
+        assert_eq!(ws_url("https://bot.example.com/"), "wss://bot.example.com/ws");
+    }
+
+    #[test]
+    fn test_take_flag_and_switch() {
+        let mut a = args(&["tx", "confirm", "--channel", "3", "abc", "--json"]);
+        assert_eq!(take_flag(&mut a, "--channel").as_deref(), Some("3"));
+        assert!(take_switch(&mut a, "--json"));
+        assert!(!take_switch(&mut a, "--json"));
+        assert_eq!(a, args(&["tx", "confirm", "abc"]));
+        assert_eq!(take_flag(&mut a, "--missing"), None);
+    }
+
+    #[test]
+    fn test_saved_token_only_for_its_url() {
+        let saved = || {
+            Some(SavedSession {
+                url: "https://bot.example.com/".to_string(),
+                token: "secret".to_string(),
+                address: None,
+                expires_at: None,
+            })
+        };
+        assert_eq!(saved_token_for(saved(), "https://bot.example.com").as_deref(), Some("secret"));
+        assert_eq!(saved_token_for(saved(), "https://evil.example.com"), None);
+        assert_eq!(saved_token_for(saved(), "http://bot.example.com"), None);
+        assert_eq!(saved_token_for(None, "https://bot.example.com"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_session_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".starkctl").join("session.json");
+        let session = SavedSession {
+            url: "https://bot.example.com".to_string(),
+            token: "secret".to_string(),
+            address: None,
+            expires_at: None,
+        };
+
+        write_session(&path, &session).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        // A file left world-readable by an older version is tightened on the next login
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_session(&path, &session).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(std::fs::read_to_string(&path).unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn test_chat_target_matches() {
+        let target = ChatTarget {
+            channel_id: 0,
+            chat_id: Some("gateway-0xabc".to_string()),
+            network: None,
+        };
+        assert!(target.matches(&json!({ "channel_id": 0, "chat_id": "gateway-0xabc" })));
+        assert!(target.matches(&json!({ "channel_id": 0 })));
+        assert!(!target.matches(&json!({ "channel_id": 0, "chat_id": "web-1234" })));
+        assert!(!target.matches(&json!({ "channel_id": 5 })));
+    }
+}
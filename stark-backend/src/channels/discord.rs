@@ -1,14 +1,18 @@
 use crate::channels::dispatcher::MessageDispatcher;
 use crate::channels::safe_mode_rate_limiter::SafeModeChannelRateLimiter;
+use crate::channels::tx_approval::{self, TxApprovalAction};
 use crate::channels::types::{ChannelType, NormalizedMessage};
 use crate::channels::util;
 use crate::db::Database;
 use crate::discord_hooks;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::{Channel, ToolOutputVerbosity};
+use crate::models::{Channel, ChannelSettingKey, ToolOutputVerbosity};
+use crate::tx_queue::QueuedTransaction;
 use serenity::all::{
-    Client, Context, EditMessage, EventHandler, GatewayIntents, Message, MessageId, Ready,
+    ButtonStyle, ChannelId, Client, ComponentInteraction, Context, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse,
+    EditMessage, EventHandler, GatewayIntents, Http, Interaction, Message, MessageId, Ready,
 };
 use std::sync::Arc;
 use tokio::sync::oneshot;
@@ -87,6 +91,37 @@ fn format_mode_change_for_discord(mode: &str, label: &str, reason: Option<&str>)
     }
 }
 
+/// Post a queued transaction summary with approve/deny buttons
+async fn send_approval_prompt(http: &Http, chat_id: &str, tx: &QueuedTransaction, has_admins: bool) {
+    let discord_channel_id = match chat_id.parse::<u64>() {
+        Ok(id) if id != 0 => ChannelId::new(id),
+        _ => {
+            log::warn!("Discord: Cannot post approval prompt to invalid channel id '{}'", chat_id);
+            return;
+        }
+    };
+
+    let summary = tx_approval::format_summary(tx);
+    let message = if has_admins {
+        CreateMessage::new()
+            .content(summary)
+            .components(vec![CreateActionRow::Buttons(vec![
+                CreateButton::new(TxApprovalAction::Approve.callback_data(&tx.uuid))
+                    .label("Approve")
+                    .style(ButtonStyle::Success),
+                CreateButton::new(TxApprovalAction::Deny.callback_data(&tx.uuid))
+                    .label("Deny")
+                    .style(ButtonStyle::Danger),
+            ])])
+    } else {
+        CreateMessage::new().content(format!("{}\n\n{}", summary, tx_approval::NO_ADMIN_NOTE))
+    };
+
+    if let Err(e) = discord_channel_id.send_message(http, message).await {
+        log::error!("Discord: Failed to send approval prompt for {}: {}", tx.uuid, e);
+    }
+}
+
 /// Check if a tool terminates the chat loop and sets the final response.
 
 struct DiscordHandler {
@@ -211,9 +246,72 @@ impl EventHandler for DiscordHandler {
     async fn ready(&self, _ctx: Context, ready: Ready) {
         log::info!("Discord: Bot connected as {}", ready.user.name);
     }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Interaction::Component(component) = interaction {
+            self.handle_approval_interaction(&ctx, component).await;
+        }
+    }
 }
 
 impl DiscordHandler {
+    /// Handle an approve/deny button press on an approval prompt.
+    /// Unlike chat commands, approvals never fall back to Discord's Administrator
+    /// permission: only the configured admin user IDs may move funds.
+    async fn handle_approval_interaction(&self, ctx: &Context, component: ComponentInteraction) {
+        let (action, uuid) = match TxApprovalAction::parse_callback(&component.data.custom_id) {
+            Some(parsed) => parsed,
+            None => return,
+        };
+
+        let user_id = component.user.id.to_string();
+        let admins = tx_approval::load_admin_ids(&self.db, self.channel_id, ChannelSettingKey::DiscordAdminUserIds);
+        if !admins.contains(&user_id) {
+            log::warn!(
+                "Discord: User {} tried to {} transaction {} but is not an admin",
+                user_id,
+                action.as_str(),
+                uuid
+            );
+            let response = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content("Only configured admin users can approve or deny transactions.")
+                    .ephemeral(true),
+            );
+            if let Err(e) = component.create_response(&ctx.http, response).await {
+                log::error!("Discord: Failed to reject approval interaction: {}", e);
+            }
+            return;
+        }
+
+        // Acknowledge first; broadcasting can take longer than Discord's 3s window
+        if let Err(e) = component.create_response(&ctx.http, CreateInteractionResponse::Acknowledge).await {
+            log::error!("Discord: Failed to acknowledge approval interaction: {}", e);
+            return;
+        }
+
+        let outcome = match tx_approval::apply_action(
+            action,
+            &uuid,
+            self.channel_id,
+            &component.user.name,
+            &self.dispatcher,
+            self.broadcaster.clone(),
+        )
+        .await
+        {
+            Ok(text) => text,
+            Err(e) => format!("⚠️ Could not {} transaction: {}", action.as_str(), e),
+        };
+
+        // Replace the buttons with the outcome
+        let content = format!("{}\n\n{}", component.message.content, outcome);
+        let edit = EditInteractionResponse::new().content(content).components(vec![]);
+        if let Err(e) = component.edit_response(&ctx.http, edit).await {
+            log::warn!("Discord: Failed to update approval prompt: {}", e);
+        }
+    }
+
     /// Dispatch a message to the AI and send the response
     async fn dispatch_and_respond(
         &self,
@@ -478,11 +576,12 @@ pub async fn start_discord_listener(
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
 
+    let tx_queue = dispatcher.tx_queue();
     let handler = DiscordHandler {
         channel_id,
         dispatcher,
         broadcaster: broadcaster.clone(),
-        db,
+        db: db.clone(),
         safe_mode_rate_limiter,
     };
 
@@ -494,6 +593,17 @@ pub async fn start_discord_listener(
 
     log::info!("Discord: Client created successfully");
 
+    // Post approve/deny prompts for transactions queued from this channel
+    let _prompt_forwarder = tx_queue.map(|tx_queue| {
+        let http = client.http.clone();
+        tx_approval::PromptForwarder::spawn(channel_id, broadcaster.clone(), tx_queue, move |chat_id, tx| {
+            let http = http.clone();
+            let has_admins =
+                !tx_approval::load_admin_ids(&db, channel_id, ChannelSettingKey::DiscordAdminUserIds).is_empty();
+            async move { send_approval_prompt(&http, &chat_id, &tx, has_admins).await }
+        })
+    });
+
     // Emit started event
     broadcaster.broadcast(GatewayEvent::channel_started(
         channel_id,
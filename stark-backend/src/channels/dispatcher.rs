@@ -3,19 +3,23 @@ use crate::ai::{
     AiClient, ArchetypeId, ArchetypeRegistry, AiResponse, Message, MessageRole, ModelArchetype,
     ThinkingLevel, ToolHistoryEntry, ToolResponse,
 };
-use crate::channels::types::{DispatchResult, NormalizedMessage};
+use crate::channels::types::{DispatchResult, NormalizedMessage, PendingUserQuestion};
 use crate::config::MemoryConfig;
+use crate::ai::usage::estimate_messages_tokens;
 use crate::context::{self, estimate_tokens, ContextManager};
 use crate::controllers::api_keys::ApiKeyId;
 use std::str::FromStr;
 use crate::db::Database;
-use crate::execution::ExecutionTracker;
+use crate::execution::{ExecutionTracker, PendingConfirmation, PendingConfirmationManager};
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
+use crate::hooks::{HookContext, HookEvent, HookResult};
 use crate::models::session_message::MessageRole as DbMessageRole;
 use crate::models::{AgentSettings, CompletionStatus, SessionScope, DEFAULT_MAX_TOOL_ITERATIONS};
+use crate::qmd_memory::embeddings::{EmbeddingClient, EmbeddingSettings};
 use crate::qmd_memory::MemoryStore;
 use crate::tools::{ToolConfig, ToolContext, ToolDefinition, ToolExecution, ToolRegistry};
+use crate::x402::budget::{self as x402_budget, BudgetCheck};
 use chrono::Utc;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -39,6 +43,21 @@ const FALLBACK_MAX_TOOL_ITERATIONS: usize = DEFAULT_MAX_TOOL_ITERATIONS as usize
 /// How often to broadcast "still waiting" events during long AI calls
 const AI_PROGRESS_INTERVAL_SECS: u64 = 30;
 
+/// Tool results recorded in an execution trace, in call order (see `execution::trace`)
+type RecordedToolResults = std::collections::VecDeque<(String, crate::tools::ToolResult)>;
+
+/// Summarize an AI request for the execution trace: message/tool counts plus
+/// the estimated prompt size in tokens
+fn ai_call_trace_input(messages: &[Message], tool_history_len: usize, tool_names: &[String]) -> (Value, u64) {
+    let tokens = estimate_messages_tokens(messages);
+    let input = serde_json::json!({
+        "messages": messages.len(),
+        "tool_history": tool_history_len,
+        "tools": tool_names,
+    });
+    (input, tokens)
+}
+
 /// Result of attempting to advance to the next task in the queue
 enum TaskAdvanceResult {
     /// Started working on the next task
@@ -108,9 +127,14 @@ pub struct MessageDispatcher {
     validator_registry: Option<Arc<crate::tool_validators::ValidatorRegistry>>,
     /// Transaction queue manager for queued web3 transactions
     tx_queue: Option<Arc<crate::tx_queue::TxQueueManager>>,
-    /// Mock AI client for integration tests (bypasses real AI API)
-    #[cfg(test)]
+    /// Mock AI client (bypasses real AI API) - used by integration tests and trace replay
     mock_ai_client: Option<crate::ai::MockAiClient>,
+    /// Recorded tool results handed back in order instead of executing tools (trace replay)
+    replay_tool_results: Option<Arc<std::sync::Mutex<RecordedToolResults>>>,
+    /// Unanswered `ask_user` questions, keyed by (channel_id, chat_id)
+    pending_questions: dashmap::DashMap<(i64, String), PendingUserQuestion>,
+    /// Tool calls held for operator confirmation, one per channel
+    pending_confirmations: PendingConfirmationManager,
 }
 
 impl MessageDispatcher {
@@ -168,7 +192,14 @@ impl MessageDispatcher {
         let memory_store = match MemoryStore::new(memory_dir, &memory_config.memory_db_path()) {
             Ok(store) => {
                 log::info!("[DISPATCHER] QMD MemoryStore initialized at {}", memory_config.memory_dir);
-                Some(Arc::new(store))
+                let store = Arc::new(store);
+                if let Some(settings) = EmbeddingSettings::resolve(&memory_config) {
+                    match EmbeddingClient::new(settings, wallet_provider.clone()) {
+                        Ok(client) => store.enable_embeddings(client),
+                        Err(e) => log::warn!("[DISPATCHER] Semantic memory search disabled: {}", e),
+                    }
+                }
+                Some(store)
             }
             Err(e) => {
                 log::error!("[DISPATCHER] Failed to create MemoryStore: {}", e);
@@ -199,13 +230,18 @@ impl MessageDispatcher {
             hook_manager: None,
             validator_registry: None,
             tx_queue: None,
-            #[cfg(test)]
             mock_ai_client: None,
+            replay_tool_results: None,
+            pending_questions: dashmap::DashMap::new(),
+            pending_confirmations: PendingConfirmationManager::new(),
         }
     }
 
     /// Set the hook manager for lifecycle events
     pub fn with_hook_manager(mut self, hook_manager: Arc<crate::hooks::HookManager>) -> Self {
+        if let Some(ref store) = self.memory_store {
+            store.set_hook_manager(hook_manager.clone());
+        }
         self.hook_manager = Some(hook_manager);
         self
     }
@@ -222,18 +258,23 @@ impl MessageDispatcher {
         self
     }
 
-    /// Set a mock AI client for integration tests (bypasses real AI API)
-    #[cfg(test)]
+    /// Set a mock AI client (bypasses real AI API), for integration tests and trace replay
     pub fn with_mock_ai_client(mut self, client: crate::ai::MockAiClient) -> Self {
         self.mock_ai_client = Some(client);
         self
     }
 
-    #[cfg(test)]
     pub fn get_mock_trace(&self) -> Vec<crate::ai::TraceEntry> {
         self.mock_ai_client.as_ref().map(|m| m.get_trace()).unwrap_or_default()
     }
 
+    /// Replay recorded tool results instead of executing tools. Each call pops the
+    /// next result; a tool name that doesn't match the recording fails the call.
+    pub fn with_replay_tool_results(mut self, results: RecordedToolResults) -> Self {
+        self.replay_tool_results = Some(Arc::new(std::sync::Mutex::new(results)));
+        self
+    }
+
     /// Create a dispatcher without tool support (for backwards compatibility)
     pub fn new_without_tools(db: Arc<Database>, broadcaster: Arc<EventBroadcaster>) -> Self {
         // Create a minimal execution tracker for legacy use
@@ -268,8 +309,10 @@ impl MessageDispatcher {
             hook_manager: None,     // No hooks without explicit setup
             validator_registry: None, // No validators without explicit setup
             tx_queue: None,         // No tx queue without explicit setup
-            #[cfg(test)]
             mock_ai_client: None,
+            replay_tool_results: None,
+            pending_questions: dashmap::DashMap::new(),
+            pending_confirmations: PendingConfirmationManager::new(),
         }
     }
 
@@ -283,6 +326,111 @@ impl MessageDispatcher {
         self.subagent_manager.clone()
     }
 
+    /// Transaction queue used for partner-mode confirmations
+    pub fn tx_queue(&self) -> Option<Arc<crate::tx_queue::TxQueueManager>> {
+        self.tx_queue.clone()
+    }
+
+    /// Wallet provider used to broadcast confirmed transactions
+    pub fn wallet_provider(&self) -> Option<Arc<dyn crate::wallet::WalletProvider>> {
+        self.wallet_provider.clone()
+    }
+
+    /// Unanswered `ask_user` questions, optionally limited to one channel
+    pub fn pending_questions(&self, channel_id: Option<i64>) -> Vec<PendingUserQuestion> {
+        let mut questions: Vec<PendingUserQuestion> = self
+            .pending_questions
+            .iter()
+            .filter(|q| channel_id.is_none_or(|id| q.channel_id == id))
+            .map(|q| q.value().clone())
+            .collect();
+        questions.sort_by_key(|q| q.asked_at);
+        questions
+    }
+
+    /// The unanswered `ask_user` question on a chat, if any
+    pub fn pending_question(&self, channel_id: i64, chat_id: &str) -> Option<PendingUserQuestion> {
+        self.pending_questions
+            .get(&(channel_id, chat_id.to_string()))
+            .map(|q| q.value().clone())
+    }
+
+    /// Tool calls held for confirmation
+    pub fn pending_confirmations(&self) -> &PendingConfirmationManager {
+        &self.pending_confirmations
+    }
+
+    /// Unexpired pending confirmation on a channel, if any
+    pub fn get_pending_confirmation(&self, channel_id: i64) -> Option<PendingConfirmation> {
+        self.pending_confirmations.get_pending(channel_id)
+    }
+
+    /// Execute the tool call held for confirmation on a channel.
+    /// Returns the tool output, or an error if nothing is pending or the tool failed.
+    pub async fn api_confirm_transaction(&self, channel_id: i64) -> Result<String, String> {
+        let pending = self
+            .pending_confirmations
+            .confirm(channel_id)
+            .ok_or_else(|| format!("No pending confirmation on channel {}", channel_id))?;
+
+        log::info!(
+            "[CONFIRMATION] Executing confirmed {} ({}) on channel {}",
+            pending.tool_name,
+            pending.id,
+            channel_id
+        );
+        self.broadcaster.broadcast(GatewayEvent::confirmation_approved(
+            channel_id,
+            &pending.id,
+            &pending.tool_name,
+        ));
+
+        let channel_type = self
+            .db
+            .get_channel(channel_id)
+            .ok()
+            .flatten()
+            .map(|c| c.channel_type)
+            .unwrap_or_else(|| "web".to_string());
+        let mut tool_context = ToolContext::new()
+            .with_channel(channel_id, channel_type)
+            .with_user(pending.user_id.clone())
+            .with_session(pending.session_id)
+            .with_workspace(crate::config::workspace_dir())
+            .with_broadcaster(self.broadcaster.clone())
+            .with_database(self.db.clone());
+        if let Some(ref tx_queue) = self.tx_queue {
+            tool_context = tool_context.with_tx_queue(tx_queue.clone());
+        }
+        if let Some(ref wallet_provider) = self.wallet_provider {
+            tool_context = tool_context.with_wallet_provider(wallet_provider.clone());
+        }
+
+        let tool_config = self.tool_registry.default_config().clone();
+        let result = self
+            .execute_tool(channel_id, &pending.tool_name, &pending.arguments, &tool_context, &tool_config)
+            .await;
+        if result.success {
+            Ok(result.content)
+        } else {
+            Err(result.content)
+        }
+    }
+
+    /// Drop the tool call held for confirmation on a channel, returning its description
+    pub fn api_cancel_transaction(&self, channel_id: i64) -> Result<String, String> {
+        let pending = self
+            .pending_confirmations
+            .cancel(channel_id)
+            .ok_or_else(|| format!("No pending confirmation on channel {}", channel_id))?;
+        self.broadcaster.broadcast(GatewayEvent::confirmation_rejected(
+            channel_id,
+            &pending.id,
+            &pending.tool_name,
+        ));
+        Ok(pending.description)
+    }
+
     /// Dispatch a normalized message to the AI and return the response
     pub async fn dispatch(&self, message: NormalizedMessage) -> DispatchResult {
         // Emit message received event
@@ -293,6 +441,10 @@ impl MessageDispatcher {
             &message.text,
         ));
 
+        // Any message on a chat answers the question the agent was waiting on
+        self.pending_questions
+            .remove(&(message.channel_id, message.chat_id.clone()));
+
         // Check for reset commands
         let text_lower = message.text.trim().to_lowercase();
         if text_lower == "/new" || text_lower == "/reset" {
@@ -385,6 +537,7 @@ impl MessageDispatcher {
                         "[DISPATCH] Deactivated previous {} session {} with {} messages for context",
                         message.channel_type, prev_session.id, messages.len()
                     );
+                    self.fire_session_end(message.channel_id, prev_session.id, "superseded").await;
                 }
 
                 messages
@@ -445,6 +598,17 @@ impl MessageDispatcher {
             }
         };
 
+        // Gateway channels always start a fresh session; others are new when still empty
+        let is_new_session = is_gateway_channel
+            || self.db.count_session_messages(session.id).map(|n| n == 0).unwrap_or(false);
+        if is_new_session {
+            self.run_hooks(
+                &mut HookContext::new(HookEvent::SessionStart)
+                    .with_channel(message.channel_id, Some(session.id))
+                    .with_extra(serde_json::json!({ "channel_type": message.channel_type })),
+            ).await;
+        }
+
         // Reset session state when a new message comes in on a previously-completed session
         // This allows the session to be reused for new requests
         if let Ok(Some(status)) = self.db.get_session_completion_status(session.id) {
@@ -468,7 +632,38 @@ impl MessageDispatcher {
         }
 
         // Use clean text (with inline thinking directive removed) for storage
-        let message_text = clean_text.as_deref().unwrap_or(&message.text);
+        let mut user_text = clean_text.clone().unwrap_or_else(|| message.text.clone());
+
+        // BeforeAgentStart hooks may drop the message (Skip), reject it (Cancel)
+        // or rewrite it (Replace) before the agent sees it
+        let start_result = self.run_hooks(
+            &mut HookContext::new(HookEvent::BeforeAgentStart)
+                .with_channel(message.channel_id, Some(session.id))
+                .with_message(user_text.clone()),
+        ).await;
+        match start_result {
+            HookResult::Skip => {
+                log::info!("[DISPATCH] Message skipped by BeforeAgentStart hook");
+                self.execution_tracker.complete_execution(message.channel_id);
+                return DispatchResult::success(String::new());
+            }
+            HookResult::Cancel(reason) => {
+                let error = format!("Message rejected by hook: {}", reason);
+                log::info!("[DISPATCH] {}", error);
+                self.broadcaster.broadcast(GatewayEvent::agent_error(
+                    message.channel_id,
+                    &error,
+                ));
+                self.execution_tracker.complete_execution(message.channel_id);
+                return DispatchResult::error(error);
+            }
+            other => {
+                if let Some(text) = other.replacement_text() {
+                    user_text = text;
+                }
+            }
+        }
+        let message_text = user_text.as_str();
 
         // Estimate tokens for the user message
         let user_tokens = estimate_tokens(message_text);
@@ -509,6 +704,9 @@ impl MessageDispatcher {
             }
         };
 
+        // Switch to a cheaper x402 endpoint if the configured one no longer fits the budget
+        let settings = self.apply_budget_fallback(settings, &message, session.id);
+
         // Infer archetype from settings
         let archetype_id = AiClient::infer_archetype(&settings);
         log::info!(
@@ -522,8 +720,7 @@ impl MessageDispatcher {
         // Sync session's max_context_tokens with agent settings for dynamic compaction
         self.context_manager.sync_max_context_tokens(session.id, settings.max_context_tokens);
 
-        // Create AI client — use mock in tests if configured, otherwise create from settings
-        #[cfg(test)]
+        // Create AI client — use the mock if configured (tests, trace replay), otherwise from settings
         let client = if let Some(ref mock) = self.mock_ai_client {
             AiClient::Mock(mock.clone())
         } else {
@@ -543,25 +740,6 @@ impl MessageDispatcher {
                 }
             }
         };
-        #[cfg(not(test))]
-        let client = match AiClient::from_settings_with_wallet_provider(&settings, self.wallet_provider.clone()) {
-            Ok(c) => c.with_broadcaster(Arc::clone(&self.broadcaster), message.channel_id),
-            Err(e) => {
-                let error = format!("Failed to create AI client: {}", e);
-                log::error!("{}", error);
-                // Store error as assistant message so it's visible in the session
-                let _ = self.db.add_session_message(
-                    session.id, DbMessageRole::Assistant,
-                    &format!("[Error] {}", error), None, None, None, None,
-                );
-                self.broadcaster.broadcast(GatewayEvent::agent_error(
-                    message.channel_id,
-                    &error,
-                ));
-                self.execution_tracker.complete_execution(message.channel_id);
-                return DispatchResult::error(error);
-            }
-        };
 
         // Add thinking event before AI generation
         self.execution_tracker.add_thinking(message.channel_id, "Processing request...");
@@ -666,8 +844,8 @@ impl MessageDispatcher {
             );
         }
 
-        // Scan user input for key terms (ETH addresses, token symbols) for context bank
-        let context_bank_items = crate::tools::scan_input(message_text);
+        // Scan user input for key terms (ETH addresses, contacts, token symbols) for context bank
+        let context_bank_items = crate::tools::scan_input_with_contacts(message_text, Some(&self.db));
         if !context_bank_items.is_empty() {
             // Create a temporary context bank for formatting
             let temp_bank = crate::tools::ContextBank::new();
@@ -780,6 +958,11 @@ impl MessageDispatcher {
             log::debug!("[DISPATCH] MemoryStore attached to tool context");
         }
 
+        // Add HookManager so git tools can fire commit/push/PR hooks
+        if let Some(ref hook_manager) = self.hook_manager {
+            tool_context = tool_context.with_hook_manager(hook_manager.clone());
+        }
+
         // Pass safe mode flag to tool context so tools can sandbox themselves
         if is_safe_mode {
             tool_context.extra.insert(
@@ -884,31 +1067,45 @@ impl MessageDispatcher {
             ).await
         } else {
             // Simple generation without tools - with x402 event emission
-            match client.generate_text_with_events(messages, &self.broadcaster, message.channel_id).await {
-                Ok((content, payment)) => {
-                    // Save x402 payment if one was made
-                    if let Some(ref payment_info) = payment {
-                        if let Err(e) = self.db.record_x402_payment(
-                            Some(message.channel_id),
-                            None,
-                            payment_info.resource.as_deref(),
-                            &payment_info.amount,
-                            &payment_info.amount_formatted,
-                            &payment_info.asset,
-                            &payment_info.pay_to,
-                            payment_info.tx_hash.as_deref(),
-                            &payment_info.status.to_string(),
-                        ) {
-                            log::error!("[DISPATCH] Failed to record x402 payment: {}", e);
-                        }
-                    }
-                    Ok(content)
+            let (trace_input, trace_input_tokens) = ai_call_trace_input(&messages, 0, &[]);
+            let ai_started = std::time::Instant::now();
+            let result = match self.check_ai_budget(&client, &message, session.id) {
+                Ok(()) => {
+                    let result = client.generate_text_with_events(messages, &self.broadcaster, message.channel_id).await;
+                    self.record_text_ai_call(message.channel_id, archetype_id.as_str(), trace_input, trace_input_tokens, &result, ai_started);
+                    result
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(response) => {
+                    self.record_ai_usage(&client, &message, session.id, archetype_id.as_str(), &response, trace_input_tokens);
+                    Ok(response.content)
                 }
                 Err(e) => Err(e),
             }
         };
 
-        match final_response {
+        // BeforeResponse hooks may suppress (Skip/Cancel) or rewrite (Replace) the response
+        let final_response = match final_response {
+            Ok(response) if !response.trim().is_empty() => {
+                let hook_result = self.run_hooks(
+                    &mut HookContext::new(HookEvent::BeforeResponse)
+                        .with_channel(message.channel_id, Some(session.id))
+                        .with_message(message_text.to_string())
+                        .with_response(response.clone()),
+                ).await;
+                if let Some(reason) = hook_result.block_reason() {
+                    log::info!("[DISPATCH] Response suppressed by hook: {}", reason);
+                    Ok(String::new())
+                } else {
+                    Ok(hook_result.replacement_text().unwrap_or(response))
+                }
+            }
+            other => other,
+        };
+
+        let dispatch_result = match final_response {
             Ok(response) => {
                 // Estimate tokens for the response
                 let response_tokens = estimate_tokens(&response);
@@ -1005,6 +1202,7 @@ impl MessageDispatcher {
                 );
 
                 // Complete execution tracking
+                self.execution_tracker.set_execution_outcome(message.channel_id, "completed", &response);
                 self.execution_tracker.complete_execution(message.channel_id);
 
                 DispatchResult::success(response)
@@ -1032,12 +1230,31 @@ impl MessageDispatcher {
                     &error,
                 ));
 
+                self.run_hooks(
+                    &mut HookContext::new(HookEvent::OnError)
+                        .with_channel(message.channel_id, Some(session.id))
+                        .with_message(message_text.to_string())
+                        .with_error(error.clone()),
+                ).await;
+
                 // Complete execution tracking on error
+                let status = if self.execution_tracker.is_cancelled(message.channel_id) { "cancelled" } else { "error" };
+                self.execution_tracker.set_execution_outcome(message.channel_id, status, &error);
                 self.execution_tracker.complete_execution(message.channel_id);
 
                 DispatchResult::error(error)
             }
-        }
+        };
+
+        self.run_hooks(
+            &mut HookContext::new(HookEvent::AfterAgentEnd)
+                .with_channel(message.channel_id, Some(session.id))
+                .with_message(message_text.to_string())
+                .with_response(dispatch_result.response.clone())
+                .with_extra(serde_json::json!({ "success": dispatch_result.error.is_none() })),
+        ).await;
+
+        dispatch_result
     }
 
     /// Generate a response with tool execution loop (supports both native and text-based tool calling)
@@ -1199,24 +1416,14 @@ impl MessageDispatcher {
 
         if tools.is_empty() {
             log::warn!("[TOOL_LOOP] No tools available, falling back to text-only generation");
-            let (content, payment) = client.generate_text_with_events(messages, &self.broadcaster, original_message.channel_id).await?;
-            // Save x402 payment if one was made
-            if let Some(ref payment_info) = payment {
-                if let Err(e) = self.db.record_x402_payment(
-                    Some(original_message.channel_id),
-                    None,
-                    payment_info.resource.as_deref(),
-                    &payment_info.amount,
-                    &payment_info.amount_formatted,
-                    &payment_info.asset,
-                    &payment_info.pay_to,
-                    payment_info.tx_hash.as_deref(),
-                    &payment_info.status.to_string(),
-                ) {
-                    log::error!("[TOOL_LOOP] Failed to record x402 payment: {}", e);
-                }
-            }
-            return Ok(content);
+            let (trace_input, trace_input_tokens) = ai_call_trace_input(&messages, 0, &[]);
+            self.check_ai_budget(client, original_message, session_id)?;
+            let ai_started = std::time::Instant::now();
+            let result = client.generate_text_with_events(messages, &self.broadcaster, original_message.channel_id).await;
+            self.record_text_ai_call(original_message.channel_id, archetype_id.as_str(), trace_input, trace_input_tokens, &result, ai_started);
+            let response = result?;
+            self.record_ai_usage(client, original_message, session_id, archetype_id.as_str(), &response, trace_input_tokens);
+            return Ok(response.content);
         }
 
         // Get the archetype for this request
@@ -1435,6 +1642,42 @@ impl MessageDispatcher {
         ));
     }
 
+    /// Run lifecycle hooks for the context's event.
+    /// Returns `Continue(None)` when no hook manager is configured.
+    async fn run_hooks(&self, hook_context: &mut HookContext) -> HookResult {
+        match &self.hook_manager {
+            Some(manager) => manager.execute(hook_context.event, hook_context).await,
+            None => HookResult::Continue(None),
+        }
+    }
+
+    /// Fire SessionEnd hooks (notification only) for a session that was reset,
+    /// superseded or deleted. Every path that ends a session calls this.
+    pub async fn fire_session_end(&self, channel_id: i64, session_id: i64, reason: &str) {
+        self.run_hooks(
+            &mut HookContext::new(HookEvent::SessionEnd)
+                .with_channel(channel_id, Some(session_id))
+                .with_extra(serde_json::json!({ "reason": reason })),
+        ).await;
+    }
+
+    /// Fire OnModeTransition hooks (notification only; the transition has already happened)
+    async fn fire_mode_transition(
+        &self,
+        channel_id: i64,
+        session_id: i64,
+        from: String,
+        to: String,
+        reason: &str,
+    ) {
+        self.run_hooks(
+            &mut HookContext::new(HookEvent::OnModeTransition)
+                .with_channel(channel_id, Some(session_id))
+                .with_mode_transition(from, to)
+                .with_extra(serde_json::json!({ "reason": reason })),
+        ).await;
+    }
+
     /// Save a memory entry when a chat session completes successfully.
     fn save_session_completion_memory(
         &self,
@@ -1504,6 +1747,50 @@ impl MessageDispatcher {
         }
     }
 
+    /// Execute a tool through the registry and record it in the execution trace.
+    ///
+    /// When replaying a recorded run, the next recorded result is returned instead
+    /// of executing the tool, so replays never repeat side effects.
+    async fn execute_tool(
+        &self,
+        channel_id: i64,
+        tool_name: &str,
+        tool_arguments: &Value,
+        tool_context: &ToolContext,
+        tool_config: &ToolConfig,
+    ) -> crate::tools::ToolResult {
+        let started = std::time::Instant::now();
+        let result = match self.replay_tool_results {
+            Some(ref recorded) => {
+                let next = recorded.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                match next {
+                    Some((name, result)) if name == tool_name => result,
+                    Some((name, _)) => crate::tools::ToolResult::error(format!(
+                        "Replay diverged: recorded run called '{}' here, not '{}'",
+                        name, tool_name
+                    )),
+                    None => crate::tools::ToolResult::error(format!(
+                        "Replay diverged: recorded run made no further tool calls (got '{}')",
+                        tool_name
+                    )),
+                }
+            }
+            None => {
+                self.tool_registry
+                    .execute(tool_name, tool_arguments.clone(), tool_context, Some(tool_config))
+                    .await
+            }
+        };
+        self.execution_tracker.record_tool_call(
+            channel_id,
+            tool_name,
+            tool_arguments,
+            &result,
+            started.elapsed().as_millis() as u64,
+        );
+        result
+    }
+
     /// Shared per-tool-call processing used by both native and text tool paths.
     ///
     /// Processes a single tool call: logging, orchestrator dispatch, skill handling,
@@ -1592,6 +1879,64 @@ impl MessageDispatcher {
             };
         }
 
+        // BeforeToolCall hooks may block the call (Skip/Cancel), supply the result
+        // themselves (Replace), or rewrite the arguments in the hook context
+        let mut before_hook = HookContext::new(HookEvent::BeforeToolCall)
+            .with_channel(original_message.channel_id, Some(session_id))
+            .with_tool(tool_name.to_string(), tool_arguments.clone());
+        let before_result = self.run_hooks(&mut before_hook).await;
+        let hooked_result = match &before_result {
+            HookResult::Skip => Some(crate::tools::ToolResult::error(format!(
+                "⚠️ Tool '{}' was skipped by a hook and not executed.",
+                tool_name
+            ))),
+            HookResult::Cancel(reason) => Some(crate::tools::ToolResult::error(format!(
+                "❌ Tool '{}' was blocked by a hook: {}",
+                tool_name, reason
+            ))),
+            HookResult::Replace(_) => before_result.replacement_text().map(crate::tools::ToolResult::success),
+            _ => None,
+        };
+        if let Some(result) = hooked_result {
+            log::info!("[HOOKS] BeforeToolCall intercepted tool '{}'", tool_name);
+            self.broadcaster.broadcast(GatewayEvent::tool_result(
+                original_message.channel_id,
+                Some(&original_message.chat_id),
+                tool_name,
+                result.success,
+                0,
+                &result.content,
+                is_safe_mode,
+            ));
+            let tool_result_content = format!(
+                "**{}:** {}\n{}",
+                if result.success { "Result" } else { "Error" },
+                tool_name,
+                result.content
+            );
+            if let Err(e) = self.db.add_session_message(
+                session_id,
+                DbMessageRole::ToolResult,
+                &tool_result_content,
+                None,
+                Some(tool_name),
+                None,
+                None,
+            ) {
+                log::error!("Failed to save tool result to session: {}", e);
+            }
+            return ToolCallProcessed {
+                result_content: result.content,
+                success: result.success,
+                orchestrator_complete: false,
+                final_summary: None,
+                waiting_for_user_response: false,
+                user_question_content: None,
+            };
+        }
+        let hooked_arguments = before_hook.tool_args.unwrap_or_else(|| tool_arguments.clone());
+        let tool_arguments = &hooked_arguments;
+
         // Check if this is an orchestrator tool
         let orchestrator_result = orchestrator.process_tool_result(tool_name, tool_arguments);
 
@@ -1722,8 +2067,8 @@ impl MessageDispatcher {
                     if let Some(error_msg) = validation_result.to_error_message() {
                         crate::tools::ToolResult::error(error_msg)
                     } else {
-                        let tool_result = self.tool_registry
-                            .execute(tool_name, tool_arguments.clone(), tool_context, Some(tool_config))
+                        let tool_result = self
+                            .execute_tool(original_message.channel_id, tool_name, tool_arguments, tool_context, tool_config)
                             .await;
                         if tool_result.success {
                             orchestrator.record_tool_call(tool_name);
@@ -1731,8 +2076,8 @@ impl MessageDispatcher {
                         tool_result
                     }
                 } else {
-                    let tool_result = self.tool_registry
-                        .execute(tool_name, tool_arguments.clone(), tool_context, Some(tool_config))
+                    let tool_result = self
+                        .execute_tool(original_message.channel_id, tool_name, tool_arguments, tool_context, tool_config)
                         .await;
                     if tool_result.success {
                         orchestrator.record_tool_call(tool_name);
@@ -1746,11 +2091,19 @@ impl MessageDispatcher {
         if tool_name == "set_agent_subtype" && result.success {
             if let Some(subtype_str) = tool_arguments.get("subtype").and_then(|v| v.as_str()) {
                 if let Some(new_subtype) = AgentSubtype::from_str(subtype_str) {
+                    let previous_subtype = orchestrator.current_subtype();
                     orchestrator.set_subtype(new_subtype);
                     log::info!(
                         "[SUBTYPE] Changed to {} mode",
                         new_subtype.label()
                     );
+                    self.fire_mode_transition(
+                        original_message.channel_id,
+                        session_id,
+                        previous_subtype.as_str().to_string(),
+                        new_subtype.as_str().to_string(),
+                        "subtype_change",
+                    ).await;
 
                     // Refresh tools for new subtype
                     *tools = self
@@ -1800,11 +2153,63 @@ impl MessageDispatcher {
             result
         };
 
+        // Execute AfterToolCall hooks (Replace rewrites the result content)
+        let mut result = result;
+        let after_result = self.run_hooks(
+            &mut HookContext::new(HookEvent::AfterToolCall)
+                .with_channel(original_message.channel_id, Some(session_id))
+                .with_tool(tool_name.to_string(), tool_arguments.clone())
+                .with_tool_result(serde_json::json!({
+                    "success": result.success,
+                    "content": result.content,
+                })),
+        ).await;
+        if let HookResult::Error(e) = &after_result {
+            log::warn!("Hook execution failed for tool '{}': {}", tool_name, e);
+        } else if let Some(text) = after_result.replacement_text() {
+            result.content = text;
+        }
+
+        // say_to_user is how the agent replies mid-loop, so BeforeResponse hooks
+        // apply to it too; a suppressed reply keeps the call successful but empty
+        if tool_name == "say_to_user" && result.success {
+            let response_result = self.run_hooks(
+                &mut HookContext::new(HookEvent::BeforeResponse)
+                    .with_channel(original_message.channel_id, Some(session_id))
+                    .with_message(original_message.text.clone())
+                    .with_response(result.content.clone()),
+            ).await;
+            if let Some(reason) = response_result.block_reason() {
+                log::info!("[HOOKS] say_to_user response suppressed by hook: {}", reason);
+                result.content = String::new();
+            } else if let Some(text) = response_result.replacement_text() {
+                result.content = text;
+            }
+        }
+
         // Check metadata for various control signals
         if let Some(metadata) = &result.metadata {
             if metadata.get("requires_user_response").and_then(|v| v.as_bool()).unwrap_or(false) {
                 processed.waiting_for_user_response = true;
                 processed.user_question_content = Some(result.content.clone());
+                let question = PendingUserQuestion {
+                    channel_id: original_message.channel_id,
+                    chat_id: original_message.chat_id.clone(),
+                    session_id,
+                    question: metadata
+                        .get("question")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(&result.content)
+                        .to_string(),
+                    options: metadata
+                        .get("options")
+                        .and_then(|v| serde_json::from_value(v.clone()).ok()),
+                    default: metadata.get("default").and_then(|v| v.as_str()).map(String::from),
+                    asked_at: Utc::now(),
+                };
+                self.broadcaster.broadcast(GatewayEvent::agent_question(&question));
+                self.pending_questions
+                    .insert((question.channel_id, question.chat_id.clone()), question);
                 log::info!("[ORCHESTRATED_LOOP] Tool requires user response, will break after processing");
             }
             // Check if add_task was called
@@ -2078,22 +2483,6 @@ impl MessageDispatcher {
             is_safe_mode,
         ));
 
-        // Execute AfterToolCall hooks
-        if let Some(hook_manager) = &self.hook_manager {
-            use crate::hooks::{HookContext, HookEvent, HookResult};
-            let mut hook_context = HookContext::new(HookEvent::AfterToolCall)
-                .with_channel(original_message.channel_id, Some(session_id))
-                .with_tool(tool_name.to_string(), tool_arguments.clone())
-                .with_tool_result(serde_json::json!({
-                    "success": result.success,
-                    "content": result.content,
-                }));
-            let hook_result = hook_manager.execute(HookEvent::AfterToolCall, &mut hook_context).await;
-            if let HookResult::Error(e) = hook_result {
-                log::warn!("Hook execution failed for tool '{}': {}", tool_name, e);
-            }
-        }
-
         // Save tool result to session
         let tool_result_content = format!(
             "**{}:** {}\n{}",
@@ -2525,6 +2914,13 @@ impl MessageDispatcher {
                     transition.to.label(),
                     Some(&transition.reason),
                 ));
+                self.fire_mode_transition(
+                    original_message.channel_id,
+                    session_id,
+                    transition.from.to_string(),
+                    transition.to.to_string(),
+                    &transition.reason,
+                ).await;
 
                 // Update tools for new mode (using current subtype, strip define_tasks unless skill requires it)
                 let subtype = orchestrator.current_subtype();
@@ -2600,9 +2996,15 @@ impl MessageDispatcher {
                 current_tools.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ")
             );
 
+            if let Err(e) = self.check_ai_budget(client, original_message, session_id) {
+                let _ = self.db.save_agent_context(session_id, orchestrator.context());
+                return Err(e);
+            }
+
             // Generate with native tool support and progress notifications
             let ai_response = match self.generate_with_progress(
                 &client,
+                archetype.id().as_str(),
                 conversation.clone(),
                 tool_history.clone(),
                 current_tools.clone(),
@@ -2662,6 +3064,8 @@ impl MessageDispatcher {
                 }
             };
 
+            self.record_ai_usage(client, original_message, session_id, archetype.id().as_str(), &ai_response, estimate_messages_tokens(&conversation));
+
             log::info!(
                 "[ORCHESTRATED_LOOP] Response - content_len: {}, tool_calls: {}",
                 ai_response.content.len(),
@@ -2678,17 +3082,6 @@ impl MessageDispatcher {
                     &payment_info.pay_to,
                     payment_info.resource.as_deref(),
                 ));
-                let _ = self.db.record_x402_payment(
-                    Some(original_message.channel_id),
-                    None,
-                    payment_info.resource.as_deref(),
-                    &payment_info.amount,
-                    &payment_info.amount_formatted,
-                    &payment_info.asset,
-                    &payment_info.pay_to,
-                    payment_info.tx_hash.as_deref(),
-                    &payment_info.status.to_string(),
-                );
             }
 
             // If no tool calls, check if this is allowed
@@ -3021,6 +3414,13 @@ impl MessageDispatcher {
                     transition.to.label(),
                     Some(&transition.reason),
                 ));
+                self.fire_mode_transition(
+                    original_message.channel_id,
+                    session_id,
+                    transition.from.to_string(),
+                    transition.to.to_string(),
+                    &transition.reason,
+                ).await;
 
                 // Update tools (using current subtype, strip define_tasks unless skill requires it)
                 let subtype = orchestrator.current_subtype();
@@ -3076,12 +3476,22 @@ impl MessageDispatcher {
                 tools.iter().map(|t| &t.name).collect::<Vec<_>>()
             );
 
-            let (ai_content, payment) = match client.generate_text_with_events(
+            if let Err(e) = self.check_ai_budget(client, original_message, session_id) {
+                let _ = self.db.save_agent_context(session_id, orchestrator.context());
+                return Err(e);
+            }
+
+            let tool_names: Vec<String> = tools.iter().map(|t| t.name.clone()).collect();
+            let (trace_input, trace_input_tokens) = ai_call_trace_input(&conversation, 0, &tool_names);
+            let ai_started = std::time::Instant::now();
+            let result = client.generate_text_with_events(
                 conversation.clone(),
                 &self.broadcaster,
                 original_message.channel_id,
-            ).await {
-                Ok(result) => result,
+            ).await;
+            self.record_text_ai_call(original_message.channel_id, archetype.id().as_str(), trace_input, trace_input_tokens, &result, ai_started);
+            let response = match result {
+                Ok(response) => response,
                 Err(e) => {
                     // AI generation failed - save summary of work done so far
                     if !tool_call_log.is_empty() {
@@ -3107,19 +3517,8 @@ impl MessageDispatcher {
                 }
             };
 
-            if let Some(ref payment_info) = payment {
-                let _ = self.db.record_x402_payment(
-                    Some(original_message.channel_id),
-                    None,
-                    payment_info.resource.as_deref(),
-                    &payment_info.amount,
-                    &payment_info.amount_formatted,
-                    &payment_info.asset,
-                    &payment_info.pay_to,
-                    payment_info.tx_hash.as_deref(),
-                    &payment_info.status.to_string(),
-                );
-            }
+            self.record_ai_usage(client, original_message, session_id, archetype.id().as_str(), &response, trace_input_tokens);
+            let ai_content = response.content;
 
             let parsed = archetype.parse_response(&ai_content);
 
@@ -3682,12 +4081,151 @@ impl MessageDispatcher {
         (None, None)
     }
 
+    /// Pre-flight check of the x402 spend budget before an AI call. Caps what the client may
+    /// sign for at the remaining allowance, or stops when the next call would not fit.
+    fn check_ai_budget(&self, client: &AiClient, message: &NormalizedMessage, session_id: i64) -> Result<(), String> {
+        let Some(endpoint) = client.x402_endpoint() else {
+            return Ok(());
+        };
+        let budget = self.db.get_ai_budget()
+            .map_err(|e| format!("Failed to read AI budget: {}", e))?;
+        if budget.is_unlimited() {
+            client.set_x402_spend_limit(None);
+            return Ok(());
+        }
+
+        let cron_job_id = message.cron_job_id();
+        let spend = self.db.get_ai_spend(Some(session_id), cron_job_id)
+            .map_err(|e| format!("Failed to read AI spend: {}", e))?;
+        let estimated_cost = x402_budget::preset_cost(endpoint)
+            .or_else(|| self.db.last_x402_payment_amount(endpoint).ok().flatten());
+
+        match x402_budget::check(&budget, &spend, cron_job_id.is_some(), estimated_cost) {
+            BudgetCheck::Unlimited => {
+                client.set_x402_spend_limit(None);
+                Ok(())
+            }
+            BudgetCheck::Allowed { remaining } => {
+                client.set_x402_spend_limit(Some(remaining));
+                Ok(())
+            }
+            exhausted => {
+                let error = exhausted.exhausted_message().unwrap_or_default();
+                log::warn!("[BUDGET] Stopping channel {}: {}", message.channel_id, error);
+                if let BudgetCheck::Exhausted { scope, limit, spent, .. } = exhausted {
+                    self.broadcaster.broadcast(GatewayEvent::custom(
+                        "ai.budget_exhausted",
+                        serde_json::json!({
+                            "channel_id": message.channel_id,
+                            "session_id": session_id,
+                            "cron_job_id": cron_job_id,
+                            "scope": scope,
+                            "limit": x402_budget::format_usdc(limit),
+                            "spent": x402_budget::format_usdc(spent),
+                        }),
+                    ));
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// When the configured x402 endpoint costs more than the budget has left, switch to the
+    /// most expensive preset of the same archetype that still fits (if fallback is enabled)
+    fn apply_budget_fallback(&self, settings: AgentSettings, message: &NormalizedMessage, session_id: i64) -> AgentSettings {
+        if self.mock_ai_client.is_some() || !crate::x402::is_x402_endpoint(&settings.endpoint) {
+            return settings;
+        }
+        let budget = match self.db.get_ai_budget() {
+            Ok(budget) if budget.fallback_enabled && !budget.is_unlimited() => budget,
+            _ => return settings,
+        };
+        let Some(cost) = x402_budget::preset_cost(&settings.endpoint) else {
+            return settings;
+        };
+        let cron_job_id = message.cron_job_id();
+        let Ok(spend) = self.db.get_ai_spend(Some(session_id), cron_job_id) else {
+            return settings;
+        };
+
+        let BudgetCheck::Exhausted { limit, spent, .. } =
+            x402_budget::check(&budget, &spend, cron_job_id.is_some(), Some(cost))
+        else {
+            return settings;
+        };
+        let presets = crate::ai_endpoint_config::list_ai_endpoints();
+        let Some((preset_id, preset)) =
+            x402_budget::cheaper_endpoint(&presets, &settings.model_archetype, cost, limit.saturating_sub(spent))
+        else {
+            return settings;
+        };
+
+        log::info!(
+            "[BUDGET] {} costs {} USDC per call, falling back to '{}' ({} USDC)",
+            settings.endpoint,
+            x402_budget::format_usdc(cost),
+            preset_id,
+            x402_budget::format_usdc(preset.x402_cost.unwrap_or(0))
+        );
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "ai.budget_fallback",
+            serde_json::json!({
+                "channel_id": message.channel_id,
+                "from": settings.endpoint,
+                "to": preset.endpoint,
+                "preset": preset_id,
+            }),
+        ));
+        AgentSettings {
+            endpoint: preset.endpoint,
+            model: preset.model,
+            ..settings
+        }
+    }
+
+    /// Persist a text-mode AI call (content + optional x402 payment) in the execution trace
+    fn record_text_ai_call(
+        &self,
+        channel_id: i64,
+        archetype: &str,
+        trace_input: Value,
+        trace_input_tokens: u64,
+        result: &Result<AiResponse, String>,
+        started: std::time::Instant,
+    ) {
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let result = result.as_ref().map_err(|e| e.as_str());
+        self.execution_tracker.record_ai_call(channel_id, archetype, trace_input, trace_input_tokens, result, duration_ms);
+    }
+
+    /// Persist the token usage, cost and x402 payment of a successful AI call. Falls back to
+    /// local token estimates when the provider reports no usage; x402-paid calls cost what was paid.
+    fn record_ai_usage(
+        &self,
+        client: &AiClient,
+        message: &NormalizedMessage,
+        session_id: i64,
+        archetype: &str,
+        response: &AiResponse,
+        estimated_input_tokens: u64,
+    ) {
+        let scope = crate::ai::UsageScope {
+            channel_id: Some(message.channel_id),
+            session_id: Some(session_id),
+            cron_job_id: message.cron_job_id(),
+        };
+        crate::ai::usage::record_usage(&self.db, client.model(), scope, archetype, response, estimated_input_tokens);
+        crate::ai::usage::record_payment(&self.db, scope, response);
+    }
+
     /// Call AI with progress notifications for long-running requests
     /// Broadcasts "still waiting" events every 30 seconds and handles timeout errors gracefully
     /// Also emits granular thinking phase tasks for better UI visibility
+    #[allow(clippy::too_many_arguments)]
     async fn generate_with_progress(
         &self,
         client: &AiClient,
+        archetype: &str,
         conversation: Vec<Message>,
         tool_history: Vec<ToolHistoryEntry>,
         tools: Vec<ToolDefinition>,
@@ -3736,6 +4274,11 @@ impl MessageDispatcher {
             &tool_history,
         ));
 
+        // Summary of the request for the persisted execution trace
+        let tool_names: Vec<String> = tools.iter().map(|t| t.name.clone()).collect();
+        let (trace_input, trace_input_tokens) = ai_call_trace_input(&conversation, tool_history.len(), &tool_names);
+        let ai_started = std::time::Instant::now();
+
         // Spawn the actual AI request
         let ai_future = client.generate_with_tools(conversation, tool_history, tools.clone());
         tokio::pin!(ai_future);
@@ -3774,6 +4317,16 @@ impl MessageDispatcher {
                         self.execution_tracker.complete_task(task_id);
                     }
 
+                    let error_text = result.as_ref().err().map(|e| e.to_string());
+                    self.execution_tracker.record_ai_call(
+                        channel_id,
+                        archetype,
+                        trace_input,
+                        trace_input_tokens,
+                        result.as_ref().map_err(|_| error_text.as_deref().unwrap_or_default()),
+                        ai_started.elapsed().as_millis() as u64,
+                    );
+
                     match result {
                         Ok(response) => {
                             // If there are tool calls, emit a planning task
@@ -3913,6 +4466,7 @@ impl MessageDispatcher {
                 // Reset the session
                 match self.db.reset_chat_session(session.id) {
                     Ok(_) => {
+                        self.fire_session_end(message.channel_id, session.id, "reset").await;
                         let response = "Session reset. Let's start fresh!".to_string();
                         self.broadcaster.broadcast(GatewayEvent::agent_response(
                             message.channel_id,
@@ -4003,4 +4557,44 @@ mod tests {
         assert_eq!(caps.get(1).map(|m| m.as_str()), Some("medium"));
         assert_eq!(caps.get(2).map(|m| m.as_str()), Some("What is the meaning of life?"));
     }
+
+    /// Records the reason of every SessionEnd it sees
+    struct SessionEndRecorder(std::sync::Mutex<Vec<(Option<i64>, Option<i64>, String)>>);
+
+    #[async_trait::async_trait]
+    impl crate::hooks::Hook for SessionEndRecorder {
+        fn id(&self) -> &str {
+            "session_end_recorder"
+        }
+
+        fn name(&self) -> &str {
+            "Session End Recorder"
+        }
+
+        fn events(&self) -> Vec<HookEvent> {
+            vec![HookEvent::SessionEnd]
+        }
+
+        async fn execute(&self, context: &mut HookContext) -> HookResult {
+            let reason = context.extra["reason"].as_str().unwrap_or_default().to_string();
+            self.0.lock().unwrap().push((context.channel_id, context.session_id, reason));
+            HookResult::Continue(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fire_session_end_runs_hooks() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let recorder = Arc::new(SessionEndRecorder(std::sync::Mutex::new(Vec::new())));
+        let hooks = Arc::new(crate::hooks::HookManager::new());
+        hooks.register(recorder.clone());
+        let dispatcher = MessageDispatcher::new_without_tools(db, Arc::new(EventBroadcaster::new()))
+            .with_hook_manager(hooks);
+
+        dispatcher.fire_session_end(3, 7, "deleted").await;
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![(Some(3), Some(7), "deleted".to_string())]
+        );
+    }
 }
@@ -52,6 +52,7 @@ impl TestHarness {
             4096,
             100_000,
             None,
+            &crate::models::ModelOptions::default(),
         )
         .expect("save agent settings");
 
@@ -132,6 +133,7 @@ impl TestHarness {
             4096,
             100_000,
             None,
+            &crate::models::ModelOptions::default(),
         )
         .expect("save agent settings");
 
@@ -834,6 +836,7 @@ async fn swap_flow_realistic() {
         4096,
         100_000,
         secret.as_deref(),
+        &crate::models::ModelOptions::default(),
     )
     .expect("save agent settings");
 
@@ -1265,3 +1268,201 @@ async fn lp_deposit_flow_with_trace() {
     assert_eq!(task_numbers[5], Some((4, 5)), "Iteration 6 should show TASK 4/5 (task 3 completed)");
     assert_eq!(task_numbers[6], Some((5, 5)), "Iteration 7 should show TASK 5/5 (task 4 completed)");
 }
+
+// ============================================================================
+// Lifecycle hooks: events fired from dispatch() and Skip/Cancel/Replace honored
+// ============================================================================
+
+use crate::hooks::{Hook, HookContext, HookEvent, HookManager, HookResult};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// Hook that records every event it sees and returns a fixed result for
+/// `target` (optionally only when the context's tool matches `tool`).
+struct ScriptedHook {
+    target: HookEvent,
+    tool: Option<&'static str>,
+    result: HookResult,
+    seen: Arc<Mutex<Vec<HookEvent>>>,
+}
+
+#[async_trait]
+impl Hook for ScriptedHook {
+    fn id(&self) -> &str {
+        "scripted_test_hook"
+    }
+
+    fn name(&self) -> &str {
+        "Scripted Test Hook"
+    }
+
+    fn events(&self) -> Vec<HookEvent> {
+        vec![
+            HookEvent::SessionStart,
+            HookEvent::BeforeAgentStart,
+            HookEvent::BeforeToolCall,
+            HookEvent::AfterToolCall,
+            HookEvent::BeforeResponse,
+            HookEvent::AfterAgentEnd,
+        ]
+    }
+
+    async fn execute(&self, context: &mut HookContext) -> HookResult {
+        self.seen.lock().unwrap().push(context.event);
+        let tool_matches = self.tool.is_none() || context.tool_name.as_deref() == self.tool;
+        if context.event == self.target && tool_matches {
+            self.result.clone()
+        } else {
+            HookResult::Continue(None)
+        }
+    }
+}
+
+/// Attach a ScriptedHook to the harness dispatcher, returning the recorded events.
+fn attach_hook(
+    mut harness: TestHarness,
+    target: HookEvent,
+    tool: Option<&'static str>,
+    result: HookResult,
+) -> (TestHarness, Arc<Mutex<Vec<HookEvent>>>) {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let manager = Arc::new(HookManager::new());
+    manager.register(Arc::new(ScriptedHook { target, tool, result, seen: seen.clone() }));
+    harness.dispatcher = harness.dispatcher.with_hook_manager(manager);
+    (harness, seen)
+}
+
+fn say_to_user_results(events: &[GatewayEvent]) -> Vec<(bool, String)> {
+    events
+        .iter()
+        .filter(|e| e.event == "tool.result")
+        .filter(|e| e.data.get("tool_name").and_then(|v| v.as_str()) == Some("say_to_user"))
+        .map(|e| {
+            (
+                e.data.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                e.data.get("content").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn hooks_fire_lifecycle_events() {
+    let responses = vec![AiResponse::with_tools(
+        String::new(),
+        vec![tool_call("say_to_user", json!({"message": "hi", "finished_task": true}))],
+    )];
+    let harness = TestHarness::new("web", false, false, responses);
+    let (mut harness, seen) =
+        attach_hook(harness, HookEvent::AfterAgentEnd, None, HookResult::Continue(None));
+
+    let (result, _events) = harness.dispatch("hello", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+
+    let seen = seen.lock().unwrap().clone();
+    for event in [
+        HookEvent::SessionStart,
+        HookEvent::BeforeAgentStart,
+        HookEvent::BeforeToolCall,
+        HookEvent::AfterToolCall,
+        HookEvent::BeforeResponse,
+        HookEvent::AfterAgentEnd,
+    ] {
+        assert!(seen.contains(&event), "expected {:?} to fire, saw {:?}", event, seen);
+    }
+}
+
+#[tokio::test]
+async fn before_agent_start_cancel_rejects_message() {
+    let harness = TestHarness::new("web", false, false, vec![]);
+    let (mut harness, _seen) = attach_hook(
+        harness,
+        HookEvent::BeforeAgentStart,
+        None,
+        HookResult::Cancel("blocked".to_string()),
+    );
+
+    let (result, _events) = harness.dispatch("hello", false).await;
+    let error = result.error.expect("dispatch should be rejected");
+    assert!(error.contains("blocked"), "unexpected error: {}", error);
+    assert!(harness.get_trace().is_empty(), "AI should not be called");
+}
+
+#[tokio::test]
+async fn before_tool_call_cancel_blocks_tool() {
+    let responses = vec![
+        AiResponse::with_tools(
+            String::new(),
+            vec![tool_call("say_to_user", json!({"message": "secret", "finished_task": true}))],
+        ),
+        AiResponse::with_tools(
+            String::new(),
+            vec![tool_call("task_fully_completed", json!({"summary": "done"}))],
+        ),
+    ];
+    let harness = TestHarness::new("web", false, false, responses);
+    let (mut harness, _seen) = attach_hook(
+        harness,
+        HookEvent::BeforeToolCall,
+        Some("say_to_user"),
+        HookResult::Cancel("not allowed".to_string()),
+    );
+
+    let (_result, events) = harness.dispatch("hello", false).await;
+    let results = say_to_user_results(&events);
+    assert!(!results.is_empty(), "say_to_user should report a result");
+    assert!(results.iter().all(|(success, _)| !success), "say_to_user must not succeed");
+    assert!(results[0].1.contains("not allowed"), "unexpected content: {}", results[0].1);
+}
+
+#[tokio::test]
+async fn before_response_replace_rewrites_reply() {
+    let responses = vec![AiResponse::with_tools(
+        String::new(),
+        vec![tool_call("task_fully_completed", json!({"summary": "original"}))],
+    )];
+    let harness = TestHarness::new("web", false, false, responses);
+    let (mut harness, _seen) = attach_hook(
+        harness,
+        HookEvent::BeforeResponse,
+        None,
+        HookResult::Replace(json!("rewritten")),
+    );
+
+    let (result, events) = harness.dispatch("hello", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+    assert_eq!(result.response, "rewritten");
+    let texts: Vec<&str> = events
+        .iter()
+        .filter(|e| e.event == "agent.response")
+        .filter_map(|e| e.data.get("text").and_then(|v| v.as_str()))
+        .collect();
+    assert_eq!(texts, vec!["rewritten"]);
+}
+
+#[tokio::test]
+async fn ai_usage_recorded_per_call() {
+    let responses = vec![AiResponse::with_tools(
+        String::new(),
+        vec![tool_call("say_to_user", json!({"message": "hi", "finished_task": true}))],
+    )
+    .with_usage(Some(crate::ai::TokenUsage { input_tokens: 1200, output_tokens: 80 }))];
+    let mut harness = TestHarness::new("web", false, false, responses);
+    let (result, _events) = harness.dispatch("hello", false).await;
+    assert!(result.error.is_none(), "dispatch should succeed: {:?}", result.error);
+
+    // One row per AI call the dispatcher made
+    let db = &harness.dispatcher.db;
+    let totals = db.get_ai_usage_totals(None).unwrap();
+    assert_eq!(totals.calls, harness.get_trace().len() as u64);
+    // Only the first response reports usage; the rest are estimated locally
+    assert_eq!(totals.estimated_calls, totals.calls - 1);
+    assert!(totals.input_tokens >= 1200 && totals.output_tokens >= 80);
+
+    let by_channel = db
+        .list_ai_usage_grouped(crate::db::tables::ai_usage::AiUsageGroupBy::Channel, None, 10)
+        .unwrap();
+    assert_eq!(by_channel.len(), 1);
+    assert_eq!(by_channel[0].key, Some(harness.channel_id.to_string()));
+    assert_eq!(by_channel[0].totals.calls, totals.calls);
+}
@@ -4,12 +4,13 @@ pub mod safe_mode_rate_limiter;
 pub mod slack;
 pub mod telegram;
 pub mod twitter;
+pub mod tx_approval;
 pub mod types;
 pub mod util;
 
 pub use dispatcher::MessageDispatcher;
 pub use safe_mode_rate_limiter::{SafeModeChannelRateLimiter, SafeModeQueryResult};
-pub use types::{ChannelHandle, ChannelType, NormalizedMessage};
+pub use types::{ChannelHandle, NormalizedMessage, PendingUserQuestion};
 
 use crate::db::Database;
 use crate::execution::ExecutionTracker;
@@ -37,7 +38,7 @@ pub struct ChannelManager {
 
 impl ChannelManager {
     pub fn new(db: Arc<Database>, broadcaster: Arc<EventBroadcaster>) -> Self {
-        let execution_tracker = Arc::new(ExecutionTracker::new(broadcaster.clone()));
+        let execution_tracker = Arc::new(ExecutionTracker::new(broadcaster.clone()).with_db(db.clone()));
         Self {
             db,
             broadcaster,
@@ -66,7 +67,7 @@ impl ChannelManager {
         tool_registry: Arc<ToolRegistry>,
         wallet_provider: Option<Arc<dyn crate::wallet::WalletProvider>>,
     ) -> Self {
-        let execution_tracker = Arc::new(ExecutionTracker::new(broadcaster.clone()));
+        let execution_tracker = Arc::new(ExecutionTracker::new(broadcaster.clone()).with_db(db.clone()));
         Self {
             db,
             broadcaster,
@@ -193,6 +194,7 @@ impl ChannelManager {
 
                     if let Err(e) = result {
                         log::error!("Telegram listener error: {}", e);
+                        crate::metrics::METRICS.channel_listener_errors.inc(&["telegram"]);
                         broadcaster.broadcast(GatewayEvent::channel_error(channel_id, &e));
                     }
 
@@ -201,17 +203,20 @@ impl ChannelManager {
                 });
             }
             types::ChannelType::Slack => {
+                let db = self.db.clone();
                 tokio::spawn(async move {
                     let result = slack::start_slack_listener(
                         channel,
                         dispatcher,
                         broadcaster.clone(),
+                        db,
                         shutdown_rx,
                     )
                     .await;
 
                     if let Err(e) = result {
                         log::error!("Slack listener error: {}", e);
+                        crate::metrics::METRICS.channel_listener_errors.inc(&["slack"]);
                         broadcaster.broadcast(GatewayEvent::channel_error(channel_id, &e));
                     }
 
@@ -235,6 +240,7 @@ impl ChannelManager {
 
                     if let Err(e) = result {
                         log::error!("Discord listener error: {}", e);
+                        crate::metrics::METRICS.channel_listener_errors.inc(&["discord"]);
                         broadcaster.broadcast(GatewayEvent::channel_error(channel_id, &e));
                     }
 
@@ -256,6 +262,7 @@ impl ChannelManager {
 
                     if let Err(e) = result {
                         log::error!("Twitter listener error: {}", e);
+                        crate::metrics::METRICS.channel_listener_errors.inc(&["twitter"]);
                         broadcaster.broadcast(GatewayEvent::channel_error(channel_id, &e));
                     }
 
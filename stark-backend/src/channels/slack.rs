@@ -1,20 +1,85 @@
 use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::tx_approval::{self, TxApprovalAction};
 use crate::channels::types::ChannelType;
+use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::Channel;
+use crate::models::{Channel, ChannelSettingKey};
+use crate::tx_queue::QueuedTransaction;
 use slack_morphism::prelude::*;
 use std::sync::Arc;
 use tokio::sync::oneshot;
 
+/// Future returned by the Socket Mode event callbacks
+type SocketModeCallbackFuture = std::pin::Pin<
+    Box<dyn std::future::Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send>,
+>;
+
+/// State shared with the Socket Mode callbacks through the listener's user state
+#[derive(Clone)]
+struct SlackListenerState {
+    channel_id: i64,
+    bot_token: SlackApiToken,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+    db: Arc<Database>,
+}
+
+/// Block Kit blocks for a queued transaction summary with approve/deny buttons
+fn approval_blocks(tx: &QueuedTransaction, has_admins: bool) -> Vec<SlackBlock> {
+    let summary = tx_approval::format_summary(tx);
+    if !has_admins {
+        return vec![
+            SlackSectionBlock::new()
+                .with_text(md!(format!("{}\n\n{}", summary, tx_approval::NO_ADMIN_NOTE)))
+                .into(),
+        ];
+    }
+
+    let button = |action: TxApprovalAction, label: &str, style: &str| -> SlackActionBlockElement {
+        let data = action.callback_data(&tx.uuid);
+        SlackBlockButtonElement::new(data.clone().into(), pt!(label))
+            .with_value(data)
+            .with_style(style.to_string())
+            .into()
+    };
+    vec![
+        SlackSectionBlock::new().with_text(md!(summary)).into(),
+        SlackActionsBlock::new(vec![
+            button(TxApprovalAction::Approve, "Approve", "primary"),
+            button(TxApprovalAction::Deny, "Deny", "danger"),
+        ])
+        .into(),
+    ]
+}
+
+/// Post a queued transaction summary with approve/deny buttons
+async fn send_approval_prompt(
+    client: &SlackHyperClient,
+    token: &SlackApiToken,
+    chat_id: &str,
+    tx: &QueuedTransaction,
+    has_admins: bool,
+) {
+    let content = SlackMessageContent::new()
+        .with_text(format!("Transaction {} awaiting approval", tx.uuid))
+        .with_blocks(approval_blocks(tx, has_admins));
+    let request = SlackApiChatPostMessageRequest::new(SlackChannelId(chat_id.to_string()), content);
+    if let Err(e) = client.open_session(token).chat_post_message(&request).await {
+        log::error!("Slack: Failed to send approval prompt for {}: {}", tx.uuid, e);
+    }
+}
+
 /// Start a Slack bot listener using Socket Mode
 ///
 /// Note: Slack Socket Mode requires a complex setup with event subscriptions.
-/// This implementation provides the basic framework for receiving messages.
+/// This implementation provides the basic framework for receiving messages
+/// and handles the approve/deny buttons on queued transaction prompts.
 pub async fn start_slack_listener(
     channel: Channel,
-    _dispatcher: Arc<MessageDispatcher>,
+    dispatcher: Arc<MessageDispatcher>,
     broadcaster: Arc<EventBroadcaster>,
+    db: Arc<Database>,
     shutdown_rx: oneshot::Receiver<()>,
 ) -> Result<(), String> {
     let channel_id = channel.id;
@@ -33,9 +98,23 @@ pub async fn start_slack_listener(
     );
 
     // Create token values
-    let _token = SlackApiToken::new(bot_token.into());
+    let token = SlackApiToken::new(bot_token.into());
     let socket_token = SlackApiToken::new(app_token.into());
 
+    // Post approve/deny prompts for transactions queued from this channel
+    let _prompt_forwarder = dispatcher.tx_queue().map(|tx_queue| {
+        let client = client.clone();
+        let token = token.clone();
+        let db = db.clone();
+        tx_approval::PromptForwarder::spawn(channel_id, broadcaster.clone(), tx_queue, move |chat_id, tx| {
+            let client = client.clone();
+            let token = token.clone();
+            let has_admins =
+                !tx_approval::load_admin_ids(&db, channel_id, ChannelSettingKey::SlackAdminUserIds).is_empty();
+            async move { send_approval_prompt(&client, &token, &chat_id, &tx, has_admins).await }
+        })
+    });
+
     // Emit started event
     broadcaster.broadcast(GatewayEvent::channel_started(
         channel_id,
@@ -45,12 +124,19 @@ pub async fn start_slack_listener(
 
     // Create listener environment
     let listener_environment = Arc::new(
-        SlackClientEventsListenerEnvironment::new(client.clone()),
+        SlackClientEventsListenerEnvironment::new(client.clone()).with_user_state(SlackListenerState {
+            channel_id,
+            bot_token: token,
+            dispatcher,
+            broadcaster: broadcaster.clone(),
+            db,
+        }),
     );
 
-    // Create Socket Mode callbacks with a simple handler
-    let socket_mode_callbacks =
-        SlackSocketModeListenerCallbacks::new().with_push_events(handle_push_event);
+    // Create Socket Mode callbacks for messages and button interactions
+    let socket_mode_callbacks = SlackSocketModeListenerCallbacks::new()
+        .with_push_events(handle_push_event)
+        .with_interaction_events(handle_interaction_event);
 
     // Create socket mode listener
     let socket_mode_listener = SlackClientSocketModeListener::new(
@@ -90,9 +176,7 @@ fn handle_push_event(
     event: SlackPushEventCallback,
     _client: Arc<SlackHyperClient>,
     _user_state: SlackClientEventsUserState,
-) -> std::pin::Pin<
-    Box<dyn std::future::Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>> + Send>,
-> {
+) -> SocketModeCallbackFuture {
     Box::pin(async move {
         // Log the received event for debugging
         if let SlackEventCallbackBody::Message(msg_event) = event.event {
@@ -120,3 +204,82 @@ fn handle_push_event(
         Ok(())
     })
 }
+
+fn handle_interaction_event(
+    event: SlackInteractionEvent,
+    client: Arc<SlackHyperClient>,
+    user_state: SlackClientEventsUserState,
+) -> SocketModeCallbackFuture {
+    Box::pin(async move {
+        let event = match event {
+            SlackInteractionEvent::BlockActions(event) => event,
+            _ => return Ok(()),
+        };
+        let state = match user_state.read().await.get_user_state::<SlackListenerState>() {
+            Some(state) => state.clone(),
+            None => return Ok(()),
+        };
+
+        let parsed = event
+            .actions
+            .iter()
+            .flatten()
+            .find_map(|a| a.value.as_deref().and_then(TxApprovalAction::parse_callback));
+        let (action, uuid) = match parsed {
+            Some(parsed) => parsed,
+            None => return Ok(()),
+        };
+        let (user, slack_channel) = match (event.user, event.channel) {
+            (Some(user), Some(channel)) => (user, channel.id),
+            _ => return Ok(()),
+        };
+
+        let session = client.open_session(&state.bot_token);
+        let admins = tx_approval::load_admin_ids(&state.db, state.channel_id, ChannelSettingKey::SlackAdminUserIds);
+        if !admins.contains(&user.id.0) {
+            log::warn!(
+                "Slack: User {} tried to {} transaction {} but is not an admin",
+                user.id.0,
+                action.as_str(),
+                uuid
+            );
+            let content = SlackMessageContent::new()
+                .with_text("Only configured admin users can approve or deny transactions.".to_string());
+            let request = SlackApiChatPostEphemeralRequest::new(slack_channel, user.id, content);
+            if let Err(e) = session.chat_post_ephemeral(&request).await {
+                log::error!("Slack: Failed to reject approval interaction: {}", e);
+            }
+            return Ok(());
+        }
+
+        let approved_by = user.username.clone().or(user.name.clone()).unwrap_or_else(|| user.id.0.clone());
+        let outcome = match tx_approval::apply_action(
+            action,
+            &uuid,
+            state.channel_id,
+            &approved_by,
+            &state.dispatcher,
+            state.broadcaster.clone(),
+        )
+        .await
+        {
+            Ok(text) => text,
+            Err(e) => format!("⚠️ Could not {} transaction: {}", action.as_str(), e),
+        };
+
+        // Replace the buttons with the outcome
+        if let Some(message) = event.message {
+            let summary = message.content.text.unwrap_or_default();
+            let text = format!("{}\n\n{}", summary, outcome);
+            let content = SlackMessageContent::new()
+                .with_text(text.clone())
+                .with_blocks(vec![SlackSectionBlock::new().with_text(md!(text)).into()]);
+            let request = SlackApiChatUpdateRequest::new(slack_channel, content, message.origin.ts);
+            if let Err(e) = session.chat_update(&request).await {
+                log::warn!("Slack: Failed to update approval prompt: {}", e);
+            }
+        }
+
+        Ok(())
+    })
+}
@@ -1,4 +1,5 @@
 use crate::channels::dispatcher::MessageDispatcher;
+use crate::channels::tx_approval::{self, TxApprovalAction};
 use crate::channels::types::{ChannelType, NormalizedMessage};
 use crate::channels::util;
 use crate::db::Database;
@@ -7,11 +8,12 @@ use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
 use crate::models::channel_settings::ChannelSettingKey;
 use crate::models::{Channel, ToolOutputVerbosity};
+use crate::tx_queue::QueuedTransaction;
 use rand::seq::SliceRandom;
 use std::sync::Arc;
 use teloxide::prelude::*;
 use teloxide::requests::Requester;
-use teloxide::types::MessageId;
+use teloxide::types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, MessageId};
 use tokio::sync::oneshot;
 
 /// Format a tool call event for Telegram display based on verbosity
@@ -234,6 +236,89 @@ async fn handle_shortcircuit_command(
     }
 }
 
+/// Post a queued transaction summary with inline approve/deny buttons
+async fn send_approval_prompt(bot: &Bot, chat_id: &str, tx: &QueuedTransaction, has_admins: bool) {
+    let chat_id = match chat_id.parse::<i64>() {
+        Ok(id) => ChatId(id),
+        Err(_) => {
+            log::warn!("Telegram: Cannot post approval prompt to invalid chat id '{}'", chat_id);
+            return;
+        }
+    };
+
+    let summary = tx_approval::format_summary(tx);
+    let request = if has_admins {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback("✅ Approve", TxApprovalAction::Approve.callback_data(&tx.uuid)),
+            InlineKeyboardButton::callback("❌ Deny", TxApprovalAction::Deny.callback_data(&tx.uuid)),
+        ]]);
+        bot.send_message(chat_id, summary).reply_markup(keyboard)
+    } else {
+        bot.send_message(chat_id, format!("{}\n\n{}", summary, tx_approval::NO_ADMIN_NOTE))
+    };
+
+    if let Err(e) = request.await {
+        log::error!("Telegram: Failed to send approval prompt for {}: {}", tx.uuid, e);
+    }
+}
+
+/// Handle an approve/deny button press on an approval prompt
+async fn handle_approval_callback(
+    bot: Bot,
+    query: CallbackQuery,
+    channel_id: i64,
+    db: &Database,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+) {
+    let (action, uuid) = match query.data.as_deref().and_then(TxApprovalAction::parse_callback) {
+        Some(parsed) => parsed,
+        None => {
+            let _ = bot.answer_callback_query(query.id).await;
+            return;
+        }
+    };
+
+    // Loaded per click so admin changes apply without restarting the channel
+    let admins = tx_approval::load_admin_ids(db, channel_id, ChannelSettingKey::TelegramAdminUserId);
+    let user_id = query.from.id.to_string();
+    if !admins.contains(&user_id) {
+        log::warn!(
+            "Telegram: User {} tried to {} transaction {} but is not an admin",
+            user_id,
+            action.as_str(),
+            uuid
+        );
+        let _ = bot
+            .answer_callback_query(query.id)
+            .text("Only the channel admin can approve or deny transactions.")
+            .show_alert(true)
+            .await;
+        return;
+    }
+
+    // Answer right away so the button stops spinning while the tx is broadcast
+    let _ = bot.answer_callback_query(query.id.clone()).await;
+
+    let user_name = query
+        .from
+        .username
+        .clone()
+        .unwrap_or_else(|| query.from.first_name.clone());
+    let outcome = match tx_approval::apply_action(action, &uuid, channel_id, &user_name, &dispatcher, broadcaster).await {
+        Ok(text) => text,
+        Err(e) => format!("⚠️ Could not {} transaction: {}", action.as_str(), e),
+    };
+
+    // Replace the buttons with the outcome
+    if let Some(message) = query.message {
+        let text = format!("{}\n\n{}", message.text().unwrap_or_default(), outcome);
+        if let Err(e) = bot.edit_message_text(message.chat.id, message.id, text).await {
+            log::warn!("Telegram: Failed to update approval prompt: {}", e);
+        }
+    }
+}
+
 /// Start a Telegram bot listener
 pub async fn start_telegram_listener(
     channel: Channel,
@@ -293,6 +378,18 @@ pub async fn start_telegram_listener(
         );
     }
 
+    // Queued transactions can be approved from Telegram by the configured admin
+    let _prompt_forwarder = dispatcher.tx_queue().map(|tx_queue| {
+        let bot = bot.clone();
+        let db = db.clone();
+        tx_approval::PromptForwarder::spawn(channel_id, broadcaster.clone(), tx_queue, move |chat_id, tx| {
+            let bot = bot.clone();
+            let has_admins =
+                !tx_approval::load_admin_ids(&db, channel_id, ChannelSettingKey::TelegramAdminUserId).is_empty();
+            async move { send_approval_prompt(&bot, &chat_id, &tx, has_admins).await }
+        })
+    });
+
     // Emit started event
     broadcaster.broadcast(GatewayEvent::channel_started(
         channel_id,
@@ -306,7 +403,7 @@ pub async fn start_telegram_listener(
     let db_for_handler = db.clone();
 
     // Create message handler
-    let handler = Update::filter_message().endpoint(
+    let message_handler = Update::filter_message().endpoint(
         move |bot: Bot, msg: teloxide::types::Message, dispatcher: Arc<MessageDispatcher>, db: Arc<Database>| {
             let channel_id = channel_id;
             let broadcaster = broadcaster_for_handler.clone();
@@ -763,6 +860,20 @@ pub async fn start_telegram_listener(
         },
     );
 
+    // Approve/deny buttons on queued transaction prompts
+    let broadcaster_for_callbacks = broadcaster.clone();
+    let callback_handler = Update::filter_callback_query().endpoint(
+        move |bot: Bot, query: CallbackQuery, dispatcher: Arc<MessageDispatcher>, db: Arc<Database>| {
+            let broadcaster = broadcaster_for_callbacks.clone();
+            async move {
+                handle_approval_callback(bot, query, channel_id, &db, dispatcher, broadcaster).await;
+                Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+            }
+        },
+    );
+
+    let handler = dptree::entry().branch(message_handler).branch(callback_handler);
+
     // Create dispatcher
     let mut tg_dispatcher = Dispatcher::builder(bot, handler)
         .dependencies(dptree::deps![dispatcher, db_for_handler])
@@ -0,0 +1,416 @@
+//! Approve or deny queued transactions from chat channels
+//!
+//! In partner mode, tools emit `tx_queue.confirmation_required` instead of
+//! broadcasting. Each running Telegram, Discord or Slack listener keeps a
+//! [`PromptForwarder`] that posts a transaction summary with approve/deny
+//! buttons into the chat the request came from. Button clicks from the
+//! channel's configured admin users go through the same confirmation path as
+//! the `tx_queue.confirm`/`tx_queue.deny` gateway methods, so the web UI sees
+//! the usual `tx_queue.confirmed`/`tx_queue.denied` events.
+
+use crate::channels::dispatcher::MessageDispatcher;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::methods::tx_queue::{confirm_queued_tx, deny_queued_tx};
+use crate::gateway::protocol::GatewayEvent;
+use crate::models::ChannelSettingKey;
+use crate::tx_queue::{QueuedTransaction, QueuedTxStatus, TxQueueManager};
+use ethers::abi::{ParamType, Token};
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// Prefix of button callback payloads (`txq:approve:<uuid>`)
+const CALLBACK_PREFIX: &str = "txq";
+
+/// Event that asks for a partner-mode confirmation
+const CONFIRMATION_EVENT: &str = "tx_queue.confirmation_required";
+
+/// Selector, function name, and argument names and types
+type KnownCall = (&'static str, &'static str, &'static [(&'static str, KnownParam)]);
+
+/// Calls the summary knows how to decode
+const KNOWN_CALLS: &[KnownCall] = &[
+    ("a9059cbb", "transfer", &[("to", KnownParam::Address), ("amount", KnownParam::Uint)]),
+    ("095ea7b3", "approve", &[("spender", KnownParam::Address), ("amount", KnownParam::Uint)]),
+    (
+        "23b872dd",
+        "transferFrom",
+        &[("from", KnownParam::Address), ("to", KnownParam::Address), ("amount", KnownParam::Uint)],
+    ),
+    ("d0e30db0", "deposit", &[]),
+    ("2e1a7d4d", "withdraw", &[("amount", KnownParam::Uint)]),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum KnownParam {
+    Address,
+    Uint,
+}
+
+impl KnownParam {
+    fn param_type(&self) -> ParamType {
+        match self {
+            Self::Address => ParamType::Address,
+            Self::Uint => ParamType::Uint(256),
+        }
+    }
+}
+
+/// What an admin chose on an approval prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxApprovalAction {
+    Approve,
+    Deny,
+}
+
+impl TxApprovalAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::Deny => "deny",
+        }
+    }
+
+    /// Button payload for this action (fits Telegram's 64-byte callback limit)
+    pub fn callback_data(&self, uuid: &str) -> String {
+        format!("{}:{}:{}", CALLBACK_PREFIX, self.as_str(), uuid)
+    }
+
+    /// Parse a button payload produced by `callback_data`
+    pub fn parse_callback(data: &str) -> Option<(Self, String)> {
+        let mut parts = data.splitn(3, ':');
+        if parts.next()? != CALLBACK_PREFIX {
+            return None;
+        }
+        let action = match parts.next()? {
+            "approve" => Self::Approve,
+            "deny" => Self::Deny,
+            _ => return None,
+        };
+        let uuid = parts.next().filter(|u| !u.is_empty())?;
+        Some((action, uuid.to_string()))
+    }
+}
+
+/// Load the admin user IDs allowed to approve transactions for a channel.
+/// An empty list means approvals are only possible from the web UI.
+pub fn load_admin_ids(db: &Database, channel_id: i64, key: ChannelSettingKey) -> Vec<String> {
+    db.get_channel_setting(channel_id, key.as_ref())
+        .ok()
+        .flatten()
+        .map(|value| parse_admin_ids(&value))
+        .unwrap_or_default()
+}
+
+/// Split a comma-separated admin ID setting
+pub fn parse_admin_ids(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Describe the calldata of a queued transaction for the approval prompt
+pub fn describe_call(data: &str) -> String {
+    let hex_data = data.trim_start_matches("0x");
+    if hex_data.is_empty() {
+        return "native transfer".to_string();
+    }
+    let bytes = match hex::decode(hex_data) {
+        Ok(bytes) if bytes.len() >= 4 => bytes,
+        _ => return format!("raw data 0x{}", hex_data),
+    };
+    let selector = hex::encode(&bytes[..4]);
+
+    let known = KNOWN_CALLS.iter().find(|(sel, _, _)| *sel == selector);
+    if let Some((_, name, params)) = known {
+        let types: Vec<ParamType> = params.iter().map(|(_, p)| p.param_type()).collect();
+        if let Ok(tokens) = ethers::abi::decode(&types, &bytes[4..]) {
+            let args: Vec<String> = params
+                .iter()
+                .zip(tokens)
+                .map(|((arg, _), token)| format!("{}: {}", arg, format_token(&token)))
+                .collect();
+            return format!("{}({})", name, args.join(", "));
+        }
+    }
+
+    format!("contract call 0x{} ({} bytes of arguments)", selector, bytes.len() - 4)
+}
+
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("{:?}", address),
+        Token::Uint(value) => value.to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Plain-text summary shown above the approve/deny buttons
+pub fn format_summary(tx: &QueuedTransaction) -> String {
+    let mut summary = format!(
+        "🔐 Transaction awaiting approval\n\n\
+         Network: {}\n\
+         From: {}\n\
+         To: {}\n\
+         Value: {}\n\
+         Call: {}",
+        tx.network,
+        tx.from,
+        tx.to,
+        tx.format_value_eth(),
+        describe_call(&tx.data)
+    );
+    if let Some(ref preset) = tx.preset {
+        summary.push_str(&format!("\nPreset: {}", preset));
+    }
+    summary.push_str(&format!("\nUUID: {}", tx.uuid));
+    summary
+}
+
+/// Note appended to the summary when nobody in this channel may approve it
+pub const NO_ADMIN_NOTE: &str =
+    "No admin users are configured for this channel - approve or deny it from the web UI.";
+
+/// Run an admin's choice through the shared tx_queue confirmation path.
+/// Returns the text that replaces the prompt.
+pub async fn apply_action(
+    action: TxApprovalAction,
+    uuid: &str,
+    channel_id: i64,
+    approved_by: &str,
+    dispatcher: &MessageDispatcher,
+    broadcaster: Arc<EventBroadcaster>,
+) -> Result<String, String> {
+    let tx_queue = dispatcher
+        .tx_queue()
+        .ok_or_else(|| "Transaction queue not available".to_string())?;
+
+    log::info!(
+        "[TX_APPROVAL] {} requested {} of {} on channel {}",
+        approved_by,
+        action.as_str(),
+        uuid,
+        channel_id
+    );
+
+    // Buttons only act on transactions queued from this channel
+    let tx = tx_queue
+        .get(uuid)
+        .ok_or_else(|| format!("Transaction {} not found", uuid))?;
+    if tx.channel_id != Some(channel_id) {
+        log::warn!(
+            "[TX_APPROVAL] Rejected {} of {} from channel {} (queued by channel {:?})",
+            action.as_str(),
+            uuid,
+            channel_id,
+            tx.channel_id
+        );
+        return Err(format!("Transaction {} was not queued from this channel", uuid));
+    }
+
+    match action {
+        TxApprovalAction::Approve => {
+            let confirmed = confirm_queued_tx(
+                uuid,
+                channel_id,
+                tx_queue,
+                broadcaster,
+                dispatcher.wallet_provider(),
+            )
+            .await?;
+            Ok(format!(
+                "✅ Approved by {} - broadcast {}\n{}",
+                approved_by, confirmed.tx_hash, confirmed.explorer_url
+            ))
+        }
+        TxApprovalAction::Deny => {
+            deny_queued_tx(uuid, channel_id, &tx_queue, &broadcaster)?;
+            Ok(format!("❌ Denied by {} - transaction {} removed from the queue", approved_by, uuid))
+        }
+    }
+}
+
+/// Pick out confirmation requests for this channel that are still pending,
+/// along with the chat they should be posted to
+fn prompt_target(
+    event: &GatewayEvent,
+    channel_id: i64,
+    tx_queue: &TxQueueManager,
+) -> Option<(String, QueuedTransaction)> {
+    if event.event != CONFIRMATION_EVENT {
+        return None;
+    }
+    if event.data.get("channel_id").and_then(|v| v.as_i64()) != Some(channel_id) {
+        return None;
+    }
+    let chat_id = event.data.get("chat_id").and_then(|v| v.as_str())?;
+    let uuid = event.data.get("uuid").and_then(|v| v.as_str())?;
+    let tx = tx_queue.get(uuid).filter(|tx| tx.status == QueuedTxStatus::Pending)?;
+    Some((chat_id.to_string(), tx))
+}
+
+/// Posts approval prompts for one running channel; unsubscribes when dropped
+pub struct PromptForwarder {
+    client_id: String,
+    broadcaster: Arc<EventBroadcaster>,
+    task: JoinHandle<()>,
+}
+
+impl PromptForwarder {
+    /// Subscribe to confirmation requests for `channel_id` and hand each pending
+    /// transaction (once per UUID) to `post` along with its platform chat ID
+    pub fn spawn<F, Fut>(
+        channel_id: i64,
+        broadcaster: Arc<EventBroadcaster>,
+        tx_queue: Arc<TxQueueManager>,
+        post: F,
+    ) -> Self
+    where
+        F: Fn(String, QueuedTransaction) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (client_id, mut event_rx) = broadcaster.subscribe();
+        let task = tokio::spawn(async move {
+            let mut prompted: HashSet<String> = HashSet::new();
+            while let Some(event) = event_rx.recv().await {
+                let target = prompt_target(&event, channel_id, &tx_queue)
+                    .filter(|(_, tx)| !prompted.contains(&tx.uuid));
+                if let Some((chat_id, tx)) = target {
+                    prompted.insert(tx.uuid.clone());
+                    post(chat_id, tx).await;
+                }
+            }
+        });
+        Self {
+            client_id,
+            broadcaster,
+            task,
+        }
+    }
+}
+
+impl Drop for PromptForwarder {
+    fn drop(&mut self) {
+        self.broadcaster.unsubscribe(&self.client_id);
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn queued_tx(data: &str) -> QueuedTransaction {
+        QueuedTransaction {
+            uuid: "0b7c1a52-2f1e-4d8e-9a57-1f2e3d4c5b6a".to_string(),
+            network: "base".to_string(),
+            from: "0x1111111111111111111111111111111111111111".to_string(),
+            to: "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".to_string(),
+            value: "0".to_string(),
+            data: data.to_string(),
+            gas_limit: "60000".to_string(),
+            max_fee_per_gas: "1000000".to_string(),
+            max_priority_fee_per_gas: "1000".to_string(),
+            nonce: 7,
+            signed_tx_hex: "0x02".to_string(),
+            status: QueuedTxStatus::Pending,
+            tx_hash: None,
+            error: None,
+            created_at: Utc::now(),
+            broadcast_at: None,
+            channel_id: Some(3),
+            explorer_url: None,
+            preset: None,
+        }
+    }
+
+    #[test]
+    fn test_callback_roundtrip() {
+        let uuid = "0b7c1a52-2f1e-4d8e-9a57-1f2e3d4c5b6a";
+        let data = TxApprovalAction::Approve.callback_data(uuid);
+        assert!(data.len() <= 64);
+        assert_eq!(
+            TxApprovalAction::parse_callback(&data),
+            Some((TxApprovalAction::Approve, uuid.to_string()))
+        );
+        assert_eq!(
+            TxApprovalAction::parse_callback(&TxApprovalAction::Deny.callback_data(uuid)),
+            Some((TxApprovalAction::Deny, uuid.to_string()))
+        );
+        assert_eq!(TxApprovalAction::parse_callback("txq:approve:"), None);
+        assert_eq!(TxApprovalAction::parse_callback("other:approve:abc"), None);
+        assert_eq!(TxApprovalAction::parse_callback("txq:maybe:abc"), None);
+    }
+
+    #[test]
+    fn test_describe_call() {
+        assert_eq!(describe_call("0x"), "native transfer");
+        let transfer = "0xa9059cbb\
+            0000000000000000000000002222222222222222222222222222222222222222\
+            00000000000000000000000000000000000000000000000000000000000f4240";
+        assert_eq!(
+            describe_call(transfer),
+            "transfer(to: 0x2222222222222222222222222222222222222222, amount: 1000000)"
+        );
+        assert_eq!(
+            describe_call("0x12345678abcd"),
+            "contract call 0x12345678 (2 bytes of arguments)"
+        );
+    }
+
+    #[test]
+    fn test_format_summary_and_admin_ids() {
+        let summary = format_summary(&queued_tx("0xd0e30db0"));
+        assert!(summary.contains("Network: base"));
+        assert!(summary.contains("Call: deposit()"));
+        assert!(summary.contains("UUID: 0b7c1a52"));
+
+        assert_eq!(parse_admin_ids(" 123, ,456 "), vec!["123".to_string(), "456".to_string()]);
+        assert!(parse_admin_ids("").is_empty());
+    }
+
+    #[test]
+    fn test_prompt_target_filters_channel_and_status() {
+        let tx_queue = TxQueueManager::new();
+        let tx = queued_tx("0x");
+        tx_queue.queue(tx.clone());
+        let event = GatewayEvent::tx_queue_confirmation_required(
+            3, Some("chat-1"), &tx.uuid, &tx.network, &tx.from, &tx.to, &tx.value, "0 ETH", &tx.data,
+        );
+
+        let (chat_id, found) = prompt_target(&event, 3, &tx_queue).unwrap();
+        assert_eq!(chat_id, "chat-1");
+        assert_eq!(found.uuid, tx.uuid);
+        assert!(prompt_target(&event, 4, &tx_queue).is_none());
+
+        tx_queue.mark_broadcasting(&tx.uuid);
+        assert!(prompt_target(&event, 3, &tx_queue).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_apply_action_rejects_other_channel() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let broadcaster = Arc::new(EventBroadcaster::new());
+        let tx_queue = Arc::new(TxQueueManager::new());
+        let tx = queued_tx("0x");
+        tx_queue.queue(tx.clone());
+        let dispatcher =
+            MessageDispatcher::new_without_tools(db, broadcaster.clone()).with_tx_queue(tx_queue.clone());
+
+        let err = apply_action(TxApprovalAction::Deny, &tx.uuid, 4, "mallory", &dispatcher, broadcaster.clone())
+            .await
+            .unwrap_err();
+        assert!(err.contains("not queued from this channel"));
+        assert!(tx_queue.get(&tx.uuid).is_some());
+
+        apply_action(TxApprovalAction::Deny, &tx.uuid, 3, "alice", &dispatcher, broadcaster)
+            .await
+            .unwrap();
+        assert!(tx_queue.get(&tx.uuid).is_none());
+    }
+}
@@ -83,6 +83,17 @@ pub struct NormalizedMessage {
     pub force_safe_mode: bool,
 }
 
+impl NormalizedMessage {
+    /// Cron job this message was dispatched for (cron runs use chat_id `cron:<job_id>`)
+    pub fn cron_job_id(&self) -> Option<&str> {
+        if self.channel_type == "cron" {
+            self.chat_id.strip_prefix("cron:")
+        } else {
+            None
+        }
+    }
+}
+
 /// Handle to a running channel listener
 pub struct ChannelHandle {
     pub channel_id: i64,
@@ -129,3 +140,18 @@ impl DispatchResult {
         }
     }
 }
+
+/// An `ask_user` question the agent is waiting on. Cleared when the next
+/// message arrives on the same chat, which is taken as the answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingUserQuestion {
+    pub channel_id: i64,
+    pub chat_id: String,
+    pub session_id: i64,
+    pub question: String,
+    #[serde(default)]
+    pub options: Option<Vec<String>>,
+    #[serde(default)]
+    pub default: Option<String>,
+    pub asked_at: chrono::DateTime<chrono::Utc>,
+}
@@ -3,6 +3,8 @@ use ethers::signers::{LocalWallet, Signer};
 use std::env;
 use std::path::{Path, PathBuf};
 
+use crate::models::OperatorRole;
+
 /// Environment variable names - single source of truth
 pub mod env_vars {
     pub const LOGIN_ADMIN_PUBLIC_ADDRESS: &str = "LOGIN_ADMIN_PUBLIC_ADDRESS";
@@ -20,6 +22,49 @@ pub mod env_vars {
     pub const MEMORY_ENABLE_PRE_COMPACTION_FLUSH: &str = "STARK_MEMORY_ENABLE_PRE_COMPACTION_FLUSH";
     pub const MEMORY_ENABLE_CROSS_SESSION: &str = "STARK_MEMORY_ENABLE_CROSS_SESSION";
     pub const MEMORY_CROSS_SESSION_LIMIT: &str = "STARK_MEMORY_CROSS_SESSION_LIMIT";
+    // Semantic memory search (ai_endpoints.ron preset name or embeddings URL)
+    pub const MEMORY_EMBEDDING_ENDPOINT: &str = "STARK_MEMORY_EMBEDDING_ENDPOINT";
+    pub const MEMORY_EMBEDDING_MODEL: &str = "STARK_MEMORY_EMBEDDING_MODEL";
+    pub const MEMORY_EMBEDDING_API_KEY: &str = "STARK_MEMORY_EMBEDDING_API_KEY";
+    // Memory consolidation (0 hours disables the scheduled job)
+    pub const MEMORY_CONSOLIDATION_INTERVAL_HOURS: &str = "STARK_MEMORY_CONSOLIDATION_INTERVAL_HOURS";
+    pub const MEMORY_CONSOLIDATE_AFTER_DAYS: &str = "STARK_MEMORY_CONSOLIDATE_AFTER_DAYS";
+    pub const MEMORY_DIGEST_ROLLUP_AFTER_DAYS: &str = "STARK_MEMORY_DIGEST_ROLLUP_AFTER_DAYS";
+    pub const MEMORY_LONG_TERM_MAX_CHARS: &str = "STARK_MEMORY_LONG_TERM_MAX_CHARS";
+    // Portfolio tracking (0 disables automatic snapshots)
+    pub const PORTFOLIO_SNAPSHOT_INTERVAL_MINS: &str = "STARK_PORTFOLIO_SNAPSHOT_INTERVAL_MINS";
+    // Polymarket position management (0 disables exit rule checks and auto-redeem)
+    pub const POLYMARKET_EXIT_CHECK_INTERVAL_SECS: &str = "STARK_POLYMARKET_EXIT_CHECK_INTERVAL_SECS";
+    pub const POLYMARKET_MAX_AUTO_EXIT_USD: &str = "STARK_POLYMARKET_MAX_AUTO_EXIT_USD";
+    pub const POLYMARKET_MAX_AUTO_EXITS_PER_DAY: &str = "STARK_POLYMARKET_MAX_AUTO_EXITS_PER_DAY";
+    pub const POLYMARKET_AUTO_REDEEM: &str = "STARK_POLYMARKET_AUTO_REDEEM";
+    // Swap tool (slippage in basis points, price impact in percent)
+    pub const SWAP_DEFAULT_SLIPPAGE_BPS: &str = "STARK_SWAP_DEFAULT_SLIPPAGE_BPS";
+    pub const SWAP_MAX_SLIPPAGE_BPS: &str = "STARK_SWAP_MAX_SLIPPAGE_BPS";
+    pub const SWAP_MAX_PRICE_IMPACT_PCT: &str = "STARK_SWAP_MAX_PRICE_IMPACT_PCT";
+    // Batch payouts (0 USD disables the total value limit)
+    pub const PAYOUT_MAX_RECIPIENTS: &str = "STARK_PAYOUT_MAX_RECIPIENTS";
+    pub const PAYOUT_MAX_TOTAL_USD: &str = "STARK_PAYOUT_MAX_TOTAL_USD";
+    // Token approval auditor (0 hours disables the scheduled scan)
+    pub const APPROVAL_SCAN_INTERVAL_HOURS: &str = "STARK_APPROVAL_SCAN_INTERVAL_HOURS";
+    pub const APPROVAL_SCAN_LOOKBACK_BLOCKS: &str = "STARK_APPROVAL_SCAN_LOOKBACK_BLOCKS";
+    // Aave health-factor watchers (automatic repays queued when a watcher fires)
+    pub const AAVE_AUTO_REPAY: &str = "STARK_AAVE_AUTO_REPAY";
+    pub const AAVE_MAX_AUTO_REPAY_USD: &str = "STARK_AAVE_MAX_AUTO_REPAY_USD";
+    pub const AAVE_MAX_AUTO_REPAYS_PER_DAY: &str = "STARK_AAVE_MAX_AUTO_REPAYS_PER_DAY";
+    pub const AAVE_TARGET_HEALTH_FACTOR: &str = "STARK_AAVE_TARGET_HEALTH_FACTOR";
+    // Event-triggered cron jobs (price alerts, on-chain watchers)
+    pub const EVENT_TRIGGER_POLL_SECS: &str = "STARK_EVENT_TRIGGER_POLL_SECS";
+    // Contract wallet login (EIP-1271 checks and Safe owner access)
+    pub const LOGIN_NETWORK: &str = "LOGIN_NETWORK";
+    pub const LOGIN_SAFE_ADDRESS: &str = "LOGIN_SAFE_ADDRESS";
+    pub const LOGIN_SAFE_ROLE: &str = "LOGIN_SAFE_ROLE";
+    // Execution traces (0 days keeps traces forever)
+    pub const EXECUTION_TRACE_RETENTION_DAYS: &str = "STARK_EXECUTION_TRACE_RETENTION_DAYS";
+    // Bearer token required to scrape /metrics (unset = open)
+    pub const METRICS_TOKEN: &str = "STARK_METRICS_TOKEN";
+    // Hot reload of RON config files (0 disables the file watcher)
+    pub const CONFIG_WATCH_INTERVAL_SECS: &str = "STARK_CONFIG_WATCH_INTERVAL_SECS";
 }
 
 /// Default values
@@ -31,6 +76,30 @@ pub mod defaults {
     pub const JOURNAL_DIR: &str = "journal";
     pub const SOUL_DIR: &str = "soul";
     pub const MEMORY_DIR: &str = "memory";
+    pub const PORTFOLIO_SNAPSHOT_INTERVAL_MINS: u64 = 60;
+    pub const MEMORY_CONSOLIDATION_INTERVAL_HOURS: u64 = 24;
+    pub const MEMORY_CONSOLIDATE_AFTER_DAYS: i64 = 14;
+    pub const MEMORY_DIGEST_ROLLUP_AFTER_DAYS: i64 = 90;
+    pub const MEMORY_LONG_TERM_MAX_CHARS: usize = 8000;
+    pub const POLYMARKET_EXIT_CHECK_INTERVAL_SECS: u64 = 60;
+    pub const POLYMARKET_MAX_AUTO_EXIT_USD: f64 = 1000.0;
+    pub const POLYMARKET_MAX_AUTO_EXITS_PER_DAY: i64 = 20;
+    pub const POLYMARKET_AUTO_REDEEM: bool = false;
+    pub const SWAP_DEFAULT_SLIPPAGE_BPS: u32 = 50;
+    pub const SWAP_MAX_SLIPPAGE_BPS: u32 = 500;
+    pub const SWAP_MAX_PRICE_IMPACT_PCT: f64 = 5.0;
+    pub const PAYOUT_MAX_RECIPIENTS: usize = 100;
+    pub const PAYOUT_MAX_TOTAL_USD: f64 = 1000.0;
+    pub const APPROVAL_SCAN_INTERVAL_HOURS: u64 = 24;
+    pub const APPROVAL_SCAN_LOOKBACK_BLOCKS: u64 = 1_000_000;
+    pub const AAVE_MAX_AUTO_REPAY_USD: f64 = 500.0;
+    pub const AAVE_MAX_AUTO_REPAYS_PER_DAY: i64 = 3;
+    pub const AAVE_TARGET_HEALTH_FACTOR: f64 = 1.5;
+    pub const EVENT_TRIGGER_POLL_SECS: u64 = 60;
+    pub const LOGIN_NETWORK: &str = "base";
+    pub const LOGIN_SAFE_ROLE: &str = "operator";
+    pub const CONFIG_WATCH_INTERVAL_SECS: u64 = 5;
+    pub const EXECUTION_TRACE_RETENTION_DAYS: i64 = 30;
 }
 
 /// Returns the absolute path to the stark-backend directory.
@@ -86,6 +155,220 @@ pub fn burner_wallet_private_key() -> Option<String> {
     env::var(env_vars::BURNER_WALLET_PRIVATE_KEY).ok()
 }
 
+/// Get the automatic portfolio snapshot interval in minutes (0 = disabled)
+pub fn portfolio_snapshot_interval_mins() -> u64 {
+    env::var(env_vars::PORTFOLIO_SNAPSHOT_INTERVAL_MINS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::PORTFOLIO_SNAPSHOT_INTERVAL_MINS)
+}
+
+/// Get the config file watcher poll interval in seconds (0 = disabled)
+pub fn config_watch_interval_secs() -> u64 {
+    env::var(env_vars::CONFIG_WATCH_INTERVAL_SECS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::CONFIG_WATCH_INTERVAL_SECS)
+}
+
+/// Get the memory consolidation interval in hours (0 = disabled)
+pub fn memory_consolidation_interval_hours() -> u64 {
+    env::var(env_vars::MEMORY_CONSOLIDATION_INTERVAL_HOURS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::MEMORY_CONSOLIDATION_INTERVAL_HOURS)
+}
+
+/// Get how old (in days) a week of daily logs must be before it is digested
+pub fn memory_consolidate_after_days() -> i64 {
+    env::var(env_vars::MEMORY_CONSOLIDATE_AFTER_DAYS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::MEMORY_CONSOLIDATE_AFTER_DAYS)
+}
+
+/// Get how old (in days) a month of weekly digests must be before it rolls up
+pub fn memory_digest_rollup_after_days() -> i64 {
+    env::var(env_vars::MEMORY_DIGEST_ROLLUP_AFTER_DAYS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::MEMORY_DIGEST_ROLLUP_AFTER_DAYS)
+}
+
+/// Get the MEMORY.md size (in characters) above which it is compacted
+pub fn memory_long_term_max_chars() -> usize {
+    env::var(env_vars::MEMORY_LONG_TERM_MAX_CHARS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::MEMORY_LONG_TERM_MAX_CHARS)
+}
+
+/// Get the Polymarket exit rule / redeem check interval in seconds (0 = disabled)
+pub fn polymarket_exit_check_interval_secs() -> u64 {
+    env::var(env_vars::POLYMARKET_EXIT_CHECK_INTERVAL_SECS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::POLYMARKET_EXIT_CHECK_INTERVAL_SECS)
+}
+
+/// Get the maximum USDC notional of a single automatic Polymarket exit order
+pub fn polymarket_max_auto_exit_usd() -> f64 {
+    env::var(env_vars::POLYMARKET_MAX_AUTO_EXIT_USD)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::POLYMARKET_MAX_AUTO_EXIT_USD)
+}
+
+/// Get the maximum number of automatic Polymarket exit orders per rolling 24 hours
+pub fn polymarket_max_auto_exits_per_day() -> i64 {
+    env::var(env_vars::POLYMARKET_MAX_AUTO_EXITS_PER_DAY)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::POLYMARKET_MAX_AUTO_EXITS_PER_DAY)
+}
+
+/// Whether resolved winning Polymarket positions are queued for redemption automatically (off by default)
+pub fn polymarket_auto_redeem_enabled() -> bool {
+    env::var(env_vars::POLYMARKET_AUTO_REDEEM)
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(defaults::POLYMARKET_AUTO_REDEEM)
+}
+
+/// Get the swap slippage tolerance used when none is requested, in basis points
+pub fn swap_default_slippage_bps() -> u32 {
+    env::var(env_vars::SWAP_DEFAULT_SLIPPAGE_BPS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::SWAP_DEFAULT_SLIPPAGE_BPS)
+}
+
+/// Get the highest slippage tolerance a swap may request, in basis points
+pub fn swap_max_slippage_bps() -> u32 {
+    env::var(env_vars::SWAP_MAX_SLIPPAGE_BPS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::SWAP_MAX_SLIPPAGE_BPS)
+}
+
+/// Get the price impact (percent) above which swaps are refused
+pub fn swap_max_price_impact_pct() -> f64 {
+    env::var(env_vars::SWAP_MAX_PRICE_IMPACT_PCT)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::SWAP_MAX_PRICE_IMPACT_PCT)
+}
+
+/// Get the maximum number of recipients in one batch payout
+pub fn payout_max_recipients() -> usize {
+    env::var(env_vars::PAYOUT_MAX_RECIPIENTS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(defaults::PAYOUT_MAX_RECIPIENTS)
+}
+
+/// Get the maximum USD value of one batch payout (0 = no limit)
+pub fn payout_max_total_usd() -> f64 {
+    env::var(env_vars::PAYOUT_MAX_TOTAL_USD)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::PAYOUT_MAX_TOTAL_USD)
+}
+
+/// Get the token approval scan interval in hours (0 = disabled)
+pub fn approval_scan_interval_hours() -> u64 {
+    env::var(env_vars::APPROVAL_SCAN_INTERVAL_HOURS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::APPROVAL_SCAN_INTERVAL_HOURS)
+}
+
+/// Get how many days execution traces are kept (0 = forever)
+pub fn execution_trace_retention_days() -> i64 {
+    env::var(env_vars::EXECUTION_TRACE_RETENTION_DAYS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::EXECUTION_TRACE_RETENTION_DAYS)
+}
+
+/// Get how many blocks back the first approval scan of a wallet starts
+pub fn approval_scan_lookback_blocks() -> u64 {
+    env::var(env_vars::APPROVAL_SCAN_LOOKBACK_BLOCKS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::APPROVAL_SCAN_LOOKBACK_BLOCKS)
+}
+
+/// Whether Aave health-factor watchers with `auto_repay` may queue repay transactions
+pub fn aave_auto_repay_enabled() -> bool {
+    env::var(env_vars::AAVE_AUTO_REPAY)
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true)
+}
+
+/// Get the maximum USD value of a single automatic Aave repay
+pub fn aave_max_auto_repay_usd() -> f64 {
+    env::var(env_vars::AAVE_MAX_AUTO_REPAY_USD)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::AAVE_MAX_AUTO_REPAY_USD)
+}
+
+/// Get the maximum number of automatic Aave repays per rolling 24 hours
+pub fn aave_max_auto_repays_per_day() -> i64 {
+    env::var(env_vars::AAVE_MAX_AUTO_REPAYS_PER_DAY)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults::AAVE_MAX_AUTO_REPAYS_PER_DAY)
+}
+
+/// Get the health factor an automatic Aave repay aims to restore
+pub fn aave_target_health_factor() -> f64 {
+    env::var(env_vars::AAVE_TARGET_HEALTH_FACTOR)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|v: &f64| *v > 1.0)
+        .unwrap_or(defaults::AAVE_TARGET_HEALTH_FACTOR)
+}
+
+/// Get how often event-triggered cron jobs poll their source, in seconds
+pub fn event_trigger_poll_secs() -> u64 {
+    env::var(env_vars::EVENT_TRIGGER_POLL_SECS)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(defaults::EVENT_TRIGGER_POLL_SECS)
+}
+
+/// Get the network used for contract wallet login checks (EIP-1271, Safe owners)
+pub fn login_network() -> String {
+    env::var(env_vars::LOGIN_NETWORK).unwrap_or_else(|_| defaults::LOGIN_NETWORK.to_string())
+}
+
+/// Get the Safe whose current owners may log in, if configured
+pub fn login_safe_address() -> Option<String> {
+    env::var(env_vars::LOGIN_SAFE_ADDRESS)
+        .ok()
+        .map(|v| v.trim().to_lowercase())
+        .filter(|v| !v.is_empty())
+}
+
+/// Get the role granted to owners of the login Safe
+pub fn login_safe_role() -> OperatorRole {
+    env::var(env_vars::LOGIN_SAFE_ROLE)
+        .ok()
+        .and_then(|v| OperatorRole::from_str(&v))
+        .unwrap_or_else(|| OperatorRole::from_str(defaults::LOGIN_SAFE_ROLE).expect("valid default role"))
+}
+
+/// Get the bearer token required by the /metrics endpoint, if configured
+pub fn metrics_token() -> Option<String> {
+    env::var(env_vars::METRICS_TOKEN)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
 /// Derive the public address from a private key
 fn derive_address_from_private_key(private_key: &str) -> Result<String, String> {
     let key_hex = private_key.strip_prefix("0x").unwrap_or(private_key);
@@ -148,6 +431,12 @@ pub struct MemoryConfig {
     pub enable_cross_session_memory: bool,
     /// Maximum number of cross-session memories to include
     pub cross_session_memory_limit: i32,
+    /// Embedding endpoint for semantic search: an ai_endpoints.ron preset or a URL
+    pub embedding_endpoint: Option<String>,
+    /// Embedding model override (default: the preset's model)
+    pub embedding_model: Option<String>,
+    /// Bearer key for non-x402 embedding endpoints
+    pub embedding_api_key: Option<String>,
 }
 
 impl Default for MemoryConfig {
@@ -158,6 +447,9 @@ impl Default for MemoryConfig {
             enable_pre_compaction_flush: true,
             enable_cross_session_memory: true,
             cross_session_memory_limit: 5,
+            embedding_endpoint: None,
+            embedding_model: None,
+            embedding_api_key: None,
         }
     }
 }
@@ -180,6 +472,9 @@ impl MemoryConfig {
                 .unwrap_or_else(|_| "5".to_string())
                 .parse()
                 .unwrap_or(5),
+            embedding_endpoint: env::var(env_vars::MEMORY_EMBEDDING_ENDPOINT).ok().filter(|v| !v.is_empty()),
+            embedding_model: env::var(env_vars::MEMORY_EMBEDDING_MODEL).ok().filter(|v| !v.is_empty()),
+            embedding_api_key: env::var(env_vars::MEMORY_EMBEDDING_API_KEY).ok().filter(|v| !v.is_empty()),
         }
     }
 
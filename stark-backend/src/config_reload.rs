@@ -0,0 +1,320 @@
+//! Hot reload of RON config files
+//!
+//! Presets, networks, tokens and validators are read from the config directory at startup.
+//! `ConfigReloader` re-reads them at runtime, either on demand (admin endpoint) or from a
+//! polling file watcher. Each reload fully parses and validates the new files before the
+//! live registry is swapped, so a bad edit is reported and the previous set stays in use.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::GatewayEvent;
+use crate::tool_validators::{self, ValidatorRegistry};
+use crate::tools::builtin::cryptocurrency::{network_lookup, token_lookup};
+use crate::tools::presets;
+
+/// A group of config files that is reloaded as a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigTarget {
+    /// x402_fetch_presets.ron, x402_rpc_presets.ron, web3_presets.ron (and the ABIs they use)
+    Presets,
+    /// networks.ron
+    Networks,
+    /// tokens.ron
+    Tokens,
+    /// validators/*.ron
+    Validators,
+}
+
+impl ConfigTarget {
+    pub const ALL: [ConfigTarget; 4] = [
+        ConfigTarget::Networks,
+        ConfigTarget::Presets,
+        ConfigTarget::Tokens,
+        ConfigTarget::Validators,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigTarget::Presets => "presets",
+            ConfigTarget::Networks => "networks",
+            ConfigTarget::Tokens => "tokens",
+            ConfigTarget::Validators => "validators",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == s)
+    }
+
+    /// Which target a changed file (relative to the config dir) belongs to
+    pub fn for_path(relative: &Path) -> Option<Self> {
+        let parent = relative.parent().and_then(|p| p.to_str()).unwrap_or("");
+        let name = relative.file_name()?.to_str()?;
+        match (parent, name) {
+            ("", "x402_fetch_presets.ron" | "x402_rpc_presets.ron" | "web3_presets.ron") => {
+                Some(ConfigTarget::Presets)
+            }
+            ("", "networks.ron") => Some(ConfigTarget::Networks),
+            ("", "tokens.ron") => Some(ConfigTarget::Tokens),
+            ("validators", n) if n.ends_with(".ron") => Some(ConfigTarget::Validators),
+            ("abis", n) if n.ends_with(".json") => Some(ConfigTarget::Presets),
+            _ => None,
+        }
+    }
+}
+
+/// Result of the most recent reload attempt for a target
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadOutcome {
+    pub target: ConfigTarget,
+    pub success: bool,
+    /// Number of entries now loaded (only set on success)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loaded: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub reloaded_at: DateTime<Utc>,
+}
+
+pub struct ConfigReloader {
+    config_dir: PathBuf,
+    validator_registry: Arc<ValidatorRegistry>,
+    broadcaster: Arc<EventBroadcaster>,
+    outcomes: Mutex<HashMap<ConfigTarget, ReloadOutcome>>,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        config_dir: &Path,
+        validator_registry: Arc<ValidatorRegistry>,
+        broadcaster: Arc<EventBroadcaster>,
+    ) -> Self {
+        Self {
+            config_dir: config_dir.to_path_buf(),
+            validator_registry,
+            broadcaster,
+            outcomes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reload one target. The live registry is only replaced if everything validates.
+    pub fn reload(&self, target: ConfigTarget) -> ReloadOutcome {
+        let result = match target {
+            ConfigTarget::Presets => presets::reload_presets(&self.config_dir),
+            ConfigTarget::Networks => network_lookup::reload_networks(&self.config_dir)
+                .and_then(|_| presets::reload_networks(&self.config_dir)),
+            ConfigTarget::Tokens => token_lookup::reload_tokens(&self.config_dir),
+            ConfigTarget::Validators => tool_validators::reload_validators_from_dir(
+                &self.config_dir.join("validators"),
+                &self.validator_registry,
+            ),
+        };
+
+        let outcome = match result {
+            Ok(loaded) => {
+                log::info!("[CONFIG_RELOAD] Reloaded {} ({} entries)", target.as_str(), loaded);
+                ReloadOutcome {
+                    target,
+                    success: true,
+                    loaded: Some(loaded),
+                    error: None,
+                    reloaded_at: Utc::now(),
+                }
+            }
+            Err(e) => {
+                log::error!(
+                    "[CONFIG_RELOAD] Rejected {} reload, keeping previous config: {}",
+                    target.as_str(),
+                    e
+                );
+                ReloadOutcome {
+                    target,
+                    success: false,
+                    loaded: None,
+                    error: Some(e),
+                    reloaded_at: Utc::now(),
+                }
+            }
+        };
+
+        self.outcomes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(target, outcome.clone());
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "config.reloaded",
+            serde_json::to_value(&outcome).unwrap_or_default(),
+        ));
+        outcome
+    }
+
+    /// Reload every target (networks first, since presets resolve against them)
+    pub fn reload_all(&self) -> Vec<ReloadOutcome> {
+        ConfigTarget::ALL.into_iter().map(|t| self.reload(t)).collect()
+    }
+
+    /// Last outcome per target, for targets reloaded since startup
+    pub fn status(&self) -> Vec<ReloadOutcome> {
+        let outcomes = self.outcomes.lock().unwrap_or_else(|e| e.into_inner());
+        ConfigTarget::ALL
+            .iter()
+            .filter_map(|t| outcomes.get(t).cloned())
+            .collect()
+    }
+
+    /// Modification times of every watched file, keyed by path relative to the config dir
+    /// (ABIs live in the repo-level abis/ folder and are keyed as `abis/<file>`)
+    fn snapshot_mtimes(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut mtimes = HashMap::new();
+        let watched = [
+            ("", self.config_dir.clone()),
+            ("validators", self.config_dir.join("validators")),
+            ("abis", crate::web3::default_abis_dir()),
+        ];
+        for (sub, dir) in watched {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let relative = Path::new(sub).join(entry.file_name());
+                if ConfigTarget::for_path(&relative).is_none() {
+                    continue;
+                }
+                if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                    mtimes.insert(relative, modified);
+                }
+            }
+        }
+        mtimes
+    }
+
+    /// Targets whose files were added, removed or modified between two snapshots
+    fn changed_targets(
+        before: &HashMap<PathBuf, SystemTime>,
+        after: &HashMap<PathBuf, SystemTime>,
+    ) -> Vec<ConfigTarget> {
+        let mut changed: Vec<ConfigTarget> = before
+            .keys()
+            .chain(after.keys())
+            .filter(|path| before.get(*path) != after.get(*path))
+            .filter_map(|path| ConfigTarget::for_path(path))
+            .collect();
+        changed.sort_by_key(|t| ConfigTarget::ALL.iter().position(|a| a == t));
+        changed.dedup();
+        changed
+    }
+
+    /// Poll the config directory and reload targets whose files changed.
+    /// Does nothing when `interval_secs` is 0.
+    pub fn spawn_watcher(self: &Arc<Self>, interval_secs: u64) {
+        if interval_secs == 0 {
+            log::info!("[CONFIG_RELOAD] File watcher disabled");
+            return;
+        }
+
+        let reloader = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut previous = reloader.snapshot_mtimes();
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let current = reloader.snapshot_mtimes();
+                for target in Self::changed_targets(&previous, &current) {
+                    log::info!("[CONFIG_RELOAD] Detected change in {} config", target.as_str());
+                    reloader.reload(target);
+                }
+                previous = current;
+            }
+        });
+        log::info!(
+            "[CONFIG_RELOAD] Watching {} every {}s",
+            self.config_dir.display(),
+            interval_secs
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_for_path() {
+        assert_eq!(
+            ConfigTarget::for_path(Path::new("web3_presets.ron")),
+            Some(ConfigTarget::Presets)
+        );
+        assert_eq!(
+            ConfigTarget::for_path(Path::new("networks.ron")),
+            Some(ConfigTarget::Networks)
+        );
+        assert_eq!(
+            ConfigTarget::for_path(Path::new("validators/polymarket.ron")),
+            Some(ConfigTarget::Validators)
+        );
+        assert_eq!(
+            ConfigTarget::for_path(Path::new("abis/erc20.json")),
+            Some(ConfigTarget::Presets)
+        );
+        assert_eq!(ConfigTarget::for_path(Path::new("rpc_providers.ron")), None);
+        assert_eq!(ConfigTarget::for_path(Path::new("validators/notes.md")), None);
+    }
+
+    #[test]
+    fn test_changed_targets() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + Duration::from_secs(1);
+        let before: HashMap<PathBuf, SystemTime> = [
+            (PathBuf::from("tokens.ron"), t0),
+            (PathBuf::from("networks.ron"), t0),
+            (PathBuf::from("validators/a.ron"), t0),
+        ]
+        .into_iter()
+        .collect();
+        let after: HashMap<PathBuf, SystemTime> = [
+            (PathBuf::from("tokens.ron"), t1),
+            (PathBuf::from("networks.ron"), t0),
+            (PathBuf::from("web3_presets.ron"), t0),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            ConfigReloader::changed_targets(&before, &after),
+            vec![ConfigTarget::Presets, ConfigTarget::Tokens, ConfigTarget::Validators]
+        );
+        assert!(ConfigReloader::changed_targets(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_bad_validator_keeps_previous_set() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("validators")).unwrap();
+        std::fs::write(dir.path().join("validators/broken.ron"), "ValidatorDef(").unwrap();
+
+        let mut registry = ValidatorRegistry::new();
+        if let Some(validators_dir) = tool_validators::default_validators_dir() {
+            tool_validators::load_validators_from_dir(validators_dir, &mut registry);
+        }
+        let before = registry.len();
+
+        let reloader = ConfigReloader::new(
+            dir.path(),
+            Arc::new(registry),
+            Arc::new(EventBroadcaster::new()),
+        );
+        let outcome = reloader.reload(ConfigTarget::Validators);
+        assert!(!outcome.success);
+        assert!(outcome.error.is_some());
+        assert_eq!(reloader.validator_registry.len(), before);
+        assert_eq!(reloader.status().len(), 1);
+    }
+}
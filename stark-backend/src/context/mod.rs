@@ -11,7 +11,7 @@
 
 pub mod tokenizer;
 
-use crate::ai::{AiClient, Message, MessageRole};
+use crate::ai::{AiClient, Message, MessageRole, UsageScope};
 use crate::config::MemoryConfig;
 use crate::db::Database;
 use crate::models::SessionMessage;
@@ -64,6 +64,11 @@ pub fn estimate_tokens(text: &str) -> i32 {
     TokenEstimator::ContentAware.estimate_text(text)
 }
 
+/// Usage scope of the summaries and memory flushes of a session
+fn session_scope(session_id: i64) -> UsageScope<'static> {
+    UsageScope { session_id: Some(session_id), ..Default::default() }
+}
+
 /// Estimate total tokens for a list of messages
 /// Uses content-aware estimation with role overhead
 pub fn estimate_messages_tokens(messages: &[SessionMessage]) -> i32 {
@@ -239,7 +244,7 @@ impl ContextManager {
         }
 
         // Generate a shorter summary for incremental compaction
-        let summary = self.generate_incremental_summary(client, &messages_to_compact).await?;
+        let summary = self.generate_incremental_summary(session_id, client, &messages_to_compact).await?;
 
         log::info!(
             "[INCREMENTAL_COMPACT] Generated summary ({} chars) for {} messages",
@@ -313,6 +318,7 @@ impl ContextManager {
     /// Generate a shorter summary for incremental compaction
     async fn generate_incremental_summary(
         &self,
+        session_id: i64,
         client: &AiClient,
         messages: &[SessionMessage],
     ) -> Result<String, String> {
@@ -350,7 +356,7 @@ impl ContextManager {
             },
         ];
 
-        client.generate_text(summary_messages).await
+        client.generate_sidecar_text(summary_messages, Some(&self.db), session_scope(session_id)).await
             .map_err(|e| format!("Failed to generate incremental summary: {}", e))
     }
 
@@ -453,7 +459,7 @@ impl ContextManager {
             },
         ];
 
-        let response = client.generate_text(flush_messages).await
+        let response = client.generate_sidecar_text(flush_messages, Some(&self.db), session_scope(session_id)).await
             .map_err(|e| format!("Failed to generate memory flush: {}", e))?;
 
         if response.contains("NO_MEMORIES_NEEDED") {
@@ -584,7 +590,7 @@ impl ContextManager {
             },
         ];
 
-        let summary = client.generate_text(summary_messages).await
+        let summary = client.generate_sidecar_text(summary_messages, Some(&self.db), session_scope(session_id)).await
             .map_err(|e| format!("Failed to generate compaction summary: {}", e))?;
 
         log::info!("[COMPACTION] Generated summary ({} chars) for session {}", summary.len(), session_id);
@@ -782,7 +788,7 @@ pub async fn save_session_memory(
         },
     ];
 
-    let response = client.generate_text(ai_messages).await
+    let response = client.generate_sidecar_text(ai_messages, Some(db.as_ref()), session_scope(session_id)).await
         .map_err(|e| format!("Failed to generate session summary: {}", e))?;
 
     // Parse title and summary from response
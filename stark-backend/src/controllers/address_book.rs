@@ -0,0 +1,296 @@
+//! Address book API endpoints
+//!
+//! CRUD for labelled contacts, including the `trusted` trust level the agent
+//! cannot assign itself, plus ENS forward/reverse resolution on mainnet.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::address_book::ContactInput;
+use crate::web3::ens;
+use crate::AppState;
+
+/// Validate session token from request
+fn validate_session(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn error_response(status: actix_web::http::StatusCode, error: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "success": false,
+        "error": error.into()
+    }))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/address-book")
+            .route("", web::get().to(list_contacts))
+            .route("", web::post().to(add_contact))
+            .route("/resolve", web::get().to(resolve))
+            .route("/{id}", web::get().to(get_contact))
+            .route("/{id}", web::put().to(update_contact))
+            .route("/{id}", web::delete().to(delete_contact)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveQuery {
+    /// ENS name to resolve
+    name: Option<String>,
+    /// Address to reverse-resolve
+    address: Option<String>,
+}
+
+/// Normalize the input and resolve its ENS name, mapping failures to 400
+async fn prepare_input(state: &web::Data<AppState>, input: ContactInput) -> Result<ContactInput, HttpResponse> {
+    let mut input = input
+        .normalize()
+        .map_err(|e| error_response(actix_web::http::StatusCode::BAD_REQUEST, e))?;
+    if input.ens_name.is_some() {
+        let rpc = ens::mainnet_rpc(&state.db, state.wallet_provider.as_ref())
+            .map_err(|e| error_response(actix_web::http::StatusCode::SERVICE_UNAVAILABLE, e))?;
+        input
+            .resolve_ens(&rpc)
+            .await
+            .map_err(|e| error_response(actix_web::http::StatusCode::BAD_REQUEST, e))?;
+    }
+    Ok(input)
+}
+
+async fn list_contacts(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    match state.db.list_contacts() {
+        Ok(contacts) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "contacts": contacts,
+        })),
+        Err(e) => {
+            log::error!("Failed to list contacts: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to list contacts",
+            )
+        }
+    }
+}
+
+async fn get_contact(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    match state.db.get_contact(path.into_inner()) {
+        Ok(Some(contact)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "contact": contact,
+        })),
+        Ok(None) => error_response(actix_web::http::StatusCode::NOT_FOUND, "Contact not found"),
+        Err(e) => {
+            log::error!("Failed to load contact: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load contact",
+            )
+        }
+    }
+}
+
+async fn add_contact(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<ContactInput>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let input = match prepare_input(&state, body.into_inner()).await {
+        Ok(i) => i,
+        Err(resp) => return resp,
+    };
+
+    match state.db.add_contact(&input) {
+        Ok(contact) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "contact": contact,
+        })),
+        Err(rusqlite::Error::SqliteFailure(err, _))
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            error_response(
+                actix_web::http::StatusCode::CONFLICT,
+                "A contact with this label or address already exists",
+            )
+        }
+        Err(e) => {
+            log::error!("Failed to add contact: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to add contact",
+            )
+        }
+    }
+}
+
+async fn update_contact(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<ContactInput>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let input = match prepare_input(&state, body.into_inner()).await {
+        Ok(i) => i,
+        Err(resp) => return resp,
+    };
+
+    match state.db.update_contact(path.into_inner(), &input) {
+        Ok(Some(contact)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "contact": contact,
+        })),
+        Ok(None) => error_response(actix_web::http::StatusCode::NOT_FOUND, "Contact not found"),
+        Err(rusqlite::Error::SqliteFailure(err, _))
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            error_response(
+                actix_web::http::StatusCode::CONFLICT,
+                "A contact with this label or address already exists",
+            )
+        }
+        Err(e) => {
+            log::error!("Failed to update contact: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to update contact",
+            )
+        }
+    }
+}
+
+async fn delete_contact(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    match state.db.delete_contact(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => error_response(actix_web::http::StatusCode::NOT_FOUND, "Contact not found"),
+        Err(e) => {
+            log::error!("Failed to delete contact: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete contact",
+            )
+        }
+    }
+}
+
+/// ENS lookup: `?name=vitalik.eth` resolves forward, `?address=0x...` reverse
+async fn resolve(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ResolveQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let rpc = match ens::mainnet_rpc(&state.db, state.wallet_provider.as_ref()) {
+        Ok(r) => r,
+        Err(e) => return error_response(actix_web::http::StatusCode::SERVICE_UNAVAILABLE, e),
+    };
+
+    match (&query.name, &query.address) {
+        (Some(name), None) => {
+            let name = ens::normalize_name(name);
+            if !ens::is_ens_name(&name) {
+                return error_response(
+                    actix_web::http::StatusCode::BAD_REQUEST,
+                    format!("'{}' is not an ENS name", name),
+                );
+            }
+            match ens::resolve_name(&rpc, &name).await {
+                Ok(address) => HttpResponse::Ok().json(serde_json::json!({
+                    "success": true,
+                    "name": name,
+                    "address": address.map(|a| format!("{:?}", a)),
+                })),
+                Err(e) => {
+                    log::error!("ENS resolution of {} failed: {}", name, e);
+                    error_response(actix_web::http::StatusCode::BAD_GATEWAY, e)
+                }
+            }
+        }
+        (None, Some(address)) => {
+            let address: ethers::types::Address = match address.trim().parse() {
+                Ok(a) => a,
+                Err(_) => {
+                    return error_response(
+                        actix_web::http::StatusCode::BAD_REQUEST,
+                        format!("Invalid address '{}'", address),
+                    );
+                }
+            };
+            match ens::lookup_address(&rpc, address).await {
+                Ok(name) => HttpResponse::Ok().json(serde_json::json!({
+                    "success": true,
+                    "address": format!("{:?}", address),
+                    "name": name,
+                })),
+                Err(e) => {
+                    log::error!("ENS reverse lookup of {:?} failed: {}", address, e);
+                    error_response(actix_web::http::StatusCode::BAD_GATEWAY, e)
+                }
+            }
+        }
+        _ => error_response(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "Provide exactly one of 'name' or 'address'",
+        ),
+    }
+}
@@ -1,5 +1,5 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
-use crate::ai::ArchetypeId;
+use crate::ai::{AiClient, ArchetypeId};
 use crate::keystore_client::{KEYSTORE_CLIENT, DEFAULT_KEYSTORE_URL};
 use crate::models::{AgentSettings, AgentSettingsResponse, UpdateAgentSettingsRequest, UpdateBotSettingsRequest};
 use crate::ai_endpoint_config;
@@ -149,23 +149,33 @@ pub async fn update_agent_settings(
     }
 
     // Validate archetype
-    if ArchetypeId::from_str(&request.model_archetype).is_none() {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("Invalid archetype: {}. Must be kimi, llama, claude, or openai.", request.model_archetype)
-        }));
+    let archetype_id = match ArchetypeId::from_str(&request.model_archetype) {
+        Some(id) => id,
+        None => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid archetype: {}. Must be kimi, llama, claude, or openai.", request.model_archetype)
+            }));
+        }
+    };
+
+    // Validate model, provider and sampling parameters against the archetype
+    if let Err(e) = AiClient::validate_model_options(archetype_id, &request.options) {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": e }));
     }
 
     // Save settings
     log::info!(
-        "Saving agent settings: endpoint={}, archetype={}, max_response_tokens={}, max_context_tokens={}, has_secret_key={}",
+        "Saving agent settings: endpoint={}, archetype={}, model={:?}, provider={}, max_response_tokens={}, max_context_tokens={}, has_secret_key={}",
         request.endpoint,
         request.model_archetype,
+        request.options.model,
+        request.options.provider.as_str(),
         request.max_response_tokens,
         request.max_context_tokens,
         request.secret_key.is_some()
     );
 
-    match state.db.save_agent_settings(&request.endpoint, &request.model_archetype, request.max_response_tokens, request.max_context_tokens, request.secret_key.as_deref()) {
+    match state.db.save_agent_settings(&request.endpoint, &request.model_archetype, request.max_response_tokens, request.max_context_tokens, request.secret_key.as_deref(), &request.options) {
         Ok(settings) => {
             log::info!("Updated agent settings to use {} endpoint with {} archetype", request.endpoint, request.model_archetype);
             let response: AgentSettingsResponse = settings.into();
@@ -373,8 +383,10 @@ pub async fn get_ai_endpoint_presets(
         return resp;
     }
 
+    // Embedding presets feed memory search, not chat
     let presets: Vec<serde_json::Value> = ai_endpoint_config::list_ai_endpoints()
         .into_iter()
+        .filter(|(_, preset)| preset.model_archetype != crate::qmd_memory::embeddings::EMBEDDING_ARCHETYPE)
         .map(|(id, preset)| {
             serde_json::json!({
                 "id": id,
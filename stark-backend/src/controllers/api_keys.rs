@@ -1053,6 +1053,10 @@ async fn backup_to_cloud(state: web::Data<AppState>, req: HttpRequest) -> impl R
                     max_context_tokens: s.max_context_tokens,
                     enabled: s.enabled,
                     secret_key: s.secret_key.clone(),
+                    model: s.model.clone(),
+                    provider: s.provider,
+                    temperature: s.temperature,
+                    top_p: s.top_p,
                 })
                 .collect();
         }
@@ -1869,6 +1873,12 @@ async fn restore_from_cloud(state: web::Data<AppState>, req: HttpRequest) -> imp
                 entry.max_response_tokens,
                 entry.max_context_tokens,
                 entry.secret_key.as_deref(),
+                &crate::models::ModelOptions {
+                    model: entry.model.clone(),
+                    provider: entry.provider,
+                    temperature: entry.temperature,
+                    top_p: entry.top_p,
+                },
             ) {
                 Ok(saved) => {
                     // save_agent_settings enables the last one saved; if the backup entry was
@@ -1,9 +1,14 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use chrono::Utc;
-use ethers::core::types::Signature;
+use ethers::core::types::{Address, Signature};
 use ethers::utils::hash_message;
 use serde::{Deserialize, Serialize};
 
+use crate::config;
+use crate::middleware::session_auth::{
+    audit, extract_token, login_rpc, resolve_login_role, resolve_session_operator, OperatorIdentity,
+};
+use crate::web3::contract_wallet;
 use crate::AppState;
 
 const SERVICE_NAME: &str = "StarkBot";
@@ -61,7 +66,8 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/generate_challenge", web::post().to(generate_challenge))
             .route("/validate_auth", web::post().to(validate_auth))
             .route("/logout", web::post().to(logout))
-            .route("/validate", web::get().to(validate)),
+            .route("/validate", web::get().to(validate))
+            .route("/me", web::get().to(me)),
     );
     // Flash mode auth - separate from /api/auth scope to allow redirect
     cfg.route("/auth/flash", web::get().to(flash_login));
@@ -86,6 +92,37 @@ fn recover_address(msg: &str, signature: &str) -> Option<String> {
     Some(format!("{:?}", recovered).to_lowercase())
 }
 
+/// Verify a login signature against a contract wallet via EIP-1271 `isValidSignature`
+async fn verify_contract_signature(
+    state: &web::Data<AppState>,
+    public_address: &str,
+    msg: &str,
+    signature: &str,
+) -> bool {
+    let wallet: Address = match public_address.parse() {
+        Ok(a) => a,
+        Err(_) => return false,
+    };
+    let sig_bytes = match hex::decode(signature.strip_prefix("0x").unwrap_or(signature)) {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+    let rpc = match login_rpc(&state.db, state.wallet_provider.as_ref()) {
+        Ok(rpc) => rpc,
+        Err(e) => {
+            log::warn!("Cannot check EIP-1271 signature for {}: {}", public_address, e);
+            return false;
+        }
+    };
+    match contract_wallet::is_valid_signature(&rpc, wallet, hash_message(msg), &sig_bytes).await {
+        Ok(valid) => valid,
+        Err(e) => {
+            log::warn!("EIP-1271 check failed for {}: {}", public_address, e);
+            false
+        }
+    }
+}
+
 async fn generate_challenge(
     state: web::Data<AppState>,
     body: web::Json<GenerateChallengeRequest>,
@@ -139,29 +176,42 @@ async fn validate_auth(
         });
     }
 
-    // Check that login is configured
-    let admin_address = match &state.config.login_admin_public_address {
-        Some(addr) => addr.to_lowercase(),
+    // Check that login is configured: either an admin address or at least one operator
+    let admin_address = state
+        .config
+        .login_admin_public_address
+        .as_ref()
+        .map(|addr| addr.to_lowercase());
+    let has_operators = state.db.list_operators().map(|o| !o.is_empty()).unwrap_or(false);
+    if admin_address.is_none() && !has_operators && config::login_safe_address().is_none() {
+        return HttpResponse::ServiceUnavailable().json(LoginResponse {
+            success: false,
+            token: None,
+            expires_at: None,
+            error: Some("Login not configured. Set LOGIN_ADMIN_PUBLIC_ADDRESS or BURNER_WALLET_BOT_PRIVATE_KEY environment variable.".to_string()),
+        });
+    }
+
+    // Check that this address is the admin address or a registered operator
+    let role = match resolve_login_role(
+        &state.db,
+        state.wallet_provider.as_ref(),
+        admin_address.as_deref(),
+        &public_address,
+    )
+    .await
+    {
+        Some(role) => role,
         None => {
-            return HttpResponse::ServiceUnavailable().json(LoginResponse {
+            return HttpResponse::Unauthorized().json(LoginResponse {
                 success: false,
                 token: None,
                 expires_at: None,
-                error: Some("Login not configured. Set LOGIN_ADMIN_PUBLIC_ADDRESS or BURNER_WALLET_BOT_PRIVATE_KEY environment variable.".to_string()),
+                error: Some("Unauthorized wallet address".to_string()),
             });
         }
     };
 
-    // Check that this address is the admin address
-    if public_address != admin_address {
-        return HttpResponse::Unauthorized().json(LoginResponse {
-            success: false,
-            token: None,
-            expires_at: None,
-            error: Some("Unauthorized wallet address".to_string()),
-        });
-    }
-
     // Verify the challenge exists and matches
     match state.db.validate_challenge(&public_address, challenge) {
         Ok(true) => {}
@@ -184,9 +234,11 @@ async fn validate_auth(
         }
     }
 
-    // Verify signature
+    // Verify signature: an EOA personal_sign, or EIP-1271 for contract wallets
     let recovered_address = recover_address(challenge, signature);
-    if recovered_address.as_deref() != Some(public_address.as_str()) {
+    let signature_valid = recovered_address.as_deref() == Some(public_address.as_str())
+        || verify_contract_signature(&state, &public_address, challenge, signature).await;
+    if !signature_valid {
         return HttpResponse::Unauthorized().json(LoginResponse {
             success: false,
             token: None,
@@ -200,12 +252,23 @@ async fn validate_auth(
 
     // Create session
     match state.db.create_session_for_address(Some(&public_address)) {
-        Ok(session) => HttpResponse::Ok().json(LoginResponse {
-            success: true,
-            token: Some(session.token),
-            expires_at: Some(session.expires_at.timestamp()),
-            error: None,
-        }),
+        Ok(session) => {
+            log::info!("Operator {} logged in as {}", public_address, role);
+            audit(
+                &state.db,
+                Some(&OperatorIdentity { public_address: public_address.clone(), role }),
+                "http",
+                "login",
+                "ok",
+                None,
+            );
+            HttpResponse::Ok().json(LoginResponse {
+                success: true,
+                token: Some(session.token),
+                expires_at: Some(session.expires_at.timestamp()),
+                error: None,
+            })
+        }
         Err(e) => {
             log::error!("Failed to create session: {}", e);
             HttpResponse::InternalServerError().json(LoginResponse {
@@ -252,6 +315,32 @@ async fn validate(state: web::Data<AppState>, req: HttpRequest) -> impl Responde
     }
 }
 
+/// GET /api/auth/me - the logged-in operator's address and role
+async fn me(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    let session = match extract_token(&req).map(|t| state.db.validate_session(&t)) {
+        Some(Ok(Some(session))) => session,
+        _ => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "Invalid or expired session"
+            }));
+        }
+    };
+
+    let admin_address = state.config.login_admin_public_address.as_deref();
+    match resolve_session_operator(&state.db, state.wallet_provider.as_ref(), admin_address, &session).await {
+        Some(operator) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "public_address": operator.public_address,
+            "role": operator.role
+        })),
+        None => HttpResponse::Forbidden().json(serde_json::json!({
+            "success": false,
+            "error": "This session is not linked to an operator account"
+        })),
+    }
+}
+
 // ==================== Flash Mode Auth ====================
 
 #[derive(Deserialize)]
@@ -334,7 +423,7 @@ async fn flash_login(
 
     // Create a local session
     // Use the wallet address as the session identifier (compatible with existing SIWE sessions)
-    let session = match state.db.create_session_for_address(Some(&flash_user.wallet_address)) {
+    let session = match state.db.create_flash_session(&flash_user.wallet_address) {
         Ok(s) => s,
         Err(e) => {
             log::error!("Failed to create session: {}", e);
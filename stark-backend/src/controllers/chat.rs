@@ -728,6 +728,7 @@ async fn new_web_session(
             match state.db.reset_chat_session(session.id) {
                 Ok(new_session) => {
                     log::info!("[CHAT] Created new web session {} (replaced {})", new_session.id, session.id);
+                    state.dispatcher.fire_session_end(session.channel_id, session.id, "reset").await;
 
                     HttpResponse::Ok().json(WebSessionResponse {
                         success: true,
@@ -0,0 +1,110 @@
+//! Config reload API endpoints
+//!
+//! Reports the last hot-reload outcome per config target and lets admins
+//! re-read presets, networks, tokens and validators without a restart.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::config_reload::ConfigTarget;
+use crate::AppState;
+
+/// Validate session token from request
+fn validate_session(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/config")
+            .route("/reload", web::get().to(reload_status))
+            .route("/reload", web::post().to(reload)),
+    );
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReloadRequest {
+    /// presets, networks, tokens or validators; omit to reload everything
+    target: Option<String>,
+}
+
+/// GET /api/config/reload - last reload outcome per target
+async fn reload_status(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "reloads": state.config_reloader.status(),
+    }))
+}
+
+/// POST /api/config/reload - re-read config files; the old set stays active on error
+async fn reload(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: Option<web::Json<ReloadRequest>>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let outcomes = match body.target.as_deref() {
+        None | Some("all") => state.config_reloader.reload_all(),
+        Some(name) => match ConfigTarget::parse(name) {
+            Some(target) => vec![state.config_reloader.reload(target)],
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "success": false,
+                    "error": format!(
+                        "Unknown config target '{}'. Expected presets, networks, tokens, validators or all",
+                        name
+                    )
+                }));
+            }
+        },
+    };
+
+    let success = outcomes.iter().all(|o| o.success);
+    let errors: Vec<String> = outcomes
+        .iter()
+        .filter_map(|o| o.error.as_ref().map(|e| format!("{}: {}", o.target.as_str(), e)))
+        .collect();
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": success,
+        "reloads": outcomes,
+        "error": if errors.is_empty() { None } else { Some(errors.join("; ")) },
+    }))
+}
@@ -5,6 +5,7 @@ use crate::models::{
     CreateCronJobRequest, CronJobResponse, HeartbeatConfigResponse,
     UpdateCronJobRequest, UpdateHeartbeatConfigRequest,
 };
+use crate::scheduler::triggers::EventTrigger;
 use crate::scheduler::Scheduler;
 use crate::AppState;
 
@@ -97,50 +98,53 @@ async fn list_jobs(state: web::Data<AppState>, req: HttpRequest) -> HttpResponse
     }
 }
 
-/// Create a new cron job
-async fn create_job(
-    state: web::Data<AppState>,
-    req: HttpRequest,
-    body: web::Json<CreateCronJobRequest>,
-) -> HttpResponse {
-    if let Err(resp) = validate_session_from_request(&state, &req) {
-        return resp;
+/// Validate the schedule and session mode of a new job.
+/// Shared with the gateway's `cron.create` method.
+pub(crate) fn validate_create_request(body: &CreateCronJobRequest) -> Result<(), String> {
+    let valid_types = ["at", "every", "cron", "event"];
+    if !valid_types.contains(&body.schedule_type.to_lowercase().as_str()) {
+        return Err("Invalid schedule_type. Valid options: at, every, cron, event".to_string());
     }
 
-    // Validate schedule type
-    let valid_types = ["at", "every", "cron"];
-    if !valid_types.contains(&body.schedule_type.to_lowercase().as_str()) {
-        return HttpResponse::BadRequest().json(CronJobResponse {
-            success: false,
-            job: None,
-            jobs: None,
-            error: Some("Invalid schedule_type. Valid options: at, every, cron".to_string()),
-        });
+    // Validate the trigger spec if type is event
+    if body.schedule_type.eq_ignore_ascii_case("event") {
+        EventTrigger::parse(&body.schedule_value)?;
     }
 
     // Validate cron expression if type is cron
-    if body.schedule_type.to_lowercase() == "cron" {
+    if body.schedule_type.eq_ignore_ascii_case("cron") {
         use cron::Schedule;
         use std::str::FromStr;
 
         if Schedule::from_str(&body.schedule_value).is_err() {
-            return HttpResponse::BadRequest().json(CronJobResponse {
-                success: false,
-                job: None,
-                jobs: None,
-                error: Some(format!("Invalid cron expression: {}", body.schedule_value)),
-            });
+            return Err(format!("Invalid cron expression: {}", body.schedule_value));
         }
     }
 
-    // Validate session mode
     let valid_modes = ["main", "isolated"];
     if !valid_modes.contains(&body.session_mode.to_lowercase().as_str()) {
+        return Err("Invalid session_mode. Valid options: main, isolated".to_string());
+    }
+
+    Ok(())
+}
+
+/// Create a new cron job
+async fn create_job(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<CreateCronJobRequest>,
+) -> HttpResponse {
+    if let Err(resp) = validate_session_from_request(&state, &req) {
+        return resp;
+    }
+
+    if let Err(e) = validate_create_request(&body) {
         return HttpResponse::BadRequest().json(CronJobResponse {
             success: false,
             job: None,
             jobs: None,
-            error: Some("Invalid session_mode. Valid options: main, isolated".to_string()),
+            error: Some(e),
         });
     }
 
@@ -236,6 +240,20 @@ async fn update_job(
                 });
             }
         }
+
+        let trigger_error = if schedule_type.eq_ignore_ascii_case("event") {
+            EventTrigger::parse(schedule_value).err()
+        } else {
+            None
+        };
+        if let Some(e) = trigger_error {
+            return HttpResponse::BadRequest().json(CronJobResponse {
+                success: false,
+                job: None,
+                jobs: None,
+                error: Some(e),
+            });
+        }
     }
 
     match state.db.update_cron_job(
@@ -1,12 +1,33 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::Serialize;
 
+use crate::db::tables::ai_usage::AiUsageTotals;
+use crate::portfolio;
 use crate::AppState;
 
 #[derive(Serialize)]
 pub struct DashboardData {
     message: String,
     timestamp: String,
+    /// Latest portfolio valuation (None until the first snapshot is taken)
+    portfolio: Option<PortfolioSummary>,
+    /// AI token usage and estimated cost
+    ai_usage: Option<AiUsageSummary>,
+}
+
+#[derive(Serialize)]
+pub struct AiUsageSummary {
+    /// Since midnight UTC
+    today: AiUsageTotals,
+    last_30_days: AiUsageTotals,
+}
+
+#[derive(Serialize)]
+pub struct PortfolioSummary {
+    total_value_usd: f64,
+    snapshot_taken_at: String,
+    change_24h_usd: f64,
+    change_24h_pct: Option<f64>,
 }
 
 #[derive(Serialize)]
@@ -38,6 +59,8 @@ async fn get_dashboard(state: web::Data<AppState>, req: HttpRequest) -> impl Res
         Ok(Some(_session)) => HttpResponse::Ok().json(DashboardData {
             message: "Welcome to StarkBot Dashboard!".to_string(),
             timestamp: chrono::Utc::now().to_rfc3339(),
+            portfolio: portfolio_summary(&state),
+            ai_usage: ai_usage_summary(&state),
         }),
         Ok(None) => HttpResponse::Unauthorized().json(ErrorResponse {
             error: "Invalid or expired session".to_string(),
@@ -50,3 +73,38 @@ async fn get_dashboard(state: web::Data<AppState>, req: HttpRequest) -> impl Res
         }
     }
 }
+
+fn portfolio_summary(state: &web::Data<AppState>) -> Option<PortfolioSummary> {
+    match portfolio::compute_change(&state.db, chrono::Duration::hours(24)) {
+        Ok(change) => change.map(|c| PortfolioSummary {
+            total_value_usd: c.end_value_usd,
+            snapshot_taken_at: c.to.taken_at.to_rfc3339(),
+            change_24h_usd: c.change_usd,
+            change_24h_pct: c.change_pct,
+        }),
+        Err(e) => {
+            log::warn!("Failed to load portfolio summary for dashboard: {}", e);
+            None
+        }
+    }
+}
+
+fn ai_usage_summary(state: &web::Data<AppState>) -> Option<AiUsageSummary> {
+    let today = chrono::Utc::now().format("%Y-%m-%d 00:00:00").to_string();
+    let month_ago = (chrono::Utc::now() - chrono::Duration::days(30))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    let result = state.db.get_ai_usage_totals(Some(&today)).and_then(|today| {
+        state.db.get_ai_usage_totals(Some(&month_ago)).map(|last_30_days| AiUsageSummary {
+            today,
+            last_30_days,
+        })
+    });
+    match result {
+        Ok(summary) => Some(summary),
+        Err(e) => {
+            log::warn!("Failed to load AI usage summary for dashboard: {}", e);
+            None
+        }
+    }
+}
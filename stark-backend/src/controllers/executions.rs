@@ -0,0 +1,310 @@
+//! Execution trace API endpoints
+//!
+//! Browse persisted executions (AI calls, tool calls, timings, token usage and
+//! x402 spend), export them as JSON or OpenTelemetry spans, and replay a
+//! recorded run offline against `MockAiClient` with the recorded tool results.
+
+use std::sync::Arc;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::ai::MockAiClient;
+use crate::channels::{MessageDispatcher, NormalizedMessage};
+use crate::execution::{trace, ExecutionTracker};
+use crate::AppState;
+
+/// Validate session token from request
+fn validate_session(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn error_response(status: actix_web::http::StatusCode, error: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "success": false,
+        "error": error.into()
+    }))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/executions")
+            .route("", web::get().to(list_executions))
+            .route("/{id}", web::get().to(get_execution))
+            .route("/{id}", web::delete().to(delete_execution))
+            .route("/{id}/export", web::get().to(export_execution))
+            .route("/{id}/replay", web::post().to(replay_execution)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListExecutionsQuery {
+    #[serde(default)]
+    session_id: Option<i64>,
+    #[serde(default)]
+    channel_id: Option<i64>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(default)]
+    offset: usize,
+}
+
+fn default_limit() -> usize {
+    50
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// "json" (default) or "otel"
+    #[serde(default)]
+    format: Option<String>,
+}
+
+/// GET /api/executions?session_id=&channel_id=&limit=&offset= - executions, newest first
+async fn list_executions(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<ListExecutionsQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let limit = query.limit.clamp(1, 200);
+    match state.db.list_execution_traces(query.session_id, query.channel_id, limit, query.offset) {
+        Ok(executions) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "executions": executions
+        })),
+        Err(e) => error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// GET /api/executions/{id} - one execution with all of its spans
+async fn get_execution(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+    let execution_id = path.into_inner();
+
+    match load_trace(&state, &execution_id) {
+        Ok((execution, spans)) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "execution": execution,
+            "spans": spans
+        })),
+        Err(resp) => resp,
+    }
+}
+
+/// DELETE /api/executions/{id} - delete an execution and its spans
+async fn delete_execution(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+    let execution_id = path.into_inner();
+
+    match state.db.delete_execution_trace(&execution_id) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => error_response(actix_web::http::StatusCode::NOT_FOUND, "Execution not found"),
+        Err(e) => error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// GET /api/executions/{id}/export?format=json|otel - download the trace
+async fn export_execution(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<ExportQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+    let execution_id = path.into_inner();
+
+    let (execution, spans) = match load_trace(&state, &execution_id) {
+        Ok(loaded) => loaded,
+        Err(resp) => return resp,
+    };
+
+    let (body, suffix) = match query.format.as_deref().unwrap_or("json") {
+        "json" => (trace::export_json(&execution, &spans), "trace"),
+        "otel" | "otlp" => (trace::export_otel(&execution, &spans), "otel"),
+        other => {
+            return error_response(
+                actix_web::http::StatusCode::BAD_REQUEST,
+                format!("Unknown export format '{}' (expected json or otel)", other),
+            );
+        }
+    };
+
+    HttpResponse::Ok()
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"execution-{}.{}.json\"", execution_id, suffix),
+        ))
+        .json(body)
+}
+
+/// POST /api/executions/{id}/replay - re-run a recorded execution offline.
+///
+/// The original user message is dispatched on a throwaway chat with the recorded
+/// AI responses (via `MockAiClient`) and recorded tool results, so no provider
+/// calls are made and no tools run. The replay is itself persisted as a new trace.
+async fn replay_execution(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+    let execution_id = path.into_inner();
+
+    let (original, spans) = match load_trace(&state, &execution_id) {
+        Ok(loaded) => loaded,
+        Err(resp) => return resp,
+    };
+    let Some(user_message) = original.user_message.clone() else {
+        return error_response(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "Execution has no recorded user message to replay",
+        );
+    };
+
+    let responses = trace::replay_responses(&spans);
+    if responses.is_empty() {
+        return error_response(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "Execution has no recorded AI calls to replay",
+        );
+    }
+    let recorded_ai_calls = responses.len();
+    let tool_results = trace::recorded_tool_results(&spans);
+    let recorded_tool_calls = tool_results.len();
+
+    // Dedicated tracker so the replay never touches the live channel's execution state
+    let tracker = Arc::new(ExecutionTracker::new(state.broadcaster.clone()).with_db(state.db.clone()));
+    let dispatcher = MessageDispatcher::new_with_wallet_and_skills(
+        state.db.clone(),
+        state.broadcaster.clone(),
+        state.tool_registry.clone(),
+        tracker,
+        None,
+        Some(state.skill_registry.clone()),
+    )
+    .with_mock_ai_client(MockAiClient::new(responses))
+    .with_replay_tool_results(tool_results);
+
+    let channel_type = state
+        .db
+        .get_channel(original.channel_id)
+        .ok()
+        .flatten()
+        .map(|c| c.channel_type)
+        .unwrap_or_else(|| "web".to_string());
+    let chat_id = format!("replay-{}-{}", execution_id, &uuid::Uuid::new_v4().to_string()[..8]);
+
+    log::info!(
+        "[EXECUTIONS] Replaying {} ({} AI calls, {} tool calls)",
+        execution_id,
+        recorded_ai_calls,
+        recorded_tool_calls
+    );
+
+    let result = dispatcher
+        .dispatch(NormalizedMessage {
+            channel_id: original.channel_id,
+            channel_type,
+            chat_id: chat_id.clone(),
+            user_id: chat_id.clone(),
+            user_name: "replay".to_string(),
+            text: user_message,
+            message_id: None,
+            session_mode: None,
+            selected_network: None,
+            force_safe_mode: false,
+        })
+        .await;
+
+    let replay = state
+        .db
+        .get_latest_execution_trace_for_chat(original.channel_id, &chat_id)
+        .ok()
+        .flatten();
+    let linked = replay
+        .as_ref()
+        .map(|r| state.db.set_execution_trace_replay_of(&r.execution_id, &execution_id));
+    if let Some(Err(e)) = linked {
+        log::warn!("[EXECUTIONS] Failed to link replay to {}: {}", execution_id, e);
+    }
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": result.error.is_none(),
+        "replay_execution_id": replay.as_ref().map(|r| r.execution_id.clone()),
+        "response": result.response,
+        "error": result.error,
+        "original_outcome": original.outcome,
+        "ai_calls": { "recorded": recorded_ai_calls, "replayed": replay.as_ref().map(|r| r.ai_calls) },
+        "tool_calls": { "recorded": recorded_tool_calls, "replayed": replay.as_ref().map(|r| r.tool_calls) }
+    }))
+}
+
+fn load_trace(
+    state: &web::Data<AppState>,
+    execution_id: &str,
+) -> Result<(crate::models::ExecutionTrace, Vec<crate::models::TraceSpan>), HttpResponse> {
+    let execution = match state.db.get_execution_trace(execution_id) {
+        Ok(Some(execution)) => execution,
+        Ok(None) => {
+            return Err(error_response(
+                actix_web::http::StatusCode::NOT_FOUND,
+                format!("Execution {} not found", execution_id),
+            ));
+        }
+        Err(e) => return Err(error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    };
+    let spans = state
+        .db
+        .list_execution_spans(execution_id)
+        .map_err(|e| error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok((execution, spans))
+}
@@ -0,0 +1,169 @@
+//! Hook API endpoints
+//!
+//! Lists registered lifecycle hooks (built-in and `config/hooks/*.ron`) with
+//! their stats, and enables/disables individual hooks per channel.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::AppState;
+
+/// Validate session token from request
+fn validate_session(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn error_response(status: actix_web::http::StatusCode, error: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "success": false,
+        "error": error.into()
+    }))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/hooks")
+            .route("", web::get().to(list_hooks))
+            .route("/{id}/channels/{channel_id}", web::put().to(set_channel_override))
+            .route("/{id}/channels/{channel_id}", web::delete().to(clear_channel_override)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChannelOverrideRequest {
+    enabled: bool,
+}
+
+/// GET /api/hooks - registered hooks with stats and per-channel overrides
+async fn list_hooks(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let manager = &state.hook_manager;
+    let mut hooks: Vec<serde_json::Value> = manager
+        .get_all_hooks()
+        .iter()
+        .map(|hook| {
+            let id = hook.id();
+            let channels: Vec<serde_json::Value> = manager
+                .channel_overrides(id)
+                .into_iter()
+                .map(|(channel_id, enabled)| {
+                    serde_json::json!({ "channel_id": channel_id, "enabled": enabled })
+                })
+                .collect();
+            serde_json::json!({
+                "id": id,
+                "name": hook.name(),
+                "description": hook.description(),
+                "events": hook.events(),
+                "priority": hook.priority(),
+                "timeout_secs": hook.timeout().as_secs(),
+                "enabled": manager.is_hook_enabled(id).unwrap_or(false),
+                "channels": channels,
+                "stats": manager.get_stats(id),
+            })
+        })
+        .collect();
+    hooks.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "hooks": hooks
+    }))
+}
+
+/// PUT /api/hooks/{id}/channels/{channel_id} - enable or disable a hook for one channel
+async fn set_channel_override(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, i64)>,
+    body: web::Json<ChannelOverrideRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+    let (hook_id, channel_id) = path.into_inner();
+
+    if state.hook_manager.is_hook_enabled(&hook_id).is_none() {
+        return error_response(actix_web::http::StatusCode::NOT_FOUND, format!("Hook '{}' not found", hook_id));
+    }
+    match state.db.get_channel(channel_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return error_response(actix_web::http::StatusCode::NOT_FOUND, format!("Channel {} not found", channel_id));
+        }
+        Err(e) => return error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+
+    if let Err(e) = state.db.set_hook_channel_override(&hook_id, channel_id, body.enabled) {
+        return error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    }
+    state.hook_manager.set_channel_enabled(&hook_id, channel_id, body.enabled);
+    log::info!(
+        "[HOOKS] Hook '{}' {} for channel {}",
+        hook_id,
+        if body.enabled { "enabled" } else { "disabled" },
+        channel_id
+    );
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "hook_id": hook_id,
+        "channel_id": channel_id,
+        "enabled": body.enabled
+    }))
+}
+
+/// DELETE /api/hooks/{id}/channels/{channel_id} - fall back to the hook's global setting
+async fn clear_channel_override(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<(String, i64)>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+    let (hook_id, channel_id) = path.into_inner();
+
+    match state.db.delete_hook_channel_override(&hook_id, channel_id) {
+        Ok(removed) => {
+            state.hook_manager.clear_channel_override(&hook_id, channel_id);
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "removed": removed
+            }))
+        }
+        Err(e) => error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
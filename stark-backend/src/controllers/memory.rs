@@ -6,7 +6,7 @@ use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
-use crate::qmd_memory::file_ops;
+use crate::qmd_memory::{consolidation, file_ops};
 use crate::AppState;
 
 /// Validate session token from request
@@ -143,6 +143,8 @@ struct MemoryInfoResponse {
     success: bool,
     memory_dir: String,
     exists: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    embeddings: Option<crate::qmd_memory::store::EmbeddingStatus>,
 }
 
 // ============================================================================
@@ -229,6 +231,10 @@ async fn list_files(data: web::Data<AppState>, req: HttpRequest) -> impl Respond
             ("long_term".to_string(), None)
         } else if let Some(d) = file_ops::parse_date_from_filename(&name) {
             ("daily_log".to_string(), Some(d.format("%Y-%m-%d").to_string()))
+        } else if let Some(d) = file_ops::parse_week_from_filename(&name) {
+            ("weekly_digest".to_string(), Some(d.format("%Y-%m-%d").to_string()))
+        } else if let Some(d) = file_ops::parse_month_from_filename(&name) {
+            ("monthly_digest".to_string(), Some(d.format("%Y-%m-%d").to_string()))
         } else {
             ("unknown".to_string(), None)
         };
@@ -360,7 +366,7 @@ async fn read_file(
     })
 }
 
-/// GET /api/memory/search - Search memories with BM25 full-text search
+/// GET /api/memory/search - Search memories (BM25, plus vectors when embeddings are configured)
 async fn search(
     data: web::Data<AppState>,
     req: HttpRequest,
@@ -384,7 +390,7 @@ async fn search(
 
     let limit = query.limit.clamp(1, 100);
 
-    match memory_store.search(&query.query, limit) {
+    match memory_store.hybrid_search(&query.query, limit).await {
         Ok(results) => {
             let results: Vec<SearchResult> = results
                 .into_iter()
@@ -745,6 +751,42 @@ async fn reindex(data: web::Data<AppState>, req: HttpRequest) -> impl Responder
     }
 }
 
+/// POST /api/memory/consolidate - Run memory consolidation now
+async fn consolidate(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session_from_request(&data, &req) {
+        return resp;
+    }
+
+    let memory_store = match data.dispatcher.memory_store() {
+        Some(store) => store,
+        None => {
+            return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "success": false,
+                "error": "Memory system not initialized"
+            }));
+        }
+    };
+
+    let result = match consolidation::ai_client(&data.db, data.wallet_provider.clone()) {
+        Ok(client) => {
+            let policy = consolidation::ConsolidationPolicy::from_config();
+            consolidation::consolidate(&memory_store, &client, Some(data.db.as_ref()), chrono::Local::now().date_naive(), &policy).await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(report) => HttpResponse::Ok().json(serde_json::json!({
+            "success": report.errors.is_empty(),
+            "report": report
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": e
+        })),
+    }
+}
+
 /// GET /api/memory/info - Get memory system info
 async fn memory_info(data: web::Data<AppState>, req: HttpRequest) -> impl Responder {
     if let Err(resp) = validate_session_from_request(&data, &req) {
@@ -760,12 +802,14 @@ async fn memory_info(data: web::Data<AppState>, req: HttpRequest) -> impl Respon
                 success: true,
                 memory_dir: memory_dir.to_string_lossy().to_string(),
                 exists: memory_dir.exists(),
+                embeddings: store.embedding_status().ok(),
             })
         }
         None => HttpResponse::Ok().json(MemoryInfoResponse {
             success: true,
             memory_dir: "Not configured".to_string(),
             exists: false,
+            embeddings: None,
         }),
     }
 }
@@ -783,6 +827,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
             .route("/long-term", web::post().to(append_long_term))
             .route("/stats", web::get().to(get_stats))
             .route("/reindex", web::post().to(reindex))
+            .route("/consolidate", web::post().to(consolidate))
             .route("/info", web::get().to(memory_info)),
     );
 }
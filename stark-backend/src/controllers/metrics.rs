@@ -0,0 +1,99 @@
+//! Prometheus metrics endpoint
+//!
+//! `GET /metrics` renders the counters and histograms recorded in `crate::metrics`
+//! plus gauges sampled at scrape time (tx queue depth, channel listener health,
+//! active executions). When `STARK_METRICS_TOKEN` is set, scrapers must send it
+//! as a bearer token; otherwise the endpoint is open like `/api/health`.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+
+use crate::metrics::{render_gauge, METRICS};
+use crate::tx_queue::QueuedTxStatus;
+use crate::AppState;
+
+const ALL_TX_STATUSES: [QueuedTxStatus; 6] = [
+    QueuedTxStatus::Pending,
+    QueuedTxStatus::Broadcasting,
+    QueuedTxStatus::Broadcast,
+    QueuedTxStatus::Confirmed,
+    QueuedTxStatus::Failed,
+    QueuedTxStatus::Expired,
+];
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(web::resource("/metrics").route(web::get().to(get_metrics)));
+}
+
+fn is_authorized(req: &HttpRequest) -> bool {
+    let Some(expected) = crate::config::metrics_token() else {
+        return true;
+    };
+    req.headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(|token| token == expected)
+        .unwrap_or(false)
+}
+
+async fn get_metrics(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if !is_authorized(&req) {
+        return HttpResponse::Unauthorized().body("Invalid or missing metrics token\n");
+    }
+
+    let mut out = METRICS.render();
+
+    let queued = state.tx_queue.list_all();
+    let depth: Vec<(Vec<String>, f64)> = ALL_TX_STATUSES
+        .iter()
+        .map(|status| {
+            let count = queued.iter().filter(|tx| tx.status == *status).count();
+            (vec![status.to_string()], count as f64)
+        })
+        .collect();
+    render_gauge(
+        &mut out,
+        "starkbot_tx_queue_depth",
+        "Transactions in the tx queue by status",
+        &["status"],
+        &depth,
+    );
+
+    let channels: Vec<(Vec<String>, f64)> = state
+        .db
+        .list_enabled_channels()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| {
+            let up = state.channel_manager.is_running(c.id);
+            (vec![c.id.to_string(), c.channel_type, c.name], if up { 1.0 } else { 0.0 })
+        })
+        .collect();
+    render_gauge(
+        &mut out,
+        "starkbot_channel_up",
+        "Whether the listener of an enabled channel is running (1) or not (0)",
+        &["channel_id", "channel_type", "name"],
+        &channels,
+    );
+
+    render_gauge(
+        &mut out,
+        "starkbot_active_executions",
+        "Agent executions currently in progress",
+        &[],
+        &[(vec![], state.execution_tracker.active_execution_count() as f64)],
+    );
+
+    render_gauge(
+        &mut out,
+        "starkbot_build_info",
+        "Backend version",
+        &["version"],
+        &[(vec![env!("CARGO_PKG_VERSION").to_string()], 1.0)],
+    );
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(out)
+}
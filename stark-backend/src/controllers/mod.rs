@@ -1,24 +1,32 @@
+pub mod address_book;
 pub mod agent_settings;
 pub mod api_keys;
 pub mod auth;
 pub mod broadcasted_transactions;
 pub mod channels;
 pub mod chat;
+pub mod config_reload;
 pub mod cron;
 pub mod dashboard;
 pub mod dev_chat;
 pub mod eip8004;
+pub mod executions;
 pub mod files;
 pub mod gmail;
 pub mod health;
+pub mod hooks;
 pub mod identity;
 pub mod intrinsic;
 pub mod journal;
 pub mod memory;
+pub mod metrics;
 pub mod mindmap;
+pub mod operators;
 pub mod payments;
+pub mod portfolio;
 pub mod sessions;
 pub mod skills;
 pub mod tools;
 pub mod tx_queue;
+pub mod usage;
 pub mod well_known;
@@ -0,0 +1,186 @@
+//! Operator account and audit log API endpoints
+//!
+//! Admins manage which wallet addresses may log in and with what role
+//! (viewer, operator, treasurer, admin). Role checks themselves happen in
+//! `middleware::session_auth::role_guard`, which also fills the audit log.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::models::UpsertOperatorRequest;
+use crate::AppState;
+
+/// Validate session token from request
+fn validate_session(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn error_response(status: actix_web::http::StatusCode, error: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "success": false,
+        "error": error.into()
+    }))
+}
+
+fn is_valid_address(address: &str) -> bool {
+    address.starts_with("0x")
+        && address.len() == 42
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/operators")
+            .route("", web::get().to(list_operators))
+            .route("", web::post().to(upsert_operator))
+            .route("/{address}", web::delete().to(delete_operator)),
+    );
+    cfg.service(web::resource("/api/audit-log").route(web::get().to(list_audit_log)));
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQuery {
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(default)]
+    offset: usize,
+}
+
+fn default_limit() -> usize {
+    100
+}
+
+/// GET /api/operators - all operator accounts, plus the configured admin address
+async fn list_operators(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    match state.db.list_operators() {
+        Ok(operators) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "admin_address": state.config.login_admin_public_address.as_ref().map(|a| a.to_lowercase()),
+            "operators": operators
+        })),
+        Err(e) => error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// POST /api/operators - add an operator or change its role/label
+async fn upsert_operator(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<UpsertOperatorRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let address = body.public_address.trim().to_lowercase();
+    if !is_valid_address(&address) {
+        return error_response(actix_web::http::StatusCode::BAD_REQUEST, "Invalid public address");
+    }
+    let is_configured_admin = state
+        .config
+        .login_admin_public_address
+        .as_ref()
+        .map(|a| a.to_lowercase() == address)
+        .unwrap_or(false);
+    if is_configured_admin {
+        return error_response(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "The LOGIN_ADMIN_PUBLIC_ADDRESS account is always admin and cannot be changed here",
+        );
+    }
+
+    let label = body.label.as_deref().map(str::trim).filter(|l| !l.is_empty());
+    match state.db.upsert_operator(&address, body.role, label) {
+        Ok(operator) => {
+            log::info!("[OPERATORS] {} set to role {}", address, operator.role);
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "operator": operator
+            }))
+        }
+        Err(e) => error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// DELETE /api/operators/{address} - revoke access and end the operator's sessions
+async fn delete_operator(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+    let address = path.into_inner().to_lowercase();
+
+    match state.db.delete_operator(&address) {
+        Ok(true) => {
+            let sessions = state.db.delete_sessions_for_address(&address).unwrap_or(0);
+            log::info!("[OPERATORS] Removed {} ({} sessions ended)", address, sessions);
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "sessions_ended": sessions
+            }))
+        }
+        Ok(false) => error_response(
+            actix_web::http::StatusCode::NOT_FOUND,
+            format!("Operator {} not found", address),
+        ),
+        Err(e) => error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// GET /api/audit-log?address=&limit=&offset= - operator actions, newest first
+async fn list_audit_log(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<AuditLogQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let limit = query.limit.clamp(1, 500);
+    match state.db.list_audit_log(query.address.as_deref(), limit, query.offset) {
+        Ok(entries) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "entries": entries
+        })),
+        Err(e) => error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
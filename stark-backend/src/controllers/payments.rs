@@ -3,6 +3,7 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 
+use crate::x402::budget::{self, AiBudget};
 use crate::AppState;
 
 #[derive(Debug, Serialize)]
@@ -40,6 +41,23 @@ struct PaymentSummary {
     payments_without_feedback: i64,
 }
 
+/// Budget limits in USDC (null = no limit)
+#[derive(Debug, Deserialize)]
+pub struct UpdateBudgetRequest {
+    #[serde(default)]
+    daily_limit_usdc: Option<f64>,
+    #[serde(default)]
+    session_limit_usdc: Option<f64>,
+    #[serde(default)]
+    cron_job_limit_usdc: Option<f64>,
+    #[serde(default = "default_true")]
+    fallback_enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PaymentListQuery {
     channel_id: Option<i64>,
@@ -52,6 +70,8 @@ pub fn config(cfg: &mut web::ServiceConfig) {
         web::scope("/api/payments")
             .route("", web::get().to(list_payments))
             .route("/summary", web::get().to(get_summary))
+            .route("/budget", web::get().to(get_budget))
+            .route("/budget", web::put().to(update_budget))
             .route("/{id}", web::get().to(get_payment))
     );
 }
@@ -194,6 +214,112 @@ async fn get_summary(
     }))
 }
 
+/// AI spend budget, today's spend and the active endpoint's per-call cost
+async fn get_budget(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(resp) = validate_auth(&state, &req) {
+        return resp;
+    }
+
+    let ai_budget = match state.db.get_ai_budget() {
+        Ok(b) => b,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": format!("Database error: {}", e)
+            }));
+        }
+    };
+    let spend = state.db.get_ai_spend(None, None).unwrap_or_default();
+    let cron_jobs: Vec<serde_json::Value> = state
+        .db
+        .list_cron_job_ai_spend_today()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(job_id, spent)| {
+            serde_json::json!({
+                "cron_job_id": job_id,
+                "spent_usdc": budget::format_usdc(spent),
+                "remaining_usdc": ai_budget.cron_job_limit.map(|l| budget::format_usdc(l.saturating_sub(spent))),
+            })
+        })
+        .collect();
+    let endpoint = state
+        .db
+        .get_active_agent_settings()
+        .ok()
+        .flatten()
+        .map(|s| s.endpoint)
+        .filter(|e| crate::x402::is_x402_endpoint(e));
+    let endpoint_cost = endpoint.as_deref().and_then(budget::preset_cost);
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "budget": budget_json(&ai_budget),
+        "spent_today_usdc": budget::format_usdc(spend.today),
+        "remaining_today_usdc": ai_budget.daily_limit.map(|l| budget::format_usdc(l.saturating_sub(spend.today))),
+        "cron_jobs": cron_jobs,
+        "endpoint": endpoint,
+        "endpoint_cost_usdc": endpoint_cost.map(budget::format_usdc),
+    }))
+}
+
+/// Replace the AI spend limits
+async fn update_budget(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<UpdateBudgetRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_auth(&state, &req) {
+        return resp;
+    }
+
+    let parse = |usdc: Option<f64>| usdc.map(budget::parse_usdc).transpose();
+    let ai_budget = match (
+        parse(body.daily_limit_usdc),
+        parse(body.session_limit_usdc),
+        parse(body.cron_job_limit_usdc),
+    ) {
+        (Ok(daily_limit), Ok(session_limit), Ok(cron_job_limit)) => AiBudget {
+            daily_limit,
+            session_limit,
+            cron_job_limit,
+            fallback_enabled: body.fallback_enabled,
+        },
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "error": e
+            }));
+        }
+    };
+
+    match state.db.save_ai_budget(&ai_budget) {
+        Ok(()) => {
+            log::info!("[BUDGET] Updated AI spend budget: {:?}", ai_budget);
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "budget": budget_json(&ai_budget)
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "success": false,
+            "error": format!("Database error: {}", e)
+        })),
+    }
+}
+
+fn budget_json(ai_budget: &AiBudget) -> serde_json::Value {
+    serde_json::json!({
+        "daily_limit_usdc": ai_budget.daily_limit.map(budget::format_usdc),
+        "session_limit_usdc": ai_budget.session_limit.map(budget::format_usdc),
+        "cron_job_limit_usdc": ai_budget.cron_job_limit.map(budget::format_usdc),
+        "fallback_enabled": ai_budget.fallback_enabled,
+    })
+}
+
 /// Get single payment
 async fn get_payment(
     state: web::Data<AppState>,
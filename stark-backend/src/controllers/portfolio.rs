@@ -0,0 +1,349 @@
+//! Portfolio API endpoints
+//!
+//! Holdings, P&L and value history from stored portfolio snapshots, plus
+//! management of the extra wallets/Safes included in snapshots.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::portfolio;
+use crate::AppState;
+
+/// Validate session token from request
+fn validate_session(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn error_response(status: actix_web::http::StatusCode, error: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "success": false,
+        "error": error.into()
+    }))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/portfolio")
+            .route("", web::get().to(get_holdings))
+            .route("/pnl", web::get().to(get_pnl))
+            .route("/history", web::get().to(get_history))
+            .route("/snapshot", web::post().to(take_snapshot))
+            .route("/wallets", web::get().to(list_wallets))
+            .route("/wallets", web::post().to(add_wallet))
+            .route("/wallets/{id}", web::put().to(update_wallet))
+            .route("/wallets/{id}", web::delete().to(delete_wallet)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PeriodQuery {
+    period: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddWalletRequest {
+    label: String,
+    address: String,
+    #[serde(default = "default_wallet_kind")]
+    kind: String,
+}
+
+fn default_wallet_kind() -> String {
+    "wallet".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWalletRequest {
+    enabled: bool,
+}
+
+/// Latest snapshot with holdings aggregated across wallets and the raw per-wallet balances
+async fn get_holdings(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let snapshot = match state.db.get_latest_portfolio_snapshot() {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "snapshot": null,
+                "holdings": [],
+                "balances": []
+            }));
+        }
+        Err(e) => {
+            log::error!("Failed to load portfolio snapshot: {}", e);
+            return error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load portfolio",
+            );
+        }
+    };
+
+    match state.db.get_portfolio_balances(snapshot.id) {
+        Ok(balances) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "holdings": portfolio::aggregate_holdings(&balances),
+            "balances": balances,
+            "snapshot": snapshot,
+        })),
+        Err(e) => {
+            log::error!("Failed to load portfolio balances: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load portfolio",
+            )
+        }
+    }
+}
+
+/// Value change over a period (default 24h)
+async fn get_pnl(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<PeriodQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let period_str = query.period.as_deref().unwrap_or("24h");
+    let period = match portfolio::parse_period(period_str) {
+        Some(p) => p,
+        None => {
+            return error_response(
+                actix_web::http::StatusCode::BAD_REQUEST,
+                format!("Invalid period '{}'. Use e.g. 24h, 7d, 30d, week, month", period_str),
+            );
+        }
+    };
+
+    match portfolio::compute_change(&state.db, period) {
+        Ok(change) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "period": period_str,
+            "pnl": change,
+        })),
+        Err(e) => {
+            log::error!("Failed to compute portfolio P&L: {}", e);
+            error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e)
+        }
+    }
+}
+
+/// Total value over time (default 30d)
+async fn get_history(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<PeriodQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let period_str = query.period.as_deref().unwrap_or("30d");
+    let period = match portfolio::parse_period(period_str) {
+        Some(p) => p,
+        None => {
+            return error_response(
+                actix_web::http::StatusCode::BAD_REQUEST,
+                format!("Invalid period '{}'. Use e.g. 24h, 7d, 30d, week, month", period_str),
+            );
+        }
+    };
+    let limit = query.limit.unwrap_or(500).min(5000);
+
+    match state.db.list_portfolio_snapshots(Utc::now() - period, limit) {
+        Ok(snapshots) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "period": period_str,
+            "snapshots": snapshots,
+        })),
+        Err(e) => {
+            log::error!("Failed to list portfolio snapshots: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load portfolio history",
+            )
+        }
+    }
+}
+
+/// Take a snapshot now
+async fn take_snapshot(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let wallet_provider = match &state.wallet_provider {
+        Some(wp) => wp.clone(),
+        None => {
+            return error_response(
+                actix_web::http::StatusCode::SERVICE_UNAVAILABLE,
+                "No wallet configured",
+            );
+        }
+    };
+
+    match portfolio::take_snapshot(&state.db, &wallet_provider).await {
+        Ok(snapshot) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "snapshot": snapshot,
+        })),
+        Err(e) => {
+            log::error!("Portfolio snapshot failed: {}", e);
+            error_response(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR, e)
+        }
+    }
+}
+
+async fn list_wallets(state: web::Data<AppState>, req: HttpRequest) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let bot_wallet = state.wallet_provider.as_ref().map(|wp| wp.get_address());
+
+    match state.db.list_portfolio_wallets() {
+        Ok(wallets) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "bot_wallet": bot_wallet,
+            "wallets": wallets,
+        })),
+        Err(e) => {
+            log::error!("Failed to list portfolio wallets: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to list wallets",
+            )
+        }
+    }
+}
+
+async fn add_wallet(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    body: web::Json<AddWalletRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let address = body.address.trim();
+    if address.parse::<ethers::types::Address>().is_err() {
+        return error_response(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            format!("Invalid address '{}'", address),
+        );
+    }
+    if body.kind != "wallet" && body.kind != "safe" {
+        return error_response(
+            actix_web::http::StatusCode::BAD_REQUEST,
+            "kind must be 'wallet' or 'safe'",
+        );
+    }
+    let label = body.label.trim();
+    if label.is_empty() {
+        return error_response(actix_web::http::StatusCode::BAD_REQUEST, "label is required");
+    }
+
+    match state.db.add_portfolio_wallet(label, address, &body.kind) {
+        Ok(wallet) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "wallet": wallet,
+        })),
+        Err(rusqlite::Error::SqliteFailure(err, _))
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            error_response(
+                actix_web::http::StatusCode::CONFLICT,
+                "Address is already tracked",
+            )
+        }
+        Err(e) => {
+            log::error!("Failed to add portfolio wallet: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to add wallet",
+            )
+        }
+    }
+}
+
+async fn update_wallet(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+    body: web::Json<UpdateWalletRequest>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    match state.db.set_portfolio_wallet_enabled(path.into_inner(), body.enabled) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => error_response(actix_web::http::StatusCode::NOT_FOUND, "Wallet not found"),
+        Err(e) => {
+            log::error!("Failed to update portfolio wallet: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to update wallet",
+            )
+        }
+    }
+}
+
+async fn delete_wallet(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    match state.db.delete_portfolio_wallet(path.into_inner()) {
+        Ok(true) => HttpResponse::Ok().json(serde_json::json!({ "success": true })),
+        Ok(false) => error_response(actix_web::http::StatusCode::NOT_FOUND, "Wallet not found"),
+        Err(e) => {
+            log::error!("Failed to delete portfolio wallet: {}", e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to delete wallet",
+            )
+        }
+    }
+}
@@ -168,6 +168,7 @@ async fn reset_session(
 
     match data.db.reset_chat_session(session_id) {
         Ok(session) => {
+            data.dispatcher.fire_session_end(session.channel_id, session_id, "reset").await;
             let response: ChatSessionResponse = session.into();
             HttpResponse::Ok().json(response)
         }
@@ -315,11 +316,14 @@ async fn delete_session(
 
     // Now delete the session
     match data.db.delete_chat_session(session_id) {
-        Ok(true) => HttpResponse::Ok().json(serde_json::json!({
-            "success": true,
-            "message": "Session deleted",
-            "cancelled_agents": cancelled_agents
-        })),
+        Ok(true) => {
+            data.dispatcher.fire_session_end(channel_id, session_id, "deleted").await;
+            HttpResponse::Ok().json(serde_json::json!({
+                "success": true,
+                "message": "Session deleted",
+                "cancelled_agents": cancelled_agents
+            }))
+        }
         Ok(false) => HttpResponse::NotFound().json(serde_json::json!({
             "error": "Session not found"
         })),
@@ -3,7 +3,7 @@ use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 
-use crate::skills::{DbSkillScript, Skill};
+use crate::skills::{DbSkillResource, DbSkillScript, Skill, SkillResourceKind};
 use crate::AppState;
 
 #[derive(Serialize)]
@@ -73,6 +73,9 @@ pub struct SkillDetail {
     pub prompt_template: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scripts: Option<Vec<ScriptInfo>>,
+    /// ABIs, web3 presets and validators bundled with the skill
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Vec<ResourceInfo>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub homepage: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -102,6 +105,21 @@ impl From<&DbSkillScript> for ScriptInfo {
     }
 }
 
+#[derive(Serialize)]
+pub struct ResourceInfo {
+    pub kind: SkillResourceKind,
+    pub name: String,
+}
+
+impl From<&DbSkillResource> for ResourceInfo {
+    fn from(resource: &DbSkillResource) -> Self {
+        ResourceInfo {
+            kind: resource.kind,
+            name: resource.name.clone(),
+        }
+    }
+}
+
 impl From<&Skill> for SkillDetail {
     fn from(skill: &Skill) -> Self {
         let missing_binaries = skill.check_binaries().err().unwrap_or_default();
@@ -132,6 +150,7 @@ impl From<&Skill> for SkillDetail {
             arguments,
             prompt_template: skill.prompt_template.clone(),
             scripts: None,
+            resources: None,
             homepage: skill.metadata.homepage.clone(),
             metadata: skill.metadata.metadata.clone(),
         }
@@ -269,6 +288,11 @@ async fn get_skill(
                 detail.scripts = Some(scripts.iter().map(|s| s.into()).collect());
             }
 
+            let resources = state.skill_registry.get_skill_resources(&name);
+            if !resources.is_empty() {
+                detail.resources = Some(resources.iter().map(|r| r.into()).collect());
+            }
+
             HttpResponse::Ok().json(SkillDetailResponse {
                 success: true,
                 skill: Some(detail),
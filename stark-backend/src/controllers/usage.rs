@@ -0,0 +1,170 @@
+//! AI usage API endpoints
+//!
+//! Token counts and estimated cost of AI calls, in total or grouped by session, channel,
+//! cron job, model, archetype or day.
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::db::tables::ai_usage::AiUsageGroupBy;
+use crate::AppState;
+
+/// Longest window a usage report covers; larger `days` values are clamped to it
+const MAX_REPORT_DAYS: i64 = 3650;
+
+/// Validate session token from request
+fn validate_session(state: &web::Data<AppState>, req: &HttpRequest) -> Result<(), HttpResponse> {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.trim_start_matches("Bearer ").to_string());
+
+    let token = match token {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::Unauthorized().json(serde_json::json!({
+                "success": false,
+                "error": "No authorization token provided"
+            })));
+        }
+    };
+
+    match state.db.validate_session(&token) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(HttpResponse::Unauthorized().json(serde_json::json!({
+            "success": false,
+            "error": "Invalid or expired session"
+        }))),
+        Err(e) => {
+            log::error!("Failed to validate session: {}", e);
+            Err(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": "Internal server error"
+            })))
+        }
+    }
+}
+
+fn error_response(status: actix_web::http::StatusCode, error: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status).json(serde_json::json!({
+        "success": false,
+        "error": error.into()
+    }))
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/usage")
+            .route("", web::get().to(get_usage))
+            .route("/sessions/{id}", web::get().to(get_session_usage)),
+    );
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// session, channel, cron_job, model, archetype or day (totals only when absent)
+    group_by: Option<String>,
+    /// Only count calls from the last N days (all time when absent)
+    days: Option<i64>,
+    limit: Option<usize>,
+}
+
+/// Usage totals for a period, optionally broken down by a grouping column
+async fn get_usage(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    query: web::Query<UsageQuery>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let group_by = match query.group_by.as_deref() {
+        None => None,
+        Some(g) => match AiUsageGroupBy::from_str(g) {
+            Some(group_by) => Some(group_by),
+            None => {
+                return error_response(
+                    actix_web::http::StatusCode::BAD_REQUEST,
+                    format!(
+                        "Invalid group_by '{}' (expected session, channel, cron_job, model, archetype or day)",
+                        g
+                    ),
+                );
+            }
+        },
+    };
+    if query.days.is_some_and(|d| d <= 0) {
+        return error_response(actix_web::http::StatusCode::BAD_REQUEST, "days must be positive");
+    }
+    let since = query.days.map(|days| {
+        (Utc::now() - chrono::Duration::days(days.min(MAX_REPORT_DAYS)))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    });
+
+    let totals = match state.db.get_ai_usage_totals(since.as_deref()) {
+        Ok(totals) => totals,
+        Err(e) => {
+            log::error!("Failed to load AI usage totals: {}", e);
+            return error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load AI usage",
+            );
+        }
+    };
+
+    let groups = match group_by {
+        Some(group_by) => {
+            let limit = query.limit.unwrap_or(50).min(500);
+            match state.db.list_ai_usage_grouped(group_by, since.as_deref(), limit) {
+                Ok(groups) => Some(groups),
+                Err(e) => {
+                    log::error!("Failed to load grouped AI usage: {}", e);
+                    return error_response(
+                        actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                        "Failed to load AI usage",
+                    );
+                }
+            }
+        }
+        None => None,
+    };
+
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "since": since,
+        "totals": totals,
+        "group_by": query.group_by,
+        "groups": groups
+    }))
+}
+
+/// Usage of a single chat session
+async fn get_session_usage(
+    state: web::Data<AppState>,
+    req: HttpRequest,
+    path: web::Path<i64>,
+) -> impl Responder {
+    if let Err(resp) = validate_session(&state, &req) {
+        return resp;
+    }
+
+    let session_id = path.into_inner();
+    match state.db.get_session_ai_usage(session_id) {
+        Ok(totals) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "session_id": session_id,
+            "totals": totals
+        })),
+        Err(e) => {
+            log::error!("Failed to load AI usage for session {}: {}", session_id, e);
+            error_response(
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load AI usage",
+            )
+        }
+    }
+}
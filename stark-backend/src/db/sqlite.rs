@@ -94,6 +94,8 @@ impl Database {
             )",
             [],
         )?;
+        // Sessions created by the Flash control plane (`/auth/flash`)
+        let _ = conn.execute("ALTER TABLE auth_sessions ADD COLUMN flash INTEGER NOT NULL DEFAULT 0", []);
 
         // Auth challenges table for SIWE
         conn.execute(
@@ -106,6 +108,40 @@ impl Database {
             [],
         )?;
 
+        // Operators allowed to log in, with their role (the configured
+        // LOGIN_ADMIN_PUBLIC_ADDRESS is always an implicit admin)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS operators (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                public_address TEXT UNIQUE NOT NULL,
+                role TEXT NOT NULL,
+                label TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Audit log of operator actions (HTTP mutations and gateway calls)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS operator_audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                public_address TEXT,
+                role TEXT,
+                source TEXT NOT NULL,
+                action TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                detail TEXT,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_operator_audit_log_created ON operator_audit_log(created_at)",
+            [],
+        )?;
+
         // External API keys table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS external_api_keys (
@@ -243,6 +279,13 @@ impl Database {
             conn.execute("ALTER TABLE agent_settings ADD COLUMN secret_key TEXT", [])?;
         }
 
+        // Migration: per-agent model selection (new column names, the legacy `model`/`provider`
+        // columns may still hold stale values on old DBs)
+        let _ = conn.execute("ALTER TABLE agent_settings ADD COLUMN model_name TEXT", []);
+        let _ = conn.execute("ALTER TABLE agent_settings ADD COLUMN provider_kind TEXT NOT NULL DEFAULT 'auto'", []);
+        let _ = conn.execute("ALTER TABLE agent_settings ADD COLUMN temperature REAL", []);
+        let _ = conn.execute("ALTER TABLE agent_settings ADD COLUMN top_p REAL", []);
+
         // Migration: Add web3_tx_requires_confirmation column to bot_settings if it doesn't exist
         let has_web3_tx_confirmation: bool = conn
             .query_row(
@@ -686,6 +729,21 @@ impl Database {
             [],
         )?;
 
+        // Skill resources table (ABIs, web3 presets and validators bundled with skills)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS skill_resources (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                skill_id INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (skill_id) REFERENCES skills(id) ON DELETE CASCADE,
+                UNIQUE(skill_id, kind, name)
+            )",
+            [],
+        )?;
+
         // Tool execution audit log
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tool_executions (
@@ -764,6 +822,87 @@ impl Database {
             [],
         )?;
 
+        // Event trigger state per cron job (last seen block, price side, seen Safe txs)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cron_trigger_state (
+                job_id INTEGER PRIMARY KEY,
+                state TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (job_id) REFERENCES cron_jobs(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Persisted execution traces (one row per agent run)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS execution_traces (
+                execution_id TEXT PRIMARY KEY,
+                channel_id INTEGER NOT NULL,
+                session_id INTEGER,
+                chat_id TEXT,
+                mode TEXT NOT NULL,
+                user_message TEXT,
+                status TEXT NOT NULL DEFAULT 'running',
+                outcome TEXT,
+                replay_of TEXT,
+                started_at TEXT NOT NULL,
+                completed_at TEXT,
+                duration_ms INTEGER,
+                ai_calls INTEGER NOT NULL DEFAULT 0,
+                tool_calls INTEGER NOT NULL DEFAULT 0,
+                tokens_used INTEGER NOT NULL DEFAULT 0,
+                x402_spend REAL NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_execution_traces_session ON execution_traces(session_id, started_at)",
+            [],
+        )?;
+
+        // Steps within an execution trace (AI calls, tool calls, tracker tasks)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS execution_spans (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                span_id TEXT NOT NULL,
+                execution_id TEXT NOT NULL,
+                parent_span_id TEXT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                description TEXT,
+                success INTEGER NOT NULL DEFAULT 1,
+                error TEXT,
+                input TEXT,
+                output TEXT,
+                started_at TEXT NOT NULL,
+                completed_at TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL DEFAULT 0,
+                tokens INTEGER NOT NULL DEFAULT 0,
+                x402_amount TEXT,
+                x402_asset TEXT,
+                FOREIGN KEY (execution_id) REFERENCES execution_traces(execution_id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_execution_spans_execution ON execution_spans(execution_id, id)",
+            [],
+        )?;
+
+        // Per-channel enable/disable overrides for lifecycle hooks
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS hook_channel_overrides (
+                hook_id TEXT NOT NULL,
+                channel_id INTEGER NOT NULL,
+                enabled INTEGER NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (hook_id, channel_id)
+            )",
+            [],
+        )?;
+
         // Heartbeat configuration table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS heartbeat_configs (
@@ -858,6 +997,64 @@ impl Database {
             [],
         )?;
 
+        // Migration: cron job that made an x402 payment (for per-job AI budgets)
+        let _ = conn.execute("ALTER TABLE x402_payments ADD COLUMN cron_job_id TEXT", []);
+
+        // Migration: what a payment was for ('ai' calls count against AI budgets, 'tool'
+        // covers tool and RPC payments). Earlier rows were only written for AI calls.
+        if conn
+            .execute("ALTER TABLE x402_payments ADD COLUMN kind TEXT NOT NULL DEFAULT 'tool'", [])
+            .is_ok()
+        {
+            let _ = conn.execute("UPDATE x402_payments SET kind = 'ai' WHERE tool_name IS NULL", []);
+        }
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_x402_payments_created ON x402_payments(created_at)",
+            [],
+        )?;
+
+        // AI spend limits in micro-USDC (single row, NULL = no limit)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ai_budget (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                daily_limit INTEGER,
+                session_limit INTEGER,
+                cron_job_limit INTEGER,
+                fallback_enabled INTEGER NOT NULL DEFAULT 1,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+
+        // Token usage and estimated cost of every AI call
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ai_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                channel_id INTEGER,
+                session_id INTEGER,
+                cron_job_id TEXT,
+                archetype TEXT NOT NULL,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                estimated INTEGER NOT NULL DEFAULT 0,
+                cost_usd REAL NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ai_usage_created ON ai_usage(created_at)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_ai_usage_session ON ai_usage(session_id)",
+            [],
+        )?;
+
         // Migration: Add status column to x402_payments if it doesn't exist
         let _ = conn.execute(
             "ALTER TABLE x402_payments ADD COLUMN status TEXT NOT NULL DEFAULT 'pending'",
@@ -1211,13 +1408,205 @@ impl Database {
             [],
         );
 
+        // Address book - labelled contacts (trust level decides verify_intent handling)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS address_book (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                label TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                address TEXT NOT NULL UNIQUE,
+                ens_name TEXT,
+                networks TEXT NOT NULL DEFAULT '[]',
+                notes TEXT,
+                trust_level TEXT NOT NULL DEFAULT 'known',
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Batch payouts - one row per batch, recipients stored as JSON with per-recipient status
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS payout_batches (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                network TEXT NOT NULL,
+                token_symbol TEXT NOT NULL,
+                token_address TEXT NOT NULL,
+                decimals INTEGER NOT NULL,
+                source_address TEXT NOT NULL,
+                method TEXT NOT NULL,
+                total_raw TEXT NOT NULL,
+                total_usd REAL,
+                recipients TEXT NOT NULL,
+                tx_uuid TEXT,
+                safe_tx_hash TEXT,
+                status TEXT NOT NULL,
+                channel_id INTEGER,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Token approvals granted by the bot wallet and tracked Safes (from Approval events)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS token_approvals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                network TEXT NOT NULL,
+                owner TEXT NOT NULL,
+                token TEXT NOT NULL,
+                spender TEXT NOT NULL,
+                token_symbol TEXT,
+                decimals INTEGER,
+                allowance TEXT NOT NULL DEFAULT '0',
+                last_event_block INTEGER NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(network, owner, token, spender)
+            )",
+            [],
+        )?;
+
+        // Last block scanned for Approval events, per network and owner
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS approval_scan_cursors (
+                network TEXT NOT NULL,
+                owner TEXT NOT NULL,
+                last_block INTEGER NOT NULL,
+                scanned_at TEXT NOT NULL,
+                PRIMARY KEY (network, owner)
+            )",
+            [],
+        )?;
+
+        // When the last approval scan started, successful or not (paces the scheduler)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS approval_scan_state (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                last_attempt_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Repays queued by Aave health-factor watchers (daily limit)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS aave_auto_repays (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                network TEXT NOT NULL,
+                wallet TEXT NOT NULL,
+                asset TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                amount_usd REAL NOT NULL,
+                tx_uuid TEXT NOT NULL,
+                queued_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Portfolio tracking - extra wallets/Safes to include alongside the bot wallet
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS portfolio_wallets (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                label TEXT NOT NULL,
+                address TEXT UNIQUE NOT NULL,
+                kind TEXT NOT NULL DEFAULT 'wallet',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Portfolio snapshots - periodic total valuation
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS portfolio_snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                taken_at TEXT NOT NULL,
+                total_value_usd REAL NOT NULL,
+                errors TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_portfolio_snapshots_taken ON portfolio_snapshots(taken_at)",
+            [],
+        )?;
+
+        // Portfolio balances - per wallet/network/token rows for each snapshot
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS portfolio_balances (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                snapshot_id INTEGER NOT NULL,
+                wallet_address TEXT NOT NULL,
+                wallet_label TEXT NOT NULL,
+                network TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                token_address TEXT NOT NULL,
+                decimals INTEGER NOT NULL,
+                raw_balance TEXT NOT NULL,
+                balance REAL NOT NULL,
+                price_usd REAL,
+                value_usd REAL,
+                FOREIGN KEY (snapshot_id) REFERENCES portfolio_snapshots(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_portfolio_balances_snapshot ON portfolio_balances(snapshot_id)",
+            [],
+        )?;
+
+        // Polymarket exit rules - take-profit / stop-loss / expiry exits evaluated by the scheduler
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS polymarket_exit_rules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                token_id TEXT NOT NULL,
+                market TEXT,
+                outcome TEXT,
+                size REAL,
+                take_profit REAL,
+                stop_loss REAL,
+                exit_at TEXT,
+                status TEXT NOT NULL DEFAULT 'active',
+                trigger_reason TEXT,
+                last_price REAL,
+                last_checked_at TEXT,
+                exit_order_id TEXT,
+                exit_price REAL,
+                exit_size REAL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                triggered_at TEXT
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_polymarket_exit_rules_status ON polymarket_exit_rules(status)",
+            [],
+        )?;
+
+        // Polymarket redemptions - one queued redeem transaction per resolved condition
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS polymarket_redemptions (
+                condition_id TEXT PRIMARY KEY,
+                tx_uuid TEXT NOT NULL,
+                queued_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
         Ok(())
     }
 
-    /// Record an x402 payment to the database
+    /// Record an x402 payment to the database (`kind`: "ai" for AI calls, "tool" otherwise)
     pub fn record_x402_payment(
         &self,
         channel_id: Option<i64>,
+        session_id: Option<i64>,
+        cron_job_id: Option<&str>,
+        kind: &str,
         tool_name: Option<&str>,
         resource: Option<&str>,
         amount: &str,
@@ -1229,10 +1618,11 @@ impl Database {
     ) -> Result<i64, rusqlite::Error> {
         let conn = self.conn();
         conn.execute(
-            "INSERT INTO x402_payments (channel_id, tool_name, resource, amount, amount_formatted, asset, pay_to, tx_hash, status)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            rusqlite::params![channel_id, tool_name, resource, amount, amount_formatted, asset, pay_to, tx_hash, status],
+            "INSERT INTO x402_payments (channel_id, session_id, cron_job_id, kind, tool_name, resource, amount, amount_formatted, asset, pay_to, tx_hash, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            rusqlite::params![channel_id, session_id, cron_job_id, kind, tool_name, resource, amount, amount_formatted, asset, pay_to, tx_hash, status],
         )?;
+        crate::metrics::METRICS.record_x402_payment(asset, amount_formatted);
         Ok(conn.last_insert_rowid())
     }
 
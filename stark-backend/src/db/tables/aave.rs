@@ -0,0 +1,65 @@
+//! Aave database operations (aave_auto_repays)
+//!
+//! One row per repay transaction the health-factor watcher queued, used to
+//! enforce the daily automatic repay limit.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+
+use super::super::Database;
+
+impl Database {
+    /// Record a queued automatic repay (`amount` in the asset's base units)
+    pub fn record_aave_auto_repay(
+        &self,
+        network: &str,
+        wallet: &str,
+        asset: &str,
+        amount: &str,
+        amount_usd: f64,
+        tx_uuid: &str,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO aave_auto_repays (network, wallet, asset, amount, amount_usd, tx_uuid, queued_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                network,
+                wallet.to_lowercase(),
+                asset.to_lowercase(),
+                amount,
+                amount_usd,
+                tx_uuid,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Number and USD total of automatic repays queued since `since`
+    pub fn aave_auto_repay_totals_since(&self, since: DateTime<Utc>) -> SqliteResult<(i64, f64)> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(amount_usd), 0) FROM aave_auto_repays WHERE queued_at >= ?1",
+            [since.to_rfc3339()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_auto_repay_totals() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.aave_auto_repay_totals_since(Utc::now() - Duration::hours(24)).unwrap(), (0, 0.0));
+
+        db.record_aave_auto_repay("base", "0xAA", "0xBB", "100000000", 100.0, "uuid-1").unwrap();
+        db.record_aave_auto_repay("base", "0xAA", "0xBB", "50000000", 50.0, "uuid-2").unwrap();
+        assert_eq!(db.aave_auto_repay_totals_since(Utc::now() - Duration::hours(24)).unwrap(), (2, 150.0));
+        assert_eq!(db.aave_auto_repay_totals_since(Utc::now() + Duration::hours(1)).unwrap().0, 0);
+    }
+}
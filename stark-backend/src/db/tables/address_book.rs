@@ -0,0 +1,114 @@
+//! Address book database operations (address_book)
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+
+use crate::address_book::{Contact, ContactInput, TrustLevel};
+use super::super::Database;
+
+const CONTACT_COLUMNS: &str =
+    "id, label, address, ens_name, networks, notes, trust_level, created_at, updated_at";
+
+fn row_to_contact(row: &rusqlite::Row) -> rusqlite::Result<Contact> {
+    let networks: String = row.get(4)?;
+    let trust_level: String = row.get(6)?;
+    Ok(Contact {
+        id: row.get(0)?,
+        label: row.get(1)?,
+        address: row.get(2)?,
+        ens_name: row.get(3)?,
+        networks: serde_json::from_str(&networks).unwrap_or_default(),
+        notes: row.get(5)?,
+        trust_level: TrustLevel::from_str(&trust_level).unwrap_or_default(),
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+impl Database {
+    /// Add a contact. `input` must be normalized and have an address.
+    pub fn add_contact(&self, input: &ContactInput) -> SqliteResult<Contact> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let networks = serde_json::to_string(&input.networks).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "INSERT INTO address_book (label, address, ens_name, networks, notes, trust_level, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            rusqlite::params![
+                input.label,
+                input.address.as_deref().unwrap_or_default(),
+                input.ens_name,
+                networks,
+                input.notes,
+                input.trust_level.as_str(),
+                now,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+        self.get_contact(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    /// Replace all fields of a contact. Returns None if it does not exist.
+    pub fn update_contact(&self, id: i64, input: &ContactInput) -> SqliteResult<Option<Contact>> {
+        let conn = self.conn();
+        let networks = serde_json::to_string(&input.networks).unwrap_or_else(|_| "[]".to_string());
+        let rows = conn.execute(
+            "UPDATE address_book SET label = ?1, address = ?2, ens_name = ?3, networks = ?4,
+                    notes = ?5, trust_level = ?6, updated_at = ?7
+             WHERE id = ?8",
+            rusqlite::params![
+                input.label,
+                input.address.as_deref().unwrap_or_default(),
+                input.ens_name,
+                networks,
+                input.notes,
+                input.trust_level.as_str(),
+                Utc::now().to_rfc3339(),
+                id,
+            ],
+        )?;
+        drop(conn);
+        if rows == 0 {
+            return Ok(None);
+        }
+        self.get_contact(id)
+    }
+
+    /// Remove a contact
+    pub fn delete_contact(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM address_book WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    pub fn get_contact(&self, id: i64) -> SqliteResult<Option<Contact>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM address_book WHERE id = ?1", CONTACT_COLUMNS))?;
+        let mut rows = stmt.query_map([id], row_to_contact)?;
+        rows.next().transpose()
+    }
+
+    /// Contact with the given address (case-insensitive)
+    pub fn get_contact_by_address(&self, address: &str) -> SqliteResult<Option<Contact>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM address_book WHERE address = ?1", CONTACT_COLUMNS))?;
+        let mut rows = stmt.query_map([address.to_lowercase()], row_to_contact)?;
+        rows.next().transpose()
+    }
+
+    /// All contacts, ordered by label
+    pub fn list_contacts(&self) -> SqliteResult<Vec<Contact>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM address_book ORDER BY label COLLATE NOCASE ASC",
+            CONTACT_COLUMNS
+        ))?;
+        let contacts = stmt
+            .query_map([], row_to_contact)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(contacts)
+    }
+}
@@ -3,7 +3,7 @@
 use chrono::{DateTime, Utc};
 use rusqlite::Result as SqliteResult;
 
-use crate::models::{AgentSettings, MIN_CONTEXT_TOKENS, DEFAULT_CONTEXT_TOKENS};
+use crate::models::{AgentSettings, ModelOptions, ProviderKind, MIN_CONTEXT_TOKENS, DEFAULT_CONTEXT_TOKENS};
 use super::super::Database;
 
 impl Database {
@@ -12,7 +12,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at
+            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at, model_name, provider_kind, temperature, top_p
              FROM agent_settings WHERE enabled = 1 LIMIT 1",
         )?;
 
@@ -28,7 +28,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at
+            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at, model_name, provider_kind, temperature, top_p
              FROM agent_settings WHERE endpoint = ?1",
         )?;
 
@@ -44,7 +44,7 @@ impl Database {
         let conn = self.conn();
 
         let mut stmt = conn.prepare(
-            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at
+            "SELECT id, endpoint, model_archetype, max_response_tokens, max_context_tokens, enabled, secret_key, created_at, updated_at, model_name, provider_kind, temperature, top_p
              FROM agent_settings ORDER BY id",
         )?;
 
@@ -64,6 +64,7 @@ impl Database {
         max_response_tokens: i32,
         max_context_tokens: i32,
         secret_key: Option<&str>,
+        options: &ModelOptions,
     ) -> SqliteResult<AgentSettings> {
         let conn = self.conn();
         let now = Utc::now().to_rfc3339();
@@ -86,15 +87,18 @@ impl Database {
         if let Some(id) = existing {
             // Update existing
             conn.execute(
-                "UPDATE agent_settings SET model_archetype = ?1, max_response_tokens = ?2, max_context_tokens = ?3, secret_key = ?4, enabled = 1, updated_at = ?5 WHERE id = ?6",
-                rusqlite::params![model_archetype, max_response_tokens, max_context_tokens, secret_key, &now, id],
+                "UPDATE agent_settings SET model_archetype = ?1, max_response_tokens = ?2, max_context_tokens = ?3, secret_key = ?4, enabled = 1, updated_at = ?5,
+                 model_name = ?7, provider_kind = ?8, temperature = ?9, top_p = ?10 WHERE id = ?6",
+                rusqlite::params![model_archetype, max_response_tokens, max_context_tokens, secret_key, &now, id,
+                    options.model, options.provider.as_str(), options.temperature, options.top_p],
             )?;
         } else {
             // Insert new
             conn.execute(
-                "INSERT INTO agent_settings (endpoint, model_archetype, max_response_tokens, max_context_tokens, secret_key, enabled, created_at, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7)",
-                rusqlite::params![endpoint, model_archetype, max_response_tokens, max_context_tokens, secret_key, &now, &now],
+                "INSERT INTO agent_settings (endpoint, model_archetype, max_response_tokens, max_context_tokens, secret_key, enabled, created_at, updated_at, model_name, provider_kind, temperature, top_p)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7, ?8, ?9, ?10, ?11)",
+                rusqlite::params![endpoint, model_archetype, max_response_tokens, max_context_tokens, secret_key, &now, &now,
+                    options.model, options.provider.as_str(), options.temperature, options.top_p],
             )?;
         }
 
@@ -125,6 +129,13 @@ impl Database {
             max_context_tokens: row.get::<_, Option<i32>>(4)?.unwrap_or(DEFAULT_CONTEXT_TOKENS),
             enabled: row.get::<_, i32>(5)? != 0,
             secret_key: row.get(6)?,
+            model: row.get::<_, Option<String>>(9)?.filter(|m| !m.is_empty()),
+            provider: row
+                .get::<_, Option<String>>(10)?
+                .and_then(|p| ProviderKind::from_str(&p))
+                .unwrap_or_default(),
+            temperature: row.get::<_, Option<f64>>(11)?.map(|t| t as f32),
+            top_p: row.get::<_, Option<f64>>(12)?.map(|t| t as f32),
             created_at: DateTime::parse_from_rfc3339(&created_at_str)
                 .unwrap()
                 .with_timezone(&Utc),
@@ -0,0 +1,148 @@
+//! AI spend budget database operations (ai_budget, x402_payments totals)
+
+use rusqlite::Result as SqliteResult;
+
+use crate::x402::budget::{AiBudget, AiSpend};
+use super::super::Database;
+
+/// Sum of USDC payments for AI calls (micro-USDC) matching a WHERE clause
+const SPEND_SQL: &str =
+    "SELECT COALESCE(SUM(CAST(amount AS INTEGER)), 0) FROM x402_payments WHERE asset = 'USDC' AND kind = 'ai' AND ";
+
+impl Database {
+    /// Get the configured AI spend limits (no row = no limits)
+    pub fn get_ai_budget(&self) -> SqliteResult<AiBudget> {
+        let conn = self.conn();
+        let result = conn.query_row(
+            "SELECT daily_limit, session_limit, cron_job_limit, fallback_enabled FROM ai_budget WHERE id = 1",
+            [],
+            |row| {
+                Ok(AiBudget {
+                    daily_limit: row.get::<_, Option<i64>>(0)?.map(|v| v.max(0) as u64),
+                    session_limit: row.get::<_, Option<i64>>(1)?.map(|v| v.max(0) as u64),
+                    cron_job_limit: row.get::<_, Option<i64>>(2)?.map(|v| v.max(0) as u64),
+                    fallback_enabled: row.get::<_, i64>(3)? != 0,
+                })
+            },
+        );
+
+        match result {
+            Ok(budget) => Ok(budget),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(AiBudget {
+                fallback_enabled: true,
+                ..AiBudget::default()
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Save AI spend limits
+    pub fn save_ai_budget(&self, budget: &AiBudget) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO ai_budget (id, daily_limit, session_limit, cron_job_limit, fallback_enabled, updated_at)
+             VALUES (1, ?1, ?2, ?3, ?4, datetime('now'))
+             ON CONFLICT(id) DO UPDATE SET
+                daily_limit = excluded.daily_limit,
+                session_limit = excluded.session_limit,
+                cron_job_limit = excluded.cron_job_limit,
+                fallback_enabled = excluded.fallback_enabled,
+                updated_at = excluded.updated_at",
+            rusqlite::params![
+                budget.daily_limit.map(|v| v as i64),
+                budget.session_limit.map(|v| v as i64),
+                budget.cron_job_limit.map(|v| v as i64),
+                budget.fallback_enabled as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// USDC spent today (UTC), in a session and by a cron job today
+    pub fn get_ai_spend(&self, session_id: Option<i64>, cron_job_id: Option<&str>) -> SqliteResult<AiSpend> {
+        let conn = self.conn();
+        let today: i64 = conn.query_row(
+            &format!("{}created_at >= date('now')", SPEND_SQL),
+            [],
+            |row| row.get(0),
+        )?;
+        let session: i64 = match session_id {
+            Some(id) => conn.query_row(&format!("{}session_id = ?1", SPEND_SQL), [id], |row| row.get(0))?,
+            None => 0,
+        };
+        let cron_job: i64 = match cron_job_id {
+            Some(id) => conn.query_row(&format!("{}cron_job_id = ?1 AND created_at >= date('now')", SPEND_SQL), [id], |row| row.get(0))?,
+            None => 0,
+        };
+
+        Ok(AiSpend {
+            today: today.max(0) as u64,
+            session: session.max(0) as u64,
+            cron_job: cron_job.max(0) as u64,
+        })
+    }
+
+    /// USDC spent today per cron job, highest first
+    pub fn list_cron_job_ai_spend_today(&self) -> SqliteResult<Vec<(String, u64)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT cron_job_id, SUM(CAST(amount AS INTEGER)) AS spent FROM x402_payments
+             WHERE asset = 'USDC' AND kind = 'ai' AND cron_job_id IS NOT NULL AND created_at >= date('now')
+             GROUP BY cron_job_id ORDER BY spent DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?.max(0) as u64))
+        })?;
+        rows.collect()
+    }
+
+    /// Amount of the most recent USDC payment to a resource (used to estimate calls to
+    /// endpoints without a configured `x402_cost`)
+    pub fn last_x402_payment_amount(&self, resource: &str) -> SqliteResult<Option<u64>> {
+        let conn = self.conn();
+        let result = conn.query_row(
+            "SELECT CAST(amount AS INTEGER) FROM x402_payments
+             WHERE asset = 'USDC' AND resource = ?1 ORDER BY id DESC LIMIT 1",
+            [resource],
+            |row| row.get::<_, i64>(0),
+        );
+        match result {
+            Ok(amount) => Ok(Some(amount.max(0) as u64)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::x402::budget::AI_PAYMENT_KIND;
+
+    fn pay(db: &Database, kind: &str, tool_name: Option<&str>, session_id: Option<i64>, cron_job_id: Option<&str>, amount: &str) {
+        db.record_x402_payment(
+            None, session_id, cron_job_id, kind, tool_name, Some("https://example.com/x"),
+            amount, "0", "USDC", "0xpay", None, "settled",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_ai_spend_ignores_tool_payments() {
+        let db = Database::new(":memory:").unwrap();
+        let session = |chat: &str| {
+            db.get_or_create_chat_session("web", 0, chat, crate::models::SessionScope::Dm, None)
+                .unwrap()
+                .id
+        };
+        let (first, second) = (session("a"), session("b"));
+        pay(&db, AI_PAYMENT_KIND, None, Some(first), Some("job-1"), "1000");
+        pay(&db, AI_PAYMENT_KIND, None, Some(second), None, "500");
+        pay(&db, "tool", Some("x402_fetch"), Some(first), Some("job-1"), "70000");
+        pay(&db, "tool", Some("swap"), None, None, "30000");
+
+        let spend = db.get_ai_spend(Some(first), Some("job-1")).unwrap();
+        assert_eq!(spend, AiSpend { today: 1500, session: 1000, cron_job: 1000 });
+        assert_eq!(db.list_cron_job_ai_spend_today().unwrap(), vec![("job-1".to_string(), 1000)]);
+    }
+}
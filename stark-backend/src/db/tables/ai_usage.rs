@@ -0,0 +1,171 @@
+//! AI usage database operations (ai_usage)
+//!
+//! One row per AI call with the tokens it consumed and its estimated cost, attributed to
+//! the channel, session and cron job it ran for.
+
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+/// Usage of a single AI call, as recorded by the dispatcher
+#[derive(Debug, Clone)]
+pub struct AiUsageRecord<'a> {
+    pub channel_id: Option<i64>,
+    pub session_id: Option<i64>,
+    pub cron_job_id: Option<&'a str>,
+    pub archetype: &'a str,
+    pub model: &'a str,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Token counts were estimated locally because the provider reported none
+    pub estimated: bool,
+    pub cost_usd: f64,
+}
+
+/// Summed usage over a set of AI calls
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AiUsageTotals {
+    pub calls: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Calls whose token counts were estimated
+    pub estimated_calls: u64,
+    pub cost_usd: f64,
+}
+
+/// Usage totals for one value of the grouping column
+#[derive(Debug, Clone, Serialize)]
+pub struct AiUsageGroup {
+    /// Session/channel/cron job id, model, archetype or day (None for unattributed calls)
+    pub key: Option<String>,
+    #[serde(flatten)]
+    pub totals: AiUsageTotals,
+}
+
+/// Column AI usage can be aggregated by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiUsageGroupBy {
+    Session,
+    Channel,
+    CronJob,
+    Model,
+    Archetype,
+    Day,
+}
+
+impl AiUsageGroupBy {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "session" => Some(Self::Session),
+            "channel" => Some(Self::Channel),
+            "cron_job" => Some(Self::CronJob),
+            "model" => Some(Self::Model),
+            "archetype" => Some(Self::Archetype),
+            "day" => Some(Self::Day),
+            _ => None,
+        }
+    }
+
+    fn column(&self) -> &'static str {
+        match self {
+            Self::Session => "CAST(session_id AS TEXT)",
+            Self::Channel => "CAST(channel_id AS TEXT)",
+            Self::CronJob => "cron_job_id",
+            Self::Model => "model",
+            Self::Archetype => "archetype",
+            Self::Day => "date(created_at)",
+        }
+    }
+}
+
+const TOTALS_COLUMNS: &str = "COUNT(*), COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0),
+     COALESCE(SUM(estimated), 0), COALESCE(SUM(cost_usd), 0)";
+
+fn row_to_totals(row: &rusqlite::Row, offset: usize) -> rusqlite::Result<AiUsageTotals> {
+    Ok(AiUsageTotals {
+        calls: row.get::<_, i64>(offset)?.max(0) as u64,
+        input_tokens: row.get::<_, i64>(offset + 1)?.max(0) as u64,
+        output_tokens: row.get::<_, i64>(offset + 2)?.max(0) as u64,
+        estimated_calls: row.get::<_, i64>(offset + 3)?.max(0) as u64,
+        cost_usd: row.get(offset + 4)?,
+    })
+}
+
+impl Database {
+    /// Record the usage of one AI call
+    pub fn record_ai_usage(&self, usage: &AiUsageRecord) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO ai_usage (channel_id, session_id, cron_job_id, archetype, model,
+                                   input_tokens, output_tokens, estimated, cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                usage.channel_id,
+                usage.session_id,
+                usage.cron_job_id,
+                usage.archetype,
+                usage.model,
+                usage.input_tokens as i64,
+                usage.output_tokens as i64,
+                usage.estimated as i64,
+                usage.cost_usd,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Total usage of calls made since `since` (SQLite datetime, None = all time)
+    pub fn get_ai_usage_totals(&self, since: Option<&str>) -> SqliteResult<AiUsageTotals> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!(
+                "SELECT {} FROM ai_usage WHERE (?1 IS NULL OR created_at >= ?1)",
+                TOTALS_COLUMNS
+            ),
+            [since],
+            |row| row_to_totals(row, 0),
+        )
+    }
+
+    /// Usage since `since` grouped by a column, most expensive first
+    /// (days are listed newest first)
+    pub fn list_ai_usage_grouped(
+        &self,
+        group_by: AiUsageGroupBy,
+        since: Option<&str>,
+        limit: usize,
+    ) -> SqliteResult<Vec<AiUsageGroup>> {
+        let conn = self.conn();
+        let order = if group_by == AiUsageGroupBy::Day {
+            "group_key DESC"
+        } else {
+            "SUM(cost_usd) DESC, SUM(input_tokens) + SUM(output_tokens) DESC"
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} AS group_key, {} FROM ai_usage
+             WHERE (?1 IS NULL OR created_at >= ?1)
+             GROUP BY group_key ORDER BY {} LIMIT ?2",
+            group_by.column(),
+            TOTALS_COLUMNS,
+            order
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![since, limit as i64], |row| {
+            Ok(AiUsageGroup {
+                key: row.get(0)?,
+                totals: row_to_totals(row, 1)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Total usage of one session
+    pub fn get_session_ai_usage(&self, session_id: i64) -> SqliteResult<AiUsageTotals> {
+        let conn = self.conn();
+        conn.query_row(
+            &format!("SELECT {} FROM ai_usage WHERE session_id = ?1", TOTALS_COLUMNS),
+            [session_id],
+            |row| row_to_totals(row, 0),
+        )
+    }
+}
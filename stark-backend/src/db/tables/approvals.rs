@@ -0,0 +1,230 @@
+//! Token approval database operations (token_approvals, approval_scan_cursors)
+//!
+//! Addresses are stored lowercase. `allowance` is the last on-chain read (raw
+//! base units, decimal string); "0" means revoked or fully spent.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use super::super::Database;
+
+/// An allowance the bot wallet or a tracked Safe granted
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenApproval {
+    pub id: i64,
+    pub network: String,
+    pub owner: String,
+    pub token: String,
+    pub spender: String,
+    pub token_symbol: Option<String>,
+    pub decimals: Option<u8>,
+    pub allowance: String,
+    /// Block of the latest Approval event seen for this pair
+    pub last_event_block: u64,
+    pub updated_at: String,
+}
+
+impl TokenApproval {
+    pub fn is_live(&self) -> bool {
+        self.allowance != "0"
+    }
+}
+
+const APPROVAL_COLUMNS: &str =
+    "id, network, owner, token, spender, token_symbol, decimals, allowance, last_event_block, updated_at";
+
+fn row_to_approval(row: &rusqlite::Row) -> rusqlite::Result<TokenApproval> {
+    Ok(TokenApproval {
+        id: row.get(0)?,
+        network: row.get(1)?,
+        owner: row.get(2)?,
+        token: row.get(3)?,
+        spender: row.get(4)?,
+        token_symbol: row.get(5)?,
+        decimals: row.get(6)?,
+        allowance: row.get(7)?,
+        last_event_block: row.get::<_, i64>(8)? as u64,
+        updated_at: row.get(9)?,
+    })
+}
+
+impl Database {
+    /// Record an Approval event, keeping the highest block seen for the pair
+    pub fn record_approval_event(
+        &self,
+        network: &str,
+        owner: &str,
+        token: &str,
+        spender: &str,
+        block: u64,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO token_approvals (network, owner, token, spender, last_event_block, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(network, owner, token, spender)
+             DO UPDATE SET last_event_block = MAX(last_event_block, excluded.last_event_block)",
+            rusqlite::params![
+                network,
+                owner.to_lowercase(),
+                token.to_lowercase(),
+                spender.to_lowercase(),
+                block as i64,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Store the current on-chain allowance (and token metadata when known)
+    pub fn update_approval_allowance(
+        &self,
+        id: i64,
+        allowance: &str,
+        token_symbol: Option<&str>,
+        decimals: Option<u8>,
+    ) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE token_approvals SET allowance = ?1,
+                token_symbol = COALESCE(?2, token_symbol),
+                decimals = COALESCE(?3, decimals),
+                updated_at = ?4
+             WHERE id = ?5",
+            rusqlite::params![allowance, token_symbol, decimals, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    pub fn get_token_approval(&self, id: i64) -> SqliteResult<Option<TokenApproval>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM token_approvals WHERE id = ?1", APPROVAL_COLUMNS))?;
+        let mut rows = stmt.query_map([id], row_to_approval)?;
+        rows.next().transpose()
+    }
+
+    /// List approvals, optionally for one network/owner and only non-zero allowances
+    pub fn list_token_approvals(
+        &self,
+        network: Option<&str>,
+        owner: Option<&str>,
+        live_only: bool,
+    ) -> SqliteResult<Vec<TokenApproval>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM token_approvals
+             WHERE (?1 IS NULL OR network = ?1)
+               AND (?2 IS NULL OR owner = ?2)
+               AND (?3 = 0 OR allowance != '0')
+             ORDER BY network, owner, token, spender",
+            APPROVAL_COLUMNS
+        ))?;
+        let approvals = stmt
+            .query_map(
+                rusqlite::params![network, owner.map(|o| o.to_lowercase()), live_only as i64],
+                row_to_approval,
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(approvals)
+    }
+
+    /// Last block scanned for `owner` on `network`
+    pub fn get_approval_scan_cursor(&self, network: &str, owner: &str) -> SqliteResult<Option<u64>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT last_block FROM approval_scan_cursors WHERE network = ?1 AND owner = ?2",
+        )?;
+        let mut rows = stmt.query_map(rusqlite::params![network, owner.to_lowercase()], |row| {
+            row.get::<_, i64>(0)
+        })?;
+        Ok(rows.next().transpose()?.map(|b| b as u64))
+    }
+
+    pub fn set_approval_scan_cursor(&self, network: &str, owner: &str, last_block: u64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO approval_scan_cursors (network, owner, last_block, scanned_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(network, owner) DO UPDATE SET last_block = excluded.last_block, scanned_at = excluded.scanned_at",
+            rusqlite::params![network, owner.to_lowercase(), last_block as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// When any wallet was last scanned (None = never)
+    pub fn latest_approval_scan_at(&self) -> SqliteResult<Option<DateTime<Utc>>> {
+        let conn = self.conn();
+        let latest: Option<String> =
+            conn.query_row("SELECT MAX(scanned_at) FROM approval_scan_cursors", [], |row| row.get(0))?;
+        Ok(latest
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    /// Record that an approval scan is starting, whether or not it will succeed
+    pub fn record_approval_scan_attempt(&self) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO approval_scan_state (id, last_attempt_at) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET last_attempt_at = excluded.last_attempt_at",
+            [Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// When the last approval scan was started (None = never)
+    pub fn last_approval_scan_attempt_at(&self) -> SqliteResult<Option<DateTime<Utc>>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT last_attempt_at FROM approval_scan_state WHERE id = 1")?;
+        let mut rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows
+            .next()
+            .transpose()?
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approval_events_and_cursor() {
+        let db = Database::new(":memory:").unwrap();
+        db.record_approval_event("base", "0xAA", "0xBB", "0xCC", 100).unwrap();
+        db.record_approval_event("base", "0xaa", "0xbb", "0xcc", 90).unwrap();
+
+        let all = db.list_token_approvals(Some("base"), Some("0xAA"), false).unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].last_event_block, 100);
+        assert!(!all[0].is_live());
+        assert!(db.list_token_approvals(None, None, true).unwrap().is_empty());
+
+        db.update_approval_allowance(all[0].id, "5000", Some("USDC"), Some(6)).unwrap();
+        let live = db.list_token_approvals(None, None, true).unwrap();
+        assert_eq!(live[0].token_symbol.as_deref(), Some("USDC"));
+        assert_eq!(live[0].decimals, Some(6));
+
+        assert_eq!(db.get_approval_scan_cursor("base", "0xaa").unwrap(), None);
+        assert!(db.latest_approval_scan_at().unwrap().is_none());
+        db.set_approval_scan_cursor("base", "0xAA", 120).unwrap();
+        assert_eq!(db.get_approval_scan_cursor("base", "0xaa").unwrap(), Some(120));
+        assert!(db.latest_approval_scan_at().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_scan_attempt_recorded() {
+        let db = Database::new(":memory:").unwrap();
+        assert!(db.last_approval_scan_attempt_at().unwrap().is_none());
+        db.record_approval_scan_attempt().unwrap();
+        let first = db.last_approval_scan_attempt_at().unwrap().unwrap();
+        db.record_approval_scan_attempt().unwrap();
+        assert!(db.last_approval_scan_attempt_at().unwrap().unwrap() >= first);
+        // No cursor was written, so no wallet counts as scanned
+        assert!(db.latest_approval_scan_at().unwrap().is_none());
+    }
+}
@@ -16,18 +16,28 @@ impl Database {
     }
 
     pub fn create_session_for_address(&self, public_address: Option<&str>) -> SqliteResult<Session> {
+        self.insert_session(public_address, false)
+    }
+
+    /// Session for the instance owner, created after the Flash control plane vouched for them
+    pub fn create_flash_session(&self, public_address: &str) -> SqliteResult<Session> {
+        self.insert_session(Some(public_address), true)
+    }
+
+    fn insert_session(&self, public_address: Option<&str>, flash: bool) -> SqliteResult<Session> {
         let conn = self.conn();
         let token = Self::generate_session_token();
         let created_at = Utc::now();
         let expires_at = created_at + Duration::hours(24);
 
         conn.execute(
-            "INSERT INTO auth_sessions (token, public_address, created_at, expires_at) VALUES (?1, ?2, ?3, ?4)",
+            "INSERT INTO auth_sessions (token, public_address, created_at, expires_at, flash) VALUES (?1, ?2, ?3, ?4, ?5)",
             rusqlite::params![
                 &token,
                 public_address,
                 &created_at.to_rfc3339(),
                 &expires_at.to_rfc3339(),
+                flash,
             ],
         )?;
 
@@ -36,6 +46,8 @@ impl Database {
         Ok(Session {
             id,
             token,
+            public_address: public_address.map(|a| a.to_string()),
+            flash,
             created_at,
             expires_at,
         })
@@ -55,7 +67,7 @@ impl Database {
         let now_str = now.to_rfc3339();
 
         let mut stmt = conn.prepare(
-            "SELECT id, token, created_at, expires_at FROM auth_sessions WHERE token = ?1 AND expires_at > ?2",
+            "SELECT id, token, created_at, expires_at, public_address, flash FROM auth_sessions WHERE token = ?1 AND expires_at > ?2",
         )?;
 
         let session = stmt
@@ -66,6 +78,8 @@ impl Database {
                 Ok(Session {
                     id: row.get(0)?,
                     token: row.get(1)?,
+                    public_address: row.get(4)?,
+                    flash: row.get(5)?,
                     created_at: DateTime::parse_from_rfc3339(&created_at_str)
                         .unwrap()
                         .with_timezone(&Utc),
@@ -241,6 +241,11 @@ impl Database {
         let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
         conn.execute(&query, params_refs.as_slice())?;
 
+        // A changed schedule starts event triggers from scratch
+        if schedule_type.is_some() || schedule_value.is_some() {
+            conn.execute("DELETE FROM cron_trigger_state WHERE job_id = ?1", [id])?;
+        }
+
         self.get_cron_job_by_id_internal(&conn, id)
     }
 
@@ -294,6 +299,7 @@ impl Database {
     /// Delete a cron job
     pub fn delete_cron_job(&self, id: i64) -> SqliteResult<bool> {
         let conn = self.conn();
+        conn.execute("DELETE FROM cron_trigger_state WHERE job_id = ?1", [id])?;
         let rows_affected = conn.execute("DELETE FROM cron_jobs WHERE id = ?1", [id])?;
         Ok(rows_affected > 0)
     }
@@ -302,6 +308,7 @@ impl Database {
     /// Returns the number of jobs deleted
     pub fn clear_cron_jobs_for_restore(&self) -> SqliteResult<usize> {
         let conn = self.conn();
+        conn.execute("DELETE FROM cron_trigger_state", [])?;
         let rows_deleted = conn.execute("DELETE FROM cron_jobs", [])?;
         Ok(rows_deleted)
     }
@@ -365,4 +372,32 @@ impl Database {
 
         Ok(runs)
     }
+
+    /// Get the persisted event trigger state (JSON) for a cron job
+    pub fn get_cron_trigger_state(&self, job_id: i64) -> SqliteResult<Option<String>> {
+        let conn = self.conn();
+        match conn.query_row(
+            "SELECT state FROM cron_trigger_state WHERE job_id = ?1",
+            [job_id],
+            |row| row.get(0),
+        ) {
+            Ok(state) => Ok(Some(state)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Save the event trigger state (JSON) for a cron job
+    pub fn set_cron_trigger_state(&self, job_id: i64, state: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO cron_trigger_state (job_id, state, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(job_id) DO UPDATE SET state = excluded.state, updated_at = excluded.updated_at",
+            rusqlite::params![job_id, state, now],
+        )?;
+
+        Ok(())
+    }
 }
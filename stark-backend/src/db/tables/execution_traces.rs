@@ -0,0 +1,311 @@
+//! Persisted execution traces (execution_traces, execution_spans) database operations
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+
+use crate::models::{ExecutionTrace, TraceSpan, TraceSpanKind};
+use super::super::Database;
+
+fn parse_datetime(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+fn parse_json(s: Option<String>) -> Option<serde_json::Value> {
+    s.and_then(|s| serde_json::from_str(&s).ok())
+}
+
+const TRACE_COLUMNS: &str = "execution_id, channel_id, session_id, chat_id, mode, user_message, status,
+     outcome, replay_of, started_at, completed_at, duration_ms, ai_calls, tool_calls, tokens_used, x402_spend";
+
+fn row_to_trace(row: &rusqlite::Row) -> rusqlite::Result<ExecutionTrace> {
+    let started_at: String = row.get(9)?;
+    let completed_at: Option<String> = row.get(10)?;
+    let duration_ms: Option<i64> = row.get(11)?;
+    let tokens_used: i64 = row.get(14)?;
+    Ok(ExecutionTrace {
+        execution_id: row.get(0)?,
+        channel_id: row.get(1)?,
+        session_id: row.get(2)?,
+        chat_id: row.get(3)?,
+        mode: row.get(4)?,
+        user_message: row.get(5)?,
+        status: row.get(6)?,
+        outcome: row.get(7)?,
+        replay_of: row.get(8)?,
+        started_at: parse_datetime(&started_at),
+        completed_at: completed_at.as_deref().map(parse_datetime),
+        duration_ms: duration_ms.map(|d| d.max(0) as u64),
+        ai_calls: row.get(12)?,
+        tool_calls: row.get(13)?,
+        tokens_used: tokens_used.max(0) as u64,
+        x402_spend: row.get(15)?,
+    })
+}
+
+fn row_to_span(row: &rusqlite::Row) -> rusqlite::Result<TraceSpan> {
+    let kind: String = row.get(3)?;
+    let success: i64 = row.get(6)?;
+    let started_at: String = row.get(10)?;
+    let completed_at: String = row.get(11)?;
+    let duration_ms: i64 = row.get(12)?;
+    let tokens: i64 = row.get(13)?;
+    Ok(TraceSpan {
+        span_id: row.get(0)?,
+        execution_id: row.get(1)?,
+        parent_span_id: row.get(2)?,
+        kind: TraceSpanKind::from_str(&kind).unwrap_or(TraceSpanKind::Task),
+        name: row.get(4)?,
+        description: row.get(5)?,
+        success: success != 0,
+        error: row.get(7)?,
+        input: parse_json(row.get(8)?),
+        output: parse_json(row.get(9)?),
+        started_at: parse_datetime(&started_at),
+        completed_at: parse_datetime(&completed_at),
+        duration_ms: duration_ms.max(0) as u64,
+        tokens: tokens.max(0) as u64,
+        x402_amount: row.get(14)?,
+        x402_asset: row.get(15)?,
+    })
+}
+
+impl Database {
+    /// Record the start of an execution
+    pub fn insert_execution_trace(
+        &self,
+        execution_id: &str,
+        channel_id: i64,
+        chat_id: Option<&str>,
+        mode: &str,
+        user_message: Option<&str>,
+        started_at: DateTime<Utc>,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR IGNORE INTO execution_traces
+                (execution_id, channel_id, chat_id, mode, user_message, status, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'running', ?6)",
+            rusqlite::params![execution_id, channel_id, chat_id, mode, user_message, started_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_execution_trace_session(&self, execution_id: &str, session_id: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE execution_traces SET session_id = ?1 WHERE execution_id = ?2",
+            rusqlite::params![session_id, execution_id],
+        )?;
+        Ok(())
+    }
+
+    /// Mark an execution as a replay of another one
+    pub fn set_execution_trace_replay_of(&self, execution_id: &str, replay_of: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE execution_traces SET replay_of = ?1 WHERE execution_id = ?2",
+            rusqlite::params![replay_of, execution_id],
+        )?;
+        Ok(())
+    }
+
+    /// Record the outcome of a running execution (final response or error).
+    /// Only the first outcome is kept so a later cancellation doesn't overwrite an error.
+    pub fn set_execution_trace_outcome(&self, execution_id: &str, status: &str, outcome: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE execution_traces SET status = ?1, outcome = ?2
+             WHERE execution_id = ?3 AND status = 'running'",
+            rusqlite::params![status, outcome, execution_id],
+        )?;
+        Ok(())
+    }
+
+    /// Close an execution and roll up its span totals
+    pub fn finish_execution_trace(
+        &self,
+        execution_id: &str,
+        status: &str,
+        completed_at: DateTime<Utc>,
+        duration_ms: u64,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "UPDATE execution_traces SET
+                status = CASE WHEN status = 'running' THEN ?1 ELSE status END,
+                completed_at = ?2,
+                duration_ms = ?3,
+                ai_calls = (SELECT COUNT(*) FROM execution_spans WHERE execution_id = ?4 AND kind = 'ai_call'),
+                tool_calls = (SELECT COUNT(*) FROM execution_spans WHERE execution_id = ?4 AND kind = 'tool_call'),
+                tokens_used = (SELECT COALESCE(SUM(tokens), 0) FROM execution_spans WHERE execution_id = ?4),
+                x402_spend = (SELECT COALESCE(SUM(CAST(x402_amount AS REAL)), 0) FROM execution_spans WHERE execution_id = ?4)
+             WHERE execution_id = ?4",
+            rusqlite::params![status, completed_at.to_rfc3339(), duration_ms as i64, execution_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_execution_span(&self, span: &TraceSpan) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO execution_spans
+                (span_id, execution_id, parent_span_id, kind, name, description, success, error,
+                 input, output, started_at, completed_at, duration_ms, tokens, x402_amount, x402_asset)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            rusqlite::params![
+                span.span_id,
+                span.execution_id,
+                span.parent_span_id,
+                span.kind.as_str(),
+                span.name,
+                span.description,
+                span.success as i64,
+                span.error,
+                span.input.as_ref().map(|v| v.to_string()),
+                span.output.as_ref().map(|v| v.to_string()),
+                span.started_at.to_rfc3339(),
+                span.completed_at.to_rfc3339(),
+                span.duration_ms as i64,
+                span.tokens as i64,
+                span.x402_amount,
+                span.x402_asset,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// List executions, newest first, optionally filtered by session or channel
+    pub fn list_execution_traces(
+        &self,
+        session_id: Option<i64>,
+        channel_id: Option<i64>,
+        limit: usize,
+        offset: usize,
+    ) -> SqliteResult<Vec<ExecutionTrace>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM execution_traces
+             WHERE (?1 IS NULL OR session_id = ?1) AND (?2 IS NULL OR channel_id = ?2)
+             ORDER BY started_at DESC LIMIT ?3 OFFSET ?4",
+            TRACE_COLUMNS
+        ))?;
+        let traces = stmt
+            .query_map(
+                rusqlite::params![session_id, channel_id, limit as i64, offset as i64],
+                row_to_trace,
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(traces)
+    }
+
+    pub fn get_execution_trace(&self, execution_id: &str) -> SqliteResult<Option<ExecutionTrace>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM execution_traces WHERE execution_id = ?1",
+            TRACE_COLUMNS
+        ))?;
+        Ok(stmt.query_row([execution_id], row_to_trace).ok())
+    }
+
+    /// Most recent execution started for a channel/chat pair
+    pub fn get_latest_execution_trace_for_chat(
+        &self,
+        channel_id: i64,
+        chat_id: &str,
+    ) -> SqliteResult<Option<ExecutionTrace>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM execution_traces WHERE channel_id = ?1 AND chat_id = ?2
+             ORDER BY started_at DESC LIMIT 1",
+            TRACE_COLUMNS
+        ))?;
+        Ok(stmt.query_row(rusqlite::params![channel_id, chat_id], row_to_trace).ok())
+    }
+
+    /// All spans of an execution, in the order they were recorded
+    pub fn list_execution_spans(&self, execution_id: &str) -> SqliteResult<Vec<TraceSpan>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT span_id, execution_id, parent_span_id, kind, name, description, success, error,
+                    input, output, started_at, completed_at, duration_ms, tokens, x402_amount, x402_asset
+             FROM execution_spans WHERE execution_id = ?1 ORDER BY id",
+        )?;
+        let spans = stmt
+            .query_map([execution_id], row_to_span)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(spans)
+    }
+
+    /// Delete an execution and its spans. Returns false if it didn't exist.
+    pub fn delete_execution_trace(&self, execution_id: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        conn.execute("DELETE FROM execution_spans WHERE execution_id = ?1", [execution_id])?;
+        let deleted = conn.execute("DELETE FROM execution_traces WHERE execution_id = ?1", [execution_id])?;
+        Ok(deleted > 0)
+    }
+
+    /// Delete executions started more than `keep_days` ago (spans included)
+    pub fn cleanup_old_execution_traces(&self, keep_days: i64) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let cutoff = (Utc::now() - chrono::Duration::days(keep_days)).to_rfc3339();
+        conn.execute(
+            "DELETE FROM execution_spans WHERE execution_id IN
+                 (SELECT execution_id FROM execution_traces WHERE started_at < ?1)",
+            [&cutoff],
+        )?;
+        conn.execute("DELETE FROM execution_traces WHERE started_at < ?1", [&cutoff])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(execution_id: &str) -> TraceSpan {
+        let now = Utc::now();
+        TraceSpan {
+            span_id: uuid::Uuid::new_v4().to_string(),
+            execution_id: execution_id.to_string(),
+            parent_span_id: None,
+            kind: TraceSpanKind::ToolCall,
+            name: "web3_balance".to_string(),
+            description: None,
+            success: true,
+            error: None,
+            input: None,
+            output: None,
+            started_at: now,
+            completed_at: now,
+            duration_ms: 0,
+            tokens: 0,
+            x402_amount: None,
+            x402_asset: None,
+        }
+    }
+
+    #[test]
+    fn test_delete_and_cleanup_traces() {
+        let db = Database::new(":memory:").unwrap();
+        let old = Utc::now() - chrono::Duration::days(40);
+        db.insert_execution_trace("old", 0, None, "chat", Some("hi"), old).unwrap();
+        db.insert_execution_trace("new", 0, None, "chat", Some("hi"), Utc::now()).unwrap();
+        db.insert_execution_trace("gone", 0, None, "chat", Some("hi"), Utc::now()).unwrap();
+        for id in ["old", "new", "gone"] {
+            db.insert_execution_span(&span(id)).unwrap();
+        }
+
+        assert!(db.delete_execution_trace("gone").unwrap());
+        assert!(!db.delete_execution_trace("gone").unwrap());
+        assert!(db.list_execution_spans("gone").unwrap().is_empty());
+
+        assert_eq!(db.cleanup_old_execution_traces(30).unwrap(), 1);
+        assert!(db.get_execution_trace("old").unwrap().is_none());
+        assert!(db.list_execution_spans("old").unwrap().is_empty());
+        assert!(db.get_execution_trace("new").unwrap().is_some());
+        assert_eq!(db.list_execution_spans("new").unwrap().len(), 1);
+    }
+}
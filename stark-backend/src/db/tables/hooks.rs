@@ -0,0 +1,47 @@
+//! Hook channel override database operations
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+
+use super::super::Database;
+
+impl Database {
+    /// List all per-channel hook overrides as (hook_id, channel_id, enabled)
+    pub fn list_hook_channel_overrides(&self) -> SqliteResult<Vec<(String, i64, bool)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT hook_id, channel_id, enabled FROM hook_channel_overrides ORDER BY hook_id, channel_id",
+        )?;
+        let overrides = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, i64>(2)? != 0))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(overrides)
+    }
+
+    /// Enable or disable a hook for a channel (upsert)
+    pub fn set_hook_channel_override(&self, hook_id: &str, channel_id: i64, enabled: bool) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO hook_channel_overrides (hook_id, channel_id, enabled, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(hook_id, channel_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at",
+            rusqlite::params![hook_id, channel_id, enabled as i64, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a hook's override for a channel
+    pub fn delete_hook_channel_override(&self, hook_id: &str, channel_id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "DELETE FROM hook_channel_overrides WHERE hook_id = ?1 AND channel_id = ?2",
+            rusqlite::params![hook_id, channel_id],
+        )?;
+        Ok(rows > 0)
+    }
+}
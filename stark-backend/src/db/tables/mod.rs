@@ -3,6 +3,7 @@
 //! Each module adds `impl Database` blocks with methods for a specific table group.
 
 mod auth;           // auth_sessions, auth_challenges
+mod operators;      // operators, operator_audit_log
 mod api_keys;       // external_api_keys
 mod channels;       // external_channels
 mod channel_settings; // channel_settings (per-channel config)
@@ -14,9 +15,19 @@ mod tool_configs;   // tool_configs, tool_executions
 mod skills;         // skills, skill_scripts
 mod cron_jobs;      // cron_jobs, cron_job_runs
 mod heartbeat;      // heartbeat_configs
+mod execution_traces; // execution_traces, execution_spans
+mod hooks;          // hook_channel_overrides
 mod gmail;          // gmail_configs
 mod agent_contexts; // agent_contexts (multi-agent orchestrator state)
 mod twitter_mentions; // twitter_processed_mentions (track processed tweets)
+mod ai_budget;      // ai_budget (x402 AI spend limits)
+pub mod ai_usage;   // ai_usage (token usage and cost per AI call)
+mod address_book;   // address_book (labelled contacts)
+pub mod payouts;    // payout_batches (batch payouts and per-recipient status)
+pub mod approvals;  // token_approvals, approval_scan_cursors (approval auditor)
+mod aave;           // aave_auto_repays (health-factor watcher repays)
 pub mod broadcasted_transactions; // broadcasted_transactions (crypto tx history)
 pub mod mind_nodes;  // mind_nodes, mind_node_connections (mind map feature)
 pub mod telegram_chat_log; // telegram_chat_messages (passive chat log for readHistory)
+pub mod polymarket;  // polymarket_exit_rules, polymarket_redemptions
+pub mod portfolio;   // portfolio_wallets, portfolio_snapshots, portfolio_balances
@@ -0,0 +1,171 @@
+//! Operators (multi-user web login with roles) and operator audit log database operations
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+
+use crate::models::{AuditLogEntry, Operator, OperatorRole};
+use super::super::Database;
+
+fn parse_datetime(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+fn row_to_operator(row: &rusqlite::Row) -> rusqlite::Result<Operator> {
+    let role: String = row.get(2)?;
+    let created_at: String = row.get(4)?;
+    let updated_at: String = row.get(5)?;
+    Ok(Operator {
+        id: row.get(0)?,
+        public_address: row.get(1)?,
+        // Unknown roles (e.g. hand-edited rows) degrade to read-only
+        role: OperatorRole::from_str(&role).unwrap_or(OperatorRole::Viewer),
+        label: row.get(3)?,
+        created_at: parse_datetime(&created_at),
+        updated_at: parse_datetime(&updated_at),
+    })
+}
+
+impl Database {
+    // ============================================
+    // Operators
+    // ============================================
+
+    pub fn list_operators(&self) -> SqliteResult<Vec<Operator>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, public_address, role, label, created_at, updated_at
+             FROM operators ORDER BY created_at",
+        )?;
+        let operators = stmt
+            .query_map([], row_to_operator)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(operators)
+    }
+
+    pub fn get_operator(&self, public_address: &str) -> SqliteResult<Option<Operator>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, public_address, role, label, created_at, updated_at
+             FROM operators WHERE public_address = ?1",
+        )?;
+        let operator = stmt
+            .query_row([public_address.to_lowercase()], row_to_operator)
+            .ok();
+        Ok(operator)
+    }
+
+    /// Add an operator or update the role/label of an existing one
+    pub fn upsert_operator(
+        &self,
+        public_address: &str,
+        role: OperatorRole,
+        label: Option<&str>,
+    ) -> SqliteResult<Operator> {
+        let now = Utc::now().to_rfc3339();
+        {
+            let conn = self.conn();
+            conn.execute(
+                "INSERT INTO operators (public_address, role, label, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?4)
+                 ON CONFLICT(public_address) DO UPDATE SET
+                    role = excluded.role,
+                    label = excluded.label,
+                    updated_at = excluded.updated_at",
+                rusqlite::params![public_address.to_lowercase(), role.as_str(), label, now],
+            )?;
+        }
+        self.get_operator(public_address)?
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    pub fn delete_operator(&self, public_address: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "DELETE FROM operators WHERE public_address = ?1",
+            [public_address.to_lowercase()],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Delete all login sessions for an address (used when an operator is removed)
+    pub fn delete_sessions_for_address(&self, public_address: &str) -> SqliteResult<usize> {
+        let conn = self.conn();
+        conn.execute(
+            "DELETE FROM auth_sessions WHERE LOWER(public_address) = ?1",
+            [public_address.to_lowercase()],
+        )
+    }
+
+    // ============================================
+    // Operator audit log
+    // ============================================
+
+    pub fn insert_audit_log(
+        &self,
+        public_address: Option<&str>,
+        role: Option<OperatorRole>,
+        source: &str,
+        action: &str,
+        outcome: &str,
+        detail: Option<&str>,
+    ) -> SqliteResult<i64> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO operator_audit_log (public_address, role, source, action, outcome, detail, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                public_address,
+                role.map(|r| r.as_str()),
+                source,
+                action,
+                outcome,
+                detail,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// List audit log entries, newest first, optionally filtered by operator address
+    pub fn list_audit_log(
+        &self,
+        public_address: Option<&str>,
+        limit: usize,
+        offset: usize,
+    ) -> SqliteResult<Vec<AuditLogEntry>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, public_address, role, source, action, outcome, detail, created_at
+             FROM operator_audit_log
+             WHERE (?1 IS NULL OR public_address = ?1)
+             ORDER BY id DESC LIMIT ?2 OFFSET ?3",
+        )?;
+        let entries = stmt
+            .query_map(
+                rusqlite::params![
+                    public_address.map(|a| a.to_lowercase()),
+                    limit as i64,
+                    offset as i64
+                ],
+                |row| {
+                    let created_at: String = row.get(7)?;
+                    Ok(AuditLogEntry {
+                        id: row.get(0)?,
+                        public_address: row.get(1)?,
+                        role: row.get(2)?,
+                        source: row.get(3)?,
+                        action: row.get(4)?,
+                        outcome: row.get(5)?,
+                        detail: row.get(6)?,
+                        created_at: parse_datetime(&created_at),
+                    })
+                },
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(entries)
+    }
+}
@@ -0,0 +1,138 @@
+//! Batch payout database operations (payout_batches)
+
+use chrono::Utc;
+use rusqlite::Result as SqliteResult;
+use serde::Serialize;
+
+use crate::payouts::PayoutRecipient;
+use super::super::Database;
+
+/// A stored batch payout
+#[derive(Debug, Clone, Serialize)]
+pub struct PayoutBatch {
+    pub id: i64,
+    pub network: String,
+    pub token_symbol: String,
+    pub token_address: String,
+    pub decimals: u8,
+    /// Bot wallet or Safe paying out
+    pub source_address: String,
+    /// "multicall3" or "safe_multisend"
+    pub method: String,
+    pub total_raw: String,
+    pub total_usd: Option<f64>,
+    pub recipients: Vec<PayoutRecipient>,
+    /// Queued transaction (the batch itself, or the Safe approveHash)
+    pub tx_uuid: Option<String>,
+    pub safe_tx_hash: Option<String>,
+    /// "queued", "awaiting_signatures", "broadcast", "confirmed" or "failed"
+    pub status: String,
+    pub channel_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Fields of a new batch
+pub struct NewPayoutBatch<'a> {
+    pub network: &'a str,
+    pub token_symbol: &'a str,
+    pub token_address: &'a str,
+    pub decimals: u8,
+    pub source_address: &'a str,
+    pub method: &'a str,
+    pub total_raw: &'a str,
+    pub total_usd: Option<f64>,
+    pub recipients: &'a [PayoutRecipient],
+    pub tx_uuid: Option<&'a str>,
+    pub safe_tx_hash: Option<&'a str>,
+    pub status: &'a str,
+    pub channel_id: Option<i64>,
+}
+
+const BATCH_COLUMNS: &str = "id, network, token_symbol, token_address, decimals, source_address, method, \
+    total_raw, total_usd, recipients, tx_uuid, safe_tx_hash, status, channel_id, created_at, updated_at";
+
+fn row_to_batch(row: &rusqlite::Row) -> rusqlite::Result<PayoutBatch> {
+    let recipients: String = row.get(9)?;
+    Ok(PayoutBatch {
+        id: row.get(0)?,
+        network: row.get(1)?,
+        token_symbol: row.get(2)?,
+        token_address: row.get(3)?,
+        decimals: row.get(4)?,
+        source_address: row.get(5)?,
+        method: row.get(6)?,
+        total_raw: row.get(7)?,
+        total_usd: row.get(8)?,
+        recipients: serde_json::from_str(&recipients).unwrap_or_default(),
+        tx_uuid: row.get(10)?,
+        safe_tx_hash: row.get(11)?,
+        status: row.get(12)?,
+        channel_id: row.get(13)?,
+        created_at: row.get(14)?,
+        updated_at: row.get(15)?,
+    })
+}
+
+impl Database {
+    /// Record a batch payout. Returns its ID.
+    pub fn insert_payout_batch(&self, batch: &NewPayoutBatch) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let recipients = serde_json::to_string(batch.recipients).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "INSERT INTO payout_batches (network, token_symbol, token_address, decimals, source_address, method,
+                total_raw, total_usd, recipients, tx_uuid, safe_tx_hash, status, channel_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?14)",
+            rusqlite::params![
+                batch.network,
+                batch.token_symbol,
+                batch.token_address,
+                batch.decimals,
+                batch.source_address,
+                batch.method,
+                batch.total_raw,
+                batch.total_usd,
+                recipients,
+                batch.tx_uuid,
+                batch.safe_tx_hash,
+                batch.status,
+                batch.channel_id,
+                now,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get_payout_batch(&self, id: i64) -> SqliteResult<Option<PayoutBatch>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM payout_batches WHERE id = ?1", BATCH_COLUMNS))?;
+        let mut rows = stmt.query_map([id], row_to_batch)?;
+        rows.next().transpose()
+    }
+
+    /// Most recent batches first
+    pub fn list_payout_batches(&self, limit: usize) -> SqliteResult<Vec<PayoutBatch>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM payout_batches ORDER BY id DESC LIMIT ?1",
+            BATCH_COLUMNS
+        ))?;
+        let batches = stmt
+            .query_map([limit as i64], row_to_batch)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(batches)
+    }
+
+    /// Update a batch's status
+    pub fn update_payout_batch_status(&self, id: i64, status: &str) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE payout_batches SET status = ?1, updated_at = ?2 WHERE id = ?3",
+            rusqlite::params![status, Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(rows > 0)
+    }
+}
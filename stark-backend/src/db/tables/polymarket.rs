@@ -0,0 +1,265 @@
+//! Polymarket position management database operations
+//!
+//! Exit rules (take-profit / stop-loss / expiry) evaluated by the scheduler,
+//! plus a record of queued redeem transactions per resolved condition.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+use super::super::Database;
+
+/// Exit rule status values
+pub const EXIT_RULE_ACTIVE: &str = "active";
+pub const EXIT_RULE_TRIGGERED: &str = "triggered";
+pub const EXIT_RULE_FAILED: &str = "failed";
+pub const EXIT_RULE_CANCELLED: &str = "cancelled";
+
+/// Exit rule for one outcome token position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolymarketExitRule {
+    pub id: i64,
+    pub token_id: String,
+    pub market: Option<String>,
+    pub outcome: Option<String>,
+    /// Shares to sell when triggered (None = the whole position)
+    pub size: Option<f64>,
+    /// Sell once the best bid reaches this price
+    pub take_profit: Option<f64>,
+    /// Sell once the best bid falls to this price
+    pub stop_loss: Option<f64>,
+    /// Sell at this time regardless of price
+    pub exit_at: Option<DateTime<Utc>>,
+    /// "active", "triggered", "failed" or "cancelled"
+    pub status: String,
+    /// "take_profit", "stop_loss" or "expiry" once triggered
+    pub trigger_reason: Option<String>,
+    pub last_price: Option<f64>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub exit_order_id: Option<String>,
+    pub exit_price: Option<f64>,
+    pub exit_size: Option<f64>,
+    /// Failed exit attempts so far
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub triggered_at: Option<DateTime<Utc>>,
+}
+
+/// Parameters for a new exit rule
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewPolymarketExitRule {
+    pub token_id: String,
+    pub market: Option<String>,
+    pub outcome: Option<String>,
+    pub size: Option<f64>,
+    pub take_profit: Option<f64>,
+    pub stop_loss: Option<f64>,
+    pub exit_at: Option<DateTime<Utc>>,
+}
+
+fn parse_timestamp(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+fn parse_optional_timestamp(s: Option<String>) -> Option<DateTime<Utc>> {
+    s.as_deref().map(parse_timestamp)
+}
+
+const EXIT_RULE_COLUMNS: &str = "id, token_id, market, outcome, size, take_profit, stop_loss, exit_at,
+     status, trigger_reason, last_price, last_checked_at, exit_order_id, exit_price, exit_size,
+     attempts, last_error, created_at, updated_at, triggered_at";
+
+fn row_to_exit_rule(row: &rusqlite::Row) -> rusqlite::Result<PolymarketExitRule> {
+    let created_at: String = row.get(17)?;
+    let updated_at: String = row.get(18)?;
+    Ok(PolymarketExitRule {
+        id: row.get(0)?,
+        token_id: row.get(1)?,
+        market: row.get(2)?,
+        outcome: row.get(3)?,
+        size: row.get(4)?,
+        take_profit: row.get(5)?,
+        stop_loss: row.get(6)?,
+        exit_at: parse_optional_timestamp(row.get(7)?),
+        status: row.get(8)?,
+        trigger_reason: row.get(9)?,
+        last_price: row.get(10)?,
+        last_checked_at: parse_optional_timestamp(row.get(11)?),
+        exit_order_id: row.get(12)?,
+        exit_price: row.get(13)?,
+        exit_size: row.get(14)?,
+        attempts: row.get(15)?,
+        last_error: row.get(16)?,
+        created_at: parse_timestamp(&created_at),
+        updated_at: parse_timestamp(&updated_at),
+        triggered_at: parse_optional_timestamp(row.get(19)?),
+    })
+}
+
+impl Database {
+    // ============================================
+    // Exit rules
+    // ============================================
+
+    /// Create a new active exit rule
+    pub fn create_polymarket_exit_rule(&self, rule: &NewPolymarketExitRule) -> SqliteResult<PolymarketExitRule> {
+        let id = {
+            let conn = self.conn();
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                "INSERT INTO polymarket_exit_rules
+                 (token_id, market, outcome, size, take_profit, stop_loss, exit_at, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)",
+                rusqlite::params![
+                    rule.token_id,
+                    rule.market,
+                    rule.outcome,
+                    rule.size,
+                    rule.take_profit,
+                    rule.stop_loss,
+                    rule.exit_at.map(|t| t.to_rfc3339()),
+                    EXIT_RULE_ACTIVE,
+                    now,
+                ],
+            )?;
+            conn.last_insert_rowid()
+        };
+
+        self.get_polymarket_exit_rule(id)?
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    /// Get an exit rule by ID
+    pub fn get_polymarket_exit_rule(&self, id: i64) -> SqliteResult<Option<PolymarketExitRule>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM polymarket_exit_rules WHERE id = ?1",
+            EXIT_RULE_COLUMNS
+        ))?;
+        let mut rows = stmt.query_map([id], row_to_exit_rule)?;
+        rows.next().transpose()
+    }
+
+    /// List exit rules, optionally filtered by status (newest first)
+    pub fn list_polymarket_exit_rules(&self, status: Option<&str>) -> SqliteResult<Vec<PolymarketExitRule>> {
+        let conn = self.conn();
+        let rules = match status {
+            Some(status) => {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {} FROM polymarket_exit_rules WHERE status = ?1 ORDER BY id DESC",
+                    EXIT_RULE_COLUMNS
+                ))?;
+                let rows = stmt.query_map([status], row_to_exit_rule)?;
+                rows.filter_map(|r| r.ok()).collect()
+            }
+            None => {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {} FROM polymarket_exit_rules ORDER BY id DESC",
+                    EXIT_RULE_COLUMNS
+                ))?;
+                let rows = stmt.query_map([], row_to_exit_rule)?;
+                rows.filter_map(|r| r.ok()).collect()
+            }
+        };
+        Ok(rules)
+    }
+
+    /// Cancel an active exit rule. Returns false if it does not exist or is no longer active.
+    pub fn cancel_polymarket_exit_rule(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE polymarket_exit_rules SET status = ?1, updated_at = ?2 WHERE id = ?3 AND status = ?4",
+            rusqlite::params![EXIT_RULE_CANCELLED, Utc::now().to_rfc3339(), id, EXIT_RULE_ACTIVE],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Record the price seen on the latest evaluation
+    pub fn record_polymarket_exit_check(&self, id: i64, price: Option<f64>) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE polymarket_exit_rules SET last_price = COALESCE(?1, last_price), last_checked_at = ?2, updated_at = ?2
+             WHERE id = ?3",
+            rusqlite::params![price, now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Mark a rule as triggered with the exit order that was placed
+    pub fn mark_polymarket_exit_triggered(
+        &self,
+        id: i64,
+        reason: &str,
+        order_id: &str,
+        price: f64,
+        size: f64,
+    ) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE polymarket_exit_rules
+             SET status = ?1, trigger_reason = ?2, exit_order_id = ?3, exit_price = ?4, exit_size = ?5,
+                 last_error = NULL, triggered_at = ?6, updated_at = ?6
+             WHERE id = ?7",
+            rusqlite::params![EXIT_RULE_TRIGGERED, reason, order_id, price, size, now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a failed exit attempt. The rule is marked failed once `max_attempts` is reached.
+    pub fn record_polymarket_exit_failure(&self, id: i64, error: &str, max_attempts: i64) -> SqliteResult<()> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE polymarket_exit_rules
+             SET attempts = attempts + 1, last_error = ?1, updated_at = ?2,
+                 status = CASE WHEN attempts + 1 >= ?3 THEN ?4 ELSE status END
+             WHERE id = ?5",
+            rusqlite::params![error, now, max_attempts, EXIT_RULE_FAILED, id],
+        )?;
+        Ok(())
+    }
+
+    /// Number of exits triggered since `since` and their total notional in USDC
+    pub fn polymarket_exit_totals_since(&self, since: DateTime<Utc>) -> SqliteResult<(i64, f64)> {
+        let conn = self.conn();
+        conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(exit_price * exit_size), 0)
+             FROM polymarket_exit_rules WHERE status = ?1 AND triggered_at >= ?2",
+            rusqlite::params![EXIT_RULE_TRIGGERED, since.to_rfc3339()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+
+    // ============================================
+    // Redemptions
+    // ============================================
+
+    /// When a redeem transaction was last queued for a condition
+    pub fn get_polymarket_redemption(&self, condition_id: &str) -> SqliteResult<Option<(String, DateTime<Utc>)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT tx_uuid, queued_at FROM polymarket_redemptions WHERE condition_id = ?1",
+        )?;
+        let mut rows = stmt.query_map([condition_id.to_lowercase()], |row| {
+            let queued_at: String = row.get(1)?;
+            Ok((row.get(0)?, parse_timestamp(&queued_at)))
+        })?;
+        rows.next().transpose()
+    }
+
+    /// Record a queued redeem transaction for a condition (replaces any earlier attempt)
+    pub fn record_polymarket_redemption(&self, condition_id: &str, tx_uuid: &str) -> SqliteResult<()> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR REPLACE INTO polymarket_redemptions (condition_id, tx_uuid, queued_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![condition_id.to_lowercase(), tx_uuid, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+//! Portfolio database operations
+//!
+//! Tracked wallets plus periodic balance snapshots used for holdings and P&L.
+
+use chrono::{DateTime, Utc};
+use rusqlite::Result as SqliteResult;
+use serde::{Deserialize, Serialize};
+
+use super::super::Database;
+
+/// A wallet (EOA or Safe) whose balances are included in portfolio snapshots.
+/// The bot's own wallet is always tracked and is not stored here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioWallet {
+    pub id: i64,
+    pub label: String,
+    pub address: String,
+    /// "wallet" or "safe"
+    pub kind: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A point-in-time portfolio valuation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub id: i64,
+    pub taken_at: DateTime<Utc>,
+    pub total_value_usd: f64,
+    /// Networks or wallets that could not be read (JSON array of strings)
+    pub errors: Vec<String>,
+}
+
+/// One token balance held by one wallet within a snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioBalance {
+    pub wallet_address: String,
+    pub wallet_label: String,
+    pub network: String,
+    pub symbol: String,
+    pub token_address: String,
+    pub decimals: u8,
+    /// Raw on-chain balance (base units, decimal string)
+    pub raw_balance: String,
+    pub balance: f64,
+    pub price_usd: Option<f64>,
+    pub value_usd: Option<f64>,
+}
+
+fn parse_timestamp(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+fn row_to_snapshot(row: &rusqlite::Row) -> rusqlite::Result<PortfolioSnapshot> {
+    let taken_at_str: String = row.get(1)?;
+    let errors_json: Option<String> = row.get(3)?;
+    Ok(PortfolioSnapshot {
+        id: row.get(0)?,
+        taken_at: parse_timestamp(&taken_at_str),
+        total_value_usd: row.get(2)?,
+        errors: errors_json
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+    })
+}
+
+impl Database {
+    // ============================================
+    // Tracked wallets
+    // ============================================
+
+    /// Add a wallet or Safe to the portfolio (address is stored lowercase)
+    pub fn add_portfolio_wallet(&self, label: &str, address: &str, kind: &str) -> SqliteResult<PortfolioWallet> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+        let address = address.to_lowercase();
+
+        conn.execute(
+            "INSERT INTO portfolio_wallets (label, address, kind, enabled, created_at)
+             VALUES (?1, ?2, ?3, 1, ?4)",
+            rusqlite::params![label, address, kind, now],
+        )?;
+
+        Ok(PortfolioWallet {
+            id: conn.last_insert_rowid(),
+            label: label.to_string(),
+            address,
+            kind: kind.to_string(),
+            enabled: true,
+            created_at: parse_timestamp(&now),
+        })
+    }
+
+    /// List tracked wallets
+    pub fn list_portfolio_wallets(&self) -> SqliteResult<Vec<PortfolioWallet>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, label, address, kind, enabled, created_at
+             FROM portfolio_wallets ORDER BY id ASC",
+        )?;
+
+        let wallets = stmt
+            .query_map([], |row| {
+                let created_at_str: String = row.get(5)?;
+                Ok(PortfolioWallet {
+                    id: row.get(0)?,
+                    label: row.get(1)?,
+                    address: row.get(2)?,
+                    kind: row.get(3)?,
+                    enabled: row.get::<_, i64>(4)? != 0,
+                    created_at: parse_timestamp(&created_at_str),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(wallets)
+    }
+
+    /// Enable or disable a tracked wallet
+    pub fn set_portfolio_wallet_enabled(&self, id: i64, enabled: bool) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE portfolio_wallets SET enabled = ?1 WHERE id = ?2",
+            rusqlite::params![enabled as i64, id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Remove a tracked wallet
+    pub fn delete_portfolio_wallet(&self, id: i64) -> SqliteResult<bool> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM portfolio_wallets WHERE id = ?1", [id])?;
+        Ok(rows > 0)
+    }
+
+    // ============================================
+    // Snapshots
+    // ============================================
+
+    /// Store a snapshot and all of its balances in one transaction
+    pub fn save_portfolio_snapshot(
+        &self,
+        total_value_usd: f64,
+        errors: &[String],
+        balances: &[PortfolioBalance],
+    ) -> SqliteResult<i64> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let now = Utc::now().to_rfc3339();
+        let errors_json = if errors.is_empty() {
+            None
+        } else {
+            serde_json::to_string(errors).ok()
+        };
+
+        tx.execute(
+            "INSERT INTO portfolio_snapshots (taken_at, total_value_usd, errors) VALUES (?1, ?2, ?3)",
+            rusqlite::params![now, total_value_usd, errors_json],
+        )?;
+        let snapshot_id = tx.last_insert_rowid();
+
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO portfolio_balances
+                 (snapshot_id, wallet_address, wallet_label, network, symbol, token_address,
+                  decimals, raw_balance, balance, price_usd, value_usd)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for b in balances {
+                stmt.execute(rusqlite::params![
+                    snapshot_id,
+                    b.wallet_address,
+                    b.wallet_label,
+                    b.network,
+                    b.symbol,
+                    b.token_address,
+                    b.decimals,
+                    b.raw_balance,
+                    b.balance,
+                    b.price_usd,
+                    b.value_usd,
+                ])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(snapshot_id)
+    }
+
+    /// Get the most recent snapshot
+    pub fn get_latest_portfolio_snapshot(&self) -> SqliteResult<Option<PortfolioSnapshot>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, taken_at, total_value_usd, errors
+             FROM portfolio_snapshots ORDER BY taken_at DESC, id DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query_map([], row_to_snapshot)?;
+        rows.next().transpose()
+    }
+
+    /// Get the snapshot to measure P&L from for a period starting at `since`:
+    /// the last snapshot taken at or before `since`, or failing that the oldest
+    /// snapshot after it (when history doesn't go back far enough).
+    pub fn get_portfolio_baseline_snapshot(&self, since: DateTime<Utc>) -> SqliteResult<Option<PortfolioSnapshot>> {
+        let conn = self.conn();
+        let since_str = since.to_rfc3339();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, taken_at, total_value_usd, errors
+             FROM portfolio_snapshots WHERE taken_at <= ?1
+             ORDER BY taken_at DESC, id DESC LIMIT 1",
+        )?;
+        if let Some(snapshot) = stmt.query_map([&since_str], row_to_snapshot)?.next().transpose()? {
+            return Ok(Some(snapshot));
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT id, taken_at, total_value_usd, errors
+             FROM portfolio_snapshots WHERE taken_at > ?1
+             ORDER BY taken_at ASC, id ASC LIMIT 1",
+        )?;
+        let mut rows = stmt.query_map([&since_str], row_to_snapshot)?;
+        rows.next().transpose()
+    }
+
+    /// List snapshots taken since a point in time (oldest first)
+    pub fn list_portfolio_snapshots(&self, since: DateTime<Utc>, limit: usize) -> SqliteResult<Vec<PortfolioSnapshot>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, taken_at, total_value_usd, errors FROM (
+                 SELECT id, taken_at, total_value_usd, errors
+                 FROM portfolio_snapshots WHERE taken_at >= ?1
+                 ORDER BY taken_at DESC LIMIT ?2
+             ) ORDER BY taken_at ASC",
+        )?;
+        let snapshots = stmt
+            .query_map(rusqlite::params![since.to_rfc3339(), limit as i64], row_to_snapshot)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(snapshots)
+    }
+
+    /// Get all balances recorded in a snapshot, largest value first
+    pub fn get_portfolio_balances(&self, snapshot_id: i64) -> SqliteResult<Vec<PortfolioBalance>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT wallet_address, wallet_label, network, symbol, token_address,
+                    decimals, raw_balance, balance, price_usd, value_usd
+             FROM portfolio_balances WHERE snapshot_id = ?1
+             ORDER BY COALESCE(value_usd, 0) DESC, symbol ASC",
+        )?;
+        let balances = stmt
+            .query_map([snapshot_id], |row| {
+                Ok(PortfolioBalance {
+                    wallet_address: row.get(0)?,
+                    wallet_label: row.get(1)?,
+                    network: row.get(2)?,
+                    symbol: row.get(3)?,
+                    token_address: row.get(4)?,
+                    decimals: row.get(5)?,
+                    raw_balance: row.get(6)?,
+                    balance: row.get(7)?,
+                    price_usd: row.get(8)?,
+                    value_usd: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(balances)
+    }
+
+    /// Delete snapshots older than the given number of days (balances cascade)
+    pub fn cleanup_old_portfolio_snapshots(&self, keep_days: i64) -> SqliteResult<usize> {
+        let conn = self.conn();
+        let cutoff = (Utc::now() - chrono::Duration::days(keep_days)).to_rfc3339();
+        conn.execute(
+            "DELETE FROM portfolio_balances WHERE snapshot_id IN
+                 (SELECT id FROM portfolio_snapshots WHERE taken_at < ?1)",
+            [&cutoff],
+        )?;
+        conn.execute("DELETE FROM portfolio_snapshots WHERE taken_at < ?1", [&cutoff])
+    }
+}
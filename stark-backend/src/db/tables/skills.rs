@@ -3,7 +3,7 @@
 use chrono::Utc;
 use rusqlite::Result as SqliteResult;
 
-use crate::skills::{DbSkill, DbSkillScript};
+use crate::skills::{DbSkill, DbSkillResource, DbSkillScript, SkillResourceKind};
 use super::super::Database;
 
 /// Compare two semantic version strings (e.g., "1.0.0", "2.1.3")
@@ -347,4 +347,91 @@ impl Database {
         )?;
         Ok(rows_affected as i64)
     }
+
+    // ============================================
+    // Skill Resources CRUD methods
+    // ============================================
+
+    /// Create or update a resource (ABI, web3 presets, validator) bundled with a skill
+    pub fn create_skill_resource(&self, resource: &DbSkillResource) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let now = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "INSERT INTO skill_resources (skill_id, kind, name, content, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(skill_id, kind, name) DO UPDATE SET
+                content = excluded.content",
+            rusqlite::params![
+                resource.skill_id,
+                resource.kind.as_str(),
+                resource.name,
+                resource.content,
+                now
+            ],
+        )?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get resources for a skill by skill name
+    pub fn get_skill_resources_by_name(&self, skill_name: &str) -> SqliteResult<Vec<DbSkillResource>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT sr.id, sr.skill_id, sr.kind, sr.name, sr.content, sr.created_at
+             FROM skill_resources sr
+             JOIN skills s ON s.id = sr.skill_id
+             WHERE s.name = ?1 ORDER BY sr.kind, sr.name"
+        )?;
+
+        let resources: Vec<DbSkillResource> = stmt
+            .query_map([skill_name], Self::row_to_skill_resource)?
+            .filter_map(|r| r.ok())
+            .flatten()
+            .collect();
+
+        Ok(resources)
+    }
+
+    /// Names of all skills that have bundled resources
+    pub fn list_skills_with_resources(&self) -> SqliteResult<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT s.name FROM skill_resources sr
+             JOIN skills s ON s.id = sr.skill_id ORDER BY s.name"
+        )?;
+
+        let names: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(names)
+    }
+
+    /// Delete all resources for a skill
+    pub fn delete_skill_resources(&self, skill_id: i64) -> SqliteResult<i64> {
+        let conn = self.conn();
+        let rows_affected = conn.execute(
+            "DELETE FROM skill_resources WHERE skill_id = ?1",
+            [skill_id],
+        )?;
+        Ok(rows_affected as i64)
+    }
+
+    /// Rows with an unknown kind (written by a newer version) are skipped
+    fn row_to_skill_resource(row: &rusqlite::Row) -> rusqlite::Result<Option<DbSkillResource>> {
+        let kind: String = row.get(2)?;
+        let Some(kind) = SkillResourceKind::from_str(&kind) else {
+            return Ok(None);
+        };
+        Ok(Some(DbSkillResource {
+            id: row.get(0)?,
+            skill_id: row.get(1)?,
+            kind,
+            name: row.get(3)?,
+            content: row.get(4)?,
+            created_at: row.get(5)?,
+        }))
+    }
 }
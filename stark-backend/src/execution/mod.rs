@@ -4,6 +4,9 @@
 //! It manages a hierarchical task tree and emits gateway events for frontend
 //! display of execution progress (similar to Claude Code's CLI display).
 //!
+//! When a database is attached, executions are also persisted as traces
+//! (see `trace`) that can be browsed, exported and replayed.
+//!
 //! Also provides session lane serialization to prevent race conditions when
 //! multiple requests arrive for the same session.
 
@@ -11,6 +14,7 @@ mod tracker;
 mod pending_confirmation;
 mod process_manager;
 mod session_lanes;
+pub mod trace;
 
 pub use tracker::ExecutionTracker;
 pub use pending_confirmation::{PendingConfirmation, PendingConfirmationManager};
@@ -218,6 +218,18 @@ impl PendingConfirmationManager {
         None
     }
 
+    /// Unexpired pending confirmations, optionally limited to one channel
+    pub fn list_pending(&self, channel_id: Option<i64>) -> Vec<PendingConfirmation> {
+        let mut pending: Vec<PendingConfirmation> = self
+            .pending
+            .iter()
+            .filter(|c| channel_id.is_none_or(|id| c.channel_id == id) && !c.is_expired())
+            .map(|c| c.value().clone())
+            .collect();
+        pending.sort_by_key(|c| c.channel_id);
+        pending
+    }
+
     /// Confirm and remove a pending confirmation
     /// Returns the confirmation if it exists and is not expired
     pub fn confirm(&self, channel_id: i64) -> Option<PendingConfirmation> {
@@ -110,11 +110,13 @@ impl SessionLaneManager {
         let semaphore = self.get_or_create_lane(session_id);
 
         // Acquire the permit (this will block if another request has it)
+        let wait_started = Instant::now();
         let permit = semaphore
             .clone()
             .acquire_owned()
             .await
             .expect("Semaphore should not be closed");
+        crate::metrics::METRICS.session_lane_wait.observe_duration(&[], wait_started.elapsed());
 
         // Update metadata
         self.metadata
@@ -0,0 +1,306 @@
+//! Export and replay of persisted execution traces
+//!
+//! Traces are written by `ExecutionTracker` when it has a database attached.
+//! This module turns them into shareable formats (plain JSON, OpenTelemetry
+//! OTLP/JSON spans) and back into a response queue for `MockAiClient`, so a
+//! bad run can be reproduced offline without calling the AI provider or
+//! re-running side-effecting tools.
+
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::ai::{AiError, AiResponse};
+use crate::models::{ExecutionTrace, TraceSpan, TraceSpanKind};
+use crate::tools::ToolResult;
+
+/// Service name reported in OpenTelemetry resource attributes
+const OTEL_SERVICE_NAME: &str = "starkbot";
+
+/// OTLP status codes
+const OTEL_STATUS_OK: u8 = 1;
+const OTEL_STATUS_ERROR: u8 = 2;
+
+/// Export a trace and its spans as a single JSON document
+pub fn export_json(trace: &ExecutionTrace, spans: &[TraceSpan]) -> Value {
+    json!({
+        "trace": trace,
+        "spans": spans,
+    })
+}
+
+/// Export a trace as OTLP/JSON (`resourceSpans`), importable by Jaeger, Tempo, etc.
+///
+/// The execution becomes the root span; AI calls, tool calls and tasks are its children.
+pub fn export_otel(trace: &ExecutionTrace, spans: &[TraceSpan]) -> Value {
+    let trace_id = otel_trace_id(&trace.execution_id);
+    let root_span_id = otel_span_id(&trace.execution_id);
+    let root_end = trace.completed_at.unwrap_or(trace.started_at);
+
+    let mut root_attributes = vec![
+        otel_attr("starkbot.channel_id", json!({ "intValue": trace.channel_id.to_string() })),
+        otel_attr("starkbot.mode", json!({ "stringValue": trace.mode })),
+        otel_attr("starkbot.status", json!({ "stringValue": trace.status })),
+        otel_attr("starkbot.ai_calls", json!({ "intValue": trace.ai_calls.to_string() })),
+        otel_attr("starkbot.tool_calls", json!({ "intValue": trace.tool_calls.to_string() })),
+        otel_attr("starkbot.tokens_used", json!({ "intValue": trace.tokens_used.to_string() })),
+        otel_attr("starkbot.x402_spend", json!({ "doubleValue": trace.x402_spend })),
+    ];
+    if let Some(session_id) = trace.session_id {
+        root_attributes.push(otel_attr("starkbot.session_id", json!({ "intValue": session_id.to_string() })));
+    }
+    if let Some(ref message) = trace.user_message {
+        root_attributes.push(otel_attr("starkbot.user_message", json!({ "stringValue": message })));
+    }
+
+    let root_ok = trace.status != "error";
+    let mut otel_spans = vec![json!({
+        "traceId": trace_id,
+        "spanId": root_span_id,
+        "name": format!("execution {}", trace.mode),
+        "kind": 1,
+        "startTimeUnixNano": unix_nanos(trace.started_at),
+        "endTimeUnixNano": unix_nanos(root_end),
+        "attributes": root_attributes,
+        "status": otel_status(root_ok, trace.outcome.as_deref().filter(|_| !root_ok)),
+    })];
+
+    for span in spans {
+        let mut attributes = vec![
+            otel_attr("starkbot.kind", json!({ "stringValue": span.kind.as_str() })),
+            otel_attr("starkbot.tokens", json!({ "intValue": span.tokens.to_string() })),
+        ];
+        if let Some(ref description) = span.description {
+            attributes.push(otel_attr("starkbot.description", json!({ "stringValue": description })));
+        }
+        if let Some(ref input) = span.input {
+            attributes.push(otel_attr("starkbot.input", json!({ "stringValue": input.to_string() })));
+        }
+        if let Some(ref output) = span.output {
+            attributes.push(otel_attr("starkbot.output", json!({ "stringValue": output.to_string() })));
+        }
+        if let Some(ref amount) = span.x402_amount {
+            attributes.push(otel_attr("starkbot.x402_amount", json!({ "stringValue": amount })));
+        }
+        if let Some(ref asset) = span.x402_asset {
+            attributes.push(otel_attr("starkbot.x402_asset", json!({ "stringValue": asset })));
+        }
+
+        let parent = span
+            .parent_span_id
+            .as_deref()
+            .map(otel_span_id)
+            .unwrap_or_else(|| root_span_id.clone());
+
+        otel_spans.push(json!({
+            "traceId": trace_id,
+            "spanId": otel_span_id(&span.span_id),
+            "parentSpanId": parent,
+            "name": span_name(span),
+            "kind": if span.kind == TraceSpanKind::AiCall { 3 } else { 1 },
+            "startTimeUnixNano": unix_nanos(span.started_at),
+            "endTimeUnixNano": unix_nanos(span.completed_at),
+            "attributes": attributes,
+            "status": otel_status(span.success, span.error.as_deref()),
+        }));
+    }
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    otel_attr("service.name", json!({ "stringValue": OTEL_SERVICE_NAME })),
+                    otel_attr("service.version", json!({ "stringValue": env!("CARGO_PKG_VERSION") })),
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "stark-backend.execution" },
+                "spans": otel_spans,
+            }]
+        }]
+    })
+}
+
+/// Rebuild the AI response queue of a recorded run, in call order.
+/// Failed AI calls are replayed as errors.
+pub fn replay_responses(spans: &[TraceSpan]) -> Vec<Result<AiResponse, AiError>> {
+    spans
+        .iter()
+        .filter(|s| s.kind == TraceSpanKind::AiCall)
+        .map(|s| {
+            if !s.success {
+                return Err(AiError::new(s.error.clone().unwrap_or_else(|| "AI call failed".to_string())));
+            }
+            s.output
+                .clone()
+                .and_then(|v| serde_json::from_value::<AiResponse>(v).ok())
+                .ok_or_else(|| AiError::new("Recorded AI response could not be decoded"))
+        })
+        .collect()
+}
+
+/// Recorded tool results of a run, in call order, keyed by tool name.
+/// Replays hand these back instead of executing tools for real.
+pub fn recorded_tool_results(spans: &[TraceSpan]) -> VecDeque<(String, ToolResult)> {
+    spans
+        .iter()
+        .filter(|s| s.kind == TraceSpanKind::ToolCall)
+        .map(|s| {
+            let result = s
+                .output
+                .clone()
+                .and_then(|v| serde_json::from_value::<ToolResult>(v).ok())
+                .unwrap_or_else(|| ToolResult::error(s.error.clone().unwrap_or_else(|| "Tool result not recorded".to_string())));
+            (s.name.clone(), result)
+        })
+        .collect()
+}
+
+fn span_name(span: &TraceSpan) -> String {
+    match span.kind {
+        TraceSpanKind::AiCall => "ai.generate".to_string(),
+        TraceSpanKind::ToolCall => format!("tool {}", span.name),
+        TraceSpanKind::Task => format!("task {}", span.name),
+    }
+}
+
+fn otel_attr(key: &str, value: Value) -> Value {
+    json!({ "key": key, "value": value })
+}
+
+fn otel_status(ok: bool, message: Option<&str>) -> Value {
+    if ok {
+        json!({ "code": OTEL_STATUS_OK })
+    } else {
+        json!({ "code": OTEL_STATUS_ERROR, "message": message.unwrap_or("") })
+    }
+}
+
+/// 32 hex chars: the execution UUID without dashes
+fn otel_trace_id(execution_id: &str) -> String {
+    let hex: String = execution_id.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    format!("{:0>32}", hex.chars().take(32).collect::<String>())
+}
+
+/// 16 hex chars taken from a span/task UUID
+fn otel_span_id(id: &str) -> String {
+    let hex: String = id.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+    format!("{:0>16}", hex.chars().take(16).collect::<String>())
+}
+
+fn unix_nanos(at: DateTime<Utc>) -> String {
+    at.timestamp_nanos_opt().unwrap_or(0).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ToolCall;
+
+    fn sample_trace() -> ExecutionTrace {
+        let now = Utc::now();
+        ExecutionTrace {
+            execution_id: "6f1c2b9a-0d3e-4a5b-8c7d-1e2f3a4b5c6d".to_string(),
+            channel_id: 1,
+            session_id: Some(7),
+            chat_id: None,
+            mode: "execute".to_string(),
+            user_message: Some("check my balance".to_string()),
+            status: "completed".to_string(),
+            outcome: Some("You have 1 ETH".to_string()),
+            replay_of: None,
+            started_at: now,
+            completed_at: Some(now),
+            duration_ms: Some(0),
+            ai_calls: 2,
+            tool_calls: 1,
+            tokens_used: 42,
+            x402_spend: 0.0,
+        }
+    }
+
+    fn span(kind: TraceSpanKind, name: &str, output: Option<Value>, success: bool) -> TraceSpan {
+        let now = Utc::now();
+        TraceSpan {
+            span_id: uuid::Uuid::new_v4().to_string(),
+            execution_id: sample_trace().execution_id,
+            parent_span_id: None,
+            kind,
+            name: name.to_string(),
+            description: None,
+            success,
+            error: if success { None } else { Some("boom".to_string()) },
+            input: None,
+            output,
+            started_at: now,
+            completed_at: now,
+            duration_ms: 0,
+            tokens: 0,
+            x402_amount: None,
+            x402_asset: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_responses_round_trip() {
+        let call = ToolCall {
+            id: "call_1".to_string(),
+            name: "web3_balance".to_string(),
+            arguments: json!({ "network": "base" }),
+        };
+        let first = AiResponse::with_tools(String::new(), vec![call]);
+        let second = AiResponse::text("You have 1 ETH".to_string());
+        let spans = vec![
+            span(TraceSpanKind::AiCall, "ai", Some(serde_json::to_value(&first).unwrap()), true),
+            span(
+                TraceSpanKind::ToolCall,
+                "web3_balance",
+                Some(serde_json::to_value(ToolResult::success("1 ETH")).unwrap()),
+                true,
+            ),
+            span(TraceSpanKind::Task, "thinking", None, true),
+            span(TraceSpanKind::AiCall, "ai", Some(serde_json::to_value(&second).unwrap()), true),
+            span(TraceSpanKind::AiCall, "ai", None, false),
+        ];
+
+        let responses = replay_responses(&spans);
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0].as_ref().unwrap().tool_calls[0].name, "web3_balance");
+        assert_eq!(responses[1].as_ref().unwrap().content, "You have 1 ETH");
+        assert_eq!(responses[2].as_ref().unwrap_err().message, "boom");
+
+        let tools = recorded_tool_results(&spans);
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].0, "web3_balance");
+        assert!(tools[0].1.success);
+        assert_eq!(tools[0].1.content, "1 ETH");
+    }
+
+    #[test]
+    fn test_export_otel_ids_and_status() {
+        let trace = sample_trace();
+        let spans = vec![
+            span(TraceSpanKind::AiCall, "ai", None, true),
+            span(TraceSpanKind::ToolCall, "web3_balance", None, false),
+        ];
+        let doc = export_otel(&trace, &spans);
+        let otel_spans = doc["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(otel_spans.len(), 3);
+
+        let root = &otel_spans[0];
+        assert_eq!(root["traceId"], "6f1c2b9a0d3e4a5b8c7d1e2f3a4b5c6d");
+        assert_eq!(root["spanId"], "6f1c2b9a0d3e4a5b");
+        assert_eq!(root["status"]["code"], 1);
+
+        let tool = &otel_spans[2];
+        assert_eq!(tool["parentSpanId"], root["spanId"]);
+        assert_eq!(tool["name"], "tool web3_balance");
+        assert_eq!(tool["status"]["code"], 2);
+        assert_eq!(tool["spanId"].as_str().unwrap().len(), 16);
+        assert_eq!(
+            doc["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "starkbot"
+        );
+    }
+}
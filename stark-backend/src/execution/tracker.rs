@@ -1,6 +1,11 @@
+use crate::ai::AiResponse;
+use crate::db::Database;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
-use crate::models::{ExecutionTask, TaskMetrics, TaskStatus, TaskType};
+use crate::metrics::METRICS;
+use crate::models::{ExecutionTask, TaskMetrics, TaskStatus, TaskType, TraceSpan, TraceSpanKind};
+use crate::tools::ToolResult;
+use chrono::{Duration, Utc};
 use dashmap::DashMap;
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
@@ -9,6 +14,8 @@ use tokio_util::sync::CancellationToken;
 ///
 /// This service manages the hierarchical task tree for execution tracking,
 /// emitting real-time events for the frontend to display progress.
+/// With a database attached (`with_db`), every execution is also persisted
+/// as a trace of AI calls, tool calls and tasks.
 pub struct ExecutionTracker {
     /// Event broadcaster for sending gateway events
     broadcaster: Arc<EventBroadcaster>,
@@ -30,6 +37,8 @@ pub struct ExecutionTracker {
     pending_task_deletions: DashMap<i64, Vec<u32>>,
     /// Current planner tasks per channel (for API access on page refresh)
     channel_planner_tasks: DashMap<i64, Vec<crate::ai::multi_agent::types::PlannerTask>>,
+    /// Database for persisting execution traces (None = in-memory only)
+    db: Option<Arc<Database>>,
 }
 
 impl ExecutionTracker {
@@ -46,9 +55,16 @@ impl ExecutionTracker {
             session_cancellation_tokens: DashMap::new(),
             pending_task_deletions: DashMap::new(),
             channel_planner_tasks: DashMap::new(),
+            db: None,
         }
     }
 
+    /// Persist execution traces to the given database
+    pub fn with_db(mut self, db: Arc<Database>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
     /// Get a cancellation token for a channel
     /// Creates a new token if one doesn't exist
     pub fn get_cancellation_token(&self, channel_id: i64) -> CancellationToken {
@@ -132,6 +148,9 @@ impl ExecutionTracker {
         if let Some(mut task) = self.tasks.get_mut(&execution_id) {
             task.session_id = Some(session_id);
         }
+        if let Some(Err(e)) = self.db.as_ref().map(|db| db.set_execution_trace_session(&execution_id, session_id)) {
+            log::warn!("[EXECUTION_TRACKER] Failed to set trace session: {}", e);
+        }
 
         // Also track by session_id for session-based cancellation
         self.session_executions.insert(session_id, execution_id.clone());
@@ -336,6 +355,20 @@ impl ExecutionTracker {
         self.channel_executions.insert(channel_id, execution_id.clone());
         self.tasks.insert(execution_id.clone(), task.clone());
 
+        let persisted = self.db.as_ref().map(|db| {
+            db.insert_execution_trace(
+                &execution_id,
+                channel_id,
+                chat_id,
+                mode,
+                user_message,
+                task.started_at.unwrap_or(task.created_at),
+            )
+        });
+        if let Some(Err(e)) = persisted {
+            log::warn!("[EXECUTION_TRACKER] Failed to persist execution trace: {}", e);
+        }
+
         // Emit event with description
         self.broadcaster.broadcast(GatewayEvent::execution_started(
             channel_id,
@@ -716,6 +749,7 @@ impl ExecutionTracker {
     pub fn complete_task(&self, task_id: &str) {
         if let Some(mut task) = self.tasks.get_mut(task_id) {
             task.complete();
+            self.persist_task_span(&task, None);
             self.broadcaster.broadcast(GatewayEvent::task_completed(
                 task_id,
                 task.channel_id,
@@ -730,6 +764,7 @@ impl ExecutionTracker {
     pub fn complete_task_with_error(&self, task_id: &str, error: &str) {
         if let Some(mut task) = self.tasks.get_mut(task_id) {
             task.complete_with_error(error);
+            self.persist_task_span(&task, Some(error));
             self.broadcaster.broadcast(GatewayEvent::task_completed(
                 task_id,
                 task.channel_id,
@@ -745,6 +780,7 @@ impl ExecutionTracker {
     /// Aggregates metrics from all child tasks
     pub fn complete_execution(&self, channel_id: i64) {
         if let Some((_, execution_id)) = self.channel_executions.remove(&channel_id) {
+            let cancelled = self.is_cancelled(channel_id);
             // Aggregate metrics from all tasks in this execution
             let mut total_metrics = TaskMetrics::default();
             let mut task_ids_to_remove = Vec::new();
@@ -765,6 +801,18 @@ impl ExecutionTracker {
                 total_metrics.duration_ms = root_task.metrics.duration_ms;
             }
 
+            if let Some(ref db) = self.db {
+                let status = if cancelled { "cancelled" } else { "completed" };
+                if let Err(e) = db.finish_execution_trace(
+                    &execution_id,
+                    status,
+                    Utc::now(),
+                    total_metrics.duration_ms.unwrap_or(0),
+                ) {
+                    log::warn!("[EXECUTION_TRACKER] Failed to finish execution trace: {}", e);
+                }
+            }
+
             // Emit completion event
             self.broadcaster.broadcast(GatewayEvent::execution_completed(
                 channel_id,
@@ -779,6 +827,166 @@ impl ExecutionTracker {
         }
     }
 
+    /// Record how the current execution of a channel ended ("completed" with the
+    /// final response, or "error" with the error message). Applied to the trace
+    /// when the execution completes.
+    pub fn set_execution_outcome(&self, channel_id: i64, status: &str, outcome: &str) {
+        let (Some(db), Some(execution_id)) = (self.db.as_ref(), self.get_execution_id(channel_id)) else {
+            return;
+        };
+        if let Err(e) = db.set_execution_trace_outcome(&execution_id, status, outcome) {
+            log::warn!("[EXECUTION_TRACKER] Failed to record execution outcome: {}", e);
+        }
+    }
+
+    /// Record one AI call of the current execution in metrics and, with a
+    /// database attached, persist it as a span.
+    ///
+    /// `input` is a summary of what was sent (message/tool counts), `input_tokens`
+    /// the estimated prompt size. The full response is stored so the run can be
+    /// replayed against `MockAiClient`.
+    pub fn record_ai_call(
+        &self,
+        channel_id: i64,
+        archetype: &str,
+        input: serde_json::Value,
+        input_tokens: u64,
+        result: Result<&AiResponse, &str>,
+        duration_ms: u64,
+    ) {
+        let output_tokens = result
+            .map(|response| {
+                let output_text = format!(
+                    "{}{}",
+                    response.content,
+                    serde_json::to_string(&response.tool_calls).unwrap_or_default()
+                );
+                crate::context::estimate_tokens(&output_text).max(0) as u64
+            })
+            .unwrap_or(0);
+        METRICS.record_ai_call(
+            archetype,
+            result.is_ok(),
+            std::time::Duration::from_millis(duration_ms),
+            input_tokens,
+            output_tokens,
+        );
+
+        let (Some(db), Some(execution_id)) = (self.db.as_ref(), self.get_execution_id(channel_id)) else {
+            return;
+        };
+        let completed_at = Utc::now();
+        let mut span = TraceSpan {
+            span_id: uuid::Uuid::new_v4().to_string(),
+            execution_id,
+            parent_span_id: None,
+            kind: TraceSpanKind::AiCall,
+            name: "ai".to_string(),
+            description: None,
+            success: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+            input: Some(input),
+            output: None,
+            started_at: completed_at - Duration::milliseconds(duration_ms as i64),
+            completed_at,
+            duration_ms,
+            tokens: input_tokens + output_tokens,
+            x402_amount: None,
+            x402_asset: None,
+        };
+        if let Ok(response) = result {
+            span.description = Some(format!(
+                "{} tool call(s), stop: {}",
+                response.tool_calls.len(),
+                response.stop_reason.as_deref().unwrap_or("unknown")
+            ));
+            if let Some(ref payment) = response.x402_payment {
+                span.x402_amount = Some(payment.amount_formatted.clone());
+                span.x402_asset = Some(payment.asset.clone());
+            }
+            span.output = serde_json::to_value(response).ok();
+        }
+        if let Err(e) = db.insert_execution_span(&span) {
+            log::warn!("[EXECUTION_TRACKER] Failed to persist AI call span: {}", e);
+        }
+    }
+
+    /// Record one tool call of the current execution in metrics and, with a
+    /// database attached, persist it with its arguments and raw result
+    pub fn record_tool_call(
+        &self,
+        channel_id: i64,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+        result: &ToolResult,
+        duration_ms: u64,
+    ) {
+        METRICS.record_tool_call(tool_name, result.success, std::time::Duration::from_millis(duration_ms));
+
+        let (Some(db), Some(execution_id)) = (self.db.as_ref(), self.get_execution_id(channel_id)) else {
+            return;
+        };
+        let completed_at = Utc::now();
+        let span = TraceSpan {
+            span_id: uuid::Uuid::new_v4().to_string(),
+            execution_id,
+            parent_span_id: None,
+            kind: TraceSpanKind::ToolCall,
+            name: tool_name.to_string(),
+            description: None,
+            success: result.success,
+            error: result.error.clone(),
+            input: Some(arguments.clone()),
+            output: serde_json::to_value(result).ok(),
+            started_at: completed_at - Duration::milliseconds(duration_ms as i64),
+            completed_at,
+            duration_ms,
+            tokens: 0,
+            x402_amount: None,
+            x402_asset: None,
+        };
+        if let Err(e) = db.insert_execution_span(&span) {
+            log::warn!("[EXECUTION_TRACKER] Failed to persist tool call span: {}", e);
+        }
+    }
+
+    /// Persist a finished child task (thinking, planning, ...) as a span.
+    /// The root execution task is represented by the trace itself.
+    fn persist_task_span(&self, task: &ExecutionTask, error: Option<&str>) {
+        let Some(ref db) = self.db else {
+            return;
+        };
+        let Some(ref parent_id) = task.parent_id else {
+            return;
+        };
+        let Some(execution_id) = self.get_execution_id(task.channel_id) else {
+            return;
+        };
+        let completed_at = task.completed_at.unwrap_or_else(Utc::now);
+        let span = TraceSpan {
+            span_id: task.id.clone(),
+            // Direct children of the root are parented to the trace's root span
+            parent_span_id: Some(parent_id.clone()).filter(|p| *p != execution_id),
+            execution_id,
+            kind: TraceSpanKind::Task,
+            name: task.task_type.to_string(),
+            description: Some(task.description.clone()),
+            success: error.is_none(),
+            error: error.map(str::to_string),
+            input: None,
+            output: None,
+            started_at: task.started_at.unwrap_or(task.created_at),
+            completed_at,
+            duration_ms: task.duration_ms().unwrap_or(0),
+            tokens: 0,
+            x402_amount: None,
+            x402_asset: None,
+        };
+        if let Err(e) = db.insert_execution_span(&span) {
+            log::warn!("[EXECUTION_TRACKER] Failed to persist task span: {}", e);
+        }
+    }
+
     /// Get a task by ID
     pub fn get_task(&self, task_id: &str) -> Option<ExecutionTask> {
         self.tasks.get(task_id).map(|t| t.clone())
@@ -891,4 +1099,59 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_execution_trace_persisted() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let tracker = ExecutionTracker::new(Arc::new(EventBroadcaster::new())).with_db(db.clone());
+
+        let execution_id = tracker.start_execution_for_session(9, 1, None, "execute", Some("send 1 usdc"));
+        let thinking = tracker.start_task(1, &execution_id, Some(&execution_id), TaskType::Thinking, "Analyzing request", None);
+        tracker.complete_task(&thinking);
+
+        let mut response = AiResponse::text("Sent".to_string());
+        response.x402_payment = Some(crate::x402::X402PaymentInfo {
+            amount: "1500".to_string(),
+            amount_formatted: "0.0015".to_string(),
+            asset: "USDC".to_string(),
+            pay_to: "0x0".to_string(),
+            resource: None,
+            tx_hash: None,
+            status: crate::x402::PaymentStatus::Confirmed,
+            timestamp: Utc::now(),
+        });
+        tracker.record_ai_call(1, "kimi", serde_json::json!({"messages": 2}), 100, Ok(&response), 250);
+        tracker.record_tool_call(1, "web3_transfer", &serde_json::json!({"amount": "1"}), &ToolResult::success("ok"), 40);
+        tracker.set_execution_outcome(1, "completed", "Sent");
+        tracker.complete_execution(1);
+
+        let trace = db.get_execution_trace(&execution_id).unwrap().unwrap();
+        assert_eq!(trace.session_id, Some(9));
+        assert_eq!(trace.status, "completed");
+        assert_eq!(trace.outcome.as_deref(), Some("Sent"));
+        assert_eq!(trace.ai_calls, 1);
+        assert_eq!(trace.tool_calls, 1);
+        assert!(trace.tokens_used > 100);
+        assert!((trace.x402_spend - 0.0015).abs() < 1e-9);
+        assert!(trace.completed_at.is_some());
+
+        let spans = db.list_execution_spans(&execution_id).unwrap();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0].kind, TraceSpanKind::Task);
+        assert_eq!(spans[0].parent_span_id, None);
+        assert_eq!(spans[2].name, "web3_transfer");
+        assert_eq!(db.list_execution_traces(Some(9), None, 10, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_cancelled_execution_trace() {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        let tracker = ExecutionTracker::new(Arc::new(EventBroadcaster::new())).with_db(db.clone());
+
+        let execution_id = tracker.start_execution(2, None, "execute", Some("long task"));
+        tracker.cancel_execution(2);
+
+        let trace = db.get_execution_trace(&execution_id).unwrap().unwrap();
+        assert_eq!(trace.status, "cancelled");
+    }
 }
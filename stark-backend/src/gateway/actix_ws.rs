@@ -2,16 +2,18 @@
 //! This allows WebSocket connections on the same port as the HTTP server,
 //! which is required for platforms like DigitalOcean App Platform that only expose one port.
 
-use crate::channels::ChannelManager;
 use crate::db::Database;
-use crate::gateway::events::EventBroadcaster;
 use crate::gateway::methods;
-use crate::gateway::protocol::{ChannelIdParams, RpcError, RpcRequest, RpcResponse};
-use crate::tx_queue::TxQueueManager;
+use crate::gateway::protocol::{ChannelIdParams, IdParams, RpcError, RpcRequest, RpcResponse, API_VERSION};
+use crate::gateway::schema;
+use crate::middleware::session_auth::{self, OperatorIdentity};
+use crate::models::OperatorRole;
 use crate::wallet::WalletProvider;
+use crate::AppState;
 use actix_web::{web, HttpRequest, HttpResponse};
 use actix_ws::AggregatedMessage;
 use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use std::sync::Arc;
 use std::time::Duration;
@@ -30,30 +32,12 @@ struct AuthParams {
 pub async fn ws_handler(
     req: HttpRequest,
     stream: web::Payload,
-    db: web::Data<Arc<Database>>,
-    channel_manager: web::Data<Arc<ChannelManager>>,
-    broadcaster: web::Data<Arc<EventBroadcaster>>,
-    tx_queue: web::Data<Arc<TxQueueManager>>,
-    wallet_provider: web::Data<Option<Arc<dyn WalletProvider>>>,
+    state: web::Data<AppState>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let (response, session, msg_stream) = actix_ws::handle(&req, stream)?;
 
     // Spawn the WebSocket handler task
-    let db = db.get_ref().clone();
-    let channel_manager = channel_manager.get_ref().clone();
-    let broadcaster = broadcaster.get_ref().clone();
-    let tx_queue = tx_queue.get_ref().clone();
-    let wallet_provider = wallet_provider.get_ref().clone();
-
-    actix_web::rt::spawn(handle_ws_connection(
-        session,
-        msg_stream,
-        db,
-        channel_manager,
-        broadcaster,
-        tx_queue,
-        wallet_provider,
-    ));
+    actix_web::rt::spawn(handle_ws_connection(session, msg_stream, state));
 
     Ok(response)
 }
@@ -61,11 +45,7 @@ pub async fn ws_handler(
 async fn handle_ws_connection(
     mut session: actix_ws::Session,
     msg_stream: actix_ws::MessageStream,
-    db: Arc<Database>,
-    channel_manager: Arc<ChannelManager>,
-    broadcaster: Arc<EventBroadcaster>,
-    tx_queue: Arc<TxQueueManager>,
-    wallet_provider: Option<Arc<dyn WalletProvider>>,
+    state: web::Data<AppState>,
 ) {
     log::info!("New Actix WebSocket connection");
 
@@ -75,14 +55,20 @@ async fn handle_ws_connection(
         .max_continuation_size(64 * 1024);
 
     // Phase 1: Authentication required before full access
-    let authenticated = match tokio::time::timeout(
+    let operator = match tokio::time::timeout(
         Duration::from_secs(AUTH_TIMEOUT_SECS),
-        wait_for_auth(&mut session, &mut msg_stream, &db),
+        wait_for_auth(
+            &mut session,
+            &mut msg_stream,
+            &state.db,
+            state.wallet_provider.as_ref(),
+            state.config.login_admin_public_address.as_deref(),
+        ),
     )
     .await
     {
-        Ok(Ok(true)) => true,
-        Ok(Ok(false)) => {
+        Ok(Ok(Some(operator))) => operator,
+        Ok(Ok(None)) => {
             log::warn!("Gateway client failed authentication");
             let _ = session.close(None).await;
             return;
@@ -106,15 +92,15 @@ async fn handle_ws_connection(
         }
     };
 
-    if !authenticated {
-        let _ = session.close(None).await;
-        return;
-    }
-
-    log::info!("Gateway client authenticated successfully");
+    log::info!(
+        "Gateway client authenticated as {} ({})",
+        operator.public_address,
+        operator.role
+    );
 
     // Phase 2: Full access after authentication
     // Subscribe to events
+    let broadcaster = state.broadcaster.clone();
     let (client_id, mut event_rx) = broadcaster.subscribe();
     log::info!(
         "Gateway client {} subscribed to events (total: {} clients)",
@@ -164,7 +150,7 @@ async fn handle_ws_connection(
         match msg_result {
             Ok(AggregatedMessage::Text(text)) => {
                 log::debug!("[DATAGRAM] <<< FROM AGENT (RPC request):\n{}", text);
-                let response = process_request(&text, &db, &channel_manager, &broadcaster, &tx_queue, &wallet_provider).await;
+                let response = process_request(&text, &operator, &state).await;
                 if let Ok(json) = serde_json::to_string(&response) {
                     let _ = tx.send(json).await;
                 }
@@ -197,7 +183,9 @@ async fn wait_for_auth(
     session: &mut actix_ws::Session,
     msg_stream: &mut (impl StreamExt<Item = Result<AggregatedMessage, actix_ws::ProtocolError>> + Unpin),
     db: &Arc<Database>,
-) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    wallet_provider: Option<&Arc<dyn WalletProvider>>,
+    admin_address: Option<&str>,
+) -> Result<Option<OperatorIdentity>, Box<dyn std::error::Error + Send + Sync>> {
     while let Some(msg_result) = msg_stream.next().await {
         match msg_result {
             Ok(AggregatedMessage::Text(text)) => {
@@ -238,15 +226,42 @@ async fn wait_for_auth(
 
                         // Validate token against database
                         match db.validate_session(&params.token) {
-                            Ok(Some(_session)) => {
+                            Ok(Some(auth_session)) => {
+                                let operator = match session_auth::resolve_session_operator(
+                                    db,
+                                    wallet_provider,
+                                    admin_address,
+                                    &auth_session,
+                                )
+                                .await
+                                {
+                                    Some(operator) => operator,
+                                    None => {
+                                        let response = RpcResponse::error(
+                                            request.id,
+                                            RpcError::new(
+                                                -32003,
+                                                "Session is not linked to an operator account".to_string(),
+                                            ),
+                                        );
+                                        if let Ok(json) = serde_json::to_string(&response) {
+                                            let _ = session.text(json).await;
+                                        }
+                                        return Ok(None);
+                                    }
+                                };
                                 let response = RpcResponse::success(
                                     request.id,
-                                    serde_json::json!({"authenticated": true}),
+                                    serde_json::json!({
+                                        "authenticated": true,
+                                        "role": operator.role,
+                                        "api_version": API_VERSION
+                                    }),
                                 );
                                 if let Ok(json) = serde_json::to_string(&response) {
                                     let _ = session.text(json).await;
                                 }
-                                return Ok(true);
+                                return Ok(Some(operator));
                             }
                             Ok(None) => {
                                 let response = RpcResponse::error(
@@ -256,7 +271,7 @@ async fn wait_for_auth(
                                 if let Ok(json) = serde_json::to_string(&response) {
                                     let _ = session.text(json).await;
                                 }
-                                return Ok(false);
+                                return Ok(None);
                             }
                             Err(e) => {
                                 log::error!("Database error validating token: {}", e);
@@ -267,7 +282,7 @@ async fn wait_for_auth(
                                 if let Ok(json) = serde_json::to_string(&response) {
                                     let _ = session.text(json).await;
                                 }
-                                return Ok(false);
+                                return Ok(None);
                             }
                         }
                     }
@@ -296,7 +311,7 @@ async fn wait_for_auth(
                 let _ = session.pong(&data).await;
             }
             Ok(AggregatedMessage::Close(_)) => {
-                return Ok(false);
+                return Ok(None);
             }
             Err(e) => {
                 log::error!("WebSocket error during auth: {:?}", e);
@@ -306,17 +321,10 @@ async fn wait_for_auth(
         }
     }
 
-    Ok(false)
+    Ok(None)
 }
 
-async fn process_request(
-    text: &str,
-    db: &Arc<Database>,
-    channel_manager: &Arc<ChannelManager>,
-    broadcaster: &Arc<EventBroadcaster>,
-    tx_queue: &Arc<TxQueueManager>,
-    wallet_provider: &Option<Arc<dyn WalletProvider>>,
-) -> RpcResponse {
+async fn process_request(text: &str, operator: &OperatorIdentity, state: &web::Data<AppState>) -> RpcResponse {
     let request: RpcRequest = match serde_json::from_str(text) {
         Ok(req) => req,
         Err(_) => {
@@ -325,8 +333,40 @@ async fn process_request(
     };
 
     let id = request.id.clone();
+    let db = &state.db;
 
-    let result = dispatch_method(&request, db, channel_manager, broadcaster, tx_queue, wallet_provider).await;
+    // Accept both `chat.send` and the version-pinned `v1.chat.send`
+    let action = match schema::resolve_method(&request.method) {
+        Ok(method) => method,
+        Err(error) => return RpcResponse::error(id, error),
+    };
+
+    // Role check before dispatch; every non-read method is audited
+    let required = session_auth::required_gateway_role(action);
+    if !operator.role.allows(required) {
+        log::warn!(
+            "[GATEWAY] {} ({}) denied {} - requires {}",
+            operator.public_address,
+            operator.role,
+            action,
+            required
+        );
+        session_auth::audit(db, Some(operator), "gateway", action, "denied", Some(&format!("requires {}", required)));
+        return RpcResponse::error(
+            id,
+            RpcError::new(-32003, format!("Requires {} role (you are {})", required, operator.role)),
+        );
+    }
+
+    let result = dispatch_method(action, &request, operator, state).await;
+
+    if required > OperatorRole::Viewer {
+        let (outcome, detail) = match &result {
+            Ok(_) => ("ok", request.params.get("uuid").or_else(|| request.params.get("id")).map(|v| v.to_string())),
+            Err(e) => ("failed", Some(e.message.clone())),
+        };
+        session_auth::audit(db, Some(operator), "gateway", action, outcome, detail.as_deref());
+    }
 
     match result {
         Ok(value) => RpcResponse::success(id, value),
@@ -334,44 +374,96 @@ async fn process_request(
     }
 }
 
+/// Deserialize method params; a missing `params` field counts as `{}`
+fn parse_params<T: DeserializeOwned>(request: &RpcRequest) -> Result<T, RpcError> {
+    let params = if request.params.is_null() {
+        serde_json::json!({})
+    } else {
+        request.params.clone()
+    };
+    serde_json::from_value(params).map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))
+}
+
 async fn dispatch_method(
+    method: &str,
     request: &RpcRequest,
-    db: &Arc<Database>,
-    channel_manager: &Arc<ChannelManager>,
-    broadcaster: &Arc<EventBroadcaster>,
-    tx_queue: &Arc<TxQueueManager>,
-    wallet_provider: &Option<Arc<dyn WalletProvider>>,
+    operator: &OperatorIdentity,
+    state: &web::Data<AppState>,
 ) -> Result<serde_json::Value, RpcError> {
-    match request.method.as_str() {
+    let db = state.db.clone();
+    let dispatcher = state.dispatcher.clone();
+    let broadcaster = state.broadcaster.clone();
+    let channel_manager = state.channel_manager.clone();
+    let tx_queue = state.tx_queue.clone();
+
+    match method {
         "ping" => methods::handle_ping().await,
-        "status" => methods::handle_status(broadcaster.clone()).await,
-        "channels.status" => {
-            methods::handle_channels_status(db.clone(), channel_manager.clone()).await
-        }
+        "status" => methods::handle_status(broadcaster).await,
+        "rpc.discover" => Ok(schema::discover()),
+        "channels.status" => methods::handle_channels_status(db, channel_manager).await,
         "channels.start" => {
-            let params: ChannelIdParams = serde_json::from_value(request.params.clone())
-                .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
-            methods::handle_channels_start(params, db.clone(), channel_manager.clone()).await
+            let params: ChannelIdParams = parse_params(request)?;
+            methods::handle_channels_start(params, db, channel_manager).await
         }
         "channels.stop" => {
-            let params: ChannelIdParams = serde_json::from_value(request.params.clone())
-                .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
-            methods::handle_channels_stop(params, channel_manager.clone(), db.clone()).await
+            let params: ChannelIdParams = parse_params(request)?;
+            methods::handle_channels_stop(params, channel_manager, db).await
         }
         "channels.restart" => {
-            let params: ChannelIdParams = serde_json::from_value(request.params.clone())
-                .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
-            methods::handle_channels_restart(params, db.clone(), channel_manager.clone()).await
+            let params: ChannelIdParams = parse_params(request)?;
+            methods::handle_channels_restart(params, db, channel_manager).await
+        }
+        "chat.send" => {
+            let params: methods::ChatSendParams = parse_params(request)?;
+            methods::handle_chat_send(params, operator, db, dispatcher, broadcaster).await
+        }
+        "chat.stop" => {
+            let params: methods::ChatChannelParams = parse_params(request)?;
+            methods::handle_chat_stop(params, dispatcher, state.execution_tracker.clone()).await
+        }
+        "chat.status" => {
+            let params: methods::ChatChannelParams = parse_params(request)?;
+            methods::handle_chat_status(params, state.execution_tracker.clone()).await
+        }
+        "sessions.list" => {
+            let params: methods::SessionsListParams = parse_params(request)?;
+            methods::handle_sessions_list(params, db).await
+        }
+        "sessions.get" => methods::handle_sessions_get(parse_params(request)?, db).await,
+        "sessions.transcript" => {
+            let params: methods::SessionTranscriptParams = parse_params(request)?;
+            methods::handle_sessions_transcript(params, db).await
+        }
+        "sessions.stop" => {
+            let params: IdParams = parse_params(request)?;
+            methods::handle_sessions_stop(params, db, dispatcher, state.execution_tracker.clone()).await
+        }
+        "sessions.resume" => methods::handle_sessions_resume(parse_params(request)?, db).await,
+        "cron.list" => methods::handle_cron_list(db).await,
+        "cron.get" => methods::handle_cron_get(parse_params(request)?, db).await,
+        "cron.runs" => methods::handle_cron_runs(parse_params(request)?, db).await,
+        "cron.create" => methods::handle_cron_create(parse_params(request)?, db).await,
+        "cron.delete" => methods::handle_cron_delete(parse_params(request)?, db).await,
+        "cron.pause" => methods::handle_cron_set_status(parse_params(request)?, "paused", db).await,
+        "cron.resume" => methods::handle_cron_set_status(parse_params(request)?, "active", db).await,
+        "cron.run" => methods::handle_cron_run(parse_params(request)?, db, state.scheduler.clone()).await,
+        "memory.search" => methods::handle_memory_search(parse_params(request)?, dispatcher).await,
+        "ask_user.pending" => methods::handle_ask_user_pending(parse_params(request)?, dispatcher).await,
+        "ask_user.answer" => {
+            let params: methods::AskUserAnswerParams = parse_params(request)?;
+            methods::handle_ask_user_answer(params, operator, db, dispatcher, broadcaster).await
         }
+        "confirmation.pending" => methods::handle_confirmation_pending(parse_params(request)?, dispatcher).await,
+        "confirmation.confirm" => methods::handle_confirmation_confirm(parse_params(request)?, dispatcher).await,
+        "confirmation.cancel" => methods::handle_confirmation_cancel(parse_params(request)?, dispatcher).await,
+        "tx_queue.pending" => methods::handle_tx_queue_pending(tx_queue).await,
         "tx_queue.confirm" => {
-            let params: methods::TxQueueParams = serde_json::from_value(request.params.clone())
-                .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
-            methods::handle_tx_queue_confirm(params, tx_queue.clone(), broadcaster.clone(), wallet_provider.clone()).await
+            let params: methods::TxQueueParams = parse_params(request)?;
+            methods::handle_tx_queue_confirm(params, tx_queue, broadcaster, state.wallet_provider.clone()).await
         }
         "tx_queue.deny" => {
-            let params: methods::TxQueueParams = serde_json::from_value(request.params.clone())
-                .map_err(|e| RpcError::invalid_params(format!("Invalid params: {}", e)))?;
-            methods::handle_tx_queue_deny(params, tx_queue.clone(), broadcaster.clone()).await
+            let params: methods::TxQueueParams = parse_params(request)?;
+            methods::handle_tx_queue_deny(params, tx_queue, broadcaster).await
         }
         _ => Err(RpcError::method_not_found()),
     }
@@ -0,0 +1,63 @@
+//! ask_user RPC methods
+//!
+//! When the agent calls `ask_user` it stops and waits for the next message on
+//! the same chat. These methods list the open questions and send the answer.
+
+use super::chat::spawn_chat;
+use crate::channels::MessageDispatcher;
+use crate::db::Database;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::RpcError;
+use crate::middleware::session_auth::OperatorIdentity;
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct AskUserPendingParams {
+    #[serde(default)]
+    pub channel_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AskUserAnswerParams {
+    #[serde(default)]
+    pub channel_id: i64,
+    pub chat_id: String,
+    pub answer: String,
+}
+
+/// Handle ask_user.pending RPC method
+pub async fn handle_ask_user_pending(
+    params: AskUserPendingParams,
+    dispatcher: Arc<MessageDispatcher>,
+) -> Result<Value, RpcError> {
+    serde_json::to_value(dispatcher.pending_questions(params.channel_id))
+        .map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Handle ask_user.answer RPC method
+pub async fn handle_ask_user_answer(
+    params: AskUserAnswerParams,
+    operator: &OperatorIdentity,
+    db: Arc<Database>,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+) -> Result<Value, RpcError> {
+    if dispatcher.pending_question(params.channel_id, &params.chat_id).is_none() {
+        return Err(RpcError::invalid_params(format!(
+            "No pending question on channel {} chat {}",
+            params.channel_id, params.chat_id
+        )));
+    }
+    spawn_chat(
+        params.channel_id,
+        params.chat_id,
+        params.answer,
+        None,
+        operator,
+        db,
+        dispatcher,
+        broadcaster,
+    )
+}
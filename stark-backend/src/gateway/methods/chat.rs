@@ -0,0 +1,156 @@
+//! Chat RPC methods
+//!
+//! `chat.send` dispatches through the same pipeline as `/api/chat`, but returns
+//! right away: progress streams to the client as regular gateway events and the
+//! final response arrives as a `chat.completed` event carrying the request ID.
+
+use crate::channels::{MessageDispatcher, NormalizedMessage};
+use crate::db::Database;
+use crate::execution::ExecutionTracker;
+use crate::gateway::events::EventBroadcaster;
+use crate::gateway::protocol::{GatewayEvent, RpcError};
+use crate::middleware::session_auth::OperatorIdentity;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Web channel ID - same reserved ID as the REST chat endpoints
+const WEB_CHANNEL_ID: i64 = 0;
+const WEB_CHANNEL_TYPE: &str = "web";
+
+#[derive(Debug, Deserialize)]
+pub struct ChatSendParams {
+    pub text: String,
+    #[serde(default)]
+    pub channel_id: i64,
+    #[serde(default)]
+    pub chat_id: Option<String>,
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatChannelParams {
+    #[serde(default)]
+    pub channel_id: i64,
+}
+
+/// Handle chat.send RPC method
+pub async fn handle_chat_send(
+    params: ChatSendParams,
+    operator: &OperatorIdentity,
+    db: Arc<Database>,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+) -> Result<Value, RpcError> {
+    if params.text.trim().is_empty() {
+        return Err(RpcError::invalid_params("text must not be empty"));
+    }
+    let chat_id = params
+        .chat_id
+        .unwrap_or_else(|| format!("gateway-{}", operator.public_address));
+    spawn_chat(
+        params.channel_id,
+        chat_id,
+        params.text,
+        params.network,
+        operator,
+        db,
+        dispatcher,
+        broadcaster,
+    )
+}
+
+/// Dispatch a message in the background and announce the result with
+/// `chat.completed`. Returns the request ID the event will carry.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_chat(
+    channel_id: i64,
+    chat_id: String,
+    text: String,
+    network: Option<String>,
+    operator: &OperatorIdentity,
+    db: Arc<Database>,
+    dispatcher: Arc<MessageDispatcher>,
+    broadcaster: Arc<EventBroadcaster>,
+) -> Result<Value, RpcError> {
+    let channel_type = if channel_id == WEB_CHANNEL_ID {
+        WEB_CHANNEL_TYPE.to_string()
+    } else {
+        db.get_channel(channel_id)
+            .map_err(|e| RpcError::internal_error(format!("Database error: {}", e)))?
+            .ok_or_else(|| RpcError::invalid_params(format!("Channel {} not found", channel_id)))?
+            .channel_type
+    };
+
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let message = NormalizedMessage {
+        channel_id,
+        channel_type,
+        chat_id: chat_id.clone(),
+        user_id: operator.public_address.clone(),
+        user_name: format!("gateway-{}", &operator.public_address[..10.min(operator.public_address.len())]),
+        text,
+        message_id: None,
+        session_mode: None,
+        selected_network: network,
+        force_safe_mode: false,
+    };
+
+    log::info!("[GATEWAY] chat.send {} on channel {} chat {}", request_id, channel_id, chat_id);
+
+    let event_request_id = request_id.clone();
+    let event_chat_id = chat_id.clone();
+    tokio::spawn(async move {
+        let result = dispatcher.dispatch(message).await;
+        broadcaster.broadcast(GatewayEvent::chat_completed(
+            &event_request_id,
+            channel_id,
+            &event_chat_id,
+            &result.response,
+            result.error.as_deref(),
+        ));
+    });
+
+    Ok(json!({
+        "request_id": request_id,
+        "channel_id": channel_id,
+        "chat_id": chat_id
+    }))
+}
+
+/// Handle chat.stop RPC method
+pub async fn handle_chat_stop(
+    params: ChatChannelParams,
+    dispatcher: Arc<MessageDispatcher>,
+    execution_tracker: Arc<ExecutionTracker>,
+) -> Result<Value, RpcError> {
+    log::info!("[GATEWAY] Stopping execution for channel {}", params.channel_id);
+    execution_tracker.cancel_execution(params.channel_id);
+    execution_tracker.cancel_all_sessions_for_channel(params.channel_id);
+
+    let mut subagents_cancelled = 0;
+    if let Some(subagent_manager) = dispatcher.subagent_manager() {
+        subagents_cancelled = subagent_manager
+            .cancel_all_for_channel_and_wait(params.channel_id, Duration::from_millis(100))
+            .await;
+    }
+
+    Ok(json!({
+        "success": true,
+        "subagents_cancelled": subagents_cancelled
+    }))
+}
+
+/// Handle chat.status RPC method
+pub async fn handle_chat_status(
+    params: ChatChannelParams,
+    execution_tracker: Arc<ExecutionTracker>,
+) -> Result<Value, RpcError> {
+    let execution_id = execution_tracker.get_execution_id(params.channel_id);
+    Ok(json!({
+        "running": execution_id.is_some(),
+        "execution_id": execution_id
+    }))
+}
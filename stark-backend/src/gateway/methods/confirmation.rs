@@ -0,0 +1,147 @@
+//! Tool confirmation RPC methods
+//!
+//! Tool calls that need confirmation are held per channel in the dispatcher's
+//! `PendingConfirmationManager`. These methods list them and confirm (execute)
+//! or cancel them, like the `/api/confirmation` endpoints.
+
+use crate::channels::MessageDispatcher;
+use crate::gateway::protocol::RpcError;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmationPendingParams {
+    #[serde(default)]
+    pub channel_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmationParams {
+    #[serde(default)]
+    pub channel_id: i64,
+}
+
+/// Handle confirmation.pending RPC method
+pub async fn handle_confirmation_pending(
+    params: ConfirmationPendingParams,
+    dispatcher: Arc<MessageDispatcher>,
+) -> Result<Value, RpcError> {
+    serde_json::to_value(dispatcher.pending_confirmations().list_pending(params.channel_id))
+        .map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Handle confirmation.confirm RPC method
+pub async fn handle_confirmation_confirm(
+    params: ConfirmationParams,
+    dispatcher: Arc<MessageDispatcher>,
+) -> Result<Value, RpcError> {
+    let pending = dispatcher.get_pending_confirmation(params.channel_id).ok_or_else(|| {
+        RpcError::invalid_params(format!("No pending confirmation on channel {}", params.channel_id))
+    })?;
+    match dispatcher.api_confirm_transaction(params.channel_id).await {
+        Ok(result) => Ok(json!({
+            "success": true,
+            "tool_name": pending.tool_name,
+            "result": result,
+        })),
+        Err(error) => Ok(json!({
+            "success": false,
+            "tool_name": pending.tool_name,
+            "error": error,
+        })),
+    }
+}
+
+/// Handle confirmation.cancel RPC method
+pub async fn handle_confirmation_cancel(
+    params: ConfirmationParams,
+    dispatcher: Arc<MessageDispatcher>,
+) -> Result<Value, RpcError> {
+    let description = dispatcher
+        .api_cancel_transaction(params.channel_id)
+        .map_err(RpcError::invalid_params)?;
+    Ok(json!({
+        "success": true,
+        "description": description,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use crate::gateway::events::EventBroadcaster;
+
+    fn dispatcher() -> Arc<MessageDispatcher> {
+        let db = Arc::new(Database::new(":memory:").unwrap());
+        Arc::new(MessageDispatcher::new_without_tools(db, Arc::new(EventBroadcaster::new())))
+    }
+
+    fn hold(dispatcher: &MessageDispatcher, channel_id: i64) {
+        dispatcher.pending_confirmations().add_pending(
+            channel_id,
+            1,
+            "web3_tx".to_string(),
+            "call-1".to_string(),
+            json!({ "to": "0x0000000000000000000000000000000000000001", "value": "1" }),
+            "user-1".to_string(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_lists_by_channel() {
+        let dispatcher = dispatcher();
+        hold(&dispatcher, 0);
+        hold(&dispatcher, 2);
+
+        let all = handle_confirmation_pending(ConfirmationPendingParams { channel_id: None }, dispatcher.clone())
+            .await
+            .unwrap();
+        assert_eq!(all.as_array().unwrap().len(), 2);
+
+        let one = handle_confirmation_pending(ConfirmationPendingParams { channel_id: Some(2) }, dispatcher)
+            .await
+            .unwrap();
+        assert_eq!(one.as_array().unwrap().len(), 1);
+        assert_eq!(one[0]["channel_id"], 2);
+        assert_eq!(one[0]["tool_name"], "web3_tx");
+    }
+
+    #[tokio::test]
+    async fn test_confirm_executes_and_clears() {
+        let dispatcher = dispatcher();
+        hold(&dispatcher, 0);
+
+        // No tools are registered, so the held call fails but is still consumed
+        let result = handle_confirmation_confirm(ConfirmationParams { channel_id: 0 }, dispatcher.clone())
+            .await
+            .unwrap();
+        assert_eq!(result["success"], false);
+        assert_eq!(result["tool_name"], "web3_tx");
+        assert!(dispatcher.get_pending_confirmation(0).is_none());
+
+        let err = handle_confirmation_confirm(ConfirmationParams { channel_id: 0 }, dispatcher)
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("No pending confirmation"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_removes_only_that_channel() {
+        let dispatcher = dispatcher();
+        hold(&dispatcher, 0);
+        hold(&dispatcher, 2);
+
+        let result = handle_confirmation_cancel(ConfirmationParams { channel_id: 2 }, dispatcher.clone())
+            .await
+            .unwrap();
+        assert_eq!(result["success"], true);
+        assert!(dispatcher.get_pending_confirmation(2).is_none());
+        assert!(dispatcher.get_pending_confirmation(0).is_some());
+
+        assert!(handle_confirmation_cancel(ConfirmationParams { channel_id: 2 }, dispatcher)
+            .await
+            .is_err());
+    }
+}
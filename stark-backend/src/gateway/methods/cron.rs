@@ -0,0 +1,118 @@
+//! Cron job RPC methods
+//!
+//! Mirror the `/api/cron/jobs` endpoints for headless clients.
+
+use crate::controllers::cron::validate_create_request;
+use crate::db::Database;
+use crate::gateway::protocol::{IdParams, RpcError};
+use crate::models::CreateCronJobRequest;
+use crate::scheduler::Scheduler;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CronRunsParams {
+    pub id: i64,
+    #[serde(default = "default_runs_limit")]
+    pub limit: i32,
+}
+
+fn default_runs_limit() -> i32 {
+    20
+}
+
+fn db_error(e: impl std::fmt::Display) -> RpcError {
+    RpcError::internal_error(format!("Database error: {}", e))
+}
+
+/// Handle cron.list RPC method
+pub async fn handle_cron_list(db: Arc<Database>) -> Result<Value, RpcError> {
+    let jobs = db.list_cron_jobs().map_err(db_error)?;
+    serde_json::to_value(jobs).map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Handle cron.get RPC method
+pub async fn handle_cron_get(params: IdParams, db: Arc<Database>) -> Result<Value, RpcError> {
+    let job = db
+        .get_cron_job(params.id)
+        .map_err(db_error)?
+        .ok_or_else(|| RpcError::invalid_params(format!("Job {} not found", params.id)))?;
+    serde_json::to_value(job).map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Handle cron.runs RPC method
+pub async fn handle_cron_runs(params: CronRunsParams, db: Arc<Database>) -> Result<Value, RpcError> {
+    let runs = db.get_cron_job_runs(params.id, params.limit).map_err(db_error)?;
+    serde_json::to_value(runs).map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Handle cron.create RPC method
+pub async fn handle_cron_create(params: CreateCronJobRequest, db: Arc<Database>) -> Result<Value, RpcError> {
+    validate_create_request(&params).map_err(RpcError::invalid_params)?;
+
+    let job = db
+        .create_cron_job(
+            &params.name,
+            params.description.as_deref(),
+            &params.schedule_type,
+            &params.schedule_value,
+            params.timezone.as_deref(),
+            &params.session_mode,
+            params.message.as_deref(),
+            params.system_event.as_deref(),
+            params.channel_id,
+            params.deliver_to.as_deref(),
+            params.deliver,
+            params.model_override.as_deref(),
+            params.thinking_level.as_deref(),
+            params.timeout_seconds,
+            params.delete_after_run,
+        )
+        .map_err(|e| RpcError::internal_error(format!("Failed to create job: {}", e)))?;
+
+    Ok(json!({ "success": true, "job": job }))
+}
+
+/// Handle cron.delete RPC method
+pub async fn handle_cron_delete(params: IdParams, db: Arc<Database>) -> Result<Value, RpcError> {
+    if !db.delete_cron_job(params.id).map_err(db_error)? {
+        return Err(RpcError::invalid_params(format!("Job {} not found", params.id)));
+    }
+    Ok(json!({ "success": true }))
+}
+
+/// Handle cron.pause / cron.resume RPC methods
+pub async fn handle_cron_set_status(
+    params: IdParams,
+    status: &str,
+    db: Arc<Database>,
+) -> Result<Value, RpcError> {
+    let job = db
+        .update_cron_job(
+            params.id,
+            None, None, None, None, None, None, None, None, None, None, None, None, None, None, None,
+            Some(status),
+        )
+        .map_err(|e| RpcError::internal_error(format!("Failed to update job: {}", e)))?;
+    Ok(json!({ "success": true, "job": job }))
+}
+
+/// Handle cron.run RPC method
+pub async fn handle_cron_run(
+    params: IdParams,
+    db: Arc<Database>,
+    scheduler: Arc<Scheduler>,
+) -> Result<Value, RpcError> {
+    let job = db
+        .get_cron_job(params.id)
+        .map_err(db_error)?
+        .ok_or_else(|| RpcError::invalid_params(format!("Job {} not found", params.id)))?;
+
+    scheduler
+        .run_job_now(&job.job_id)
+        .await
+        .map_err(|e| RpcError::new(-32000, e))?;
+
+    Ok(json!({ "success": true, "job": job }))
+}
@@ -0,0 +1,40 @@
+//! Memory RPC methods
+
+use crate::channels::MessageDispatcher;
+use crate::gateway::protocol::RpcError;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct MemorySearchParams {
+    pub query: String,
+    #[serde(default = "default_limit")]
+    pub limit: i32,
+}
+
+fn default_limit() -> i32 {
+    20
+}
+
+/// Handle memory.search RPC method
+pub async fn handle_memory_search(
+    params: MemorySearchParams,
+    dispatcher: Arc<MessageDispatcher>,
+) -> Result<Value, RpcError> {
+    let memory_store = dispatcher
+        .memory_store()
+        .ok_or_else(|| RpcError::new(-32000, "Memory system not initialized"))?;
+
+    let results = memory_store
+        .hybrid_search(&params.query, params.limit.clamp(1, 100))
+        .await
+        .map_err(|e| RpcError::internal_error(format!("Search failed: {}", e)))?;
+
+    Ok(Value::Array(
+        results
+            .into_iter()
+            .map(|r| json!({ "file_path": r.file_path, "snippet": r.snippet, "score": r.score }))
+            .collect(),
+    ))
+}
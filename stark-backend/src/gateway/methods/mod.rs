@@ -1,7 +1,19 @@
+pub mod ask_user;
 pub mod channels;
+pub mod chat;
+pub mod confirmation;
+pub mod cron;
+pub mod memory;
+pub mod sessions;
 pub mod status;
 pub mod tx_queue;
 
+pub use ask_user::*;
 pub use channels::*;
+pub use chat::*;
+pub use confirmation::*;
+pub use cron::*;
+pub use memory::*;
+pub use sessions::*;
 pub use status::*;
 pub use tx_queue::*;
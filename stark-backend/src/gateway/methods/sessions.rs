@@ -0,0 +1,127 @@
+//! Chat session RPC methods
+//!
+//! Mirror the `/api/sessions` endpoints for headless clients.
+
+use crate::channels::MessageDispatcher;
+use crate::db::Database;
+use crate::execution::ExecutionTracker;
+use crate::gateway::protocol::{IdParams, RpcError};
+use crate::models::{ChatSessionResponse, CompletionStatus};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SessionsListParams {
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionTranscriptParams {
+    pub id: i64,
+    #[serde(default)]
+    pub limit: Option<i32>,
+}
+
+fn db_error(e: impl std::fmt::Display) -> RpcError {
+    RpcError::internal_error(format!("Database error: {}", e))
+}
+
+/// Load a session as its API response, with message count
+fn session_response(db: &Database, session_id: i64) -> Result<ChatSessionResponse, RpcError> {
+    let session = db
+        .get_chat_session(session_id)
+        .map_err(db_error)?
+        .ok_or_else(|| RpcError::invalid_params(format!("Session {} not found", session_id)))?;
+    let mut response: ChatSessionResponse = session.into();
+    response.message_count = db.count_session_messages(session_id).ok();
+    Ok(response)
+}
+
+/// Handle sessions.list RPC method
+pub async fn handle_sessions_list(params: SessionsListParams, db: Arc<Database>) -> Result<Value, RpcError> {
+    let sessions = db.list_chat_sessions().map_err(db_error)?;
+    let responses: Vec<ChatSessionResponse> = sessions
+        .into_iter()
+        .take(params.limit.unwrap_or(usize::MAX))
+        .map(|s| {
+            let session_id = s.id;
+            let mut response: ChatSessionResponse = s.into();
+            response.message_count = db.count_session_messages(session_id).ok();
+            response
+        })
+        .collect();
+    serde_json::to_value(responses).map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Handle sessions.get RPC method
+pub async fn handle_sessions_get(params: IdParams, db: Arc<Database>) -> Result<Value, RpcError> {
+    let response = session_response(&db, params.id)?;
+    serde_json::to_value(response).map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Handle sessions.transcript RPC method
+pub async fn handle_sessions_transcript(
+    params: SessionTranscriptParams,
+    db: Arc<Database>,
+) -> Result<Value, RpcError> {
+    let messages = match params.limit {
+        Some(limit) => db.get_recent_session_messages(params.id, limit),
+        None => db.get_session_messages(params.id),
+    }
+    .map_err(db_error)?;
+    let total = db
+        .count_session_messages(params.id)
+        .unwrap_or(messages.len() as i64);
+
+    Ok(json!({
+        "session_id": params.id,
+        "messages": messages,
+        "total_count": total
+    }))
+}
+
+/// Handle sessions.stop RPC method
+pub async fn handle_sessions_stop(
+    params: IdParams,
+    db: Arc<Database>,
+    dispatcher: Arc<MessageDispatcher>,
+    execution_tracker: Arc<ExecutionTracker>,
+) -> Result<Value, RpcError> {
+    let session = session_response(&db, params.id)?;
+    let channel_id = session.channel_id;
+
+    let cancelled_agents = dispatcher
+        .subagent_manager()
+        .map(|m| m.cancel_all_for_channel(channel_id))
+        .unwrap_or(0);
+    execution_tracker.cancel_execution(channel_id);
+    execution_tracker.cancel_all_sessions_for_channel(channel_id);
+    execution_tracker.clear_tasks_for_session(params.id);
+
+    db.update_session_completion_status(params.id, CompletionStatus::Cancelled)
+        .map_err(db_error)?;
+
+    Ok(json!({
+        "success": true,
+        "session": session_response(&db, params.id)?,
+        "cancelled_agents": cancelled_agents
+    }))
+}
+
+/// Handle sessions.resume RPC method
+pub async fn handle_sessions_resume(params: IdParams, db: Arc<Database>) -> Result<Value, RpcError> {
+    let session = session_response(&db, params.id)?;
+    if session.completion_status == CompletionStatus::Complete {
+        return Err(RpcError::invalid_params("Cannot resume a completed session"));
+    }
+
+    db.update_session_completion_status(params.id, CompletionStatus::Active)
+        .map_err(db_error)?;
+
+    Ok(json!({
+        "success": true,
+        "session": session_response(&db, params.id)?
+    }))
+}
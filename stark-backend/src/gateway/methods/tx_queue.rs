@@ -19,6 +19,19 @@ pub struct TxQueueParams {
     pub channel_id: i64,
 }
 
+/// Handle tx_queue.pending RPC method
+/// Lists queued transactions still waiting for confirmation
+pub async fn handle_tx_queue_pending(tx_queue: Arc<TxQueueManager>) -> Result<Value, RpcError> {
+    serde_json::to_value(tx_queue.list_pending()).map_err(|e| RpcError::internal_error(e.to_string()))
+}
+
+/// Result of broadcasting a confirmed queued transaction
+#[derive(Debug, Clone)]
+pub struct ConfirmedTx {
+    pub tx_hash: String,
+    pub explorer_url: String,
+}
+
 /// Handle tx_queue.confirm RPC method
 /// Broadcasts the transaction and emits result events
 pub async fn handle_tx_queue_confirm(
@@ -27,23 +40,67 @@ pub async fn handle_tx_queue_confirm(
     broadcaster: Arc<EventBroadcaster>,
     wallet_provider: Option<Arc<dyn WalletProvider>>,
 ) -> Result<Value, RpcError> {
-    log::info!("[tx_queue.confirm] Confirming transaction {}", params.uuid);
+    let confirmed = confirm_queued_tx(
+        &params.uuid,
+        params.channel_id,
+        tx_queue,
+        broadcaster,
+        wallet_provider,
+    )
+    .await
+    .map_err(|e| RpcError::new(-32000, e))?;
+
+    Ok(json!({
+        "success": true,
+        "uuid": params.uuid,
+        "tx_hash": confirmed.tx_hash,
+        "explorer_url": confirmed.explorer_url
+    }))
+}
+
+/// Handle tx_queue.deny RPC method
+/// Removes the transaction from the queue without broadcasting
+pub async fn handle_tx_queue_deny(
+    params: TxQueueParams,
+    tx_queue: Arc<TxQueueManager>,
+    broadcaster: Arc<EventBroadcaster>,
+) -> Result<Value, RpcError> {
+    deny_queued_tx(&params.uuid, params.channel_id, &tx_queue, &broadcaster)
+        .map_err(|e| RpcError::new(-32000, e))?;
+
+    Ok(json!({
+        "success": true,
+        "uuid": params.uuid,
+        "action": "denied_and_deleted"
+    }))
+}
+
+/// Broadcast a pending queued transaction and emit the tx_queue.confirmed event.
+/// Shared by the RPC method and the chat channel approval buttons.
+pub async fn confirm_queued_tx(
+    uuid: &str,
+    channel_id: i64,
+    tx_queue: Arc<TxQueueManager>,
+    broadcaster: Arc<EventBroadcaster>,
+    wallet_provider: Option<Arc<dyn WalletProvider>>,
+) -> Result<ConfirmedTx, String> {
+    log::info!("[tx_queue.confirm] Confirming transaction {}", uuid);
 
     // Get transaction
-    let tx = tx_queue.get(&params.uuid)
-        .ok_or_else(|| RpcError::new(-32000, format!("Transaction {} not found", params.uuid)))?;
+    let tx = tx_queue.get(uuid)
+        .ok_or_else(|| format!("Transaction {} not found", uuid))?;
 
     // Validate pending status
     if tx.status != QueuedTxStatus::Pending {
-        return Err(RpcError::new(-32000, format!("Transaction {} is not pending (status: {:?})", params.uuid, tx.status)));
+        return Err(format!("Transaction {} is not pending (status: {:?})", uuid, tx.status));
     }
 
     // Mark broadcasting
-    tx_queue.mark_broadcasting(&params.uuid);
+    tx_queue.mark_broadcasting(uuid);
 
     // Get wallet provider for x402 payments
     let wallet_provider = wallet_provider
-        .ok_or_else(|| RpcError::new(-32000, "Wallet not configured".to_string()))?;
+        .ok_or_else(|| "Wallet not configured".to_string())?;
 
     // Resolve RPC configuration
     let rpc_config = resolve_rpc_from_network(&tx.network);
@@ -55,63 +112,62 @@ pub async fn handle_tx_queue_confirm(
         Some(rpc_config.url.clone()),
         rpc_config.use_x402,
     ).map_err(|e| {
-        tx_queue.mark_failed(&params.uuid, &e);
-        RpcError::new(-32000, format!("RPC error: {}", e))
+        tx_queue.mark_failed(uuid, &e);
+        format!("RPC error: {}", e)
     })?;
 
     // Decode signed transaction from hex
     let signed_tx_bytes = hex::decode(tx.signed_tx_hex.trim_start_matches("0x"))
         .map_err(|e| {
-            tx_queue.mark_failed(&params.uuid, &format!("Invalid tx hex: {}", e));
-            RpcError::new(-32000, format!("Invalid tx hex: {}", e))
+            tx_queue.mark_failed(uuid, &format!("Invalid tx hex: {}", e));
+            format!("Invalid tx hex: {}", e)
         })?;
 
     // Broadcast the transaction
     let tx_hash = rpc.send_raw_transaction(&signed_tx_bytes).await
         .map_err(|e| {
-            tx_queue.mark_failed(&params.uuid, &e);
-            RpcError::new(-32000, format!("Broadcast failed: {}", e))
+            tx_queue.mark_failed(uuid, &e);
+            format!("Broadcast failed: {}", e)
         })?;
 
     let tx_hash_str = format!("{:?}", tx_hash);
     let explorer_url = format!("{}/{}", tx.get_explorer_base_url(), tx_hash_str);
 
     // Mark as broadcast (partner mode - user confirmed)
-    tx_queue.mark_broadcast(&params.uuid, &tx_hash_str, &explorer_url, "partner");
+    tx_queue.mark_broadcast(uuid, &tx_hash_str, &explorer_url, "partner");
 
-    log::info!("[tx_queue.confirm] Transaction {} broadcast as {}", params.uuid, tx_hash_str);
+    log::info!("[tx_queue.confirm] Transaction {} broadcast as {}", uuid, tx_hash_str);
 
     // Emit tx.pending event
     broadcaster.broadcast(GatewayEvent::tx_pending(
-        params.channel_id, &tx_hash_str, &tx.network, &explorer_url
+        channel_id, &tx_hash_str, &tx.network, &explorer_url
     ));
 
     // Clone values for the spawned task
-    let uuid = params.uuid.clone();
-    let channel_id = params.channel_id;
+    let uuid_clone = uuid.to_string();
     let network = tx.network.clone();
     let tx_queue_clone = tx_queue.clone();
     let broadcaster_clone = broadcaster.clone();
     let tx_hash_clone = tx_hash_str.clone();
 
-    // Wait for confirmation in a spawned task (don't block the RPC response)
+    // Wait for confirmation in a spawned task (don't block the caller)
     tokio::spawn(async move {
         match rpc.wait_for_receipt(tx_hash, Duration::from_secs(120)).await {
             Ok(receipt) => {
                 let status = if receipt.status == Some(ethers::types::U64::from(1)) {
-                    tx_queue_clone.mark_confirmed(&uuid);
+                    tx_queue_clone.mark_confirmed(&uuid_clone);
                     "confirmed"
                 } else {
-                    tx_queue_clone.mark_failed(&uuid, "Reverted");
+                    tx_queue_clone.mark_failed(&uuid_clone, "Reverted");
                     "reverted"
                 };
                 broadcaster_clone.broadcast(GatewayEvent::tx_confirmed(
                     channel_id, &tx_hash_clone, &network, status
                 ));
-                log::info!("[tx_queue.confirm] Transaction {} {}", uuid, status);
+                log::info!("[tx_queue.confirm] Transaction {} {}", uuid_clone, status);
             }
             Err(e) => {
-                log::warn!("[tx_queue.confirm] Receipt wait timeout for {}: {}", uuid, e);
+                log::warn!("[tx_queue.confirm] Receipt wait timeout for {}: {}", uuid_clone, e);
                 // Timeout - tx may still confirm, don't mark as failed
             }
         }
@@ -119,43 +175,32 @@ pub async fn handle_tx_queue_confirm(
 
     // Emit tx_queue.confirmed event
     broadcaster.broadcast(GatewayEvent::tx_queue_confirmed(
-        params.channel_id, &params.uuid, &tx_hash_str
+        channel_id, uuid, &tx_hash_str
     ));
 
-    Ok(json!({
-        "success": true,
-        "uuid": params.uuid,
-        "tx_hash": tx_hash_str,
-        "explorer_url": explorer_url
-    }))
+    Ok(ConfirmedTx {
+        tx_hash: tx_hash_str,
+        explorer_url,
+    })
 }
 
-/// Handle tx_queue.deny RPC method
-/// Removes the transaction from the queue without broadcasting
-pub async fn handle_tx_queue_deny(
-    params: TxQueueParams,
-    tx_queue: Arc<TxQueueManager>,
-    broadcaster: Arc<EventBroadcaster>,
-) -> Result<Value, RpcError> {
-    log::info!("[tx_queue.deny] Denying transaction {}", params.uuid);
+/// Remove a queued transaction without broadcasting and emit the tx_queue.denied event
+pub fn deny_queued_tx(
+    uuid: &str,
+    channel_id: i64,
+    tx_queue: &TxQueueManager,
+    broadcaster: &EventBroadcaster,
+) -> Result<(), String> {
+    log::info!("[tx_queue.deny] Denying transaction {}", uuid);
 
     // Remove from queue
-    let removed = tx_queue.remove(&params.uuid);
-
-    if removed.is_none() {
-        return Err(RpcError::new(-32000, format!("Transaction {} not found", params.uuid)));
+    if tx_queue.remove(uuid).is_none() {
+        return Err(format!("Transaction {} not found", uuid));
     }
 
     // Emit denied event
-    broadcaster.broadcast(GatewayEvent::tx_queue_denied(
-        params.channel_id, &params.uuid
-    ));
+    broadcaster.broadcast(GatewayEvent::tx_queue_denied(channel_id, uuid));
 
-    log::info!("[tx_queue.deny] Transaction {} denied and deleted", params.uuid);
-
-    Ok(json!({
-        "success": true,
-        "uuid": params.uuid,
-        "action": "denied_and_deleted"
-    }))
+    log::info!("[tx_queue.deny] Transaction {} denied and deleted", uuid);
+    Ok(())
 }
@@ -2,6 +2,7 @@ pub mod actix_ws;
 pub mod events;
 pub mod methods;
 pub mod protocol;
+pub mod schema;
 
 pub use events::EventBroadcaster;
 
@@ -1,7 +1,12 @@
+use crate::channels::PendingUserQuestion;
 use crate::models::{ExecutionTask, TaskMetrics};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Version of the gateway JSON-RPC surface. Methods may be called with a
+/// `v<major>.` prefix (e.g. `v1.chat.send`) to pin the major version.
+pub const API_VERSION: &str = "1.0";
+
 /// Event types for gateway broadcasts
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EventType {
@@ -18,6 +23,7 @@ pub enum EventType {
     AgentThinking,     // Progress update during long AI calls
     AgentError,        // Error notification (timeout, etc.)
     AgentWarning,      // Warning when agent tries to skip tool calls
+    AgentQuestion,     // Agent is waiting on an ask_user answer
     // Tool events
     ToolExecution,
     ToolResult,
@@ -83,6 +89,8 @@ pub enum EventType {
     TxQueueDenied,                // User denied, tx deleted
     // Context management events
     ContextCompacting,  // Session context is being compacted to reduce token usage
+    // Gateway RPC events
+    ChatCompleted,  // A chat.send request finished dispatching
 }
 
 impl EventType {
@@ -99,6 +107,7 @@ impl EventType {
             Self::AgentThinking => "agent.thinking",
             Self::AgentError => "agent.error",
             Self::AgentWarning => "agent.warning",
+            Self::AgentQuestion => "agent.question",
             Self::ToolExecution => "tool.execution",
             Self::ToolResult => "tool.result",
             Self::ToolWaiting => "tool.waiting",
@@ -147,6 +156,7 @@ impl EventType {
             Self::TxQueueConfirmed => "tx_queue.confirmed",
             Self::TxQueueDenied => "tx_queue.denied",
             Self::ContextCompacting => "context.compacting",
+            Self::ChatCompleted => "chat.completed",
         }
     }
 }
@@ -317,6 +327,34 @@ impl GatewayEvent {
         )
     }
 
+    /// The agent asked the user a question (ask_user) and is waiting for the answer
+    pub fn agent_question(question: &PendingUserQuestion) -> Self {
+        Self::new(
+            EventType::AgentQuestion,
+            serde_json::to_value(question).unwrap_or_default(),
+        )
+    }
+
+    /// Final result of a gateway `chat.send` request
+    pub fn chat_completed(
+        request_id: &str,
+        channel_id: i64,
+        chat_id: &str,
+        response: &str,
+        error: Option<&str>,
+    ) -> Self {
+        Self::new(
+            EventType::ChatCompleted,
+            serde_json::json!({
+                "request_id": request_id,
+                "channel_id": channel_id,
+                "chat_id": chat_id,
+                "response": response,
+                "error": error
+            }),
+        )
+    }
+
     /// Emit a tool call notification for real-time display in chat
     /// The `chat_id` is the platform-specific conversation ID (e.g., Discord channel snowflake)
     pub fn agent_tool_call(channel_id: i64, chat_id: Option<&str>, tool_name: &str, parameters: &Value) -> Self {
@@ -706,6 +744,7 @@ impl GatewayEvent {
     /// Transaction queue confirmation required - partner mode needs user approval
     pub fn tx_queue_confirmation_required(
         channel_id: i64,
+        chat_id: Option<&str>,
         uuid: &str,
         network: &str,
         from: &str,
@@ -718,6 +757,7 @@ impl GatewayEvent {
             EventType::TxQueueConfirmationRequired,
             serde_json::json!({
                 "channel_id": channel_id,
+                "chat_id": chat_id,
                 "uuid": uuid,
                 "network": network,
                 "from": from,
@@ -1253,3 +1293,9 @@ impl GatewayEvent {
 pub struct ChannelIdParams {
     pub id: i64,
 }
+
+/// Params for operations on a record by its numeric ID (sessions, cron jobs)
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdParams {
+    pub id: i64,
+}
@@ -0,0 +1,483 @@
+//! Machine-readable description of the gateway JSON-RPC surface
+//!
+//! Every method the gateway dispatches is listed here with its minimum operator
+//! role and JSON Schemas for its params and result. `rpc.discover` returns the
+//! whole document so CLIs and bots can be generated against it, and the role
+//! check in `session_auth::required_gateway_role` reads from the same table.
+
+use crate::gateway::protocol::{EventType, RpcError, API_VERSION};
+use crate::models::OperatorRole;
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+
+/// One RPC method
+pub struct MethodSpec {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub role: OperatorRole,
+    pub params: Value,
+    pub result: Value,
+}
+
+static METHODS: Lazy<Vec<MethodSpec>> = Lazy::new(build_methods);
+
+/// All methods, in documentation order
+pub fn methods() -> &'static [MethodSpec] {
+    &METHODS
+}
+
+/// Minimum role for a method, or None if the gateway doesn't know it
+pub fn method_role(method: &str) -> Option<OperatorRole> {
+    methods().iter().find(|m| m.name == method).map(|m| m.role)
+}
+
+/// Strip an optional `v<major>.` prefix and check the major version matches.
+/// `v1.chat.send` and `chat.send` both resolve to `chat.send`.
+pub fn resolve_method(method: &str) -> Result<&str, RpcError> {
+    let Some((prefix, rest)) = method.split_once('.') else {
+        return Ok(method);
+    };
+    let Some(major) = prefix.strip_prefix('v').and_then(|m| m.parse::<u32>().ok()) else {
+        return Ok(method);
+    };
+    if major != api_major() {
+        return Err(RpcError::new(
+            -32004,
+            format!("Unsupported API version v{} (gateway speaks {})", major, API_VERSION),
+        ));
+    }
+    Ok(rest)
+}
+
+fn api_major() -> u32 {
+    API_VERSION
+        .split('.')
+        .next()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(1)
+}
+
+/// The `rpc.discover` document
+pub fn discover() -> Value {
+    let methods: Vec<Value> = methods()
+        .iter()
+        .map(|m| {
+            json!({
+                "name": m.name,
+                "summary": m.summary,
+                "role": m.role,
+                "params": m.params,
+                "result": m.result,
+            })
+        })
+        .collect();
+
+    json!({
+        "api_version": API_VERSION,
+        "methods": methods,
+        "events": events(),
+    })
+}
+
+/// Server-push events a headless client typically follows while a chat runs
+fn events() -> Vec<Value> {
+    [
+        (EventType::ChannelMessage, "A message was received on a channel"),
+        (EventType::StreamStart, "AI response streaming started"),
+        (EventType::StreamContentDelta, "Chunk of streamed response text"),
+        (EventType::StreamEnd, "AI response streaming finished"),
+        (EventType::AgentToolCall, "The agent called a tool"),
+        (EventType::ToolResult, "A tool returned a result"),
+        (EventType::AgentResponse, "The agent sent a reply"),
+        (EventType::AgentQuestion, "The agent is waiting on an ask_user answer"),
+        (EventType::ExecutionStarted, "An execution started"),
+        (EventType::ExecutionCompleted, "An execution finished"),
+        (EventType::ExecutionStopped, "An execution was cancelled"),
+        (EventType::TxQueueConfirmationRequired, "A queued transaction needs approval"),
+        (EventType::ChatCompleted, "A chat.send request finished, with the final response"),
+    ]
+    .iter()
+    .map(|(event, description)| json!({ "name": event.as_str(), "description": description }))
+    .collect()
+}
+
+fn object(required: &[&str], properties: Value) -> Value {
+    json!({
+        "type": "object",
+        "required": required,
+        "properties": properties,
+    })
+}
+
+fn no_params() -> Value {
+    object(&[], json!({}))
+}
+
+fn success_result(properties: Value) -> Value {
+    let mut props = json!({ "success": { "type": "boolean" } });
+    if let (Some(base), Some(extra)) = (props.as_object_mut(), properties.as_object()) {
+        base.extend(extra.clone());
+    }
+    object(&["success"], props)
+}
+
+fn array_of(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+fn build_methods() -> Vec<MethodSpec> {
+    use OperatorRole::{Operator, Treasurer, Viewer};
+
+    let id = || json!({ "type": "integer" });
+    let string = || json!({ "type": "string" });
+    let channel_id = || json!({ "type": "integer", "description": "Channel ID; 0 is the web channel", "default": 0 });
+    let id_params = || object(&["id"], json!({ "id": id() }));
+    let any_object = || json!({ "type": "object" });
+    let job_result = || success_result(json!({ "job": any_object() }));
+
+    vec![
+        // Core
+        MethodSpec {
+            name: "ping",
+            summary: "Liveness check",
+            role: Viewer,
+            params: no_params(),
+            result: json!({ "const": "pong" }),
+        },
+        MethodSpec {
+            name: "status",
+            summary: "Gateway status and connected client count",
+            role: Viewer,
+            params: no_params(),
+            result: object(&["status"], json!({ "status": string(), "connected_clients": id() })),
+        },
+        MethodSpec {
+            name: "rpc.discover",
+            summary: "This document: API version, methods with param/result schemas, and events",
+            role: Viewer,
+            params: no_params(),
+            result: object(&["api_version", "methods", "events"], json!({
+                "api_version": string(),
+                "methods": array_of(any_object()),
+                "events": array_of(any_object()),
+            })),
+        },
+        // Channels
+        MethodSpec {
+            name: "channels.status",
+            summary: "List channels with their running state",
+            role: Viewer,
+            params: no_params(),
+            result: array_of(any_object()),
+        },
+        MethodSpec {
+            name: "channels.start",
+            summary: "Start a channel and mark it enabled",
+            role: Operator,
+            params: id_params(),
+            result: success_result(json!({ "channel_id": id() })),
+        },
+        MethodSpec {
+            name: "channels.stop",
+            summary: "Stop a channel and mark it disabled",
+            role: Operator,
+            params: id_params(),
+            result: success_result(json!({ "channel_id": id() })),
+        },
+        MethodSpec {
+            name: "channels.restart",
+            summary: "Restart a channel",
+            role: Operator,
+            params: id_params(),
+            result: success_result(json!({ "channel_id": id() })),
+        },
+        // Chat
+        MethodSpec {
+            name: "chat.send",
+            summary: "Send a message to the agent. Returns immediately; follow stream.*/agent.* events \
+                      and wait for chat.completed (matched by request_id) for the final response",
+            role: Operator,
+            params: object(&["text"], json!({
+                "text": string(),
+                "channel_id": channel_id(),
+                "chat_id": { "type": "string", "description": "Conversation to continue; defaults to one per operator" },
+                "network": { "type": "string", "description": "Selected network, e.g. base" },
+            })),
+            result: object(&["request_id", "channel_id", "chat_id"], json!({
+                "request_id": string(),
+                "channel_id": id(),
+                "chat_id": string(),
+            })),
+        },
+        MethodSpec {
+            name: "chat.stop",
+            summary: "Cancel the running execution (and its subagents) on a channel",
+            role: Operator,
+            params: object(&[], json!({ "channel_id": channel_id() })),
+            result: success_result(json!({ "subagents_cancelled": id() })),
+        },
+        MethodSpec {
+            name: "chat.status",
+            summary: "Whether an execution is running on a channel",
+            role: Viewer,
+            params: object(&[], json!({ "channel_id": channel_id() })),
+            result: object(&["running"], json!({
+                "running": { "type": "boolean" },
+                "execution_id": string(),
+            })),
+        },
+        // Sessions
+        MethodSpec {
+            name: "sessions.list",
+            summary: "List chat sessions",
+            role: Viewer,
+            params: object(&[], json!({ "limit": id() })),
+            result: array_of(any_object()),
+        },
+        MethodSpec {
+            name: "sessions.get",
+            summary: "Get one chat session",
+            role: Viewer,
+            params: id_params(),
+            result: any_object(),
+        },
+        MethodSpec {
+            name: "sessions.transcript",
+            summary: "Messages of a session, optionally only the most recent",
+            role: Viewer,
+            params: object(&["id"], json!({ "id": id(), "limit": id() })),
+            result: object(&["session_id", "messages", "total_count"], json!({
+                "session_id": id(),
+                "messages": array_of(any_object()),
+                "total_count": id(),
+            })),
+        },
+        MethodSpec {
+            name: "sessions.stop",
+            summary: "Cancel a session's executions and mark it cancelled",
+            role: Operator,
+            params: id_params(),
+            result: success_result(json!({ "session": any_object() })),
+        },
+        MethodSpec {
+            name: "sessions.resume",
+            summary: "Mark a cancelled session active again so it can continue",
+            role: Operator,
+            params: id_params(),
+            result: success_result(json!({ "session": any_object() })),
+        },
+        // Cron
+        MethodSpec {
+            name: "cron.list",
+            summary: "List cron jobs",
+            role: Viewer,
+            params: no_params(),
+            result: array_of(any_object()),
+        },
+        MethodSpec {
+            name: "cron.get",
+            summary: "Get one cron job",
+            role: Viewer,
+            params: id_params(),
+            result: any_object(),
+        },
+        MethodSpec {
+            name: "cron.runs",
+            summary: "Recent runs of a cron job",
+            role: Viewer,
+            params: object(&["id"], json!({ "id": id(), "limit": { "type": "integer", "default": 20 } })),
+            result: array_of(any_object()),
+        },
+        MethodSpec {
+            name: "cron.create",
+            summary: "Create a cron job (same fields as POST /api/cron/jobs)",
+            role: Operator,
+            params: object(&["name", "schedule_type", "schedule_value"], json!({
+                "name": string(),
+                "description": string(),
+                "schedule_type": { "type": "string", "enum": ["at", "every", "cron", "event"] },
+                "schedule_value": string(),
+                "timezone": string(),
+                "session_mode": { "type": "string", "enum": ["main", "isolated"], "default": "isolated" },
+                "message": string(),
+                "system_event": string(),
+                "channel_id": id(),
+                "deliver_to": string(),
+                "deliver": { "type": "boolean" },
+                "model_override": string(),
+                "thinking_level": string(),
+                "timeout_seconds": id(),
+                "delete_after_run": { "type": "boolean" },
+            })),
+            result: job_result(),
+        },
+        MethodSpec {
+            name: "cron.delete",
+            summary: "Delete a cron job",
+            role: Operator,
+            params: id_params(),
+            result: success_result(json!({})),
+        },
+        MethodSpec {
+            name: "cron.pause",
+            summary: "Pause a cron job",
+            role: Operator,
+            params: id_params(),
+            result: job_result(),
+        },
+        MethodSpec {
+            name: "cron.resume",
+            summary: "Resume a paused cron job",
+            role: Operator,
+            params: id_params(),
+            result: job_result(),
+        },
+        MethodSpec {
+            name: "cron.run",
+            summary: "Run a cron job now",
+            role: Operator,
+            params: id_params(),
+            result: job_result(),
+        },
+        // Memory
+        MethodSpec {
+            name: "memory.search",
+            summary: "Full-text search over memory files",
+            role: Viewer,
+            params: object(&["query"], json!({
+                "query": string(),
+                "limit": { "type": "integer", "default": 20 },
+            })),
+            result: array_of(object(&["file_path", "snippet", "score"], json!({
+                "file_path": string(),
+                "snippet": string(),
+                "score": { "type": "number" },
+            }))),
+        },
+        // ask_user
+        MethodSpec {
+            name: "ask_user.pending",
+            summary: "Questions the agent asked via ask_user that have not been answered",
+            role: Viewer,
+            params: object(&[], json!({ "channel_id": { "type": "integer" } })),
+            result: array_of(object(&["channel_id", "chat_id", "session_id", "question"], json!({
+                "channel_id": id(),
+                "chat_id": string(),
+                "session_id": id(),
+                "question": string(),
+                "options": array_of(string()),
+                "default": string(),
+                "asked_at": { "type": "string", "format": "date-time" },
+            }))),
+        },
+        MethodSpec {
+            name: "ask_user.answer",
+            summary: "Answer a pending ask_user question; the agent continues like chat.send",
+            role: Operator,
+            params: object(&["chat_id", "answer"], json!({
+                "channel_id": channel_id(),
+                "chat_id": string(),
+                "answer": string(),
+            })),
+            result: object(&["request_id", "channel_id", "chat_id"], json!({
+                "request_id": string(),
+                "channel_id": id(),
+                "chat_id": string(),
+            })),
+        },
+        // Tool calls held for confirmation
+        MethodSpec {
+            name: "confirmation.pending",
+            summary: "Tool calls waiting for confirmation (at most one per channel)",
+            role: Viewer,
+            params: object(&[], json!({ "channel_id": { "type": "integer" } })),
+            result: array_of(object(&["id", "channel_id", "tool_name", "description"], json!({
+                "id": string(),
+                "channel_id": id(),
+                "session_id": id(),
+                "tool_name": string(),
+                "tool_call_id": string(),
+                "arguments": any_object(),
+                "description": string(),
+                "user_id": string(),
+            }))),
+        },
+        MethodSpec {
+            name: "confirmation.confirm",
+            summary: "Execute the tool call held for confirmation on a channel",
+            role: Treasurer,
+            params: object(&[], json!({ "channel_id": channel_id() })),
+            result: success_result(json!({ "tool_name": string(), "result": string(), "error": string() })),
+        },
+        MethodSpec {
+            name: "confirmation.cancel",
+            summary: "Drop the tool call held for confirmation on a channel",
+            role: Operator,
+            params: object(&[], json!({ "channel_id": channel_id() })),
+            result: success_result(json!({ "description": string() })),
+        },
+        // Transaction queue (pending confirmations)
+        MethodSpec {
+            name: "tx_queue.pending",
+            summary: "Queued transactions awaiting confirmation",
+            role: Viewer,
+            params: no_params(),
+            result: array_of(any_object()),
+        },
+        MethodSpec {
+            name: "tx_queue.confirm",
+            summary: "Approve and broadcast a queued transaction",
+            role: Treasurer,
+            params: object(&["uuid", "channel_id"], json!({ "uuid": string(), "channel_id": id() })),
+            result: success_result(json!({ "tx_hash": string(), "explorer_url": string() })),
+        },
+        MethodSpec {
+            name: "tx_queue.deny",
+            summary: "Reject a queued transaction",
+            role: Operator,
+            params: object(&["uuid", "channel_id"], json!({ "uuid": string(), "channel_id": id() })),
+            result: success_result(json!({})),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_method_names_unique_and_described() {
+        let mut seen = HashSet::new();
+        for method in methods() {
+            assert!(seen.insert(method.name), "duplicate method {}", method.name);
+            assert!(!method.summary.is_empty());
+            assert_eq!(method.params["type"], "object", "{} params must be an object schema", method.name);
+        }
+        let doc = discover();
+        assert_eq!(doc["api_version"], API_VERSION);
+        assert_eq!(doc["methods"].as_array().unwrap().len(), methods().len());
+        assert!(doc["events"].as_array().unwrap().iter().any(|e| e["name"] == "chat.completed"));
+        assert!(doc["methods"].as_array().unwrap().iter().any(|m| m["name"] == "confirmation.confirm"));
+    }
+
+    #[test]
+    fn test_resolve_method_versions() {
+        assert_eq!(resolve_method("chat.send").unwrap(), "chat.send");
+        assert_eq!(resolve_method("v1.chat.send").unwrap(), "chat.send");
+        assert_eq!(resolve_method("ping").unwrap(), "ping");
+        assert_eq!(resolve_method("v2.chat.send").unwrap_err().code, -32004);
+    }
+
+    #[test]
+    fn test_mutating_methods_require_operator() {
+        assert_eq!(method_role("tx_queue.confirm"), Some(OperatorRole::Treasurer));
+        assert_eq!(method_role("confirmation.confirm"), Some(OperatorRole::Treasurer));
+        assert_eq!(method_role("confirmation.cancel"), Some(OperatorRole::Operator));
+        assert_eq!(method_role("confirmation.pending"), Some(OperatorRole::Viewer));
+        assert_eq!(method_role("chat.send"), Some(OperatorRole::Operator));
+        assert_eq!(method_role("cron.create"), Some(OperatorRole::Operator));
+        assert_eq!(method_role("sessions.list"), Some(OperatorRole::Viewer));
+        assert_eq!(method_role("nope"), None);
+    }
+}
@@ -0,0 +1,530 @@
+//! External hooks defined in RON files
+//!
+//! Hooks in `config/hooks/*.ron` run an external command or POST to a webhook
+//! with the serialized `HookContext`, and turn the JSON verdict they return
+//! into a `HookResult`:
+//!
+//! ```ron
+//! ExternalHookDef(
+//!     id: "compliance_check",
+//!     name: "Compliance Check",
+//!     events: [before_tool_call],
+//!     tools: ["send_eth", "broadcast_web3_tx"],
+//!     priority: high,
+//!     timeout_secs: 5,
+//!     fail_closed: true,
+//!     action: Webhook(url: "http://localhost:9000/hooks"),
+//! )
+//! ```
+//!
+//! Verdicts look like `{"action": "cancel", "reason": "..."}`. Actions are
+//! `continue` (optionally with `value` and rewritten `tool_args`), `skip`,
+//! `cancel`, `replace` (with `value`) and `error`. Empty output means continue.
+//! Commands receive the context on stdin and run from the hooks directory.
+//! A failing hook (timeout, non-zero exit, HTTP error, malformed verdict) is
+//! logged and skipped unless `fail_closed` is set, in which case the action is
+//! cancelled.
+
+use super::manager::HookManager;
+use super::types::{Hook, HookContext, HookEvent, HookPriority, HookResult};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+fn default_priority() -> HookPriority {
+    HookPriority::Normal
+}
+
+fn default_timeout_secs() -> u64 {
+    5
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Hook definition from a `.ron` file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalHookDef {
+    /// Unique identifier for this hook
+    pub id: String,
+    /// Human-readable name
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Events this hook subscribes to
+    pub events: Vec<HookEvent>,
+    /// Only run for these tools on tool events (empty = all tools)
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default = "default_priority")]
+    pub priority: HookPriority,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Global default; can be overridden per channel through the API
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Cancel the action when the hook fails instead of letting it through
+    #[serde(default)]
+    pub fail_closed: bool,
+    pub action: ExternalAction,
+}
+
+/// What an external hook runs
+#[derive(Debug, Clone, Deserialize)]
+pub enum ExternalAction {
+    /// Run a program with the context as JSON on stdin; the verdict is read from stdout
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        env: HashMap<String, String>,
+    },
+    /// POST the context as JSON; the verdict is the response body
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum VerdictAction {
+    #[default]
+    Continue,
+    Skip,
+    Cancel,
+    Replace,
+    Error,
+}
+
+/// JSON verdict returned by a command or webhook
+#[derive(Debug, Default, Deserialize)]
+struct Verdict {
+    #[serde(default)]
+    action: VerdictAction,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    value: Option<Value>,
+    #[serde(default)]
+    tool_args: Option<Value>,
+}
+
+/// A hook backed by an external command or webhook
+pub struct ExternalHook {
+    def: ExternalHookDef,
+    /// Working directory for commands (the hooks config directory)
+    base_dir: PathBuf,
+}
+
+impl ExternalHook {
+    pub fn new(def: ExternalHookDef, base_dir: PathBuf) -> Self {
+        Self { def, base_dir }
+    }
+
+    /// Load a hook from a RON file; commands run from the file's directory
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let def = Self::parse(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+        Ok(Self::new(def, base_dir))
+    }
+
+    /// Parse a hook definition from a RON string
+    pub fn parse(content: &str) -> Result<ExternalHookDef, String> {
+        let options = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME);
+        let def: ExternalHookDef = options
+            .from_str(content)
+            .map_err(|e| format!("Failed to parse RON: {}", e))?;
+        if def.events.is_empty() {
+            return Err(format!("Hook '{}' has no events", def.id));
+        }
+        Ok(def)
+    }
+
+    async fn run_command(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+        payload: &[u8],
+        event: HookEvent,
+    ) -> Result<String, String> {
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .envs(env)
+            .env("STARK_HOOK_EVENT", event.as_str())
+            .env("STARK_HOOK_ID", &self.def.id)
+            .current_dir(&self.base_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // The manager drops the future on timeout; make sure the process goes with it
+            .kill_on_drop(true);
+
+        let mut child = cmd.spawn().map_err(|e| format!("Failed to run '{}': {}", program, e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A command that ignores stdin may exit before reading it; that's fine
+            let _ = stdin.write_all(payload).await;
+        }
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| format!("Failed to wait for '{}': {}", program, e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "'{}' exited with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    async fn run_webhook(
+        &self,
+        url: &str,
+        headers: &HashMap<String, String>,
+        payload: Vec<u8>,
+    ) -> Result<String, String> {
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout())
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+        let mut request = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(payload);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Webhook request failed: {}", e))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read webhook response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("Webhook returned {}: {}", status, body.trim()));
+        }
+        Ok(body)
+    }
+
+    /// Whether the hook's tool filter lets this context through
+    fn matches_tool(&self, context: &HookContext) -> bool {
+        match &context.tool_name {
+            Some(name) if !self.def.tools.is_empty() => self.def.tools.iter().any(|t| t == name),
+            _ => true,
+        }
+    }
+}
+
+/// Turn a verdict into a HookResult, applying rewritten tool args to the context
+fn parse_verdict(hook_id: &str, output: &str, context: &mut HookContext) -> HookResult {
+    let output = output.trim();
+    if output.is_empty() {
+        return HookResult::Continue(None);
+    }
+    let verdict: Verdict = match serde_json::from_str(output) {
+        Ok(v) => v,
+        Err(e) => return HookResult::Error(format!("Invalid verdict from hook '{}': {}", hook_id, e)),
+    };
+    match verdict.action {
+        VerdictAction::Continue => {
+            if let Some(args) = verdict.tool_args {
+                context.tool_args = Some(args);
+            }
+            HookResult::Continue(verdict.value)
+        }
+        VerdictAction::Skip => HookResult::Skip,
+        VerdictAction::Cancel => HookResult::Cancel(
+            verdict
+                .reason
+                .unwrap_or_else(|| format!("Cancelled by hook '{}'", hook_id)),
+        ),
+        VerdictAction::Replace => match verdict.value {
+            Some(value) => HookResult::Replace(value),
+            None => HookResult::Error(format!("Hook '{}' returned replace without a value", hook_id)),
+        },
+        VerdictAction::Error => HookResult::Error(
+            verdict
+                .reason
+                .unwrap_or_else(|| format!("Hook '{}' reported an error", hook_id)),
+        ),
+    }
+}
+
+#[async_trait]
+impl Hook for ExternalHook {
+    fn id(&self) -> &str {
+        &self.def.id
+    }
+
+    fn name(&self) -> &str {
+        &self.def.name
+    }
+
+    fn description(&self) -> &str {
+        self.def.description.as_deref().unwrap_or("")
+    }
+
+    fn events(&self) -> Vec<HookEvent> {
+        self.def.events.clone()
+    }
+
+    fn priority(&self) -> HookPriority {
+        self.def.priority
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.def.timeout_secs.max(1))
+    }
+
+    fn enabled(&self) -> bool {
+        self.def.enabled
+    }
+
+    fn fail_closed(&self) -> bool {
+        self.def.fail_closed
+    }
+
+    async fn execute(&self, context: &mut HookContext) -> HookResult {
+        if !self.matches_tool(context) {
+            return HookResult::Continue(None);
+        }
+
+        let payload = match serde_json::to_vec(&*context) {
+            Ok(p) => p,
+            Err(e) => return HookResult::Error(format!("Failed to serialize hook context: {}", e)),
+        };
+
+        let output = match &self.def.action {
+            ExternalAction::Command { program, args, env } => {
+                self.run_command(program, args, env, &payload, context.event).await
+            }
+            ExternalAction::Webhook { url, headers } => self.run_webhook(url, headers, payload).await,
+        };
+
+        match output {
+            Ok(output) => parse_verdict(&self.def.id, &output, context),
+            Err(e) => HookResult::Error(e),
+        }
+    }
+}
+
+/// Load all hooks from `{config_dir}/hooks/*.ron` into the manager
+pub fn load_external_hooks(config_dir: &Path, manager: &HookManager) -> usize {
+    let dir = config_dir.join("hooks");
+    if !dir.exists() {
+        log::debug!("[HOOKS] No external hooks directory at {}", dir.display());
+        return 0;
+    }
+
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("[HOOKS] Failed to read directory {}: {}", dir.display(), e);
+            return 0;
+        }
+    };
+
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().map(|e| e != "ron").unwrap_or(true) {
+            continue;
+        }
+        match ExternalHook::from_file(&path) {
+            Ok(hook) => {
+                log::info!(
+                    "[HOOKS] Loaded external hook {} from {}{}",
+                    hook.id(),
+                    path.display(),
+                    if hook.enabled() { "" } else { " (disabled)" }
+                );
+                manager.register(Arc::new(hook));
+                count += 1;
+            }
+            Err(e) => log::warn!("[HOOKS] Failed to load {}: {}", path.display(), e),
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn command_hook(script: &str) -> ExternalHook {
+        command_hook_with(script, "")
+    }
+
+    /// Command hook with extra definition fields (e.g. "fail_closed: true,")
+    fn command_hook_with(script: &str, fields: &str) -> ExternalHook {
+        let def = ExternalHook::parse(&format!(
+            r#"ExternalHookDef(
+                id: "test_command",
+                name: "Test Command",
+                events: [before_tool_call],
+                tools: ["send_eth"],
+                priority: high,
+                {}
+                action: Command(program: "sh", args: ["-c", {:?}]),
+            )"#,
+            fields, script
+        ))
+        .expect("parse hook");
+        ExternalHook::new(def, std::env::temp_dir())
+    }
+
+    fn tool_context(tool: &str) -> HookContext {
+        HookContext::new(HookEvent::BeforeToolCall)
+            .with_channel(1, Some(2))
+            .with_tool(tool.to_string(), json!({"to": "0xabc"}))
+    }
+
+    #[test]
+    fn test_parse_definition() {
+        let def = ExternalHook::parse(
+            r#"ExternalHookDef(
+                id: "compliance",
+                name: "Compliance",
+                events: [before_tool_call, before_response],
+                enabled: false,
+                action: Webhook(url: "http://localhost:9000/hooks", headers: {"X-Token": "abc"}),
+            )"#,
+        )
+        .expect("parse hook");
+        assert_eq!(def.events, vec![HookEvent::BeforeToolCall, HookEvent::BeforeResponse]);
+        assert_eq!(def.priority, HookPriority::Normal);
+        assert_eq!(def.timeout_secs, 5);
+        assert!(!def.enabled);
+        assert!(!def.fail_closed);
+        assert!(matches!(def.action, ExternalAction::Webhook { .. }));
+    }
+
+    #[test]
+    fn test_parse_verdicts() {
+        let mut ctx = tool_context("send_eth");
+        assert!(matches!(parse_verdict("h", "", &mut ctx), HookResult::Continue(None)));
+        assert!(matches!(parse_verdict("h", r#"{"action":"skip"}"#, &mut ctx), HookResult::Skip));
+        match parse_verdict("h", r#"{"action":"cancel","reason":"blocked"}"#, &mut ctx) {
+            HookResult::Cancel(reason) => assert_eq!(reason, "blocked"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse_verdict("h", r#"{"action":"replace","value":"new"}"#, &mut ctx) {
+            HookResult::Replace(value) => assert_eq!(value, json!("new")),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(parse_verdict("h", r#"{"action":"replace"}"#, &mut ctx), HookResult::Error(_)));
+        assert!(matches!(parse_verdict("h", "not json", &mut ctx), HookResult::Error(_)));
+
+        parse_verdict("h", r#"{"tool_args":{"to":"0xdef"}}"#, &mut ctx);
+        assert_eq!(ctx.tool_args, Some(json!({"to": "0xdef"})));
+    }
+
+    #[test]
+    fn test_load_shipped_hooks() {
+        let config_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../config");
+        let manager = HookManager::new();
+        assert!(load_external_hooks(&config_dir, &manager) >= 1);
+        // The shipped example is off until enabled globally or per channel
+        assert_eq!(manager.is_hook_enabled("compliance_webhook"), Some(false));
+    }
+
+    #[test]
+    fn test_shipped_compliance_hook_names_real_tools() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../config/hooks/compliance_webhook.ron");
+        let def = ExternalHook::parse(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let registry = crate::tools::create_default_registry();
+        for tool in &def.tools {
+            assert!(registry.has_tool(tool), "unknown tool '{}' in compliance_webhook.ron", tool);
+        }
+        for tool in [
+            "send_eth",
+            "broadcast_web3_tx",
+            "batch_payout",
+            "swap",
+            "token_approvals",
+            "aave",
+            "polymarket_trade",
+        ] {
+            assert!(def.tools.iter().any(|t| t == tool), "compliance_webhook.ron misses '{}'", tool);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_command_receives_context_and_cancels() {
+        // Echo back the tool name from the stdin payload as the cancel reason
+        let hook = command_hook(
+            r#"tool=$(sed -n 's/.*"tool_name":"\([^"]*\)".*/\1/p'); printf '{"action":"cancel","reason":"%s"}' "$tool""#,
+        );
+        let mut ctx = tool_context("send_eth");
+        match hook.execute(&mut ctx).await {
+            HookResult::Cancel(reason) => assert_eq!(reason, "send_eth"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Tools outside the filter are not sent to the command
+        let mut other = tool_context("say_to_user");
+        assert!(matches!(hook.execute(&mut other).await, HookResult::Continue(None)));
+    }
+
+    #[tokio::test]
+    async fn test_command_failure_is_error() {
+        let hook = command_hook("echo nope >&2; exit 3");
+        let mut ctx = tool_context("send_eth");
+        match hook.execute(&mut ctx).await {
+            HookResult::Error(e) => assert!(e.contains("nope"), "unexpected error: {}", e),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fail_closed_timeout_cancels() {
+        let manager = HookManager::new();
+        manager.register(Arc::new(command_hook_with("sleep 5", "timeout_secs: 1, fail_closed: true,")));
+        let mut ctx = tool_context("send_eth");
+        match manager.execute(HookEvent::BeforeToolCall, &mut ctx).await {
+            HookResult::Cancel(reason) => assert!(reason.contains("timed out"), "unexpected reason: {}", reason),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Without fail_closed the same failure lets the action through
+        let manager = HookManager::new();
+        manager.register(Arc::new(command_hook_with("sleep 5", "timeout_secs: 1,")));
+        let mut ctx = tool_context("send_eth");
+        assert!(manager.execute(HookEvent::BeforeToolCall, &mut ctx).await.should_continue());
+    }
+
+    #[tokio::test]
+    async fn test_fail_closed_malformed_verdict_cancels() {
+        let manager = HookManager::new();
+        manager.register(Arc::new(command_hook_with("echo not-json", "fail_closed: true,")));
+        let mut ctx = tool_context("send_eth");
+        assert!(matches!(
+            manager.execute(HookEvent::BeforeToolCall, &mut ctx).await,
+            HookResult::Cancel(_)
+        ));
+    }
+}
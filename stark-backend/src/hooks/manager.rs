@@ -5,6 +5,7 @@
 //! - Executing hooks in priority order
 //! - Tracking hook statistics
 //! - Managing hook configuration from database
+//! - Per-channel enable/disable overrides
 
 use super::types::{BoxedHook, Hook, HookConfig, HookContext, HookEvent, HookPriority, HookResult, HookStats};
 use dashmap::DashMap;
@@ -23,6 +24,8 @@ pub struct HookManager {
     configs: DashMap<String, HookConfig>,
     /// Hook statistics
     stats: DashMap<String, HookStats>,
+    /// Per-channel enable/disable overrides keyed by (hook ID, channel ID)
+    channel_overrides: DashMap<(String, i64), bool>,
     /// Whether to continue on hook errors
     continue_on_error: bool,
 }
@@ -35,6 +38,7 @@ impl HookManager {
             hooks_by_event: DashMap::new(),
             configs: DashMap::new(),
             stats: DashMap::new(),
+            channel_overrides: DashMap::new(),
             continue_on_error: true,
         }
     }
@@ -46,6 +50,7 @@ impl HookManager {
             hooks_by_event: DashMap::new(),
             configs: DashMap::new(),
             stats: DashMap::new(),
+            channel_overrides: DashMap::new(),
             continue_on_error: false,
         }
     }
@@ -126,6 +131,44 @@ impl HookManager {
         hook.enabled()
     }
 
+    /// Check if a hook is enabled for a channel (channel overrides win)
+    fn is_enabled_for_channel(&self, hook: &dyn Hook, channel_id: Option<i64>) -> bool {
+        channel_id
+            .and_then(|channel_id| {
+                self.channel_overrides
+                    .get(&(hook.id().to_string(), channel_id))
+                    .map(|enabled| *enabled)
+            })
+            .unwrap_or_else(|| self.is_enabled(hook))
+    }
+
+    /// Enable or disable a hook for a single channel
+    pub fn set_channel_enabled(&self, id: &str, channel_id: i64, enabled: bool) {
+        self.channel_overrides.insert((id.to_string(), channel_id), enabled);
+    }
+
+    /// Remove a channel override so the hook falls back to its global setting
+    pub fn clear_channel_override(&self, id: &str, channel_id: i64) {
+        self.channel_overrides.remove(&(id.to_string(), channel_id));
+    }
+
+    /// Channel overrides for a hook as (channel ID, enabled) pairs
+    pub fn channel_overrides(&self, id: &str) -> Vec<(i64, bool)> {
+        let mut overrides: Vec<(i64, bool)> = self
+            .channel_overrides
+            .iter()
+            .filter(|entry| entry.key().0 == id)
+            .map(|entry| (entry.key().1, *entry.value()))
+            .collect();
+        overrides.sort();
+        overrides
+    }
+
+    /// Whether a hook is enabled globally (config override or hook default)
+    pub fn is_hook_enabled(&self, id: &str) -> Option<bool> {
+        self.hooks.get(id).map(|hook| self.is_enabled(hook.as_ref()))
+    }
+
     /// Get timeout for a hook
     fn get_timeout(&self, hook: &dyn Hook) -> std::time::Duration {
         // Check config override first
@@ -162,8 +205,8 @@ impl HookManager {
                 None => continue,
             };
 
-            // Check if enabled
-            if !self.is_enabled(hook.as_ref()) {
+            // Check if enabled (globally or for this channel)
+            if !self.is_enabled_for_channel(hook.as_ref(), context.channel_id) {
                 continue;
             }
 
@@ -198,6 +241,15 @@ impl HookManager {
                 }
             );
 
+            // A failing fail-closed hook (e.g. a compliance check) blocks the action
+            let result = match result {
+                HookResult::Error(msg) if hook.fail_closed() => {
+                    log::warn!("[HOOKS] Fail-closed hook {} failed, cancelling: {}", hook.id(), msg);
+                    HookResult::Cancel(format!("Hook '{}' failed: {}", hook.id(), msg))
+                }
+                other => other,
+            };
+
             // Handle result
             match &result {
                 HookResult::Continue(data) => {
@@ -337,4 +389,27 @@ mod tests {
 
         assert!(result.should_continue());
     }
+
+    #[tokio::test]
+    async fn test_channel_override() {
+        let manager = HookManager::new();
+        manager.register(Arc::new(TestHook {
+            id: "test_hook".to_string(),
+            events: vec![HookEvent::BeforeAgentStart],
+            priority: HookPriority::Normal,
+        }));
+        manager.set_channel_enabled("test_hook", 7, false);
+
+        let mut context = HookContext::new(HookEvent::BeforeAgentStart).with_channel(7, None);
+        manager.execute(HookEvent::BeforeAgentStart, &mut context).await;
+        assert_eq!(manager.get_stats("test_hook").unwrap().executions, 0);
+
+        let mut context = HookContext::new(HookEvent::BeforeAgentStart).with_channel(8, None);
+        manager.execute(HookEvent::BeforeAgentStart, &mut context).await;
+        assert_eq!(manager.get_stats("test_hook").unwrap().executions, 1);
+
+        assert_eq!(manager.channel_overrides("test_hook"), vec![(7, false)]);
+        manager.clear_channel_override("test_hook", 7);
+        assert!(manager.channel_overrides("test_hook").is_empty());
+    }
 }
@@ -8,6 +8,7 @@
 //! - Transform data (before_response)
 //! - Log and audit (logging hook)
 //! - Enforce limits (rate_limit hook)
+//! - Call out to external scripts or webhooks (`config/hooks/*.ron`)
 //!
 //! # Example
 //!
@@ -28,9 +29,11 @@
 //! ```
 
 pub mod builtin;
+pub mod external;
 mod manager;
 mod types;
 
+pub use external::load_external_hooks;
 pub use manager::HookManager;
 pub use types::{
     BoxedHook, Hook, HookConfig, HookContext, HookEvent, HookPriority, HookResult, HookStats,
@@ -107,6 +107,24 @@ impl HookResult {
             _ => None,
         }
     }
+
+    /// Reason the operation was blocked (Skip or Cancel), if any
+    pub fn block_reason(&self) -> Option<String> {
+        match self {
+            HookResult::Skip => Some("skipped by hook".to_string()),
+            HookResult::Cancel(msg) => Some(msg.clone()),
+            _ => None,
+        }
+    }
+
+    /// Replacement value rendered as text (JSON strings are used verbatim)
+    pub fn replacement_text(&self) -> Option<String> {
+        match self {
+            HookResult::Replace(Value::String(s)) => Some(s.clone()),
+            HookResult::Replace(v) => Some(v.to_string()),
+            _ => None,
+        }
+    }
 }
 
 /// Priority levels for hook execution order
@@ -132,7 +150,7 @@ impl Default for HookPriority {
 }
 
 /// Context passed to hooks during execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct HookContext {
     /// The event that triggered this hook
     pub event: HookEvent,
@@ -325,6 +343,12 @@ pub trait Hook: Send + Sync {
         true
     }
 
+    /// Whether a failure of this hook (error or timeout) cancels the action
+    /// instead of being logged and skipped
+    fn fail_closed(&self) -> bool {
+        false
+    }
+
     /// Execute the hook
     async fn execute(&self, context: &mut HookContext) -> HookResult;
 }
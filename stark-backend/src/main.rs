@@ -4,11 +4,16 @@ use actix_web::{middleware::Logger, web, App, HttpServer};
 use dotenv::dotenv;
 use std::sync::Arc;
 
+mod aave;
 mod ai;
 mod ai_endpoint_config;
+mod ai_pricing;
+mod approvals;
+mod address_book;
 mod backup;
 mod channels;
 mod config;
+mod config_reload;
 mod context;
 mod controllers;
 mod db;
@@ -19,9 +24,13 @@ mod gateway;
 mod integrations;
 mod middleware;
 mod models;
+mod payouts;
+mod polymarket;
+mod portfolio;
 mod qmd_memory;
 mod scheduler;
 mod skills;
+mod swap;
 mod tools;
 mod wallet;
 mod x402;
@@ -30,6 +39,7 @@ mod eip8004;
 mod hooks;
 mod tool_validators;
 mod tx_queue;
+mod metrics;
 mod web3;
 mod keystore_client;
 mod identity_client;
@@ -64,6 +74,8 @@ pub struct AppState {
     /// Either EnvWalletProvider (Standard mode) or FlashWalletProvider (Flash mode)
     /// None if no wallet is configured (graceful degradation - shows warning on login page)
     pub wallet_provider: Option<Arc<dyn WalletProvider>>,
+    /// Hot reload of presets, networks, tokens and validators
+    pub config_reloader: Arc<config_reload::ConfigReloader>,
 }
 
 /// Auto-retrieve backup from keystore on fresh instance
@@ -575,10 +587,14 @@ async fn main() -> std::io::Result<()> {
     tools::builtin::cryptocurrency::token_lookup::load_tokens(config_dir);
     log::info!("Loading network configs from config directory");
     tools::builtin::cryptocurrency::network_lookup::load_networks(config_dir);
+    log::info!("Loading Uniswap pool configs from config directory");
+    swap::load_uniswap_pools(config_dir);
     log::info!("Loading RPC provider configs from config directory");
     tools::rpc_config::load_rpc_providers(config_dir);
     log::info!("Loading AI endpoint presets from config directory");
     ai_endpoint_config::load_ai_endpoints(config_dir);
+    log::info!("Loading AI model prices from config directory");
+    ai_pricing::load_ai_pricing(config_dir);
 
     let mut config = Config::from_env();
     let port = config.port;
@@ -618,9 +634,17 @@ async fn main() -> std::io::Result<()> {
     let tool_registry = Arc::new(tools::create_default_registry());
     log::info!("Registered {} tools", tool_registry.len());
 
+    // Initialize Tool Validator Registry (before skills, which can bundle validators)
+    log::info!("Initializing tool validator registry");
+    let validator_registry = Arc::new(tool_validators::create_default_registry());
+    log::info!("Registered {} tool validators", validator_registry.len());
+
     // Initialize Skill Registry (database-backed)
     log::info!("Initializing skill registry");
-    let skill_registry = Arc::new(skills::create_default_registry(db.clone()));
+    let skill_registry = Arc::new(
+        skills::create_default_registry(db.clone())
+            .with_validator_registry(validator_registry.clone()),
+    );
 
     // Load file-based skills into database (for backward compatibility)
     let skill_count = skill_registry.load_all().await.unwrap_or_else(|e| {
@@ -628,6 +652,8 @@ async fn main() -> std::io::Result<()> {
         0
     });
     log::info!("Loaded {} skills from disk, {} total in database", skill_count, skill_registry.len());
+    let bundle_count = skill_registry.load_bundles();
+    log::info!("Registered ABIs, presets and validators bundled with {} skills", bundle_count);
 
     // Initialize Transaction Queue Manager with DB for persistent broadcast history
     // NOTE: Must be created before Gateway so channels can use it for web3 transactions
@@ -708,17 +734,29 @@ async fn main() -> std::io::Result<()> {
 
     // Initialize Execution Tracker for progress display
     log::info!("Initializing execution tracker");
-    let execution_tracker = Arc::new(ExecutionTracker::new(gateway.broadcaster().clone()));
+    let execution_tracker = Arc::new(ExecutionTracker::new(gateway.broadcaster().clone()).with_db(db.clone()));
 
     // Initialize Hook Manager
     log::info!("Initializing hook manager");
     let hook_manager = Arc::new(HookManager::new());
-    log::info!("Hook manager initialized");
+    let external_hooks = hooks::load_external_hooks(config_dir, &hook_manager);
+    match db.list_hook_channel_overrides() {
+        Ok(overrides) => {
+            for (hook_id, channel_id, enabled) in overrides {
+                hook_manager.set_channel_enabled(&hook_id, channel_id, enabled);
+            }
+        }
+        Err(e) => log::warn!("Failed to load hook channel overrides: {}", e),
+    }
+    log::info!("Hook manager initialized ({} external hooks)", external_hooks);
 
-    // Initialize Tool Validator Registry
-    log::info!("Initializing tool validator registry");
-    let validator_registry = Arc::new(tool_validators::create_default_registry());
-    log::info!("Registered {} tool validators", validator_registry.len());
+    // Watch config files so preset/network/token/validator edits apply without a restart
+    let config_reloader = Arc::new(config_reload::ConfigReloader::new(
+        config_dir,
+        validator_registry.clone(),
+        gateway.broadcaster().clone(),
+    ));
+    config_reloader.spawn_watcher(config::config_watch_interval_secs());
 
     // Create the shared MessageDispatcher for all message processing
     log::info!("Initializing message dispatcher");
@@ -735,7 +773,7 @@ async fn main() -> std::io::Result<()> {
          .with_tx_queue(tx_queue.clone())
     );
 
-    // Get broadcaster and channel_manager for the /ws route
+    // Get broadcaster and channel_manager for AppState
     let broadcaster = gateway.broadcaster();
     let channel_manager = gateway.channel_manager();
 
@@ -753,7 +791,7 @@ async fn main() -> std::io::Result<()> {
         execution_tracker.clone(),
         scheduler_config,
         wallet_provider.clone(),
-    ));
+    ).with_tx_queue(tx_queue.clone()));
 
     // Start scheduler background task
     let scheduler_handle = Arc::clone(&scheduler);
@@ -803,6 +841,7 @@ async fn main() -> std::io::Result<()> {
     let tx_q = tx_queue.clone();
     let safe_mode_rl = safe_mode_rate_limiter.clone();
     let wallet_prov = wallet_provider.clone();
+    let cfg_reloader = config_reloader.clone();
     let frontend_dist = frontend_dist.to_string();
     let dev_mode = dev_mode;
 
@@ -829,18 +868,18 @@ async fn main() -> std::io::Result<()> {
                 tx_queue: Arc::clone(&tx_q),
                 safe_mode_rate_limiter: safe_mode_rl.clone(),
                 wallet_provider: wallet_prov.clone(),
+                config_reloader: Arc::clone(&cfg_reloader),
             }))
             .app_data(web::Data::new(Arc::clone(&sched)))
-            // WebSocket data for /ws route
-            .app_data(web::Data::new(Arc::clone(&db)))
-            .app_data(web::Data::new(Arc::clone(&chan_mgr)))
-            .app_data(web::Data::new(Arc::clone(&bcast)))
-            .app_data(web::Data::new(Arc::clone(&tx_q)))
-            .app_data(web::Data::new(wallet_prov.clone()))
+            // Role-based access control + audit log for operator sessions
+            .wrap(actix_web::middleware::from_fn(middleware::session_auth::role_guard))
             .wrap(Logger::default())
             .wrap(cors)
             .configure(controllers::health::config_routes)
+            .configure(controllers::metrics::config)
             .configure(controllers::auth::config)
+            .configure(controllers::operators::config)
+            .configure(controllers::executions::config)
             .configure(controllers::dashboard::config)
             .configure(controllers::chat::config)
             .configure(controllers::api_keys::config)
@@ -851,16 +890,21 @@ async fn main() -> std::io::Result<()> {
             .configure(controllers::tools::config)
             .configure(controllers::skills::config)
             .configure(controllers::cron::config)
+            .configure(controllers::hooks::config)
             .configure(controllers::gmail::config)
             .configure(controllers::payments::config)
+            .configure(controllers::usage::config)
             .configure(controllers::eip8004::config)
             .configure(controllers::files::config)
             .configure(controllers::intrinsic::config)
             .configure(controllers::journal::config)
             .configure(controllers::tx_queue::config)
             .configure(controllers::broadcasted_transactions::config)
+            .configure(controllers::portfolio::config)
+            .configure(controllers::address_book::config)
             .configure(controllers::mindmap::config)
             .configure(controllers::memory::config)
+            .configure(controllers::config_reload::config)
             .configure(controllers::well_known::config)
             // WebSocket Gateway route (same port as HTTP, required for single-port platforms)
             .route("/ws", web::get().to(gateway::actix_ws::ws_handler));
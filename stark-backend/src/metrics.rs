@@ -0,0 +1,344 @@
+//! Prometheus metrics
+//!
+//! A small in-process registry of labelled counters and histograms, rendered in
+//! the Prometheus text exposition format by `controllers::metrics` at `/metrics`.
+//! Call sites record through the global `METRICS`; point-in-time gauges (tx queue
+//! depth, channel listener health) are computed at scrape time instead.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+/// Global metrics registry
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+/// Buckets (seconds) for AI provider calls, which routinely take tens of seconds
+const AI_LATENCY_BUCKETS: &[f64] = &[0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 300.0];
+/// Buckets (seconds) for tool calls and lane waits
+const SHORT_LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+/// Buckets (seconds) for scheduler job runs (full agent executions)
+const JOB_LATENCY_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0];
+
+/// A counter family with a fixed set of label names
+pub struct CounterVec {
+    name: &'static str,
+    help: &'static str,
+    label_names: &'static [&'static str],
+    values: Mutex<BTreeMap<Vec<String>, f64>>,
+}
+
+impl CounterVec {
+    fn new(name: &'static str, help: &'static str, label_names: &'static [&'static str]) -> Self {
+        Self { name, help, label_names, values: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Add `value` to the series with the given label values (in `label_names` order)
+    pub fn inc_by(&self, labels: &[&str], value: f64) {
+        debug_assert_eq!(labels.len(), self.label_names.len(), "label count for {}", self.name);
+        let key = labels.iter().map(|l| l.to_string()).collect();
+        *self.values.lock().entry(key).or_insert(0.0) += value;
+    }
+
+    pub fn inc(&self, labels: &[&str]) {
+        self.inc_by(labels, 1.0);
+    }
+
+    /// Current value of a series (0 if never recorded)
+    #[cfg(test)]
+    pub fn get(&self, labels: &[&str]) -> f64 {
+        let key: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+        self.values.lock().get(&key).copied().unwrap_or(0.0)
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} counter", self.name);
+        for (labels, value) in self.values.lock().iter() {
+            let _ = writeln!(out, "{}{} {}", self.name, format_labels(self.label_names, labels, None), value);
+        }
+    }
+}
+
+#[derive(Clone)]
+struct HistogramValue {
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// A histogram family with a fixed set of label names and buckets
+pub struct HistogramVec {
+    name: &'static str,
+    help: &'static str,
+    label_names: &'static [&'static str],
+    buckets: &'static [f64],
+    values: Mutex<BTreeMap<Vec<String>, HistogramValue>>,
+}
+
+impl HistogramVec {
+    fn new(
+        name: &'static str,
+        help: &'static str,
+        label_names: &'static [&'static str],
+        buckets: &'static [f64],
+    ) -> Self {
+        Self { name, help, label_names, buckets, values: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Record one observation (in seconds)
+    pub fn observe(&self, labels: &[&str], value: f64) {
+        debug_assert_eq!(labels.len(), self.label_names.len(), "label count for {}", self.name);
+        let key = labels.iter().map(|l| l.to_string()).collect();
+        let mut values = self.values.lock();
+        let entry = values.entry(key).or_insert_with(|| HistogramValue {
+            bucket_counts: vec![0; self.buckets.len()],
+            sum: 0.0,
+            count: 0,
+        });
+        for (i, bound) in self.buckets.iter().enumerate() {
+            if value <= *bound {
+                entry.bucket_counts[i] += 1;
+            }
+        }
+        entry.sum += value;
+        entry.count += 1;
+    }
+
+    pub fn observe_duration(&self, labels: &[&str], duration: Duration) {
+        self.observe(labels, duration.as_secs_f64());
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} histogram", self.name);
+        for (labels, value) in self.values.lock().iter() {
+            for (bound, count) in self.buckets.iter().zip(&value.bucket_counts) {
+                let le = bound.to_string();
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    self.name,
+                    format_labels(self.label_names, labels, Some(&le)),
+                    count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{} {}",
+                self.name,
+                format_labels(self.label_names, labels, Some("+Inf")),
+                value.count
+            );
+            let plain = format_labels(self.label_names, labels, None);
+            let _ = writeln!(out, "{}_sum{} {}", self.name, plain, value.sum);
+            let _ = writeln!(out, "{}_count{} {}", self.name, plain, value.count);
+        }
+    }
+}
+
+/// All metrics recorded by the backend
+pub struct Metrics {
+    pub ai_calls: CounterVec,
+    pub ai_call_duration: HistogramVec,
+    pub ai_tokens: CounterVec,
+    pub tool_calls: CounterVec,
+    pub tool_call_duration: HistogramVec,
+    pub channel_listener_errors: CounterVec,
+    pub scheduler_runs: CounterVec,
+    pub scheduler_run_duration: HistogramVec,
+    pub x402_payments: CounterVec,
+    pub x402_spend: CounterVec,
+    pub session_lane_wait: HistogramVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            ai_calls: CounterVec::new(
+                "starkbot_ai_calls_total",
+                "AI provider calls by model archetype and outcome (ok/error)",
+                &["archetype", "outcome"],
+            ),
+            ai_call_duration: HistogramVec::new(
+                "starkbot_ai_call_duration_seconds",
+                "AI provider call latency by model archetype",
+                &["archetype"],
+                AI_LATENCY_BUCKETS,
+            ),
+            ai_tokens: CounterVec::new(
+                "starkbot_ai_tokens_total",
+                "Estimated tokens sent to (input) and received from (output) the AI provider",
+                &["archetype", "direction"],
+            ),
+            tool_calls: CounterVec::new(
+                "starkbot_tool_calls_total",
+                "Tool calls by tool and outcome (ok/error)",
+                &["tool", "outcome"],
+            ),
+            tool_call_duration: HistogramVec::new(
+                "starkbot_tool_call_duration_seconds",
+                "Tool call duration by tool",
+                &["tool"],
+                SHORT_LATENCY_BUCKETS,
+            ),
+            channel_listener_errors: CounterVec::new(
+                "starkbot_channel_listener_errors_total",
+                "Channel listeners that exited with an error, by channel type",
+                &["channel_type"],
+            ),
+            scheduler_runs: CounterVec::new(
+                "starkbot_scheduler_job_runs_total",
+                "Scheduler runs by kind (cron/heartbeat) and outcome (ok/error)",
+                &["kind", "outcome"],
+            ),
+            scheduler_run_duration: HistogramVec::new(
+                "starkbot_scheduler_job_duration_seconds",
+                "Scheduler run duration by kind",
+                &["kind"],
+                JOB_LATENCY_BUCKETS,
+            ),
+            x402_payments: CounterVec::new(
+                "starkbot_x402_payments_total",
+                "x402 payments made, by asset",
+                &["asset"],
+            ),
+            x402_spend: CounterVec::new(
+                "starkbot_x402_spend_total",
+                "Amount spent on x402 payments, in asset units (e.g. USDC)",
+                &["asset"],
+            ),
+            session_lane_wait: HistogramVec::new(
+                "starkbot_session_lane_wait_seconds",
+                "Time spent waiting to acquire a session lane",
+                &[],
+                SHORT_LATENCY_BUCKETS,
+            ),
+        }
+    }
+
+    /// Record one AI provider call
+    pub fn record_ai_call(&self, archetype: &str, ok: bool, duration: Duration, input_tokens: u64, output_tokens: u64) {
+        self.ai_calls.inc(&[archetype, outcome(ok)]);
+        self.ai_call_duration.observe_duration(&[archetype], duration);
+        self.ai_tokens.inc_by(&[archetype, "input"], input_tokens as f64);
+        self.ai_tokens.inc_by(&[archetype, "output"], output_tokens as f64);
+    }
+
+    /// Record one tool call
+    pub fn record_tool_call(&self, tool: &str, ok: bool, duration: Duration) {
+        self.tool_calls.inc(&[tool, outcome(ok)]);
+        self.tool_call_duration.observe_duration(&[tool], duration);
+    }
+
+    /// Record one scheduler run ("cron" or "heartbeat")
+    pub fn record_scheduler_run(&self, kind: &str, ok: bool, duration: Duration) {
+        self.scheduler_runs.inc(&[kind, outcome(ok)]);
+        self.scheduler_run_duration.observe_duration(&[kind], duration);
+    }
+
+    /// Record an x402 payment; `amount_formatted` is in asset units (e.g. "0.0015")
+    pub fn record_x402_payment(&self, asset: &str, amount_formatted: &str) {
+        self.x402_payments.inc(&[asset]);
+        if let Ok(amount) = amount_formatted.trim().parse::<f64>() {
+            self.x402_spend.inc_by(&[asset], amount);
+        }
+    }
+
+    /// Render all recorded counters and histograms in Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.ai_calls.render(&mut out);
+        self.ai_call_duration.render(&mut out);
+        self.ai_tokens.render(&mut out);
+        self.tool_calls.render(&mut out);
+        self.tool_call_duration.render(&mut out);
+        self.channel_listener_errors.render(&mut out);
+        self.scheduler_runs.render(&mut out);
+        self.scheduler_run_duration.render(&mut out);
+        self.x402_payments.render(&mut out);
+        self.x402_spend.render(&mut out);
+        self.session_lane_wait.render(&mut out);
+        out
+    }
+}
+
+fn outcome(ok: bool) -> &'static str {
+    if ok { "ok" } else { "error" }
+}
+
+/// Render a gauge family computed at scrape time
+pub fn render_gauge(out: &mut String, name: &str, help: &str, label_names: &[&str], samples: &[(Vec<String>, f64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, format_labels(label_names, labels, None), value);
+    }
+}
+
+fn format_labels(names: &[&str], values: &[String], le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = names
+        .iter()
+        .zip(values)
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_render() {
+        let counter = CounterVec::new("test_total", "Test counter", &["tool", "outcome"]);
+        counter.inc(&["web_fetch", "ok"]);
+        counter.inc(&["web_fetch", "ok"]);
+        counter.inc_by(&["say \"hi\"", "error"], 0.5);
+        assert_eq!(counter.get(&["web_fetch", "ok"]), 2.0);
+
+        let mut out = String::new();
+        counter.render(&mut out);
+        assert!(out.contains("# TYPE test_total counter"));
+        assert!(out.contains("test_total{tool=\"web_fetch\",outcome=\"ok\"} 2"));
+        assert!(out.contains("test_total{tool=\"say \\\"hi\\\"\",outcome=\"error\"} 0.5"));
+    }
+
+    #[test]
+    fn test_histogram_render() {
+        let histogram = HistogramVec::new("test_seconds", "Test histogram", &[], &[0.1, 1.0]);
+        histogram.observe(&[], 0.0625);
+        histogram.observe(&[], 0.5);
+        histogram.observe(&[], 5.0);
+
+        let mut out = String::new();
+        histogram.render(&mut out);
+        assert!(out.contains("test_seconds_bucket{le=\"0.1\"} 1"));
+        assert!(out.contains("test_seconds_bucket{le=\"1\"} 2"));
+        assert!(out.contains("test_seconds_bucket{le=\"+Inf\"} 3"));
+        assert!(out.contains("test_seconds_sum 5.5625"));
+        assert!(out.contains("test_seconds_count 3"));
+    }
+
+    #[test]
+    fn test_x402_spend_parsing() {
+        let metrics = Metrics::new();
+        metrics.record_x402_payment("USDC", "0.25");
+        metrics.record_x402_payment("USDC", "not-a-number");
+        assert_eq!(metrics.x402_payments.get(&["USDC"]), 2.0);
+        assert_eq!(metrics.x402_spend.get(&["USDC"]), 0.25);
+    }
+}
@@ -1,12 +1,27 @@
 // Session authentication middleware
-// This module provides middleware for validating session tokens on protected routes.
-// Currently, authentication is handled directly in controllers, but this module
-// can be extended to provide a reusable middleware wrapper for protected endpoints.
+// Controllers still validate the session token themselves; this module adds
+// role-based access control on top. `role_guard` resolves the operator behind
+// a session, rejects requests whose route needs a higher role, and writes an
+// audit log entry for every mutating request.
 
-use actix_web::{HttpRequest, HttpResponse};
-use std::sync::Arc;
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::{web, HttpRequest, HttpResponse};
+use ethers::types::Address;
+use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use crate::config;
 use crate::db::Database;
+use crate::models::{OperatorRole, Session};
+use crate::tools::rpc_config::resolve_rpc_config;
+use crate::wallet::WalletProvider;
+use crate::web3::contract_wallet;
+use crate::x402::X402EvmRpc;
+use crate::AppState;
 
 pub fn extract_token(req: &HttpRequest) -> Option<String> {
     req.headers()
@@ -35,3 +50,430 @@ pub async fn validate_request(db: &Arc<Database>, req: &HttpRequest) -> Result<(
         }
     }
 }
+
+/// The operator behind an authenticated session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorIdentity {
+    pub public_address: String,
+    pub role: OperatorRole,
+}
+
+/// Resolve the role of a logged-in address.
+///
+/// The configured `LOGIN_ADMIN_PUBLIC_ADDRESS` is always admin; other addresses
+/// take their role from the `operators` table.
+pub fn resolve_operator_role(
+    db: &Database,
+    admin_address: Option<&str>,
+    public_address: &str,
+) -> Option<OperatorRole> {
+    let address = public_address.to_lowercase();
+    if admin_address.map(|a| a.to_lowercase() == address).unwrap_or(false) {
+        return Some(OperatorRole::Admin);
+    }
+    match db.get_operator(&address) {
+        Ok(Some(operator)) => Some(operator.role),
+        Ok(None) => None,
+        Err(e) => {
+            log::error!("Failed to look up operator {}: {}", address, e);
+            None
+        }
+    }
+}
+
+/// How long a Safe's owner list is trusted before it is read from chain again
+const SAFE_OWNERS_CACHE_SECS: u64 = 60;
+
+struct CachedSafeOwners {
+    safe: String,
+    fetched_at: Instant,
+    owners: Vec<String>,
+}
+
+static SAFE_OWNERS_CACHE: Lazy<Mutex<Option<CachedSafeOwners>>> = Lazy::new(|| Mutex::new(None));
+
+/// Build an RPC client for login checks on the configured login network
+pub fn login_rpc(
+    db: &Database,
+    wallet_provider: Option<&Arc<dyn WalletProvider>>,
+) -> Result<X402EvmRpc, String> {
+    let network = config::login_network();
+    let settings = db
+        .get_bot_settings()
+        .map_err(|e| format!("Failed to load bot settings: {}", e))?;
+    let (url, use_x402) =
+        resolve_rpc_config(&settings.rpc_provider, settings.custom_rpc_endpoints.as_ref(), &network)
+            .ok_or_else(|| format!("No RPC endpoint configured for {}", network))?;
+    match (wallet_provider, config::burner_wallet_private_key()) {
+        (Some(wp), _) => X402EvmRpc::new_with_wallet_provider(wp.clone(), &network, Some(url), use_x402),
+        (None, Some(pk)) => X402EvmRpc::new_with_config(&pk, &network, Some(url), use_x402),
+        (None, None) => Err("Contract wallet login needs a configured wallet for RPC access".to_string()),
+    }
+}
+
+/// Current owners of the login Safe (lowercased), cached for a short time
+async fn login_safe_owners(
+    db: &Database,
+    wallet_provider: Option<&Arc<dyn WalletProvider>>,
+    safe: &str,
+) -> Result<Vec<String>, String> {
+    let cached = SAFE_OWNERS_CACHE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|c| c.safe == safe && c.fetched_at.elapsed().as_secs() < SAFE_OWNERS_CACHE_SECS)
+        .map(|c| c.owners.clone());
+    if let Some(owners) = cached {
+        return Ok(owners);
+    }
+
+    let safe_address: Address = safe
+        .parse()
+        .map_err(|_| format!("Invalid LOGIN_SAFE_ADDRESS: {}", safe))?;
+    let rpc = login_rpc(db, wallet_provider)?;
+    let owners: Vec<String> = contract_wallet::get_safe_owners(&rpc, safe_address)
+        .await?
+        .iter()
+        .map(|o| format!("{:?}", o).to_lowercase())
+        .collect();
+
+    *SAFE_OWNERS_CACHE.lock().unwrap() = Some(CachedSafeOwners {
+        safe: safe.to_string(),
+        fetched_at: Instant::now(),
+        owners: owners.clone(),
+    });
+    Ok(owners)
+}
+
+/// Resolve the role of an address, including owners of the configured login Safe
+/// (`LOGIN_SAFE_ADDRESS`), which are checked against the chain.
+pub async fn resolve_login_role(
+    db: &Database,
+    wallet_provider: Option<&Arc<dyn WalletProvider>>,
+    admin_address: Option<&str>,
+    public_address: &str,
+) -> Option<OperatorRole> {
+    if let Some(role) = resolve_operator_role(db, admin_address, public_address) {
+        return Some(role);
+    }
+    let safe = config::login_safe_address()?;
+    let address = public_address.to_lowercase();
+    // The Safe itself (signing via EIP-1271) counts as one of its owners
+    if address == safe {
+        return Some(config::login_safe_role());
+    }
+    match login_safe_owners(db, wallet_provider, &safe).await {
+        Ok(owners) if owners.contains(&address) => Some(config::login_safe_role()),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("Failed to read owners of login Safe {}: {}", safe, e);
+            None
+        }
+    }
+}
+
+/// Resolve the operator for a session. Sessions without an address (or whose
+/// address is no longer an operator or Safe owner, or whose Safe owners could
+/// not be read) have no identity. In Flash mode the owner's session comes from
+/// the control plane (`/auth/flash`), so an unlisted address on such a session
+/// is the instance owner.
+pub async fn resolve_session_operator(
+    db: &Database,
+    wallet_provider: Option<&Arc<dyn WalletProvider>>,
+    admin_address: Option<&str>,
+    session: &Session,
+) -> Option<OperatorIdentity> {
+    let address = session.public_address.as_deref()?;
+    resolve_login_role(db, wallet_provider, admin_address, address)
+        .await
+        .or_else(|| flash_owner_role(session, crate::wallet::is_flash_mode()))
+        .map(|role| OperatorIdentity {
+            public_address: address.to_lowercase(),
+            role,
+        })
+}
+
+/// Role of a session whose address resolved to no operator: only the owner's
+/// session created by `/auth/flash` falls back to Admin, and only in Flash mode
+fn flash_owner_role(session: &Session, flash_mode: bool) -> Option<OperatorRole> {
+    (flash_mode && session.flash).then_some(OperatorRole::Admin)
+}
+
+/// Minimum role required for an HTTP route, or None for routes the guard ignores
+/// (public endpoints, static files and the WebSocket upgrade, which authenticates itself).
+pub fn required_role(method: &Method, path: &str) -> Option<OperatorRole> {
+    let path = path.trim_end_matches('/');
+    if !path.starts_with("/api/") {
+        return None;
+    }
+
+    const PUBLIC: &[&str] = &[
+        "/api/auth",
+        "/api/health",
+        "/api/version",
+        "/api/gmail/webhook",
+        "/api/mindmap/graph/guest",
+    ];
+    if PUBLIC.iter().any(|p| path == *p || path.starts_with(&format!("{}/", p))) {
+        return None;
+    }
+
+    let under = |prefix: &str| path == prefix || path.starts_with(&format!("{}/", prefix));
+    let read_only = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+
+    // Operator management, audit trail, secrets (channel responses carry bot
+    // tokens), backups, deleting execution history and hook changes (turning
+    // off a fail-closed hook lets the calls it guards through)
+    if under("/api/operators")
+        || under("/api/audit-log")
+        || under("/api/keys/value")
+        || under("/api/keys/cloud_backup")
+        || under("/api/keys/cloud_restore")
+        || under("/api/keys/cloud_preview")
+        || (read_only && under("/api/channels"))
+        || (*method == Method::DELETE && under("/api/executions"))
+        || (!read_only
+            && (under("/api/keys")
+                || under("/api/bot-settings")
+                || under("/api/config")
+                || under("/api/hooks")
+                || under("/api/payments/budget")))
+    {
+        return Some(OperatorRole::Admin);
+    }
+
+    if read_only {
+        return Some(OperatorRole::Viewer);
+    }
+
+    // Anything that moves funds or signs on-chain, and the address book
+    // (trusted contacts skip the recipient check in verify_intent)
+    if under("/api/tx-queue")
+        || under("/api/confirmation/confirm")
+        || under("/api/payments")
+        || under("/api/eip8004")
+        || under("/api/address-book")
+    {
+        return Some(OperatorRole::Treasurer);
+    }
+
+    Some(OperatorRole::Operator)
+}
+
+/// Minimum role required for a gateway JSON-RPC method (see `gateway::schema`).
+/// Unknown methods only need Viewer; the dispatcher rejects them anyway.
+pub fn required_gateway_role(method: &str) -> OperatorRole {
+    crate::gateway::schema::method_role(method).unwrap_or(OperatorRole::Viewer)
+}
+
+/// Record an operator action in the audit log
+pub fn audit(
+    db: &Database,
+    operator: Option<&OperatorIdentity>,
+    source: &str,
+    action: &str,
+    outcome: &str,
+    detail: Option<&str>,
+) {
+    if let Err(e) = db.insert_audit_log(
+        operator.map(|o| o.public_address.as_str()),
+        operator.map(|o| o.role),
+        source,
+        action,
+        outcome,
+        detail,
+    ) {
+        log::error!("Failed to write audit log entry for {}: {}", action, e);
+    }
+}
+
+fn forbidden(error: String) -> HttpResponse {
+    HttpResponse::Forbidden().json(serde_json::json!({
+        "success": false,
+        "error": error
+    }))
+}
+
+/// Role guard middleware (`actix_web::middleware::from_fn(role_guard)`).
+///
+/// Requests without a valid session pass through untouched so each
+/// controller keeps returning its own 401.
+pub async fn role_guard<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, actix_web::Error> {
+    let required = match required_role(req.method(), req.path()) {
+        Some(role) => role,
+        None => return next.call(req).await.map(|res| res.map_into_left_body()),
+    };
+
+    let state = match req.app_data::<web::Data<AppState>>() {
+        Some(state) => state.clone(),
+        None => return next.call(req).await.map(|res| res.map_into_left_body()),
+    };
+
+    let session = match extract_token(req.request()).map(|t| state.db.validate_session(&t)) {
+        Some(Ok(Some(session))) => session,
+        _ => return next.call(req).await.map(|res| res.map_into_left_body()),
+    };
+
+    let action = format!("{} {}", req.method(), req.path());
+    let mutating = !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let admin_address = state.config.login_admin_public_address.as_deref();
+
+    let operator = match resolve_session_operator(
+        &state.db,
+        state.wallet_provider.as_ref(),
+        admin_address,
+        &session,
+    )
+    .await
+    {
+        Some(operator) => operator,
+        None => {
+            audit(&state.db, None, "http", &action, "denied", Some("session has no operator"));
+            let resp = forbidden("This session is not linked to an operator account".to_string());
+            return Ok(req.into_response(resp).map_into_right_body());
+        }
+    };
+
+    if !operator.role.allows(required) {
+        log::warn!(
+            "[AUTH] {} ({}) denied {} - requires {}",
+            operator.public_address,
+            operator.role,
+            action,
+            required
+        );
+        audit(&state.db, Some(&operator), "http", &action, "denied", Some(&format!("requires {}", required)));
+        let resp = forbidden(format!("Requires {} role (you are {})", required, operator.role));
+        return Ok(req.into_response(resp).map_into_right_body());
+    }
+
+    let res = next.call(req).await?;
+    if mutating {
+        let status = res.status();
+        let outcome = if status.is_success() { "ok" } else { "failed" };
+        audit(&state.db, Some(&operator), "http", &action, outcome, Some(status.as_str()));
+    }
+    Ok(res.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_and_non_api_routes_are_ignored() {
+        assert_eq!(required_role(&Method::POST, "/api/auth/validate_auth"), None);
+        assert_eq!(required_role(&Method::GET, "/api/health"), None);
+        assert_eq!(required_role(&Method::POST, "/api/gmail/webhook"), None);
+        assert_eq!(required_role(&Method::GET, "/ws"), None);
+        assert_eq!(required_role(&Method::GET, "/index.html"), None);
+    }
+
+    #[test]
+    fn test_reads_need_viewer() {
+        assert_eq!(required_role(&Method::GET, "/api/dashboard"), Some(OperatorRole::Viewer));
+        assert_eq!(required_role(&Method::GET, "/api/tx-queue/pending"), Some(OperatorRole::Viewer));
+        assert_eq!(required_role(&Method::GET, "/api/keys"), Some(OperatorRole::Viewer));
+    }
+
+    #[test]
+    fn test_mutations_need_operator() {
+        assert_eq!(required_role(&Method::POST, "/api/chat"), Some(OperatorRole::Operator));
+        assert_eq!(required_role(&Method::PUT, "/api/channels/3"), Some(OperatorRole::Operator));
+        assert_eq!(required_role(&Method::DELETE, "/api/cron/jobs/abc"), Some(OperatorRole::Operator));
+    }
+
+    #[test]
+    fn test_financial_routes_need_treasurer() {
+        assert_eq!(required_role(&Method::POST, "/api/confirmation/confirm"), Some(OperatorRole::Treasurer));
+        assert_eq!(required_role(&Method::POST, "/api/payments"), Some(OperatorRole::Treasurer));
+        assert_eq!(required_role(&Method::PUT, "/api/address-book/4"), Some(OperatorRole::Treasurer));
+        assert_eq!(required_role(&Method::POST, "/api/confirmation/cancel"), Some(OperatorRole::Operator));
+    }
+
+    #[test]
+    fn test_admin_routes() {
+        assert_eq!(required_role(&Method::GET, "/api/operators"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::GET, "/api/audit-log"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::GET, "/api/keys/value"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::POST, "/api/keys"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::POST, "/api/config/reload"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::PUT, "/api/payments/budget"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::DELETE, "/api/executions/abc"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::POST, "/api/executions/abc/replay"), Some(OperatorRole::Operator));
+        assert_eq!(required_role(&Method::GET, "/api/config/reload"), Some(OperatorRole::Viewer));
+        // Prefix matching is per path segment
+        assert_eq!(required_role(&Method::GET, "/api/keysmith"), Some(OperatorRole::Viewer));
+    }
+
+    #[test]
+    fn test_channel_reads_need_admin() {
+        // Channel responses and settings include bot/app tokens
+        assert_eq!(required_role(&Method::GET, "/api/channels"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::GET, "/api/channels/3"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::GET, "/api/channels/3/settings"), Some(OperatorRole::Admin));
+        assert_eq!(required_role(&Method::POST, "/api/channels/3/start"), Some(OperatorRole::Operator));
+    }
+
+    #[test]
+    fn test_flash_admin_fallback_needs_flash_session() {
+        let db = Database::new(":memory:").unwrap();
+        let owner = db.create_flash_session("0x00000000000000000000000000000000000000aa").unwrap();
+        let safe_owner = db.create_session_for_address(Some("0x00000000000000000000000000000000000000bb")).unwrap();
+        assert!(db.validate_session(&owner.token).unwrap().unwrap().flash);
+        assert!(!db.validate_session(&safe_owner.token).unwrap().unwrap().flash);
+
+        assert_eq!(flash_owner_role(&owner, true), Some(OperatorRole::Admin));
+        assert_eq!(flash_owner_role(&owner, false), None);
+        // A wallet login that lost its role (removed Safe owner, RPC error) gets no access
+        assert_eq!(flash_owner_role(&safe_owner, true), None);
+    }
+
+    #[test]
+    fn test_hook_changes_need_admin() {
+        // Turning off a fail-closed compliance hook would let transactions through
+        assert_eq!(required_role(&Method::GET, "/api/hooks"), Some(OperatorRole::Viewer));
+        assert_eq!(
+            required_role(&Method::PUT, "/api/hooks/compliance_webhook/channels/3"),
+            Some(OperatorRole::Admin)
+        );
+        assert_eq!(
+            required_role(&Method::DELETE, "/api/hooks/compliance_webhook/channels/3"),
+            Some(OperatorRole::Admin)
+        );
+    }
+
+    #[test]
+    fn test_gateway_roles() {
+        assert_eq!(required_gateway_role("tx_queue.confirm"), OperatorRole::Treasurer);
+        assert_eq!(required_gateway_role("tx_queue.deny"), OperatorRole::Operator);
+        assert_eq!(required_gateway_role("confirmation.confirm"), OperatorRole::Treasurer);
+        assert_eq!(required_gateway_role("channels.start"), OperatorRole::Operator);
+        assert_eq!(required_gateway_role("status"), OperatorRole::Viewer);
+        assert_eq!(required_gateway_role("chat.send"), OperatorRole::Operator);
+        assert_eq!(required_gateway_role("ask_user.answer"), OperatorRole::Operator);
+        assert_eq!(required_gateway_role("memory.search"), OperatorRole::Viewer);
+    }
+
+    #[test]
+    fn test_resolve_operator_role() {
+        let db = Database::new(":memory:").expect("db");
+        let admin = "0x00000000000000000000000000000000000000aa";
+        let treasurer = "0x00000000000000000000000000000000000000bb";
+
+        assert_eq!(
+            resolve_operator_role(&db, Some(admin), &admin.to_uppercase().replace("0X", "0x")),
+            Some(OperatorRole::Admin)
+        );
+        assert_eq!(resolve_operator_role(&db, Some(admin), treasurer), None);
+
+        db.upsert_operator(treasurer, OperatorRole::Treasurer, Some("finance")).unwrap();
+        assert_eq!(resolve_operator_role(&db, Some(admin), treasurer), Some(OperatorRole::Treasurer));
+
+        db.delete_operator(treasurer).unwrap();
+        assert_eq!(resolve_operator_role(&db, Some(admin), treasurer), None);
+    }
+}
@@ -11,10 +11,74 @@ pub struct AgentSettings {
     pub max_context_tokens: i32,
     pub enabled: bool,
     pub secret_key: Option<String>,
+    /// Explicit model name (None = the archetype's default model)
+    pub model: Option<String>,
+    /// Which client talks to the endpoint
+    pub provider: ProviderKind,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// API flavour spoken by the configured endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// Anthropic for the claude archetype, OpenAI-compatible for everything else
+    #[default]
+    Auto,
+    /// Native Anthropic Messages API
+    Anthropic,
+    /// OpenAI-compatible chat completions (OpenAI, Kimi, x402 relays, vLLM, ...)
+    OpenAI,
+    /// Native Ollama /api/chat
+    Ollama,
+}
+
+impl ProviderKind {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "" | "auto" => Some(ProviderKind::Auto),
+            "anthropic" | "claude" => Some(ProviderKind::Anthropic),
+            "openai" => Some(ProviderKind::OpenAI),
+            "ollama" | "llama" => Some(ProviderKind::Ollama),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::Auto => "auto",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::OpenAI => "openai",
+            ProviderKind::Ollama => "ollama",
+        }
+    }
+}
+
+/// Model selection and sampling parameters saved alongside an endpoint
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelOptions {
+    pub model: Option<String>,
+    pub provider: ProviderKind,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+}
+
+impl AgentSettings {
+    /// Model selection and sampling parameters of these settings
+    pub fn model_options(&self) -> ModelOptions {
+        ModelOptions {
+            model: self.model.clone(),
+            provider: self.provider,
+            temperature: self.temperature,
+            top_p: self.top_p,
+        }
+    }
+}
+
 /// Minimum allowed context tokens (ensures compaction has room to work)
 pub const MIN_CONTEXT_TOKENS: i32 = 80_000;
 /// Default context tokens (Claude/most models)
@@ -32,6 +96,10 @@ impl Default for AgentSettings {
             max_context_tokens: DEFAULT_CONTEXT_TOKENS,
             enabled: true,
             secret_key: None,
+            model: None,
+            provider: ProviderKind::Auto,
+            temperature: None,
+            top_p: None,
             created_at: now,
             updated_at: now,
         }
@@ -48,6 +116,10 @@ pub struct AgentSettingsResponse {
     pub max_context_tokens: i32,
     pub enabled: bool,
     pub has_secret_key: bool,
+    pub model: Option<String>,
+    pub provider: ProviderKind,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -62,6 +134,10 @@ impl From<AgentSettings> for AgentSettingsResponse {
             max_context_tokens: settings.max_context_tokens,
             enabled: settings.enabled,
             has_secret_key: settings.secret_key.is_some(),
+            model: settings.model,
+            provider: settings.provider,
+            temperature: settings.temperature,
+            top_p: settings.top_p,
             created_at: settings.created_at,
             updated_at: settings.updated_at,
         }
@@ -79,6 +155,9 @@ pub struct UpdateAgentSettingsRequest {
     #[serde(default = "default_max_context_tokens")]
     pub max_context_tokens: i32,
     pub secret_key: Option<String>,
+    /// Model name, provider and sampling parameters (all optional)
+    #[serde(flatten)]
+    pub options: ModelOptions,
 }
 
 fn default_archetype() -> String {
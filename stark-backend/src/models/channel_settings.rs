@@ -64,6 +64,8 @@ pub enum ChannelSettingKey {
     SlackBotToken,
     /// Slack: App-level token for Socket Mode (xapp-...)
     SlackAppToken,
+    /// Slack: Comma-separated list of Slack member IDs allowed to approve queued transactions
+    SlackAdminUserIds,
     /// Twitter: Bot's Twitter handle without @ (e.g., "starkbotai")
     TwitterBotHandle,
     /// Twitter: Numeric Twitter user ID (required for mentions API)
@@ -90,6 +92,7 @@ impl ChannelSettingKey {
             Self::TelegramBotToken => "Bot Token",
             Self::SlackBotToken => "Bot Token",
             Self::SlackAppToken => "App Token (Socket Mode)",
+            Self::SlackAdminUserIds => "Admin User IDs (Optional)",
             Self::TwitterBotHandle => "Bot Handle",
             Self::TwitterBotUserId => "Bot User ID",
             Self::TwitterPollIntervalSecs => "Poll Interval (seconds)",
@@ -115,6 +118,7 @@ impl ChannelSettingKey {
                 "Comma-separated Discord user IDs that have full agent access. \
                  If left empty, Discord's Administrator permission is used. \
                  If any IDs are set, ONLY those users have admin access (Discord admin role is ignored). \
+                 Approving queued transactions from Discord always requires an ID listed here. \
                  Get your ID: enable Developer Mode in Discord settings, then right-click your username."
             }
             Self::TelegramBotToken => {
@@ -129,6 +133,11 @@ impl ChannelSettingKey {
                 "Your Slack app-level token for Socket Mode (starts with xapp-). \
                  Found under Basic Information > App-Level Tokens in your Slack app settings."
             }
+            Self::SlackAdminUserIds => {
+                "Comma-separated Slack member IDs allowed to approve or deny queued transactions \
+                 from Slack. If left empty, transactions can only be approved from the web UI. \
+                 Find a member ID under the user's profile > More > Copy member ID."
+            }
             Self::TwitterBotHandle => {
                 "Your bot's Twitter handle without the @ symbol (e.g., 'starkbotai'). \
                  This is used to remove self-mentions from incoming tweets."
@@ -160,6 +169,7 @@ impl ChannelSettingKey {
                 "Telegram numeric user ID of the admin. Messages from this user get full agent access; \
                  all other users are restricted to safe mode. If not set, all users get full access \
                  (backwards-compatible). Find your ID by messaging @userinfobot on Telegram. \
+                 Only this user can approve queued transactions from Telegram. \
                  WARNING: This account gets full agent access — only set this to a user you control."
             }
         }
@@ -174,6 +184,7 @@ impl ChannelSettingKey {
             Self::TelegramBotToken => SettingInputType::Text,
             Self::SlackBotToken => SettingInputType::Text,
             Self::SlackAppToken => SettingInputType::Text,
+            Self::SlackAdminUserIds => SettingInputType::Text,
             Self::TwitterBotHandle => SettingInputType::Text,
             Self::TwitterBotUserId => SettingInputType::Text,
             Self::TwitterPollIntervalSecs => SettingInputType::Number,
@@ -193,6 +204,7 @@ impl ChannelSettingKey {
             Self::TelegramBotToken => "123456:ABC-DEF...",
             Self::SlackBotToken => "xoxb-...",
             Self::SlackAppToken => "xapp-...",
+            Self::SlackAdminUserIds => "U0123456789, U9876543210",
             Self::TwitterBotHandle => "starkbotai",
             Self::TwitterBotUserId => "1234567890123456789",
             Self::TwitterPollIntervalSecs => "120",
@@ -227,6 +239,7 @@ impl ChannelSettingKey {
             Self::TelegramBotToken => "",
             Self::SlackBotToken => "",
             Self::SlackAppToken => "",
+            Self::SlackAdminUserIds => "",
             Self::TwitterBotHandle => "",
             Self::TwitterBotUserId => "",
             Self::TwitterPollIntervalSecs => "120",
@@ -359,6 +372,7 @@ pub fn get_settings_for_channel_type(channel_type: ChannelType) -> Vec<ChannelSe
         ChannelType::Slack => vec![
             ChannelSettingKey::SlackBotToken.into(),
             ChannelSettingKey::SlackAppToken.into(),
+            ChannelSettingKey::SlackAdminUserIds.into(),
         ],
         ChannelType::Twitter => vec![
             ChannelSettingKey::TwitterBotHandle.into(),
@@ -407,11 +421,12 @@ mod tests {
     #[test]
     fn test_slack_settings() {
         let settings = get_settings_for_channel_type(ChannelType::Slack);
-        // 1 common + 2 Slack-specific (bot_token, app_token)
-        assert_eq!(settings.len(), 3);
+        // 1 common + 3 Slack-specific (bot_token, app_token, admin_user_ids)
+        assert_eq!(settings.len(), 4);
         assert_eq!(settings[0].key, "auto_start_on_boot");
         assert_eq!(settings[1].key, "slack_bot_token");
         assert_eq!(settings[2].key, "slack_app_token");
+        assert_eq!(settings[3].key, "slack_admin_user_ids");
     }
 
     #[test]
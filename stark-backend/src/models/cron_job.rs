@@ -11,6 +11,9 @@ pub enum ScheduleType {
     Every,
     /// Standard 5-field cron expression
     Cron,
+    /// Event trigger (price threshold, token transfer, Safe pending tx, log filter)
+    /// polled by the scheduler; schedule_value holds the JSON trigger spec
+    Event,
 }
 
 impl ScheduleType {
@@ -19,6 +22,7 @@ impl ScheduleType {
             ScheduleType::At => "at",
             ScheduleType::Every => "every",
             ScheduleType::Cron => "cron",
+            ScheduleType::Event => "event",
         }
     }
 
@@ -27,6 +31,7 @@ impl ScheduleType {
             "at" => Some(ScheduleType::At),
             "every" => Some(ScheduleType::Every),
             "cron" => Some(ScheduleType::Cron),
+            "event" => Some(ScheduleType::Event),
             _ => None,
         }
     }
@@ -98,7 +103,8 @@ pub struct CronJob {
     pub name: String,
     pub description: Option<String>,
     pub schedule_type: String,
-    /// For "at": ISO 8601 timestamp, "every": milliseconds, "cron": cron expression
+    /// For "at": ISO 8601 timestamp, "every": milliseconds, "cron": cron expression,
+    /// "event": JSON trigger spec
     pub schedule_value: String,
     /// IANA timezone for cron expressions
     pub timezone: Option<String>,
@@ -309,6 +315,11 @@ impl CronJob {
                 let schedule = Schedule::from_str(&self.schedule_value).ok()?;
                 schedule.upcoming(Utc).next()
             }
+            ScheduleType::Event => {
+                // Event triggers are polled: next check after the poll interval
+                let secs = crate::config::event_trigger_poll_secs() as i64;
+                Some(now + chrono::Duration::seconds(secs))
+            }
         }
     }
 
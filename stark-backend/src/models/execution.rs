@@ -256,6 +256,89 @@ impl TaskMetrics {
     }
 }
 
+/// A persisted execution (one agent run), as stored in `execution_traces`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// Execution ID (same as the root task ID)
+    pub execution_id: String,
+    pub channel_id: i64,
+    pub session_id: Option<i64>,
+    pub chat_id: Option<String>,
+    pub mode: String,
+    pub user_message: Option<String>,
+    /// "running", "completed", "error" or "cancelled"
+    pub status: String,
+    /// Final response on success, error message on failure
+    pub outcome: Option<String>,
+    /// Execution this run replayed, if it is a replay
+    pub replay_of: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<u64>,
+    pub ai_calls: u32,
+    pub tool_calls: u32,
+    /// Estimated tokens across all AI calls (input + output)
+    pub tokens_used: u64,
+    /// Sum of x402 payments made during the run (formatted units, e.g. USDC)
+    pub x402_spend: f64,
+}
+
+/// Kind of span recorded in an execution trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceSpanKind {
+    /// One request to the AI provider
+    AiCall,
+    /// One tool executed through the tool registry
+    ToolCall,
+    /// A tracker task (thinking, planning, ...)
+    Task,
+}
+
+impl TraceSpanKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TraceSpanKind::AiCall => "ai_call",
+            TraceSpanKind::ToolCall => "tool_call",
+            TraceSpanKind::Task => "task",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "ai_call" => Some(TraceSpanKind::AiCall),
+            "tool_call" => Some(TraceSpanKind::ToolCall),
+            "task" => Some(TraceSpanKind::Task),
+            _ => None,
+        }
+    }
+}
+
+/// One step of a persisted execution, as stored in `execution_spans`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceSpan {
+    pub span_id: String,
+    pub execution_id: String,
+    pub parent_span_id: Option<String>,
+    pub kind: TraceSpanKind,
+    /// Tool name, task type, or "ai" for AI calls
+    pub name: String,
+    pub description: Option<String>,
+    /// Whether the step succeeded
+    pub success: bool,
+    pub error: Option<String>,
+    /// Tool arguments, or a summary of what was sent to the AI
+    pub input: Option<serde_json::Value>,
+    /// Tool result, or the full AI response (used for replay)
+    pub output: Option<serde_json::Value>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub tokens: u64,
+    pub x402_amount: Option<String>,
+    pub x402_asset: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
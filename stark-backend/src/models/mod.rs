@@ -7,10 +7,11 @@ pub mod chat_session;
 pub mod cron_job;
 pub mod execution;
 pub mod identity;
+pub mod operator;
 pub mod session;
 pub mod session_message;
 
-pub use agent_settings::{AgentSettings, AgentSettingsResponse, UpdateAgentSettingsRequest, MIN_CONTEXT_TOKENS, DEFAULT_CONTEXT_TOKENS};
+pub use agent_settings::{AgentSettings, AgentSettingsResponse, ModelOptions, ProviderKind, UpdateAgentSettingsRequest, MIN_CONTEXT_TOKENS, DEFAULT_CONTEXT_TOKENS};
 pub use bot_settings::{BotSettings, UpdateBotSettingsRequest, DEFAULT_MAX_TOOL_ITERATIONS, DEFAULT_SAFE_MODE_MAX_QUERIES_PER_10MIN};
 pub use api_key::{ApiKey, ApiKeyResponse};
 pub use channel::{Channel, ChannelResponse, ChannelType, CreateChannelRequest, CreateSafeModeChannelRequest, UpdateChannelRequest};
@@ -27,6 +28,7 @@ pub use identity::{
     GetOrCreateIdentityRequest, IdentityLink, IdentityResponse, LinkIdentityRequest,
     LinkedAccountInfo,
 };
+pub use operator::{AuditLogEntry, Operator, OperatorRole, UpsertOperatorRequest};
 pub use session::Session;
 pub use session_message::{AddMessageRequest, MessageRole, SessionMessage, SessionTranscriptResponse};
 pub use cron_job::{
@@ -34,4 +36,4 @@ pub use cron_job::{
     HeartbeatConfigResponse, JobStatus, ScheduleType, SessionMode, UpdateCronJobRequest,
     UpdateHeartbeatConfigRequest,
 };
-pub use execution::{ExecutionTask, TaskMetrics, TaskStatus, TaskType};
+pub use execution::{ExecutionTask, ExecutionTrace, TaskMetrics, TaskStatus, TaskType, TraceSpan, TraceSpanKind};
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Role granted to a web/gateway operator. Roles are ordered: each role
+/// includes every permission of the roles below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperatorRole {
+    /// Read-only access to dashboards, sessions and history
+    Viewer,
+    /// Can chat with the agent and change non-financial configuration
+    Operator,
+    /// Can approve transactions, payments and manage API keys
+    Treasurer,
+    /// Full access, including operator management and the audit log
+    Admin,
+}
+
+impl OperatorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperatorRole::Viewer => "viewer",
+            OperatorRole::Operator => "operator",
+            OperatorRole::Treasurer => "treasurer",
+            OperatorRole::Admin => "admin",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Some(OperatorRole::Viewer),
+            "operator" => Some(OperatorRole::Operator),
+            "treasurer" => Some(OperatorRole::Treasurer),
+            "admin" => Some(OperatorRole::Admin),
+            _ => None,
+        }
+    }
+
+    /// Whether this role satisfies the given minimum role
+    pub fn allows(&self, required: OperatorRole) -> bool {
+        *self >= required
+    }
+}
+
+impl std::fmt::Display for OperatorRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An address allowed to log in, with its role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operator {
+    pub id: i64,
+    pub public_address: String,
+    pub role: OperatorRole,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request to add or update an operator
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpsertOperatorRequest {
+    pub public_address: String,
+    pub role: OperatorRole,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// One entry in the operator audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    /// Address of the operator who made the request (None for unattributed sessions)
+    pub public_address: Option<String>,
+    pub role: Option<String>,
+    /// "http" or "gateway"
+    pub source: String,
+    /// e.g. "POST /api/channels/3/start" or "tx_queue.confirm"
+    pub action: String,
+    /// "ok", "failed" or "denied"
+    pub outcome: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_ordering() {
+        assert!(OperatorRole::Admin.allows(OperatorRole::Treasurer));
+        assert!(OperatorRole::Treasurer.allows(OperatorRole::Operator));
+        assert!(OperatorRole::Operator.allows(OperatorRole::Viewer));
+        assert!(!OperatorRole::Operator.allows(OperatorRole::Treasurer));
+        assert!(!OperatorRole::Viewer.allows(OperatorRole::Operator));
+    }
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in [
+            OperatorRole::Viewer,
+            OperatorRole::Operator,
+            OperatorRole::Treasurer,
+            OperatorRole::Admin,
+        ] {
+            assert_eq!(OperatorRole::from_str(role.as_str()), Some(role));
+        }
+        assert_eq!(OperatorRole::from_str("Treasurer"), Some(OperatorRole::Treasurer));
+        assert_eq!(OperatorRole::from_str("root"), None);
+    }
+}
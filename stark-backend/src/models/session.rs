@@ -5,6 +5,12 @@ use serde::{Deserialize, Serialize};
 pub struct Session {
     pub id: i64,
     pub token: String,
+    /// Wallet address that signed in (None for legacy/unattributed sessions)
+    #[serde(default)]
+    pub public_address: Option<String>,
+    /// Created by the Flash control plane (`/auth/flash`) for the instance owner
+    #[serde(default)]
+    pub flash: bool,
     pub created_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
 }
@@ -0,0 +1,102 @@
+//! Recipients from a Discord role: members holding the role who registered a
+//! public address with `@starkbot register` (see `discord_hooks`).
+//!
+//! Only registered users are looked up (one member request each), so listing
+//! guild members, which needs the privileged members intent, is not required.
+
+use super::PayoutEntry;
+use crate::db::Database;
+use crate::discord_hooks::db::list_registered_profiles;
+use serde::Deserialize;
+
+const DISCORD_API: &str = "https://discord.com/api/v10";
+
+#[derive(Debug, Deserialize)]
+struct GuildMember {
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// Role ID from a role mention (`<@&123>`) or a bare ID
+pub fn parse_role_id(role: &str) -> Option<String> {
+    let id = role
+        .trim()
+        .trim_start_matches("<@&")
+        .trim_end_matches('>');
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then(|| id.to_string())
+}
+
+/// Payout entries (address + Discord username as label) for every registered
+/// user holding `role` in `guild_id`
+pub async fn discord_role_recipients(
+    db: &Database,
+    bot_token: &str,
+    guild_id: &str,
+    role: &str,
+) -> Result<Vec<PayoutEntry>, String> {
+    let role_id = parse_role_id(role)
+        .ok_or_else(|| format!("Invalid Discord role '{}'. Use a role mention or role ID.", role))?;
+    let profiles = list_registered_profiles(db)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+
+    let mut entries = Vec::new();
+    for profile in profiles {
+        let Some(address) = profile.public_address else {
+            continue;
+        };
+        let url = format!("{}/guilds/{}/members/{}", DISCORD_API, guild_id, profile.discord_user_id);
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bot {}", bot_token))
+            .send()
+            .await
+            .map_err(|e| format!("Discord request failed: {}", e))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            // Registered with the bot but not a member of this guild
+            continue;
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Discord API error ({}): {}", status, body));
+        }
+
+        let member: GuildMember = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Discord member: {}", e))?;
+        if member.roles.contains(&role_id) {
+            entries.push(PayoutEntry {
+                address,
+                amount: None,
+                label: profile.discord_username.or(Some(profile.discord_user_id)),
+            });
+        }
+    }
+
+    log::info!(
+        "[payouts] Discord role {} in guild {}: {} registered members",
+        role_id,
+        guild_id,
+        entries.len()
+    );
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_role_id() {
+        assert_eq!(parse_role_id("<@&123456>").as_deref(), Some("123456"));
+        assert_eq!(parse_role_id(" 987 ").as_deref(), Some("987"));
+        assert_eq!(parse_role_id("@contributors"), None);
+        assert_eq!(parse_role_id(""), None);
+    }
+}
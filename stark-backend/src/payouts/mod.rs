@@ -0,0 +1,487 @@
+//! Batch payouts: pay many recipients in a single transaction
+//!
+//! Recipients come from a CSV/JSON list or from the registered addresses of a
+//! Discord role's members. Every entry is validated like a `verify_intent`
+//! recipient (address book trust level and network restrictions, mentioned by
+//! the user) plus address and amount checks, and deduped;
+//! skipped entries keep the reason so they can be reported per recipient.
+//!
+//! Execution is atomic:
+//! - native currency from the bot wallet goes through Multicall3 `aggregate3Value`
+//! - ERC-20 tokens are paid from a Safe through `MultiSendCallOnly`. Paying
+//!   ERC-20s from the bot wallet through Multicall3 would need an allowance to
+//!   Multicall3, which anyone can spend, so it is not supported.
+
+mod discord_role;
+
+pub use discord_role::discord_role_recipients;
+
+use crate::address_book::{self, Contact, TrustLevel};
+use crate::config;
+use crate::swap;
+use crate::tools::builtin::cryptocurrency::token_lookup::get_network_tokens;
+use crate::tools::builtin::cryptocurrency::{dexscreener, geckoterminal, ToRawAmountTool};
+use crate::web3::multicall::Call3Value;
+use crate::web3::safe::MultiSendCall;
+use ethers::abi::{self, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::id;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// One row of the input list before validation
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct PayoutEntry {
+    /// Address, address book label or ENS name of an address book contact
+    #[serde(alias = "to", alias = "recipient")]
+    pub address: String,
+    /// Human-readable amount; falls back to the batch's `amount_each`
+    #[serde(default, deserialize_with = "deserialize_amount")]
+    pub amount: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Accept amounts as JSON strings or numbers
+fn deserialize_amount<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<serde_json::Value> = Option::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_json::Value::String(s)) => Some(s),
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecipientStatus {
+    /// Part of the batch transaction
+    Included,
+    /// Left out; `reason` says why
+    Skipped,
+}
+
+/// A validated recipient
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutRecipient {
+    /// What the input row said (address, label or ENS name)
+    pub input: String,
+    /// Lowercase 0x address (empty if it could not be resolved)
+    pub address: String,
+    pub label: Option<String>,
+    /// Human-readable amount
+    pub amount: String,
+    /// Amount in raw token units
+    pub raw_amount: String,
+    pub status: RecipientStatus,
+    pub reason: Option<String>,
+}
+
+impl PayoutRecipient {
+    pub fn is_included(&self) -> bool {
+        self.status == RecipientStatus::Included
+    }
+
+    pub fn raw(&self) -> U256 {
+        U256::from_dec_str(&self.raw_amount).unwrap_or_default()
+    }
+}
+
+/// Everything recipients are checked against
+pub struct RecipientRules<'a> {
+    pub network: &'a str,
+    /// Token contract (None for the native currency)
+    pub token: Option<Address>,
+    pub decimals: u8,
+    /// Wallet or Safe paying out
+    pub source: Address,
+    pub contacts: &'a [Contact],
+    /// Lowercase addresses the user supplied (context bank, registers) or that
+    /// come from Discord registrations. Other recipients must be trusted contacts.
+    pub known_addresses: &'a HashSet<String>,
+    /// Amount for entries without one
+    pub amount_each: Option<&'a str>,
+}
+
+/// Parse a recipient list: a JSON array of objects (`address`, `amount`, `label`)
+/// or of address strings, or CSV lines `address[,amount[,label]]` (a header row
+/// is skipped).
+pub fn parse_recipients(text: &str) -> Result<Vec<PayoutEntry>, String> {
+    let text = text.trim();
+    if text.starts_with('[') {
+        let values: Vec<serde_json::Value> =
+            serde_json::from_str(text).map_err(|e| format!("Invalid JSON recipient list: {}", e))?;
+        return values
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| match v {
+                serde_json::Value::String(address) => Ok(PayoutEntry { address, ..Default::default() }),
+                other => serde_json::from_value(other)
+                    .map_err(|e| format!("Invalid recipient #{}: {}", i + 1, e)),
+            })
+            .collect();
+    }
+
+    let mut entries = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split([',', ';', '\t']).map(str::trim).collect();
+        let is_header = i == 0
+            && fields.first().is_some_and(|f| {
+                let f = f.to_lowercase();
+                f == "address" || f == "recipient" || f == "to"
+            });
+        if is_header {
+            continue;
+        }
+        entries.push(PayoutEntry {
+            address: fields[0].to_string(),
+            amount: fields.get(1).filter(|a| !a.is_empty()).map(|a| a.to_string()),
+            label: fields.get(2).filter(|l| !l.is_empty()).map(|l| l.to_string()),
+        });
+    }
+    Ok(entries)
+}
+
+/// Validate and dedupe entries. Every entry yields a recipient; invalid ones are
+/// `Skipped` with a reason.
+pub fn validate_recipients(entries: &[PayoutEntry], rules: &RecipientRules) -> Vec<PayoutRecipient> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut recipients = Vec::with_capacity(entries.len());
+
+    for (i, entry) in entries.iter().enumerate() {
+        let mut recipient = PayoutRecipient {
+            input: entry.address.trim().to_string(),
+            address: String::new(),
+            label: entry.label.clone(),
+            amount: entry.amount.as_deref().or(rules.amount_each).unwrap_or_default().trim().to_string(),
+            raw_amount: "0".to_string(),
+            status: RecipientStatus::Skipped,
+            reason: None,
+        };
+        match check_entry(&mut recipient, rules) {
+            Ok(()) => match seen.get(&recipient.address) {
+                Some(first) => recipient.reason = Some(format!("duplicate of row {}", first)),
+                None => {
+                    seen.insert(recipient.address.clone(), i + 1);
+                    recipient.status = RecipientStatus::Included;
+                }
+            },
+            Err(reason) => recipient.reason = Some(reason),
+        }
+        recipients.push(recipient);
+    }
+    recipients
+}
+
+fn check_entry(recipient: &mut PayoutRecipient, rules: &RecipientRules) -> Result<(), String> {
+    let contact = if recipient.input.starts_with("0x") {
+        let address: Address = recipient
+            .input
+            .parse()
+            .map_err(|_| format!("invalid address '{}'", recipient.input))?;
+        recipient.address = format!("{:?}", address);
+        address_book::find_contact(rules.contacts, &recipient.address)
+    } else {
+        let contact = address_book::find_contact(rules.contacts, &recipient.input)
+            .ok_or_else(|| format!("'{}' is not an address or address book contact", recipient.input))?;
+        recipient.address = contact.address.clone();
+        Some(contact)
+    };
+    if recipient.label.is_none() {
+        recipient.label = contact.map(|c| c.label.clone());
+    }
+
+    let address: Address = recipient.address.parse().map_err(|_| "invalid address".to_string())?;
+    if address.is_zero() {
+        return Err("zero address (tokens would be burned)".to_string());
+    }
+    if Some(address) == rules.token {
+        return Err("token contract address (tokens would be burned)".to_string());
+    }
+    if address == rules.source {
+        return Err("the paying wallet itself".to_string());
+    }
+
+    match contact {
+        Some(c) if c.trust_level == TrustLevel::Blocked => {
+            return Err(format!("'{}' is blocked in the address book", c.label));
+        }
+        Some(c) if !c.allows_network(rules.network) => {
+            return Err(format!("'{}' only receives on {}", c.label, c.networks.join(", ")));
+        }
+        Some(c) if c.trust_level == TrustLevel::Trusted => {}
+        _ if rules.known_addresses.contains(&recipient.address) => {}
+        _ => {
+            return Err("address was not provided by the user or trusted in the address book".to_string());
+        }
+    }
+
+    if recipient.amount.is_empty() {
+        return Err("no amount (set amount_each or an amount per recipient)".to_string());
+    }
+    let raw = ToRawAmountTool::convert_to_raw(&recipient.amount, rules.decimals)
+        .map_err(|e| format!("invalid amount '{}': {}", recipient.amount, e))?;
+    if U256::from_dec_str(&raw).unwrap_or_default().is_zero() {
+        return Err("amount is zero".to_string());
+    }
+    recipient.raw_amount = raw;
+    Ok(())
+}
+
+/// Sum of the included recipients' raw amounts
+pub fn total_raw(recipients: &[PayoutRecipient]) -> U256 {
+    recipients
+        .iter()
+        .filter(|r| r.is_included())
+        .fold(U256::zero(), |acc, r| acc.saturating_add(r.raw()))
+}
+
+/// Check the batch against the payout spending policy
+/// (`STARK_PAYOUT_MAX_RECIPIENTS`, `STARK_PAYOUT_MAX_TOTAL_USD`)
+pub fn check_policy(included: usize, total_usd: Option<f64>) -> Result<(), String> {
+    if included == 0 {
+        return Err("No valid recipients to pay".to_string());
+    }
+    let max_recipients = config::payout_max_recipients();
+    if included > max_recipients {
+        return Err(format!(
+            "Batch has {} recipients, above the limit of {}. Split it into smaller batches.",
+            included, max_recipients
+        ));
+    }
+    let max_usd = config::payout_max_total_usd();
+    if max_usd > 0.0 {
+        match total_usd {
+            Some(usd) if usd > max_usd => {
+                return Err(format!(
+                    "Batch total of ${:.2} exceeds the payout limit of ${:.2}",
+                    usd, max_usd
+                ));
+            }
+            Some(_) => {}
+            None => {
+                return Err(format!(
+                    "Could not price the token to check the ${:.2} payout limit",
+                    max_usd
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// USD price of the paid token (native currency is priced via its wrapped token).
+/// None if no price source knows it.
+pub async fn token_price_usd(network: &str, token: &swap::SwapToken) -> Option<f64> {
+    let address = if token.is_native() {
+        let wrapped = format!("W{}", token.symbol.to_uppercase());
+        get_network_tokens(network)
+            .into_iter()
+            .find(|(symbol, _)| symbol.eq_ignore_ascii_case(&wrapped))?
+            .1
+            .address
+            .to_lowercase()
+    } else {
+        format!("{:?}", token.address)
+    };
+
+    match geckoterminal::fetch_token_prices_usd(network, std::slice::from_ref(&address)).await {
+        Ok(prices) if prices.contains_key(&address) => return prices.get(&address).copied(),
+        Ok(_) => {}
+        Err(e) => log::warn!("[payouts] GeckoTerminal price failed for {}: {}", address, e),
+    }
+    match dexscreener::fetch_token_price_usd(network, &address).await {
+        Ok(price) => price,
+        Err(e) => {
+            log::warn!("[payouts] DexScreener price failed for {}: {}", address, e);
+            None
+        }
+    }
+}
+
+/// Multicall3 calls sending native currency to each included recipient.
+/// Failures are not allowed, so one bad recipient reverts the whole batch
+/// instead of stranding funds in Multicall3.
+pub fn native_calls(recipients: &[PayoutRecipient]) -> Vec<Call3Value> {
+    recipients
+        .iter()
+        .filter(|r| r.is_included())
+        .filter_map(|r| {
+            Some(Call3Value {
+                target: r.address.parse().ok()?,
+                allow_failure: false,
+                value: r.raw(),
+                call_data: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Calldata for ERC-20 `transfer(to, amount)`
+pub fn encode_transfer(to: Address, amount: U256) -> Vec<u8> {
+    let mut data = id("transfer(address,uint256)").to_vec();
+    data.extend(abi::encode(&[Token::Address(to), Token::Uint(amount)]));
+    data
+}
+
+/// MultiSend calls paying each included recipient from a Safe
+pub fn multi_send_calls(token: &swap::SwapToken, recipients: &[PayoutRecipient]) -> Vec<MultiSendCall> {
+    recipients
+        .iter()
+        .filter(|r| r.is_included())
+        .filter_map(|r| {
+            let to: Address = r.address.parse().ok()?;
+            Some(if token.is_native() {
+                MultiSendCall { to, value: r.raw(), data: Vec::new() }
+            } else {
+                MultiSendCall { to: token.address, value: U256::zero(), data: encode_transfer(to, r.raw()) }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "0x742d35cc6634c0532925a3b844bc9e7595f8fdf0";
+    const BOB: &str = "0x1111111111111111111111111111111111111111";
+    const MALLORY: &str = "0x2222222222222222222222222222222222222222";
+
+    fn contact(label: &str, address: &str, trust_level: TrustLevel, networks: &[&str]) -> Contact {
+        Contact {
+            id: 0,
+            label: label.to_string(),
+            address: address.to_string(),
+            ens_name: None,
+            networks: networks.iter().map(|n| n.to_string()).collect(),
+            notes: None,
+            trust_level,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_and_json() {
+        let csv = "address,amount,label\n0x742D35Cc6634C0532925a3b844Bc9e7595f8FdF0, 1.5, Alice\n\n# comment\nBob;2\n";
+        let entries = parse_recipients(csv).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].amount.as_deref(), Some("1.5"));
+        assert_eq!(entries[0].label.as_deref(), Some("Alice"));
+        assert_eq!(entries[1], PayoutEntry { address: "Bob".to_string(), amount: Some("2".to_string()), label: None });
+
+        let json = r#"[{"address": "0xabc", "amount": 3}, {"to": "Bob", "amount": "0.5"}, "0xdef"]"#;
+        let entries = parse_recipients(json).unwrap();
+        assert_eq!(entries[0].amount.as_deref(), Some("3"));
+        assert_eq!(entries[1].address, "Bob");
+        assert_eq!(entries[2].address, "0xdef");
+        assert!(entries[2].amount.is_none());
+    }
+
+    #[test]
+    fn test_validate_recipients() {
+        let contacts = vec![
+            contact("Bob", BOB, TrustLevel::Trusted, &[]),
+            contact("Mallory", MALLORY, TrustLevel::Blocked, &[]),
+        ];
+        let known: HashSet<String> = [ALICE.to_string()].into_iter().collect();
+        let rules = RecipientRules {
+            network: "base",
+            token: Some("0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".parse().unwrap()),
+            decimals: 6,
+            source: "0x3333333333333333333333333333333333333333".parse().unwrap(),
+            contacts: &contacts,
+            known_addresses: &known,
+            amount_each: Some("10"),
+        };
+        let entries = parse_recipients(&format!(
+            "{alice},1.5\nbob\n{alice},2\nMallory,1\n0x4444444444444444444444444444444444444444,1\n{alice_upper},0\nnot-an-address,1",
+            alice = ALICE,
+            alice_upper = ALICE.to_uppercase().replace("0X", "0x"),
+        ))
+        .unwrap();
+        let recipients = validate_recipients(&entries, &rules);
+
+        let included: Vec<_> = recipients.iter().filter(|r| r.is_included()).collect();
+        assert_eq!(included.len(), 2);
+        assert_eq!(included[0].raw_amount, "1500000");
+        assert_eq!(included[1].address, BOB);
+        assert_eq!(included[1].label.as_deref(), Some("Bob"));
+        assert_eq!(included[1].raw_amount, "10000000");
+        assert_eq!(total_raw(&recipients), U256::from(11_500_000u64));
+
+        let reason = |i: usize| recipients[i].reason.clone().unwrap_or_default();
+        assert_eq!(reason(2), "duplicate of row 1");
+        assert!(reason(3).contains("blocked"), "{}", reason(3));
+        assert!(reason(4).contains("not provided by the user"), "{}", reason(4));
+        assert_eq!(reason(5), "amount is zero");
+        assert!(reason(6).contains("not an address"), "{}", reason(6));
+    }
+
+    #[test]
+    fn test_network_restricted_contact() {
+        let contacts = vec![contact("Bob", BOB, TrustLevel::Trusted, &["mainnet"])];
+        let known = HashSet::new();
+        let rules = RecipientRules {
+            network: "base",
+            token: None,
+            decimals: 18,
+            source: Address::repeat_byte(0x33),
+            contacts: &contacts,
+            known_addresses: &known,
+            amount_each: Some("0.1"),
+        };
+        let recipients = validate_recipients(&[PayoutEntry { address: "bob".to_string(), ..Default::default() }], &rules);
+        assert!(!recipients[0].is_included());
+        assert!(recipients[0].reason.as_deref().unwrap().contains("only receives on mainnet"));
+    }
+
+    #[test]
+    fn test_check_policy_limits() {
+        assert!(check_policy(0, Some(1.0)).is_err());
+        assert!(check_policy(config::payout_max_recipients() + 1, Some(1.0)).is_err());
+        assert!(check_policy(3, Some(1.0)).is_ok());
+        assert!(check_policy(3, Some(config::payout_max_total_usd() + 1.0)).is_err());
+        assert!(check_policy(3, None).is_err());
+    }
+
+    #[test]
+    fn test_batch_calls() {
+        let included = |address: &str, raw: &str| PayoutRecipient {
+            input: address.to_string(),
+            address: address.to_string(),
+            label: None,
+            amount: String::new(),
+            raw_amount: raw.to_string(),
+            status: RecipientStatus::Included,
+            reason: None,
+        };
+        let mut skipped = included(MALLORY, "5");
+        skipped.status = RecipientStatus::Skipped;
+        let recipients = vec![included(ALICE, "100"), skipped, included(BOB, "200")];
+
+        let calls = native_calls(&recipients);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].value, U256::from(200));
+        assert!(calls.iter().all(|c| !c.allow_failure && c.call_data.is_empty()));
+
+        let usdc = swap::SwapToken {
+            symbol: "USDC".to_string(),
+            address: Address::repeat_byte(0xaa),
+            decimals: 6,
+        };
+        let calls = multi_send_calls(&usdc, &recipients);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].to, usdc.address);
+        assert_eq!(calls[0].data, encode_transfer(ALICE.parse().unwrap(), U256::from(100)));
+        assert_eq!(hex::encode(&calls[0].data[..4]), "a9059cbb");
+    }
+}
@@ -0,0 +1,428 @@
+//! Exit rule evaluation: take-profit, stop-loss and expiry-based exits
+
+use super::market::{fetch_best_bid, fetch_positions};
+use crate::config;
+use crate::db::tables::polymarket::{PolymarketExitRule, EXIT_RULE_ACTIVE};
+use crate::db::Database;
+use crate::tools::builtin::cryptocurrency::polymarket_signing::{self, AuthenticatedClient};
+use crate::wallet::WalletProvider;
+use chrono::{DateTime, Duration, Utc};
+use polymarket_client_sdk::auth::Uuid;
+use polymarket_client_sdk::clob::types::{OrderType, Side};
+use polymarket_client_sdk::types::{Decimal, U256};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+
+/// Failed exit attempts before a rule is given up on
+const MAX_EXIT_ATTEMPTS: i64 = 5;
+
+/// Unix timestamp of the last exit check run
+static LAST_CHECK: AtomicI64 = AtomicI64::new(0);
+
+/// Prevents overlapping runs when a check takes longer than the interval
+static CHECK_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+struct InProgressGuard;
+
+impl InProgressGuard {
+    fn acquire() -> Option<Self> {
+        CHECK_IN_PROGRESS
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| InProgressGuard)
+    }
+}
+
+impl Drop for InProgressGuard {
+    fn drop(&mut self) {
+        CHECK_IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Why a rule fired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitTrigger {
+    TakeProfit,
+    StopLoss,
+    Expiry,
+}
+
+impl ExitTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitTrigger::TakeProfit => "take_profit",
+            ExitTrigger::StopLoss => "stop_loss",
+            ExitTrigger::Expiry => "expiry",
+        }
+    }
+}
+
+/// Limits on orders the scheduler places without a human in the loop
+#[derive(Debug, Clone)]
+pub struct ExitPolicy {
+    /// Maximum USDC notional of one exit order
+    pub max_order_usd: f64,
+    /// Maximum exit orders per rolling 24 hours
+    pub max_exits_per_day: i64,
+}
+
+impl ExitPolicy {
+    pub fn from_config() -> Self {
+        ExitPolicy {
+            max_order_usd: config::polymarket_max_auto_exit_usd(),
+            max_exits_per_day: config::polymarket_max_auto_exits_per_day(),
+        }
+    }
+
+    /// Check an exit order against the policy given the exits already placed today
+    pub fn check(&self, notional_usd: f64, exits_last_24h: i64) -> Result<(), String> {
+        if exits_last_24h >= self.max_exits_per_day {
+            return Err(format!(
+                "Daily automatic exit limit reached ({} per 24h)",
+                self.max_exits_per_day
+            ));
+        }
+        if notional_usd > self.max_order_usd {
+            return Err(format!(
+                "Exit order of ${:.2} exceeds the automatic exit limit of ${:.2}",
+                notional_usd, self.max_order_usd
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one triggered rule, reported to the dashboard
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitResult {
+    pub rule_id: i64,
+    pub token_id: String,
+    pub market: Option<String>,
+    pub trigger: String,
+    pub price: Option<f64>,
+    pub size: Option<f64>,
+    pub order_id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Validate rule thresholds before storing them
+pub fn validate_rule(
+    take_profit: Option<f64>,
+    stop_loss: Option<f64>,
+    exit_at: Option<DateTime<Utc>>,
+    size: Option<f64>,
+) -> Result<(), String> {
+    if take_profit.is_none() && stop_loss.is_none() && exit_at.is_none() {
+        return Err("Set at least one of take_profit, stop_loss or exit_at".to_string());
+    }
+    for (name, price) in [("take_profit", take_profit), ("stop_loss", stop_loss)] {
+        if let Some(p) = price.filter(|p| !(*p > 0.0 && *p < 1.0)) {
+            return Err(format!("{} must be between 0 and 1, got {}", name, p));
+        }
+    }
+    if let Some((tp, sl)) = take_profit.zip(stop_loss).filter(|(tp, sl)| sl >= tp) {
+        return Err(format!("stop_loss ({}) must be below take_profit ({})", sl, tp));
+    }
+    if exit_at.is_some_and(|at| at <= Utc::now()) {
+        return Err("exit_at must be in the future".to_string());
+    }
+    if let Some(s) = size.filter(|s| *s <= 0.0) {
+        return Err(format!("size must be positive, got {}", s));
+    }
+    Ok(())
+}
+
+/// Decide whether a rule fires given the current best bid
+pub fn evaluate_rule(rule: &PolymarketExitRule, best_bid: Option<f64>, now: DateTime<Utc>) -> Option<ExitTrigger> {
+    if rule.exit_at.is_some_and(|at| at <= now) {
+        return Some(ExitTrigger::Expiry);
+    }
+    let bid = best_bid?;
+    if rule.take_profit.is_some_and(|tp| bid >= tp) {
+        return Some(ExitTrigger::TakeProfit);
+    }
+    if rule.stop_loss.is_some_and(|sl| bid <= sl) {
+        return Some(ExitTrigger::StopLoss);
+    }
+    None
+}
+
+/// Shares to sell: the rule size capped at what is held, rounded down to 2 decimals
+fn exit_size(rule_size: Option<f64>, held: f64) -> f64 {
+    let size = rule_size.map_or(held, |s| s.min(held));
+    (size * 100.0).floor() / 100.0
+}
+
+/// Whether any exit rule is waiting to be evaluated
+pub fn has_active_exit_rules(db: &Database) -> bool {
+    db.list_polymarket_exit_rules(Some(EXIT_RULE_ACTIVE))
+        .map(|rules| !rules.is_empty())
+        .unwrap_or(false)
+}
+
+/// Returns true when the check interval has elapsed and no run is in progress
+pub fn check_due(interval_secs: u64) -> bool {
+    if interval_secs == 0 || CHECK_IN_PROGRESS.load(Ordering::SeqCst) {
+        return false;
+    }
+    let now = Utc::now().timestamp();
+    let last = LAST_CHECK.load(Ordering::SeqCst);
+    if now - last < interval_secs as i64 {
+        return false;
+    }
+    LAST_CHECK.store(now, Ordering::SeqCst);
+    true
+}
+
+/// Evaluate all active exit rules and place exit orders for the ones that fire
+pub async fn run_exit_checks(
+    db: &Arc<Database>,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    policy: &ExitPolicy,
+) -> Result<Vec<ExitResult>, String> {
+    let _guard = InProgressGuard::acquire()
+        .ok_or_else(|| "A Polymarket exit check is already in progress".to_string())?;
+
+    let rules = db
+        .list_polymarket_exit_rules(Some(EXIT_RULE_ACTIVE))
+        .map_err(|e| format!("Failed to load exit rules: {}", e))?;
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let wallet = wallet_provider.get_address();
+    let held: HashMap<String, f64> = fetch_positions(&wallet)
+        .await?
+        .into_iter()
+        .map(|p| (p.asset, p.size))
+        .collect();
+
+    let mut client: Option<(AuthenticatedClient, Uuid)> = None;
+    let mut results = Vec::new();
+    let now = Utc::now();
+
+    for rule in rules {
+        let bid = match fetch_best_bid(&rule.token_id).await {
+            Ok(bid) => bid,
+            Err(e) => {
+                log::warn!("[polymarket] Price check failed for rule {}: {}", rule.id, e);
+                None
+            }
+        };
+        if let Err(e) = db.record_polymarket_exit_check(rule.id, bid) {
+            log::error!("[polymarket] Failed to record check for rule {}: {}", rule.id, e);
+        }
+
+        let trigger = match evaluate_rule(&rule, bid, now) {
+            Some(t) => t,
+            None => continue,
+        };
+
+        log::info!(
+            "[polymarket] Exit rule {} triggered ({}) at bid {:?}",
+            rule.id,
+            trigger.as_str(),
+            bid
+        );
+
+        let outcome = execute_exit(
+            db,
+            wallet_provider,
+            policy,
+            &rule,
+            bid,
+            held.get(&rule.token_id).copied().unwrap_or(0.0),
+            &mut client,
+        )
+        .await;
+
+        let result = match outcome {
+            Ok((order_id, price, size)) => {
+                if let Err(e) = db.mark_polymarket_exit_triggered(rule.id, trigger.as_str(), &order_id, price, size) {
+                    log::error!("[polymarket] Failed to mark rule {} triggered: {}", rule.id, e);
+                }
+                ExitResult {
+                    rule_id: rule.id,
+                    token_id: rule.token_id.clone(),
+                    market: rule.market.clone(),
+                    trigger: trigger.as_str().to_string(),
+                    price: Some(price),
+                    size: Some(size),
+                    order_id: Some(order_id),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                log::warn!("[polymarket] Exit for rule {} failed: {}", rule.id, e);
+                if let Err(db_err) = db.record_polymarket_exit_failure(rule.id, &e, MAX_EXIT_ATTEMPTS) {
+                    log::error!("[polymarket] Failed to record exit failure for rule {}: {}", rule.id, db_err);
+                }
+                ExitResult {
+                    rule_id: rule.id,
+                    token_id: rule.token_id.clone(),
+                    market: rule.market.clone(),
+                    trigger: trigger.as_str().to_string(),
+                    price: bid,
+                    size: None,
+                    order_id: None,
+                    error: Some(e),
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+/// Sell the position for a triggered rule at the best bid (fill-or-kill)
+///
+/// Returns (order_id, price, size). The CLOB client is authenticated lazily
+/// and reused for the rest of the run.
+async fn execute_exit(
+    db: &Database,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    policy: &ExitPolicy,
+    rule: &PolymarketExitRule,
+    bid: Option<f64>,
+    held: f64,
+    client: &mut Option<(AuthenticatedClient, Uuid)>,
+) -> Result<(String, f64, f64), String> {
+    let price = bid.ok_or_else(|| "No bids in the order book".to_string())?;
+    let size = exit_size(rule.size, held);
+    if size <= 0.0 {
+        return Err("No shares of this outcome are held".to_string());
+    }
+
+    let (exits_last_24h, _) = db
+        .polymarket_exit_totals_since(Utc::now() - Duration::hours(24))
+        .map_err(|e| format!("Failed to read exit history: {}", e))?;
+    policy.check(price * size, exits_last_24h)?;
+
+    let token_id = U256::from_str(&rule.token_id).map_err(|e| format!("Invalid token_id: {}", e))?;
+    let price_decimal = Decimal::try_from(price).map_err(|e| format!("Invalid price: {}", e))?;
+    let size_decimal = Decimal::try_from(size).map_err(|e| format!("Invalid size: {}", e))?;
+
+    if client.is_none() {
+        *client = Some(polymarket_signing::authenticate(wallet_provider).await?);
+    }
+    let (clob, api_key) = client.as_ref().expect("authenticated above");
+
+    let response = polymarket_signing::place_limit_order(
+        clob,
+        *api_key,
+        wallet_provider,
+        token_id,
+        Side::Sell,
+        price_decimal,
+        size_decimal,
+        OrderType::FOK,
+    )
+    .await?;
+
+    if !response.success {
+        return Err(response
+            .error_msg
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| "Exit order was not accepted".to_string()));
+    }
+
+    Ok((response.order_id, price, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(take_profit: Option<f64>, stop_loss: Option<f64>, exit_at: Option<DateTime<Utc>>) -> PolymarketExitRule {
+        let now = Utc::now();
+        PolymarketExitRule {
+            id: 1,
+            token_id: "1".to_string(),
+            market: None,
+            outcome: None,
+            size: None,
+            take_profit,
+            stop_loss,
+            exit_at,
+            status: EXIT_RULE_ACTIVE.to_string(),
+            trigger_reason: None,
+            last_price: None,
+            last_checked_at: None,
+            exit_order_id: None,
+            exit_price: None,
+            exit_size: None,
+            attempts: 0,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+            triggered_at: None,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_take_profit_and_stop_loss() {
+        let now = Utc::now();
+        let r = rule(Some(0.8), Some(0.3), None);
+        assert_eq!(evaluate_rule(&r, Some(0.5), now), None);
+        assert_eq!(evaluate_rule(&r, Some(0.8), now), Some(ExitTrigger::TakeProfit));
+        assert_eq!(evaluate_rule(&r, Some(0.25), now), Some(ExitTrigger::StopLoss));
+        assert_eq!(evaluate_rule(&r, None, now), None);
+    }
+
+    #[test]
+    fn test_evaluate_expiry_without_price() {
+        let now = Utc::now();
+        let r = rule(None, None, Some(now - Duration::minutes(1)));
+        assert_eq!(evaluate_rule(&r, None, now), Some(ExitTrigger::Expiry));
+        let r = rule(None, None, Some(now + Duration::minutes(1)));
+        assert_eq!(evaluate_rule(&r, Some(0.5), now), None);
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(validate_rule(None, None, None, None).is_err());
+        assert!(validate_rule(Some(1.2), None, None, None).is_err());
+        assert!(validate_rule(Some(0.5), Some(0.6), None, None).is_err());
+        assert!(validate_rule(Some(0.7), Some(0.3), None, Some(0.0)).is_err());
+        assert!(validate_rule(None, None, Some(Utc::now() - Duration::hours(1)), None).is_err());
+        assert!(validate_rule(Some(0.7), Some(0.3), None, Some(10.0)).is_ok());
+    }
+
+    #[test]
+    fn test_exit_size_and_policy() {
+        assert_eq!(exit_size(None, 12.345), 12.34);
+        assert_eq!(exit_size(Some(5.0), 12.0), 5.0);
+        assert_eq!(exit_size(Some(50.0), 12.0), 12.0);
+
+        let policy = ExitPolicy { max_order_usd: 100.0, max_exits_per_day: 2 };
+        assert!(policy.check(50.0, 0).is_ok());
+        assert!(policy.check(150.0, 0).is_err());
+        assert!(policy.check(50.0, 2).is_err());
+    }
+
+    #[test]
+    fn test_has_active_exit_rules() {
+        let db = Database::new(":memory:").unwrap();
+        assert!(!has_active_exit_rules(&db));
+
+        let rule = db
+            .create_polymarket_exit_rule(&crate::db::tables::polymarket::NewPolymarketExitRule {
+                token_id: "1".to_string(),
+                market: None,
+                outcome: None,
+                size: None,
+                take_profit: Some(0.8),
+                stop_loss: None,
+                exit_at: None,
+            })
+            .unwrap();
+        assert!(has_active_exit_rules(&db));
+
+        db.cancel_polymarket_exit_rule(rule.id).unwrap();
+        assert!(!has_active_exit_rules(&db));
+    }
+}
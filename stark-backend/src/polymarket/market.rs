@@ -0,0 +1,122 @@
+//! Read-only Polymarket market data: positions (Data API) and order books (CLOB)
+
+use crate::tools::builtin::cryptocurrency::polymarket_signing::CLOB_API_URL;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Polymarket Data API base URL
+const DATA_API_URL: &str = "https://data-api.polymarket.com";
+
+/// A position as returned by the Data API `/positions` endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataApiPosition {
+    /// Outcome token ID
+    #[serde(default)]
+    pub asset: String,
+    #[serde(default)]
+    pub condition_id: String,
+    /// Shares held
+    #[serde(default)]
+    pub size: f64,
+    #[serde(default)]
+    pub cur_price: f64,
+    /// Market resolved and this position can be redeemed
+    #[serde(default)]
+    pub redeemable: bool,
+    /// Neg-risk market (redeemed through the Neg Risk Adapter)
+    #[serde(default)]
+    pub negative_risk: bool,
+    #[serde(default)]
+    pub outcome_index: u32,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub outcome: String,
+}
+
+/// Fetch all positions held by a wallet
+pub async fn fetch_positions(wallet: &str) -> Result<Vec<DataApiPosition>, String> {
+    let url = format!("{}/positions?user={}&sizeThreshold=0", DATA_API_URL, wallet);
+    let response = reqwest::Client::new()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch positions: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Positions request failed ({})", response.status()));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse positions: {}", e))
+}
+
+/// Highest bid in the order book for an outcome token (the price a sell fills at)
+pub async fn fetch_best_bid(token_id: &str) -> Result<Option<f64>, String> {
+    let url = format!("{}/book?token_id={}", CLOB_API_URL, token_id);
+    let book: Value = reqwest::Client::new()
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch order book: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse order book: {}", e))?;
+
+    Ok(best_bid(&book))
+}
+
+/// Best bid from a CLOB `/book` response. Bid ordering is not relied on.
+pub fn best_bid(book: &Value) -> Option<f64> {
+    book.get("bids")?
+        .as_array()?
+        .iter()
+        .filter_map(|level| level.get("price")?.as_str()?.parse::<f64>().ok())
+        .fold(None, |best, price| match best {
+            Some(b) if b >= price => Some(b),
+            _ => Some(price),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_best_bid_ignores_ordering() {
+        let book = json!({
+            "bids": [
+                {"price": "0.41", "size": "100"},
+                {"price": "0.45", "size": "10"},
+                {"price": "0.43", "size": "50"}
+            ],
+            "asks": [{"price": "0.47", "size": "20"}]
+        });
+        assert_eq!(best_bid(&book), Some(0.45));
+        assert_eq!(best_bid(&json!({"bids": []})), None);
+    }
+
+    #[test]
+    fn test_parse_data_api_position() {
+        let raw = json!({
+            "asset": "123",
+            "conditionId": "0xabc",
+            "size": 25.5,
+            "curPrice": 1,
+            "redeemable": true,
+            "negativeRisk": false,
+            "outcomeIndex": 0,
+            "title": "Will it rain?",
+            "outcome": "Yes",
+            "avgPrice": 0.4
+        });
+        let position: DataApiPosition = serde_json::from_value(raw).unwrap();
+        assert_eq!(position.condition_id, "0xabc");
+        assert!(position.redeemable);
+        assert_eq!(position.size, 25.5);
+    }
+}
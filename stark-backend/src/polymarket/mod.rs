@@ -0,0 +1,15 @@
+//! Polymarket position management
+//!
+//! Exit rules (take-profit, stop-loss, expiry) stored per outcome token are
+//! evaluated on a scheduler tick against the live order book; triggered rules
+//! sell the position with a fill-or-kill order, capped by the automation policy
+//! in config. Resolved winning positions are queued for redemption in the tx
+//! queue so the user can broadcast them.
+
+mod exits;
+mod market;
+mod redeem;
+
+pub use exits::{check_due, has_active_exit_rules, run_exit_checks, validate_rule, ExitPolicy};
+pub use market::fetch_positions;
+pub use redeem::{queue_redemptions, redeem_call};
@@ -0,0 +1,259 @@
+//! Redeeming resolved positions
+//!
+//! Regular markets redeem through the CTF contract (`redeemPositions` with both
+//! index sets); neg-risk markets redeem through the Neg Risk Adapter, which
+//! needs the held amount of each outcome.
+
+use super::market::{fetch_positions, DataApiPosition};
+use crate::db::Database;
+use crate::tools::rpc_config::{resolve_rpc_config, ResolvedRpcConfig};
+use crate::tx_queue::TxQueueManager;
+use crate::wallet::WalletProvider;
+use crate::web3::{default_abis_dir, encode_call, find_function_with_params, load_abi, parse_abi, queue_signed};
+use crate::x402::X402EvmRpc;
+use chrono::{Duration, Utc};
+use ethers::abi::Token;
+use ethers::types::{Address, U256};
+use polymarket_client_sdk::{contract_config, POLYGON};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Preset name recorded on queued redeem transactions
+const REDEEM_PRESET: &str = "polymarket_redeem";
+
+/// Don't re-queue a condition whose redeem was queued more recently than this
+const REQUEUE_AFTER_HOURS: i64 = 24;
+
+/// An ABI-level contract call that redeems one condition
+#[derive(Debug, Clone)]
+pub struct RedeemCall {
+    pub abi: &'static str,
+    pub contract: String,
+    pub function: &'static str,
+    pub params: Vec<Value>,
+}
+
+/// A redeem transaction queued by the scheduler
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedRedemption {
+    pub uuid: String,
+    pub condition_id: String,
+    pub market: String,
+    pub neg_risk: bool,
+}
+
+/// Build the redeem call for all held positions of one resolved condition
+pub async fn redeem_call(
+    rpc: &X402EvmRpc,
+    owner: Address,
+    positions: &[DataApiPosition],
+) -> Result<RedeemCall, String> {
+    let first = positions
+        .first()
+        .ok_or_else(|| "No positions to redeem".to_string())?;
+    let condition_id = first.condition_id.clone();
+
+    let standard = contract_config(POLYGON, false)
+        .ok_or_else(|| "No Polymarket contracts configured for Polygon".to_string())?;
+
+    if !first.negative_risk {
+        return Ok(RedeemCall {
+            abi: "conditional_tokens",
+            contract: format!("{:#x}", standard.conditional_tokens),
+            function: "redeemPositions",
+            params: vec![
+                json!(format!("{:#x}", standard.collateral)),
+                json!(format!("0x{}", "0".repeat(64))),
+                json!(condition_id),
+                json!(["1", "2"]),
+            ],
+        });
+    }
+
+    let adapter = contract_config(POLYGON, true)
+        .and_then(|c| c.neg_risk_adapter)
+        .ok_or_else(|| "No Polymarket neg-risk adapter configured for Polygon".to_string())?;
+    let ctf: Address = format!("{:#x}", standard.conditional_tokens)
+        .parse()
+        .map_err(|_| "Invalid CTF address".to_string())?;
+
+    // Amounts are indexed by outcome; read exact balances on-chain
+    let mut amounts = [U256::zero(); 2];
+    for position in positions {
+        let index = position.outcome_index as usize;
+        if index >= amounts.len() {
+            return Err(format!("Unexpected outcome index {}", index));
+        }
+        amounts[index] = ctf_balance(rpc, ctf, owner, &position.asset).await?;
+    }
+
+    Ok(RedeemCall {
+        abi: "neg_risk_adapter",
+        contract: format!("{:#x}", adapter),
+        function: "redeemPositions",
+        params: vec![
+            json!(condition_id),
+            json!(amounts.iter().map(|a| a.to_string()).collect::<Vec<_>>()),
+        ],
+    })
+}
+
+/// ERC-1155 balance of an outcome token
+async fn ctf_balance(rpc: &X402EvmRpc, ctf: Address, owner: Address, token_id: &str) -> Result<U256, String> {
+    let id = U256::from_dec_str(token_id).map_err(|e| format!("Invalid token id {}: {}", token_id, e))?;
+    // balanceOf(address,uint256)
+    let mut data = vec![0x00, 0xfd, 0xd5, 0x8e];
+    data.extend(ethers::abi::encode(&[Token::Address(owner), Token::Uint(id)]));
+    let result = rpc.call(ctf, &data).await?;
+    if result.len() < 32 {
+        return Err("Invalid balanceOf response".to_string());
+    }
+    Ok(U256::from_big_endian(&result[..32]))
+}
+
+/// Group redeemable positions by condition
+fn redeemable_by_condition(positions: Vec<DataApiPosition>) -> BTreeMap<String, Vec<DataApiPosition>> {
+    let mut groups: BTreeMap<String, Vec<DataApiPosition>> = BTreeMap::new();
+    for position in positions.into_iter().filter(|p| p.redeemable && p.size > 0.0) {
+        groups
+            .entry(position.condition_id.to_lowercase())
+            .or_default()
+            .push(position);
+    }
+    groups
+}
+
+/// Queue a redeem transaction for the next resolved condition the wallet holds
+///
+/// At most one transaction is queued per call, and none while the wallet has
+/// other pending transactions (see [`TxQueueManager::has_pending_from`]).
+pub async fn queue_redemptions(
+    db: &Arc<Database>,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    tx_queue: &Arc<TxQueueManager>,
+) -> Result<Option<QueuedRedemption>, String> {
+    let wallet = wallet_provider.get_address();
+    if tx_queue.has_pending_from(&wallet) {
+        return Ok(None);
+    }
+
+    let groups = redeemable_by_condition(fetch_positions(&wallet).await?);
+    let cutoff = Utc::now() - Duration::hours(REQUEUE_AFTER_HOURS);
+    let mut next = None;
+    for (condition_id, positions) in groups {
+        match db.get_polymarket_redemption(&condition_id) {
+            Ok(Some((_, queued_at))) if queued_at > cutoff => continue,
+            Ok(_) => {
+                next = Some((condition_id, positions));
+                break;
+            }
+            Err(e) => return Err(format!("Failed to read redemptions: {}", e)),
+        }
+    }
+    let (condition_id, positions) = match next {
+        Some(n) => n,
+        None => return Ok(None),
+    };
+
+    let settings = db
+        .get_bot_settings()
+        .map_err(|e| format!("Failed to load bot settings: {}", e))?;
+    let (url, use_x402) = resolve_rpc_config(
+        &settings.rpc_provider,
+        settings.custom_rpc_endpoints.as_ref(),
+        "polygon",
+    )
+    .ok_or_else(|| "No RPC endpoint configured for polygon".to_string())?;
+    let rpc_config = ResolvedRpcConfig { url, use_x402 };
+
+    let rpc = X402EvmRpc::new_with_wallet_provider(
+        wallet_provider.clone(),
+        "polygon",
+        Some(rpc_config.url.clone()),
+        rpc_config.use_x402,
+    )?;
+    let owner: Address = wallet.parse().map_err(|_| "Invalid wallet address".to_string())?;
+
+    let call = redeem_call(&rpc, owner, &positions).await?;
+    let abi = parse_abi(&load_abi(&default_abis_dir(), call.abi)?)?;
+    let function = find_function_with_params(&abi, call.function, call.params.len())?;
+    let calldata = encode_call(function, &call.params)?;
+    let to: Address = call
+        .contract
+        .parse()
+        .map_err(|_| format!("Invalid contract address: {}", call.contract))?;
+
+    let uuid = queue_signed(
+        "polygon",
+        to,
+        calldata,
+        U256::zero(),
+        REDEEM_PRESET,
+        &rpc_config,
+        wallet_provider,
+        tx_queue,
+    )
+    .await?;
+
+    if let Err(e) = db.record_polymarket_redemption(&condition_id, &uuid) {
+        log::error!("[polymarket] Failed to record redemption for {}: {}", condition_id, e);
+    }
+
+    log::info!("[polymarket] Queued redeem {} for condition {}", uuid, condition_id);
+
+    Ok(Some(QueuedRedemption {
+        uuid,
+        condition_id,
+        market: positions[0].title.clone(),
+        neg_risk: positions[0].negative_risk,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redeemable_grouping() {
+        let position = |condition: &str, asset: &str, redeemable: bool, size: f64| DataApiPosition {
+            asset: asset.to_string(),
+            condition_id: condition.to_string(),
+            size,
+            redeemable,
+            ..Default::default()
+        };
+        let groups = redeemable_by_condition(vec![
+            position("0xAA", "1", true, 10.0),
+            position("0xaa", "2", true, 5.0),
+            position("0xbb", "3", false, 10.0),
+            position("0xcc", "4", true, 0.0),
+        ]);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups["0xaa"].len(), 2);
+    }
+
+    #[test]
+    fn test_redeem_abis_encode() {
+        let dir = default_abis_dir();
+        let ctf = parse_abi(&load_abi(&dir, "conditional_tokens").unwrap()).unwrap();
+        let f = find_function_with_params(&ctf, "redeemPositions", 4).unwrap();
+        let data = encode_call(
+            f,
+            &[
+                json!("0x2791bca1f2de4661ed88a30c99a7a9449aa84174"),
+                json!(format!("0x{}", "0".repeat(64))),
+                json!(format!("0x{}", "ab".repeat(32))),
+                json!(["1", "2"]),
+            ],
+        )
+        .unwrap();
+        assert_eq!(hex::encode(&data[..4]), "01b7037c");
+
+        let adapter = parse_abi(&load_abi(&dir, "neg_risk_adapter").unwrap()).unwrap();
+        let f = find_function_with_params(&adapter, "redeemPositions", 2).unwrap();
+        let data = encode_call(f, &[json!(format!("0x{}", "ab".repeat(32))), json!(["100", "0"])]).unwrap();
+        assert_eq!(hex::encode(&data[..4]), "dbeccb23");
+    }
+}
@@ -0,0 +1,13 @@
+//! Portfolio tracking
+//!
+//! Periodically snapshots token balances of the bot wallet and any extra
+//! tracked wallets/Safes across every network in `config/tokens.ron`, values
+//! them in USD (GeckoTerminal, falling back to DexScreener) and stores the
+//! result so holdings and P&L over time can be reported by the dashboard,
+//! the `/api/portfolio` endpoints and the `portfolio` tool.
+
+mod pnl;
+mod snapshot;
+
+pub use pnl::{aggregate_holdings, compute_change, parse_period};
+pub use snapshot::{snapshot_due, take_snapshot};
@@ -0,0 +1,302 @@
+//! Holdings aggregation and P&L between snapshots.
+//!
+//! P&L is the change in USD value between two snapshots. It does not separate
+//! deposits/withdrawals from market moves, so per-asset balance and price
+//! changes are reported alongside to make that distinction visible.
+
+use crate::db::tables::portfolio::{PortfolioBalance, PortfolioSnapshot};
+use crate::db::Database;
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A token position aggregated across all tracked wallets
+#[derive(Debug, Clone, Serialize)]
+pub struct Holding {
+    pub network: String,
+    pub symbol: String,
+    pub token_address: String,
+    pub balance: f64,
+    pub price_usd: Option<f64>,
+    pub value_usd: Option<f64>,
+}
+
+/// Change of a single asset between two snapshots
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetChange {
+    pub network: String,
+    pub symbol: String,
+    pub token_address: String,
+    pub start_balance: f64,
+    pub end_balance: f64,
+    pub start_price_usd: Option<f64>,
+    pub end_price_usd: Option<f64>,
+    pub start_value_usd: f64,
+    pub end_value_usd: f64,
+    pub value_change_usd: f64,
+    pub price_change_pct: Option<f64>,
+}
+
+/// Portfolio value change between two snapshots
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioChange {
+    pub from: PortfolioSnapshot,
+    pub to: PortfolioSnapshot,
+    pub start_value_usd: f64,
+    pub end_value_usd: f64,
+    pub change_usd: f64,
+    pub change_pct: Option<f64>,
+    /// Sorted by absolute value change, largest first
+    pub assets: Vec<AssetChange>,
+}
+
+/// Parse a reporting period ("24h", "day", "7d", "week", "30d", "month", "1y", "all")
+pub fn parse_period(period: &str) -> Option<Duration> {
+    let p = period.trim().to_lowercase();
+    match p.as_str() {
+        "day" | "today" => return Some(Duration::days(1)),
+        "week" => return Some(Duration::weeks(1)),
+        "month" => return Some(Duration::days(30)),
+        "year" => return Some(Duration::days(365)),
+        "all" => return Some(Duration::days(365 * 100)),
+        _ => {}
+    }
+
+    let (num, unit) = p.split_at(p.find(|c: char| !c.is_ascii_digit())?);
+    let n: i64 = num.parse().ok().filter(|n| *n > 0)?;
+    match unit {
+        "h" => Some(Duration::hours(n)),
+        "d" => Some(Duration::days(n)),
+        "w" => Some(Duration::weeks(n)),
+        "m" => Some(Duration::days(30 * n)),
+        "y" => Some(Duration::days(365 * n)),
+        _ => None,
+    }
+}
+
+fn asset_key(b: &PortfolioBalance) -> (String, String) {
+    (b.network.clone(), b.token_address.to_lowercase())
+}
+
+/// Sum balances of the same token across wallets, largest value first
+pub fn aggregate_holdings(balances: &[PortfolioBalance]) -> Vec<Holding> {
+    let mut by_asset: BTreeMap<(String, String), Holding> = BTreeMap::new();
+    for b in balances {
+        let h = by_asset.entry(asset_key(b)).or_insert_with(|| Holding {
+            network: b.network.clone(),
+            symbol: b.symbol.clone(),
+            token_address: b.token_address.clone(),
+            balance: 0.0,
+            price_usd: b.price_usd,
+            value_usd: None,
+        });
+        h.balance += b.balance;
+        if let Some(v) = b.value_usd {
+            h.value_usd = Some(h.value_usd.unwrap_or(0.0) + v);
+        }
+    }
+
+    let mut holdings: Vec<Holding> = by_asset.into_values().collect();
+    holdings.sort_by(|a, b| {
+        b.value_usd
+            .unwrap_or(0.0)
+            .partial_cmp(&a.value_usd.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    holdings
+}
+
+/// Compare two snapshots and their balances
+pub fn compare_snapshots(
+    from: PortfolioSnapshot,
+    from_balances: &[PortfolioBalance],
+    to: PortfolioSnapshot,
+    to_balances: &[PortfolioBalance],
+) -> PortfolioChange {
+    let start: BTreeMap<_, _> = aggregate_holdings(from_balances)
+        .into_iter()
+        .map(|h| ((h.network.clone(), h.token_address.to_lowercase()), h))
+        .collect();
+    let end: BTreeMap<_, _> = aggregate_holdings(to_balances)
+        .into_iter()
+        .map(|h| ((h.network.clone(), h.token_address.to_lowercase()), h))
+        .collect();
+
+    let mut keys: Vec<_> = start.keys().chain(end.keys()).cloned().collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut assets: Vec<AssetChange> = keys
+        .into_iter()
+        .map(|key| {
+            let s = start.get(&key);
+            let e = end.get(&key);
+            let any = e.or(s).expect("key comes from one of the maps");
+            let start_value = s.and_then(|h| h.value_usd).unwrap_or(0.0);
+            let end_value = e.and_then(|h| h.value_usd).unwrap_or(0.0);
+            let start_price = s.and_then(|h| h.price_usd);
+            let end_price = e.and_then(|h| h.price_usd);
+            AssetChange {
+                network: any.network.clone(),
+                symbol: any.symbol.clone(),
+                token_address: any.token_address.clone(),
+                start_balance: s.map(|h| h.balance).unwrap_or(0.0),
+                end_balance: e.map(|h| h.balance).unwrap_or(0.0),
+                start_price_usd: start_price,
+                end_price_usd: end_price,
+                start_value_usd: start_value,
+                end_value_usd: end_value,
+                value_change_usd: end_value - start_value,
+                price_change_pct: match (start_price, end_price) {
+                    (Some(sp), Some(ep)) if sp > 0.0 => Some((ep - sp) / sp * 100.0),
+                    _ => None,
+                },
+            }
+        })
+        .collect();
+
+    assets.sort_by(|a, b| {
+        b.value_change_usd
+            .abs()
+            .partial_cmp(&a.value_change_usd.abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let start_value_usd = from.total_value_usd;
+    let end_value_usd = to.total_value_usd;
+    let change_usd = end_value_usd - start_value_usd;
+    let change_pct = if start_value_usd > 0.0 {
+        Some(change_usd / start_value_usd * 100.0)
+    } else {
+        None
+    };
+
+    PortfolioChange {
+        from,
+        to,
+        start_value_usd,
+        end_value_usd,
+        change_usd,
+        change_pct,
+        assets,
+    }
+}
+
+/// Compute the change between the latest snapshot and the baseline snapshot
+/// for `period`. Returns `Ok(None)` when there are no snapshots yet.
+pub fn compute_change(db: &Database, period: Duration) -> Result<Option<PortfolioChange>, String> {
+    let latest = match db
+        .get_latest_portfolio_snapshot()
+        .map_err(|e| format!("Failed to load latest snapshot: {}", e))?
+    {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+
+    let baseline = db
+        .get_portfolio_baseline_snapshot(Utc::now() - period)
+        .map_err(|e| format!("Failed to load baseline snapshot: {}", e))?
+        .unwrap_or_else(|| latest.clone());
+
+    let from_balances = db
+        .get_portfolio_balances(baseline.id)
+        .map_err(|e| format!("Failed to load balances: {}", e))?;
+    let to_balances = db
+        .get_portfolio_balances(latest.id)
+        .map_err(|e| format!("Failed to load balances: {}", e))?;
+
+    Ok(Some(compare_snapshots(baseline, &from_balances, latest, &to_balances)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(wallet: &str, symbol: &str, amount: f64, price: Option<f64>) -> PortfolioBalance {
+        PortfolioBalance {
+            wallet_address: wallet.to_string(),
+            wallet_label: wallet.to_string(),
+            network: "base".to_string(),
+            symbol: symbol.to_string(),
+            token_address: format!("0x{}", symbol.to_lowercase()),
+            decimals: 18,
+            raw_balance: "0".to_string(),
+            balance: amount,
+            price_usd: price,
+            value_usd: price.map(|p| p * amount),
+        }
+    }
+
+    fn snapshot(id: i64, total: f64) -> PortfolioSnapshot {
+        PortfolioSnapshot {
+            id,
+            taken_at: Utc::now(),
+            total_value_usd: total,
+            errors: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(parse_period("24h"), Some(Duration::hours(24)));
+        assert_eq!(parse_period("day"), Some(Duration::days(1)));
+        assert_eq!(parse_period("7d"), Some(Duration::days(7)));
+        assert_eq!(parse_period("Week"), Some(Duration::weeks(1)));
+        assert_eq!(parse_period("1m"), Some(Duration::days(30)));
+        assert_eq!(parse_period("0d"), None);
+        assert_eq!(parse_period("soon"), None);
+        assert_eq!(parse_period("d"), None);
+    }
+
+    #[test]
+    fn test_aggregate_holdings_across_wallets() {
+        let balances = vec![
+            balance("a", "ETH", 1.0, Some(2000.0)),
+            balance("b", "ETH", 0.5, Some(2000.0)),
+            balance("a", "USDC", 100.0, Some(1.0)),
+            balance("a", "XYZ", 5.0, None),
+        ];
+        let holdings = aggregate_holdings(&balances);
+        assert_eq!(holdings.len(), 3);
+        assert_eq!(holdings[0].symbol, "ETH");
+        assert_eq!(holdings[0].balance, 1.5);
+        assert_eq!(holdings[0].value_usd, Some(3000.0));
+        assert_eq!(holdings[1].symbol, "USDC");
+        assert_eq!(holdings[2].value_usd, None);
+    }
+
+    #[test]
+    fn test_compare_snapshots() {
+        let from_balances = vec![
+            balance("a", "ETH", 1.0, Some(2000.0)),
+            balance("a", "DEGEN", 1000.0, Some(0.01)),
+        ];
+        let to_balances = vec![
+            balance("a", "ETH", 1.0, Some(2500.0)),
+            balance("a", "USDC", 50.0, Some(1.0)),
+        ];
+
+        let change = compare_snapshots(snapshot(1, 2010.0), &from_balances, snapshot(2, 2550.0), &to_balances);
+
+        assert_eq!(change.change_usd, 540.0);
+        assert!((change.change_pct.unwrap() - 26.865).abs() < 0.01);
+        assert_eq!(change.assets.len(), 3);
+
+        let eth = &change.assets[0];
+        assert_eq!(eth.symbol, "ETH");
+        assert_eq!(eth.value_change_usd, 500.0);
+        assert_eq!(eth.price_change_pct, Some(25.0));
+
+        let degen = change.assets.iter().find(|a| a.symbol == "DEGEN").unwrap();
+        assert_eq!(degen.end_balance, 0.0);
+        assert_eq!(degen.value_change_usd, -10.0);
+        assert_eq!(degen.end_price_usd, None);
+    }
+
+    #[test]
+    fn test_compare_from_empty_portfolio() {
+        let change = compare_snapshots(snapshot(1, 0.0), &[], snapshot(2, 100.0), &[balance("a", "USDC", 100.0, Some(1.0))]);
+        assert_eq!(change.change_usd, 100.0);
+        assert_eq!(change.change_pct, None);
+    }
+}
@@ -0,0 +1,292 @@
+//! Taking portfolio snapshots: read balances on-chain and value them in USD.
+
+use crate::db::tables::portfolio::{PortfolioBalance, PortfolioSnapshot};
+use crate::db::Database;
+use crate::tools::builtin::cryptocurrency::token_lookup::{get_network_tokens, get_token_networks, TokenInfo};
+use crate::tools::builtin::cryptocurrency::{dexscreener, geckoterminal};
+use crate::tools::rpc_config::resolve_rpc_config;
+use crate::wallet::WalletProvider;
+use crate::web3::multicall;
+use crate::x402::X402EvmRpc;
+use chrono::{Duration, Utc};
+use ethers::types::{Address, U256};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Placeholder address used in tokens.ron for the native currency
+const NATIVE_TOKEN_ADDRESS: &str = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+
+/// Prevents overlapping snapshots (scheduler tick + manual trigger)
+static SNAPSHOT_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+struct InProgressGuard;
+
+impl InProgressGuard {
+    fn acquire() -> Option<Self> {
+        SNAPSHOT_IN_PROGRESS
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| InProgressGuard)
+    }
+}
+
+impl Drop for InProgressGuard {
+    fn drop(&mut self) {
+        SNAPSHOT_IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
+
+struct TrackedWallet {
+    label: String,
+    address: Address,
+}
+
+/// Returns true when no snapshot exists yet or the latest one is older than `interval_mins`
+pub fn snapshot_due(db: &Database, interval_mins: u64) -> bool {
+    if interval_mins == 0 || SNAPSHOT_IN_PROGRESS.load(Ordering::SeqCst) {
+        return false;
+    }
+    match db.get_latest_portfolio_snapshot() {
+        Ok(Some(latest)) => Utc::now() - latest.taken_at >= Duration::minutes(interval_mins as i64),
+        Ok(None) => true,
+        Err(e) => {
+            log::error!("[portfolio] Failed to read latest snapshot: {}", e);
+            false
+        }
+    }
+}
+
+/// Read balances for all tracked wallets on all configured networks, price them
+/// and persist a new snapshot.
+///
+/// Networks without a configured RPC endpoint are skipped. Failures on a single
+/// network are recorded in the snapshot's `errors` instead of failing the whole run.
+pub async fn take_snapshot(
+    db: &Arc<Database>,
+    wallet_provider: &Arc<dyn WalletProvider>,
+) -> Result<PortfolioSnapshot, String> {
+    let _guard = InProgressGuard::acquire()
+        .ok_or_else(|| "A portfolio snapshot is already in progress".to_string())?;
+
+    let wallets = tracked_wallets(db, wallet_provider)?;
+    let settings = db
+        .get_bot_settings()
+        .map_err(|e| format!("Failed to load bot settings: {}", e))?;
+
+    let mut balances = Vec::new();
+    let mut errors = Vec::new();
+
+    for network in get_token_networks() {
+        let (url, use_x402) = match resolve_rpc_config(
+            &settings.rpc_provider,
+            settings.custom_rpc_endpoints.as_ref(),
+            &network,
+        ) {
+            Some(cfg) => cfg,
+            None => {
+                log::debug!("[portfolio] No RPC endpoint for {}, skipping", network);
+                continue;
+            }
+        };
+
+        match snapshot_network(&network, &url, use_x402, &wallets, wallet_provider).await {
+            Ok(mut network_balances) => balances.append(&mut network_balances),
+            Err(e) => {
+                log::warn!("[portfolio] Failed to read balances on {}: {}", network, e);
+                errors.push(format!("{}: {}", network, e));
+            }
+        }
+    }
+
+    let total_value_usd: f64 = balances.iter().filter_map(|b| b.value_usd).sum();
+
+    let snapshot_id = db
+        .save_portfolio_snapshot(total_value_usd, &errors, &balances)
+        .map_err(|e| format!("Failed to save snapshot: {}", e))?;
+
+    log::info!(
+        "[portfolio] Snapshot {} saved: {} balances, ${:.2} total, {} errors",
+        snapshot_id,
+        balances.len(),
+        total_value_usd,
+        errors.len()
+    );
+
+    db.get_latest_portfolio_snapshot()
+        .map_err(|e| format!("Failed to load snapshot: {}", e))?
+        .ok_or_else(|| "Snapshot not found after save".to_string())
+}
+
+/// The bot wallet plus all enabled tracked wallets, deduplicated by address
+fn tracked_wallets(
+    db: &Database,
+    wallet_provider: &Arc<dyn WalletProvider>,
+) -> Result<Vec<TrackedWallet>, String> {
+    let mut seen = HashSet::new();
+    let mut wallets = Vec::new();
+
+    let bot_address: Address = wallet_provider
+        .get_address()
+        .parse()
+        .map_err(|_| "Invalid bot wallet address".to_string())?;
+    seen.insert(bot_address);
+    wallets.push(TrackedWallet {
+        label: "Bot wallet".to_string(),
+        address: bot_address,
+    });
+
+    let extra = db
+        .list_portfolio_wallets()
+        .map_err(|e| format!("Failed to list tracked wallets: {}", e))?;
+    for wallet in extra.into_iter().filter(|w| w.enabled) {
+        match wallet.address.parse::<Address>() {
+            Ok(address) if seen.insert(address) => wallets.push(TrackedWallet {
+                label: wallet.label,
+                address,
+            }),
+            Ok(_) => {}
+            Err(_) => log::warn!("[portfolio] Skipping invalid tracked address {}", wallet.address),
+        }
+    }
+
+    Ok(wallets)
+}
+
+/// Read and price all non-zero balances on one network
+async fn snapshot_network(
+    network: &str,
+    rpc_url: &str,
+    use_x402: bool,
+    wallets: &[TrackedWallet],
+    wallet_provider: &Arc<dyn WalletProvider>,
+) -> Result<Vec<PortfolioBalance>, String> {
+    let tokens = get_network_tokens(network);
+    if tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rpc = X402EvmRpc::new_with_wallet_provider(
+        wallet_provider.clone(),
+        network,
+        Some(rpc_url.to_string()),
+        use_x402,
+    )?;
+
+    // One Call3 per (wallet, token), all sent through a single aggregate3
+    let mut calls = Vec::new();
+    let mut call_keys: Vec<(&TrackedWallet, &String, &TokenInfo)> = Vec::new();
+    for wallet in wallets {
+        for (symbol, info) in &tokens {
+            let call = if is_native(&info.address) {
+                multicall::native_balance_call(wallet.address)
+            } else {
+                match info.address.parse::<Address>() {
+                    Ok(token) => multicall::erc20_balance_call(token, wallet.address),
+                    Err(_) => continue,
+                }
+            };
+            calls.push(call);
+            call_keys.push((wallet, symbol, info));
+        }
+    }
+
+    let results = multicall::aggregate3(&rpc, &calls).await?;
+
+    let mut balances = Vec::new();
+    for ((wallet, symbol, info), result) in call_keys.into_iter().zip(results) {
+        let raw = match result.as_u256() {
+            Some(raw) if !raw.is_zero() => raw,
+            _ => continue,
+        };
+        balances.push(PortfolioBalance {
+            wallet_address: format!("{:?}", wallet.address),
+            wallet_label: wallet.label.clone(),
+            network: network.to_string(),
+            symbol: symbol.clone(),
+            token_address: info.address.clone(),
+            decimals: info.decimals,
+            raw_balance: raw.to_string(),
+            balance: to_float(raw, info.decimals),
+            price_usd: None,
+            value_usd: None,
+        });
+    }
+
+    if balances.is_empty() {
+        return Ok(balances);
+    }
+
+    let prices = fetch_prices(network, &tokens, &balances).await;
+    for b in &mut balances {
+        if let Some(price) = prices.get(&b.token_address.to_lowercase()) {
+            b.price_usd = Some(*price);
+            b.value_usd = Some(b.balance * price);
+        }
+    }
+
+    Ok(balances)
+}
+
+/// Price every token that has a balance. Native tokens are priced via their
+/// wrapped counterpart (ETH -> WETH, MATIC -> WMATIC). Returned map is keyed by
+/// the lowercase address as it appears in tokens.ron.
+async fn fetch_prices(
+    network: &str,
+    tokens: &[(String, TokenInfo)],
+    balances: &[PortfolioBalance],
+) -> HashMap<String, f64> {
+    // token address (as held) -> address to look up the price for
+    let mut lookup: HashMap<String, String> = HashMap::new();
+    for b in balances {
+        let held = b.token_address.to_lowercase();
+        let priced = if is_native(&held) {
+            let wrapped = format!("W{}", b.symbol.to_uppercase());
+            match tokens.iter().find(|(s, _)| s.eq_ignore_ascii_case(&wrapped)) {
+                Some((_, info)) => info.address.to_lowercase(),
+                None => continue,
+            }
+        } else {
+            held.clone()
+        };
+        lookup.insert(held, priced);
+    }
+
+    let mut unique: Vec<String> = lookup.values().cloned().collect();
+    unique.sort();
+    unique.dedup();
+
+    let mut prices = match geckoterminal::fetch_token_prices_usd(network, &unique).await {
+        Ok(p) => p,
+        Err(e) => {
+            log::warn!("[portfolio] GeckoTerminal prices failed on {}: {}", network, e);
+            HashMap::new()
+        }
+    };
+
+    for address in unique.iter().filter(|a| !prices.contains_key(*a)).cloned().collect::<Vec<_>>() {
+        match dexscreener::fetch_token_price_usd(network, &address).await {
+            Ok(Some(price)) => {
+                prices.insert(address, price);
+            }
+            Ok(None) => log::debug!("[portfolio] No price for {} on {}", address, network),
+            Err(e) => log::warn!("[portfolio] DexScreener price failed for {}: {}", address, e),
+        }
+    }
+
+    lookup
+        .into_iter()
+        .filter_map(|(held, priced)| prices.get(&priced).map(|p| (held, *p)))
+        .collect()
+}
+
+fn is_native(address: &str) -> bool {
+    address.eq_ignore_ascii_case(NATIVE_TOKEN_ADDRESS)
+}
+
+fn to_float(raw: U256, decimals: u8) -> f64 {
+    ethers::utils::format_units(raw, decimals as u32)
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(0.0)
+}
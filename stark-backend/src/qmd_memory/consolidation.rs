@@ -0,0 +1,667 @@
+//! Memory consolidation and decay
+//!
+//! Daily logs only ever grow, so a scheduled job folds them into digests:
+//! - daily logs of ISO weeks older than `consolidate_after_days` become a weekly
+//!   digest (YYYY-Www.md) and durable facts are promoted into MEMORY.md
+//! - weekly digests of months older than `rollup_after_days` become a monthly
+//!   digest (YYYY-MM.md)
+//! - consolidated sources move to `.archive/`, which is not indexed
+//! - MEMORY.md is deduplicated, and rewritten by the AI (contradictions resolved
+//!   in favor of the newest entry, with source dates kept) when facts were
+//!   promoted or it grows past `long_term_max_chars`
+
+use super::file_ops;
+use super::MemoryStore;
+use crate::ai::{AiClient, Message, MessageRole, UsageScope};
+use crate::config;
+use crate::db::Database;
+use crate::wallet::WalletProvider;
+use chrono::{Datelike, Duration, Local, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+
+/// Line in a digest listing the files it was built from
+const SOURCES_PREFIX: &str = "Sources: ";
+
+/// Unix timestamp of the last scheduled consolidation
+static LAST_RUN: AtomicI64 = AtomicI64::new(0);
+
+/// Prevents overlapping runs (scheduled and manual)
+static RUN_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+struct InProgressGuard;
+
+impl InProgressGuard {
+    fn acquire() -> Option<Self> {
+        RUN_IN_PROGRESS
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .ok()
+            .map(|_| InProgressGuard)
+    }
+}
+
+impl Drop for InProgressGuard {
+    fn drop(&mut self) {
+        RUN_IN_PROGRESS.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Returns true when the consolidation interval has elapsed and no run is in progress
+pub fn check_due(interval_secs: u64) -> bool {
+    if interval_secs == 0 || RUN_IN_PROGRESS.load(Ordering::SeqCst) {
+        return false;
+    }
+    let now = Utc::now().timestamp();
+    if now - LAST_RUN.load(Ordering::SeqCst) < interval_secs as i64 {
+        return false;
+    }
+    LAST_RUN.store(now, Ordering::SeqCst);
+    true
+}
+
+/// Age thresholds and size limits for consolidation
+#[derive(Debug, Clone)]
+pub struct ConsolidationPolicy {
+    /// Daily logs are digested once their whole ISO week is at least this old
+    pub consolidate_after_days: i64,
+    /// Weekly digests roll up once their whole month is at least this old
+    pub rollup_after_days: i64,
+    /// MEMORY.md is compacted by the AI past this size
+    pub long_term_max_chars: usize,
+}
+
+impl ConsolidationPolicy {
+    pub fn from_config() -> Self {
+        Self {
+            consolidate_after_days: config::memory_consolidate_after_days(),
+            rollup_after_days: config::memory_digest_rollup_after_days(),
+            long_term_max_chars: config::memory_long_term_max_chars(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestKind {
+    Weekly,
+    Monthly,
+}
+
+/// A set of memory files to fold into one digest
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestBatch {
+    pub kind: DigestKind,
+    pub identity_id: Option<String>,
+    pub period_start: NaiveDate,
+    /// Digest path relative to the memory dir
+    pub digest_path: String,
+    /// Source paths relative to the memory dir, oldest first
+    pub sources: Vec<String>,
+}
+
+impl DigestBatch {
+    fn period_label(&self) -> String {
+        let name = Path::new(&self.digest_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match self.kind {
+            DigestKind::Weekly => format!(
+                "week {} ({} to {})",
+                name,
+                self.period_start,
+                self.period_start + Duration::days(6)
+            ),
+            DigestKind::Monthly => format!("month {}", name),
+        }
+    }
+}
+
+/// What a consolidation run did
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ConsolidationReport {
+    pub digests: Vec<String>,
+    pub archived: Vec<String>,
+    pub facts_promoted: usize,
+    pub duplicates_removed: usize,
+    pub long_term_rewritten: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl ConsolidationReport {
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+            && self.archived.is_empty()
+            && self.duplicates_removed == 0
+            && self.long_term_rewritten.is_empty()
+            && self.errors.is_empty()
+    }
+}
+
+/// Group old daily logs into weekly batches and old weekly digests into monthly ones
+pub fn plan(files: &[String], today: NaiveDate, policy: &ConsolidationPolicy) -> Vec<DigestBatch> {
+    let mut groups: BTreeMap<(DigestKind, Option<String>, NaiveDate), Vec<String>> = BTreeMap::new();
+
+    for rel_path in files {
+        let path = Path::new(rel_path);
+        let name = match path.file_name() {
+            Some(n) => n.to_string_lossy().to_string(),
+            None => continue,
+        };
+        let identity_id = path
+            .parent()
+            .and_then(|p| p.to_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+
+        if let Some(date) = file_ops::parse_date_from_filename(&name) {
+            let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+            let week_end = week_start + Duration::days(6);
+            if (today - week_end).num_days() >= policy.consolidate_after_days {
+                groups
+                    .entry((DigestKind::Weekly, identity_id, week_start))
+                    .or_default()
+                    .push(rel_path.clone());
+            }
+        } else if let Some(week_start) = file_ops::parse_week_from_filename(&name) {
+            let month_start = week_start.with_day(1).unwrap_or(week_start);
+            let next_month = (month_start + Duration::days(32)).with_day(1).unwrap_or(month_start);
+            let month_end = next_month - Duration::days(1);
+            if (today - month_end).num_days() >= policy.rollup_after_days {
+                groups
+                    .entry((DigestKind::Monthly, identity_id, month_start))
+                    .or_default()
+                    .push(rel_path.clone());
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((kind, identity_id, period_start), mut sources)| {
+            sources.sort();
+            let digest_path = match kind {
+                DigestKind::Weekly => file_ops::weekly_digest_path(Path::new(""), period_start, identity_id.as_deref()),
+                DigestKind::Monthly => file_ops::monthly_digest_path(Path::new(""), period_start, identity_id.as_deref()),
+            };
+            DigestBatch {
+                kind,
+                identity_id,
+                period_start,
+                digest_path: digest_path.to_string_lossy().to_string(),
+                sources,
+            }
+        })
+        .collect()
+}
+
+/// Parsed AI digest: summary markdown and durable facts (with their source dates)
+#[derive(Debug, Default, PartialEq)]
+pub struct Digest {
+    pub summary: String,
+    pub facts: Vec<String>,
+}
+
+/// Extract the `## Summary` and `## Durable Facts` sections of a digest response
+pub fn parse_digest(response: &str) -> Digest {
+    let section = |title: &str| -> String {
+        let Some(start) = response.find(title) else {
+            return String::new();
+        };
+        let body = &response[start + title.len()..];
+        let end = body.find("\n## ").unwrap_or(body.len());
+        body[..end].trim().to_string()
+    };
+
+    let facts = section("## Durable Facts")
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("- ").map(str::trim))
+        .filter(|fact| !fact.is_empty() && !fact.eq_ignore_ascii_case("none"))
+        .map(String::from)
+        .collect();
+
+    Digest {
+        summary: section("## Summary"),
+        facts,
+    }
+}
+
+/// Normalize a memory line for duplicate detection: drop bullets, trailing
+/// provenance in parentheses, punctuation and case
+pub fn normalize_fact(line: &str) -> String {
+    let mut text = line.trim().trim_start_matches(['-', '*']).trim();
+    if let Some(open) = text.rfind('(').filter(|_| text.ends_with(')')) {
+        text = &text[..open];
+    }
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Remove repeated bullet lines (keeping the first, oldest occurrence) and
+/// headings left without content. Returns the new content and lines removed.
+pub fn dedupe_long_term(content: &str) -> (String, usize) {
+    let mut seen = HashSet::new();
+    let mut kept: Vec<&str> = Vec::new();
+    let mut removed = 0;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("- ") || trimmed.starts_with("* ") {
+            let key = normalize_fact(trimmed);
+            if !key.is_empty() && !seen.insert(key) {
+                removed += 1;
+                continue;
+            }
+        }
+        kept.push(line);
+    }
+    if removed == 0 {
+        return (content.to_string(), 0);
+    }
+
+    // Drop `## ` timestamp headings whose entries were all duplicates
+    let mut result: Vec<&str> = Vec::new();
+    for (i, line) in kept.iter().enumerate() {
+        if line.starts_with("## ") {
+            let has_body = kept[i + 1..]
+                .iter()
+                .take_while(|l| !l.starts_with("## ") && !l.starts_with("# "))
+                .any(|l| !l.trim().is_empty());
+            if !has_body {
+                continue;
+            }
+        }
+        result.push(line);
+    }
+
+    let mut text = result.join("\n");
+    while text.contains("\n\n\n") {
+        text = text.replace("\n\n\n", "\n\n");
+    }
+    (format!("{}\n", text.trim_end()), removed)
+}
+
+/// AI client for consolidation, built from the active agent settings
+pub fn ai_client(db: &Database, wallet_provider: Option<Arc<dyn WalletProvider>>) -> Result<AiClient, String> {
+    let settings = db
+        .get_active_agent_settings()
+        .map_err(|e| format!("Database error: {}", e))?
+        .unwrap_or_default();
+    AiClient::from_settings_with_wallet_provider(&settings, wallet_provider)
+}
+
+/// Consolidate old logs into digests, promote durable facts and compact MEMORY.md.
+/// AI calls are recorded as sidecar usage when `db` is given.
+pub async fn consolidate(
+    store: &MemoryStore,
+    client: &AiClient,
+    db: Option<&Database>,
+    today: NaiveDate,
+    policy: &ConsolidationPolicy,
+) -> Result<ConsolidationReport, String> {
+    let _guard = InProgressGuard::acquire().ok_or("Memory consolidation is already running")?;
+    let mut report = ConsolidationReport::default();
+
+    let files = store.list_files().map_err(|e| format!("Failed to list memory files: {}", e))?;
+    let batches = plan(&files, today, policy);
+    let mut promoted_identities: HashSet<Option<String>> = HashSet::new();
+
+    for batch in &batches {
+        match digest_batch(store, client, db, today, batch).await {
+            Ok(promoted) => {
+                report.digests.push(batch.digest_path.clone());
+                report.archived.extend(batch.sources.iter().cloned());
+                if promoted > 0 {
+                    report.facts_promoted += promoted;
+                    promoted_identities.insert(batch.identity_id.clone());
+                }
+            }
+            Err(e) => {
+                log::error!("[MEMORY_CONSOLIDATION] {} failed: {}", batch.digest_path, e);
+                report.errors.push(format!("{}: {}", batch.digest_path, e));
+            }
+        }
+    }
+
+    // Compact every MEMORY.md (including ones promotion just created): exact
+    // duplicates always, AI rewrite when needed
+    let files = store.list_files().map_err(|e| format!("Failed to list memory files: {}", e))?;
+    let long_term_files: Vec<String> = files
+        .iter()
+        .filter(|f| Path::new(f).file_name().is_some_and(|n| n == "MEMORY.md"))
+        .cloned()
+        .collect();
+    for rel_path in long_term_files {
+        let identity_id = Path::new(&rel_path)
+            .parent()
+            .and_then(|p| p.to_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+        let needs_rewrite = promoted_identities.contains(&identity_id);
+        match compact_long_term(store, client, db, &rel_path, needs_rewrite, policy).await {
+            Ok((removed, rewritten)) => {
+                report.duplicates_removed += removed;
+                if rewritten {
+                    report.long_term_rewritten.push(rel_path);
+                }
+            }
+            Err(e) => {
+                log::error!("[MEMORY_CONSOLIDATION] Compacting {} failed: {}", rel_path, e);
+                report.errors.push(format!("{}: {}", rel_path, e));
+            }
+        }
+    }
+
+    if !report.is_empty() {
+        log::info!(
+            "[MEMORY_CONSOLIDATION] {} digests, {} files archived, {} facts promoted, {} duplicates removed, {} errors",
+            report.digests.len(),
+            report.archived.len(),
+            report.facts_promoted,
+            report.duplicates_removed,
+            report.errors.len()
+        );
+    }
+    Ok(report)
+}
+
+/// Summarize one batch into its digest, promote facts and archive the sources.
+/// Returns the number of facts promoted.
+async fn digest_batch(
+    store: &MemoryStore,
+    client: &AiClient,
+    db: Option<&Database>,
+    today: NaiveDate,
+    batch: &DigestBatch,
+) -> Result<usize, String> {
+    // A digest may already exist when late logs arrive for a consolidated period
+    let existing = store.get_file(&batch.digest_path).unwrap_or_default();
+    let mut sources: Vec<String> = existing
+        .lines()
+        .find_map(|l| l.strip_prefix(SOURCES_PREFIX))
+        .map(|list| list.split(", ").map(String::from).collect())
+        .unwrap_or_default();
+
+    let mut logs = String::new();
+    if !existing.trim().is_empty() {
+        logs.push_str(&format!("### Existing digest {}\n{}\n\n", batch.digest_path, existing.trim()));
+    }
+    for source in &batch.sources {
+        let content = store.get_file(source).map_err(|e| format!("Failed to read {}: {}", source, e))?;
+        logs.push_str(&format!("### {}\n{}\n\n", source, content.trim()));
+        let name = Path::new(source).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        if !sources.contains(&name) {
+            sources.push(name);
+        }
+    }
+
+    let label = batch.period_label();
+    let prompt = format!(
+        "Consolidate these memory logs for {} into a digest.\n\n\
+        Respond in markdown with exactly these sections:\n\
+        ## Summary\n\
+        - concise bullets: what happened, decisions made, open follow-ups (keep dates where they matter)\n\n\
+        ## Durable Facts\n\
+        - facts worth keeping long-term (preferences, addresses, people, recurring plans), especially ones that \
+        come up more than once, each ending with its source dates in parentheses, \
+        e.g. \"- Alice's wallet is 0xabc... (2024-01-15, 2024-01-17)\"\n\
+        - write \"- None\" if there are none\n\n\
+        Logs:\n\n{}",
+        label, logs
+    );
+    let response = client
+        .generate_sidecar_text(vec![
+            Message {
+                role: MessageRole::System,
+                content: "You are a memory consolidation assistant. Summarize logs faithfully; never invent facts.".to_string(),
+            },
+            Message {
+                role: MessageRole::User,
+                content: prompt,
+            },
+        ], db, UsageScope::default())
+        .await?;
+
+    let digest = parse_digest(&response);
+    if digest.summary.is_empty() {
+        return Err("AI response had no ## Summary section".to_string());
+    }
+
+    let title = match batch.kind {
+        DigestKind::Weekly => "Weekly digest",
+        DigestKind::Monthly => "Monthly digest",
+    };
+    let mut content = format!(
+        "# {} - {}\n\n{}{}\nConsolidated: {}\n\n## Summary\n{}\n",
+        title,
+        label,
+        SOURCES_PREFIX,
+        sources.join(", "),
+        today,
+        digest.summary
+    );
+    if !digest.facts.is_empty() {
+        content.push_str(&format!(
+            "\n## Durable Facts\n{}\n",
+            digest.facts.iter().map(|f| format!("- {}", f)).collect::<Vec<_>>().join("\n")
+        ));
+    }
+    store
+        .replace_file(&batch.digest_path, &content)
+        .map_err(|e| format!("Failed to write digest: {}", e))?;
+
+    // Promote weekly facts that MEMORY.md doesn't already hold
+    let mut promoted = 0;
+    if batch.kind == DigestKind::Weekly && !digest.facts.is_empty() {
+        let long_term = store.get_long_term(batch.identity_id.as_deref()).unwrap_or_default();
+        let known: HashSet<String> = long_term.lines().map(normalize_fact).collect();
+        let new_facts: Vec<&String> = digest
+            .facts
+            .iter()
+            .filter(|f| !known.contains(&normalize_fact(f)))
+            .collect();
+        if !new_facts.is_empty() {
+            let entry = format!(
+                "Promoted from {}:\n{}",
+                batch.digest_path,
+                new_facts.iter().map(|f| format!("- {}", f)).collect::<Vec<_>>().join("\n")
+            );
+            store
+                .append_long_term(&entry, batch.identity_id.as_deref())
+                .map_err(|e| format!("Failed to promote facts: {}", e))?;
+            promoted = new_facts.len();
+        }
+    }
+
+    for source in &batch.sources {
+        store
+            .archive_file(source)
+            .map_err(|e| format!("Failed to archive {}: {}", source, e))?;
+    }
+
+    Ok(promoted)
+}
+
+/// Drop exact duplicates from a MEMORY.md and, when facts were just promoted or
+/// the file is over the size limit, have the AI merge contradictory entries.
+/// Returns (duplicates removed, rewritten).
+async fn compact_long_term(
+    store: &MemoryStore,
+    client: &AiClient,
+    db: Option<&Database>,
+    rel_path: &str,
+    force_rewrite: bool,
+    policy: &ConsolidationPolicy,
+) -> Result<(usize, bool), String> {
+    let content = store.get_file(rel_path).map_err(|e| e.to_string())?;
+    if content.trim().is_empty() {
+        return Ok((0, false));
+    }
+
+    let (deduped, removed) = dedupe_long_term(&content);
+    if !force_rewrite && deduped.len() <= policy.long_term_max_chars {
+        if removed > 0 {
+            store.replace_file(rel_path, &deduped).map_err(|e| e.to_string())?;
+        }
+        return Ok((removed, false));
+    }
+
+    let prompt = format!(
+        "Rewrite this long-term memory file so it stays short and consistent.\n\
+        - Merge duplicate facts into one bullet.\n\
+        - When entries contradict, keep the most recent one and note what it replaced, \
+        e.g. \"- Prefers Base (2024-02-01; previously Arbitrum, 2024-01-10)\".\n\
+        - Keep the source date(s) of every fact in parentheses at the end of its bullet.\n\
+        - Drop stale one-off details; never invent facts.\n\
+        Output the complete file as markdown starting with \"# Long-Term Memory\", \
+        grouped under short ## topic headings.\n\n\
+        Current file:\n\n{}",
+        deduped
+    );
+    let response = client
+        .generate_sidecar_text(vec![
+            Message {
+                role: MessageRole::System,
+                content: "You are a memory consolidation assistant. Preserve every durable fact and its provenance.".to_string(),
+            },
+            Message {
+                role: MessageRole::User,
+                content: prompt,
+            },
+        ], db, UsageScope::default())
+        .await?;
+
+    let rewritten = response.trim();
+    let Some(start) = rewritten.find("# Long-Term Memory") else {
+        return Err("AI rewrite did not return a memory file".to_string());
+    };
+    let rewritten = &rewritten[start..];
+    if !rewritten.lines().any(|l| l.trim_start().starts_with("- ")) {
+        return Err("AI rewrite contained no facts".to_string());
+    }
+
+    // Keep the previous version in the archive
+    let backup = Path::new(rel_path)
+        .with_file_name(format!("MEMORY-{}.md", Local::now().format("%Y%m%d%H%M%S")))
+        .to_string_lossy()
+        .to_string();
+    store
+        .archive_copy(rel_path, &backup)
+        .map_err(|e| format!("Failed to back up {}: {}", rel_path, e))?;
+    store
+        .replace_file(rel_path, &format!("{}\n", rewritten))
+        .map_err(|e| e.to_string())?;
+
+    Ok((removed, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{AiResponse, MockAiClient};
+    use tempfile::tempdir;
+
+    fn policy() -> ConsolidationPolicy {
+        ConsolidationPolicy {
+            consolidate_after_days: 14,
+            rollup_after_days: 90,
+            long_term_max_chars: 8000,
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_plan_groups_whole_weeks_and_months() {
+        let files: Vec<String> = [
+            "MEMORY.md",
+            "2024-01-15.md",
+            "2024-01-17.md",
+            "2024-01-22.md",
+            "user1/2024-01-16.md",
+            "2023-W40.md",
+            "2024-W01.md",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        // Week of 2024-01-15 ends 01-21: 14 days later it is due; week of 01-22 is not
+        let batches = plan(&files, date(2024, 2, 4), &policy());
+        let paths: Vec<&str> = batches.iter().map(|b| b.digest_path.as_str()).collect();
+        assert_eq!(paths, vec!["2024-W03.md", "user1/2024-W03.md", "2023-10.md"]);
+        assert_eq!(batches[0].sources, vec!["2024-01-15.md", "2024-01-17.md"]);
+        assert_eq!(batches[1].identity_id.as_deref(), Some("user1"));
+        assert_eq!(batches[2].kind, DigestKind::Monthly);
+        assert_eq!(batches[2].sources, vec!["2023-W40.md"]);
+    }
+
+    #[test]
+    fn test_parse_digest() {
+        let response = "## Summary\n- Swapped ETH for USDC\n\n## Durable Facts\n- Alice's wallet is 0xabc (2024-01-15, 2024-01-17)\n- None\n";
+        let digest = parse_digest(response);
+        assert_eq!(digest.summary, "- Swapped ETH for USDC");
+        assert_eq!(digest.facts, vec!["Alice's wallet is 0xabc (2024-01-15, 2024-01-17)"]);
+
+        assert_eq!(parse_digest("## Durable Facts\n- None").facts, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_dedupe_long_term() {
+        let content = "\n## 2024-01-10 09:00\n- Prefers dark mode\n\n## 2024-01-12 10:00\n- prefers dark mode! (2024-01-12)\n\n## 2024-01-13 11:00\n- Uses Base\n";
+        let (deduped, removed) = dedupe_long_term(content);
+        assert_eq!(removed, 1);
+        assert!(deduped.contains("## 2024-01-10 09:00\n- Prefers dark mode"));
+        assert!(!deduped.contains("2024-01-12"));
+        assert!(deduped.contains("- Uses Base"));
+
+        let (same, removed) = dedupe_long_term("- one\n- two\n");
+        assert_eq!((same.as_str(), removed), ("- one\n- two\n", 0));
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_writes_digest_promotes_and_archives() {
+        let dir = tempdir().unwrap();
+        let mem_dir = dir.path().join("memory");
+        let store = MemoryStore::new(mem_dir.clone(), dir.path().join("test.db").to_str().unwrap()).unwrap();
+        std::fs::write(mem_dir.join("2024-01-15.md"), "\n## 09:00\nAlice shared her wallet 0xabc\n").unwrap();
+        std::fs::write(mem_dir.join("2024-01-17.md"), "\n## 10:00\nSent Alice 5 USDC at 0xabc\n").unwrap();
+        store.reindex().unwrap();
+
+        let client = AiClient::Mock(MockAiClient::new(vec![
+            Ok(AiResponse::text(
+                "## Summary\n- Paid Alice 5 USDC\n\n## Durable Facts\n- Alice's wallet is 0xabc (2024-01-15, 2024-01-17)".to_string(),
+            )),
+            Ok(AiResponse::text(
+                "# Long-Term Memory\n\n## People\n- Alice's wallet is 0xabc (2024-01-15, 2024-01-17)\n".to_string(),
+            )),
+        ]));
+
+        let report = consolidate(&store, &client, None, date(2024, 2, 4), &policy()).await.unwrap();
+        assert_eq!(report.digests, vec!["2024-W03.md"]);
+        assert_eq!(report.archived.len(), 2);
+        assert_eq!(report.facts_promoted, 1);
+        assert_eq!(report.long_term_rewritten, vec!["MEMORY.md"]);
+        assert!(report.errors.is_empty());
+
+        let digest = store.get_file("2024-W03.md").unwrap();
+        assert!(digest.contains("Sources: 2024-01-15.md, 2024-01-17.md"));
+        assert!(digest.contains("Paid Alice 5 USDC"));
+        assert!(store.get_long_term(None).unwrap().starts_with("# Long-Term Memory"));
+
+        let files = store.list_files().unwrap();
+        assert!(!files.iter().any(|f| f == "2024-01-15.md"));
+        assert!(mem_dir.join(".archive/2024-01-15.md").exists());
+        assert!(!store.search("Alice", 10).unwrap().iter().any(|r| r.file_path == "2024-01-17.md"));
+    }
+}
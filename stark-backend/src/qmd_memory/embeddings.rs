@@ -0,0 +1,311 @@
+//! Embedding support for semantic memory search
+//!
+//! Memory files are split into paragraph chunks which are embedded through an
+//! OpenAI-compatible `/v1/embeddings` endpoint - a preset from `ai_endpoints.ron`
+//! (paid over x402 when it is a defirelay endpoint) or any URL such as a local
+//! Ollama server. Vectors are stored in SQLite next to the FTS5 index and fused
+//! with BM25 ranks at query time.
+
+use crate::ai_endpoint_config;
+use crate::config::MemoryConfig;
+use crate::wallet::WalletProvider;
+use crate::x402::{is_x402_endpoint, X402Client};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Model used when neither the preset nor the environment names one
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// `model_archetype` that marks an `ai_endpoints.ron` preset as an embedding endpoint
+pub const EMBEDDING_ARCHETYPE: &str = "embedding";
+
+/// Chunks are built from whole paragraphs up to roughly this many characters
+const MAX_CHUNK_CHARS: usize = 800;
+
+/// Texts sent per embeddings request
+pub const EMBED_BATCH_SIZE: usize = 32;
+
+/// Reciprocal rank fusion constant (the usual k=60 from the RRF paper)
+const RRF_K: f64 = 60.0;
+
+/// Chunks below this cosine similarity are not considered semantic matches
+pub const MIN_SIMILARITY: f32 = 0.25;
+
+/// Resolved embedding endpoint settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingSettings {
+    pub endpoint: String,
+    pub model: String,
+    pub api_key: Option<String>,
+}
+
+impl EmbeddingSettings {
+    /// Resolve settings from the memory config.
+    ///
+    /// `STARK_MEMORY_EMBEDDING_ENDPOINT` may name an `ai_endpoints.ron` preset or be
+    /// a URL; when unset, the first preset with `model_archetype: "embedding"` is
+    /// used. Returns None when no embedding endpoint is configured.
+    pub fn resolve(config: &MemoryConfig) -> Option<Self> {
+        let presets = ai_endpoint_config::list_ai_endpoints();
+        let preset = match config.embedding_endpoint.as_deref() {
+            Some(value) => presets.into_iter().find(|(id, _)| id == value).map(|(_, p)| p),
+            None => presets
+                .into_iter()
+                .find(|(_, p)| p.model_archetype == EMBEDDING_ARCHETYPE)
+                .map(|(_, p)| p),
+        };
+
+        let (endpoint, preset_model) = match (preset, config.embedding_endpoint.as_deref()) {
+            (Some(preset), _) => (preset.endpoint, preset.model),
+            (None, Some(url)) if url.starts_with("http://") || url.starts_with("https://") => {
+                (url.to_string(), None)
+            }
+            (None, Some(other)) => {
+                log::warn!("[QMD_MEMORY] Unknown embedding endpoint preset '{}', semantic search disabled", other);
+                return None;
+            }
+            (None, None) => return None,
+        };
+
+        Some(Self {
+            endpoint,
+            model: config
+                .embedding_model
+                .clone()
+                .or(preset_model)
+                .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string()),
+            api_key: config.embedding_api_key.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+/// Client for an OpenAI-compatible embeddings endpoint
+pub struct EmbeddingClient {
+    settings: EmbeddingSettings,
+    client: reqwest::Client,
+    x402: Option<X402Client>,
+}
+
+impl EmbeddingClient {
+    /// Create a client; x402 endpoints need a wallet provider to pay for requests
+    pub fn new(
+        settings: EmbeddingSettings,
+        wallet_provider: Option<Arc<dyn WalletProvider>>,
+    ) -> Result<Self, String> {
+        let x402 = if is_x402_endpoint(&settings.endpoint) {
+            let provider = wallet_provider
+                .ok_or_else(|| format!("{} requires x402 payment but no wallet is configured", settings.endpoint))?;
+            Some(X402Client::new(provider)?)
+        } else {
+            None
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self { settings, client, x402 })
+    }
+
+    /// Model name stored alongside each vector
+    pub fn model(&self) -> &str {
+        &self.settings.model
+    }
+
+    /// Embed a batch of texts, returning one vector per input in order
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = json!({ "model": self.settings.model, "input": texts });
+
+        let response = match self.x402 {
+            Some(ref x402) => {
+                let paid = x402.post_with_payment(&self.settings.endpoint, &body).await?;
+                if let Some(ref payment) = paid.payment {
+                    log::info!(
+                        "[QMD_MEMORY] Paid {} {} for {} embeddings",
+                        payment.amount_formatted,
+                        payment.asset,
+                        texts.len()
+                    );
+                    crate::metrics::METRICS.record_x402_payment(&payment.asset, &payment.amount_formatted);
+                }
+                paid.response
+            }
+            None => {
+                let mut request = self.client.post(&self.settings.endpoint).json(&body);
+                if let Some(ref key) = self.settings.api_key {
+                    request = request.bearer_auth(key);
+                }
+                request
+                    .send()
+                    .await
+                    .map_err(|e| format!("Embedding request failed: {}", e))?
+            }
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("Embedding endpoint returned {}: {}", status, text));
+        }
+        let parsed: EmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid embedding response: {}", e))?;
+        if parsed.data.len() != texts.len() {
+            return Err(format!(
+                "Embedding endpoint returned {} vectors for {} inputs",
+                parsed.data.len(),
+                texts.len()
+            ));
+        }
+
+        let mut data = parsed.data;
+        data.sort_by_key(|d| d.index.unwrap_or(usize::MAX));
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// Split a markdown file into paragraph-aligned chunks of up to MAX_CHUNK_CHARS.
+/// Daily logs only grow at the end, so earlier chunks (and their vectors) stay stable.
+pub fn chunk_markdown(content: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in content.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        // A heading starts a new chunk so log entries stay together with their timestamp
+        if paragraph.starts_with('#') && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() && current.len() + paragraph.len() + 2 > MAX_CHUNK_CHARS {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if paragraph.len() > MAX_CHUNK_CHARS {
+            let chars: Vec<char> = paragraph.chars().collect();
+            for piece in chars.chunks(MAX_CHUNK_CHARS) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Stable content hash used to keep vectors for unchanged chunks
+pub fn content_hash(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+/// Encode a vector as a little-endian f32 BLOB
+pub fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Decode a little-endian f32 BLOB
+pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// Cosine similarity; 0.0 for mismatched or zero vectors
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Reciprocal rank fusion contribution of a 0-based rank
+pub fn rrf_score(rank: usize) -> f64 {
+    1.0 / (RRF_K + rank as f64 + 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_markdown_splits_on_headings_and_size() {
+        let content = "# Memory\n\nAlice's address is 0xabc.\n\n## 09:15\nCofounder wallet noted.\n\n## 10:00\nLikes dark mode.";
+        let chunks = chunk_markdown(content);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].contains("Alice"));
+        assert!(chunks[1].starts_with("## 09:15"));
+
+        let long = "word ".repeat(400);
+        let chunks = chunk_markdown(&long);
+        assert!(chunks.len() >= 2);
+        assert!(chunks.iter().all(|c| c.chars().count() <= MAX_CHUNK_CHARS));
+    }
+
+    #[test]
+    fn test_chunk_hashes_stable_when_appending() {
+        let before = chunk_markdown("## 09:00\nfirst entry");
+        let after = chunk_markdown("## 09:00\nfirst entry\n\n## 10:00\nsecond entry");
+        assert_eq!(content_hash(&before[0]), content_hash(&after[0]));
+        assert_eq!(after.len(), 2);
+    }
+
+    #[test]
+    fn test_vector_roundtrip_and_similarity() {
+        let v = vec![0.5f32, -1.25, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&v)), v);
+        assert!((cosine_similarity(&v, &v) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+        assert!(rrf_score(0) > rrf_score(1));
+    }
+
+    #[test]
+    fn test_resolve_settings_from_url() {
+        let config = MemoryConfig {
+            embedding_endpoint: Some("http://localhost:11434/v1/embeddings".to_string()),
+            embedding_model: Some("nomic-embed-text".to_string()),
+            ..MemoryConfig::default()
+        };
+        let settings = EmbeddingSettings::resolve(&config).unwrap();
+        assert_eq!(settings.endpoint, "http://localhost:11434/v1/embeddings");
+        assert_eq!(settings.model, "nomic-embed-text");
+
+        let config = MemoryConfig {
+            embedding_endpoint: Some("no-such-preset".to_string()),
+            ..MemoryConfig::default()
+        };
+        assert!(EmbeddingSettings::resolve(&config).is_none());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Handles reading/writing markdown files and directory structure.
 
-use chrono::{Local, NaiveDate};
+use chrono::{Datelike, Local, NaiveDate, Weekday};
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
@@ -24,6 +24,34 @@ pub fn long_term_path(memory_dir: &Path, identity_id: Option<&str>) -> PathBuf {
     }
 }
 
+/// Directory (inside the memory dir) holding consolidated raw logs; hidden so
+/// archived files are neither listed nor indexed
+pub const ARCHIVE_DIR: &str = ".archive";
+
+/// Get the path to a weekly digest file (YYYY-Www.md, ISO week)
+pub fn weekly_digest_path(memory_dir: &Path, week_start: NaiveDate, identity_id: Option<&str>) -> PathBuf {
+    let week = week_start.iso_week();
+    let filename = format!("{}-W{:02}.md", week.year(), week.week());
+    match identity_id {
+        Some(id) => memory_dir.join(id).join(&filename),
+        None => memory_dir.join(&filename),
+    }
+}
+
+/// Get the path to a monthly digest file (YYYY-MM.md)
+pub fn monthly_digest_path(memory_dir: &Path, month_start: NaiveDate, identity_id: Option<&str>) -> PathBuf {
+    let filename = format!("{}.md", month_start.format("%Y-%m"));
+    match identity_id {
+        Some(id) => memory_dir.join(id).join(&filename),
+        None => memory_dir.join(&filename),
+    }
+}
+
+/// Where a memory file (given relative to memory_dir) is moved when archived
+pub fn archive_path(memory_dir: &Path, relative_path: &str) -> PathBuf {
+    memory_dir.join(ARCHIVE_DIR).join(relative_path)
+}
+
 /// Ensure the memory directory structure exists
 pub fn ensure_memory_dirs(memory_dir: &Path, identity_id: Option<&str>) -> io::Result<()> {
     fs::create_dir_all(memory_dir)?;
@@ -35,6 +63,11 @@ pub fn ensure_memory_dirs(memory_dir: &Path, identity_id: Option<&str>) -> io::R
 
 /// Append content to a file with a timestamp header
 pub fn append_to_file(path: &Path, content: &str) -> io::Result<()> {
+    append_with_header(path, &Local::now().format("%H:%M").to_string(), content)
+}
+
+/// Append content under a `## {header}` heading
+pub fn append_with_header(path: &Path, header: &str, content: &str) -> io::Result<()> {
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -54,9 +87,7 @@ pub fn append_to_file(path: &Path, content: &str) -> io::Result<()> {
         writeln!(file)?;
     }
 
-    // Add timestamp header and content
-    let timestamp = Local::now().format("%H:%M");
-    writeln!(file, "\n## {}\n{}", timestamp, content.trim())?;
+    writeln!(file, "\n## {}\n{}", header, content.trim())?;
 
     Ok(())
 }
@@ -107,6 +138,10 @@ pub fn list_memory_files(memory_dir: &Path) -> io::Result<Vec<PathBuf>> {
             let entry = entry?;
             let path = entry.path();
             if path.is_dir() {
+                // Skip hidden directories such as the archive
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
                 visit_dir(&path, files)?;
             } else if path.extension().map(|e| e == "md").unwrap_or(false) {
                 files.push(path);
@@ -133,6 +168,22 @@ pub fn parse_date_from_filename(filename: &str) -> Option<NaiveDate> {
     NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()
 }
 
+/// Parse the Monday of an ISO week from a filename like "2024-W03.md"
+pub fn parse_week_from_filename(filename: &str) -> Option<NaiveDate> {
+    let stem = filename.strip_suffix(".md")?;
+    let (year, week) = stem.split_once("-W")?;
+    NaiveDate::from_isoywd_opt(year.parse().ok()?, week.parse().ok()?, Weekday::Mon)
+}
+
+/// Parse the first day of a month from a filename like "2024-01.md"
+pub fn parse_month_from_filename(filename: &str) -> Option<NaiveDate> {
+    let stem = filename.strip_suffix(".md")?;
+    if stem.len() != 7 {
+        return None;
+    }
+    NaiveDate::parse_from_str(&format!("{}-01", stem), "%Y-%m-%d").ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +244,9 @@ mod tests {
         fs::write(mem_dir.join("2024-01-15.md"), "content").unwrap();
         fs::create_dir(mem_dir.join("user1")).unwrap();
         fs::write(mem_dir.join("user1/MEMORY.md"), "content").unwrap();
+        // Archived logs are not listed
+        fs::create_dir_all(mem_dir.join(".archive")).unwrap();
+        fs::write(mem_dir.join(".archive/2024-01-01.md"), "content").unwrap();
 
         let files = list_memory_files(mem_dir).unwrap();
         assert_eq!(files.len(), 3);
@@ -207,4 +261,23 @@ mod tests {
         assert_eq!(parse_date_from_filename("MEMORY.md"), None);
         assert_eq!(parse_date_from_filename("invalid.md"), None);
     }
+
+    #[test]
+    fn test_digest_filenames() {
+        let dir = PathBuf::from("/memory");
+        let monday = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+
+        assert_eq!(
+            weekly_digest_path(&dir, monday, Some("user1")),
+            PathBuf::from("/memory/user1/2024-W03.md")
+        );
+        assert_eq!(parse_week_from_filename("2024-W03.md"), Some(monday));
+        assert_eq!(parse_week_from_filename("2024-01-15.md"), None);
+
+        let first = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert_eq!(monthly_digest_path(&dir, first, None), PathBuf::from("/memory/2024-01.md"));
+        assert_eq!(parse_month_from_filename("2024-01.md"), Some(first));
+        assert_eq!(parse_month_from_filename("2024-01-15.md"), None);
+        assert_eq!(parse_month_from_filename("MEMORY.md"), None);
+    }
 }
@@ -3,10 +3,14 @@
 //! A simplified memory system where markdown files are the source of truth:
 //! - MEMORY.md - Global long-term facts and preferences
 //! - YYYY-MM-DD.md - Daily logs
+//! - YYYY-Www.md / YYYY-MM.md - Weekly / monthly digests of consolidated logs
 //! - {identity_id}/ - Per-identity memories (optional)
 //!
-//! SQLite FTS5 provides fast BM25 full-text search across all memory files.
+//! SQLite FTS5 provides fast BM25 full-text search across all memory files;
+//! with an embedding endpoint configured, chunk vectors add semantic recall.
 
+pub mod consolidation;
+pub mod embeddings;
 pub mod file_ops;
 pub mod store;
 
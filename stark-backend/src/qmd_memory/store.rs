@@ -3,13 +3,25 @@
 //! The MemoryStore manages:
 //! - Reading/writing markdown memory files
 //! - FTS5 full-text search indexing
+//! - Chunk embeddings for hybrid BM25 + vector search
 //! - Reindexing when files change
 
+use super::embeddings::{self, EmbeddingClient};
 use super::file_ops;
+use crate::hooks::{HookContext, HookEvent, HookManager};
 use chrono::{Local, NaiveDate};
 use rusqlite::{params, Connection, Result as SqliteResult};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How often the embedding worker retries chunks that failed to embed
+const EMBED_RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Characters of chunk text shown as the snippet for semantic-only matches
+const SEMANTIC_SNIPPET_CHARS: usize = 300;
 
 /// Search result from the memory store
 #[derive(Debug, Clone)]
@@ -18,16 +30,39 @@ pub struct SearchResult {
     pub file_path: String,
     /// Matching text snippet
     pub snippet: String,
-    /// BM25 relevance score (lower is better in FTS5)
+    /// Relevance score, lower is better: BM25 from FTS5, or the negated
+    /// fusion score for hybrid results
     pub score: f64,
 }
 
+/// A memory chunk ranked by vector similarity
+#[derive(Debug, Clone)]
+pub struct ChunkMatch {
+    pub file_path: String,
+    pub content: String,
+    pub similarity: f32,
+}
+
+/// Embedding coverage of the chunk index
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct EmbeddingStatus {
+    pub enabled: bool,
+    pub total_chunks: usize,
+    pub embedded_chunks: usize,
+}
+
 /// Memory store wrapping SQLite FTS5 for markdown file indexing
 pub struct MemoryStore {
     /// Path to the memory directory
     memory_dir: PathBuf,
     /// SQLite connection for FTS5 index
     conn: Mutex<Connection>,
+    /// Hook manager notified with OnMemoryUpdate after each write
+    hook_manager: OnceLock<Arc<HookManager>>,
+    /// Embedding client; semantic search is off until one is attached
+    embedder: OnceLock<Arc<EmbeddingClient>>,
+    /// Wakes the embedding worker when chunks change
+    embed_notify: Arc<Notify>,
 }
 
 impl MemoryStore {
@@ -39,25 +74,7 @@ impl MemoryStore {
         // Open or create SQLite database
         let conn = Connection::open(db_path)?;
 
-        // Create FTS5 table for indexing
-        conn.execute(
-            "CREATE VIRTUAL TABLE IF NOT EXISTS qmd_memory_fts USING fts5(
-                file_path,
-                content,
-                tokenize='porter'
-            )",
-            [],
-        )?;
-
-        let store = Self {
-            memory_dir,
-            conn: Mutex::new(conn),
-        };
-
-        // Initial reindex
-        store.reindex()?;
-
-        Ok(store)
+        Self::with_connection(memory_dir, conn)
     }
 
     /// Create memory store using an existing database connection
@@ -74,15 +91,110 @@ impl MemoryStore {
             [],
         )?;
 
+        // Paragraph chunks with their embeddings (NULL until embedded)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS qmd_memory_chunks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                file_path TEXT NOT NULL,
+                chunk_index INTEGER NOT NULL,
+                content TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                embedding BLOB,
+                embedding_model TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_qmd_memory_chunks_file ON qmd_memory_chunks(file_path)",
+            [],
+        )?;
+
         let store = Self {
             memory_dir,
             conn: Mutex::new(conn),
+            hook_manager: OnceLock::new(),
+            embedder: OnceLock::new(),
+            embed_notify: Arc::new(Notify::new()),
         };
 
         store.reindex()?;
         Ok(store)
     }
 
+    /// Attach a hook manager so memory writes fire OnMemoryUpdate hooks
+    pub fn set_hook_manager(&self, hook_manager: Arc<HookManager>) {
+        let _ = self.hook_manager.set(hook_manager);
+    }
+
+    /// Fire OnMemoryUpdate hooks in the background (writes are synchronous)
+    fn notify_update(&self, path: &std::path::Path, content: &str, identity_id: Option<&str>) {
+        let manager = match self.hook_manager.get() {
+            Some(m) => m.clone(),
+            None => return,
+        };
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(h) => h,
+            Err(_) => return,
+        };
+        let file = path
+            .strip_prefix(&self.memory_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        let mut context = HookContext::new(HookEvent::OnMemoryUpdate)
+            .with_message(content.to_string())
+            .with_extra(serde_json::json!({
+                "file": file,
+                "identity_id": identity_id,
+            }));
+        handle.spawn(async move {
+            manager.execute(HookEvent::OnMemoryUpdate, &mut context).await;
+        });
+    }
+
+    /// Attach an embedding client and start the background worker that embeds
+    /// new and changed chunks. Requires a Tokio runtime.
+    pub fn enable_embeddings(self: &Arc<Self>, client: EmbeddingClient) {
+        let model = client.model().to_string();
+        if self.embedder.set(Arc::new(client)).is_err() {
+            return;
+        }
+        let handle = match tokio::runtime::Handle::try_current() {
+            Ok(h) => h,
+            Err(_) => {
+                log::warn!("[QMD_MEMORY] No async runtime, chunks will not be embedded");
+                return;
+            }
+        };
+        log::info!("[QMD_MEMORY] Semantic search enabled with model {}", model);
+
+        let store = Arc::downgrade(self);
+        let notify = self.embed_notify.clone();
+        // Embed whatever the initial reindex left pending
+        notify.notify_one();
+        handle.spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = notify.notified() => {}
+                    _ = tokio::time::sleep(EMBED_RETRY_INTERVAL) => {}
+                }
+                let Some(store) = store.upgrade() else {
+                    break;
+                };
+                match store.embed_pending().await {
+                    Ok(0) => {}
+                    Ok(count) => log::info!("[QMD_MEMORY] Embedded {} memory chunks", count),
+                    Err(e) => log::warn!("[QMD_MEMORY] Embedding failed, will retry: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Whether hybrid search has an embedding client
+    pub fn semantic_enabled(&self) -> bool {
+        self.embedder.get().is_some()
+    }
+
     /// Get the memory directory path
     pub fn memory_dir(&self) -> &PathBuf {
         &self.memory_dir
@@ -99,6 +211,7 @@ impl MemoryStore {
         let files = file_ops::list_memory_files(&self.memory_dir).unwrap_or_default();
 
         let mut count = 0;
+        let mut indexed = HashSet::new();
         for file_path in files {
             if let Ok(content) = file_ops::read_file(&file_path) {
                 if let Some(rel_path) = file_ops::relative_path(&self.memory_dir, &file_path) {
@@ -106,11 +219,27 @@ impl MemoryStore {
                         "INSERT INTO qmd_memory_fts (file_path, content) VALUES (?1, ?2)",
                         params![rel_path, content],
                     )?;
+                    sync_chunks(&conn, &rel_path, &content)?;
+                    indexed.insert(rel_path);
                     count += 1;
                 }
             }
         }
 
+        // Drop chunks of files that no longer exist
+        let stale: Vec<String> = conn
+            .prepare("SELECT DISTINCT file_path FROM qmd_memory_chunks")?
+            .query_map([], |row| row.get(0))?
+            .collect::<SqliteResult<Vec<String>>>()?
+            .into_iter()
+            .filter(|path| !indexed.contains(path))
+            .collect();
+        for path in stale {
+            conn.execute("DELETE FROM qmd_memory_chunks WHERE file_path = ?1", params![path])?;
+        }
+        drop(conn);
+        self.embed_notify.notify_one();
+
         log::info!("[QMD_MEMORY] Indexed {} memory files", count);
         Ok(count)
     }
@@ -143,6 +272,123 @@ impl MemoryStore {
         Ok(results)
     }
 
+    /// Hybrid search: BM25 and chunk-vector rankings fused with reciprocal rank
+    /// fusion, so paraphrased queries still find memories. Falls back to BM25 when
+    /// no embedding client is attached or the query can't be embedded.
+    pub async fn hybrid_search(&self, query: &str, limit: i32) -> Result<Vec<SearchResult>, String> {
+        let limit = limit.max(1);
+        let candidates = limit * 3;
+        let keyword = self.search(query, candidates).map_err(|e| e.to_string())?;
+
+        let Some(embedder) = self.embedder.get().cloned() else {
+            return Ok(keyword.into_iter().take(limit as usize).collect());
+        };
+        let query_vector = match embedder.embed(&[query.to_string()]).await {
+            Ok(mut vectors) => vectors.pop().unwrap_or_default(),
+            Err(e) => {
+                log::warn!("[QMD_MEMORY] Query embedding failed, using BM25 only: {}", e);
+                return Ok(keyword.into_iter().take(limit as usize).collect());
+            }
+        };
+        let semantic = self
+            .vector_search(&query_vector, embedder.model(), candidates as usize)
+            .map_err(|e| e.to_string())?;
+
+        Ok(fuse_results(keyword, semantic, limit as usize))
+    }
+
+    /// Rank embedded chunks by cosine similarity to a query vector
+    pub fn vector_search(&self, query_vector: &[f32], model: &str, limit: usize) -> SqliteResult<Vec<ChunkMatch>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT file_path, content, embedding FROM qmd_memory_chunks
+             WHERE embedding IS NOT NULL AND embedding_model = ?1",
+        )?;
+        let mut matches = stmt
+            .query_map(params![model], |row| {
+                let embedding: Vec<u8> = row.get(2)?;
+                Ok(ChunkMatch {
+                    file_path: row.get(0)?,
+                    content: row.get(1)?,
+                    similarity: embeddings::cosine_similarity(query_vector, &embeddings::decode_vector(&embedding)),
+                })
+            })?
+            .collect::<SqliteResult<Vec<_>>>()?;
+
+        matches.retain(|m| m.similarity >= embeddings::MIN_SIMILARITY);
+        matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        matches.truncate(limit);
+        Ok(matches)
+    }
+
+    /// Embed chunks that have no vector for the current model. Returns how many
+    /// were embedded.
+    pub async fn embed_pending(&self) -> Result<usize, String> {
+        let Some(embedder) = self.embedder.get().cloned() else {
+            return Ok(0);
+        };
+        let model = embedder.model().to_string();
+        let mut total = 0;
+
+        loop {
+            let batch: Vec<(i64, String, String)> = {
+                let conn = self.conn.lock().unwrap();
+                let mut stmt = conn
+                    .prepare(
+                        "SELECT id, content, content_hash FROM qmd_memory_chunks
+                         WHERE embedding IS NULL OR embedding_model IS NOT ?1
+                         ORDER BY id LIMIT ?2",
+                    )
+                    .map_err(|e| e.to_string())?;
+                stmt.query_map(params![model, embeddings::EMBED_BATCH_SIZE as i64], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })
+                .map_err(|e| e.to_string())?
+                .collect::<SqliteResult<Vec<_>>>()
+                .map_err(|e| e.to_string())?
+            };
+            if batch.is_empty() {
+                break;
+            }
+
+            let texts: Vec<String> = batch.iter().map(|(_, content, _)| content.clone()).collect();
+            let vectors = embedder.embed(&texts).await?;
+
+            let conn = self.conn.lock().unwrap();
+            for ((id, _, hash), vector) in batch.iter().zip(vectors) {
+                // The hash check skips chunks rewritten while the request was in flight
+                conn.execute(
+                    "UPDATE qmd_memory_chunks SET embedding = ?1, embedding_model = ?2
+                     WHERE id = ?3 AND content_hash = ?4",
+                    params![embeddings::encode_vector(&vector), model, id, hash],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            total += batch.len();
+        }
+
+        Ok(total)
+    }
+
+    /// How much of the chunk index has vectors for the current model
+    pub fn embedding_status(&self) -> SqliteResult<EmbeddingStatus> {
+        let conn = self.conn.lock().unwrap();
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM qmd_memory_chunks", [], |row| row.get(0))?;
+        let embedded: i64 = match self.embedder.get() {
+            Some(embedder) => conn.query_row(
+                "SELECT COUNT(*) FROM qmd_memory_chunks WHERE embedding IS NOT NULL AND embedding_model = ?1",
+                params![embedder.model()],
+                |row| row.get(0),
+            )?,
+            None => 0,
+        };
+        Ok(EmbeddingStatus {
+            enabled: self.semantic_enabled(),
+            total_chunks: total as usize,
+            embedded_chunks: embedded as usize,
+        })
+    }
+
     /// Get content of a specific memory file
     pub fn get_file(&self, relative_path: &str) -> std::io::Result<String> {
         let full_path = self.memory_dir.join(relative_path);
@@ -166,6 +412,7 @@ impl MemoryStore {
 
         // Update index for this file
         self.index_file(&path).ok();
+        self.notify_update(&path, content, identity_id);
 
         Ok(())
     }
@@ -181,12 +428,46 @@ impl MemoryStore {
         // Ensure directory exists
         file_ops::ensure_memory_dirs(&self.memory_dir, identity_id)?;
 
-        // Append with timestamp
-        file_ops::append_to_file(&path, content)?;
+        // Append under a dated header so consolidation knows where facts came from
+        let header = Local::now().format("%Y-%m-%d %H:%M").to_string();
+        file_ops::append_with_header(&path, &header, content)?;
 
         // Update index for this file
         self.index_file(&path).ok();
+        self.notify_update(&path, content, identity_id);
+
+        Ok(())
+    }
+
+    /// Overwrite a memory file (digests, compacted MEMORY.md) and reindex it
+    pub fn replace_file(&self, relative_path: &str, content: &str) -> std::io::Result<()> {
+        let path = self.memory_dir.join(relative_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)?;
+        self.index_file(&path).ok();
+        Ok(())
+    }
 
+    /// Move a memory file into the archive and drop it from the index
+    pub fn archive_file(&self, relative_path: &str) -> std::io::Result<()> {
+        let archived = file_ops::archive_path(&self.memory_dir, relative_path);
+        if let Some(parent) = archived.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(self.memory_dir.join(relative_path), &archived)?;
+        self.remove_from_index(relative_path).ok();
+        Ok(())
+    }
+
+    /// Copy a memory file into the archive under another name (backups)
+    pub fn archive_copy(&self, relative_path: &str, archived_name: &str) -> std::io::Result<()> {
+        let archived = file_ops::archive_path(&self.memory_dir, archived_name);
+        if let Some(parent) = archived.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(self.memory_dir.join(relative_path), archived)?;
         Ok(())
     }
 
@@ -239,6 +520,10 @@ impl MemoryStore {
                     "INSERT INTO qmd_memory_fts (file_path, content) VALUES (?1, ?2)",
                     params![rel_path, content],
                 )?;
+
+                // Re-chunk; only new or changed chunks need embedding
+                sync_chunks(&conn, &rel_path, &content)?;
+                self.embed_notify.notify_one();
             }
         }
 
@@ -246,6 +531,102 @@ impl MemoryStore {
     }
 }
 
+impl MemoryStore {
+    fn remove_from_index(&self, relative_path: &str) -> SqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM qmd_memory_fts WHERE file_path = ?1", params![relative_path])?;
+        conn.execute("DELETE FROM qmd_memory_chunks WHERE file_path = ?1", params![relative_path])?;
+        Ok(())
+    }
+}
+
+/// Replace a file's chunks, carrying over vectors of chunks whose text is unchanged
+fn sync_chunks(conn: &Connection, rel_path: &str, content: &str) -> SqliteResult<()> {
+    let tx = conn.unchecked_transaction()?;
+
+    let mut existing: HashMap<String, (Vec<u8>, String)> = HashMap::new();
+    {
+        let mut stmt = tx.prepare(
+            "SELECT content_hash, embedding, embedding_model FROM qmd_memory_chunks
+             WHERE file_path = ?1 AND embedding IS NOT NULL AND embedding_model IS NOT NULL",
+        )?;
+        let rows = stmt.query_map(params![rel_path], |row| {
+            Ok((row.get::<_, String>(0)?, (row.get::<_, Vec<u8>>(1)?, row.get::<_, String>(2)?)))
+        })?;
+        for row in rows {
+            let (hash, vector) = row?;
+            existing.insert(hash, vector);
+        }
+    }
+
+    tx.execute("DELETE FROM qmd_memory_chunks WHERE file_path = ?1", params![rel_path])?;
+    for (index, chunk) in embeddings::chunk_markdown(content).iter().enumerate() {
+        let hash = embeddings::content_hash(chunk);
+        let (embedding, model) = match existing.get(&hash) {
+            Some((embedding, model)) => (Some(embedding.clone()), Some(model.clone())),
+            None => (None, None),
+        };
+        tx.execute(
+            "INSERT INTO qmd_memory_chunks (file_path, chunk_index, content, content_hash, embedding, embedding_model)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![rel_path, index as i64, chunk, hash, embedding, model],
+        )?;
+    }
+
+    tx.commit()
+}
+
+/// Fuse BM25 file results with chunk-similarity matches using reciprocal rank
+/// fusion. Each file is ranked once per list (by its best chunk for vectors).
+fn fuse_results(keyword: Vec<SearchResult>, semantic: Vec<ChunkMatch>, limit: usize) -> Vec<SearchResult> {
+    let mut fused: Vec<(SearchResult, f64)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for (rank, result) in keyword.into_iter().enumerate() {
+        positions.insert(result.file_path.clone(), fused.len());
+        fused.push((result, embeddings::rrf_score(rank)));
+    }
+
+    let mut seen = HashSet::new();
+    for chunk in semantic {
+        if !seen.insert(chunk.file_path.clone()) {
+            continue;
+        }
+        let score = embeddings::rrf_score(seen.len() - 1);
+        match positions.get(&chunk.file_path) {
+            Some(&pos) => fused[pos].1 += score,
+            None => {
+                positions.insert(chunk.file_path.clone(), fused.len());
+                fused.push((
+                    SearchResult {
+                        file_path: chunk.file_path,
+                        snippet: semantic_snippet(&chunk.content),
+                        score: 0.0,
+                    },
+                    score,
+                ));
+            }
+        }
+    }
+
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1));
+    fused
+        .into_iter()
+        .take(limit)
+        .map(|(mut result, score)| {
+            result.score = -score;
+            result
+        })
+        .collect()
+}
+
+fn semantic_snippet(content: &str) -> String {
+    if content.chars().count() <= SEMANTIC_SNIPPET_CHARS {
+        return content.to_string();
+    }
+    format!("{}...", content.chars().take(SEMANTIC_SNIPPET_CHARS).collect::<String>())
+}
+
 /// Escape special characters for FTS5 query
 fn escape_fts5_query(query: &str) -> String {
     // Split into words and join with OR for multi-word queries
@@ -356,4 +737,68 @@ mod tests {
         assert!(user2_mem.contains("tea"));
         assert!(!user2_mem.contains("coffee"));
     }
+
+    #[test]
+    fn test_chunks_keep_vectors_on_append() {
+        let dir = tempdir().unwrap();
+        let mem_dir = dir.path().join("memory");
+        let db_path = dir.path().join("test.db");
+
+        let store =
+            MemoryStore::new(mem_dir.clone(), db_path.to_str().unwrap()).expect("Failed to create store");
+        store
+            .append_daily_log("Alice's address is 0xabc", None)
+            .expect("Failed to append");
+
+        // Pretend the first chunk was embedded
+        {
+            let conn = store.conn.lock().unwrap();
+            conn.execute(
+                "UPDATE qmd_memory_chunks SET embedding = ?1, embedding_model = 'test'",
+                params![embeddings::encode_vector(&[1.0, 0.0])],
+            )
+            .unwrap();
+        }
+
+        store
+            .append_daily_log("Bought more ETH", None)
+            .expect("Failed to append");
+
+        let conn = store.conn.lock().unwrap();
+        let (total, embedded): (i64, i64) = conn
+            .query_row(
+                "SELECT COUNT(*), COUNT(embedding) FROM qmd_memory_chunks",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(embedded, 1);
+        drop(conn);
+
+        let matches = store.vector_search(&[0.9, 0.1], "test", 5).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].content.contains("Alice"));
+    }
+
+    #[test]
+    fn test_fuse_results() {
+        let keyword = vec![
+            SearchResult { file_path: "a.md".into(), snippet: "a".into(), score: -3.0 },
+            SearchResult { file_path: "b.md".into(), snippet: "b".into(), score: -1.0 },
+        ];
+        let semantic = vec![
+            ChunkMatch { file_path: "c.md".into(), content: "cofounder wallet".into(), similarity: 0.9 },
+            ChunkMatch { file_path: "b.md".into(), content: "b chunk".into(), similarity: 0.8 },
+            ChunkMatch { file_path: "c.md".into(), content: "other chunk".into(), similarity: 0.7 },
+        ];
+
+        let fused = fuse_results(keyword, semantic, 10);
+        let files: Vec<&str> = fused.iter().map(|r| r.file_path.as_str()).collect();
+        // b.md ranks in both lists and wins; c.md only appears once despite two chunks
+        assert_eq!(files, vec!["b.md", "a.md", "c.md"]);
+        assert_eq!(fused[1].snippet, "a");
+        assert_eq!(fused[2].snippet, "cofounder wallet");
+        assert!(fused[0].score < fused[1].score);
+    }
 }
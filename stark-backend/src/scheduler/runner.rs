@@ -1,11 +1,20 @@
 use crate::channels::dispatcher::MessageDispatcher;
 use crate::channels::types::NormalizedMessage;
+use crate::aave;
+use crate::approvals;
+use crate::config;
 use crate::db::Database;
 use crate::execution::ExecutionTracker;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
+use crate::metrics::METRICS;
 use crate::models::{CronJob, HeartbeatConfig, JobStatus, ScheduleType};
+use crate::polymarket;
+use crate::portfolio;
+use crate::qmd_memory::consolidation;
 use crate::tools::ToolRegistry;
+use crate::tx_queue::TxQueueManager;
+use super::triggers::{EventTrigger, TriggerFire, TriggerState};
 use crate::wallet;
 use chrono::{DateTime, Duration, Local, NaiveTime, Utc, Weekday, Datelike, Timelike};
 use std::sync::Arc;
@@ -55,6 +64,8 @@ pub struct Scheduler {
     config: SchedulerConfig,
     /// Wallet provider for x402 payments in scheduled tasks (heartbeats, cron jobs)
     wallet_provider: Option<Arc<dyn wallet::WalletProvider>>,
+    /// Transaction queue for system-initiated transactions (Polymarket redeems)
+    tx_queue: Option<Arc<TxQueueManager>>,
 }
 
 impl Scheduler {
@@ -73,9 +84,16 @@ impl Scheduler {
             execution_tracker,
             config,
             wallet_provider,
+            tx_queue: None,
         }
     }
 
+    /// Attach the transaction queue used for system-initiated transactions
+    pub fn with_tx_queue(mut self, tx_queue: Arc<TxQueueManager>) -> Self {
+        self.tx_queue = Some(tx_queue);
+        self
+    }
+
     /// Compatibility method - db_url is no longer needed with connection pool
     #[deprecated(note = "Use new() instead - db_url is no longer needed with r2d2 connection pool")]
     pub fn new_with_db_url(
@@ -128,6 +146,18 @@ impl Scheduler {
             log::error!("Error processing heartbeats: {}", e);
         }
 
+        // Take a portfolio snapshot when the latest one is older than the configured interval
+        self.process_portfolio_snapshot();
+
+        // Scan token approvals of the bot wallet and tracked Safes
+        self.process_approval_scan();
+
+        // Evaluate Polymarket exit rules and queue redeems for resolved markets
+        self.process_polymarket_positions();
+
+        // Fold old daily logs into digests and compact long-term memory
+        self.process_memory_consolidation();
+
         // Run periodic cleanup tasks once per hour (at minute 0, second 0-1)
         let now = Local::now();
         if now.minute() == 0 && now.second() <= 1 {
@@ -158,6 +188,193 @@ impl Scheduler {
                 log::error!("Scheduler: Failed to cleanup safe mode channels: {}", e);
             }
         }
+
+        // Cleanup old portfolio snapshots (keep last 365 days)
+        match self.db.cleanup_old_portfolio_snapshots(365) {
+            Ok(count) if count > 0 => {
+                log::info!("Scheduler: Cleaned up {} old portfolio snapshots", count);
+            }
+            Ok(_) => {} // Nothing to clean up
+            Err(e) => {
+                log::error!("Scheduler: Failed to cleanup portfolio snapshots: {}", e);
+            }
+        }
+
+        // Cleanup old execution traces (STARK_EXECUTION_TRACE_RETENTION_DAYS, 0 keeps them)
+        let trace_retention_days = config::execution_trace_retention_days();
+        if trace_retention_days > 0 {
+            match self.db.cleanup_old_execution_traces(trace_retention_days) {
+                Ok(count) if count > 0 => {
+                    log::info!("Scheduler: Cleaned up {} old execution traces", count);
+                }
+                Ok(_) => {} // Nothing to clean up
+                Err(e) => {
+                    log::error!("Scheduler: Failed to cleanup execution traces: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Spawn a portfolio snapshot if one is due (requires a wallet provider for RPC access)
+    fn process_portfolio_snapshot(&self) {
+        let wallet_provider = match &self.wallet_provider {
+            Some(wp) => Arc::clone(wp),
+            None => return,
+        };
+
+        if !portfolio::snapshot_due(&self.db, config::portfolio_snapshot_interval_mins()) {
+            return;
+        }
+
+        let db = Arc::clone(&self.db);
+        let broadcaster = Arc::clone(&self.broadcaster);
+        tokio::spawn(async move {
+            match portfolio::take_snapshot(&db, &wallet_provider).await {
+                Ok(snapshot) => {
+                    broadcaster.broadcast(GatewayEvent::custom(
+                        "portfolio_snapshot",
+                        serde_json::json!({
+                            "snapshot_id": snapshot.id,
+                            "total_value_usd": snapshot.total_value_usd,
+                            "errors": snapshot.errors,
+                            "taken_at": snapshot.taken_at.to_rfc3339(),
+                        }),
+                    ));
+                }
+                Err(e) => log::error!("Scheduler: Portfolio snapshot failed: {}", e),
+            }
+        });
+    }
+
+    /// Spawn a token approval scan if one is due; high-risk allowances are announced
+    fn process_approval_scan(&self) {
+        let wallet_provider = match &self.wallet_provider {
+            Some(wp) => Arc::clone(wp),
+            None => return,
+        };
+
+        if !approvals::scan_due(&self.db, config::approval_scan_interval_hours()) {
+            return;
+        }
+
+        let db = Arc::clone(&self.db);
+        let broadcaster = Arc::clone(&self.broadcaster);
+        tokio::spawn(async move {
+            match approvals::scan_approvals(&db, &wallet_provider).await {
+                Ok(summary) => {
+                    let contacts = db.list_contacts().unwrap_or_default();
+                    let labels = approvals::spender_labels(&contacts);
+                    let high_risk: Vec<_> = approvals::audit(
+                        db.list_token_approvals(None, None, true).unwrap_or_default(),
+                        &labels,
+                    )
+                    .into_iter()
+                    .filter(|a| a.risk == approvals::Risk::High)
+                    .collect();
+                    broadcaster.broadcast(GatewayEvent::custom(
+                        "approval_scan",
+                        serde_json::json!({
+                            "summary": summary,
+                            "high_risk": high_risk,
+                        }),
+                    ));
+                }
+                Err(e) => log::error!("Scheduler: Approval scan failed: {}", e),
+            }
+        });
+    }
+
+    /// Spawn a Polymarket position check if one is due: exit rules are evaluated and
+    /// triggered exits placed, then one redeem is queued for a resolved market (if enabled).
+    /// Nothing runs without active exit rules or auto-redeem.
+    fn process_polymarket_positions(&self) {
+        let wallet_provider = match &self.wallet_provider {
+            Some(wp) => Arc::clone(wp),
+            None => return,
+        };
+
+        let redeem_enabled = config::polymarket_auto_redeem_enabled();
+        if !redeem_enabled && !polymarket::has_active_exit_rules(&self.db) {
+            return;
+        }
+
+        if !polymarket::check_due(config::polymarket_exit_check_interval_secs()) {
+            return;
+        }
+
+        let db = Arc::clone(&self.db);
+        let broadcaster = Arc::clone(&self.broadcaster);
+        let tx_queue = self
+            .tx_queue
+            .clone()
+            .filter(|_| redeem_enabled);
+        tokio::spawn(async move {
+            let policy = polymarket::ExitPolicy::from_config();
+            match polymarket::run_exit_checks(&db, &wallet_provider, &policy).await {
+                Ok(results) => {
+                    for result in results {
+                        broadcaster.broadcast(GatewayEvent::custom(
+                            "polymarket_exit",
+                            serde_json::to_value(&result).unwrap_or_default(),
+                        ));
+                    }
+                }
+                Err(e) => log::error!("Scheduler: Polymarket exit check failed: {}", e),
+            }
+
+            if let Some(tx_queue) = tx_queue {
+                match polymarket::queue_redemptions(&db, &wallet_provider, &tx_queue).await {
+                    Ok(Some(redemption)) => {
+                        broadcaster.broadcast(GatewayEvent::custom(
+                            "polymarket_redeem_queued",
+                            serde_json::to_value(&redemption).unwrap_or_default(),
+                        ));
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::error!("Scheduler: Polymarket redeem failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Spawn a memory consolidation run if one is due
+    fn process_memory_consolidation(&self) {
+        let interval_secs = config::memory_consolidation_interval_hours() * 3600;
+        if !consolidation::check_due(interval_secs) {
+            return;
+        }
+        let Some(store) = self.dispatcher.memory_store() else {
+            return;
+        };
+
+        let db = Arc::clone(&self.db);
+        let broadcaster = Arc::clone(&self.broadcaster);
+        let wallet_provider = self.wallet_provider.clone();
+        tokio::spawn(async move {
+            let started = std::time::Instant::now();
+            let result = match consolidation::ai_client(&db, wallet_provider) {
+                Ok(client) => {
+                    let policy = consolidation::ConsolidationPolicy::from_config();
+                    consolidation::consolidate(&store, &client, Some(db.as_ref()), Local::now().date_naive(), &policy).await
+                }
+                Err(e) => Err(e),
+            };
+            METRICS.record_scheduler_run(
+                "memory_consolidation",
+                result.as_ref().is_ok_and(|r| r.errors.is_empty()),
+                started.elapsed(),
+            );
+            match result {
+                Ok(report) if !report.is_empty() => {
+                    broadcaster.broadcast(GatewayEvent::custom(
+                        "memory_consolidated",
+                        serde_json::to_value(&report).unwrap_or_default(),
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("Scheduler: Memory consolidation failed: {}", e),
+            }
+        });
     }
 
     /// Process due cron jobs
@@ -169,8 +386,14 @@ impl Scheduler {
 
         for job in due_jobs {
             let scheduler = Arc::clone(&Arc::new(self.clone_inner()));
+            let is_event = ScheduleType::from_str(&job.schedule_type) == Some(ScheduleType::Event);
             tokio::spawn(async move {
-                if let Err(e) = scheduler.execute_cron_job(&job).await {
+                let result = if is_event {
+                    scheduler.check_event_trigger(&job).await
+                } else {
+                    scheduler.execute_cron_job(&job, None).await
+                };
+                if let Err(e) = result {
                     log::error!("Cron job '{}' failed: {}", job.name, e);
                 }
             });
@@ -187,11 +410,94 @@ impl Scheduler {
             execution_tracker: Arc::clone(&self.execution_tracker),
             config: self.config.clone(),
             wallet_provider: self.wallet_provider.clone(),
+            tx_queue: self.tx_queue.clone(),
+        }
+    }
+
+    /// Poll an event-triggered job's source and run the job if the trigger fired
+    async fn check_event_trigger(&self, job: &CronJob) -> Result<(), String> {
+        // Push next_run_at forward first so slow sources are not polled twice
+        let next_poll = self.calculate_next_run(job).map(|dt| dt.to_rfc3339());
+        if let Err(e) = self.db.mark_cron_job_started(job.id, next_poll.as_deref()) {
+            log::error!("Failed to mark event job as polled: {}", e);
+        }
+
+        let trigger = EventTrigger::parse(&job.schedule_value)?;
+        let mut state: TriggerState = self
+            .db
+            .get_cron_trigger_state(job.id)
+            .map_err(|e| format!("Failed to load trigger state: {}", e))?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
+        let fired = trigger
+            .poll(&mut state, &self.db, self.wallet_provider.as_ref())
+            .await;
+
+        // Keep progress (scanned blocks, seen txs) even when the poll failed part-way
+        let state_json = serde_json::to_string(&state).unwrap_or_default();
+        if let Err(e) = self.db.set_cron_trigger_state(job.id, &state_json) {
+            log::error!("Failed to save trigger state for '{}': {}", job.name, e);
+        }
+
+        let mut fire = match fired {
+            Ok(Some(fire)) => fire,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                log::warn!("Event trigger for '{}' could not be checked: {}", job.name, e);
+                return Ok(());
+            }
+        };
+
+        log::info!("Event trigger fired for cron job '{}': {}", job.name, fire.summary);
+
+        if let EventTrigger::AaveHealthFactor { network, address, auto_repay: true, .. } = &trigger {
+            let repay = self.queue_aave_repay(network, address.as_deref()).await;
+            fire.details["auto_repay"] = match repay {
+                Ok(repay) => {
+                    self.broadcaster.broadcast(GatewayEvent::custom(
+                        "aave_repay_queued",
+                        serde_json::to_value(&repay).unwrap_or_default(),
+                    ));
+                    serde_json::to_value(&repay).unwrap_or_default()
+                }
+                Err(e) => {
+                    log::warn!("Aave auto-repay for '{}' not queued: {}", job.name, e);
+                    serde_json::json!({ "error": e })
+                }
+            };
+        }
+        self.broadcaster.broadcast(GatewayEvent::custom(
+            "cron_trigger_fired",
+            serde_json::json!({
+                "job_id": job.job_id,
+                "name": job.name,
+                "summary": fire.summary,
+                "details": fire.details,
+            }),
+        ));
+
+        self.execute_cron_job(job, Some(&fire)).await
+    }
+
+    /// Queue an automatic repay for a fired Aave health-factor watcher (bot wallet only)
+    async fn queue_aave_repay(&self, network: &str, address: Option<&str>) -> Result<aave::AutoRepay, String> {
+        if !config::aave_auto_repay_enabled() {
+            return Err("Automatic repays are disabled (STARK_AAVE_AUTO_REPAY)".to_string());
+        }
+        let (wallet_provider, tx_queue) = match (&self.wallet_provider, &self.tx_queue) {
+            (Some(wp), Some(queue)) => (wp, queue),
+            _ => return Err("Automatic repays need a wallet and the transaction queue".to_string()),
+        };
+        if address.is_some_and(|a| !a.eq_ignore_ascii_case(&wallet_provider.get_address())) {
+            return Err("Automatic repays only cover the bot wallet".to_string());
         }
+        aave::queue_auto_repay(&self.db, wallet_provider, tx_queue, &aave::RepayPolicy::from_config(), network).await
     }
 
-    /// Execute a single cron job
-    async fn execute_cron_job(&self, job: &CronJob) -> Result<(), String> {
+    /// Execute a single cron job. For event-triggered jobs, `trigger` describes
+    /// the event and is appended to the prompt.
+    async fn execute_cron_job(&self, job: &CronJob, trigger: Option<&TriggerFire>) -> Result<(), String> {
         let started_at = Utc::now();
         let started_at_str = started_at.to_rfc3339();
 
@@ -224,6 +530,10 @@ impl Scheduler {
             .clone()
             .or_else(|| job.system_event.clone())
             .unwrap_or_else(|| format!("[Cron: {}]", job.name));
+        let message_text = match trigger {
+            Some(fire) => format!("{}\n\n{}", message_text, fire.prompt_context()),
+            None => message_text,
+        };
 
         // Determine channel ID based on session_mode
         // - "main" mode: use channel 0 (web channel) to share session with web UI
@@ -282,6 +592,7 @@ impl Scheduler {
         // Note: next_run_at was already set at the start to prevent race conditions
         // Update job status with final result
         let success = result.error.is_none();
+        METRICS.record_scheduler_run("cron", success, (completed_at - started_at).to_std().unwrap_or_default());
         self.db
             .update_cron_job_run_status(
                 job.id,
@@ -364,6 +675,9 @@ impl Scheduler {
                 let schedule = Schedule::from_str(&job.schedule_value).ok()?;
                 schedule.upcoming(Utc).next()
             }
+            ScheduleType::Event => {
+                Some(now + Duration::seconds(config::event_trigger_poll_secs() as i64))
+            }
         }
     }
 
@@ -568,6 +882,11 @@ impl Scheduler {
 
         // Execute the heartbeat
         let result = self.dispatcher.dispatch(normalized).await;
+        METRICS.record_scheduler_run(
+            "heartbeat",
+            result.error.is_none(),
+            (Utc::now() - now).to_std().unwrap_or_default(),
+        );
 
         // === GET SESSION ID ===
         // Query the session using the fixed heartbeat session key
@@ -623,7 +942,7 @@ impl Scheduler {
             .map_err(|e| format!("Database error: {}", e))?
             .ok_or_else(|| format!("Job not found: {}", job_id))?;
 
-        self.execute_cron_job(&job).await?;
+        self.execute_cron_job(&job, None).await?;
 
         Ok(format!("Job '{}' executed successfully", job.name))
     }
@@ -655,7 +974,7 @@ impl Scheduler {
             ));
 
             // Create dispatcher for this task (uses shared db pool)
-            let tracker = Arc::new(ExecutionTracker::new(broadcaster.clone()));
+            let tracker = Arc::new(ExecutionTracker::new(broadcaster.clone()).with_db(db.clone()));
             let tool_registry = Arc::new(ToolRegistry::new());
 
             // Use the wallet provider from the scheduler (works in both Standard and Flash mode)
@@ -828,7 +1147,9 @@ async fn execute_heartbeat_isolated(
         log::info!("[HEARTBEAT-AI] channel_type={}, channel_id={}, chat_id={}",
             HEARTBEAT_CHANNEL_TYPE, HEARTBEAT_CHANNEL_ID, HEARTBEAT_CHAT_ID);
 
+        let dispatch_started = std::time::Instant::now();
         let result = dispatcher.dispatch(normalized).await;
+        METRICS.record_scheduler_run("heartbeat", result.error.is_none(), dispatch_started.elapsed());
 
         log::info!("[HEARTBEAT-AI] Dispatch returned. Response len: {}, Error: {:?}",
             result.response.len(), result.error);
@@ -0,0 +1,560 @@
+//! Event triggers for cron jobs
+//!
+//! A cron job with `schedule_type: "event"` keeps a JSON trigger spec in
+//! `schedule_value`. The scheduler polls the trigger every
+//! `STARK_EVENT_TRIGGER_POLL_SECS` and runs the job's prompt when it fires.
+//! Per-job state (last scanned block, price side, seen Safe transactions) lives
+//! in `cron_trigger_state`, so each event fires the job once.
+
+use crate::aave;
+use crate::db::Database;
+use crate::tools::builtin::cryptocurrency::token_lookup::get_network_tokens;
+use crate::tools::builtin::cryptocurrency::{dexscreener, geckoterminal};
+use crate::tools::rpc_config::{resolve_rpc_config, Network};
+use crate::wallet::WalletProvider;
+use crate::x402::{FilterLog, X402EvmRpc};
+use ethers::types::{Address, H256, U256};
+use ethers::utils::{keccak256, to_checksum};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Most blocks scanned in one poll; after a long pause older blocks are skipped
+const MAX_BLOCK_RANGE: u64 = 2_000;
+/// Most events listed in the prompt of a fired job
+const MAX_EVENTS_IN_PROMPT: usize = 20;
+/// Seen Safe transaction hashes remembered per job
+const MAX_SEEN_SAFE_TXS: usize = 200;
+
+fn default_network() -> String {
+    "base".to_string()
+}
+
+/// Which side of a transfer the watched address is on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferDirection {
+    In,
+    Out,
+    #[default]
+    Any,
+}
+
+/// Trigger spec stored in a cron job's `schedule_value`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EventTrigger {
+    /// Token USD price at or above / at or below a threshold (GeckoTerminal, DexScreener fallback)
+    Price {
+        #[serde(default = "default_network")]
+        network: String,
+        /// Token address or symbol from tokens.ron
+        token: String,
+        above: Option<f64>,
+        below: Option<f64>,
+    },
+    /// ERC-20 Transfer into or out of a watched address (any token when `token` is unset)
+    Erc20Transfer {
+        #[serde(default = "default_network")]
+        network: String,
+        address: String,
+        token: Option<String>,
+        #[serde(default)]
+        direction: TransferDirection,
+    },
+    /// New pending multisig transactions on a Safe (Safe Transaction Service)
+    SafePending {
+        #[serde(default = "default_network")]
+        network: String,
+        safe: String,
+    },
+    /// Logs from a contract, optionally filtered by topics (null = any)
+    Log {
+        #[serde(default = "default_network")]
+        network: String,
+        address: String,
+        #[serde(default)]
+        topics: Vec<Option<String>>,
+    },
+    /// Aave V3 health factor at or below a threshold (bot wallet when `address` is unset).
+    /// With `auto_repay`, the scheduler queues a repay within the repay policy when it fires.
+    AaveHealthFactor {
+        #[serde(default = "default_network")]
+        network: String,
+        address: Option<String>,
+        below: f64,
+        #[serde(default)]
+        auto_repay: bool,
+    },
+}
+
+/// State carried between polls of one trigger
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TriggerState {
+    /// Last block scanned for log-based triggers
+    #[serde(default)]
+    pub last_block: Option<u64>,
+    /// Whether the price condition held at the last poll
+    #[serde(default)]
+    pub price_condition_met: Option<bool>,
+    #[serde(default)]
+    pub last_price: Option<f64>,
+    /// Safe transactions already reported (None until the first poll)
+    #[serde(default)]
+    pub seen_safe_txs: Option<Vec<String>>,
+    /// Whether the Aave health factor was at or below the threshold at the last poll
+    #[serde(default)]
+    pub health_factor_low: Option<bool>,
+    #[serde(default)]
+    pub last_health_factor: Option<f64>,
+}
+
+/// A fired trigger: a one-line summary plus the events behind it
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerFire {
+    pub summary: String,
+    pub details: Value,
+}
+
+impl TriggerFire {
+    /// Text appended to the job's prompt
+    pub fn prompt_context(&self) -> String {
+        let details = serde_json::to_string_pretty(&self.details).unwrap_or_default();
+        format!("[Event trigger] {}\n```json\n{}\n```", self.summary, details)
+    }
+}
+
+impl EventTrigger {
+    /// Parse and validate a trigger spec
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let trigger: EventTrigger =
+            serde_json::from_str(spec).map_err(|e| format!("Invalid event trigger: {}", e))?;
+        trigger.validate()?;
+        Ok(trigger)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        Network::from_str(self.network())
+            .map_err(|_| format!("Unsupported network '{}' (base, mainnet, polygon)", self.network()))?;
+
+        match self {
+            EventTrigger::Price { above, below, token, .. } => {
+                if above.is_none() && below.is_none() {
+                    return Err("Price trigger needs 'above' and/or 'below'".to_string());
+                }
+                if token.trim().is_empty() {
+                    return Err("Price trigger needs a 'token'".to_string());
+                }
+            }
+            EventTrigger::Erc20Transfer { address, token, .. } => {
+                parse_address(address)?;
+                if let Some(token) = token {
+                    parse_address(token)?;
+                }
+            }
+            EventTrigger::SafePending { safe, .. } => {
+                parse_address(safe)?;
+            }
+            EventTrigger::Log { address, topics, .. } => {
+                parse_address(address)?;
+                if topics.len() > 4 {
+                    return Err("A log filter has at most 4 topics".to_string());
+                }
+                for topic in topics.iter().flatten() {
+                    H256::from_str(topic).map_err(|_| format!("Invalid topic: {}", topic))?;
+                }
+            }
+            EventTrigger::AaveHealthFactor { network, address, below, .. } => {
+                if aave::pool_address(network).is_none() {
+                    return Err(format!("Aave V3 is not available on {}", network));
+                }
+                if let Some(address) = address {
+                    parse_address(address)?;
+                }
+                // Positions are liquidated below 1
+                if below.is_nan() || *below <= 1.0 {
+                    return Err("Aave health factor trigger needs 'below' greater than 1".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn network(&self) -> &str {
+        match self {
+            EventTrigger::Price { network, .. }
+            | EventTrigger::Erc20Transfer { network, .. }
+            | EventTrigger::SafePending { network, .. }
+            | EventTrigger::Log { network, .. }
+            | EventTrigger::AaveHealthFactor { network, .. } => network,
+        }
+    }
+
+    /// Poll the trigger's source once, updating `state`. Returns the fire, if any.
+    pub async fn poll(
+        &self,
+        state: &mut TriggerState,
+        db: &Database,
+        wallet_provider: Option<&Arc<dyn WalletProvider>>,
+    ) -> Result<Option<TriggerFire>, String> {
+        match self {
+            EventTrigger::Price { network, token, above, below } => {
+                let address = resolve_token_address(network, token)?;
+                let price = fetch_price(network, &address)
+                    .await?
+                    .ok_or_else(|| format!("No USD price for {} on {}", token, network))?;
+                let (met, fire) = price_condition(price, *above, *below, state.price_condition_met);
+                state.price_condition_met = Some(met);
+                state.last_price = Some(price);
+                Ok(fire.then(|| TriggerFire {
+                    summary: format!(
+                        "{} on {} is ${} ({})",
+                        token,
+                        network,
+                        price,
+                        threshold_text(price, *above, *below)
+                    ),
+                    details: json!({ "token": token, "address": address, "network": network, "price_usd": price }),
+                }))
+            }
+            EventTrigger::Erc20Transfer { network, address, token, direction } => {
+                let rpc = rpc_for(db, wallet_provider, network)?;
+                let watched = parse_address(address)?;
+                let filters = transfer_filters(watched, token.as_deref(), *direction)?;
+                let logs = poll_logs(&rpc, filters, state).await?;
+                let transfers: Vec<Value> = logs.iter().filter_map(decode_transfer).collect();
+                if transfers.is_empty() {
+                    return Ok(None);
+                }
+                Ok(Some(TriggerFire {
+                    summary: format!("{} ERC-20 transfer(s) involving {} on {}", transfers.len(), address, network),
+                    details: json!({ "network": network, "address": address, "transfers": truncate(transfers) }),
+                }))
+            }
+            EventTrigger::SafePending { network, safe } => {
+                let pending = fetch_safe_pending(network, safe).await?;
+                // The first poll only records what is already pending
+                let first_poll = state.seen_safe_txs.is_none();
+                let seen = state.seen_safe_txs.get_or_insert_with(Vec::new);
+                let new: Vec<Value> = pending
+                    .into_iter()
+                    .filter(|tx| {
+                        tx.get("safeTxHash")
+                            .and_then(|h| h.as_str())
+                            .is_some_and(|h| !seen.iter().any(|s| s == h))
+                    })
+                    .collect();
+                for tx in &new {
+                    if let Some(hash) = tx.get("safeTxHash").and_then(|h| h.as_str()) {
+                        seen.push(hash.to_string());
+                    }
+                }
+                let overflow = seen.len().saturating_sub(MAX_SEEN_SAFE_TXS);
+                seen.drain(..overflow);
+                if first_poll || new.is_empty() {
+                    return Ok(None);
+                }
+                Ok(Some(TriggerFire {
+                    summary: format!("{} new pending Safe transaction(s) on {} ({})", new.len(), safe, network),
+                    details: json!({ "network": network, "safe": safe, "transactions": truncate(new) }),
+                }))
+            }
+            EventTrigger::Log { network, address, topics } => {
+                let rpc = rpc_for(db, wallet_provider, network)?;
+                let filter = json!({ "address": address, "topics": topics });
+                let logs = poll_logs(&rpc, vec![filter], state).await?;
+                if logs.is_empty() {
+                    return Ok(None);
+                }
+                let events: Vec<Value> = logs
+                    .iter()
+                    .map(|log| {
+                        json!({
+                            "address": format!("{:?}", log.address),
+                            "topics": log.topics.iter().map(|t| format!("{:?}", t)).collect::<Vec<_>>(),
+                            "data": format!("0x{}", hex::encode(&log.data)),
+                            "block": log.block_number.map(|b| b.as_u64()),
+                            "tx_hash": log.transaction_hash.map(|h| format!("{:?}", h)),
+                        })
+                    })
+                    .collect();
+                Ok(Some(TriggerFire {
+                    summary: format!("{} matching log(s) from {} on {}", events.len(), address, network),
+                    details: json!({ "network": network, "address": address, "logs": truncate(events) }),
+                }))
+            }
+            EventTrigger::AaveHealthFactor { network, address, below, .. } => {
+                let rpc = rpc_for(db, wallet_provider, network)?;
+                let user = match address {
+                    Some(address) => parse_address(address)?,
+                    None => wallet_provider
+                        .and_then(|wp| wp.get_address().parse().ok())
+                        .ok_or_else(|| "No wallet address to watch".to_string())?,
+                };
+                let pool = aave::pool_address(network).ok_or_else(|| format!("Aave V3 is not available on {}", network))?;
+                let account = aave::get_account_data(&rpc, pool, user).await?;
+                // No debt means no health factor (and nothing to liquidate)
+                let Some(health_factor) = account.health_factor else {
+                    state.health_factor_low = Some(false);
+                    state.last_health_factor = None;
+                    return Ok(None);
+                };
+                let (low, fire) = price_condition(health_factor, None, Some(*below), state.health_factor_low);
+                state.health_factor_low = Some(low);
+                state.last_health_factor = Some(health_factor);
+                Ok(fire.then(|| TriggerFire {
+                    summary: format!(
+                        "Aave health factor of {:?} on {} is {:.3} (at or below {})",
+                        user, network, health_factor, below
+                    ),
+                    details: json!({
+                        "network": network,
+                        "address": format!("{:?}", user),
+                        "health_factor": health_factor,
+                        "below": below,
+                        "collateral_usd": account.collateral_usd,
+                        "debt_usd": account.debt_usd,
+                        "liquidation_threshold_bps": account.liquidation_threshold_bps,
+                    }),
+                }))
+            }
+        }
+    }
+}
+
+/// Evaluate a price (or health factor) against thresholds. Returns (condition
+/// met now, fire). Fires only when the condition becomes true, so a value that
+/// stays past the threshold fires once until it comes back.
+fn price_condition(price: f64, above: Option<f64>, below: Option<f64>, was_met: Option<bool>) -> (bool, bool) {
+    let met = above.is_some_and(|a| price >= a) || below.is_some_and(|b| price <= b);
+    (met, met && was_met != Some(true))
+}
+
+fn threshold_text(price: f64, above: Option<f64>, below: Option<f64>) -> String {
+    match (above.filter(|a| price >= *a), below.filter(|b| price <= *b)) {
+        (Some(a), _) => format!("at or above ${}", a),
+        (_, Some(b)) => format!("at or below ${}", b),
+        _ => "threshold".to_string(),
+    }
+}
+
+fn parse_address(address: &str) -> Result<Address, String> {
+    Address::from_str(address.trim()).map_err(|_| format!("Invalid address: {}", address))
+}
+
+/// Token address for a price trigger: an address as-is, or a symbol from tokens.ron
+fn resolve_token_address(network: &str, token: &str) -> Result<String, String> {
+    if token.starts_with("0x") {
+        return parse_address(token).map(|a| format!("{:?}", a));
+    }
+    get_network_tokens(network)
+        .into_iter()
+        .find(|(symbol, _)| symbol.eq_ignore_ascii_case(token))
+        .map(|(_, info)| info.address.to_lowercase())
+        .ok_or_else(|| format!("Unknown token '{}' on {}", token, network))
+}
+
+async fn fetch_price(network: &str, address: &str) -> Result<Option<f64>, String> {
+    let address = address.to_lowercase();
+    match geckoterminal::fetch_token_prices_usd(network, std::slice::from_ref(&address)).await {
+        Ok(prices) if prices.contains_key(&address) => return Ok(prices.get(&address).copied()),
+        Ok(_) => {}
+        Err(e) => log::debug!("[triggers] GeckoTerminal price failed for {}: {}", address, e),
+    }
+    dexscreener::fetch_token_price_usd(network, &address).await
+}
+
+fn rpc_for(
+    db: &Database,
+    wallet_provider: Option<&Arc<dyn WalletProvider>>,
+    network: &str,
+) -> Result<X402EvmRpc, String> {
+    let wallet_provider = wallet_provider.ok_or_else(|| "On-chain triggers need a wallet provider for RPC access".to_string())?;
+    let settings = db
+        .get_bot_settings()
+        .map_err(|e| format!("Failed to load bot settings: {}", e))?;
+    let (url, use_x402) = resolve_rpc_config(&settings.rpc_provider, settings.custom_rpc_endpoints.as_ref(), network)
+        .ok_or_else(|| format!("No RPC endpoint configured for {}", network))?;
+    X402EvmRpc::new_with_wallet_provider(wallet_provider.clone(), network, Some(url), use_x402)
+}
+
+fn transfer_topic() -> H256 {
+    H256::from(keccak256("Transfer(address,address,uint256)"))
+}
+
+/// eth_getLogs filters for transfers to and/or from `watched`
+fn transfer_filters(watched: Address, token: Option<&str>, direction: TransferDirection) -> Result<Vec<Value>, String> {
+    let topic0 = format!("{:?}", transfer_topic());
+    let watched_topic = format!("{:?}", H256::from(watched));
+    let token = token.map(parse_address).transpose()?.map(|t| format!("{:?}", t));
+
+    let filter = |topics: Value| {
+        let mut filter = json!({ "topics": topics });
+        if let Some(token) = &token {
+            filter["address"] = json!(token);
+        }
+        filter
+    };
+    let incoming = filter(json!([topic0, null, watched_topic]));
+    let outgoing = filter(json!([topic0, watched_topic]));
+
+    Ok(match direction {
+        TransferDirection::In => vec![incoming],
+        TransferDirection::Out => vec![outgoing],
+        TransferDirection::Any => vec![incoming, outgoing],
+    })
+}
+
+/// Scan blocks since the last poll with each filter. The first poll only
+/// records the current block.
+async fn poll_logs(rpc: &X402EvmRpc, filters: Vec<Value>, state: &mut TriggerState) -> Result<Vec<FilterLog>, String> {
+    let head = rpc.block_number().await?;
+    let from = match state.last_block {
+        Some(last) => last + 1,
+        None => {
+            state.last_block = Some(head);
+            return Ok(Vec::new());
+        }
+    };
+    if from > head {
+        return Ok(Vec::new());
+    }
+    let from = from.max(head.saturating_sub(MAX_BLOCK_RANGE - 1));
+
+    let mut logs = Vec::new();
+    for mut filter in filters {
+        filter["fromBlock"] = json!(format!("{:#x}", from));
+        filter["toBlock"] = json!(format!("{:#x}", head));
+        logs.extend(rpc.get_logs(filter).await?);
+    }
+    // Self-transfers match both directions
+    logs.sort_by_key(|log| (log.block_number, log.log_index));
+    logs.dedup_by(|a, b| a.transaction_hash == b.transaction_hash && a.log_index == b.log_index);
+
+    state.last_block = Some(head);
+    Ok(logs)
+}
+
+/// ERC-20 Transfer details (ERC-721 transfers, with the id as a 4th topic, are skipped)
+fn decode_transfer(log: &FilterLog) -> Option<Value> {
+    if log.topics.len() != 3 || log.topics[0] != transfer_topic() {
+        return None;
+    }
+    let from = Address::from(log.topics[1]);
+    let to = Address::from(log.topics[2]);
+    let value = U256::from_big_endian(log.data.as_ref());
+    Some(json!({
+        "token": format!("{:?}", log.address),
+        "from": format!("{:?}", from),
+        "to": format!("{:?}", to),
+        "value": value.to_string(),
+        "block": log.block_number.map(|b| b.as_u64()),
+        "tx_hash": log.transaction_hash.map(|h| format!("{:?}", h)),
+    }))
+}
+
+/// Pending (unexecuted) multisig transactions from the Safe Transaction Service
+async fn fetch_safe_pending(network: &str, safe: &str) -> Result<Vec<Value>, String> {
+    let address = parse_address(safe)?;
+    let url = format!(
+        "https://safe-transaction-{}.safe.global/api/v1/safes/{}/multisig-transactions/?executed=false&limit=20",
+        network,
+        to_checksum(&address, None)
+    );
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .user_agent("StarkBot/1.0")
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+    let response: Value = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Safe Transaction Service request failed: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Safe Transaction Service error: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Safe Transaction Service response: {}", e))?;
+
+    let results = response
+        .get("results")
+        .and_then(|r| r.as_array())
+        .cloned()
+        .unwrap_or_default();
+    Ok(results
+        .into_iter()
+        .map(|tx| {
+            json!({
+                "safeTxHash": tx.get("safeTxHash"),
+                "nonce": tx.get("nonce"),
+                "to": tx.get("to"),
+                "value": tx.get("value"),
+                "method": tx.pointer("/dataDecoded/method"),
+                "confirmations": tx.get("confirmations").and_then(|c| c.as_array()).map(|c| c.len()),
+                "confirmationsRequired": tx.get("confirmationsRequired"),
+            })
+        })
+        .collect())
+}
+
+fn truncate(mut events: Vec<Value>) -> Vec<Value> {
+    events.truncate(MAX_EVENTS_IN_PROMPT);
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_triggers() {
+        let price = EventTrigger::parse(r#"{"kind": "price", "token": "WETH", "above": 4000}"#).unwrap();
+        assert_eq!(price.network(), "base");
+
+        let transfer = EventTrigger::parse(
+            r#"{"kind": "erc20_transfer", "network": "mainnet", "address": "0x000000000022D473030F116dDEE9F6B43aC78BA3", "direction": "in"}"#,
+        )
+        .unwrap();
+        assert!(matches!(transfer, EventTrigger::Erc20Transfer { direction: TransferDirection::In, .. }));
+
+        assert!(EventTrigger::parse(r#"{"kind": "price", "token": "WETH"}"#).is_err());
+        assert!(EventTrigger::parse(r#"{"kind": "safe_pending", "safe": "0x1234"}"#).is_err());
+        assert!(EventTrigger::parse(r#"{"kind": "log", "network": "solana", "address": "0x000000000022D473030F116dDEE9F6B43aC78BA3"}"#).is_err());
+
+        let aave = EventTrigger::parse(r#"{"kind": "aave_health_factor", "network": "polygon", "below": 1.3, "auto_repay": true}"#).unwrap();
+        assert!(matches!(aave, EventTrigger::AaveHealthFactor { address: None, auto_repay: true, .. }));
+        assert_eq!(aave.network(), "polygon");
+        assert!(EventTrigger::parse(r#"{"kind": "aave_health_factor", "below": 0.9}"#).is_err());
+    }
+
+    #[test]
+    fn test_price_condition_fires_once_per_crossing() {
+        // First poll already past the threshold fires
+        assert_eq!(price_condition(4100.0, Some(4000.0), None, None), (true, true));
+        // Staying past it does not fire again
+        assert_eq!(price_condition(4200.0, Some(4000.0), None, Some(true)), (true, false));
+        // Falling back re-arms, crossing again fires
+        assert_eq!(price_condition(3900.0, Some(4000.0), None, Some(true)), (false, false));
+        assert_eq!(price_condition(4000.0, Some(4000.0), None, Some(false)), (true, true));
+        // Below threshold
+        assert_eq!(price_condition(0.9, None, Some(0.95), Some(false)), (true, true));
+    }
+
+    #[test]
+    fn test_transfer_filters() {
+        let watched: Address = "0x000000000022D473030F116dDEE9F6B43aC78BA3".parse().unwrap();
+        let filters = transfer_filters(watched, None, TransferDirection::Any).unwrap();
+        assert_eq!(filters.len(), 2);
+        assert_eq!(filters[0]["topics"][0], json!(format!("{:?}", transfer_topic())));
+        assert!(filters[0]["topics"][1].is_null());
+        assert_eq!(filters[0]["topics"][2], json!(format!("{:?}", H256::from(watched))));
+        assert!(filters[0].get("address").is_none());
+
+        let filters = transfer_filters(watched, Some("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"), TransferDirection::Out).unwrap();
+        assert_eq!(filters.len(), 1);
+        assert_eq!(filters[0]["topics"][1], json!(format!("{:?}", H256::from(watched))));
+        assert_eq!(filters[0]["address"], json!("0x833589fcd6edb6e08f4c7c32d4f71b54bda02913"));
+    }
+}
@@ -0,0 +1,286 @@
+//! Contract integrations bundled with skills
+//!
+//! A skill package can ship `abis/*.json`, a `web3_presets.ron` and `validators/*.ron`.
+//! Everything is registered under the skill's namespace (`<skill>/<name>`) so it can never
+//! shadow the files in `abis/` or `config/`, and is removed again when the skill is uninstalled.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::skills::types::{DbSkillResource, SkillResourceKind};
+use crate::skills::zip_parser::ParsedResource;
+use crate::tool_validators::ron::RonValidator;
+use crate::tool_validators::{ToolValidator, ValidatorRegistry};
+use crate::tools::presets::{self, Web3Preset};
+use crate::web3;
+
+/// Namespaced name for something a skill registers
+pub fn namespaced(skill: &str, name: &str) -> String {
+    format!("{}/{}", skill, name)
+}
+
+/// A skill's bundled resources, parsed and validated but not yet registered
+pub struct SkillBundle {
+    skill: String,
+    /// Namespaced ABI name -> ABI file JSON
+    abis: HashMap<String, String>,
+    /// Namespaced preset name -> preset (with skill ABIs already namespaced)
+    presets: HashMap<String, Web3Preset>,
+    validators: Vec<RonValidator>,
+}
+
+impl SkillBundle {
+    /// Parse and validate a skill's resources. Fails on any invalid file and on
+    /// presets whose name collides with a preset from config/web3_presets.ron.
+    pub fn prepare(skill: &str, resources: &[ParsedResource]) -> Result<Self, String> {
+        let mut bundle = SkillBundle {
+            skill: skill.to_string(),
+            abis: HashMap::new(),
+            presets: HashMap::new(),
+            validators: Vec::new(),
+        };
+        if resources.is_empty() {
+            return Ok(bundle);
+        }
+        if skill.contains('/') {
+            return Err(format!("Skill name '{}' must not contain '/' to bundle resources", skill));
+        }
+
+        for resource in resources.iter().filter(|r| r.kind == SkillResourceKind::Abi) {
+            let abi_file = web3::parse_abi_file(&resource.name, &resource.content)?;
+            web3::parse_abi(&abi_file).map_err(|e| format!("ABI '{}': {}", resource.name, e))?;
+            bundle
+                .abis
+                .insert(namespaced(skill, &resource.name), resource.content.clone());
+        }
+
+        for resource in resources.iter().filter(|r| r.kind == SkillResourceKind::Web3Presets) {
+            let parsed: HashMap<String, Web3Preset> = ron::from_str(&resource.content)
+                .map_err(|e| format!("Failed to parse web3_presets.ron: {}", e))?;
+            bundle.add_presets(parsed)?;
+        }
+
+        for resource in resources.iter().filter(|r| r.kind == SkillResourceKind::Validator) {
+            let validator = RonValidator::from_str(&resource.content)
+                .map_err(|e| format!("Validator '{}': {}", resource.name, e))?
+                .with_id_prefix(skill);
+            if bundle.validators.iter().any(|v| v.id() == validator.id()) {
+                return Err(format!("Duplicate validator id '{}'", validator.id()));
+            }
+            bundle.validators.push(validator);
+        }
+
+        Ok(bundle)
+    }
+
+    /// Parse a skill's resources as stored in the database
+    pub fn from_db(skill: &str, resources: &[DbSkillResource]) -> Result<Self, String> {
+        let parsed: Vec<ParsedResource> = resources
+            .iter()
+            .map(|r| ParsedResource {
+                kind: r.kind,
+                name: r.name.clone(),
+                content: r.content.clone(),
+            })
+            .collect();
+        Self::prepare(skill, &parsed)
+    }
+
+    fn add_presets(&mut self, parsed: HashMap<String, Web3Preset>) -> Result<(), String> {
+        let config_presets = presets::list_config_web3_presets();
+        let mut conflicts: Vec<&str> = parsed
+            .keys()
+            .filter(|name| config_presets.contains(name))
+            .map(|name| name.as_str())
+            .collect();
+        if !conflicts.is_empty() {
+            conflicts.sort();
+            return Err(format!(
+                "Skill '{}' defines presets that conflict with bundled presets: {}",
+                self.skill,
+                conflicts.join(", ")
+            ));
+        }
+
+        let mut namespaced_presets = HashMap::new();
+        for (name, mut preset) in parsed {
+            if name.contains('/') {
+                return Err(format!("Preset name '{}' must not contain '/'", name));
+            }
+            // Presets refer to the skill's own ABIs by their short name
+            let own_abi = namespaced(&self.skill, &preset.abi);
+            if self.abis.contains_key(&own_abi) {
+                preset.abi = own_abi;
+            }
+            namespaced_presets.insert(namespaced(&self.skill, &name), preset);
+        }
+
+        let abis_dir = web3::default_abis_dir();
+        presets::validate_web3_presets_with(&namespaced_presets, |abi| {
+            if let Some(content) = self.abis.get(abi) {
+                web3::parse_abi_file(abi, content).map(Some)
+            } else if abis_dir.exists() {
+                web3::load_abi(&abis_dir, abi).map(Some)
+            } else {
+                Ok(None)
+            }
+        })?;
+
+        self.presets.extend(namespaced_presets);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.abis.is_empty() && self.presets.is_empty() && self.validators.is_empty()
+    }
+
+    /// Make the bundle live, replacing anything the skill registered before
+    pub fn register(self, validator_registry: Option<&ValidatorRegistry>) {
+        if self.is_empty() {
+            unregister(&self.skill, validator_registry);
+            return;
+        }
+
+        log::info!(
+            "[skills] Registering '{}' bundle: {} ABIs, {} presets, {} validators",
+            self.skill,
+            self.abis.len(),
+            self.presets.len(),
+            self.validators.len()
+        );
+        web3::register_skill_abis(&self.skill, self.abis);
+        presets::register_skill_web3_presets(&self.skill, self.presets);
+        if let Some(registry) = validator_registry {
+            registry.set_skill_validators(
+                &self.skill,
+                self.validators
+                    .into_iter()
+                    .map(|v| Arc::new(v) as Arc<dyn ToolValidator>)
+                    .collect(),
+            );
+        }
+    }
+}
+
+/// Remove everything a skill registered
+pub fn unregister(skill: &str, validator_registry: Option<&ValidatorRegistry>) {
+    web3::unregister_skill_abis(skill);
+    presets::unregister_skill_web3_presets(skill);
+    if let Some(registry) = validator_registry {
+        registry.remove_skill_validators(skill);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_ABI: &str = r#"{
+        "name": "Vault",
+        "abi": [{
+            "name": "deposit",
+            "type": "function",
+            "stateMutability": "nonpayable",
+            "inputs": [{"name": "amount", "type": "uint256"}],
+            "outputs": []
+        }]
+    }"#;
+
+    const VAULT_PRESETS: &str = r#"{
+        "vault_deposit": (
+            abi: "vault",
+            contracts: { "base": "0x4200000000000000000000000000000000000006" },
+            function: "deposit",
+            params_registers: ["amount"],
+            description: "Deposit into the vault",
+        ),
+    }"#;
+
+    const LIMIT_VALIDATOR: &str = r#"ValidatorDef(
+        id: "limit",
+        name: "Vault limit",
+        applies_to: ["web3_preset_function_call"],
+        priority: High,
+        rules: [],
+        default: Allow,
+    )"#;
+
+    fn resource(kind: SkillResourceKind, name: &str, content: &str) -> ParsedResource {
+        ParsedResource {
+            kind,
+            name: name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_prepare_namespaces_resources() {
+        let bundle = SkillBundle::prepare(
+            "vault",
+            &[
+                resource(SkillResourceKind::Abi, "vault", VAULT_ABI),
+                resource(SkillResourceKind::Web3Presets, "web3_presets", VAULT_PRESETS),
+                resource(SkillResourceKind::Validator, "limit", LIMIT_VALIDATOR),
+            ],
+        )
+        .unwrap();
+
+        assert!(bundle.abis.contains_key("vault/vault"));
+        assert_eq!(bundle.presets["vault/vault_deposit"].abi, "vault/vault");
+        assert_eq!(bundle.validators[0].id(), "vault/limit");
+    }
+
+    #[test]
+    fn test_preset_conflict_rejected() {
+        let taken = presets::list_config_web3_presets().into_iter().next().unwrap();
+        let conflicting = VAULT_PRESETS.replace("vault_deposit", &taken);
+        let err = SkillBundle::prepare(
+            "vault",
+            &[
+                resource(SkillResourceKind::Abi, "vault", VAULT_ABI),
+                resource(SkillResourceKind::Web3Presets, "web3_presets", &conflicting),
+            ],
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("conflict"), "{}", err);
+        assert!(err.contains(&taken));
+    }
+
+    #[test]
+    fn test_preset_with_unknown_function_rejected() {
+        let presets = VAULT_PRESETS.replace("function: \"deposit\"", "function: \"withdraw\"");
+        assert!(SkillBundle::prepare(
+            "vault",
+            &[
+                resource(SkillResourceKind::Abi, "vault", VAULT_ABI),
+                resource(SkillResourceKind::Web3Presets, "web3_presets", &presets),
+            ],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_register_and_unregister() {
+        let registry = ValidatorRegistry::new();
+        SkillBundle::prepare(
+            "vault_test_skill",
+            &[
+                resource(SkillResourceKind::Abi, "vault", VAULT_ABI),
+                resource(SkillResourceKind::Web3Presets, "web3_presets", VAULT_PRESETS),
+                resource(SkillResourceKind::Validator, "limit", LIMIT_VALIDATOR),
+            ],
+        )
+        .unwrap()
+        .register(Some(&registry));
+
+        assert!(presets::get_web3_preset("vault_test_skill/vault_deposit").is_some());
+        assert!(web3::load_abi(&web3::default_abis_dir(), "vault_test_skill/vault").is_ok());
+        assert!(registry.get("vault_test_skill/limit").is_some());
+
+        unregister("vault_test_skill", Some(&registry));
+        assert!(presets::get_web3_preset("vault_test_skill/vault_deposit").is_none());
+        assert!(web3::load_abi(&web3::default_abis_dir(), "vault_test_skill/vault").is_err());
+        assert!(registry.is_empty());
+    }
+}
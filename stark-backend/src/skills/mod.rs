@@ -1,3 +1,4 @@
+pub mod bundle;
 pub mod loader;
 pub mod registry;
 pub mod types;
@@ -5,5 +6,5 @@ pub mod zip_parser;
 
 pub use loader::{load_skill_from_file, load_skills_from_directory, parse_skill_file};
 pub use registry::{create_default_registry, SkillRegistry};
-pub use types::{DbSkill, DbSkillScript, InstalledSkill, Skill, SkillArgument, SkillMetadata, SkillSource};
+pub use types::{DbSkill, DbSkillResource, DbSkillScript, InstalledSkill, Skill, SkillArgument, SkillMetadata, SkillResourceKind, SkillSource};
 pub use zip_parser::{parse_skill_md, parse_skill_zip, ParsedScript, ParsedSkill};
@@ -1,6 +1,8 @@
 use crate::db::Database;
-use crate::skills::types::{DbSkill, DbSkillScript, Skill, SkillSource};
-use crate::skills::zip_parser::{parse_skill_md, parse_skill_zip, ParsedSkill};
+use crate::skills::bundle::{self, SkillBundle};
+use crate::skills::types::{DbSkill, DbSkillResource, DbSkillScript, Skill, SkillSource};
+use crate::skills::zip_parser::{parse_skill_md, parse_skill_zip, ParsedResource, ParsedSkill};
+use crate::tool_validators::ValidatorRegistry;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -12,6 +14,8 @@ pub struct SkillRegistry {
     bundled_path: Option<PathBuf>,
     managed_path: Option<PathBuf>,
     workspace_path: Option<PathBuf>,
+    /// Validator registry that skill-bundled validators are added to
+    validator_registry: Option<Arc<ValidatorRegistry>>,
 }
 
 impl SkillRegistry {
@@ -21,6 +25,7 @@ impl SkillRegistry {
             bundled_path: None,
             managed_path: None,
             workspace_path: None,
+            validator_registry: None,
         }
     }
 
@@ -36,9 +41,16 @@ impl SkillRegistry {
             bundled_path,
             managed_path,
             workspace_path,
+            validator_registry: None,
         }
     }
 
+    /// Register validators bundled with skills into this validator registry
+    pub fn with_validator_registry(mut self, registry: Arc<ValidatorRegistry>) -> Self {
+        self.validator_registry = Some(registry);
+        self
+    }
+
     /// Get a skill by name
     pub fn get(&self, name: &str) -> Option<Skill> {
         match self.db.get_skill(name) {
@@ -119,6 +131,7 @@ impl SkillRegistry {
             tags: metadata.tags,
             subagent_type: metadata.subagent_type,
             scripts: Vec::new(),
+            resources: Vec::new(),
         };
 
         self.create_skill_from_parsed_force(parsed)
@@ -142,6 +155,7 @@ impl SkillRegistry {
             tags: metadata.tags,
             subagent_type: metadata.subagent_type,
             scripts: Vec::new(), // No scripts for plain markdown
+            resources: Vec::new(),
         };
 
         self.create_skill_from_parsed(parsed)
@@ -149,6 +163,8 @@ impl SkillRegistry {
 
     /// Create a skill from parsed skill data
     pub fn create_skill_from_parsed(&self, parsed: ParsedSkill) -> Result<DbSkill, String> {
+        // Validate bundled ABIs/presets/validators before touching the database
+        let bundle = SkillBundle::prepare(&parsed.name, &parsed.resources)?;
         let now = chrono::Utc::now().to_rfc3339();
 
         let db_skill = DbSkill {
@@ -189,13 +205,18 @@ impl SkillRegistry {
         }
 
         // Return the created skill
-        self.db.get_skill(&parsed.name)
+        let created = self.db.get_skill(&parsed.name)
             .map_err(|e| format!("Failed to retrieve created skill: {}", e))?
-            .ok_or_else(|| "Skill not found after creation".to_string())
+            .ok_or_else(|| "Skill not found after creation".to_string())?;
+
+        self.install_bundle(&created, &parsed.resources, bundle)?;
+        Ok(created)
     }
 
     /// Create a skill from parsed skill data, bypassing version checks (force update)
     pub fn create_skill_from_parsed_force(&self, parsed: ParsedSkill) -> Result<DbSkill, String> {
+        // Validate bundled ABIs/presets/validators before touching the database
+        let bundle = SkillBundle::prepare(&parsed.name, &parsed.resources)?;
         let now = chrono::Utc::now().to_rfc3339();
 
         let db_skill = DbSkill {
@@ -236,17 +257,89 @@ impl SkillRegistry {
         }
 
         // Return the created skill
-        self.db.get_skill(&parsed.name)
+        let created = self.db.get_skill(&parsed.name)
             .map_err(|e| format!("Failed to retrieve created skill: {}", e))?
-            .ok_or_else(|| "Skill not found after creation".to_string())
+            .ok_or_else(|| "Skill not found after creation".to_string())?;
+
+        self.install_bundle(&created, &parsed.resources, bundle)?;
+        Ok(created)
+    }
+
+    /// Store a skill's bundled resources, replacing the previous set, and register them
+    fn install_bundle(
+        &self,
+        skill: &DbSkill,
+        resources: &[ParsedResource],
+        bundle: SkillBundle,
+    ) -> Result<(), String> {
+        let skill_id = skill.id.ok_or_else(|| "Created skill has no id".to_string())?;
+        self.db.delete_skill_resources(skill_id)
+            .map_err(|e| format!("Failed to clear skill resources: {}", e))?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        for resource in resources {
+            let db_resource = DbSkillResource {
+                id: None,
+                skill_id,
+                kind: resource.kind,
+                name: resource.name.clone(),
+                content: resource.content.clone(),
+                created_at: now.clone(),
+            };
+            self.db.create_skill_resource(&db_resource)
+                .map_err(|e| format!("Failed to create skill resource: {}", e))?;
+        }
+
+        bundle.register(self.validator_registry.as_deref());
+        Ok(())
+    }
+
+    /// Register the ABIs, presets and validators of every installed skill (called at startup)
+    pub fn load_bundles(&self) -> usize {
+        let names = match self.db.list_skills_with_resources() {
+            Ok(names) => names,
+            Err(e) => {
+                log::error!("Failed to list skill resources: {}", e);
+                return 0;
+            }
+        };
+
+        let mut loaded = 0;
+        for name in names {
+            let resources = self.get_skill_resources(&name);
+            match SkillBundle::from_db(&name, &resources) {
+                Ok(bundle) => {
+                    bundle.register(self.validator_registry.as_deref());
+                    loaded += 1;
+                }
+                Err(e) => log::warn!("Failed to load resources bundled with skill '{}': {}", name, e),
+            }
+        }
+        loaded
     }
 
-    /// Delete a skill and its scripts
+    /// Delete a skill, its scripts and its bundled resources
     pub fn delete_skill(&self, name: &str) -> Result<bool, String> {
+        if let Ok(Some(DbSkill { id: Some(skill_id), .. })) = self.db.get_skill(name) {
+            bundle::unregister(name, self.validator_registry.as_deref());
+            self.db.delete_skill_resources(skill_id)
+                .map_err(|e| format!("Failed to delete skill resources: {}", e))?;
+        }
         self.db.delete_skill(name)
             .map_err(|e| format!("Failed to delete skill: {}", e))
     }
 
+    /// Get ABIs, presets and validators bundled with a skill
+    pub fn get_skill_resources(&self, skill_name: &str) -> Vec<DbSkillResource> {
+        match self.db.get_skill_resources_by_name(skill_name) {
+            Ok(resources) => resources,
+            Err(e) => {
+                log::error!("Failed to get skill resources: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Get scripts for a skill
     pub fn get_skill_scripts(&self, skill_name: &str) -> Vec<DbSkillScript> {
         match self.db.get_skill_scripts_by_name(skill_name) {
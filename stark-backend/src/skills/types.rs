@@ -229,6 +229,48 @@ pub struct DbSkillScript {
     pub created_at: String,
 }
 
+/// Kind of contract-integration file bundled in a skill package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillResourceKind {
+    /// `abis/<name>.json`
+    Abi,
+    /// `web3_presets.ron`
+    Web3Presets,
+    /// `validators/<name>.ron`
+    Validator,
+}
+
+impl SkillResourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkillResourceKind::Abi => "abi",
+            SkillResourceKind::Web3Presets => "web3_presets",
+            SkillResourceKind::Validator => "validator",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "abi" => Some(SkillResourceKind::Abi),
+            "web3_presets" => Some(SkillResourceKind::Web3Presets),
+            "validator" => Some(SkillResourceKind::Validator),
+            _ => None,
+        }
+    }
+}
+
+/// Database record for ABIs, presets and validators bundled with a skill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbSkillResource {
+    pub id: Option<i64>,
+    pub skill_id: i64,
+    pub kind: SkillResourceKind,
+    pub name: String,
+    pub content: String,
+    pub created_at: String,
+}
+
 /// Legacy database record for installed skills (deprecated)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledSkill {
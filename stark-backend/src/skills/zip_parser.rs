@@ -1,4 +1,4 @@
-use crate::skills::types::{SkillArgument, SkillMetadata};
+use crate::skills::types::{SkillArgument, SkillMetadata, SkillResourceKind};
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
 use zip::ZipArchive;
@@ -19,6 +19,8 @@ pub struct ParsedSkill {
     pub tags: Vec<String>,
     pub subagent_type: Option<String>,
     pub scripts: Vec<ParsedScript>,
+    /// ABIs, web3 presets and validators bundled with the skill
+    pub resources: Vec<ParsedResource>,
 }
 
 /// Parsed script from ZIP file
@@ -29,6 +31,36 @@ pub struct ParsedScript {
     pub language: String,
 }
 
+/// Contract-integration file from a skill ZIP (`abis/*.json`, `web3_presets.ron`, `validators/*.ron`)
+#[derive(Debug, Clone)]
+pub struct ParsedResource {
+    pub kind: SkillResourceKind,
+    /// File name without extension
+    pub name: String,
+    pub content: String,
+}
+
+impl ParsedResource {
+    /// Classify a path relative to the skill root, returning the kind and resource name
+    pub fn classify(relative: &str) -> Option<(SkillResourceKind, String)> {
+        let stem = |file: &str, ext: &str| {
+            file.strip_suffix(ext)
+                .filter(|s| !s.is_empty() && !s.contains('/'))
+                .map(|s| s.to_string())
+        };
+        if relative == "web3_presets.ron" {
+            return Some((SkillResourceKind::Web3Presets, "web3_presets".to_string()));
+        }
+        if let Some(file) = relative.strip_prefix("abis/") {
+            return stem(file, ".json").map(|n| (SkillResourceKind::Abi, n));
+        }
+        if let Some(file) = relative.strip_prefix("validators/") {
+            return stem(file, ".ron").map(|n| (SkillResourceKind::Validator, n));
+        }
+        None
+    }
+}
+
 impl ParsedScript {
     /// Determine language from file extension
     pub fn detect_language(filename: &str) -> String {
@@ -51,6 +83,7 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
         .map_err(|e| format!("Failed to read ZIP file: {}", e))?;
 
     let mut scripts: Vec<ParsedScript> = Vec::new();
+    let mut resources: Vec<ParsedResource> = Vec::new();
     let mut skill_md_path: Option<String> = None;
 
     // First pass: find SKILL.md and collect info about structure
@@ -68,7 +101,7 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
         // Normalize path (handle nested folder in ZIP)
         let normalized = normalize_zip_path(&name);
 
-        if normalized.eq_ignore_ascii_case("skill.md") || normalized.ends_with("/skill.md") {
+        if normalized.eq_ignore_ascii_case("skill.md") || normalized.to_lowercase().ends_with("/skill.md") {
             skill_md_path = Some(name.clone());
         }
     }
@@ -86,7 +119,7 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
     };
     let (metadata, body) = parse_skill_md(&skill_md)?;
 
-    // Third pass: collect scripts
+    // Third pass: collect scripts and bundled resources
     let base_dir = skill_md_path.as_ref()
         .and_then(|p| p.rsplit('/').nth(1))
         .unwrap_or("");
@@ -111,6 +144,25 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
             normalized.starts_with("scripts/")
         };
 
+        let relative = if base_dir.is_empty() {
+            normalized.as_str()
+        } else {
+            normalized
+                .strip_prefix(&format!("{}/", base_dir))
+                .unwrap_or(&normalized)
+        };
+        if let Some((kind, resource_name)) = ParsedResource::classify(relative) {
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .map_err(|e| format!("Failed to read {}: {}", relative, e))?;
+            resources.push(ParsedResource {
+                kind,
+                name: resource_name,
+                content,
+            });
+            continue;
+        }
+
         if is_script {
             // Extract script name (last component of path)
             let script_name = name.rsplit('/').next().unwrap_or(&name);
@@ -147,6 +199,7 @@ pub fn parse_skill_zip(data: &[u8]) -> Result<ParsedSkill, String> {
         tags: metadata.tags,
         subagent_type: metadata.subagent_type,
         scripts,
+        resources,
     })
 }
 
@@ -350,6 +403,49 @@ You are a code reviewer. Review the code at {{path}} and provide feedback.
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_zip_with_resources() {
+        use std::io::Write;
+
+        let mut buf = Vec::new();
+        {
+            let mut zip = zip::ZipWriter::new(Cursor::new(&mut buf));
+            let options = zip::write::FileOptions::default();
+            let files = [
+                ("vault/SKILL.md", "---\nname: vault\ndescription: Vault deposits\n---\nDeposit."),
+                ("vault/abis/vault.json", "{\"name\": \"Vault\", \"abi\": []}"),
+                ("vault/web3_presets.ron", "{}"),
+                ("vault/validators/limit.ron", "()"),
+                ("vault/scripts/check.py", "print(1)"),
+                ("vault/abis/nested/skip.json", "{}"),
+            ];
+            for (path, content) in files {
+                zip.start_file(path, options).unwrap();
+                zip.write_all(content.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let parsed = parse_skill_zip(&buf).unwrap();
+        assert_eq!(parsed.name, "vault");
+        assert_eq!(parsed.scripts.len(), 1);
+
+        let mut found: Vec<_> = parsed
+            .resources
+            .iter()
+            .map(|r| (r.kind, r.name.as_str()))
+            .collect();
+        found.sort_by_key(|(kind, _)| kind.as_str());
+        assert_eq!(
+            found,
+            vec![
+                (SkillResourceKind::Abi, "vault"),
+                (SkillResourceKind::Validator, "limit"),
+                (SkillResourceKind::Web3Presets, "web3_presets"),
+            ]
+        );
+    }
+
     #[test]
     fn test_detect_language() {
         assert_eq!(ParsedScript::detect_language("test.py"), "python");
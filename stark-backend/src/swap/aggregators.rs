@@ -0,0 +1,230 @@
+//! Aggregator quotes: 0x (AllowanceHolder, via DeFi Relay with x402 payment) and KyberSwap
+
+use super::{min_buy_amount, price_impact_pct, Allowance, SwapQuote, SwapRequest, SwapTx};
+use crate::tools::presets;
+use crate::tools::rpc_config::Network;
+use crate::wallet::WalletProvider;
+use crate::x402::X402Client;
+use ethers::types::{Address, U256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Used when the `swap_quote` x402_fetch preset is not configured
+const ZERO_X_QUOTE_URL: &str = "https://quoter.defirelay.com/swap/allowance-holder/quote";
+
+const KYBERSWAP_API_URL: &str = "https://aggregator-api.kyberswap.com";
+const KYBERSWAP_CLIENT_ID: &str = "starkbot";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ZeroXQuote {
+    buy_amount: Option<String>,
+    min_buy_amount: Option<String>,
+    transaction: Option<ZeroXTransaction>,
+    #[serde(default)]
+    liquidity_available: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ZeroXTransaction {
+    to: String,
+    data: String,
+    #[serde(default)]
+    value: Option<String>,
+    #[serde(default)]
+    gas: Option<String>,
+}
+
+/// Quote from 0x through the DeFi Relay quoter (AllowanceHolder flow)
+pub async fn zero_x_quote(
+    request: &SwapRequest,
+    wallet_provider: &Arc<dyn WalletProvider>,
+) -> Result<SwapQuote, String> {
+    let base_url = presets::get_fetch_preset("swap_quote")
+        .map(|p| p.base_url)
+        .unwrap_or_else(|| ZERO_X_QUOTE_URL.to_string());
+    let url = format!(
+        "{}?chainId={}&sellToken={:?}&buyToken={:?}&sellAmount={}&taker={:?}&slippageBps={}",
+        base_url,
+        request.network.chain_id(),
+        request.sell.address,
+        request.buy.address,
+        request.sell_amount,
+        request.taker,
+        request.slippage_bps,
+    );
+
+    let client = X402Client::new(wallet_provider.clone())?;
+    let response = client.get_with_payment(&url).await?.response;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
+    if !status.is_success() {
+        return Err(format!("HTTP {}: {}", status, body));
+    }
+
+    let quote: ZeroXQuote = serde_json::from_str(&body).map_err(|e| format!("Invalid quote response: {}", e))?;
+    if quote.liquidity_available == Some(false) {
+        return Err("No liquidity available".to_string());
+    }
+    let tx = quote.transaction.ok_or_else(|| "Quote has no transaction".to_string())?;
+    let buy_amount = parse_amount(quote.buy_amount.as_deref(), "buyAmount")?;
+    // Keep whichever minimum is stricter: the quoter's or our own slippage bound
+    let local_min = min_buy_amount(buy_amount, request.slippage_bps);
+    let min_buy = quote
+        .min_buy_amount
+        .as_deref()
+        .and_then(|s| U256::from_dec_str(s).ok())
+        .map_or(local_min, |m| m.max(local_min));
+
+    let to: Address = tx.to.parse().map_err(|_| format!("Invalid transaction target: {}", tx.to))?;
+    Ok(SwapQuote {
+        source: "0x".to_string(),
+        buy_amount,
+        min_buy_amount: min_buy,
+        price_impact_pct: None,
+        gas_estimate: tx.gas.as_deref().and_then(|g| g.parse().ok()),
+        allowance: if request.sell.is_native() {
+            Allowance::None
+        } else {
+            // AllowanceHolder pulls the sell token itself
+            Allowance::Erc20 { spender: to }
+        },
+        tx: SwapTx {
+            to,
+            data: decode_hex(&tx.data)?,
+            value: parse_amount(tx.value.as_deref().or(Some("0")), "value")?,
+        },
+    })
+}
+
+/// KyberSwap chain slug
+fn kyberswap_chain(network: &Network) -> &'static str {
+    match network {
+        Network::Base => "base",
+        Network::Mainnet => "ethereum",
+        Network::Polygon => "polygon",
+    }
+}
+
+/// Quote from KyberSwap: fetch the route, then build its transaction with our slippage
+pub async fn kyberswap_quote(request: &SwapRequest) -> Result<SwapQuote, String> {
+    let chain = kyberswap_chain(&request.network);
+    let client = reqwest::Client::new();
+
+    let routes_url = format!(
+        "{}/{}/api/v1/routes?tokenIn={:?}&tokenOut={:?}&amountIn={}",
+        KYBERSWAP_API_URL, chain, request.sell.address, request.buy.address, request.sell_amount
+    );
+    let routes: Value = client
+        .get(&routes_url)
+        .header("x-client-id", KYBERSWAP_CLIENT_ID)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid routes response: {}", e))?;
+    let route_summary = routes
+        .pointer("/data/routeSummary")
+        .cloned()
+        .ok_or_else(|| kyberswap_error(&routes))?;
+
+    let build_url = format!("{}/{}/api/v1/route/build", KYBERSWAP_API_URL, chain);
+    let taker = format!("{:?}", request.taker);
+    let built: Value = client
+        .post(&build_url)
+        .header("x-client-id", KYBERSWAP_CLIENT_ID)
+        .json(&json!({
+            "routeSummary": route_summary,
+            "sender": taker,
+            "recipient": taker,
+            "slippageTolerance": request.slippage_bps,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid build response: {}", e))?;
+    let data = built.get("data").ok_or_else(|| kyberswap_error(&built))?;
+
+    let buy_amount = parse_amount(data.get("amountOut").and_then(|v| v.as_str()), "amountOut")?;
+    let router = data
+        .get("routerAddress")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Build response has no routerAddress".to_string())?;
+    let to: Address = router.parse().map_err(|_| format!("Invalid router address: {}", router))?;
+
+    let usd = |key: &str| {
+        route_summary
+            .get(key)
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<f64>().ok())
+    };
+    let price_impact = match (usd("amountInUsd"), usd("amountOutUsd")) {
+        (Some(usd_in), Some(usd_out)) => price_impact_pct(usd_in, usd_out),
+        _ => None,
+    };
+
+    Ok(SwapQuote {
+        source: "kyberswap".to_string(),
+        buy_amount,
+        min_buy_amount: min_buy_amount(buy_amount, request.slippage_bps),
+        price_impact_pct: price_impact,
+        gas_estimate: data
+            .get("gas")
+            .and_then(|v| v.as_str())
+            .and_then(|g| g.parse().ok()),
+        allowance: if request.sell.is_native() {
+            Allowance::None
+        } else {
+            Allowance::Erc20 { spender: to }
+        },
+        tx: SwapTx {
+            to,
+            data: decode_hex(data.get("data").and_then(|v| v.as_str()).unwrap_or_default())?,
+            value: parse_amount(
+                data.get("transactionValue").and_then(|v| v.as_str()).or(Some("0")),
+                "transactionValue",
+            )?,
+        },
+    })
+}
+
+fn kyberswap_error(response: &Value) -> String {
+    response
+        .get("message")
+        .and_then(|v| v.as_str())
+        .map(|m| format!("KyberSwap error: {}", m))
+        .unwrap_or_else(|| "No route found".to_string())
+}
+
+fn parse_amount(value: Option<&str>, field: &str) -> Result<U256, String> {
+    let value = value.ok_or_else(|| format!("Quote has no {}", field))?;
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    };
+    parsed.ok_or_else(|| format!("Invalid {}: {}", field, value))
+}
+
+fn decode_hex(data: &str) -> Result<Vec<u8>, String> {
+    if data.is_empty() {
+        return Err("Quote has no calldata".to_string());
+    }
+    hex::decode(data.strip_prefix("0x").unwrap_or(data)).map_err(|e| format!("Invalid calldata: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount(Some("1000"), "x").unwrap(), U256::from(1000u64));
+        assert_eq!(parse_amount(Some("0x10"), "x").unwrap(), U256::from(16u64));
+        assert!(parse_amount(None, "x").is_err());
+        assert!(parse_amount(Some("abc"), "x").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Allowance checks for swap quotes
+//!
+//! Approvals are queued one at a time (each queued tx takes its nonce from the
+//! chain), so this returns only the next missing approval.
+
+use super::{abi_address, call_abi, value_to_u256, Allowance, SwapRequest};
+use crate::x402::X402EvmRpc;
+use chrono::Utc;
+use ethers::types::U256;
+use serde_json::{json, Value};
+
+/// Permit2 allowances granted for swaps expire after this many days
+const PERMIT2_EXPIRATION_DAYS: i64 = 30;
+
+/// An approval transaction to queue through `execute_resolved_call`
+#[derive(Debug, Clone)]
+pub struct ApprovalCall {
+    pub label: String,
+    pub abi: &'static str,
+    pub contract: String,
+    pub function: &'static str,
+    pub params: Vec<Value>,
+}
+
+fn max_uint(bits: usize) -> String {
+    if bits >= 256 {
+        return U256::MAX.to_string();
+    }
+    ((U256::one() << bits) - U256::one()).to_string()
+}
+
+/// The next approval the quote still needs, or None if everything is in place
+pub async fn next_approval(
+    rpc: &X402EvmRpc,
+    request: &SwapRequest,
+    allowance: &Allowance,
+) -> Result<Option<ApprovalCall>, String> {
+    let (spender, via_permit2) = match allowance {
+        Allowance::None => return Ok(None),
+        Allowance::Erc20 { spender } => (*spender, false),
+        Allowance::Permit2 { spender } => (*spender, true),
+    };
+    let token = request.sell.address;
+    let owner = request.taker;
+
+    // The ERC-20 allowance goes to Permit2 or directly to the spender
+    let erc20_spender = if via_permit2 {
+        abi_address("permit2", &request.network)?
+    } else {
+        spender
+    };
+    let current = call_abi(
+        rpc,
+        "erc20",
+        token,
+        "allowance",
+        &[json!(format!("{:?}", owner)), json!(format!("{:?}", erc20_spender))],
+    )
+    .await?;
+    if value_to_u256(&current)? < request.sell_amount {
+        return Ok(Some(ApprovalCall {
+            label: format!(
+                "Approve {} for {}",
+                request.sell.symbol,
+                if via_permit2 { "Permit2".to_string() } else { format!("{:?}", spender) }
+            ),
+            abi: "erc20",
+            contract: format!("{:?}", token),
+            function: "approve",
+            params: vec![json!(format!("{:?}", erc20_spender)), json!(max_uint(256))],
+        }));
+    }
+    if !via_permit2 {
+        return Ok(None);
+    }
+
+    let permit2 = erc20_spender;
+    let granted = call_abi(
+        rpc,
+        "permit2",
+        permit2,
+        "allowance",
+        &[
+            json!(format!("{:?}", owner)),
+            json!(format!("{:?}", token)),
+            json!(format!("{:?}", spender)),
+        ],
+    )
+    .await?;
+    let fields = granted.as_array().cloned().unwrap_or_default();
+    let amount = fields.first().map(value_to_u256).transpose()?.unwrap_or_default();
+    let expiration = fields.get(1).map(value_to_u256).transpose()?.unwrap_or_default();
+    if amount >= request.sell_amount && expiration > U256::from(Utc::now().timestamp()) {
+        return Ok(None);
+    }
+
+    let expires = Utc::now().timestamp() + PERMIT2_EXPIRATION_DAYS * 24 * 60 * 60;
+    Ok(Some(ApprovalCall {
+        label: format!("Permit2 allowance of {} for the Uniswap Universal Router", request.sell.symbol),
+        abi: "permit2",
+        contract: format!("{:?}", permit2),
+        function: "approve",
+        params: vec![
+            json!(format!("{:?}", token)),
+            json!(format!("{:?}", spender)),
+            json!(max_uint(160)),
+            json!(expires.to_string()),
+        ],
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_uint() {
+        assert_eq!(max_uint(8), "255");
+        assert_eq!(max_uint(256), U256::MAX.to_string());
+        assert_eq!(max_uint(160), "1461501637330902918203684832716283019655932542975");
+    }
+}
@@ -0,0 +1,277 @@
+//! Token swap quoting and routing
+//!
+//! Quotes come from DEX aggregators (0x via DeFi Relay, KyberSwap) and directly
+//! from the Uniswap V3/V4 pools listed in `config/uniswap_pools.ron`. Every
+//! quote carries the transaction that executes it, the allowance it needs
+//! (a plain ERC-20 approval or Permit2 for the Universal Router) and the minimum
+//! output derived from the requested slippage.
+
+mod aggregators;
+mod approvals;
+mod uniswap;
+
+pub use approvals::next_approval;
+pub use uniswap::load_uniswap_pools;
+
+use crate::tools::builtin::cryptocurrency::token_lookup::get_network_tokens;
+use crate::tools::rpc_config::Network;
+use crate::wallet::WalletProvider;
+use crate::web3::{decode_return, default_abis_dir, encode_call, find_function_with_params, load_abi, parse_abi};
+use crate::x402::X402EvmRpc;
+use ethers::types::{Address, U256};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Placeholder address aggregators use for the native currency
+pub const NATIVE_TOKEN: &str = "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE";
+
+/// A token being swapped
+#[derive(Debug, Clone)]
+pub struct SwapToken {
+    pub symbol: String,
+    pub address: Address,
+    pub decimals: u8,
+}
+
+impl SwapToken {
+    pub fn is_native(&self) -> bool {
+        format!("{:#x}", self.address) == NATIVE_TOKEN.to_lowercase()
+    }
+}
+
+/// What to quote
+#[derive(Debug, Clone)]
+pub struct SwapRequest {
+    pub network: Network,
+    pub sell: SwapToken,
+    pub buy: SwapToken,
+    /// Sell amount in raw units
+    pub sell_amount: U256,
+    /// Wallet that sends the sell token and receives the buy token
+    pub taker: Address,
+    pub slippage_bps: u32,
+}
+
+/// Allowance a quote needs before its transaction can execute
+#[derive(Debug, Clone, PartialEq)]
+pub enum Allowance {
+    /// Native currency sells need no approval
+    None,
+    /// ERC-20 `approve` to the spender
+    Erc20 { spender: Address },
+    /// ERC-20 `approve` to Permit2, then a Permit2 allowance for the spender
+    Permit2 { spender: Address },
+}
+
+/// Transaction that executes a quote
+#[derive(Debug, Clone)]
+pub struct SwapTx {
+    pub to: Address,
+    pub data: Vec<u8>,
+    pub value: U256,
+}
+
+/// A quote from one source
+#[derive(Debug, Clone)]
+pub struct SwapQuote {
+    /// "0x", "kyberswap" or "uniswap_v3:<pool>" / "uniswap_v4:<pool>"
+    pub source: String,
+    pub buy_amount: U256,
+    pub min_buy_amount: U256,
+    /// Loss against the spot / USD price in percent, when the source reports it
+    pub price_impact_pct: Option<f64>,
+    pub gas_estimate: Option<u64>,
+    pub allowance: Allowance,
+    pub tx: SwapTx,
+}
+
+impl SwapQuote {
+    /// Quote summary for tool metadata and dashboards
+    pub fn to_json(&self, request: &SwapRequest) -> Value {
+        json!({
+            "source": self.source,
+            "buy_amount": self.buy_amount.to_string(),
+            "buy_amount_formatted": format_units(self.buy_amount, request.buy.decimals),
+            "min_buy_amount": self.min_buy_amount.to_string(),
+            "min_buy_amount_formatted": format_units(self.min_buy_amount, request.buy.decimals),
+            "price_impact_pct": self.price_impact_pct,
+            "gas_estimate": self.gas_estimate,
+            "to": format!("{:?}", self.tx.to),
+            "value": self.tx.value.to_string(),
+        })
+    }
+}
+
+/// Minimum output after slippage (rounded down)
+pub fn min_buy_amount(buy_amount: U256, slippage_bps: u32) -> U256 {
+    let bps = U256::from(10_000u32.saturating_sub(slippage_bps));
+    buy_amount * bps / U256::from(10_000u32)
+}
+
+/// Price impact in percent of `actual` output against the `expected` output at spot price
+pub fn price_impact_pct(expected: f64, actual: f64) -> Option<f64> {
+    if expected.is_nan() || expected <= 0.0 || !actual.is_finite() {
+        return None;
+    }
+    Some(((expected - actual) / expected * 100.0).max(0.0))
+}
+
+/// Format a raw amount with the token's decimals, without trailing zeros
+pub fn format_units(amount: U256, decimals: u8) -> String {
+    let formatted = ethers::utils::format_units(amount, decimals as u32).unwrap_or_else(|_| amount.to_string());
+    if formatted.contains('.') {
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        formatted
+    }
+}
+
+/// Raw amount as f64 in whole tokens (for ratios only)
+fn to_f64(amount: U256, decimals: u8) -> f64 {
+    format_units(amount, decimals).parse().unwrap_or(0.0)
+}
+
+/// Resolve a token symbol from config/tokens.ron, or an address (decimals/symbol read on-chain)
+pub async fn resolve_token(network: &Network, token: &str, rpc: &X402EvmRpc) -> Result<SwapToken, String> {
+    let token = token.trim();
+    let configured = get_network_tokens(network.as_ref());
+
+    if token.starts_with("0x") && token.len() == 42 {
+        let address: Address = token.parse().map_err(|_| format!("Invalid token address: {}", token))?;
+        if let Some((symbol, info)) = configured
+            .iter()
+            .find(|(_, info)| info.address.eq_ignore_ascii_case(token))
+        {
+            return Ok(SwapToken {
+                symbol: symbol.clone(),
+                address,
+                decimals: info.decimals,
+            });
+        }
+
+        let network_name = network.as_ref();
+        let decimals = call_abi(rpc, "erc20", address, "decimals", &[]).await?;
+        let decimals = value_to_u256(&decimals)?.as_u32() as u8;
+        let symbol = call_abi(rpc, "erc20", address, "symbol", &[])
+            .await
+            .ok()
+            .and_then(|v| v.as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| format!("{}…", &token[..8]));
+        log::info!("[swap] Resolved {} on {} to {} ({} decimals)", token, network_name, symbol, decimals);
+        return Ok(SwapToken { symbol, address, decimals });
+    }
+
+    let (symbol, info) = configured
+        .into_iter()
+        .find(|(symbol, _)| symbol.eq_ignore_ascii_case(token))
+        .ok_or_else(|| {
+            format!(
+                "Unknown token '{}' on {}. Use the token address, or token_lookup to list known tokens.",
+                token, network
+            )
+        })?;
+    let address = info
+        .address
+        .parse()
+        .map_err(|_| format!("Invalid address for {} in tokens.ron", symbol))?;
+    Ok(SwapToken {
+        symbol,
+        address,
+        decimals: info.decimals,
+    })
+}
+
+/// Fetch quotes from every source concurrently.
+/// Returns quotes sorted by output (best first) and the errors of sources that failed.
+pub async fn fetch_quotes(
+    request: &SwapRequest,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    rpc: &X402EvmRpc,
+) -> (Vec<SwapQuote>, Vec<String>) {
+    let (zero_x, kyber, pools) = futures_util::future::join3(
+        aggregators::zero_x_quote(request, wallet_provider),
+        aggregators::kyberswap_quote(request),
+        uniswap::pool_quotes(request, rpc),
+    )
+    .await;
+
+    let mut quotes = Vec::new();
+    let mut errors = Vec::new();
+    for (source, result) in [("0x".to_string(), zero_x), ("kyberswap".to_string(), kyber)].into_iter().chain(pools) {
+        match result {
+            Ok(quote) => quotes.push(quote),
+            Err(e) => errors.push(format!("{}: {}", source, e)),
+        }
+    }
+    quotes.sort_by_key(|q| std::cmp::Reverse(q.buy_amount));
+    (quotes, errors)
+}
+
+/// eth_call a function from an ABI file and decode its return value
+async fn call_abi(
+    rpc: &X402EvmRpc,
+    abi_name: &str,
+    contract: Address,
+    function: &str,
+    params: &[Value],
+) -> Result<Value, String> {
+    let abi = parse_abi(&load_abi(&default_abis_dir(), abi_name)?)?;
+    let function = find_function_with_params(&abi, function, params.len())?;
+    let data = encode_call(function, params)?;
+    let result = rpc.call(contract, &data).await?;
+    decode_return(function, &result)
+}
+
+/// Contract address for a network from an ABI file's `address` map
+fn abi_address(abi_name: &str, network: &Network) -> Result<Address, String> {
+    let abi = load_abi(&default_abis_dir(), abi_name)?;
+    abi.address
+        .get(network.as_ref())
+        .ok_or_else(|| format!("{} is not configured for {}", abi.name, network))?
+        .parse()
+        .map_err(|_| format!("Invalid {} address for {}", abi.name, network))
+}
+
+/// Parse a decoded uint (decimal string) into U256
+fn value_to_u256(value: &Value) -> Result<U256, String> {
+    value
+        .as_str()
+        .and_then(|s| U256::from_dec_str(s).ok())
+        .ok_or_else(|| format!("Expected uint, got {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_buy_amount() {
+        assert_eq!(min_buy_amount(U256::from(1_000_000u64), 50), U256::from(995_000u64));
+        assert_eq!(min_buy_amount(U256::from(999u64), 100), U256::from(989u64));
+        assert_eq!(min_buy_amount(U256::from(1_000u64), 0), U256::from(1_000u64));
+    }
+
+    #[test]
+    fn test_price_impact() {
+        assert_eq!(price_impact_pct(100.0, 99.0), Some(1.0));
+        assert_eq!(price_impact_pct(100.0, 101.0), Some(0.0));
+        assert_eq!(price_impact_pct(0.0, 1.0), None);
+    }
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(U256::from(1_500_000u64), 6), "1.5");
+        assert_eq!(format_units(U256::from(2_000_000u64), 6), "2");
+        assert_eq!(format_units(U256::exp10(18), 18), "1");
+    }
+
+    #[test]
+    fn test_native_token() {
+        let token = SwapToken {
+            symbol: "ETH".to_string(),
+            address: NATIVE_TOKEN.parse().unwrap(),
+            decimals: 18,
+        };
+        assert!(token.is_native());
+    }
+}
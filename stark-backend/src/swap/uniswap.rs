@@ -0,0 +1,349 @@
+//! Direct Uniswap V3/V4 pool quotes and Universal Router swaps
+//!
+//! Pools come from `config/uniswap_pools.ron`. Quotes use the official
+//! quoter contracts; price impact is measured against the pool's current
+//! sqrt price (so it includes the pool fee). Swaps go through the Universal
+//! Router, which pulls the sell token through Permit2.
+
+use super::{
+    abi_address, call_abi, min_buy_amount, price_impact_pct, to_f64, value_to_u256, Allowance,
+    SwapQuote, SwapRequest, SwapTx,
+};
+use crate::web3::{default_abis_dir, encode_call, find_function_with_params, load_abi, parse_abi};
+use crate::x402::X402EvmRpc;
+use chrono::Utc;
+use ethers::abi::Token;
+use ethers::types::{Address, I256, U256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Universal Router command: exact-input swap through V3 pools
+const V3_SWAP_EXACT_IN: u8 = 0x00;
+/// Universal Router command: V4 swap (actions + params)
+const V4_SWAP: u8 = 0x10;
+/// V4 router actions
+const SWAP_EXACT_IN_SINGLE: u8 = 0x06;
+const SETTLE_ALL: u8 = 0x0c;
+const TAKE_ALL: u8 = 0x0f;
+
+/// Universal Router deadline for queued swaps
+const SWAP_DEADLINE_SECS: i64 = 20 * 60;
+
+static POOLS: OnceLock<HashMap<String, UniswapPool>> = OnceLock::new();
+
+fn default_version() -> u8 {
+    4
+}
+
+fn default_network() -> String {
+    "base".to_string()
+}
+
+fn default_hooks() -> String {
+    "0x0000000000000000000000000000000000000000".to_string()
+}
+
+/// Pool entry from config/uniswap_pools.ron (symbols and description are
+/// informational and not read here)
+#[derive(Debug, Clone, Deserialize)]
+pub struct UniswapPool {
+    #[serde(default = "default_version")]
+    pub version: u8,
+    #[serde(default = "default_network")]
+    pub network: String,
+    #[serde(default)]
+    pub pool_id: String,
+    #[serde(default)]
+    pub pool_address: String,
+    pub token0_address: String,
+    pub token1_address: String,
+    pub fee: u32,
+    #[serde(default)]
+    pub tick_spacing: i32,
+    #[serde(default = "default_hooks")]
+    pub hooks: String,
+}
+
+/// Load pools from config directory. Pools are optional: a missing or invalid
+/// file only disables direct pool quotes.
+pub fn load_uniswap_pools(config_dir: &Path) {
+    let pools_path = config_dir.join("uniswap_pools.ron");
+    let pools: HashMap<String, UniswapPool> = match std::fs::read_to_string(&pools_path) {
+        Ok(content) => match ron::from_str(&content) {
+            Ok(pools) => pools,
+            Err(e) => {
+                log::warn!("[swap] Failed to parse {:?}: {}", pools_path, e);
+                HashMap::new()
+            }
+        },
+        Err(_) => {
+            log::info!("[swap] No Uniswap pool config at {:?}", pools_path);
+            HashMap::new()
+        }
+    };
+    log::info!("[swap] Loaded {} Uniswap pools from {:?}", pools.len(), pools_path);
+    let _ = POOLS.set(pools);
+}
+
+/// Configured pools that trade the requested pair on the requested network
+fn matching_pools(request: &SwapRequest) -> Vec<(String, UniswapPool)> {
+    let sell = format!("{:#x}", request.sell.address);
+    let buy = format!("{:#x}", request.buy.address);
+    let mut pools: Vec<(String, UniswapPool)> = POOLS
+        .get()
+        .map(|pools| {
+            pools
+                .iter()
+                .filter(|(_, pool)| pool.network == request.network.as_ref())
+                .filter(|(_, pool)| {
+                    let t0 = pool.token0_address.to_lowercase();
+                    let t1 = pool.token1_address.to_lowercase();
+                    (t0 == sell && t1 == buy) || (t0 == buy && t1 == sell)
+                })
+                .map(|(name, pool)| (name.clone(), pool.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    pools.sort_by(|a, b| a.0.cmp(&b.0));
+    pools
+}
+
+/// Quote every matching pool. Returns (source, result) pairs.
+pub async fn pool_quotes(request: &SwapRequest, rpc: &X402EvmRpc) -> Vec<(String, Result<SwapQuote, String>)> {
+    let mut results = Vec::new();
+    for (name, pool) in matching_pools(request) {
+        let source = format!("uniswap_v{}:{}", pool.version, name);
+        let result = match pool.version {
+            3 => quote_v3(request, &pool, rpc).await,
+            4 => quote_v4(request, &pool, rpc).await,
+            v => Err(format!("Unsupported Uniswap version {}", v)),
+        };
+        results.push((source.clone(), result.map(|q| SwapQuote { source, ..q })));
+    }
+    results
+}
+
+/// Spot output for `amount_in` at sqrtPriceX96 (token1 per token0 in raw units)
+fn spot_output(sqrt_price_x96: U256, amount_in: f64, zero_for_one: bool) -> f64 {
+    let sqrt = u256_to_f64(sqrt_price_x96) / 2f64.powi(96);
+    let price = sqrt * sqrt;
+    if zero_for_one {
+        amount_in * price
+    } else {
+        amount_in / price
+    }
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    f64::from_str(&value.to_string()).unwrap_or(0.0)
+}
+
+/// First element of a decoded multi-value return, or the value itself
+fn first_value(value: &Value) -> &Value {
+    value.as_array().and_then(|a| a.first()).unwrap_or(value)
+}
+
+fn price_impact(request: &SwapRequest, sqrt_price_x96: U256, zero_for_one: bool, buy_amount: U256) -> Option<f64> {
+    let expected_raw = spot_output(sqrt_price_x96, u256_to_f64(request.sell_amount), zero_for_one);
+    let expected = expected_raw / 10f64.powi(request.buy.decimals as i32);
+    price_impact_pct(expected, to_f64(buy_amount, request.buy.decimals))
+}
+
+async fn quote_v3(request: &SwapRequest, pool: &UniswapPool, rpc: &X402EvmRpc) -> Result<SwapQuote, String> {
+    let quoter = abi_address("uniswap_v3_quoter", &request.network)?;
+    let pool_address: Address = pool
+        .pool_address
+        .parse()
+        .map_err(|_| format!("Invalid pool_address: {}", pool.pool_address))?;
+    let zero_for_one = request.sell.address == parse_address(&pool.token0_address)?;
+
+    let quoted = call_abi(
+        rpc,
+        "uniswap_v3_quoter",
+        quoter,
+        "quoteExactInputSingle",
+        &[json!([
+            format!("{:?}", request.sell.address),
+            format!("{:?}", request.buy.address),
+            request.sell_amount.to_string(),
+            pool.fee.to_string(),
+            "0",
+        ])],
+    )
+    .await?;
+    let buy_amount = value_to_u256(first_value(&quoted))?;
+    let gas_estimate = quoted
+        .as_array()
+        .and_then(|a| a.get(3))
+        .and_then(|v| value_to_u256(v).ok())
+        .map(|g| g.as_u64());
+
+    let slot0 = call_abi(rpc, "uniswap_v3_pool", pool_address, "slot0", &[]).await?;
+    let sqrt_price = value_to_u256(first_value(&slot0))?;
+
+    let min_buy = min_buy_amount(buy_amount, request.slippage_bps);
+    let mut path = request.sell.address.as_bytes().to_vec();
+    path.extend_from_slice(&pool.fee.to_be_bytes()[1..]);
+    path.extend_from_slice(request.buy.address.as_bytes());
+    let input = ethers::abi::encode(&[
+        Token::Address(request.taker),
+        Token::Uint(request.sell_amount),
+        Token::Uint(min_buy),
+        Token::Bytes(path),
+        // payerIsUser: pull the sell token from the caller through Permit2
+        Token::Bool(true),
+    ]);
+
+    router_quote(request, V3_SWAP_EXACT_IN, input, buy_amount, min_buy, gas_estimate, price_impact(request, sqrt_price, zero_for_one, buy_amount))
+}
+
+async fn quote_v4(request: &SwapRequest, pool: &UniswapPool, rpc: &X402EvmRpc) -> Result<SwapQuote, String> {
+    let quoter = abi_address("uniswap_v4_quoter", &request.network)?;
+    let state_view = abi_address("uniswap_v4_state_view", &request.network)?;
+    let currency0 = parse_address(&pool.token0_address)?;
+    let currency1 = parse_address(&pool.token1_address)?;
+    let hooks = parse_address(&pool.hooks)?;
+    let zero_for_one = request.sell.address == currency0;
+
+    let pool_key = json!([
+        format!("{:?}", currency0),
+        format!("{:?}", currency1),
+        pool.fee.to_string(),
+        pool.tick_spacing.to_string(),
+        format!("{:?}", hooks),
+    ]);
+    let quoted = call_abi(
+        rpc,
+        "uniswap_v4_quoter",
+        quoter,
+        "quoteExactInputSingle",
+        &[json!([pool_key, zero_for_one, request.sell_amount.to_string(), "0x"])],
+    )
+    .await?;
+    let buy_amount = value_to_u256(first_value(&quoted))?;
+    let gas_estimate = quoted
+        .as_array()
+        .and_then(|a| a.get(1))
+        .and_then(|v| value_to_u256(v).ok())
+        .map(|g| g.as_u64());
+
+    let slot0 = call_abi(rpc, "uniswap_v4_state_view", state_view, "getSlot0", &[json!(pool.pool_id)]).await?;
+    let sqrt_price = value_to_u256(first_value(&slot0))?;
+
+    let min_buy = min_buy_amount(buy_amount, request.slippage_bps);
+    let swap_params = ethers::abi::encode(&[Token::Tuple(vec![
+        Token::Tuple(vec![
+            Token::Address(currency0),
+            Token::Address(currency1),
+            Token::Uint(U256::from(pool.fee)),
+            Token::Int(I256::from(pool.tick_spacing).into_raw()),
+            Token::Address(hooks),
+        ]),
+        Token::Bool(zero_for_one),
+        Token::Uint(request.sell_amount),
+        Token::Uint(min_buy),
+        Token::Bytes(Vec::new()),
+    ])]);
+    let settle = ethers::abi::encode(&[Token::Address(request.sell.address), Token::Uint(request.sell_amount)]);
+    let take = ethers::abi::encode(&[Token::Address(request.buy.address), Token::Uint(min_buy)]);
+    let input = ethers::abi::encode(&[
+        Token::Bytes(vec![SWAP_EXACT_IN_SINGLE, SETTLE_ALL, TAKE_ALL]),
+        Token::Array(vec![Token::Bytes(swap_params), Token::Bytes(settle), Token::Bytes(take)]),
+    ]);
+
+    router_quote(request, V4_SWAP, input, buy_amount, min_buy, gas_estimate, price_impact(request, sqrt_price, zero_for_one, buy_amount))
+}
+
+/// Wrap one Universal Router command into a quote
+fn router_quote(
+    request: &SwapRequest,
+    command: u8,
+    input: Vec<u8>,
+    buy_amount: U256,
+    min_buy: U256,
+    gas_estimate: Option<u64>,
+    price_impact_pct: Option<f64>,
+) -> Result<SwapQuote, String> {
+    let router = abi_address("universal_router", &request.network)?;
+    let abi = parse_abi(&load_abi(&default_abis_dir(), "universal_router")?)?;
+    let execute = find_function_with_params(&abi, "execute", 3)?;
+    let deadline = Utc::now().timestamp() + SWAP_DEADLINE_SECS;
+    let data = encode_call(
+        execute,
+        &[
+            json!(format!("0x{:02x}", command)),
+            json!([format!("0x{}", hex::encode(&input))]),
+            json!(deadline.to_string()),
+        ],
+    )?;
+
+    Ok(SwapQuote {
+        source: String::new(),
+        buy_amount,
+        min_buy_amount: min_buy,
+        price_impact_pct,
+        gas_estimate,
+        allowance: Allowance::Permit2 { spender: router },
+        tx: SwapTx {
+            to: router,
+            data,
+            value: U256::zero(),
+        },
+    })
+}
+
+fn parse_address(address: &str) -> Result<Address, String> {
+    address.parse().map_err(|_| format!("Invalid address: {}", address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swap::SwapToken;
+    use crate::tools::rpc_config::Network;
+
+    #[test]
+    fn test_pool_config_parses() {
+        let content = std::fs::read_to_string(crate::config::repo_root().join("config/uniswap_pools.ron")).unwrap();
+        let pools: HashMap<String, UniswapPool> = ron::from_str(&content).unwrap();
+        let v4 = &pools["starkbot_weth"];
+        assert_eq!((v4.version, v4.network.as_str(), v4.tick_spacing), (4, "base", 200));
+        let v3 = &pools["weth_usdc_v3"];
+        assert_eq!((v3.version, v3.fee), (3, 500));
+        assert!(v3.pool_address.starts_with("0x"));
+    }
+
+    #[test]
+    fn test_spot_output() {
+        // sqrtPriceX96 = 2^96 means price 1; 4x price means sqrt 2 * 2^96
+        let one = U256::from(2u64).pow(U256::from(96u64));
+        assert_eq!(spot_output(one, 100.0, true), 100.0);
+        assert_eq!(spot_output(one * 2, 100.0, true), 400.0);
+        assert_eq!(spot_output(one * 2, 400.0, false), 100.0);
+    }
+
+    #[test]
+    fn test_router_calldata() {
+        let token = |address: &str| SwapToken {
+            symbol: String::new(),
+            address: address.parse().unwrap(),
+            decimals: 18,
+        };
+        let request = SwapRequest {
+            network: Network::Base,
+            sell: token("0x4200000000000000000000000000000000000006"),
+            buy: token("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"),
+            sell_amount: U256::exp10(18),
+            taker: Address::repeat_byte(0x11),
+            slippage_bps: 50,
+        };
+        let quote = router_quote(&request, V3_SWAP_EXACT_IN, vec![0u8; 32], U256::from(100u64), U256::from(99u64), None, None).unwrap();
+        // execute(bytes,bytes[],uint256)
+        assert_eq!(hex::encode(&quote.tx.data[..4]), "3593564c");
+        assert!(matches!(quote.allowance, Allowance::Permit2 { .. }));
+    }
+}
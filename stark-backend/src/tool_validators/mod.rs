@@ -58,20 +58,26 @@ pub use traits::*;
 pub use registry::*;
 
 use std::path::Path;
+use std::sync::Arc;
+
+/// Directory holding RON validators: ./config/validators, then ../config/validators
+pub fn default_validators_dir() -> Option<&'static Path> {
+    [Path::new("./config/validators"), Path::new("../config/validators")]
+        .into_iter()
+        .find(|dir| dir.exists())
+}
 
 /// Create the default validator registry, loading RON validators from config/validators/
 pub fn create_default_registry() -> ValidatorRegistry {
     let mut registry = ValidatorRegistry::new();
 
     // Load RON validators from config directory
-    // Check ./config/validators first, then ../config/validators
-    let validators_dir = if Path::new("./config/validators").exists() {
-        Path::new("./config/validators")
-    } else if Path::new("../config/validators").exists() {
-        Path::new("../config/validators")
-    } else {
-        log::debug!("[TOOL_VALIDATORS] No validators directory found");
-        return registry;
+    let validators_dir = match default_validators_dir() {
+        Some(dir) => dir,
+        None => {
+            log::debug!("[TOOL_VALIDATORS] No validators directory found");
+            return registry;
+        }
     };
 
     let count = ron::load_validators_from_dir(validators_dir, &mut registry);
@@ -88,3 +94,17 @@ pub fn create_default_registry() -> ValidatorRegistry {
 pub fn load_validators_from_dir(dir: &Path, registry: &mut ValidatorRegistry) -> usize {
     ron::load_validators_from_dir(dir, registry)
 }
+
+/// Re-read a validators directory and swap the registry's validators.
+/// Nothing changes unless every file parses.
+pub fn reload_validators_from_dir(dir: &Path, registry: &ValidatorRegistry) -> Result<usize, String> {
+    let validators = ron::parse_validators_dir(dir)?;
+    let count = validators.len();
+    registry.replace(
+        validators
+            .into_iter()
+            .map(|v| Arc::new(v) as Arc<dyn ToolValidator>)
+            .collect(),
+    );
+    Ok(count)
+}
@@ -1,19 +1,24 @@
 //! Registry for managing tool validators
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 use super::traits::ToolValidator;
 use super::types::{ValidationContext, ValidationResult};
 
-/// Registry that holds all tool validators
+/// Registry that holds all tool validators.
+/// The set can be swapped at runtime with `replace` when config/validators changes.
+/// Validators bundled with skills are kept separately so config reloads don't drop them.
 pub struct ValidatorRegistry {
-    validators: Vec<Arc<dyn ToolValidator>>,
+    validators: RwLock<Vec<Arc<dyn ToolValidator>>>,
+    skill_validators: RwLock<HashMap<String, Vec<Arc<dyn ToolValidator>>>>,
 }
 
 impl ValidatorRegistry {
     /// Create a new empty registry
     pub fn new() -> Self {
         Self {
-            validators: Vec::new(),
+            validators: RwLock::new(Vec::new()),
+            skill_validators: RwLock::new(HashMap::new()),
         }
     }
 
@@ -25,10 +30,11 @@ impl ValidatorRegistry {
         let name = validator.name().to_string();
         let priority = validator.priority();
 
-        self.validators.push(validator);
+        let validators = self.validators.get_mut().unwrap_or_else(|e| e.into_inner());
+        validators.push(validator);
 
         // Sort by priority (lower = first)
-        self.validators.sort_by_key(|v| v.priority() as u32);
+        validators.sort_by_key(|v| v.priority() as u32);
 
         log::info!(
             "[VALIDATOR_REGISTRY] Registered validator '{}' ({}) with priority {:?}",
@@ -38,12 +44,51 @@ impl ValidatorRegistry {
         );
     }
 
+    /// Atomically replace every registered validator with a new set
+    pub fn replace(&self, mut validators: Vec<Arc<dyn ToolValidator>>) {
+        validators.sort_by_key(|v| v.priority() as u32);
+        log::info!("[VALIDATOR_REGISTRY] Replaced validators ({} registered)", validators.len());
+        *self.validators.write().unwrap_or_else(|e| e.into_inner()) = validators;
+    }
+
+    /// Register the validators shipped by a skill, replacing any it registered before
+    pub fn set_skill_validators(&self, skill: &str, validators: Vec<Arc<dyn ToolValidator>>) {
+        log::info!(
+            "[VALIDATOR_REGISTRY] Registered {} validators for skill '{}'",
+            validators.len(),
+            skill
+        );
+        self.skill_validators
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(skill.to_string(), validators);
+    }
+
+    /// Remove every validator registered by a skill
+    pub fn remove_skill_validators(&self, skill: &str) {
+        self.skill_validators
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(skill);
+    }
+
+    /// Snapshot of config and skill validators in priority order, so no lock is held across awaits
+    fn snapshot(&self) -> Vec<Arc<dyn ToolValidator>> {
+        let mut validators = self.validators.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let skill_validators = self.skill_validators.read().unwrap_or_else(|e| e.into_inner());
+        if !skill_validators.is_empty() {
+            validators.extend(skill_validators.values().flatten().cloned());
+            validators.sort_by_key(|v| v.priority() as u32);
+        }
+        validators
+    }
+
     /// Run all applicable validators against a tool call
     ///
     /// Validators are run in priority order. The first validator to return
     /// a Block result will stop execution and return that result.
     pub async fn validate(&self, ctx: &ValidationContext) -> ValidationResult {
-        for validator in &self.validators {
+        for validator in self.snapshot() {
             // Skip disabled validators
             if !validator.enabled() {
                 continue;
@@ -75,22 +120,22 @@ impl ValidatorRegistry {
 
     /// Get the number of registered validators
     pub fn len(&self) -> usize {
-        self.validators.len()
+        self.snapshot().len()
     }
 
     /// Check if the registry is empty
     pub fn is_empty(&self) -> bool {
-        self.validators.is_empty()
+        self.len() == 0
     }
 
     /// List all registered validators
-    pub fn list(&self) -> Vec<&Arc<dyn ToolValidator>> {
-        self.validators.iter().collect()
+    pub fn list(&self) -> Vec<Arc<dyn ToolValidator>> {
+        self.snapshot()
     }
 
     /// Get a validator by ID
-    pub fn get(&self, id: &str) -> Option<&Arc<dyn ToolValidator>> {
-        self.validators.iter().find(|v| v.id() == id)
+    pub fn get(&self, id: &str) -> Option<Arc<dyn ToolValidator>> {
+        self.snapshot().into_iter().find(|v| v.id() == id)
     }
 }
 
@@ -183,4 +228,40 @@ mod tests {
         );
         assert!(registry.validate(&ctx2).await.is_allowed());
     }
+
+    #[tokio::test]
+    async fn test_replace_swaps_validators() {
+        let mut registry = ValidatorRegistry::new();
+        registry.register(Arc::new(AlwaysBlockValidator));
+        let ctx = ValidationContext::new(
+            "test_tool".into(),
+            json!({}),
+            Arc::new(ToolContext::new()),
+        );
+        assert!(registry.validate(&ctx).await.is_blocked());
+
+        registry.replace(vec![Arc::new(AlwaysAllowValidator)]);
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get("always_block").is_none());
+        assert!(registry.validate(&ctx).await.is_allowed());
+    }
+
+    #[tokio::test]
+    async fn test_skill_validators_survive_replace() {
+        let registry = ValidatorRegistry::new();
+        registry.set_skill_validators("my-skill", vec![Arc::new(AlwaysBlockValidator)]);
+        registry.replace(vec![Arc::new(AlwaysAllowValidator)]);
+        assert_eq!(registry.len(), 2);
+
+        let ctx = ValidationContext::new(
+            "test_tool".into(),
+            json!({}),
+            Arc::new(ToolContext::new()),
+        );
+        assert!(registry.validate(&ctx).await.is_blocked());
+
+        registry.remove_skill_validators("my-skill");
+        assert_eq!(registry.len(), 1);
+        assert!(registry.validate(&ctx).await.is_allowed());
+    }
 }
@@ -35,6 +35,12 @@ impl RonValidator {
             .map_err(|e| format!("Failed to parse RON: {}", e))?;
         Ok(Self { def })
     }
+
+    /// Prefix the validator ID (e.g. `<skill>/<id>` for validators bundled with a skill)
+    pub fn with_id_prefix(mut self, prefix: &str) -> Self {
+        self.def.id = format!("{}/{}", prefix, self.def.id);
+        self
+    }
 }
 
 impl Action {
@@ -133,6 +139,45 @@ pub fn load_validators_from_dir(dir: &Path, registry: &mut ValidatorRegistry) ->
     count
 }
 
+/// Parse every validator in a directory without touching any registry.
+/// Fails if any file is invalid or two files share an ID, so a reload can be all-or-nothing.
+pub fn parse_validators_dir(dir: &Path) -> Result<Vec<RonValidator>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|e| e == "ron").unwrap_or(false))
+        .collect();
+    paths.sort();
+
+    let mut validators: Vec<RonValidator> = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        match RonValidator::from_file(&path) {
+            Ok(validator) => {
+                if validators.iter().any(|v| v.id() == validator.id()) {
+                    errors.push(format!("Duplicate validator id '{}' in {}", validator.id(), path.display()));
+                } else {
+                    validators.push(validator);
+                }
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(validators)
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,3 +1,4 @@
+use crate::hooks::{HookContext, HookEvent};
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -422,12 +423,26 @@ impl Tool for GitTool {
                     _ => return ToolResult::error("Commit message is required"),
                 };
 
+                // BeforeCommit hooks may block the commit or replace its message
+                let hook_result = context
+                    .run_hooks(HookContext::new(HookEvent::BeforeCommit).with_commit(message.clone(), Vec::new()))
+                    .await;
+                if let Some(reason) = hook_result.block_reason() {
+                    return ToolResult::error(format!("Commit blocked by hook: {}", reason));
+                }
+                let message = hook_result.replacement_text().unwrap_or(message);
+
                 // Create commit
                 match self
                     .run_git(&["commit", "-m", &message], &workspace, context)
                     .await
                 {
-                    Ok(output) => ToolResult::success(format!("Committed:\n{}", output)),
+                    Ok(output) => {
+                        context
+                            .run_hooks(HookContext::new(HookEvent::AfterCommit).with_commit(message, Vec::new()))
+                            .await;
+                        ToolResult::success(format!("Committed:\n{}", output))
+                    }
                     Err(e) => ToolResult::error(e),
                 }
             }
@@ -590,8 +605,22 @@ impl Tool for GitTool {
                 args.push(remote);
                 args.push(&branch);
 
+                let push_hook = HookContext::new(HookEvent::BeforePush)
+                    .with_branch(branch.clone())
+                    .with_remote(remote.to_string());
+                if let Some(reason) = context.run_hooks(push_hook).await.block_reason() {
+                    return ToolResult::error(format!("Push blocked by hook: {}", reason));
+                }
+
                 match self.run_git(&args, &workspace, context).await {
                     Ok(output) => {
+                        context
+                            .run_hooks(
+                                HookContext::new(HookEvent::AfterPush)
+                                    .with_branch(branch.clone())
+                                    .with_remote(remote.to_string()),
+                            )
+                            .await;
                         let result = if output.is_empty() {
                             format!("Pushed branch '{}' to {}/{}", branch, remote, branch)
                         } else {
@@ -1,3 +1,4 @@
+use crate::hooks::{HookContext, HookEvent};
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -472,6 +473,19 @@ impl Tool for CommitterTool {
             ));
         }
 
+        // BeforeCommit hooks may block the commit or replace its message
+        let hook_result = context
+            .run_hooks(
+                HookContext::new(HookEvent::BeforeCommit)
+                    .with_commit(params.message.clone(), params.files.clone())
+                    .with_branch(branch.clone()),
+            )
+            .await;
+        if let Some(reason) = hook_result.block_reason() {
+            return ToolResult::error(format!("Commit blocked by hook: {}", reason));
+        }
+        let message = hook_result.replacement_text().unwrap_or_else(|| params.message.clone());
+
         // Stage the files
         let mut stage_args = vec!["add"];
         for f in &params.files {
@@ -486,7 +500,7 @@ impl Tool for CommitterTool {
         let bot_email = context.get_bot_email();
         let full_message = format!(
             "{}\n\nCo-Authored-By: {} <{}>",
-            params.message, bot_name, bot_email
+            message, bot_name, bot_email
         );
 
         // Create commit
@@ -497,15 +511,41 @@ impl Tool for CommitterTool {
                     params.files.len(),
                     branch,
                     params.files.iter().map(|f| format!("  - {}", f)).collect::<Vec<_>>().join("\n"),
-                    params.message,
+                    message,
                     output
                 );
 
+                context
+                    .run_hooks(
+                        HookContext::new(HookEvent::AfterCommit)
+                            .with_commit(message.clone(), params.files.clone())
+                            .with_branch(branch.clone()),
+                    )
+                    .await;
+
                 // Push if requested
                 if push {
+                    let push_hook = context
+                        .run_hooks(
+                            HookContext::new(HookEvent::BeforePush)
+                                .with_branch(branch.clone())
+                                .with_remote("origin".to_string()),
+                        )
+                        .await;
+                    if let Some(reason) = push_hook.block_reason() {
+                        result.push_str(&format!("\nPush blocked by hook: {}", reason));
+                        return ToolResult::success(result);
+                    }
                     match self.run_git(&["push", "-u", "origin", &branch], &workspace, context).await {
                         Ok(push_output) => {
                             result.push_str(&format!("\nPushed to origin/{}:\n{}", branch, push_output));
+                            context
+                                .run_hooks(
+                                    HookContext::new(HookEvent::AfterPush)
+                                        .with_branch(branch.clone())
+                                        .with_remote("origin".to_string()),
+                                )
+                                .await;
                         }
                         Err(e) => {
                             result.push_str(&format!("\nCommit succeeded but push failed: {}", e));
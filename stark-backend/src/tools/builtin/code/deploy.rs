@@ -1,4 +1,5 @@
 use crate::controllers::api_keys::ApiKeyId;
+use crate::hooks::{HookContext, HookEvent};
 use crate::tools::registry::Tool;
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
@@ -394,8 +395,22 @@ impl Tool for DeployTool {
                 args.push(remote);
                 args.push(&branch);
 
+                let push_hook = HookContext::new(HookEvent::BeforePush)
+                    .with_branch(branch.clone())
+                    .with_remote(remote.to_string());
+                if let Some(reason) = context.run_hooks(push_hook).await.block_reason() {
+                    return ToolResult::error(format!("Push blocked by hook: {}", reason));
+                }
+
                 match self.run_git(&args, &workspace, context).await {
                     Ok(output) => {
+                        context
+                            .run_hooks(
+                                HookContext::new(HookEvent::AfterPush)
+                                    .with_branch(branch.clone())
+                                    .with_remote(remote.to_string()),
+                            )
+                            .await;
                         let result = if output.is_empty() {
                             format!("Pushed branch '{}' to {}/{}", branch, remote, branch)
                         } else {
@@ -463,6 +478,14 @@ impl Tool for DeployTool {
                     ));
                 }
 
+                let pr_hook = HookContext::new(HookEvent::BeforePrCreate)
+                    .with_pr(title.clone(), params.body.clone())
+                    .with_branch(branch.clone())
+                    .with_remote(remote.to_string());
+                if let Some(reason) = context.run_hooks(pr_hook).await.block_reason() {
+                    return ToolResult::error(format!("PR creation blocked by hook: {}", reason));
+                }
+
                 // Push branch first
                 if let Err(e) = self.run_git(&["push", "-u", remote, &branch], &workspace, context).await {
                     return ToolResult::error(format!("Failed to push branch before creating PR: {}", e));
@@ -480,10 +503,22 @@ impl Tool for DeployTool {
                 }
 
                 match self.run_gh(&args, &workspace, context).await {
-                    Ok(output) => ToolResult::success(format!(
-                        "Created PR: {} -> {}\n{}",
-                        branch, base, output
-                    )),
+                    Ok(output) => {
+                        // gh prints the PR URL as the last line of output
+                        let pr_url = output.lines().rev().find(|l| l.starts_with("http")).unwrap_or("");
+                        context
+                            .run_hooks(
+                                HookContext::new(HookEvent::AfterPrCreate)
+                                    .with_pr(title.clone(), params.body.clone())
+                                    .with_pr_url(pr_url.to_string())
+                                    .with_branch(branch.clone()),
+                            )
+                            .await;
+                        ToolResult::success(format!(
+                            "Created PR: {} -> {}\n{}",
+                            branch, base, output
+                        ))
+                    }
                     Err(e) => ToolResult::error(e),
                 }
             }
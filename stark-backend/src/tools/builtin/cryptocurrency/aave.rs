@@ -0,0 +1,361 @@
+//! Aave tool - V3 positions and health-factor watchers
+//!
+//! Reports collateral, debt, health factor and per-reserve balances on every
+//! Aave network (see `crate::aave`) and creates event-triggered cron jobs
+//! that alert when the health factor drops below a threshold, optionally
+//! queueing a repay within the automatic repay policy. Supply, withdraw,
+//! borrow and repay go through the `aave_*` web3 presets.
+
+use crate::aave;
+use crate::scheduler::triggers::EventTrigger;
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::resolve_rpc_from_context;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::x402::X402EvmRpc;
+use async_trait::async_trait;
+use ethers::types::Address;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Prompt of the cron job a watcher creates (the event details are appended)
+const WATCH_MESSAGE: &str = "An Aave health-factor watcher fired. Check the position with the aave tool \
+(action 'positions') and tell the user how close it is to liquidation. If an automatic repay was queued \
+(auto_repay in the event details), report its UUID and amount and ask the user to broadcast it. If it \
+failed, explain why and suggest repaying debt or supplying collateral.";
+
+/// Aave tool
+pub struct AaveTool {
+    definition: ToolDefinition,
+}
+
+impl AaveTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'positions' = collateral, debt, health factor and reserve balances per network, 'watch' = alert when the health factor drops below a threshold".to_string(),
+                default: Some(json!("positions")),
+                items: None,
+                enum_values: Some(vec!["positions".to_string(), "watch".to_string()]),
+            },
+        );
+        properties.insert(
+            "network".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Network (base, mainnet, polygon). 'positions' checks all of them when unset; 'watch' defaults to base.".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(aave::networks().map(String::from).collect()),
+            },
+        );
+        properties.insert(
+            "address".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Wallet to check or watch (defaults to the bot wallet)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "below".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Health factor threshold for 'watch' (greater than 1, e.g. 1.3)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "auto_repay".to_string(),
+            PropertySchema {
+                schema_type: "boolean".to_string(),
+                description: "For 'watch' on the bot wallet: queue a repay of borrowed assets the wallet holds when the watcher fires, within the automatic repay limits. The repay still has to be broadcast.".to_string(),
+                default: Some(json!(false)),
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "name".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Name of the watcher job (optional)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        AaveTool {
+            definition: ToolDefinition {
+                name: "aave".to_string(),
+                description: "Aave V3 lending positions: collateral, debt, available borrows and health factor per network with supplied/borrowed balances and rates. Can set up a watcher that alerts (and optionally queues a repay) when the health factor drops below a threshold.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+            },
+        }
+    }
+}
+
+impl Default for AaveTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AaveParams {
+    #[serde(default = "default_action")]
+    action: String,
+    network: Option<String>,
+    address: Option<String>,
+    below: Option<f64>,
+    #[serde(default)]
+    auto_repay: bool,
+    name: Option<String>,
+}
+
+fn default_action() -> String {
+    "positions".to_string()
+}
+
+fn format_health_factor(health_factor: Option<f64>) -> String {
+    match health_factor {
+        Some(hf) => format!("{:.3}", hf),
+        None => "no debt".to_string(),
+    }
+}
+
+fn format_position(position: &aave::Position) -> String {
+    let account = &position.account;
+    let mut lines = vec![format!(
+        "{}: health factor {} | collateral ${:.2} | debt ${:.2} | available to borrow ${:.2} | LTV {:.2}% | liquidation threshold {:.2}%",
+        position.network,
+        format_health_factor(account.health_factor),
+        account.collateral_usd,
+        account.debt_usd,
+        account.available_borrows_usd,
+        account.ltv_bps as f64 / 100.0,
+        account.liquidation_threshold_bps as f64 / 100.0,
+    )];
+    for reserve in &position.reserves {
+        let mut parts = Vec::new();
+        if reserve.supplied != "0" {
+            parts.push(format!(
+                "supplied {} (${:.2}, {:.2}% APR)",
+                reserve.supplied, reserve.supplied_usd, reserve.supply_apr_pct
+            ));
+        }
+        if reserve.borrowed != "0" {
+            parts.push(format!(
+                "borrowed {} (${:.2}, {:.2}% variable APR)",
+                reserve.borrowed, reserve.borrowed_usd, reserve.variable_borrow_apr_pct
+            ));
+        }
+        lines.push(format!("  - {}: {}", reserve.name(), parts.join(", ")));
+    }
+    lines.join("\n")
+}
+
+impl AaveTool {
+    async fn positions(&self, params: &AaveParams, context: &ToolContext) -> ToolResult {
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("No wallet configured - cannot read Aave positions"),
+        };
+        let user: Address = match params.address.as_deref().unwrap_or(&wallet_provider.get_address()).parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error(format!("Invalid address: {}", params.address.as_deref().unwrap_or_default())),
+        };
+        let networks: Vec<&str> = match params.network.as_deref() {
+            Some(network) if aave::pool_address(network).is_none() => {
+                return ToolResult::error(format!("Aave V3 is not available on {}", network));
+            }
+            Some(network) => vec![network],
+            None => aave::networks().collect(),
+        };
+
+        let mut positions = Vec::new();
+        let mut errors = Vec::new();
+        for network in networks {
+            let rpc_config = resolve_rpc_from_context(&context.extra, network);
+            let rpc = match X402EvmRpc::new_with_wallet_provider(
+                wallet_provider.clone(),
+                network,
+                Some(rpc_config.url.clone()),
+                rpc_config.use_x402,
+            ) {
+                Ok(r) => r,
+                Err(e) => {
+                    errors.push(format!("{}: {}", network, e));
+                    continue;
+                }
+            };
+            match aave::get_position(&rpc, network, user).await {
+                Ok(position) => positions.push(position),
+                Err(e) => errors.push(format!("{}: {}", network, e)),
+            }
+        }
+
+        let open: Vec<&aave::Position> = positions.iter().filter(|p| p.account.has_position()).collect();
+        if open.is_empty() && errors.is_empty() {
+            return ToolResult::success(format!("No Aave V3 position for {:?}.", user))
+                .with_metadata(json!({ "address": format!("{:?}", user), "positions": [] }));
+        }
+
+        let mut lines = vec![format!("Aave V3 positions of {:?}", user)];
+        lines.extend(open.iter().map(|p| format_position(p)));
+        if !errors.is_empty() {
+            lines.push(format!("Could not read: {}", errors.join("; ")));
+        }
+        ToolResult::success(lines.join("\n")).with_metadata(json!({
+            "address": format!("{:?}", user),
+            "positions": open,
+            "errors": errors,
+        }))
+    }
+
+    fn watch(&self, params: &AaveParams, context: &ToolContext) -> ToolResult {
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let Some(below) = params.below else {
+            return ToolResult::error("'below' (health factor threshold) is required for watch");
+        };
+        let network = params.network.clone().unwrap_or_else(|| "base".to_string());
+
+        let mut spec = json!({
+            "kind": "aave_health_factor",
+            "network": network,
+            "below": below,
+            "auto_repay": params.auto_repay,
+        });
+        if let Some(address) = &params.address {
+            spec["address"] = json!(address);
+        }
+        let spec = spec.to_string();
+        if let Err(e) = EventTrigger::parse(&spec) {
+            return ToolResult::error(e);
+        }
+
+        let name = params
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Aave health factor below {} on {}", below, network));
+        let description = format!(
+            "Alerts when the Aave V3 health factor of {} on {} drops to {} or below{}",
+            params.address.as_deref().unwrap_or("the bot wallet"),
+            network,
+            below,
+            if params.auto_repay { ", then queues a repay" } else { "" }
+        );
+        match db.create_cron_job(
+            &name,
+            Some(&description),
+            "event",
+            &spec,
+            None,
+            "main",
+            Some(WATCH_MESSAGE),
+            None,
+            None,
+            None,
+            false,
+            None,
+            None,
+            None,
+            false,
+        ) {
+            Ok(job) => {
+                let mut msg = format!(
+                    "Watcher '{}' created (job {}). The health factor is checked every poll and the alert fires once each time it drops to {} or below.",
+                    job.name, job.job_id, below
+                );
+                if params.auto_repay {
+                    msg.push_str(
+                        "\nWhen it fires, a repay of a borrowed asset the wallet holds (with a Pool allowance) is queued within the automatic repay limits. It still has to be broadcast.",
+                    );
+                }
+                ToolResult::success(msg).with_metadata(json!({ "job_id": job.job_id, "trigger": spec }))
+            }
+            Err(e) => ToolResult::error(format!("Failed to create watcher: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for AaveTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: AaveParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        match params.action.as_str() {
+            "positions" => self.positions(&params, context).await,
+            "watch" => self.watch(&params, context),
+            other => ToolResult::error(format!("Unknown action '{}'. Use: positions, watch", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn watch_params(below: f64, auto_repay: bool) -> AaveParams {
+        AaveParams {
+            action: "watch".to_string(),
+            network: Some("mainnet".to_string()),
+            address: None,
+            below: Some(below),
+            auto_repay,
+            name: None,
+        }
+    }
+
+    #[test]
+    fn test_watch_creates_event_job() {
+        let db = Arc::new(crate::db::Database::new(":memory:").unwrap());
+        let ctx = ToolContext::new().with_database(db.clone());
+        let tool = AaveTool::new();
+
+        let result = tool.watch(&watch_params(1.3, true), &ctx);
+        assert!(result.success, "{}", result.content);
+        let jobs = db.list_cron_jobs().unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].schedule_type, "event");
+        let trigger = EventTrigger::parse(&jobs[0].schedule_value).unwrap();
+        assert!(matches!(trigger, EventTrigger::AaveHealthFactor { auto_repay: true, .. }));
+        assert_eq!(trigger.network(), "mainnet");
+
+        assert!(!tool.watch(&watch_params(0.95, false), &ctx).success);
+        assert_eq!(db.list_cron_jobs().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_format_health_factor() {
+        assert_eq!(format_health_factor(Some(1.23456)), "1.235");
+        assert_eq!(format_health_factor(None), "no debt");
+    }
+}
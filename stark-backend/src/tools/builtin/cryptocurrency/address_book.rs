@@ -0,0 +1,481 @@
+//! Address book tool - labelled contacts and ENS resolution
+//!
+//! Lets the agent look up, add and maintain contacts (see `crate::address_book`) and
+//! resolve ENS names on mainnet. Resolving an ENS name the user typed adds the resolved
+//! address to the context bank so it passes the recipient check in `verify_intent`.
+//! Trusted contacts are managed by operators only: the tool cannot create, change or
+//! remove them.
+
+use crate::address_book::{self, Contact, ContactInput, TrustLevel};
+use crate::gateway::protocol::GatewayEvent;
+use crate::tools::context_bank::ContextBankItem;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::web3::ens;
+use async_trait::async_trait;
+use ethers::types::Address;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Address book tool
+pub struct AddressBookTool {
+    definition: ToolDefinition,
+}
+
+impl AddressBookTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'list' = all contacts, 'lookup' = find a contact by label/address/ENS name, 'add' / 'update' / 'remove' = manage a contact, 'resolve_ens' = ENS name to address, 'reverse_ens' = address to its primary ENS name".to_string(),
+                default: Some(json!("list")),
+                items: None,
+                enum_values: Some(vec![
+                    "list".to_string(),
+                    "lookup".to_string(),
+                    "add".to_string(),
+                    "update".to_string(),
+                    "remove".to_string(),
+                    "resolve_ens".to_string(),
+                    "reverse_ens".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "name".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "For lookup/update/remove: the contact's label, address or ENS name. For resolve_ens: the ENS name (e.g. 'vitalik.eth'). For reverse_ens: the address.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "label".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Contact label for add/update (e.g. 'Alice', 'Team multisig')".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "address".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Contact address for add/update (0x + 40 hex). Optional when ens_name is given.".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "ens_name".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "ENS name for add/update; resolved on mainnet and must match the address if both are given".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "networks".to_string(),
+            PropertySchema {
+                schema_type: "array".to_string(),
+                description: "Networks the contact receives on (empty = any)".to_string(),
+                default: None,
+                items: Some(Box::new(PropertySchema {
+                    schema_type: "string".to_string(),
+                    description: "Network name, e.g. 'base'".to_string(),
+                    default: None,
+                    items: None,
+                    enum_values: None,
+                })),
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "notes".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Free-form notes for add/update".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "trust_level".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'known' (default) or 'blocked' (never send to this address). Only operators can mark contacts as trusted.".to_string(),
+                default: Some(json!("known")),
+                items: None,
+                enum_values: Some(vec!["known".to_string(), "blocked".to_string()]),
+            },
+        );
+
+        AddressBookTool {
+            definition: ToolDefinition {
+                name: "address_book".to_string(),
+                description: "Address book of labelled contacts with ENS support. Look up who 'Alice' or 'vitalik.eth' is before sending funds, save new recipients, or resolve ENS names (mainnet). Contacts the user names in their message are accepted as transaction recipients.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+            },
+        }
+    }
+}
+
+impl Default for AddressBookTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AddressBookParams {
+    #[serde(default = "default_action")]
+    action: String,
+    name: Option<String>,
+    label: Option<String>,
+    address: Option<String>,
+    ens_name: Option<String>,
+    networks: Option<Vec<String>>,
+    notes: Option<String>,
+    trust_level: Option<String>,
+}
+
+fn default_action() -> String {
+    "list".to_string()
+}
+
+fn format_contact(c: &Contact) -> String {
+    let mut line = format!("- {} ({}): {}", c.label, c.trust_level.as_str(), c.address);
+    if let Some(ref name) = c.ens_name {
+        line.push_str(&format!(" [{}]", name));
+    }
+    if !c.networks.is_empty() {
+        line.push_str(&format!(" on {}", c.networks.join(", ")));
+    }
+    if let Some(ref notes) = c.notes {
+        line.push_str(&format!(" - {}", notes));
+    }
+    line
+}
+
+/// Trust level the agent may assign (never `trusted`)
+fn agent_trust_level(value: Option<&str>) -> Result<TrustLevel, String> {
+    match value.map(TrustLevel::from_str) {
+        None => Ok(TrustLevel::Known),
+        Some(Some(TrustLevel::Trusted)) => {
+            Err("Only operators can mark contacts as trusted (via the address book API)".to_string())
+        }
+        Some(Some(level)) => Ok(level),
+        Some(None) => Err("trust_level must be 'known' or 'blocked'".to_string()),
+    }
+}
+
+/// Resolve the input's ENS name (if any) on mainnet
+async fn resolve_input_ens(input: &mut ContactInput, context: &ToolContext) -> Result<(), String> {
+    if input.ens_name.is_none() {
+        return Ok(());
+    }
+    let db = context.database.as_ref().ok_or("Database not available")?;
+    let rpc = ens::mainnet_rpc(db, context.wallet_provider.as_ref())?;
+    input.resolve_ens(&rpc).await
+}
+
+#[async_trait]
+impl Tool for AddressBookTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: AddressBookParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+
+        let contacts = match db.list_contacts() {
+            Ok(c) => c,
+            Err(e) => return ToolResult::error(format!("Failed to load address book: {}", e)),
+        };
+        let find = |name: &Option<String>| -> Result<Contact, String> {
+            let name = name.as_deref().ok_or("'name' is required for this action")?;
+            address_book::find_contact(&contacts, name)
+                .cloned()
+                .ok_or_else(|| format!("No contact matches '{}'", name))
+        };
+
+        match params.action.as_str() {
+            "list" => {
+                if contacts.is_empty() {
+                    return ToolResult::success("The address book is empty.");
+                }
+                let lines: Vec<String> = contacts.iter().map(format_contact).collect();
+                ToolResult::success(format!("{} contacts:\n{}", contacts.len(), lines.join("\n")))
+                    .with_metadata(json!({ "contacts": contacts }))
+            }
+
+            "lookup" => match find(&params.name) {
+                Ok(contact) => ToolResult::success(format_contact(&contact))
+                    .with_metadata(json!({ "contact": contact })),
+                Err(e) => ToolResult::error(e),
+            },
+
+            "add" => {
+                let trust_level = match agent_trust_level(params.trust_level.as_deref()) {
+                    Ok(t) => t,
+                    Err(e) => return ToolResult::error(e),
+                };
+                let input = ContactInput {
+                    label: params.label.unwrap_or_default(),
+                    address: params.address,
+                    ens_name: params.ens_name,
+                    networks: params.networks.unwrap_or_default(),
+                    notes: params.notes,
+                    trust_level,
+                };
+                let mut input = match input.normalize() {
+                    Ok(i) => i,
+                    Err(e) => return ToolResult::error(e),
+                };
+                if let Err(e) = resolve_input_ens(&mut input, context).await {
+                    return ToolResult::error(e);
+                }
+                match db.add_contact(&input) {
+                    Ok(contact) => ToolResult::success(format!("Added contact:\n{}", format_contact(&contact)))
+                        .with_metadata(json!({ "contact": contact })),
+                    Err(rusqlite::Error::SqliteFailure(err, _))
+                        if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+                    {
+                        ToolResult::error("A contact with this label or address already exists")
+                    }
+                    Err(e) => ToolResult::error(format!("Failed to add contact: {}", e)),
+                }
+            }
+
+            "update" => {
+                let existing = match find(&params.name) {
+                    Ok(c) => c,
+                    Err(e) => return ToolResult::error(e),
+                };
+                if existing.trust_level == TrustLevel::Trusted {
+                    return ToolResult::error("Trusted contacts can only be changed by an operator");
+                }
+                let trust_level = match params.trust_level.as_deref() {
+                    None => existing.trust_level,
+                    Some(t) => match agent_trust_level(Some(t)) {
+                        Ok(t) => t,
+                        Err(e) => return ToolResult::error(e),
+                    },
+                };
+                // A new ENS name replaces the address unless one is given too
+                let address = match (&params.address, &params.ens_name) {
+                    (Some(a), _) => Some(a.clone()),
+                    (None, Some(_)) => None,
+                    (None, None) => Some(existing.address.clone()),
+                };
+                let input = ContactInput {
+                    label: params.label.unwrap_or(existing.label),
+                    address,
+                    ens_name: params.ens_name.or(existing.ens_name),
+                    networks: params.networks.unwrap_or(existing.networks),
+                    notes: params.notes.or(existing.notes),
+                    trust_level,
+                };
+                let mut input = match input.normalize() {
+                    Ok(i) => i,
+                    Err(e) => return ToolResult::error(e),
+                };
+                if let Err(e) = resolve_input_ens(&mut input, context).await {
+                    return ToolResult::error(e);
+                }
+                match db.update_contact(existing.id, &input) {
+                    Ok(Some(contact)) => ToolResult::success(format!("Updated contact:\n{}", format_contact(&contact)))
+                        .with_metadata(json!({ "contact": contact })),
+                    Ok(None) => ToolResult::error("Contact no longer exists"),
+                    Err(e) => ToolResult::error(format!("Failed to update contact: {}", e)),
+                }
+            }
+
+            "remove" => {
+                let existing = match find(&params.name) {
+                    Ok(c) => c,
+                    Err(e) => return ToolResult::error(e),
+                };
+                if existing.trust_level == TrustLevel::Trusted {
+                    return ToolResult::error("Trusted contacts can only be removed by an operator");
+                }
+                match db.delete_contact(existing.id) {
+                    Ok(_) => ToolResult::success(format!("Removed contact '{}'", existing.label)),
+                    Err(e) => ToolResult::error(format!("Failed to remove contact: {}", e)),
+                }
+            }
+
+            "resolve_ens" => {
+                let name = match params.name.as_deref().map(ens::normalize_name) {
+                    Some(n) if ens::is_ens_name(&n) => n,
+                    _ => return ToolResult::error("'name' must be an ENS name such as 'vitalik.eth'"),
+                };
+                let rpc = match ens::mainnet_rpc(db, context.wallet_provider.as_ref()) {
+                    Ok(r) => r,
+                    Err(e) => return ToolResult::error(e),
+                };
+                let address = match ens::resolve_name(&rpc, &name).await {
+                    Ok(Some(a)) => format!("{:?}", a),
+                    Ok(None) => return ToolResult::error(format!("{} does not resolve to an address", name)),
+                    Err(e) => return ToolResult::error(format!("ENS resolution failed: {}", e)),
+                };
+
+                // Only names the user typed make the resolved address a valid recipient
+                let user_mentioned = context
+                    .context_bank
+                    .items()
+                    .iter()
+                    .any(|i| i.item_type == "ens_name" && i.value == name);
+                if user_mentioned {
+                    context.context_bank.add(ContextBankItem {
+                        value: address.clone(),
+                        item_type: "eth_address".to_string(),
+                        label: Some(name.clone()),
+                    });
+                    if let (Some(broadcaster), Some(channel_id)) = (&context.broadcaster, context.channel_id) {
+                        broadcaster.broadcast(GatewayEvent::context_bank_update(
+                            channel_id,
+                            context.context_bank.to_json(),
+                        ));
+                    }
+                }
+
+                let mut msg = format!("{} resolves to {}", name, address);
+                if let Some(contact) = address_book::find_contact(&contacts, &address) {
+                    msg.push_str(&format!("\nAddress book: {}", format_contact(contact)));
+                }
+                ToolResult::success(msg).with_metadata(json!({
+                    "name": name,
+                    "address": address,
+                    "added_to_context_bank": user_mentioned,
+                }))
+            }
+
+            "reverse_ens" => {
+                let address: Address = match params.name.as_deref().map(str::trim).map(str::parse) {
+                    Some(Ok(a)) => a,
+                    _ => return ToolResult::error("'name' must be an address (0x + 40 hex)"),
+                };
+                let rpc = match ens::mainnet_rpc(db, context.wallet_provider.as_ref()) {
+                    Ok(r) => r,
+                    Err(e) => return ToolResult::error(e),
+                };
+                match ens::lookup_address(&rpc, address).await {
+                    Ok(Some(name)) => ToolResult::success(format!("{:?} is {}", address, name))
+                        .with_metadata(json!({ "address": format!("{:?}", address), "name": name })),
+                    Ok(None) => ToolResult::success(format!("{:?} has no primary ENS name", address))
+                        .with_metadata(json!({ "address": format!("{:?}", address), "name": null })),
+                    Err(e) => ToolResult::error(format!("ENS lookup failed: {}", e)),
+                }
+            }
+
+            other => ToolResult::error(format!(
+                "Unknown action '{}'. Use: list, lookup, add, update, remove, resolve_ens, reverse_ens",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn context() -> ToolContext {
+        let db = Arc::new(crate::db::Database::new(":memory:").unwrap());
+        ToolContext::new().with_database(db)
+    }
+
+    #[tokio::test]
+    async fn test_add_lookup_remove() {
+        let tool = AddressBookTool::new();
+        let ctx = context();
+
+        let result = tool
+            .execute(
+                json!({"action": "add", "label": "Alice", "address": "0x742D35Cc6634C0532925a3b844Bc9e7595f8FdF0", "networks": ["base"]}),
+                &ctx,
+            )
+            .await;
+        assert!(result.success, "{}", result.content);
+
+        let result = tool.execute(json!({"action": "lookup", "name": "alice"}), &ctx).await;
+        assert!(result.success);
+        assert!(result.content.contains("0x742d35cc6634c0532925a3b844bc9e7595f8fdf0"));
+
+        let result = tool
+            .execute(json!({"action": "add", "label": "Alice", "address": "0x1111111111111111111111111111111111111111"}), &ctx)
+            .await;
+        assert!(!result.success, "duplicate label should be rejected");
+
+        let result = tool.execute(json!({"action": "remove", "name": "Alice"}), &ctx).await;
+        assert!(result.success);
+        assert!(ctx.database.as_ref().unwrap().list_contacts().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_agent_cannot_manage_trusted_contacts() {
+        let tool = AddressBookTool::new();
+        let ctx = context();
+
+        let result = tool
+            .execute(
+                json!({"action": "add", "label": "Eve", "address": "0x1111111111111111111111111111111111111111", "trust_level": "trusted"}),
+                &ctx,
+            )
+            .await;
+        assert!(!result.success);
+
+        ctx.database
+            .as_ref()
+            .unwrap()
+            .add_contact(&ContactInput {
+                label: "Treasury".to_string(),
+                address: Some("0x2222222222222222222222222222222222222222".to_string()),
+                trust_level: TrustLevel::Trusted,
+                ..Default::default()
+            })
+            .unwrap();
+        let result = tool
+            .execute(json!({"action": "update", "name": "Treasury", "address": "0x1111111111111111111111111111111111111111"}), &ctx)
+            .await;
+        assert!(!result.success);
+        let result = tool.execute(json!({"action": "remove", "name": "Treasury"}), &ctx).await;
+        assert!(!result.success);
+    }
+}
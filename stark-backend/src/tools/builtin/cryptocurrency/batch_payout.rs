@@ -0,0 +1,674 @@
+//! Batch payout tool - pay many recipients in one transaction
+//!
+//! `preview` validates the recipient list and checks totals against the paying
+//! wallet's balance and the payout policy. `execute` does the same, then queues
+//! one transaction: Multicall3 `aggregate3Value` for native currency from the bot
+//! wallet, or a Safe `execTransaction` into MultiSendCallOnly (or the bot's
+//! `approveHash` when the Safe needs more confirmations). `status` reports the
+//! batch and every recipient. See `crate::payouts` for the validation rules.
+
+use crate::db::tables::broadcasted_transactions::BroadcastedTxStatus;
+use crate::db::tables::payouts::{NewPayoutBatch, PayoutBatch};
+use crate::payouts::{self, PayoutEntry, PayoutRecipient, RecipientRules};
+use crate::swap::{self, SwapToken};
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::{resolve_rpc_from_context, Network};
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::tx_queue::QueuedTxStatus;
+use crate::web3::contract_wallet::get_safe_owners;
+use crate::web3::multicall::{self, encode_aggregate3_value};
+use crate::web3::safe::{self, Operation};
+use crate::web3::{queue_raw_transaction, resolve_network};
+use crate::x402::X402EvmRpc;
+use async_trait::async_trait;
+use ethers::types::{Address, U256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Batch payout tool
+pub struct BatchPayoutTool {
+    definition: ToolDefinition,
+}
+
+impl BatchPayoutTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+        let string_prop = |description: &str| PropertySchema {
+            schema_type: "string".to_string(),
+            description: description.to_string(),
+            default: None,
+            items: None,
+            enum_values: None,
+        };
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'preview' = validate recipients and totals without sending, 'execute' = queue the batch transaction, 'status' = per-recipient status of a batch".to_string(),
+                default: Some(json!("preview")),
+                items: None,
+                enum_values: Some(vec![
+                    "preview".to_string(),
+                    "execute".to_string(),
+                    "status".to_string(),
+                ]),
+            },
+        );
+        properties.insert(
+            "token".to_string(),
+            string_prop("Token symbol (e.g. 'USDC', 'ETH') or address to pay out"),
+        );
+        properties.insert(
+            "recipients".to_string(),
+            string_prop("Recipient list as CSV lines 'address,amount,label' or a JSON array of {address, amount, label}. Address book labels work in place of addresses. Amounts are human-readable (e.g. '12.5')."),
+        );
+        properties.insert(
+            "amount_each".to_string(),
+            string_prop("Amount for recipients without their own amount (required for discord_role)"),
+        );
+        properties.insert(
+            "discord_role".to_string(),
+            string_prop("Pay every member of this Discord role (mention '<@&ID>' or role ID) who registered an address with '@starkbot register'"),
+        );
+        properties.insert(
+            "discord_guild_id".to_string(),
+            string_prop("Discord server ID for discord_role (defaults to the discord_server_id register)"),
+        );
+        properties.insert(
+            "safe".to_string(),
+            string_prop("Safe address to pay from (the bot wallet must be an owner). Required for ERC-20 payouts; native payouts default to the bot wallet."),
+        );
+        properties.insert(
+            "network".to_string(),
+            string_prop("Network (defaults to the selected network)"),
+        );
+        properties.insert(
+            "batch_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Batch ID for status (defaults to the latest batch)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        BatchPayoutTool {
+            definition: ToolDefinition {
+                name: "batch_payout".to_string(),
+                description: "Pay many recipients in a single transaction (contributor payouts, airdrops, tipping a Discord role). Validates and dedupes recipients, checks the total against balance and payout limits, and reports per-recipient status. Always preview first and confirm the list with the user before execute.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+            },
+        }
+    }
+}
+
+impl Default for BatchPayoutTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchPayoutParams {
+    #[serde(default = "default_action")]
+    action: String,
+    token: Option<String>,
+    recipients: Option<String>,
+    amount_each: Option<String>,
+    discord_role: Option<String>,
+    discord_guild_id: Option<String>,
+    safe: Option<String>,
+    network: Option<String>,
+    batch_id: Option<i64>,
+}
+
+fn default_action() -> String {
+    "preview".to_string()
+}
+
+/// Validated batch, ready to preview or execute
+struct PreparedBatch {
+    network: Network,
+    rpc: X402EvmRpc,
+    token: SwapToken,
+    bot: Address,
+    /// Paying Safe (None = bot wallet)
+    safe: Option<Address>,
+    recipients: Vec<PayoutRecipient>,
+    total: U256,
+    total_usd: Option<f64>,
+    balance: U256,
+}
+
+impl PreparedBatch {
+    fn source(&self) -> Address {
+        self.safe.unwrap_or(self.bot)
+    }
+
+    fn included(&self) -> usize {
+        self.recipients.iter().filter(|r| r.is_included()).count()
+    }
+
+    fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "Batch payout of {} {} to {} recipients on {} from {:?} ({})",
+            swap::format_units(self.total, self.token.decimals),
+            self.token.symbol,
+            self.included(),
+            self.network,
+            self.source(),
+            if self.safe.is_some() { "Safe" } else { "bot wallet" },
+        )];
+        if let Some(usd) = self.total_usd {
+            lines.push(format!("Total value: ${:.2}", usd));
+        }
+        lines.push(format!(
+            "Balance: {} {}",
+            swap::format_units(self.balance, self.token.decimals),
+            self.token.symbol
+        ));
+        lines.push(String::new());
+        lines.extend(self.recipients.iter().map(|r| recipient_line(r, None)));
+        lines.join("\n")
+    }
+}
+
+fn recipient_line(r: &PayoutRecipient, batch_status: Option<&str>) -> String {
+    let who = match &r.label {
+        Some(label) if !r.address.is_empty() => format!("{} ({})", r.address, label),
+        _ if !r.address.is_empty() => r.address.clone(),
+        _ => r.input.clone(),
+    };
+    match &r.reason {
+        Some(reason) => format!("- {} {}: skipped - {}", who, r.amount, reason),
+        None => format!("- {} {}: {}", who, r.amount, batch_status.unwrap_or("ok")),
+    }
+}
+
+/// Addresses the user supplied in this conversation (context bank, registers)
+fn user_supplied_addresses(context: &ToolContext) -> HashSet<String> {
+    let mut known: HashSet<String> = context
+        .context_bank
+        .items()
+        .into_iter()
+        .filter(|i| i.item_type == "eth_address" || i.item_type == "contact")
+        .map(|i| i.value.to_lowercase())
+        .collect();
+    known.extend(
+        context
+            .registers
+            .keys()
+            .iter()
+            .filter_map(|key| context.registers.get(key))
+            .filter_map(|v| v.as_str().map(str::to_lowercase))
+            .filter(|s| s.starts_with("0x") && s.len() == 42),
+    );
+    known
+}
+
+/// Raw balance of `owner` in the paid token
+async fn token_balance(rpc: &X402EvmRpc, token: &SwapToken, owner: Address) -> Result<U256, String> {
+    if token.is_native() {
+        return rpc.get_balance(owner).await;
+    }
+    let data = rpc.call(token.address, &multicall::encode_balance_of(owner)).await?;
+    if data.len() < 32 {
+        return Err(format!("Failed to read {} balance", token.symbol));
+    }
+    Ok(U256::from_big_endian(&data[..32]))
+}
+
+async fn prepare(params: &BatchPayoutParams, context: &ToolContext) -> Result<PreparedBatch, String> {
+    let wallet_provider = context
+        .wallet_provider
+        .as_ref()
+        .ok_or_else(|| "Wallet not configured. A wallet is required for payouts.".to_string())?;
+    let db = context.database.as_ref().ok_or("Database not available")?;
+    let network = resolve_network(params.network.as_deref(), context.selected_network.as_deref())?;
+
+    let rpc_config = resolve_rpc_from_context(&context.extra, network.as_ref());
+    let rpc = X402EvmRpc::new_with_wallet_provider(
+        wallet_provider.clone(),
+        network.as_ref(),
+        Some(rpc_config.url.clone()),
+        rpc_config.use_x402,
+    )?;
+
+    let token_param = params.token.as_deref().ok_or("'token' is required")?;
+    let token = swap::resolve_token(&network, token_param, &rpc).await?;
+
+    let wallet = wallet_provider.get_address();
+    let bot: Address = wallet.parse().map_err(|_| format!("Invalid wallet address: {}", wallet))?;
+
+    let safe = match params.safe.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(s) => {
+            let safe: Address = s.parse().map_err(|_| format!("Invalid Safe address: {}", s))?;
+            let owners = get_safe_owners(&rpc, safe)
+                .await
+                .map_err(|e| format!("Failed to read Safe owners of {:?}: {}", safe, e))?;
+            if !owners.contains(&bot) {
+                return Err(format!("The bot wallet {} is not an owner of Safe {:?}", wallet, safe));
+            }
+            Some(safe)
+        }
+        None if !token.is_native() => {
+            return Err(format!(
+                "ERC-20 batch payouts must be paid from a Safe the bot co-owns (pass 'safe'). \
+                 Batching {} transfers from the bot wallet would need an allowance anyone could spend.",
+                token.symbol
+            ));
+        }
+        None => None,
+    };
+
+    let mut entries: Vec<PayoutEntry> = match params.recipients.as_deref() {
+        Some(text) => payouts::parse_recipients(text)?,
+        None => Vec::new(),
+    };
+    let mut known = user_supplied_addresses(context);
+
+    if let Some(role) = params.discord_role.as_deref() {
+        if params.amount_each.is_none() {
+            return Err("'amount_each' is required when paying a Discord role".to_string());
+        }
+        let guild_id = params
+            .discord_guild_id
+            .clone()
+            .or_else(|| context.registers.get("discord_server_id").and_then(|v| v.as_str().map(str::to_string)))
+            .ok_or("'discord_guild_id' is required for discord_role (or run discord_read channelList first)")?;
+        let bot_token = context
+            .find_channel_bot_token("discord", "discord_bot_token")
+            .ok_or("Discord bot token not available. Configure it in your Discord channel settings.")?;
+        let role_entries = payouts::discord_role_recipients(db, &bot_token, &guild_id, role).await?;
+        // Registered addresses come from the users themselves, not from the model
+        known.extend(role_entries.iter().map(|e| e.address.to_lowercase()));
+        entries.extend(role_entries);
+    }
+
+    if entries.is_empty() {
+        return Err("No recipients. Provide 'recipients' and/or 'discord_role'.".to_string());
+    }
+
+    let contacts = db.list_contacts().map_err(|e| format!("Failed to load address book: {}", e))?;
+    let source = safe.unwrap_or(bot);
+    let rules = RecipientRules {
+        network: network.as_ref(),
+        token: (!token.is_native()).then_some(token.address),
+        decimals: token.decimals,
+        source,
+        contacts: &contacts,
+        known_addresses: &known,
+        amount_each: params.amount_each.as_deref(),
+    };
+    let recipients = payouts::validate_recipients(&entries, &rules);
+    let total = payouts::total_raw(&recipients);
+
+    let balance = token_balance(&rpc, &token, source).await?;
+    let total_usd = if total.is_zero() {
+        None
+    } else {
+        payouts::token_price_usd(network.as_ref(), &token)
+            .await
+            .map(|price| swap::format_units(total, token.decimals).parse::<f64>().unwrap_or(0.0) * price)
+    };
+
+    Ok(PreparedBatch {
+        network,
+        rpc,
+        token,
+        bot,
+        safe,
+        recipients,
+        total,
+        total_usd,
+        balance,
+    })
+}
+
+/// Checks shared by preview and execute
+fn check_batch(batch: &PreparedBatch) -> Result<(), String> {
+    payouts::check_policy(batch.included(), batch.total_usd)?;
+    if batch.balance < batch.total {
+        return Err(format!(
+            "Insufficient balance: paying {} {} but {:?} holds {} {}",
+            swap::format_units(batch.total, batch.token.decimals),
+            batch.token.symbol,
+            batch.source(),
+            swap::format_units(batch.balance, batch.token.decimals),
+            batch.token.symbol
+        ));
+    }
+    Ok(())
+}
+
+/// Map the queued transaction's state to a batch status
+fn batch_status(batch: &PayoutBatch, context: &ToolContext) -> String {
+    let Some(ref uuid) = batch.tx_uuid else {
+        return batch.status.clone();
+    };
+    let tx_status = match context.tx_queue.as_ref().and_then(|q| q.get(uuid)) {
+        Some(tx) => Some(tx.status),
+        None => context
+            .database
+            .as_ref()
+            .and_then(|db| db.get_broadcasted_transaction(uuid).ok().flatten())
+            .map(|tx| match tx.status {
+                BroadcastedTxStatus::Confirmed => QueuedTxStatus::Confirmed,
+                BroadcastedTxStatus::Failed => QueuedTxStatus::Failed,
+                BroadcastedTxStatus::Broadcast => QueuedTxStatus::Broadcast,
+            }),
+    };
+    // The approveHash of a multi-owner Safe only adds the bot's confirmation
+    let awaiting_signatures = batch.status == "awaiting_signatures";
+    match tx_status {
+        Some(QueuedTxStatus::Pending) if !awaiting_signatures => "queued".to_string(),
+        Some(QueuedTxStatus::Broadcasting) | Some(QueuedTxStatus::Broadcast) if !awaiting_signatures => {
+            "broadcast".to_string()
+        }
+        Some(QueuedTxStatus::Confirmed) if !awaiting_signatures => "confirmed".to_string(),
+        Some(QueuedTxStatus::Failed) | Some(QueuedTxStatus::Expired) => "failed".to_string(),
+        _ => batch.status.clone(),
+    }
+}
+
+/// Per-recipient status wording for a batch status
+fn recipient_status(status: &str) -> &'static str {
+    match status {
+        "confirmed" => "paid",
+        "failed" => "failed (batch reverted or was not sent)",
+        "awaiting_signatures" => "waiting for Safe owners",
+        _ => "pending",
+    }
+}
+
+impl BatchPayoutTool {
+    async fn preview(&self, params: &BatchPayoutParams, context: &ToolContext) -> ToolResult {
+        let batch = match prepare(params, context).await {
+            Ok(b) => b,
+            Err(e) => return ToolResult::error(e),
+        };
+        let check = check_batch(&batch);
+        let mut content = format!("PAYOUT PREVIEW (nothing sent)\n\n{}", batch.summary());
+        match &check {
+            Ok(()) => content.push_str("\n\nChecks passed. Confirm the list with the user, then call batch_payout with action 'execute' and the same parameters."),
+            Err(e) => content.push_str(&format!("\n\nCANNOT EXECUTE: {}", e)),
+        }
+        ToolResult::success(content).with_metadata(json!({
+            "network": batch.network.to_string(),
+            "token": batch.token.symbol,
+            "source": format!("{:?}", batch.source()),
+            "total_raw": batch.total.to_string(),
+            "total": swap::format_units(batch.total, batch.token.decimals),
+            "total_usd": batch.total_usd,
+            "recipients": batch.recipients,
+            "executable": check.is_ok(),
+        }))
+    }
+
+    async fn execute_batch(&self, params: &BatchPayoutParams, context: &ToolContext) -> ToolResult {
+        let batch = match prepare(params, context).await {
+            Ok(b) => b,
+            Err(e) => return ToolResult::error(e),
+        };
+        if let Err(e) = check_batch(&batch) {
+            return ToolResult::error(format!("Payout blocked: {}\n\n{}", e, batch.summary()));
+        }
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+
+        let description = format!(
+            "Batch payout of {} {} to {} recipients on {}",
+            swap::format_units(batch.total, batch.token.decimals),
+            batch.token.symbol,
+            batch.included(),
+            batch.network
+        );
+
+        // (to, calldata, value, method, batch status, Safe tx hash)
+        let (to, calldata, value, method, status, safe_tx_hash) = match batch.safe {
+            None => (
+                multicall::multicall3_address(),
+                encode_aggregate3_value(&payouts::native_calls(&batch.recipients)),
+                batch.total,
+                "multicall3",
+                "queued",
+                None,
+            ),
+            Some(safe_address) => {
+                let call = match safe::prepare_owner_call(
+                    &batch.rpc,
+                    safe_address,
+                    batch.bot,
+                    safe::multi_send_call_only_address(),
+                    U256::zero(),
+                    safe::encode_multi_send(&payouts::multi_send_calls(&batch.token, &batch.recipients)),
+                    Operation::DelegateCall,
+                )
+                .await
+                {
+                    Ok(call) => call,
+                    Err(e) => return ToolResult::error(e),
+                };
+                (
+                    safe_address,
+                    call.calldata,
+                    U256::zero(),
+                    "safe_multisend",
+                    if call.executes { "queued" } else { "awaiting_signatures" },
+                    Some(call.safe_tx_hash),
+                )
+            }
+        };
+
+        let (uuid, signed) = match queue_raw_transaction(
+            &batch.network,
+            to,
+            calldata,
+            value,
+            "batch_payout",
+            description.clone(),
+            None,
+            context,
+        )
+        .await
+        {
+            Ok(queued) => queued,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let safe_tx_hash = safe_tx_hash.map(|h| format!("{:?}", h));
+        let total_raw = batch.total.to_string();
+        let token_address = format!("{:?}", batch.token.address);
+        let source_address = format!("{:?}", batch.source());
+        let batch_id = match db.insert_payout_batch(&NewPayoutBatch {
+            network: batch.network.as_ref(),
+            token_symbol: &batch.token.symbol,
+            token_address: &token_address,
+            decimals: batch.token.decimals,
+            source_address: &source_address,
+            method,
+            total_raw: &total_raw,
+            total_usd: batch.total_usd,
+            recipients: &batch.recipients,
+            tx_uuid: Some(&uuid),
+            safe_tx_hash: safe_tx_hash.as_deref(),
+            status,
+            channel_id: context.channel_id,
+        }) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                log::error!("[batch_payout] Failed to record batch {}: {}", uuid, e);
+                None
+            }
+        };
+
+        let next_steps = if status == "awaiting_signatures" {
+            format!(
+                "This queues the bot's on-chain approval (approveHash) of Safe tx {}.\n\
+                 To broadcast: use `broadcast_web3_tx` with uuid: {}\n\
+                 The Safe needs more confirmations: other owners approve the same hash, then anyone \
+                 calls execTransaction (see the safe_wallet skill). Safe nonce and MultiSend data are in the metadata.",
+                safe_tx_hash.as_deref().unwrap_or_default(),
+                uuid
+            )
+        } else {
+            format!("To broadcast: use `broadcast_web3_tx` with uuid: {}", uuid)
+        };
+
+        ToolResult::success(format!(
+            "PAYOUT QUEUED (not yet broadcast)\n\n\
+            Batch ID: {}\n\
+            UUID: {}\n\
+            {}\n\n\
+            --- Next Steps ---\n\
+            {}\n\
+            Check per-recipient status with batch_payout action 'status'.",
+            batch_id.map(|id| id.to_string()).unwrap_or_else(|| "n/a".to_string()),
+            uuid,
+            batch.summary(),
+            next_steps
+        ))
+        .with_metadata(json!({
+            "batch_id": batch_id,
+            "uuid": uuid,
+            "status": status,
+            "method": method,
+            "safe_tx_hash": safe_tx_hash,
+            "from": signed.from,
+            "to": signed.to,
+            "value": signed.value,
+            "nonce": signed.nonce,
+            "network": signed.network,
+            "total_raw": total_raw,
+            "recipients": batch.recipients,
+        }))
+    }
+
+    fn status(&self, params: &BatchPayoutParams, context: &ToolContext) -> ToolResult {
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let batch = match params.batch_id {
+            Some(id) => db.get_payout_batch(id),
+            None => db.list_payout_batches(1).map(|b| b.into_iter().next()),
+        };
+        let batch = match batch {
+            Ok(Some(b)) => b,
+            Ok(None) => return ToolResult::error("No payout batch found"),
+            Err(e) => return ToolResult::error(format!("Failed to load payout batch: {}", e)),
+        };
+
+        let status = batch_status(&batch, context);
+        if status != batch.status {
+            let _ = db
+                .update_payout_batch_status(batch.id, &status)
+                .map_err(|e| log::warn!("[batch_payout] Failed to update batch {} status: {}", batch.id, e));
+        }
+
+        let per_recipient = recipient_status(&status);
+        let lines: Vec<String> = batch
+            .recipients
+            .iter()
+            .map(|r| recipient_line(r, Some(per_recipient)))
+            .collect();
+        let total = U256::from_dec_str(&batch.total_raw).unwrap_or_default();
+        ToolResult::success(format!(
+            "Batch {} ({}): {} {} on {} from {}\n\n{}",
+            batch.id,
+            status,
+            swap::format_units(total, batch.decimals),
+            batch.token_symbol,
+            batch.network,
+            batch.source_address,
+            lines.join("\n")
+        ))
+        .with_metadata(json!({
+            "batch": batch,
+            "status": status,
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for BatchPayoutTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: BatchPayoutParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        match params.action.as_str() {
+            "preview" => self.preview(&params, context).await,
+            "execute" => self.execute_batch(&params, context).await,
+            "status" => self.status(&params, context),
+            other => ToolResult::error(format!(
+                "Unknown action '{}'. Use: preview, execute, status",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payouts::RecipientStatus;
+
+    #[test]
+    fn test_recipient_lines() {
+        let mut r = PayoutRecipient {
+            input: "bob".to_string(),
+            address: "0x1111111111111111111111111111111111111111".to_string(),
+            label: Some("Bob".to_string()),
+            amount: "5".to_string(),
+            raw_amount: "5000000".to_string(),
+            status: RecipientStatus::Included,
+            reason: None,
+        };
+        assert_eq!(
+            recipient_line(&r, Some(recipient_status("confirmed"))),
+            "- 0x1111111111111111111111111111111111111111 (Bob) 5: paid"
+        );
+        r.status = RecipientStatus::Skipped;
+        r.reason = Some("duplicate of row 1".to_string());
+        assert!(recipient_line(&r, Some("paid")).ends_with("skipped - duplicate of row 1"));
+    }
+
+    #[test]
+    fn test_status_without_batch() {
+        let db = std::sync::Arc::new(crate::db::Database::new(":memory:").unwrap());
+        let ctx = ToolContext::new().with_database(db);
+        let result = BatchPayoutTool::new().status(
+            &BatchPayoutParams {
+                action: "status".to_string(),
+                token: None,
+                recipients: None,
+                amount_each: None,
+                discord_role: None,
+                discord_guild_id: None,
+                safe: None,
+                network: None,
+                batch_id: None,
+            },
+            &ctx,
+        );
+        assert!(!result.success);
+    }
+}
@@ -511,6 +511,7 @@ impl Tool for BridgeUsdcTool {
             preset_name: None,
             destination_chain: Some(params.to_chain.clone()),
             calldata: None,
+            swap_sell: None,
             description: format!(
                 "Bridge {} USDC from {} to {} via Across Protocol, recipient {}",
                 params.amount, params.from_chain, params.to_chain, recipient,
@@ -155,6 +155,7 @@ impl Tool for BroadcastWeb3TxTool {
             if let (Some(broadcaster), Some(ch_id)) = (&context.broadcaster, context.channel_id) {
                 broadcaster.broadcast(GatewayEvent::tx_queue_confirmation_required(
                     ch_id,
+                    context.platform_chat_id.as_deref(),
                     &queued_tx.uuid,
                     &queued_tx.network,
                     &queued_tx.from,
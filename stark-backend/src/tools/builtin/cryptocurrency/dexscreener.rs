@@ -204,6 +204,61 @@ struct Boost {
     url: Option<String>,
 }
 
+/// Map our network names to DexScreener chain ids
+fn normalize_chain(network: &str) -> &str {
+    match network {
+        "mainnet" | "eth" => "ethereum",
+        "matic" => "polygon",
+        _ => network,
+    }
+}
+
+/// Fetch the USD price of a token from its most liquid DexScreener pair.
+///
+/// Only pairs where the token is the base token are considered, since
+/// `priceUsd` is always quoted for the base side. Returns `Ok(None)` when
+/// no pair is found.
+pub async fn fetch_token_price_usd(network: &str, address: &str) -> Result<Option<f64>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .user_agent("StarkBot/1.0")
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+
+    let url = format!("{}/tokens/v1/{}/{}", BASE_URL, normalize_chain(network), address);
+
+    let resp = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !resp.status().is_success() {
+        return Err(format!("API error: {}", resp.status()));
+    }
+
+    let pairs: Vec<Pair> = resp
+        .json()
+        .await
+        .map_err(|e| format!("Parse error: {}", e))?;
+
+    let best = pairs
+        .iter()
+        .filter(|p| {
+            p.base_token
+                .as_ref()
+                .and_then(|t| t.address.as_deref())
+                .is_some_and(|a| a.eq_ignore_ascii_case(address))
+        })
+        .filter_map(|p| {
+            let price = p.price_usd.as_deref()?.parse::<f64>().ok()?;
+            Some((price, p.liquidity.usd.unwrap_or(0.0)))
+        })
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok(best.map(|(price, _)| price))
+}
+
 fn format_number(n: f64) -> String {
     if n >= 1_000_000_000.0 {
         format!("{:.2}B", n / 1_000_000_000.0)
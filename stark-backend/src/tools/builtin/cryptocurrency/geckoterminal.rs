@@ -165,6 +165,80 @@ fn parse_f64(s: &str) -> Option<f64> {
     s.parse::<f64>().ok()
 }
 
+/// Max addresses GeckoTerminal accepts per simple token_price request
+const MAX_PRICE_ADDRESSES: usize = 30;
+
+#[derive(Debug, Deserialize)]
+struct TokenPriceResponse {
+    data: Option<TokenPriceData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenPriceData {
+    attributes: Option<TokenPriceAttributes>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenPriceAttributes {
+    #[serde(default)]
+    token_prices: HashMap<String, Option<String>>,
+}
+
+/// Fetch USD prices for a set of token contract addresses on one network.
+///
+/// Returns a map keyed by lowercase address. Tokens GeckoTerminal has no
+/// price for are simply absent from the map.
+pub async fn fetch_token_prices_usd(
+    network: &str,
+    addresses: &[String],
+) -> Result<HashMap<String, f64>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .user_agent("StarkBot/1.0")
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+
+    let normalized = normalize_network(network);
+    let mut prices = HashMap::new();
+
+    for chunk in addresses.chunks(MAX_PRICE_ADDRESSES) {
+        let joined = chunk
+            .iter()
+            .map(|a| a.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(",");
+        let url = format!(
+            "{}/simple/networks/{}/token_price/{}",
+            API_BASE, normalized, joined
+        );
+
+        let resp = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("GeckoTerminal request failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            return Err(format!("GeckoTerminal API error: {}", resp.status()));
+        }
+
+        let data: TokenPriceResponse = resp
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        if let Some(attrs) = data.data.and_then(|d| d.attributes) {
+            for (address, price) in attrs.token_prices {
+                if let Some(p) = price.as_deref().and_then(parse_f64) {
+                    prices.insert(address.to_lowercase(), p);
+                }
+            }
+        }
+    }
+
+    Ok(prices)
+}
+
 fn format_pool_output(pool: &PoolData, output_type: ChannelOutputType, network_fallback: Option<&str>) -> Option<String> {
     let attrs = pool.attributes.as_ref()?;
     let name = attrs.name.as_deref().unwrap_or("Unknown Pool");
@@ -3,19 +3,27 @@
 //! Tools for interacting with blockchain networks, EVM transactions,
 //! token operations, x402 payment protocol, and prediction markets.
 
+mod aave;
+mod address_book;
+mod batch_payout;
 mod bridge_usdc;
 mod broadcast_web3_tx;
 pub mod verify_intent;
 mod verify_tx_broadcast;
 mod decode_calldata;
-mod dexscreener;
-mod geckoterminal;
+pub mod dexscreener;
+pub mod geckoterminal;
 mod list_queued_web3_tx;
 pub mod network_lookup;
+pub mod polymarket_signing;
+mod polymarket_positions;
 mod polymarket_trade;
+mod portfolio;
 mod select_web3_network;
 mod set_address;
+mod swap;
 mod to_raw_amount;
+mod token_approvals;
 pub mod token_lookup;
 mod web3_function_call;
 mod web3_preset_function_call;
@@ -27,6 +35,9 @@ mod x402_post;
 mod x402_rpc;
 
 pub use erc8128_fetch::Erc8128FetchTool;
+pub use aave::AaveTool;
+pub use address_book::AddressBookTool;
+pub use batch_payout::BatchPayoutTool;
 pub use bridge_usdc::BridgeUsdcTool;
 pub use broadcast_web3_tx::BroadcastWeb3TxTool;
 pub use decode_calldata::DecodeCalldataTool;
@@ -34,10 +45,14 @@ pub use dexscreener::DexScreenerTool;
 pub use geckoterminal::GeckoTerminalTool;
 pub use list_queued_web3_tx::ListQueuedWeb3TxTool;
 pub use network_lookup::load_networks;
+pub use polymarket_positions::PolymarketPositionsTool;
 pub use polymarket_trade::PolymarketTradeTool;
+pub use portfolio::PortfolioTool;
 pub use set_address::SetAddressTool;
 pub use select_web3_network::SelectWeb3NetworkTool;
+pub use swap::{SwapQuoteTool, SwapTool};
 pub use to_raw_amount::ToRawAmountTool;
+pub use token_approvals::TokenApprovalsTool;
 pub use token_lookup::{load_tokens, TokenLookupTool};
 pub use web3_preset_function_call::Web3PresetFunctionCallTool;
 pub use verify_tx_broadcast::VerifyTxBroadcastTool;
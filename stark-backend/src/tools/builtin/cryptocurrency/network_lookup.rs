@@ -1,15 +1,15 @@
 //! Network Lookup for context bank scanning
 //!
-//! Loads network configuration from config/networks.ron at startup.
+//! Loads network configuration from config/networks.ron at startup; it can be reloaded at runtime.
 //! Used by context bank to detect network names in user input.
 
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::RwLock;
 
-/// Global network storage (loaded once at startup)
-static NETWORKS: OnceLock<HashMap<String, NetworkInfo>> = OnceLock::new();
+/// Global network storage (loaded at startup, swapped as a whole on reload)
+static NETWORKS: RwLock<Option<HashMap<String, NetworkInfo>>> = RwLock::new(None);
 
 /// Network info loaded from config
 #[derive(Debug, Clone, Deserialize)]
@@ -22,54 +22,56 @@ pub struct NetworkInfo {
 
 /// Load networks from config directory. Logs warning if config file is missing.
 pub fn load_networks(config_dir: &Path) {
-    let networks_path = config_dir.join("networks.ron");
-
-    if !networks_path.exists() {
-        log::warn!("[networks] Config file not found: {:?}, using defaults", networks_path);
-        let mut defaults = HashMap::new();
-        defaults.insert("base".to_string(), NetworkInfo {
-            name: "Base".to_string(),
-            chain_id: 8453,
-            aliases: vec!["base mainnet".to_string()],
-        });
-        defaults.insert("mainnet".to_string(), NetworkInfo {
-            name: "Ethereum Mainnet".to_string(),
-            chain_id: 1,
-            aliases: vec!["ethereum".to_string(), "eth".to_string()],
-        });
-        let _ = NETWORKS.set(defaults);
-        return;
+    if let Err(e) = reload_networks(config_dir) {
+        log::error!("[networks] {}", e);
     }
+}
 
-    let content = match std::fs::read_to_string(&networks_path) {
-        Ok(c) => c,
-        Err(e) => {
-            log::error!("[networks] Failed to read {:?}: {}", networks_path, e);
-            return;
-        }
-    };
+/// Parse networks.ron and swap it in. On error the current networks stay loaded.
+pub fn reload_networks(config_dir: &Path) -> Result<usize, String> {
+    let networks_path = config_dir.join("networks.ron");
 
-    let networks: HashMap<String, NetworkInfo> = match ron::from_str(&content) {
-        Ok(n) => n,
-        Err(e) => {
-            log::error!("[networks] Failed to parse {:?}: {}", networks_path, e);
-            return;
-        }
+    let networks = if networks_path.exists() {
+        let content = std::fs::read_to_string(&networks_path)
+            .map_err(|e| format!("Failed to read {:?}: {}", networks_path, e))?;
+        let networks: HashMap<String, NetworkInfo> = ron::from_str(&content)
+            .map_err(|e| format!("Failed to parse {:?}: {}", networks_path, e))?;
+        log::info!(
+            "[networks] Loaded {} networks from {:?}",
+            networks.len(),
+            networks_path
+        );
+        networks
+    } else {
+        log::warn!("[networks] Config file not found: {:?}, using defaults", networks_path);
+        default_networks()
     };
 
-    log::info!(
-        "[networks] Loaded {} networks from {:?}",
-        networks.len(),
-        networks_path
-    );
+    let count = networks.len();
+    *NETWORKS.write().unwrap_or_else(|e| e.into_inner()) = Some(networks);
+    Ok(count)
+}
 
-    let _ = NETWORKS.set(networks);
+fn default_networks() -> HashMap<String, NetworkInfo> {
+    let mut defaults = HashMap::new();
+    defaults.insert("base".to_string(), NetworkInfo {
+        name: "Base".to_string(),
+        chain_id: 8453,
+        aliases: vec!["base mainnet".to_string()],
+    });
+    defaults.insert("mainnet".to_string(), NetworkInfo {
+        name: "Ethereum Mainnet".to_string(),
+        chain_id: 1,
+        aliases: vec!["ethereum".to_string(), "eth".to_string()],
+    });
+    defaults
 }
 
 /// Get all network identifiers with their names (for context bank scanning)
 /// Returns a list of (identifier, display_name) pairs including aliases
 pub fn get_all_network_identifiers() -> Vec<(String, String)> {
-    let networks = match NETWORKS.get() {
+    let guard = NETWORKS.read().unwrap_or_else(|e| e.into_inner());
+    let networks = match guard.as_ref() {
         Some(n) => n,
         None => return Vec::new(),
     };
@@ -0,0 +1,460 @@
+//! Polymarket position manager tool
+//!
+//! Stores exit rules per outcome token position (take-profit, stop-loss,
+//! expiry-based exit) that the scheduler evaluates and executes (see
+//! `crate::polymarket`), and redeems winning positions after resolution.
+//!
+//! ## Actions
+//! - `set_exit`: Create an exit rule for a position
+//! - `list_exits`: List exit rules (optionally by status)
+//! - `cancel_exit`: Cancel an active exit rule
+//! - `check_exits`: Evaluate active rules now instead of waiting for the next tick
+//! - `redeem`: Queue a redeem transaction for a resolved market
+
+use crate::db::tables::polymarket::NewPolymarketExitRule;
+use crate::polymarket;
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::{resolve_rpc_from_context, Network};
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::web3::{default_abis_dir, execute_resolved_call};
+use crate::x402::X402EvmRpc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ethers::types::Address;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Polymarket position manager tool
+pub struct PolymarketPositionsTool {
+    definition: ToolDefinition,
+}
+
+impl PolymarketPositionsTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'set_exit' = create an exit rule for a position, 'list_exits' = list exit rules, 'cancel_exit' = cancel a rule, 'check_exits' = evaluate active rules now, 'redeem' = queue a redeem tx for a resolved market".to_string(),
+                default: None,
+                items: None,
+                enum_values: Some(vec![
+                    "set_exit".to_string(),
+                    "list_exits".to_string(),
+                    "cancel_exit".to_string(),
+                    "check_exits".to_string(),
+                    "redeem".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "token_id".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Outcome token ID of the position (set_exit)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "take_profit".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Sell when the best bid reaches this price (0-1)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "stop_loss".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Sell when the best bid falls to this price (0-1)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "exit_at".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Sell at this time regardless of price (RFC 3339, e.g. '2026-11-03T12:00:00Z')".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "size".to_string(),
+            PropertySchema {
+                schema_type: "number".to_string(),
+                description: "Shares to sell when triggered (default: the whole position)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "market".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Market question, for display (set_exit)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "outcome".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Outcome name, e.g. 'Yes' (set_exit)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "rule_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Exit rule ID (cancel_exit)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "status".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Filter for list_exits: 'active', 'triggered', 'failed', 'cancelled' (default: all)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        properties.insert(
+            "condition_id".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Condition ID of the resolved market (redeem)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        PolymarketPositionsTool {
+            definition: ToolDefinition {
+                name: "polymarket_positions".to_string(),
+                description: "Manage Polymarket positions: set take-profit / stop-loss / time-based exit rules that are checked automatically and sold within the automation limits, and redeem winning positions after a market resolves.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec!["action".to_string()],
+                },
+                group: ToolGroup::Finance,
+            },
+        }
+    }
+}
+
+impl Default for PolymarketPositionsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PositionsParams {
+    action: String,
+    token_id: Option<String>,
+    take_profit: Option<f64>,
+    stop_loss: Option<f64>,
+    exit_at: Option<String>,
+    size: Option<f64>,
+    market: Option<String>,
+    outcome: Option<String>,
+    rule_id: Option<i64>,
+    status: Option<String>,
+    condition_id: Option<String>,
+}
+
+fn format_price(price: Option<f64>) -> String {
+    price.map_or("-".to_string(), |p| format!("{:.3}", p))
+}
+
+impl PolymarketPositionsTool {
+    fn set_exit(&self, params: PositionsParams, context: &ToolContext) -> ToolResult {
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let token_id = match params.token_id {
+            Some(t) if !t.trim().is_empty() => t.trim().to_string(),
+            _ => return ToolResult::error("token_id is required for set_exit"),
+        };
+        let exit_at = match params.exit_at.as_deref().map(DateTime::parse_from_rfc3339) {
+            Some(Ok(t)) => Some(t.with_timezone(&Utc)),
+            Some(Err(e)) => return ToolResult::error(format!("Invalid exit_at (expected RFC 3339): {}", e)),
+            None => None,
+        };
+        if let Err(e) = polymarket::validate_rule(params.take_profit, params.stop_loss, exit_at, params.size) {
+            return ToolResult::error(e);
+        }
+
+        let new_rule = NewPolymarketExitRule {
+            token_id,
+            market: params.market,
+            outcome: params.outcome,
+            size: params.size,
+            take_profit: params.take_profit,
+            stop_loss: params.stop_loss,
+            exit_at,
+        };
+        match db.create_polymarket_exit_rule(&new_rule) {
+            Ok(rule) => {
+                let mut conditions = Vec::new();
+                if let Some(tp) = rule.take_profit {
+                    conditions.push(format!("take-profit at {:.3}", tp));
+                }
+                if let Some(sl) = rule.stop_loss {
+                    conditions.push(format!("stop-loss at {:.3}", sl));
+                }
+                if let Some(at) = rule.exit_at {
+                    conditions.push(format!("exit at {}", at.to_rfc3339()));
+                }
+                ToolResult::success(format!(
+                    "Exit rule #{} created for {}: {}. Sells {} when triggered.",
+                    rule.id,
+                    rule.market.as_deref().unwrap_or(&rule.token_id),
+                    conditions.join(", "),
+                    rule.size.map_or("the whole position".to_string(), |s| format!("{} shares", s)),
+                ))
+                .with_metadata(json!({ "rule": rule }))
+            }
+            Err(e) => ToolResult::error(format!("Failed to save exit rule: {}", e)),
+        }
+    }
+
+    fn list_exits(&self, params: PositionsParams, context: &ToolContext) -> ToolResult {
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let rules = match db.list_polymarket_exit_rules(params.status.as_deref()) {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(format!("Failed to load exit rules: {}", e)),
+        };
+        if rules.is_empty() {
+            return ToolResult::success("No Polymarket exit rules found.");
+        }
+
+        let mut lines = vec![format!("Polymarket exit rules ({}):", rules.len())];
+        for rule in &rules {
+            let mut line = format!(
+                "#{} [{}] {}{} - TP {} / SL {} / exit {} (last bid {})",
+                rule.id,
+                rule.status,
+                rule.market.as_deref().unwrap_or(&rule.token_id),
+                rule.outcome.as_deref().map(|o| format!(" ({})", o)).unwrap_or_default(),
+                format_price(rule.take_profit),
+                format_price(rule.stop_loss),
+                rule.exit_at.map_or("-".to_string(), |t| t.to_rfc3339()),
+                format_price(rule.last_price),
+            );
+            if let Some(reason) = &rule.trigger_reason {
+                line.push_str(&format!(
+                    " - {} order {} at {}",
+                    reason,
+                    rule.exit_order_id.as_deref().unwrap_or("?"),
+                    format_price(rule.exit_price)
+                ));
+            }
+            if let Some(error) = &rule.last_error {
+                line.push_str(&format!(" - last error: {}", error));
+            }
+            lines.push(line);
+        }
+        ToolResult::success(lines.join("\n")).with_metadata(json!({ "rules": rules }))
+    }
+
+    fn cancel_exit(&self, params: PositionsParams, context: &ToolContext) -> ToolResult {
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let rule_id = match params.rule_id {
+            Some(id) => id,
+            None => return ToolResult::error("rule_id is required for cancel_exit"),
+        };
+        match db.cancel_polymarket_exit_rule(rule_id) {
+            Ok(true) => ToolResult::success(format!("Exit rule #{} cancelled.", rule_id)),
+            Ok(false) => ToolResult::error(format!("Exit rule #{} not found or no longer active", rule_id)),
+            Err(e) => ToolResult::error(format!("Failed to cancel exit rule: {}", e)),
+        }
+    }
+
+    async fn check_exits(&self, context: &ToolContext) -> ToolResult {
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured. A wallet is required to trade on Polymarket."),
+        };
+
+        let results = match polymarket::run_exit_checks(db, wallet_provider, &polymarket::ExitPolicy::from_config()).await {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(e),
+        };
+        if results.is_empty() {
+            return ToolResult::success("Exit rules checked - none triggered.");
+        }
+
+        let lines: Vec<String> = results
+            .iter()
+            .map(|r| match (&r.order_id, &r.error) {
+                (Some(order_id), _) => format!(
+                    "#{} {}: sold {} at {} (order {})",
+                    r.rule_id,
+                    r.trigger,
+                    r.size.unwrap_or_default(),
+                    format_price(r.price),
+                    order_id
+                ),
+                (None, error) => format!(
+                    "#{} {}: exit failed - {}",
+                    r.rule_id,
+                    r.trigger,
+                    error.as_deref().unwrap_or("unknown error")
+                ),
+            })
+            .collect();
+        ToolResult::success(lines.join("\n")).with_metadata(json!({ "results": results }))
+    }
+
+    async fn redeem(&self, params: PositionsParams, context: &ToolContext) -> ToolResult {
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured. A wallet is required to redeem on Polymarket."),
+        };
+        let condition_id = match params.condition_id {
+            Some(c) if !c.trim().is_empty() => c.trim().to_lowercase(),
+            _ => return ToolResult::error("condition_id is required for redeem"),
+        };
+
+        let wallet = wallet_provider.get_address();
+        let owner: Address = match wallet.parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error("Invalid wallet address"),
+        };
+
+        let positions: Vec<_> = match polymarket::fetch_positions(&wallet).await {
+            Ok(p) => p
+                .into_iter()
+                .filter(|p| p.condition_id.to_lowercase() == condition_id && p.size > 0.0)
+                .collect(),
+            Err(e) => return ToolResult::error(e),
+        };
+        if positions.is_empty() {
+            return ToolResult::error(format!("No position held in condition {}", condition_id));
+        }
+        if !positions.iter().any(|p| p.redeemable) {
+            return ToolResult::error("This market has not resolved yet - nothing to redeem");
+        }
+
+        let rpc_config = resolve_rpc_from_context(&context.extra, "polygon");
+        let rpc = match X402EvmRpc::new_with_wallet_provider(
+            wallet_provider.clone(),
+            "polygon",
+            Some(rpc_config.url.clone()),
+            rpc_config.use_x402,
+        ) {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(format!("Failed to create Polygon RPC client: {}", e)),
+        };
+
+        let call = match polymarket::redeem_call(&rpc, owner, &positions).await {
+            Ok(c) => c,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let mut result = execute_resolved_call(
+            &default_abis_dir(),
+            call.abi,
+            &call.contract,
+            call.function,
+            &call.params,
+            "0",
+            false,
+            &Network::Polygon,
+            context,
+            None,
+        )
+        .await;
+
+        if result.success {
+            let uuid = result.metadata.as_ref().and_then(|m| m.get("uuid")).and_then(|u| u.as_str());
+            let recorded = context
+                .database
+                .as_ref()
+                .zip(uuid)
+                .map(|(db, uuid)| db.record_polymarket_redemption(&condition_id, uuid));
+            if let Some(Err(e)) = recorded {
+                log::error!("[polymarket_positions] Failed to record redemption: {}", e);
+            }
+            result.content.push_str(&format!(
+                "\n\nRedeem queued for {}. Broadcast it to receive the USDC payout.",
+                positions[0].title
+            ));
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Tool for PolymarketPositionsTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: PositionsParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        match params.action.as_str() {
+            "set_exit" => self.set_exit(params, context),
+            "list_exits" => self.list_exits(params, context),
+            "cancel_exit" => self.cancel_exit(params, context),
+            "check_exits" => self.check_exits(context).await,
+            "redeem" => self.redeem(params, context).await,
+            _ => ToolResult::error(format!(
+                "Unknown action: '{}'. Use set_exit, list_exits, cancel_exit, check_exits or redeem",
+                params.action
+            )),
+        }
+    }
+}
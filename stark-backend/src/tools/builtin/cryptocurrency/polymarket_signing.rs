@@ -0,0 +1,436 @@
+//! Polymarket signing through a `WalletProvider`
+//!
+//! The polymarket SDK signs with an alloy `Signer` over a raw EIP-712 hash,
+//! which only works with a local private key. Flash (Privy) wallets can only
+//! sign full typed data, so instead:
+//!
+//! - L1 authentication (ClobAuth) is signed here and the resulting API
+//!   credentials are handed to the SDK, so it never asks the signer for a hash.
+//! - Orders are built by the SDK, signed here as typed data and posted as a
+//!   `SignedOrder`.
+//!
+//! Shared by the `polymarket_trade` tool and the scheduler's position manager.
+//!
+//! Every typed-data payload also carries the precomputed `_hash` so Standard
+//! mode signs exactly the digest Polymarket verifies.
+
+use crate::wallet::WalletProvider;
+use async_trait::async_trait;
+use ethers::types::transaction::eip712::{Eip712, TypedData};
+use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::auth::{Credentials, Normal, Uuid};
+use polymarket_client_sdk::clob::types::response::PostOrderResponse;
+use polymarket_client_sdk::clob::types::{Order, OrderType, Side, SignableOrder, SignedOrder};
+use polymarket_client_sdk::clob::{Client, Config as ClobConfig};
+use polymarket_client_sdk::types::{Address, ChainId, Decimal, Signature, B256, U256};
+use polymarket_client_sdk::{contract_config, POLYGON};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Polymarket CLOB API base URL
+pub const CLOB_API_URL: &str = "https://clob.polymarket.com";
+
+/// Type alias for authenticated CLOB client
+pub type AuthenticatedClient = Client<Authenticated<Normal>>;
+
+/// Message the CLOB expects in the ClobAuth struct
+const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+
+/// alloy `Signer` backed by a `WalletProvider`
+///
+/// Only exposes the address and chain id the SDK needs to build an authenticated
+/// client and orders. Hash signing is refused: all signatures go through
+/// `sign_typed_data` in this module.
+#[derive(Clone)]
+pub struct WalletProviderSigner {
+    address: Address,
+    chain_id: Option<ChainId>,
+}
+
+impl WalletProviderSigner {
+    pub fn new(wallet_provider: &Arc<dyn WalletProvider>, chain_id: ChainId) -> Result<Self, String> {
+        let address = wallet_provider
+            .get_address()
+            .parse::<Address>()
+            .map_err(|e| format!("Invalid wallet address: {}", e))?;
+        Ok(Self {
+            address,
+            chain_id: Some(chain_id),
+        })
+    }
+}
+
+#[async_trait]
+impl alloy_signer::Signer for WalletProviderSigner {
+    async fn sign_hash(&self, _hash: &B256) -> alloy_signer::Result<Signature> {
+        Err(alloy_signer::Error::message(
+            "raw hash signing is not supported; Polymarket payloads are signed as typed data",
+        ))
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+/// EIP-712 typed data for the ClobAuth message used to create/derive API keys
+pub fn clob_auth_typed_data(address: Address, timestamp: i64, nonce: u32, chain_id: ChainId) -> Value {
+    json!({
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"}
+            ],
+            "ClobAuth": [
+                {"name": "address", "type": "address"},
+                {"name": "timestamp", "type": "string"},
+                {"name": "nonce", "type": "uint256"},
+                {"name": "message", "type": "string"}
+            ]
+        },
+        "primaryType": "ClobAuth",
+        "domain": {
+            "name": "ClobAuthDomain",
+            "version": "1",
+            "chainId": chain_id
+        },
+        "message": {
+            "address": address.to_string(),
+            "timestamp": timestamp.to_string(),
+            "nonce": nonce.to_string(),
+            "message": CLOB_AUTH_MESSAGE
+        }
+    })
+}
+
+/// EIP-712 typed data for a CTF exchange order
+pub fn order_typed_data(order: &Order, chain_id: ChainId, exchange: Address) -> Value {
+    json!({
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Order": [
+                {"name": "salt", "type": "uint256"},
+                {"name": "maker", "type": "address"},
+                {"name": "signer", "type": "address"},
+                {"name": "taker", "type": "address"},
+                {"name": "tokenId", "type": "uint256"},
+                {"name": "makerAmount", "type": "uint256"},
+                {"name": "takerAmount", "type": "uint256"},
+                {"name": "expiration", "type": "uint256"},
+                {"name": "nonce", "type": "uint256"},
+                {"name": "feeRateBps", "type": "uint256"},
+                {"name": "side", "type": "uint8"},
+                {"name": "signatureType", "type": "uint8"}
+            ]
+        },
+        "primaryType": "Order",
+        "domain": {
+            "name": "Polymarket CTF Exchange",
+            "version": "1",
+            "chainId": chain_id,
+            "verifyingContract": exchange.to_string()
+        },
+        "message": {
+            "salt": order.salt.to_string(),
+            "maker": order.maker.to_string(),
+            "signer": order.signer.to_string(),
+            "taker": order.taker.to_string(),
+            "tokenId": order.tokenId.to_string(),
+            "makerAmount": order.makerAmount.to_string(),
+            "takerAmount": order.takerAmount.to_string(),
+            "expiration": order.expiration.to_string(),
+            "nonce": order.nonce.to_string(),
+            "feeRateBps": order.feeRateBps.to_string(),
+            "side": order.side,
+            "signatureType": order.signatureType
+        }
+    })
+}
+
+/// EIP-712 signing digest of a typed-data JSON payload
+pub fn typed_data_hash(typed_data: &Value) -> Result<[u8; 32], String> {
+    let parsed: TypedData = serde_json::from_value(typed_data.clone())
+        .map_err(|e| format!("Invalid typed data: {}", e))?;
+    parsed
+        .encode_eip712()
+        .map_err(|e| format!("Failed to hash typed data: {}", e))
+}
+
+/// Sign typed data with the wallet provider and return an alloy signature
+pub async fn sign_typed_data(
+    wallet_provider: &Arc<dyn WalletProvider>,
+    typed_data: Value,
+) -> Result<Signature, String> {
+    // Precomputed digest for Standard mode; Flash mode signs the typed data itself
+    let digest = typed_data_hash(&typed_data)?;
+    let mut typed_data_with_hash = typed_data;
+    typed_data_with_hash["_hash"] = json!(format!("0x{}", hex::encode(digest)));
+
+    let signature = wallet_provider
+        .sign_typed_data(&typed_data_with_hash)
+        .await?;
+
+    Signature::from_raw(&signature.to_vec())
+        .map_err(|e| format!("Invalid signature from wallet: {}", e))
+}
+
+/// API key response from `/auth/api-key` and `/auth/derive-api-key`
+#[derive(Debug, Deserialize)]
+struct ApiKeyResponse {
+    #[serde(rename = "apiKey")]
+    api_key: Uuid,
+    secret: String,
+    passphrase: String,
+}
+
+/// Create (or derive, if one already exists) CLOB API credentials for the wallet.
+///
+/// Returns the API key separately since the SDK keeps it private in `Credentials`
+/// and it is needed as the `owner` of signed orders.
+pub async fn create_or_derive_api_key(
+    host: &str,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    chain_id: ChainId,
+) -> Result<(Uuid, Credentials), String> {
+    let http = reqwest::Client::new();
+    let host = host.trim_end_matches('/');
+
+    let address = wallet_provider
+        .get_address()
+        .parse::<Address>()
+        .map_err(|e| format!("Invalid wallet address: {}", e))?;
+
+    // Timestamps must be within the server's window, so use server time
+    let timestamp: i64 = http
+        .get(format!("{}/time", host))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch CLOB server time: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read CLOB server time: {}", e))?
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid CLOB server time: {}", e))?;
+
+    let nonce = 0u32;
+    let signature = sign_typed_data(
+        wallet_provider,
+        clob_auth_typed_data(address, timestamp, nonce, chain_id),
+    )
+    .await?;
+
+    let headers = [
+        ("POLY_ADDRESS", format!("{:#x}", address)),
+        ("POLY_SIGNATURE", signature.to_string()),
+        ("POLY_TIMESTAMP", timestamp.to_string()),
+        ("POLY_NONCE", nonce.to_string()),
+    ];
+
+    let mut create = http.post(format!("{}/auth/api-key", host));
+    for (name, value) in &headers {
+        create = create.header(*name, value);
+    }
+    let response = match create.send().await {
+        Ok(resp) if resp.status().is_success() => resp,
+        _ => {
+            // Key already exists for this nonce - derive it instead
+            let mut derive = http.get(format!("{}/auth/derive-api-key", host));
+            for (name, value) in &headers {
+                derive = derive.header(*name, value);
+            }
+            let resp = derive
+                .send()
+                .await
+                .map_err(|e| format!("Failed to derive CLOB API key: {}", e))?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                return Err(format!("CLOB authentication failed ({}): {}", status, body));
+            }
+            resp
+        }
+    };
+
+    let key: ApiKeyResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid CLOB API key response: {}", e))?;
+
+    Ok((
+        key.api_key,
+        Credentials::new(key.api_key, key.secret, key.passphrase),
+    ))
+}
+
+/// Build an authenticated CLOB client for the wallet on Polygon
+///
+/// Returns the client together with the API key that owns submitted orders.
+pub async fn authenticate(
+    wallet_provider: &Arc<dyn WalletProvider>,
+) -> Result<(AuthenticatedClient, Uuid), String> {
+    let (api_key, credentials) = create_or_derive_api_key(CLOB_API_URL, wallet_provider, POLYGON).await?;
+
+    let signer = WalletProviderSigner::new(wallet_provider, POLYGON)?;
+
+    let config = ClobConfig::builder()
+        .use_server_time(true)
+        .build();
+
+    let client = Client::new(CLOB_API_URL, config)
+        .map_err(|e| format!("Failed to create CLOB client: {}", e))?
+        .authentication_builder(&signer)
+        .credentials(credentials)
+        .authenticate()
+        .await
+        .map_err(|e| format!("Failed to authenticate with CLOB: {}", e))?;
+
+    Ok((client, api_key))
+}
+
+/// Sign an order built by the SDK with the wallet provider
+pub async fn sign_order(
+    client: &AuthenticatedClient,
+    api_key: Uuid,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    order: SignableOrder,
+) -> Result<SignedOrder, String> {
+    let neg_risk = client
+        .neg_risk(order.order.tokenId)
+        .await
+        .map_err(|e| format!("Failed to look up market type: {}", e))?
+        .neg_risk;
+
+    let exchange = contract_config(POLYGON, neg_risk)
+        .ok_or_else(|| "No Polymarket exchange configured for Polygon".to_string())?
+        .exchange;
+
+    let typed_data = order_typed_data(&order.order, POLYGON, exchange);
+    let signature = sign_typed_data(wallet_provider, typed_data).await?;
+
+    Ok(SignedOrder::builder()
+        .order(order.order)
+        .signature(signature)
+        .order_type(order.order_type)
+        .owner(api_key)
+        .maybe_post_only(order.post_only)
+        .build())
+}
+
+/// Build, sign and submit a limit order
+#[allow(clippy::too_many_arguments)]
+pub async fn place_limit_order(
+    client: &AuthenticatedClient,
+    api_key: Uuid,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    token_id: U256,
+    side: Side,
+    price: Decimal,
+    size: Decimal,
+    order_type: OrderType,
+) -> Result<PostOrderResponse, String> {
+    let order = client
+        .limit_order()
+        .token_id(token_id)
+        .price(price)
+        .size(size)
+        .side(side)
+        .order_type(order_type)
+        .build()
+        .await
+        .map_err(|e| format!("Failed to build order: {}", e))?;
+
+    let signed_order = sign_order(client, api_key, wallet_provider, order)
+        .await
+        .map_err(|e| format!("Failed to sign order: {}", e))?;
+
+    client
+        .post_order(signed_order)
+        .await
+        .map_err(|e| format!("Failed to submit order: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::{eip712_domain, sol, SolStruct};
+
+    sol! {
+        struct ClobAuth {
+            address address;
+            string  timestamp;
+            uint256 nonce;
+            string  message;
+        }
+    }
+
+    fn test_address(byte: u8) -> Address {
+        Address::from([byte; 20])
+    }
+
+    #[test]
+    fn test_clob_auth_hash_matches_sdk_encoding() {
+        let address = test_address(0x11);
+        let typed = clob_auth_typed_data(address, 1_700_000_000, 0, POLYGON);
+
+        let expected = ClobAuth {
+            address,
+            timestamp: "1700000000".to_string(),
+            nonce: U256::ZERO,
+            message: CLOB_AUTH_MESSAGE.to_string(),
+        }
+        .eip712_signing_hash(&eip712_domain! {
+            name: "ClobAuthDomain",
+            version: "1",
+            chain_id: POLYGON,
+        });
+
+        assert_eq!(typed_data_hash(&typed).unwrap(), expected.0);
+    }
+
+    #[test]
+    fn test_order_hash_matches_sdk_encoding() {
+        let exchange = test_address(0x4b);
+        let mut order = Order::default();
+        order.salt = U256::from(123_456_789u64);
+        order.maker = test_address(0x22);
+        order.signer = test_address(0x22);
+        order.taker = Address::ZERO;
+        order.tokenId = U256::from_str_radix(
+            "71321045679252212594626385532706912750332728571942532289631379312455583992563",
+            10,
+        )
+        .unwrap();
+        order.makerAmount = U256::from(6_500_000u64);
+        order.takerAmount = U256::from(10_000_000u64);
+        order.nonce = U256::ZERO;
+        order.feeRateBps = U256::ZERO;
+        order.side = 0;
+        order.signatureType = 0;
+
+        let typed = order_typed_data(&order, POLYGON, exchange);
+        let expected = order.eip712_signing_hash(&eip712_domain! {
+            name: "Polymarket CTF Exchange",
+            version: "1",
+            chain_id: POLYGON,
+            verifying_contract: exchange,
+        });
+
+        assert_eq!(typed_data_hash(&typed).unwrap(), expected.0);
+    }
+}
@@ -1,7 +1,8 @@
 //! Polymarket CLOB Trading Tool
 //!
 //! Enables trading on Polymarket prediction markets using the polymarket-client-sdk.
-//! Uses the burner wallet private key for signing EIP-712 orders.
+//! EIP-712 orders and CLOB authentication are signed through the configured
+//! `WalletProvider`, so trading works in both Standard and Flash mode.
 //!
 //! ## Discovery Actions (no auth required)
 //! - `search_markets`: Search markets by keyword
@@ -16,12 +17,20 @@
 //! - `get_orders`: List open orders
 //! - `get_positions`: Get current positions and balances
 //! - `get_balance`: Get USDC balance and allowances on Polygon
+//! - `setup_approvals`: Queue the next missing USDC/CTF approval for the exchanges
 
+use super::polymarket_signing::{self, AuthenticatedClient};
 use crate::tools::registry::Tool;
+use crate::tools::rpc_config::{resolve_rpc_from_context, Network};
 use crate::tools::types::{
     PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
 };
+use crate::wallet::WalletProvider;
+use crate::web3::{default_abis_dir, execute_resolved_call};
+use crate::x402::X402EvmRpc;
 use async_trait::async_trait;
+use ethers::abi::Token;
+use ethers::types::{Address as EthersAddress, U256 as EthersU256};
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -30,19 +39,11 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 // Polymarket SDK imports - use SDK's re-exports
-use polymarket_client_sdk::auth::state::Authenticated;
-use polymarket_client_sdk::auth::{LocalSigner, Normal, Signer};
+use polymarket_client_sdk::auth::Uuid;
 use polymarket_client_sdk::clob::types::request::OrdersRequest;
 use polymarket_client_sdk::clob::types::{OrderType, Side};
-use polymarket_client_sdk::clob::{Client, Config as ClobConfig};
 use polymarket_client_sdk::types::{Decimal, U256};
-use polymarket_client_sdk::POLYGON;
-
-/// Type alias for authenticated CLOB client
-type AuthenticatedClient = Client<Authenticated<Normal>>;
-
-/// Polymarket CLOB API base URL
-const CLOB_API_URL: &str = "https://clob.polymarket.com";
+use polymarket_client_sdk::{contract_config, POLYGON};
 
 /// Polymarket trading tool
 pub struct PolymarketTradeTool {
@@ -52,8 +53,10 @@ pub struct PolymarketTradeTool {
 }
 
 /// Cached authenticated client
-/// Note: We don't cache the signer since creating it is cheap
+/// Keyed by wallet address; orders are signed per call through the wallet provider
 struct CachedClient {
+    address: String,
+    api_key: Uuid,
     client: AuthenticatedClient,
 }
 
@@ -65,7 +68,7 @@ impl PolymarketTradeTool {
             "action".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Action: search_markets, trending_markets, get_market, get_price (discovery) | place_order, cancel_order, cancel_all, get_orders, get_positions, get_balance (trading)".to_string(),
+                description: "Action: search_markets, trending_markets, get_market, get_price (discovery) | place_order, cancel_order, cancel_all, get_orders, get_positions, get_balance, setup_approvals (trading)".to_string(),
                 default: None,
                 items: None,
                 enum_values: Some(vec![
@@ -81,6 +84,7 @@ impl PolymarketTradeTool {
                     "get_orders".to_string(),
                     "get_positions".to_string(),
                     "get_balance".to_string(),
+                    "setup_approvals".to_string(),
                 ]),
             },
         );
@@ -219,7 +223,7 @@ impl PolymarketTradeTool {
         PolymarketTradeTool {
             definition: ToolDefinition {
                 name: "polymarket_trade".to_string(),
-                description: "Explore and trade on Polymarket prediction markets. Discovery: search_markets, trending_markets, get_market, get_price. Trading: place_order, cancel_order, get_orders, get_positions, get_balance. Trading requires BURNER_WALLET_BOT_PRIVATE_KEY with USDC on Polygon.".to_string(),
+                description: "Explore and trade on Polymarket prediction markets. Discovery: search_markets, trending_markets, get_market, get_price. Trading: place_order, cancel_order, get_orders, get_positions, get_balance. Trading requires the bot wallet to hold USDC on Polygon; run setup_approvals first to queue the one-time USDC/CTF approvals (broadcast each, then repeat until all are in place).".to_string(),
                 input_schema: ToolInputSchema {
                     schema_type: "object".to_string(),
                     properties,
@@ -231,83 +235,53 @@ impl PolymarketTradeTool {
         }
     }
 
-    /// Get the private key from environment
-    fn get_private_key() -> Result<String, String> {
-        crate::config::burner_wallet_private_key()
-            .ok_or_else(|| "BURNER_WALLET_BOT_PRIVATE_KEY not set. Configure this env var to trade on Polymarket.".to_string())
+    /// Get the wallet provider used for Polymarket signing
+    fn get_wallet_provider(context: &ToolContext) -> Result<&Arc<dyn WalletProvider>, String> {
+        context
+            .wallet_provider
+            .as_ref()
+            .ok_or_else(|| "Wallet not configured. A wallet is required to trade on Polymarket.".to_string())
     }
 
-    /// Get wallet address from private key (for trading operations that use the local key)
-    fn get_wallet_address() -> Result<String, String> {
-        let pk = Self::get_private_key()?;
-        let pk_clean = pk.strip_prefix("0x").unwrap_or(&pk);
-
-        let wallet: ethers::signers::LocalWallet = pk_clean
-            .parse()
-            .map_err(|e| format!("Invalid private key: {}", e))?;
-
-        use ethers::signers::Signer as EthersSigner;
-        Ok(format!("{:?}", wallet.address()))
-    }
-
-    /// Get wallet address — prefers wallet provider (correct in Flash/Privy mode)
+    /// Get wallet address from the wallet provider (correct in both Standard and Flash mode)
     fn get_wallet_address_from_context(context: &ToolContext) -> Result<String, String> {
-        if let Some(ref wp) = context.wallet_provider {
-            return Ok(wp.get_address());
-        }
-        Self::get_wallet_address()
+        Self::get_wallet_provider(context).map(|wp| wp.get_address())
     }
 
-    /// Get or create authenticated CLOB client
-    async fn get_authenticated_client(&self) -> Result<AuthenticatedClient, String> {
-        // Check cache first
+    /// Get or create authenticated CLOB client for the context's wallet
+    ///
+    /// Returns the client together with the API key that owns submitted orders.
+    async fn get_authenticated_client(&self, context: &ToolContext) -> Result<(AuthenticatedClient, Uuid), String> {
+        let wallet_provider = Self::get_wallet_provider(context)?;
+        let address = wallet_provider.get_address().to_lowercase();
+
+        // Check cache first (keyed by wallet so a wallet change re-authenticates)
         {
             let cache = self.client_cache.lock().await;
-            if let Some(cached) = cache.as_ref() {
-                return Ok(cached.client.clone());
+            if let Some(cached) = cache.as_ref().filter(|c| c.address == address) {
+                return Ok((cached.client.clone(), cached.api_key));
             }
         }
 
-        // Create new authenticated client
-        let pk = Self::get_private_key()?;
-        let pk_clean = pk.strip_prefix("0x").unwrap_or(&pk);
-
-        let signer = LocalSigner::from_str(pk_clean)
-            .map(|s| s.with_chain_id(Some(POLYGON)))
-            .map_err(|e| format!("Invalid private key: {}", e))?;
-
-        let config = ClobConfig::builder()
-            .use_server_time(true)
-            .build();
-
-        let client = Client::new(CLOB_API_URL, config)
-            .map_err(|e| format!("Failed to create CLOB client: {}", e))?
-            .authentication_builder(&signer)
-            .authenticate()
-            .await
-            .map_err(|e| format!("Failed to authenticate with CLOB: {}", e))?;
+        // L1 auth is signed as typed data by the wallet provider; the SDK only
+        // receives the resulting credentials and never signs itself
+        let (client, api_key) = polymarket_signing::authenticate(wallet_provider).await?;
 
         // Cache for future use
         {
             let mut cache = self.client_cache.lock().await;
-            *cache = Some(CachedClient { client: client.clone() });
+            *cache = Some(CachedClient {
+                address,
+                api_key,
+                client: client.clone(),
+            });
         }
 
-        Ok(client)
-    }
-
-    /// Create a fresh signer for signing operations
-    fn create_signer_for_signing() -> Result<impl Signer + Clone, String> {
-        let pk = Self::get_private_key()?;
-        let pk_clean = pk.strip_prefix("0x").unwrap_or(&pk);
-
-        LocalSigner::from_str(pk_clean)
-            .map(|s| s.with_chain_id(Some(POLYGON)))
-            .map_err(|e| format!("Invalid private key: {}", e))
+        Ok((client, api_key))
     }
 
     /// Place a limit order on Polymarket
-    async fn place_order(&self, params: &PolymarketParams) -> ToolResult {
+    async fn place_order(&self, params: &PolymarketParams, context: &ToolContext) -> ToolResult {
         // Validate required parameters
         let token_id_str = match &params.token_id {
             Some(t) => t,
@@ -376,41 +350,32 @@ impl PolymarketTradeTool {
             Err(e) => return ToolResult::error(format!("Invalid size decimal: {}", e)),
         };
 
-        // Get authenticated client and signer
-        let client = match self.get_authenticated_client().await {
-            Ok(c) => c,
+        // Get authenticated client and the wallet that signs the order
+        let wallet_provider = match Self::get_wallet_provider(context) {
+            Ok(wp) => wp,
             Err(e) => return ToolResult::error(e),
         };
 
-        let signer = match Self::create_signer_for_signing() {
-            Ok(s) => s,
+        let (client, api_key) = match self.get_authenticated_client(context).await {
+            Ok(c) => c,
             Err(e) => return ToolResult::error(e),
         };
 
         // Build the limit order
-        let order = match client
-            .limit_order()
-            .token_id(token_id)
-            .price(price_decimal)
-            .size(size_decimal)
-            .side(side)
-            .order_type(order_type)
-            .build()
-            .await
+        // Build, sign (via the wallet provider) and submit the order
+        let wallet_address = wallet_provider.get_address();
+        match polymarket_signing::place_limit_order(
+            &client,
+            api_key,
+            wallet_provider,
+            token_id,
+            side,
+            price_decimal,
+            size_decimal,
+            order_type,
+        )
+        .await
         {
-            Ok(o) => o,
-            Err(e) => return ToolResult::error(format!("Failed to build order: {}", e)),
-        };
-
-        // Sign the order
-        let signed_order = match client.sign(&signer, order).await {
-            Ok(s) => s,
-            Err(e) => return ToolResult::error(format!("Failed to sign order: {}", e)),
-        };
-
-        // Submit the order
-        let wallet_address = Self::get_wallet_address().unwrap_or_else(|_| "unknown".to_string());
-        match client.post_order(signed_order).await {
             Ok(response) => {
                 let usdc_cost = size * price;
                 let result = json!({
@@ -431,18 +396,18 @@ impl PolymarketTradeTool {
                 });
                 ToolResult::success(serde_json::to_string_pretty(&result).unwrap())
             }
-            Err(e) => ToolResult::error(format!("Failed to submit order: {}", e))
+            Err(e) => ToolResult::error(e)
         }
     }
 
     /// Cancel a specific order
-    async fn cancel_order(&self, params: &PolymarketParams) -> ToolResult {
+    async fn cancel_order(&self, params: &PolymarketParams, context: &ToolContext) -> ToolResult {
         let order_id = match &params.order_id {
             Some(id) => id,
             None => return ToolResult::error("order_id is required for cancel_order"),
         };
 
-        let client = match self.get_authenticated_client().await {
+        let (client, _) = match self.get_authenticated_client(context).await {
             Ok(c) => c,
             Err(e) => return ToolResult::error(e),
         };
@@ -462,8 +427,8 @@ impl PolymarketTradeTool {
     }
 
     /// Cancel all open orders
-    async fn cancel_all(&self) -> ToolResult {
-        let client = match self.get_authenticated_client().await {
+    async fn cancel_all(&self, context: &ToolContext) -> ToolResult {
+        let (client, _) = match self.get_authenticated_client(context).await {
             Ok(c) => c,
             Err(e) => return ToolResult::error(e),
         };
@@ -482,13 +447,13 @@ impl PolymarketTradeTool {
     }
 
     /// Get open orders
-    async fn get_orders(&self) -> ToolResult {
-        let client = match self.get_authenticated_client().await {
+    async fn get_orders(&self, context: &ToolContext) -> ToolResult {
+        let (client, _) = match self.get_authenticated_client(context).await {
             Ok(c) => c,
             Err(e) => return ToolResult::error(e),
         };
 
-        let wallet_address = Self::get_wallet_address().unwrap_or_else(|_| "unknown".to_string());
+        let wallet_address = Self::get_wallet_address_from_context(context).unwrap_or_else(|_| "unknown".to_string());
 
         let request = OrdersRequest::default();
         match client.orders(&request, None).await {
@@ -520,11 +485,7 @@ impl PolymarketTradeTool {
     }
 
     /// Get current positions from Data API
-    async fn get_positions(&self) -> ToolResult {
-        self.get_positions_with_context(&ToolContext::new()).await
-    }
-
-    async fn get_positions_with_context(&self, context: &ToolContext) -> ToolResult {
+    async fn get_positions(&self, context: &ToolContext) -> ToolResult {
         let wallet_address = match Self::get_wallet_address_from_context(context) {
             Ok(addr) => addr,
             Err(e) => return ToolResult::error(e),
@@ -553,12 +514,8 @@ impl PolymarketTradeTool {
     }
 
     /// Get balance and allowance info
-    async fn get_balance(&self) -> ToolResult {
-        self.get_balance_with_context(&ToolContext::new()).await
-    }
-
-    async fn get_balance_with_context(&self, context: &ToolContext) -> ToolResult {
-        let client = match self.get_authenticated_client().await {
+    async fn get_balance(&self, context: &ToolContext) -> ToolResult {
+        let (client, _) = match self.get_authenticated_client(context).await {
             Ok(c) => c,
             Err(e) => return ToolResult::error(e),
         };
@@ -591,6 +548,106 @@ impl PolymarketTradeTool {
         }
     }
 
+    /// Check the on-chain approvals trading needs and queue the next missing one
+    ///
+    /// Trading requires USDC allowance and CTF operator approval for the CTF
+    /// exchange, the neg-risk exchange and the neg-risk adapter. Only one
+    /// approval is queued per call: queued transactions take their nonce from
+    /// the chain, so several unbroadcast approvals would collide.
+    async fn setup_approvals(&self, context: &ToolContext) -> ToolResult {
+        let wallet_provider = match Self::get_wallet_provider(context) {
+            Ok(wp) => wp,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let owner: EthersAddress = match wallet_provider.get_address().parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error("Invalid wallet address"),
+        };
+
+        let approvals = match required_approvals() {
+            Ok(a) => a,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let rpc_config = resolve_rpc_from_context(&context.extra, "polygon");
+        let rpc = match X402EvmRpc::new_with_wallet_provider(
+            wallet_provider.clone(),
+            "polygon",
+            Some(rpc_config.url.clone()),
+            rpc_config.use_x402,
+        ) {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(format!("Failed to create Polygon RPC client: {}", e)),
+        };
+
+        let mut status = Vec::new();
+        let mut missing = Vec::new();
+        for approval in &approvals {
+            let approved = match approval.is_granted(&rpc, owner).await {
+                Ok(a) => a,
+                Err(e) => return ToolResult::error(format!("Failed to check {}: {}", approval.label(), e)),
+            };
+            status.push(json!({ "approval": approval.label(), "approved": approved }));
+            if !approved {
+                missing.push(approval);
+            }
+        }
+
+        let next = match missing.first() {
+            Some(next) => next,
+            None => {
+                let result = json!({
+                    "status": "success",
+                    "wallet": wallet_provider.get_address(),
+                    "network": "polygon",
+                    "approvals": status,
+                    "message": "All Polymarket approvals are in place. Ready to trade."
+                });
+                return ToolResult::success(serde_json::to_string_pretty(&result).unwrap());
+            }
+        };
+
+        log::info!(
+            "[polymarket] Queueing {} ({} of {} approvals missing)",
+            next.label(),
+            missing.len(),
+            approvals.len()
+        );
+
+        let (abi, contract, function, call_params) = next.call();
+        let mut result = execute_resolved_call(
+            &default_abis_dir(),
+            abi,
+            &contract,
+            function,
+            &call_params,
+            "0",
+            false,
+            &Network::Polygon,
+            context,
+            None,
+        )
+        .await;
+
+        if result.success {
+            let remaining = missing.len() - 1;
+            result.content.push_str(&format!(
+                "\n\nPolymarket approval queued: {}. {}",
+                next.label(),
+                if remaining == 0 {
+                    "This is the last approval needed.".to_string()
+                } else {
+                    format!(
+                        "{} more approval(s) needed - broadcast this one, then run setup_approvals again.",
+                        remaining
+                    )
+                }
+            ));
+        }
+        result
+    }
+
     // ==================== DISCOVERY METHODS ====================
 
     /// Search markets by keyword (lightweight summaries only)
@@ -906,6 +963,96 @@ struct PolymarketParams {
     order_id: Option<String>,
 }
 
+/// An on-chain approval needed to trade on Polymarket
+enum PolymarketApproval {
+    /// USDC allowance for a spender
+    Collateral { token: EthersAddress, spender: EthersAddress, name: &'static str },
+    /// CTF `setApprovalForAll` for an operator
+    Outcomes { ctf: EthersAddress, operator: EthersAddress, name: &'static str },
+}
+
+impl PolymarketApproval {
+    fn label(&self) -> String {
+        match self {
+            Self::Collateral { name, .. } => format!("USDC allowance for {}", name),
+            Self::Outcomes { name, .. } => format!("CTF approval for {}", name),
+        }
+    }
+
+    /// Whether the approval is already granted on-chain
+    async fn is_granted(&self, rpc: &X402EvmRpc, owner: EthersAddress) -> Result<bool, String> {
+        match self {
+            Self::Collateral { token, spender, .. } => {
+                // allowance(address,address)
+                let mut data = vec![0xdd, 0x62, 0xed, 0x3e];
+                data.extend(ethers::abi::encode(&[Token::Address(owner), Token::Address(*spender)]));
+                let result = rpc.call(*token, &data).await?;
+                if result.len() < 32 {
+                    return Err("Invalid allowance response".to_string());
+                }
+                // Treat anything short of an unlimited approval as missing
+                Ok(EthersU256::from_big_endian(&result[..32]) >= EthersU256::MAX >> 1)
+            }
+            Self::Outcomes { ctf, operator, .. } => {
+                // isApprovedForAll(address,address)
+                let mut data = vec![0xe9, 0x85, 0xe9, 0xc5];
+                data.extend(ethers::abi::encode(&[Token::Address(owner), Token::Address(*operator)]));
+                let result = rpc.call(*ctf, &data).await?;
+                if result.len() < 32 {
+                    return Err("Invalid isApprovedForAll response".to_string());
+                }
+                Ok(result[31] == 1)
+            }
+        }
+    }
+
+    /// ABI name, contract, function and params for the approval transaction
+    fn call(&self) -> (&'static str, String, &'static str, Vec<Value>) {
+        match self {
+            Self::Collateral { token, spender, .. } => (
+                "erc20",
+                format!("{:?}", token),
+                "approve",
+                vec![json!(format!("{:?}", spender)), json!(EthersU256::MAX.to_string())],
+            ),
+            Self::Outcomes { ctf, operator, .. } => (
+                "conditional_tokens",
+                format!("{:?}", ctf),
+                "setApprovalForAll",
+                vec![json!(format!("{:?}", operator)), json!(true)],
+            ),
+        }
+    }
+}
+
+/// All approvals needed to trade both regular and neg-risk markets on Polygon
+fn required_approvals() -> Result<Vec<PolymarketApproval>, String> {
+    let standard = contract_config(POLYGON, false)
+        .ok_or_else(|| "No Polymarket contracts configured for Polygon".to_string())?;
+    let neg_risk = contract_config(POLYGON, true)
+        .ok_or_else(|| "No Polymarket neg-risk contracts configured for Polygon".to_string())?;
+    let neg_risk_adapter = neg_risk
+        .neg_risk_adapter
+        .ok_or_else(|| "No Polymarket neg-risk adapter configured for Polygon".to_string())?;
+
+    let to_ethers = |a: polymarket_client_sdk::types::Address| EthersAddress::from(a.into_array());
+    let token = to_ethers(standard.collateral);
+    let ctf = to_ethers(standard.conditional_tokens);
+
+    let spenders = [
+        (to_ethers(standard.exchange), "CTF Exchange"),
+        (to_ethers(neg_risk.exchange), "Neg Risk CTF Exchange"),
+        (to_ethers(neg_risk_adapter), "Neg Risk Adapter"),
+    ];
+
+    let mut approvals = Vec::new();
+    for (spender, name) in spenders {
+        approvals.push(PolymarketApproval::Collateral { token, spender, name });
+        approvals.push(PolymarketApproval::Outcomes { ctf, operator: spender, name });
+    }
+    Ok(approvals)
+}
+
 #[async_trait]
 impl Tool for PolymarketTradeTool {
     fn definition(&self) -> ToolDefinition {
@@ -925,14 +1072,15 @@ impl Tool for PolymarketTradeTool {
             "get_market" => self.get_market(&params).await,
             "get_price" => self.get_price(&params).await,
             // Trading actions (require wallet)
-            "place_order" => self.place_order(&params).await,
-            "cancel_order" => self.cancel_order(&params).await,
-            "cancel_all" => self.cancel_all().await,
-            "get_orders" => self.get_orders().await,
-            "get_positions" => self.get_positions_with_context(context).await,
-            "get_balance" => self.get_balance_with_context(context).await,
+            "place_order" => self.place_order(&params, context).await,
+            "cancel_order" => self.cancel_order(&params, context).await,
+            "cancel_all" => self.cancel_all(context).await,
+            "get_orders" => self.get_orders(context).await,
+            "get_positions" => self.get_positions(context).await,
+            "get_balance" => self.get_balance(context).await,
+            "setup_approvals" => self.setup_approvals(context).await,
             _ => ToolResult::error(format!(
-                "Unknown action: '{}'. Discovery: search_markets, trending_markets, get_market, get_price. Trading: place_order, cancel_order, cancel_all, get_orders, get_positions, get_balance",
+                "Unknown action: '{}'. Discovery: search_markets, trending_markets, get_market, get_price. Trading: place_order, cancel_order, cancel_all, get_orders, get_positions, get_balance, setup_approvals",
                 params.action
             )),
         }
@@ -0,0 +1,263 @@
+//! Portfolio tool - holdings and P&L from stored portfolio snapshots
+//!
+//! Answers questions like "how is my portfolio doing this week?" using the
+//! periodic snapshots taken by the scheduler (see `crate::portfolio`).
+
+use crate::portfolio;
+use crate::tools::registry::Tool;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Portfolio tool
+pub struct PortfolioTool {
+    definition: ToolDefinition,
+}
+
+impl PortfolioTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'holdings' = current balances and USD value, 'pnl' = value change over a period, 'history' = total value over time, 'snapshot' = refresh balances now".to_string(),
+                default: Some(json!("holdings")),
+                items: None,
+                enum_values: Some(vec![
+                    "holdings".to_string(),
+                    "pnl".to_string(),
+                    "history".to_string(),
+                    "snapshot".to_string(),
+                ]),
+            },
+        );
+
+        properties.insert(
+            "period".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Period for pnl/history: '24h', '7d', '30d', 'day', 'week', 'month' (default '24h' for pnl, '30d' for history)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+
+        PortfolioTool {
+            definition: ToolDefinition {
+                name: "portfolio".to_string(),
+                description: "Portfolio holdings and P&L across all configured networks for the bot wallet and tracked wallets/Safes. Uses periodic balance snapshots valued in USD. Use 'pnl' with a period to answer 'how is my portfolio doing this week?'.".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+            },
+        }
+    }
+}
+
+impl Default for PortfolioTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PortfolioParams {
+    #[serde(default = "default_action")]
+    action: String,
+    period: Option<String>,
+}
+
+fn default_action() -> String {
+    "holdings".to_string()
+}
+
+fn format_usd(value: f64) -> String {
+    if value < 0.0 {
+        format!("-${:.2}", value.abs())
+    } else {
+        format!("${:.2}", value)
+    }
+}
+
+fn format_pct(pct: Option<f64>) -> String {
+    match pct {
+        Some(p) => format!("{:+.2}%", p),
+        None => "n/a".to_string(),
+    }
+}
+
+#[async_trait]
+impl Tool for PortfolioTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: PortfolioParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+
+        match params.action.as_str() {
+            "snapshot" => {
+                let wallet_provider = match &context.wallet_provider {
+                    Some(wp) => wp,
+                    None => return ToolResult::error("No wallet configured - cannot read balances"),
+                };
+                match portfolio::take_snapshot(db, wallet_provider).await {
+                    Ok(snapshot) => {
+                        let mut msg = format!(
+                            "Portfolio snapshot taken: {} total",
+                            format_usd(snapshot.total_value_usd)
+                        );
+                        if !snapshot.errors.is_empty() {
+                            msg.push_str(&format!("\nSome networks could not be read: {}", snapshot.errors.join("; ")));
+                        }
+                        ToolResult::success(msg).with_metadata(json!({ "snapshot": snapshot }))
+                    }
+                    Err(e) => ToolResult::error(format!("Snapshot failed: {}", e)),
+                }
+            }
+
+            "holdings" => {
+                let snapshot = match db.get_latest_portfolio_snapshot() {
+                    Ok(Some(s)) => s,
+                    Ok(None) => return ToolResult::success("No portfolio snapshots yet. Use action 'snapshot' to take one."),
+                    Err(e) => return ToolResult::error(format!("Failed to load portfolio: {}", e)),
+                };
+                let balances = match db.get_portfolio_balances(snapshot.id) {
+                    Ok(b) => b,
+                    Err(e) => return ToolResult::error(format!("Failed to load balances: {}", e)),
+                };
+                let holdings = portfolio::aggregate_holdings(&balances);
+
+                let mut msg = format!(
+                    "Portfolio: {} (as of {})\n\n",
+                    format_usd(snapshot.total_value_usd),
+                    snapshot.taken_at.format("%Y-%m-%d %H:%M UTC")
+                );
+                for h in &holdings {
+                    msg.push_str(&format!(
+                        "- {} on {}: {:.6} ({})\n",
+                        h.symbol,
+                        h.network,
+                        h.balance,
+                        h.value_usd.map(format_usd).unwrap_or_else(|| "no price".to_string())
+                    ));
+                }
+                if holdings.is_empty() {
+                    msg.push_str("No token balances found.\n");
+                }
+                if !snapshot.errors.is_empty() {
+                    msg.push_str(&format!("\nIncomplete: {}\n", snapshot.errors.join("; ")));
+                }
+
+                ToolResult::success(msg).with_metadata(json!({
+                    "snapshot": snapshot,
+                    "holdings": holdings,
+                }))
+            }
+
+            "pnl" => {
+                let period_str = params.period.as_deref().unwrap_or("24h");
+                let period = match portfolio::parse_period(period_str) {
+                    Some(p) => p,
+                    None => return ToolResult::error(format!("Invalid period '{}'. Use e.g. 24h, 7d, 30d, week, month", period_str)),
+                };
+                let change = match portfolio::compute_change(db, period) {
+                    Ok(Some(c)) => c,
+                    Ok(None) => return ToolResult::success("No portfolio snapshots yet. Use action 'snapshot' to take one."),
+                    Err(e) => return ToolResult::error(e),
+                };
+
+                let mut msg = format!(
+                    "Portfolio P&L ({}): {} -> {} ({} / {})\n",
+                    period_str,
+                    format_usd(change.start_value_usd),
+                    format_usd(change.end_value_usd),
+                    format_usd(change.change_usd),
+                    format_pct(change.change_pct)
+                );
+                msg.push_str(&format!(
+                    "Measured from {} to {}\n",
+                    change.from.taken_at.format("%Y-%m-%d %H:%M UTC"),
+                    change.to.taken_at.format("%Y-%m-%d %H:%M UTC")
+                ));
+                if Utc::now() - period < change.from.taken_at {
+                    msg.push_str("Note: snapshot history does not cover the full period.\n");
+                }
+                msg.push_str("\nBy asset (value change includes deposits/withdrawals, price change does not):\n");
+                for a in change.assets.iter().take(15) {
+                    msg.push_str(&format!(
+                        "- {} on {}: {} (balance {:.6} -> {:.6}, price {})\n",
+                        a.symbol,
+                        a.network,
+                        format_usd(a.value_change_usd),
+                        a.start_balance,
+                        a.end_balance,
+                        format_pct(a.price_change_pct)
+                    ));
+                }
+
+                ToolResult::success(msg).with_metadata(json!({
+                    "period": period_str,
+                    "pnl": change,
+                }))
+            }
+
+            "history" => {
+                let period_str = params.period.as_deref().unwrap_or("30d");
+                let period = match portfolio::parse_period(period_str) {
+                    Some(p) => p,
+                    None => return ToolResult::error(format!("Invalid period '{}'. Use e.g. 24h, 7d, 30d, week, month", period_str)),
+                };
+                let snapshots = match db.list_portfolio_snapshots(Utc::now() - period, 500) {
+                    Ok(s) => s,
+                    Err(e) => return ToolResult::error(format!("Failed to load history: {}", e)),
+                };
+                if snapshots.is_empty() {
+                    return ToolResult::success(format!("No portfolio snapshots in the last {}.", period_str));
+                }
+
+                // Keep the text output short: at most ~20 evenly spaced points
+                let step = (snapshots.len() / 20).max(1);
+                let mut msg = format!("Portfolio value over {} ({} snapshots):\n", period_str, snapshots.len());
+                for (i, s) in snapshots.iter().enumerate() {
+                    if i % step == 0 || i == snapshots.len() - 1 {
+                        msg.push_str(&format!(
+                            "- {}: {}\n",
+                            s.taken_at.format("%Y-%m-%d %H:%M"),
+                            format_usd(s.total_value_usd)
+                        ));
+                    }
+                }
+
+                ToolResult::success(msg).with_metadata(json!({
+                    "period": period_str,
+                    "snapshots": snapshots,
+                }))
+            }
+
+            other => ToolResult::error(format!(
+                "Unknown action '{}'. Use: holdings, pnl, history, snapshot",
+                other
+            )),
+        }
+    }
+}
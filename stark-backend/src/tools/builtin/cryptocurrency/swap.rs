@@ -0,0 +1,399 @@
+//! Swap tools - quote and execute token swaps across aggregators and Uniswap pools
+//!
+//! `swap_quote` compares 0x, KyberSwap and the configured Uniswap V3/V4 pools.
+//! `swap` picks the best quote (or a requested source), queues any missing
+//! approval (ERC-20 or Permit2), then queues the swap itself. The sell amount is
+//! passed to `verify_intent` directly instead of through registers.
+
+use super::verify_intent::SwapSellAmount;
+use super::ToRawAmountTool;
+use crate::config;
+use crate::swap::{self, SwapQuote, SwapRequest};
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::resolve_rpc_from_context;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::web3::{default_abis_dir, execute_resolved_call, queue_raw_transaction, resolve_network};
+use crate::x402::X402EvmRpc;
+use async_trait::async_trait;
+use ethers::types::{Address, U256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Parameters shared by `swap_quote` and `swap`
+#[derive(Debug, Deserialize)]
+struct SwapParams {
+    sell_token: String,
+    buy_token: String,
+    /// Human-readable sell amount (e.g. "1.5")
+    amount: String,
+    network: Option<String>,
+    slippage_bps: Option<u32>,
+    /// Only for `swap`: use this quote source instead of the best one
+    source: Option<String>,
+}
+
+fn swap_properties(include_source: bool) -> HashMap<String, PropertySchema> {
+    let mut properties = HashMap::new();
+    let string_prop = |description: &str| PropertySchema {
+        schema_type: "string".to_string(),
+        description: description.to_string(),
+        default: None,
+        items: None,
+        enum_values: None,
+    };
+
+    properties.insert(
+        "sell_token".to_string(),
+        string_prop("Token to sell: symbol from the token list (e.g. 'USDC', 'ETH') or contract address"),
+    );
+    properties.insert(
+        "buy_token".to_string(),
+        string_prop("Token to buy: symbol from the token list or contract address"),
+    );
+    properties.insert(
+        "amount".to_string(),
+        string_prop("Amount of sell_token to sell, human-readable (e.g. '1.5')"),
+    );
+    properties.insert(
+        "network".to_string(),
+        string_prop("Network: 'base', 'mainnet' or 'polygon' (default: selected network)"),
+    );
+    properties.insert(
+        "slippage_bps".to_string(),
+        PropertySchema {
+            schema_type: "integer".to_string(),
+            description: format!(
+                "Slippage tolerance in basis points (default {}, max {})",
+                config::swap_default_slippage_bps(),
+                config::swap_max_slippage_bps()
+            ),
+            default: None,
+            items: None,
+            enum_values: None,
+        },
+    );
+    if include_source {
+        properties.insert(
+            "source".to_string(),
+            string_prop("Quote source to use instead of the best one, as listed by swap_quote (e.g. 'kyberswap', 'uniswap_v4:starkbot_weth')"),
+        );
+    }
+    properties
+}
+
+fn swap_definition(name: &str, description: &str, include_source: bool) -> ToolDefinition {
+    ToolDefinition {
+        name: name.to_string(),
+        description: description.to_string(),
+        input_schema: ToolInputSchema {
+            schema_type: "object".to_string(),
+            properties: swap_properties(include_source),
+            required: vec!["sell_token".to_string(), "buy_token".to_string(), "amount".to_string()],
+        },
+        group: ToolGroup::Finance,
+    }
+}
+
+/// Resolved request plus the RPC client used for quotes and allowance checks
+struct PreparedSwap {
+    request: SwapRequest,
+    rpc: X402EvmRpc,
+}
+
+async fn prepare(params: &SwapParams, context: &ToolContext) -> Result<PreparedSwap, String> {
+    let wallet_provider = context
+        .wallet_provider
+        .as_ref()
+        .ok_or_else(|| "Wallet not configured. A wallet is required to quote swaps.".to_string())?;
+    let network = resolve_network(params.network.as_deref(), context.selected_network.as_deref())?;
+
+    let slippage_bps = params.slippage_bps.unwrap_or_else(config::swap_default_slippage_bps);
+    let max_slippage = config::swap_max_slippage_bps();
+    if slippage_bps > max_slippage {
+        return Err(format!(
+            "Slippage of {} bps exceeds the maximum of {} bps",
+            slippage_bps, max_slippage
+        ));
+    }
+
+    let rpc_config = resolve_rpc_from_context(&context.extra, network.as_ref());
+    let rpc = X402EvmRpc::new_with_wallet_provider(
+        wallet_provider.clone(),
+        network.as_ref(),
+        Some(rpc_config.url.clone()),
+        rpc_config.use_x402,
+    )?;
+
+    let sell = swap::resolve_token(&network, &params.sell_token, &rpc).await?;
+    let buy = swap::resolve_token(&network, &params.buy_token, &rpc).await?;
+    if sell.address == buy.address {
+        return Err("sell_token and buy_token are the same token".to_string());
+    }
+
+    let raw = ToRawAmountTool::convert_to_raw(&params.amount, sell.decimals)?;
+    let sell_amount = U256::from_dec_str(&raw).map_err(|e| format!("Invalid amount: {}", e))?;
+    if sell_amount.is_zero() {
+        return Err("amount must be greater than zero".to_string());
+    }
+
+    let wallet = wallet_provider.get_address();
+    let taker: Address = wallet.parse().map_err(|_| format!("Invalid wallet address: {}", wallet))?;
+
+    Ok(PreparedSwap {
+        request: SwapRequest {
+            network,
+            sell,
+            buy,
+            sell_amount,
+            taker,
+            slippage_bps,
+        },
+        rpc,
+    })
+}
+
+fn format_impact(impact: Option<f64>) -> String {
+    impact.map_or("n/a".to_string(), |p| format!("{:.2}%", p))
+}
+
+fn quote_line(quote: &SwapQuote, request: &SwapRequest) -> String {
+    format!(
+        "{}: {} {} (min {} after {} bps slippage, price impact {})",
+        quote.source,
+        swap::format_units(quote.buy_amount, request.buy.decimals),
+        request.buy.symbol,
+        swap::format_units(quote.min_buy_amount, request.buy.decimals),
+        request.slippage_bps,
+        format_impact(quote.price_impact_pct),
+    )
+}
+
+/// Swap quote tool
+pub struct SwapQuoteTool {
+    definition: ToolDefinition,
+}
+
+impl SwapQuoteTool {
+    pub fn new() -> Self {
+        SwapQuoteTool {
+            definition: swap_definition(
+                "swap_quote",
+                "Compare swap quotes from 0x, KyberSwap and configured Uniswap V3/V4 pools. Shows expected output, minimum output after slippage and price impact for each source. Read-only; use `swap` to execute.",
+                false,
+            ),
+        }
+    }
+}
+
+impl Default for SwapQuoteTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for SwapQuoteTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: SwapParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let prepared = match prepare(&params, context).await {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(e),
+        };
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured"),
+        };
+        let request = &prepared.request;
+
+        let (quotes, errors) = swap::fetch_quotes(request, wallet_provider, &prepared.rpc).await;
+        if quotes.is_empty() {
+            return ToolResult::error(format!("No swap quotes available. {}", errors.join("; ")));
+        }
+
+        let mut lines = vec![format!(
+            "Quotes for {} {} → {} on {} (best first):",
+            params.amount, request.sell.symbol, request.buy.symbol, request.network
+        )];
+        lines.extend(quotes.iter().map(|q| format!("- {}", quote_line(q, request))));
+        if !errors.is_empty() {
+            lines.push(format!("Unavailable: {}", errors.join("; ")));
+        }
+
+        ToolResult::success(lines.join("\n")).with_metadata(json!({
+            "network": request.network.to_string(),
+            "sell_token": format!("{:?}", request.sell.address),
+            "buy_token": format!("{:?}", request.buy.address),
+            "sell_amount": request.sell_amount.to_string(),
+            "slippage_bps": request.slippage_bps,
+            "quotes": quotes.iter().map(|q| q.to_json(request)).collect::<Vec<_>>(),
+            "errors": errors,
+        }))
+    }
+}
+
+/// Swap execution tool
+pub struct SwapTool {
+    definition: ToolDefinition,
+}
+
+impl SwapTool {
+    pub fn new() -> Self {
+        SwapTool {
+            definition: swap_definition(
+                "swap",
+                "Swap tokens using the best quote from 0x, KyberSwap or configured Uniswap V3/V4 pools. Queues the next missing approval (ERC-20 or Permit2) if needed - broadcast it and call swap again - otherwise queues the swap transaction. Refuses quotes above the configured price impact limit. Broadcast the queued swap with broadcast_web3_tx.",
+                true,
+            ),
+        }
+    }
+}
+
+impl Default for SwapTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for SwapTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: SwapParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+        let prepared = match prepare(&params, context).await {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(e),
+        };
+        let wallet_provider = match &context.wallet_provider {
+            Some(wp) => wp,
+            None => return ToolResult::error("Wallet not configured"),
+        };
+        let request = &prepared.request;
+
+        let (quotes, errors) = swap::fetch_quotes(request, wallet_provider, &prepared.rpc).await;
+        let quote = match &params.source {
+            Some(source) => quotes.iter().find(|q| q.source.eq_ignore_ascii_case(source.trim())),
+            None => quotes.first(),
+        };
+        let quote = match quote {
+            Some(q) => q,
+            None => {
+                return ToolResult::error(format!(
+                    "No swap quote available{}. {}",
+                    params.source.as_deref().map(|s| format!(" from '{}'", s)).unwrap_or_default(),
+                    errors.join("; ")
+                ))
+            }
+        };
+
+        let max_impact = config::swap_max_price_impact_pct();
+        if let Some(impact) = quote.price_impact_pct.filter(|i| *i > max_impact) {
+            return ToolResult::error(format!(
+                "Swap blocked: price impact of {:.2}% via {} exceeds the {:.2}% limit. Try a smaller amount.",
+                impact, quote.source, max_impact
+            ));
+        }
+
+        // Approvals first: queue only the next missing one
+        let approval = match swap::next_approval(&prepared.rpc, request, &quote.allowance).await {
+            Ok(a) => a,
+            Err(e) => return ToolResult::error(format!("Failed to check allowance: {}", e)),
+        };
+        if let Some(approval) = approval {
+            let mut result = execute_resolved_call(
+                &default_abis_dir(),
+                approval.abi,
+                &approval.contract,
+                approval.function,
+                &approval.params,
+                "0",
+                false,
+                &request.network,
+                context,
+                None,
+            )
+            .await;
+            if result.success {
+                result.content.push_str(&format!(
+                    "\n\nAPPROVAL NEEDED BEFORE SWAPPING: {}. Broadcast this approval, wait for confirmation, then call swap again with the same parameters.",
+                    approval.label
+                ));
+            }
+            return result;
+        }
+
+        let description = format!(
+            "Swap {} {} for at least {} {} via {} on {}",
+            params.amount,
+            request.sell.symbol,
+            swap::format_units(quote.min_buy_amount, request.buy.decimals),
+            request.buy.symbol,
+            quote.source,
+            request.network,
+        );
+        let swap_sell = SwapSellAmount {
+            raw_amount: request.sell_amount.to_string(),
+            decimals: request.sell.decimals as u32,
+            symbol: request.sell.symbol.clone(),
+        };
+
+        match queue_raw_transaction(
+            &request.network,
+            quote.tx.to,
+            quote.tx.data.clone(),
+            quote.tx.value,
+            "swap",
+            description.clone(),
+            Some(swap_sell),
+            context,
+        )
+        .await
+        {
+            Ok((uuid, signed)) => ToolResult::success(format!(
+                "SWAP QUEUED (not yet broadcast)\n\n\
+                UUID: {}\n\
+                {}\n\
+                Expected: {} {}\n\
+                Price impact: {}\n\
+                Network: {}\n\
+                Nonce: {}\n\n\
+                --- Next Steps ---\n\
+                To broadcast: use `broadcast_web3_tx` with uuid: {}",
+                uuid,
+                description,
+                swap::format_units(quote.buy_amount, request.buy.decimals),
+                request.buy.symbol,
+                format_impact(quote.price_impact_pct),
+                signed.network,
+                signed.nonce,
+                uuid
+            ))
+            .with_metadata(json!({
+                "uuid": uuid,
+                "status": "queued",
+                "quote": quote.to_json(request),
+                "from": signed.from,
+                "to": signed.to,
+                "value": signed.value,
+                "nonce": signed.nonce,
+                "network": signed.network,
+            })),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
@@ -0,0 +1,425 @@
+//! Token approvals tool - audit and revoke ERC-20 allowances
+//!
+//! Lists the live allowances of the bot wallet and tracked Safes found by the
+//! approval scanner (see `crate::approvals`) with spender labels and risk, runs
+//! a scan on demand and queues `approve(spender, 0)` revocations.
+
+use crate::approvals;
+use crate::db::tables::approvals::TokenApproval;
+use crate::tools::registry::Tool;
+use crate::tools::rpc_config::resolve_rpc_from_context;
+use crate::tools::types::{
+    PropertySchema, ToolContext, ToolDefinition, ToolGroup, ToolInputSchema, ToolResult,
+};
+use crate::web3::contract_wallet::get_safe_owners;
+use crate::web3::multicall;
+use crate::web3::safe::{self, Operation};
+use crate::web3::{queue_raw_transaction, resolve_network};
+use crate::x402::X402EvmRpc;
+use async_trait::async_trait;
+use ethers::types::{Address, U256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Token approvals tool
+pub struct TokenApprovalsTool {
+    definition: ToolDefinition,
+}
+
+impl TokenApprovalsTool {
+    pub fn new() -> Self {
+        let mut properties = HashMap::new();
+
+        properties.insert(
+            "action".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "'list' = live allowances with spender and risk, 'scan' = scan Approval events on all networks now, 'revoke' = queue a transaction setting an allowance to 0".to_string(),
+                default: Some(json!("list")),
+                items: None,
+                enum_values: Some(vec![
+                    "list".to_string(),
+                    "scan".to_string(),
+                    "revoke".to_string(),
+                ]),
+            },
+        );
+        properties.insert(
+            "approval_id".to_string(),
+            PropertySchema {
+                schema_type: "integer".to_string(),
+                description: "Approval ID from 'list' (required for revoke)".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "network".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Only list approvals on this network".to_string(),
+                default: None,
+                items: None,
+                enum_values: None,
+            },
+        );
+        properties.insert(
+            "min_risk".to_string(),
+            PropertySchema {
+                schema_type: "string".to_string(),
+                description: "Only list approvals at or above this risk".to_string(),
+                default: Some(json!("low")),
+                items: None,
+                enum_values: Some(vec![
+                    "low".to_string(),
+                    "medium".to_string(),
+                    "high".to_string(),
+                ]),
+            },
+        );
+
+        TokenApprovalsTool {
+            definition: ToolDefinition {
+                name: "token_approvals".to_string(),
+                description: "Audit ERC-20 token approvals granted by the bot wallet and tracked Safes on every network: spender labels, unlimited approvals and unknown spenders. Can queue revocations (approve 0).".to_string(),
+                input_schema: ToolInputSchema {
+                    schema_type: "object".to_string(),
+                    properties,
+                    required: vec![],
+                },
+                group: ToolGroup::Finance,
+            },
+        }
+    }
+}
+
+impl Default for TokenApprovalsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenApprovalsParams {
+    #[serde(default = "default_action")]
+    action: String,
+    approval_id: Option<i64>,
+    network: Option<String>,
+    min_risk: Option<String>,
+}
+
+fn default_action() -> String {
+    "list".to_string()
+}
+
+fn parse_risk(risk: Option<&str>) -> Result<approvals::Risk, String> {
+    match risk.unwrap_or("low") {
+        "low" => Ok(approvals::Risk::Low),
+        "medium" => Ok(approvals::Risk::Medium),
+        "high" => Ok(approvals::Risk::High),
+        other => Err(format!("Unknown risk '{}'. Use: low, medium, high", other)),
+    }
+}
+
+fn token_name(approval: &TokenApproval) -> String {
+    approval
+        .token_symbol
+        .clone()
+        .unwrap_or_else(|| approval.token.clone())
+}
+
+impl TokenApprovalsTool {
+    fn list(&self, params: &TokenApprovalsParams, context: &ToolContext) -> ToolResult {
+        let db = match &context.database {
+            Some(db) => db,
+            None => return ToolResult::error("Database not available"),
+        };
+        let min_risk = match parse_risk(params.min_risk.as_deref()) {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(e),
+        };
+        let live = match db.list_token_approvals(params.network.as_deref(), None, true) {
+            Ok(a) => a,
+            Err(e) => return ToolResult::error(format!("Failed to load approvals: {}", e)),
+        };
+        let last_scan = db.latest_approval_scan_at().ok().flatten();
+        if last_scan.is_none() {
+            return ToolResult::success("No approval scan has run yet. Use action 'scan' to scan now.");
+        }
+
+        let contacts = db.list_contacts().unwrap_or_default();
+        let labels = approvals::spender_labels(&contacts);
+        let audited: Vec<_> = approvals::audit(live, &labels)
+            .into_iter()
+            .filter(|a| a.risk >= min_risk)
+            .collect();
+
+        let bot = context
+            .wallet_provider
+            .as_ref()
+            .map(|wp| wp.get_address().to_lowercase())
+            .unwrap_or_default();
+        let mut lines = vec![format!(
+            "{} live approvals (last scan {})",
+            audited.len(),
+            last_scan.map(|t| t.to_rfc3339()).unwrap_or_default()
+        )];
+        for a in &audited {
+            let owner = if a.approval.owner == bot {
+                "bot wallet".to_string()
+            } else {
+                format!("Safe {}", a.approval.owner)
+            };
+            let mut line = format!(
+                "- #{} [{}] {} {} on {} from {} -> {} ({})",
+                a.approval.id,
+                a.risk.as_str(),
+                a.amount,
+                token_name(&a.approval),
+                a.approval.network,
+                owner,
+                a.approval.spender,
+                a.spender_label.as_deref().unwrap_or("unknown"),
+            );
+            if !a.reasons.is_empty() {
+                line.push_str(&format!(": {}", a.reasons.join(", ")));
+            }
+            lines.push(line);
+        }
+        if audited.iter().any(|a| a.risk > approvals::Risk::Low) {
+            lines.push("\nTo revoke one, call token_approvals with action 'revoke' and its approval_id.".to_string());
+        }
+
+        ToolResult::success(lines.join("\n")).with_metadata(json!({ "approvals": audited }))
+    }
+
+    async fn scan(&self, context: &ToolContext) -> ToolResult {
+        let (db, wallet_provider) = match (&context.database, &context.wallet_provider) {
+            (Some(db), Some(wp)) => (db, wp),
+            (None, _) => return ToolResult::error("Database not available"),
+            (_, None) => return ToolResult::error("No wallet configured - cannot scan approvals"),
+        };
+        match approvals::scan_approvals(db, wallet_provider).await {
+            Ok(summary) => {
+                let mut msg = format!(
+                    "Approval scan done: {} networks, {} wallets, {} new Approval events, {} live approvals.",
+                    summary.networks.len(),
+                    summary.wallets,
+                    summary.new_events,
+                    summary.live_approvals
+                );
+                if !summary.errors.is_empty() {
+                    msg.push_str(&format!("\nIncomplete: {}", summary.errors.join("; ")));
+                }
+                msg.push_str("\nUse action 'list' to review them.");
+                ToolResult::success(msg).with_metadata(json!({ "summary": summary }))
+            }
+            Err(e) => ToolResult::error(format!("Approval scan failed: {}", e)),
+        }
+    }
+
+    async fn revoke(&self, params: &TokenApprovalsParams, context: &ToolContext) -> ToolResult {
+        let (db, wallet_provider) = match (&context.database, &context.wallet_provider) {
+            (Some(db), Some(wp)) => (db, wp),
+            (None, _) => return ToolResult::error("Database not available"),
+            (_, None) => return ToolResult::error("Wallet not configured. A wallet is required to revoke approvals."),
+        };
+        let Some(id) = params.approval_id else {
+            return ToolResult::error("'approval_id' is required for revoke (see action 'list')");
+        };
+        let approval = match db.get_token_approval(id) {
+            Ok(Some(a)) => a,
+            Ok(None) => return ToolResult::error(format!("Approval #{} not found", id)),
+            Err(e) => return ToolResult::error(format!("Failed to load approval: {}", e)),
+        };
+
+        let network = match resolve_network(Some(&approval.network), None) {
+            Ok(n) => n,
+            Err(e) => return ToolResult::error(e),
+        };
+        let (Ok(token), Ok(owner), Ok(spender)) = (
+            approval.token.parse::<Address>(),
+            approval.owner.parse::<Address>(),
+            approval.spender.parse::<Address>(),
+        ) else {
+            return ToolResult::error(format!("Approval #{} has an invalid address", id));
+        };
+        let bot: Address = match wallet_provider.get_address().parse() {
+            Ok(a) => a,
+            Err(_) => return ToolResult::error("Invalid wallet address"),
+        };
+
+        let rpc_config = resolve_rpc_from_context(&context.extra, network.as_ref());
+        let rpc = match X402EvmRpc::new_with_wallet_provider(
+            wallet_provider.clone(),
+            network.as_ref(),
+            Some(rpc_config.url.clone()),
+            rpc_config.use_x402,
+        ) {
+            Ok(r) => r,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        // Re-read the allowance so an already revoked or spent approval is not revoked twice
+        let current = match multicall::aggregate3(&rpc, &[multicall::erc20_allowance_call(token, owner, spender)]).await {
+            Ok(results) => results.first().and_then(|r| r.as_u256()),
+            Err(e) => return ToolResult::error(format!("Failed to read allowance: {}", e)),
+        };
+        if current == Some(U256::zero()) {
+            let _ = db.update_approval_allowance(id, "0", None, None);
+            return ToolResult::success(format!(
+                "Approval #{} ({} to {}) is already 0. Nothing to revoke.",
+                id,
+                token_name(&approval),
+                approval.spender
+            ));
+        }
+
+        let revoke_data = approvals::encode_approve(spender, U256::zero());
+        let description = format!(
+            "Revoke {} allowance of {} on {}",
+            token_name(&approval),
+            approval.spender,
+            network
+        );
+
+        // The bot wallet revokes directly; a Safe revokes through a Safe transaction
+        let (to, calldata, safe_call) = if owner == bot {
+            (token, revoke_data, None)
+        } else {
+            match get_safe_owners(&rpc, owner).await {
+                Ok(owners) if owners.contains(&bot) => {}
+                Ok(_) => {
+                    return ToolResult::error(format!(
+                        "The bot wallet is not an owner of Safe {}; revoke from the Safe UI instead.",
+                        approval.owner
+                    ));
+                }
+                Err(e) => return ToolResult::error(format!("Failed to read Safe owners: {}", e)),
+            }
+            match safe::prepare_owner_call(&rpc, owner, bot, token, U256::zero(), revoke_data, Operation::Call).await {
+                Ok(call) => (owner, call.calldata.clone(), Some(call)),
+                Err(e) => return ToolResult::error(e),
+            }
+        };
+
+        let (uuid, signed) = match queue_raw_transaction(
+            &network,
+            to,
+            calldata,
+            U256::zero(),
+            "revoke_approval",
+            description.clone(),
+            None,
+            context,
+        )
+        .await
+        {
+            Ok(queued) => queued,
+            Err(e) => return ToolResult::error(e),
+        };
+
+        let safe_note = match &safe_call {
+            Some(call) if !call.executes => format!(
+                "\nThis queues the bot's approveHash of Safe tx {:?}; other Safe owners must confirm it before it executes.",
+                call.safe_tx_hash
+            ),
+            _ => String::new(),
+        };
+
+        ToolResult::success(format!(
+            "REVOCATION QUEUED (not yet broadcast)\n\n\
+            UUID: {}\n\
+            {}\n\
+            From: {}\n\
+            Nonce: {}{}\n\n\
+            --- Next Steps ---\n\
+            To broadcast: use `broadcast_web3_tx` with uuid: {}\n\
+            Run token_approvals action 'scan' after it confirms to refresh the list.",
+            uuid, description, signed.from, signed.nonce, safe_note, uuid
+        ))
+        .with_metadata(json!({
+            "uuid": uuid,
+            "approval_id": id,
+            "network": signed.network,
+            "token": approval.token,
+            "spender": approval.spender,
+            "owner": approval.owner,
+            "safe_tx_hash": safe_call.map(|c| format!("{:?}", c.safe_tx_hash)),
+            "from": signed.from,
+            "to": signed.to,
+            "nonce": signed.nonce,
+        }))
+    }
+}
+
+#[async_trait]
+impl Tool for TokenApprovalsTool {
+    fn definition(&self) -> ToolDefinition {
+        self.definition.clone()
+    }
+
+    async fn execute(&self, params: Value, context: &ToolContext) -> ToolResult {
+        let params: TokenApprovalsParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => return ToolResult::error(format!("Invalid parameters: {}", e)),
+        };
+
+        match params.action.as_str() {
+            "list" => self.list(&params, context),
+            "scan" => self.scan(context).await,
+            "revoke" => self.revoke(&params, context).await,
+            other => ToolResult::error(format!(
+                "Unknown action '{}'. Use: list, scan, revoke",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn params(action: &str) -> TokenApprovalsParams {
+        TokenApprovalsParams {
+            action: action.to_string(),
+            approval_id: None,
+            network: None,
+            min_risk: Some("medium".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_list_filters_by_risk() {
+        let db = Arc::new(crate::db::Database::new(":memory:").unwrap());
+        let owner = "0x1111111111111111111111111111111111111111";
+        let permit2 = "0x000000000022d473030f116ddee9f6b43ac78ba3";
+        let unknown = "0x2222222222222222222222222222222222222222";
+        db.record_approval_event("base", owner, "0x3333333333333333333333333333333333333333", permit2, 1).unwrap();
+        db.record_approval_event("base", owner, "0x3333333333333333333333333333333333333333", unknown, 2).unwrap();
+        for approval in db.list_token_approvals(None, None, false).unwrap() {
+            db.update_approval_allowance(approval.id, "1000", Some("USDC"), Some(6)).unwrap();
+        }
+        db.set_approval_scan_cursor("base", owner, 2).unwrap();
+
+        let ctx = ToolContext::new().with_database(db);
+        let result = TokenApprovalsTool::new().list(&params("list"), &ctx);
+        assert!(result.success);
+        let approvals = result.metadata.unwrap()["approvals"].as_array().unwrap().clone();
+        assert_eq!(approvals.len(), 1);
+        assert_eq!(approvals[0]["spender"], unknown);
+        assert_eq!(approvals[0]["risk"], "medium");
+        assert_eq!(approvals[0]["amount"], "0.001");
+    }
+
+    #[test]
+    fn test_parse_risk() {
+        assert_eq!(parse_risk(None).unwrap(), approvals::Risk::Low);
+        assert_eq!(parse_risk(Some("high")).unwrap(), approvals::Risk::High);
+        assert!(parse_risk(Some("extreme")).is_err());
+    }
+}
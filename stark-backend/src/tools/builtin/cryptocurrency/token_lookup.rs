@@ -1,7 +1,7 @@
 //! Token Lookup tool for resolving token symbols to addresses
 //!
 //! Provides a lookup table for known tokens on supported networks.
-//! Token data is loaded from config/tokens.ron at startup.
+//! Token data is loaded from config/tokens.ron at startup and can be reloaded at runtime.
 //! This prevents hallucination of token addresses for common tokens.
 
 use crate::tools::registry::Tool;
@@ -13,10 +13,13 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::{Arc, RwLock};
 
-/// Global token storage (loaded once at startup)
-static TOKENS: OnceLock<HashMap<String, HashMap<String, TokenInfo>>> = OnceLock::new();
+/// Tokens per network, keyed by uppercase symbol
+type TokenMap = HashMap<String, HashMap<String, TokenInfo>>;
+
+/// Global token storage (loaded at startup, swapped as a whole on reload)
+static TOKENS: RwLock<Option<Arc<TokenMap>>> = RwLock::new(None);
 
 /// Token info loaded from config
 #[derive(Debug, Clone, Deserialize)]
@@ -28,17 +31,20 @@ pub struct TokenInfo {
 
 /// Load tokens from config directory. Panics if config file is missing or invalid.
 pub fn load_tokens(config_dir: &Path) {
-    let tokens_path = config_dir.join("tokens.ron");
-
-    if !tokens_path.exists() {
-        panic!("[tokens] Config file not found: {:?}", tokens_path);
+    if let Err(e) = reload_tokens(config_dir) {
+        panic!("[tokens] {}", e);
     }
+}
 
+/// Parse and validate tokens.ron, then swap it in. On error the current tokens stay loaded.
+/// Returns the number of tokens across all networks.
+pub fn reload_tokens(config_dir: &Path) -> Result<usize, String> {
+    let tokens_path = config_dir.join("tokens.ron");
     let content = std::fs::read_to_string(&tokens_path)
-        .unwrap_or_else(|e| panic!("[tokens] Failed to read {:?}: {}", tokens_path, e));
-
-    let tokens: HashMap<String, HashMap<String, TokenInfo>> = ron::from_str(&content)
-        .unwrap_or_else(|e| panic!("[tokens] Failed to parse {:?}: {}", tokens_path, e));
+        .map_err(|e| format!("Failed to read {:?}: {}", tokens_path, e))?;
+    let tokens: TokenMap = ron::from_str(&content)
+        .map_err(|e| format!("Failed to parse {:?}: {}", tokens_path, e))?;
+    validate_tokens(&tokens)?;
 
     let total: usize = tokens.values().map(|t| t.len()).sum();
     log::info!(
@@ -48,18 +54,35 @@ pub fn load_tokens(config_dir: &Path) {
         tokens_path
     );
 
-    let _ = TOKENS.set(tokens);
+    *TOKENS.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(tokens));
+    Ok(total)
+}
+
+fn validate_tokens(tokens: &TokenMap) -> Result<(), String> {
+    for (network, network_tokens) in tokens {
+        for (symbol, info) in network_tokens {
+            if info.address.parse::<ethers::types::Address>().is_err() {
+                return Err(format!("Token {} on {}: invalid address '{}'", symbol, network, info.address));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Current token set, if loaded
+fn loaded_tokens() -> Option<Arc<TokenMap>> {
+    TOKENS.read().unwrap_or_else(|e| e.into_inner()).clone()
 }
 
 /// Get tokens. Panics if load_tokens() was not called.
-fn get_tokens() -> &'static HashMap<String, HashMap<String, TokenInfo>> {
-    TOKENS.get().expect("[tokens] Token config not loaded - call load_tokens() first")
+fn get_tokens() -> Arc<TokenMap> {
+    loaded_tokens().expect("[tokens] Token config not loaded - call load_tokens() first")
 }
 
 /// Get all token symbols with their names (for context bank scanning)
 /// Returns a list of (symbol, name) pairs from all networks
 pub fn get_all_token_symbols() -> Vec<(String, String)> {
-    let tokens = match TOKENS.get() {
+    let tokens = match loaded_tokens() {
         Some(t) => t,
         None => return Vec::new(), // Return empty if tokens not loaded yet
     };
@@ -79,6 +102,32 @@ pub fn get_all_token_symbols() -> Vec<(String, String)> {
     result
 }
 
+/// Get the networks that have a token list configured
+pub fn get_token_networks() -> Vec<String> {
+    let mut networks: Vec<String> = loaded_tokens()
+        .map(|tokens| tokens.keys().cloned().collect())
+        .unwrap_or_default();
+    networks.sort();
+    networks
+}
+
+/// Get all configured tokens for a network as (symbol, info) pairs, sorted by symbol.
+/// Returns an empty list for unknown networks (no fallback to base).
+pub fn get_network_tokens(network: &str) -> Vec<(String, TokenInfo)> {
+    let mut tokens: Vec<(String, TokenInfo)> = loaded_tokens()
+        .as_deref()
+        .and_then(|tokens| tokens.get(network))
+        .map(|network_tokens| {
+            network_tokens
+                .iter()
+                .map(|(symbol, info)| (symbol.clone(), info.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    tokens.sort_by(|a, b| a.0.cmp(&b.0));
+    tokens
+}
+
 /// Token Lookup tool
 pub struct TokenLookupTool {
     definition: ToolDefinition,
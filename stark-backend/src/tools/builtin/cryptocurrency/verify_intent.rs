@@ -11,10 +11,10 @@
 //! 3. Run isolated AI verification call
 //! 4. Return `Ok(())` or `Err(reason)`
 
+use crate::address_book::{Contact, TrustLevel};
 use crate::ai::{AiClient, Message, MessageRole};
 use crate::gateway::protocol::GatewayEvent;
 use crate::tools::types::ToolContext;
-use serde_json::Value;
 
 /// Describes the transaction about to be queued.
 #[derive(Debug, Clone)]
@@ -29,9 +29,19 @@ pub struct TransactionIntent {
     pub preset_name: Option<String>,
     pub destination_chain: Option<String>,
     pub calldata: Option<String>,
+    /// Sell side of a swap, set by tools that build the swap themselves
+    pub swap_sell: Option<SwapSellAmount>,
     pub description: String,
 }
 
+/// Amount a swap sells, in raw token units.
+#[derive(Debug, Clone)]
+pub struct SwapSellAmount {
+    pub raw_amount: String,
+    pub decimals: u32,
+    pub symbol: String,
+}
+
 // ─── Public entry point ──────────────────────────────────────────────────────
 
 /// Verify that a transaction intent matches the user's original request.
@@ -108,7 +118,7 @@ async fn run_verification(
         },
     ];
 
-    let ai_response = client.generate_text(messages).await;
+    let ai_response = client.generate_sidecar_text(messages, context.database.as_deref(), context.usage_scope()).await;
 
     match ai_response {
         Ok(text) => parse_verification_response(&text),
@@ -186,23 +196,43 @@ fn run_deterministic_checks(
         }
     }
 
-    // 3. Recipient address should appear in registers or context bank
-    //    (anti-hallucination check)
+    // 3. Address book: blocked contacts and contacts restricted to other networks
+    let contact = address_book_contact(&to_lower, context);
+    if let Some(ref contact) = contact {
+        if contact.trust_level == TrustLevel::Blocked {
+            return Err(format!(
+                "Transaction blocked: {} ({}) is marked as blocked in the address book.",
+                intent.to, contact.label
+            ));
+        }
+        if !contact.allows_network(&intent.network) {
+            return Err(format!(
+                "Transaction blocked: address book contact '{}' only receives on {}, not {}.",
+                contact.label,
+                contact.networks.join(", "),
+                intent.network
+            ));
+        }
+    }
+
+    // 4. Recipient address should appear in registers, the context bank or as a
+    //    trusted address book contact (anti-hallucination check)
     if intent.tx_type == "eth_transfer" {
         let address_in_registers = address_exists_in_registers(&to_lower, context);
         let address_in_context_bank = address_exists_in_context_bank(&to_lower, context);
+        let trusted_contact = contact.is_some_and(|c| c.trust_level == TrustLevel::Trusted);
 
-        if !address_in_registers && !address_in_context_bank {
+        if !address_in_registers && !address_in_context_bank && !trusted_contact {
             return Err(format!(
-                "Transaction blocked: recipient address {} was not found in any register \
-                 or in the context bank. This may indicate a hallucinated address. \
-                 Use set_address to store the address first.",
+                "Transaction blocked: recipient address {} was not found in any register, \
+                 in the context bank or as a trusted address book contact. This may indicate \
+                 a hallucinated address. Use set_address to store the address first.",
                 intent.to
             ));
         }
     }
 
-    // 4. Swap sell amount verification (for swap_execute preset only)
+    // 5. Swap sell amount verification (swap tool or swap_execute preset)
     check_swap_sell_amount(intent, context)?;
 
     Ok(())
@@ -222,10 +252,25 @@ fn address_exists_in_registers(addr: &str, context: &ToolContext) -> bool {
     false
 }
 
-/// Check whether `addr` (lowercase) appears in the context bank's eth_address items.
+/// Address book entry for `addr` (lowercase), if any
+fn address_book_contact(addr: &str, context: &ToolContext) -> Option<Contact> {
+    let db = context.database.as_ref()?;
+    match db.get_contact_by_address(addr) {
+        Ok(contact) => contact,
+        Err(e) => {
+            log::warn!("[verify_intent] Address book lookup failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Check whether `addr` (lowercase) appears in the context bank's eth_address or
+/// contact items (contacts the user referred to by label or ENS name).
 fn address_exists_in_context_bank(addr: &str, context: &ToolContext) -> bool {
     for item in context.context_bank.items() {
-        if item.item_type == "eth_address" && item.value.to_lowercase() == addr {
+        if (item.item_type == "eth_address" || item.item_type == "contact")
+            && item.value.to_lowercase() == addr
+        {
             return true;
         }
     }
@@ -234,55 +279,34 @@ fn address_exists_in_context_bank(addr: &str, context: &ToolContext) -> bool {
 
 /// Check that the swap sell amount matches what the user stated in their message.
 ///
-/// Only applies to `swap_execute` preset transactions. Reads `sell_amount`,
-/// `sell_token_decimals`, and `sell_token_symbol` from registers, then parses
-/// the user's original message for amounts paired with the sell token.
+/// Uses `intent.swap_sell` when the tool supplied it (the `swap` tool). Otherwise
+/// only applies to `swap_execute` preset transactions and reads `sell_amount`,
+/// `sell_token_decimals`, and `sell_token_symbol` from registers. The user's
+/// original message is then parsed for amounts paired with the sell token.
 ///
 /// Handles shorthand: "1k" = 1,000, "1m" = 1,000,000, "1b" = 1,000,000,000.
 /// Also handles comma-separated numbers and word multipliers ("1 million").
 ///
-/// Fails open if any register is missing (the check simply cannot run).
+/// Fails open if the sell amount is unknown (the check simply cannot run).
 /// Fails closed (blocks) only when a clear mismatch is found.
 fn check_swap_sell_amount(
     intent: &TransactionIntent,
     context: &ToolContext,
 ) -> Result<(), String> {
-    // Only applies to swap_execute preset
-    if intent.preset_name.as_deref() != Some("swap_execute") {
-        return Ok(());
-    }
-
-    // Read required registers — skip check if any is missing
-    let sell_amount_raw = match context
-        .registers
-        .get("sell_amount")
-        .and_then(|v| v.as_str().map(|s| s.to_string()))
-    {
-        Some(s) => s,
-        None => return Ok(()),
-    };
-
-    let decimals: u32 = match context.registers.get("sell_token_decimals") {
-        Some(v) => {
-            let d = v
-                .as_u64()
-                .map(|d| d as u32)
-                .or_else(|| v.as_str().and_then(|s| s.parse().ok()));
-            match d {
-                Some(d) => d,
+    let sell = match &intent.swap_sell {
+        Some(sell) => sell.clone(),
+        // Only the swap_execute preset reads the sell side from registers
+        None if intent.preset_name.as_deref() == Some("swap_execute") => {
+            match swap_sell_from_registers(context) {
+                Some(sell) => sell,
                 None => return Ok(()),
             }
         }
         None => return Ok(()),
     };
-
-    let sell_symbol = match context.registers.get("sell_token_symbol") {
-        Some(v) => match v.as_str() {
-            Some(s) => s.to_uppercase(),
-            None => return Ok(()),
-        },
-        None => return Ok(()),
-    };
+    let sell_amount_raw = sell.raw_amount;
+    let decimals = sell.decimals;
+    let sell_symbol = sell.symbol.to_uppercase();
 
     let user_message = match context
         .extra
@@ -311,7 +335,7 @@ fn check_swap_sell_amount(
     for amount in &paired_amounts {
         if amounts_match(*amount, human_sell) {
             log::info!(
-                "[verify_intent] Swap sell amount check PASSED: user said {} {}, register has {} {}",
+                "[verify_intent] Swap sell amount check PASSED: user said {} {}, swap sells {} {}",
                 amount, sell_symbol, human_sell, sell_symbol
             );
             return Ok(());
@@ -320,17 +344,43 @@ fn check_swap_sell_amount(
 
     // Mismatch detected — block
     log::warn!(
-        "[verify_intent] Swap sell amount MISMATCH: user said {:?} {}, but register has {} raw = {} {}",
+        "[verify_intent] Swap sell amount MISMATCH: user said {:?} {}, but swap sells {} raw = {} {}",
         paired_amounts, sell_symbol, sell_amount_raw, human_sell, sell_symbol
     );
     Err(format!(
         "Transaction blocked: swap sell amount mismatch. \
-         User requested {:?} {} but sell_amount register contains {} ({} {}). \
+         User requested {:?} {} but the swap sells {} raw ({} {}). \
          Verify the correct amount before retrying.",
         paired_amounts, sell_symbol, sell_amount_raw, human_sell, sell_symbol,
     ))
 }
 
+/// Read the swap sell side from the registers set by the swap skill.
+/// Returns None if any register is missing.
+fn swap_sell_from_registers(context: &ToolContext) -> Option<SwapSellAmount> {
+    let raw_amount = context
+        .registers
+        .get("sell_amount")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))?;
+
+    let decimals = context.registers.get("sell_token_decimals").and_then(|v| {
+        v.as_u64()
+            .map(|d| d as u32)
+            .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+    })?;
+
+    let symbol = context
+        .registers
+        .get("sell_token_symbol")
+        .and_then(|v| v.as_str().map(|s| s.to_string()))?;
+
+    Some(SwapSellAmount {
+        raw_amount,
+        decimals,
+        symbol,
+    })
+}
+
 /// Check if two amounts match within tolerance (0.1%).
 fn amounts_match(a: f64, b: f64) -> bool {
     if a == 0.0 && b == 0.0 {
@@ -603,6 +653,7 @@ mod tests {
             preset_name: None,
             destination_chain: None,
             calldata: None,
+            swap_sell: None,
             description: "test tx".to_string(),
         }
     }
@@ -675,6 +726,55 @@ mod tests {
         assert!(run_deterministic_checks(&intent, &ctx).is_ok());
     }
 
+    fn context_with_contact(addr: &str, trust_level: TrustLevel, networks: &[&str]) -> ToolContext {
+        let db = std::sync::Arc::new(crate::db::Database::new(":memory:").unwrap());
+        db.add_contact(&crate::address_book::ContactInput {
+            label: "Alice".to_string(),
+            address: Some(addr.to_string()),
+            networks: networks.iter().map(|n| n.to_string()).collect(),
+            trust_level,
+            ..Default::default()
+        })
+        .unwrap();
+        ToolContext::new().with_database(db)
+    }
+
+    #[test]
+    fn test_address_book_trust_levels() {
+        let addr = "0x1111111111111111111111111111111111111111";
+        let intent = make_intent("eth_transfer", addr);
+
+        // Trusted contacts are accepted without being mentioned
+        let ctx = context_with_contact(addr, TrustLevel::Trusted, &[]);
+        assert!(run_deterministic_checks(&intent, &ctx).is_ok());
+
+        // Known contacts still have to come from the user's message
+        let ctx = context_with_contact(addr, TrustLevel::Known, &[]);
+        assert!(run_deterministic_checks(&intent, &ctx).is_err());
+        ctx.context_bank.add(ContextBankItem {
+            value: addr.to_string(),
+            item_type: "contact".to_string(),
+            label: Some("Alice".to_string()),
+        });
+        assert!(run_deterministic_checks(&intent, &ctx).is_ok());
+
+        // Blocked contacts are rejected for every transaction type
+        let ctx = context_with_contact(addr, TrustLevel::Blocked, &[]);
+        ctx.registers.set("send_to", serde_json::json!(addr), "set_address");
+        let err = run_deterministic_checks(&intent, &ctx).unwrap_err();
+        assert!(err.contains("blocked in the address book"), "got: {}", err);
+        let err = run_deterministic_checks(&make_intent("contract_call", addr), &ctx).unwrap_err();
+        assert!(err.contains("blocked in the address book"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_address_book_network_restriction() {
+        let addr = "0x1111111111111111111111111111111111111111";
+        let ctx = context_with_contact(addr, TrustLevel::Trusted, &["mainnet"]);
+        let err = run_deterministic_checks(&make_intent("eth_transfer", addr), &ctx).unwrap_err();
+        assert!(err.contains("only receives on mainnet"), "got: {}", err);
+    }
+
     #[test]
     fn test_contract_call_skips_register_check() {
         // Contract calls don't require the "to" address to be in registers
@@ -888,6 +988,7 @@ mod tests {
             preset_name: None,
             destination_chain: None,
             calldata: None,
+            swap_sell: None,
             description: "Send 0.01 ETH".to_string(),
         };
 
@@ -920,6 +1021,7 @@ mod tests {
             preset_name: None,
             destination_chain: None,
             calldata: None,
+            swap_sell: None,
             description: "ERC20 transfer".to_string(),
         };
 
@@ -949,6 +1051,7 @@ mod tests {
             preset_name: None,
             destination_chain: Some("polygon".to_string()),
             calldata: None,
+            swap_sell: None,
             description: "Bridge 100 USDC from base to polygon".to_string(),
         };
 
@@ -1119,6 +1222,7 @@ mod tests {
             preset_name: Some("swap_execute".to_string()),
             destination_chain: None,
             calldata: None,
+            swap_sell: None,
             description: "Swap via 0x".to_string(),
         }
     }
@@ -1176,6 +1280,34 @@ mod tests {
         assert!(result.unwrap_err().contains("mismatch"), "Should mention mismatch");
     }
 
+    #[test]
+    fn test_swap_amount_check_uses_intent_sell_amount() {
+        // The swap tool supplies the sell side directly; registers are not consulted
+        let mut intent = make_swap_intent();
+        intent.preset_name = None;
+        intent.swap_sell = Some(SwapSellAmount {
+            raw_amount: "1000000000".to_string(), // 1000 USDC
+            decimals: 6,
+            symbol: "USDC".to_string(),
+        });
+        let registers = RegisterStore::new();
+        registers.set("sell_amount", serde_json::json!("1000000"), "to_raw_amount");
+        registers.set("sell_token_decimals", serde_json::json!(6), "token_lookup");
+        registers.set("sell_token_symbol", serde_json::json!("USDC"), "token_lookup");
+        let mut ctx = ToolContext::new().with_registers(registers);
+        ctx.extra.insert(
+            "original_user_message".to_string(),
+            serde_json::json!("swap 1 USDC to WETH"),
+        );
+        assert!(check_swap_sell_amount(&intent, &ctx).is_err());
+
+        ctx.extra.insert(
+            "original_user_message".to_string(),
+            serde_json::json!("swap 1k USDC to WETH"),
+        );
+        assert!(check_swap_sell_amount(&intent, &ctx).is_ok());
+    }
+
     #[test]
     fn test_swap_amount_check_passes_eth_18_decimals() {
         // User says "swap 0.5 ETH", sell_amount = 500000000000000000 (0.5 ETH)
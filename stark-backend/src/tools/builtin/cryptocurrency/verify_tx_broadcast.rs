@@ -438,7 +438,7 @@ async fn run_ai_verification(
         },
     ];
 
-    match client.generate_text(messages).await {
+    match client.generate_sidecar_text(messages, context.database.as_deref(), context.usage_scope()).await {
         Ok(text) => Some(parse_post_tx_response(&text)),
         Err(e) => {
             log::warn!(
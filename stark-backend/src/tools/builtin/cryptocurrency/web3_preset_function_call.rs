@@ -191,6 +191,9 @@ impl Tool for Web3PresetFunctionCallTool {
                     };
                     resolved_params.push(json!(param_str));
                 }
+                None if preset.register_defaults.contains_key(reg_key) => {
+                    resolved_params.push(json!(preset.register_defaults[reg_key]));
+                }
                 None => {
                     return ToolResult::error(format!(
                         "Preset '{}' requires register '{}' but it's not set",
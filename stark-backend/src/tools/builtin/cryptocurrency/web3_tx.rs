@@ -414,6 +414,7 @@ impl Tool for SendEthTool {
                     preset_name: None,
                     destination_chain: None,
                     calldata: None,
+                    swap_sell: None,
                     description: format!(
                         "Send {} to {} on {}",
                         Self::format_eth(&signed.value),
@@ -32,9 +32,9 @@ pub use core::{
     SetAgentSubtypeTool, SubagentStatusTool, SubagentTool, TaskFullyCompletedTool,
 };
 pub use cryptocurrency::{
-    load_networks, load_tokens, BridgeUsdcTool, BroadcastWeb3TxTool, DecodeCalldataTool,
-    DexScreenerTool, Erc8128FetchTool, GeckoTerminalTool, ListQueuedWeb3TxTool, PolymarketTradeTool,
-    SelectWeb3NetworkTool, SendEthTool, SetAddressTool, ToRawAmountTool, TokenLookupTool,
+    AaveTool, AddressBookTool, BatchPayoutTool, BridgeUsdcTool, BroadcastWeb3TxTool, DecodeCalldataTool,
+    DexScreenerTool, Erc8128FetchTool, GeckoTerminalTool, ListQueuedWeb3TxTool, PolymarketPositionsTool, PolymarketTradeTool,
+    PortfolioTool, SelectWeb3NetworkTool, SendEthTool, SetAddressTool, SwapQuoteTool, SwapTool, ToRawAmountTool, TokenApprovalsTool, TokenLookupTool,
     VerifyTxBroadcastTool, Web3PresetFunctionCallTool, X402AgentInvokeTool, X402FetchTool,
     X402PostTool, X402RpcTool,
 };
@@ -1,6 +1,7 @@
 //! QMD Memory Search Tool
 //!
-//! Full-text search across memory markdown files using FTS5 BM25 ranking.
+//! Search across memory markdown files: FTS5 BM25 ranking, fused with
+//! embedding similarity when semantic search is configured.
 //! In safe mode, results are sandboxed to the safemode/ memory directory only.
 
 use crate::tools::registry::Tool;
@@ -25,7 +26,7 @@ impl QmdMemorySearchTool {
             "query".to_string(),
             PropertySchema {
                 schema_type: "string".to_string(),
-                description: "Search query - words to search for in memories. Multiple words are matched with OR logic.".to_string(),
+                description: "Search query - keywords or a natural-language description. Words are matched with OR logic; with semantic search enabled, paraphrases match too.".to_string(),
                 default: None,
                 items: None,
                 enum_values: None,
@@ -114,7 +115,7 @@ impl Tool for QmdMemorySearchTool {
         let result_limit = params.limit.unwrap_or(10).min(50).max(1);
 
         // Perform search
-        match memory_store.search(&params.query, search_limit) {
+        match memory_store.hybrid_search(&params.query, search_limit).await {
             Ok(results) => {
                 // In safe mode, filter to only safemode/ directory files
                 let results: Vec<_> = if safe_mode {
@@ -144,7 +145,7 @@ impl Tool for QmdMemorySearchTool {
                         "### {}. {}\n**Score:** {:.2}\n{}\n\n",
                         i + 1,
                         result.file_path,
-                        -result.score, // Negate because BM25 returns negative scores
+                        -result.score, // Negate because scores are lower-is-better
                         result.snippet.replace(">>>", "**").replace("<<<", "**")
                     ));
                 }
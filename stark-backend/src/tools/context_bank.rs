@@ -2,6 +2,7 @@
 //!
 //! Scans user messages for:
 //! - Ethereum wallet addresses (0x...)
+//! - ENS names (name.eth) and address book contact labels
 //! - Token symbols from config/tokens.ron
 //! - Network names from config/networks.ron
 //! - Numeric values (amounts, quantities, etc.)
@@ -10,6 +11,8 @@
 //! These extracted terms are stored in the context bank and made available
 //! to the agent in the system context.
 
+use crate::address_book::{Contact, TrustLevel};
+use crate::db::Database;
 use crate::tools::builtin::cryptocurrency::network_lookup::get_all_network_identifiers;
 use crate::tools::builtin::cryptocurrency::token_lookup::get_all_token_symbols;
 use regex::Regex;
@@ -22,7 +25,7 @@ use std::sync::{Arc, RwLock};
 pub struct ContextBankItem {
     /// The detected value (address, symbol, etc.)
     pub value: String,
-    /// Type of the item: "eth_address", "token_symbol"
+    /// Type of the item: "eth_address", "ens_name", "contact", "token_symbol", ...
     pub item_type: String,
     /// Optional additional info (e.g., token name for symbols)
     pub label: Option<String>,
@@ -102,10 +105,31 @@ impl ContextBank {
         }
 
         if !addresses.is_empty() {
-            let addr_list: Vec<_> = addresses.iter().map(|a| a.value.as_str()).collect();
+            let addr_list: Vec<_> = addresses
+                .iter()
+                .map(|a| match a.label {
+                    Some(ref label) => format!("{} ({})", a.value, label),
+                    None => a.value.clone(),
+                })
+                .collect();
             parts.push(format!("Addresses: {}", addr_list.join(", ")));
         }
 
+        let contacts: Vec<_> = items.iter().filter(|i| i.item_type == "contact").collect();
+        if !contacts.is_empty() {
+            let contact_list: Vec<_> = contacts
+                .iter()
+                .map(|c| format!("{} = {}", c.label.as_deref().unwrap_or("?"), c.value))
+                .collect();
+            parts.push(format!("Address book contacts: {}", contact_list.join(", ")));
+        }
+
+        let ens_names: Vec<_> = items.iter().filter(|i| i.item_type == "ens_name").collect();
+        if !ens_names.is_empty() {
+            let name_list: Vec<_> = ens_names.iter().map(|n| n.value.as_str()).collect();
+            parts.push(format!("ENS names (resolve with address_book): {}", name_list.join(", ")));
+        }
+
         if !tokens.is_empty() {
             let token_list: Vec<_> = tokens
                 .iter()
@@ -196,6 +220,19 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
         });
     }
 
+    // Scan for ENS names (resolved on demand by the address_book tool)
+    let ens_regex = Regex::new(r"(?i)\b(?:[a-z0-9_-]+\.)+eth\b").unwrap();
+    for cap in ens_regex.find_iter(text) {
+        let name = cap.as_str().to_lowercase();
+        if crate::web3::ens::is_ens_name(&name) {
+            items.push(ContextBankItem {
+                value: name,
+                item_type: "ens_name".to_string(),
+                label: None,
+            });
+        }
+    }
+
     // Scan for token symbols from config
     let token_symbols = get_all_token_symbols();
 
@@ -313,6 +350,39 @@ pub fn scan_input(text: &str) -> Vec<ContextBankItem> {
     items
 }
 
+/// `scan_input` plus address book contacts mentioned in the text
+pub fn scan_input_with_contacts(text: &str, db: Option<&Database>) -> Vec<ContextBankItem> {
+    let mut items = scan_input(text);
+    if let Some(db) = db {
+        match db.list_contacts() {
+            Ok(contacts) => items.extend(scan_contacts(text, &contacts)),
+            Err(e) => log::warn!("[CONTEXT_BANK] Failed to load address book: {}", e),
+        }
+    }
+    items
+}
+
+/// Scan input text for address book contacts referred to by label, ENS name or address.
+/// Blocked contacts are never added (they must not become transaction recipients).
+pub fn scan_contacts(text: &str, contacts: &[Contact]) -> Vec<ContextBankItem> {
+    let lower = text.to_lowercase();
+    contacts
+        .iter()
+        .filter(|c| c.trust_level != TrustLevel::Blocked)
+        .filter(|c| {
+            let label = format!(r"(?i)(^|[^\w]){}($|[^\w])", regex::escape(&c.label));
+            Regex::new(&label).map(|re| re.is_match(text)).unwrap_or(false)
+                || lower.contains(&c.address)
+                || c.ens_name.as_ref().is_some_and(|n| lower.contains(n.as_str()))
+        })
+        .map(|c| ContextBankItem {
+            value: c.address.clone(),
+            item_type: "contact".to_string(),
+            label: Some(c.label.clone()),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -444,6 +514,43 @@ mod tests {
         assert!(numbers.iter().any(|n| n.value == "10000000000"), "Expected 10000000000, got: {:?}", numbers);
     }
 
+    #[test]
+    fn test_scan_ens_name() {
+        let text = "send 0.1 ETH to Vitalik.eth and pay.nick.eth, not ethereum.org";
+        let items = scan_input(text);
+        let names: Vec<_> = items
+            .iter()
+            .filter(|i| i.item_type == "ens_name")
+            .map(|i| i.value.as_str())
+            .collect();
+        assert_eq!(names, vec!["vitalik.eth", "pay.nick.eth"]);
+    }
+
+    #[test]
+    fn test_scan_contacts() {
+        let contact = |label: &str, address: &str, trust_level| Contact {
+            id: 0,
+            label: label.to_string(),
+            address: address.to_string(),
+            ens_name: None,
+            networks: vec![],
+            notes: None,
+            trust_level,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let contacts = vec![
+            contact("Alice", "0x742d35cc6634c0532925a3b844bc9e7595f8fdf0", TrustLevel::Known),
+            contact("Al", "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", TrustLevel::Known),
+            contact("Mallory", "0x1111111111111111111111111111111111111111", TrustLevel::Blocked),
+        ];
+
+        let items = scan_contacts("send 5 USDC to alice and mallory", &contacts);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].label.as_deref(), Some("Alice"));
+        assert_eq!(items[0].value, "0x742d35cc6634c0532925a3b844bc9e7595f8fdf0");
+    }
+
     #[test]
     fn test_context_bank() {
         let bank = ContextBank::new();
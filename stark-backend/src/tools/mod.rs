@@ -7,7 +7,7 @@ pub mod registry;
 pub mod rpc_config;
 pub mod types;
 
-pub use context_bank::{scan_input, ContextBank, ContextBankItem};
+pub use context_bank::{scan_input_with_contacts, ContextBank};
 pub use register::{PresetOrCustom, RegisterStore};
 pub use registry::{Tool, ToolRegistry};
 pub use types::{
@@ -55,16 +55,30 @@ fn register_all_tools(registry: &mut ToolRegistry) {
     registry.register(Arc::new(builtin::TokenLookupTool::new()));
     registry.register(Arc::new(builtin::ToRawAmountTool::new()));
     registry.register(Arc::new(builtin::SetAddressTool::new()));
+    // Labelled contacts and ENS resolution
+    registry.register(Arc::new(builtin::AddressBookTool::new()));
+    // Multi-recipient payouts (Multicall3 / Safe MultiSend)
+    registry.register(Arc::new(builtin::BatchPayoutTool::new()));
+    // ERC-20 allowance audit and revocation
+    registry.register(Arc::new(builtin::TokenApprovalsTool::new()));
+    // Aave V3 positions and health-factor watchers
+    registry.register(Arc::new(builtin::AaveTool::new()));
     // Post-broadcast transaction verification (AI-based)
     registry.register(Arc::new(builtin::VerifyTxBroadcastTool::new()));
     // Network selection for chain-specific operations
     registry.register(Arc::new(builtin::SelectWeb3NetworkTool::new()));
     // Polymarket prediction market trading
     registry.register(Arc::new(builtin::PolymarketTradeTool::new()));
+    registry.register(Arc::new(builtin::PolymarketPositionsTool::new()));
+    // Token swaps via aggregators and configured Uniswap pools
+    registry.register(Arc::new(builtin::SwapQuoteTool::new()));
+    registry.register(Arc::new(builtin::SwapTool::new()));
     // DexScreener market data
     registry.register(Arc::new(builtin::DexScreenerTool::new()));
     // GeckoTerminal interactive price charts
     registry.register(Arc::new(builtin::GeckoTerminalTool::new()));
+    // Portfolio holdings and P&L from periodic balance snapshots
+    registry.register(Arc::new(builtin::PortfolioTool::new()));
     // Cross-chain USDC bridging via Across Protocol
     registry.register(Arc::new(builtin::BridgeUsdcTool::new()));
     // ERC-8128 signed HTTP requests (Ethereum identity)
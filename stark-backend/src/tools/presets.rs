@@ -3,16 +3,23 @@
 //! Presets define how tools should build requests from register values,
 //! preventing hallucination of URLs, params, and other critical data.
 
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::RwLock;
 
-/// Global preset storage (loaded once at startup)
-static FETCH_PRESETS: OnceLock<HashMap<String, FetchPreset>> = OnceLock::new();
-static RPC_PRESETS: OnceLock<HashMap<String, RpcPreset>> = OnceLock::new();
-static WEB3_PRESETS: OnceLock<HashMap<String, Web3Preset>> = OnceLock::new();
-static NETWORKS: OnceLock<HashMap<String, NetworkConfig>> = OnceLock::new();
+type Registry<T> = RwLock<Option<HashMap<String, T>>>;
+
+/// Global preset storage, loaded at startup and swapped as a whole on reload
+static FETCH_PRESETS: Registry<FetchPreset> = RwLock::new(None);
+static RPC_PRESETS: Registry<RpcPreset> = RwLock::new(None);
+static WEB3_PRESETS: Registry<Web3Preset> = RwLock::new(None);
+static NETWORKS: Registry<NetworkConfig> = RwLock::new(None);
+
+/// Web3 presets bundled with installed skills, keyed by skill name.
+/// Preset names are namespaced as `<skill>/<preset>` so they never shadow config presets.
+static SKILL_WEB3_PRESETS: Registry<HashMap<String, Web3Preset>> = RwLock::new(None);
 
 /// x402_fetch preset configuration
 #[derive(Debug, Clone, Deserialize)]
@@ -54,6 +61,10 @@ pub struct Web3Preset {
     /// Register keys to read for function params (in order)
     #[serde(default)]
     pub params_registers: Vec<String>,
+    /// Values used for params registers that are not set (e.g. a default
+    /// interest rate mode between register params)
+    #[serde(default)]
+    pub register_defaults: HashMap<String, String>,
     /// Register key for ETH value (for payable functions)
     pub value_register: Option<String>,
     /// Static params (not from registers)
@@ -71,167 +82,227 @@ pub struct NetworkConfig {
     pub explorer: String,
 }
 
-/// Load presets from config directory
+/// Load presets and networks from config directory.
+/// Invalid files are logged and the built-in defaults are used instead.
 pub fn load_presets(config_dir: &Path) {
-    // Load fetch presets
-    let fetch_path = config_dir.join("x402_fetch_presets.ron");
-    if fetch_path.exists() {
-        match std::fs::read_to_string(&fetch_path) {
-            Ok(content) => {
-                match ron::from_str::<HashMap<String, FetchPreset>>(&content) {
-                    Ok(presets) => {
-                        log::info!("[presets] Loaded {} fetch presets from {:?}", presets.len(), fetch_path);
-                        let _ = FETCH_PRESETS.set(presets);
-                    }
-                    Err(e) => log::error!("[presets] Failed to parse fetch presets: {}", e),
-                }
-            }
-            Err(e) => log::error!("[presets] Failed to read fetch presets file: {}", e),
-        }
-    } else {
-        log::warn!("[presets] Fetch presets file not found: {:?}", fetch_path);
-        let _ = FETCH_PRESETS.set(default_fetch_presets());
+    if let Err(e) = reload_networks(config_dir) {
+        log::error!("[presets] {}", e);
+    }
+    if let Err(e) = reload_presets(config_dir) {
+        log::error!("[presets] {}", e);
+    }
+}
+
+/// Read and parse a preset map; Ok(None) when the file does not exist
+fn parse_file<T: DeserializeOwned>(path: &Path) -> Result<Option<HashMap<String, T>>, String> {
+    if !path.exists() {
+        log::warn!("[presets] {:?} not found, using defaults", path);
+        return Ok(None);
     }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    ron::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+}
 
-    // Load RPC presets
-    let rpc_path = config_dir.join("x402_rpc_presets.ron");
-    if rpc_path.exists() {
-        match std::fs::read_to_string(&rpc_path) {
-            Ok(content) => {
-                match ron::from_str::<HashMap<String, RpcPreset>>(&content) {
-                    Ok(presets) => {
-                        log::info!("[presets] Loaded {} RPC presets from {:?}", presets.len(), rpc_path);
-                        let _ = RPC_PRESETS.set(presets);
-                    }
-                    Err(e) => log::error!("[presets] Failed to parse RPC presets: {}", e),
-                }
-            }
-            Err(e) => log::error!("[presets] Failed to read RPC presets file: {}", e),
+/// Reload fetch, RPC and Web3 presets. All three files are parsed and validated
+/// before any registry is replaced, so a bad edit leaves the current presets in place.
+/// Returns the total number of presets loaded.
+pub fn reload_presets(config_dir: &Path) -> Result<usize, String> {
+    let fetch = parse_file(&config_dir.join("x402_fetch_presets.ron"))?
+        .unwrap_or_else(default_fetch_presets);
+    let rpc = parse_file(&config_dir.join("x402_rpc_presets.ron"))?
+        .unwrap_or_else(default_rpc_presets);
+    let web3 = parse_file(&config_dir.join("web3_presets.ron"))?
+        .unwrap_or_else(default_web3_presets);
+
+    validate_fetch_presets(&fetch)?;
+    validate_web3_presets(&web3, &crate::web3::default_abis_dir())?;
+
+    let total = fetch.len() + rpc.len() + web3.len();
+    log::info!(
+        "[presets] Loaded {} fetch, {} RPC and {} Web3 presets from {:?}",
+        fetch.len(),
+        rpc.len(),
+        web3.len(),
+        config_dir
+    );
+    replace(&FETCH_PRESETS, fetch);
+    replace(&RPC_PRESETS, rpc);
+    replace(&WEB3_PRESETS, web3);
+    Ok(total)
+}
+
+/// Reload networks.ron, keeping the current networks if it is invalid
+pub fn reload_networks(config_dir: &Path) -> Result<usize, String> {
+    let networks = parse_file(&config_dir.join("networks.ron"))?
+        .unwrap_or_else(default_networks);
+    validate_networks(&networks)?;
+
+    log::info!("[presets] Loaded {} networks from {:?}", networks.len(), config_dir);
+    let count = networks.len();
+    replace(&NETWORKS, networks);
+    Ok(count)
+}
+
+fn validate_fetch_presets(presets: &HashMap<String, FetchPreset>) -> Result<(), String> {
+    for (name, preset) in presets {
+        if !preset.base_url.starts_with("http://") && !preset.base_url.starts_with("https://") {
+            return Err(format!("Fetch preset '{}': base_url must be an http(s) URL", name));
         }
-    } else {
-        log::warn!("[presets] RPC presets file not found: {:?}", rpc_path);
-        let _ = RPC_PRESETS.set(default_rpc_presets());
     }
+    Ok(())
+}
 
-    // Load Web3 presets
-    let web3_path = config_dir.join("web3_presets.ron");
-    if web3_path.exists() {
-        match std::fs::read_to_string(&web3_path) {
-            Ok(content) => {
-                match ron::from_str::<HashMap<String, Web3Preset>>(&content) {
-                    Ok(presets) => {
-                        log::info!("[presets] Loaded {} Web3 presets from {:?}", presets.len(), web3_path);
-                        let _ = WEB3_PRESETS.set(presets);
-                    }
-                    Err(e) => log::error!("[presets] Failed to parse Web3 presets: {}", e),
-                }
+fn validate_web3_presets(presets: &HashMap<String, Web3Preset>, abis_dir: &Path) -> Result<(), String> {
+    // ABIs are only checked when the abis directory ships with this deployment
+    let check_abis = abis_dir.exists();
+    validate_web3_presets_with(presets, |abi| {
+        if check_abis {
+            crate::web3::load_abi(&abis_dir.to_path_buf(), abi).map(Some)
+        } else {
+            Ok(None)
+        }
+    })
+}
+
+/// Validate Web3 presets, resolving each preset's ABI with `load_abi`
+/// (returning Ok(None) skips the ABI and function checks)
+pub fn validate_web3_presets_with(
+    presets: &HashMap<String, Web3Preset>,
+    load_abi: impl Fn(&str) -> Result<Option<crate::web3::AbiFile>, String>,
+) -> Result<(), String> {
+    for (name, preset) in presets {
+        if preset.contracts.is_empty() && preset.contract_register.is_none() {
+            return Err(format!("Web3 preset '{}': needs contracts or contract_register", name));
+        }
+        for (network, address) in &preset.contracts {
+            if address.parse::<ethers::types::Address>().is_err() {
+                return Err(format!(
+                    "Web3 preset '{}': invalid contract address '{}' for {}",
+                    name, address, network
+                ));
             }
-            Err(e) => log::error!("[presets] Failed to read Web3 presets file: {}", e),
         }
-    } else {
-        log::warn!("[presets] Web3 presets file not found: {:?}", web3_path);
-        let _ = WEB3_PRESETS.set(default_web3_presets());
+        if let Some(key) = preset.register_defaults.keys().find(|k| !preset.params_registers.contains(k)) {
+            return Err(format!(
+                "Web3 preset '{}': register default '{}' is not one of its params_registers",
+                name, key
+            ));
+        }
+        let abi_file = load_abi(&preset.abi).map_err(|e| format!("Web3 preset '{}': {}", name, e))?;
+        if let Some(abi_file) = abi_file {
+            let abi = crate::web3::parse_abi(&abi_file)
+                .map_err(|e| format!("Web3 preset '{}': {}", name, e))?;
+            crate::web3::find_function(&abi, &preset.function)
+                .map_err(|e| format!("Web3 preset '{}': {}", name, e))?;
+        }
     }
+    Ok(())
+}
 
-    // Load networks
-    let networks_path = config_dir.join("networks.ron");
-    if networks_path.exists() {
-        match std::fs::read_to_string(&networks_path) {
-            Ok(content) => {
-                match ron::from_str::<HashMap<String, NetworkConfig>>(&content) {
-                    Ok(networks) => {
-                        log::info!("[presets] Loaded {} networks from {:?}", networks.len(), networks_path);
-                        let _ = NETWORKS.set(networks);
-                    }
-                    Err(e) => {
-                        log::error!("[presets] Failed to parse networks config: {}", e);
-                        let _ = NETWORKS.set(default_networks());
-                    }
-                }
-            }
-            Err(e) => {
-                log::error!("[presets] Failed to read networks file: {}", e);
-                let _ = NETWORKS.set(default_networks());
-            }
+fn validate_networks(networks: &HashMap<String, NetworkConfig>) -> Result<(), String> {
+    if networks.is_empty() {
+        return Err("networks.ron defines no networks".to_string());
+    }
+    for (name, network) in networks {
+        if network.chain_id == 0 {
+            return Err(format!("Network '{}': chain_id must not be 0", name));
+        }
+        if !network.explorer.starts_with("http://") && !network.explorer.starts_with("https://") {
+            return Err(format!("Network '{}': explorer must be an http(s) URL", name));
         }
-    } else {
-        log::warn!("[presets] Networks file not found: {:?}, using defaults", networks_path);
-        let _ = NETWORKS.set(default_networks());
     }
+    Ok(())
+}
+
+/// Atomically swap in a new registry
+fn replace<T>(registry: &Registry<T>, value: HashMap<String, T>) {
+    *registry.write().unwrap_or_else(|e| e.into_inner()) = Some(value);
 }
 
-/// Get networks, loading defaults if not already loaded
-fn get_networks() -> &'static HashMap<String, NetworkConfig> {
-    NETWORKS.get_or_init(default_networks)
+/// Run `f` against a registry, falling back to the defaults if nothing was loaded
+fn with_registry<T, R>(
+    registry: &Registry<T>,
+    defaults: fn() -> HashMap<String, T>,
+    f: impl FnOnce(&HashMap<String, T>) -> R,
+) -> R {
+    let guard = registry.read().unwrap_or_else(|e| e.into_inner());
+    match guard.as_ref() {
+        Some(map) => f(map),
+        None => f(&defaults()),
+    }
 }
 
 /// Get a fetch preset by name
 pub fn get_fetch_preset(name: &str) -> Option<FetchPreset> {
-    FETCH_PRESETS.get()
-        .or_else(|| {
-            // Fallback to defaults if not loaded
-            let _ = FETCH_PRESETS.set(default_fetch_presets());
-            FETCH_PRESETS.get()
-        })
-        .and_then(|p| p.get(name).cloned())
+    with_registry(&FETCH_PRESETS, default_fetch_presets, |p| p.get(name).cloned())
 }
 
 /// Get an RPC preset by name
 pub fn get_rpc_preset(name: &str) -> Option<RpcPreset> {
-    RPC_PRESETS.get()
-        .or_else(|| {
-            let _ = RPC_PRESETS.set(default_rpc_presets());
-            RPC_PRESETS.get()
-        })
-        .and_then(|p| p.get(name).cloned())
+    with_registry(&RPC_PRESETS, default_rpc_presets, |p| p.get(name).cloned())
 }
 
-/// Get a Web3 preset by name
+/// Get a Web3 preset by name (config presets first, then skill-bundled `<skill>/<preset>`)
 pub fn get_web3_preset(name: &str) -> Option<Web3Preset> {
-    WEB3_PRESETS.get()
+    with_registry(&WEB3_PRESETS, default_web3_presets, |p| p.get(name).cloned())
         .or_else(|| {
-            let _ = WEB3_PRESETS.set(default_web3_presets());
-            WEB3_PRESETS.get()
+            let skill = name.split_once('/')?.0;
+            let guard = SKILL_WEB3_PRESETS.read().unwrap_or_else(|e| e.into_inner());
+            guard.as_ref()?.get(skill)?.get(name).cloned()
         })
-        .and_then(|p| p.get(name).cloned())
+}
+
+/// Names of the Web3 presets loaded from config (excluding skill-bundled presets)
+pub fn list_config_web3_presets() -> Vec<String> {
+    with_registry(&WEB3_PRESETS, default_web3_presets, |p| p.keys().cloned().collect())
+}
+
+/// Register the Web3 presets shipped by a skill, replacing any it registered before.
+/// Keys must already be namespaced as `<skill>/<preset>`.
+pub fn register_skill_web3_presets(skill: &str, presets: HashMap<String, Web3Preset>) {
+    let mut guard = SKILL_WEB3_PRESETS.write().unwrap_or_else(|e| e.into_inner());
+    guard.get_or_insert_with(HashMap::new).insert(skill.to_string(), presets);
+}
+
+/// Remove every Web3 preset registered by a skill
+pub fn unregister_skill_web3_presets(skill: &str) {
+    let mut guard = SKILL_WEB3_PRESETS.write().unwrap_or_else(|e| e.into_inner());
+    if let Some(map) = guard.as_mut() {
+        map.remove(skill);
+    }
 }
 
 /// Get network config by name
 pub fn get_network(name: &str) -> Option<NetworkConfig> {
-    get_networks().get(name).cloned()
+    with_registry(&NETWORKS, default_networks, |n| n.get(name).cloned())
 }
 
 /// List available fetch preset names
 pub fn list_fetch_presets() -> Vec<String> {
-    FETCH_PRESETS.get()
-        .map(|p| p.keys().cloned().collect())
-        .unwrap_or_else(|| vec!["swap_quote".to_string()])
+    with_registry(&FETCH_PRESETS, default_fetch_presets, |p| p.keys().cloned().collect())
 }
 
 /// List available RPC preset names
 pub fn list_rpc_presets() -> Vec<String> {
-    RPC_PRESETS.get()
-        .map(|p| p.keys().cloned().collect())
-        .unwrap_or_else(|| vec!["gas_price".to_string(), "get_balance".to_string(), "get_nonce".to_string(), "block_number".to_string()])
+    with_registry(&RPC_PRESETS, default_rpc_presets, |p| p.keys().cloned().collect())
 }
 
-/// List available Web3 preset names
+/// List available Web3 preset names, including skill-bundled presets
 pub fn list_web3_presets() -> Vec<String> {
-    WEB3_PRESETS.get()
-        .map(|p| p.keys().cloned().collect())
-        .unwrap_or_else(|| vec![
-            "weth_deposit".to_string(),
-            "weth_withdraw".to_string(),
-            "erc20_approve_permit2".to_string(),
-            "erc20_allowance_permit2".to_string(),
-            "erc20_balance".to_string(),
-            "erc20_transfer".to_string(),
-        ])
+    let mut names = list_config_web3_presets();
+    let guard = SKILL_WEB3_PRESETS.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(skills) = guard.as_ref() {
+        names.extend(skills.values().flat_map(|p| p.keys().cloned()));
+    }
+    names
 }
 
 /// List available network names
 pub fn list_networks() -> Vec<String> {
-    get_networks().keys().cloned().collect()
+    with_registry(&NETWORKS, default_networks, |n| n.keys().cloned().collect())
 }
 
 /// Default fetch presets (fallback if config not found)
@@ -298,6 +369,7 @@ fn default_web3_presets() -> HashMap<String, Web3Preset> {
         contract_register: None,
         function: "deposit".to_string(),
         params_registers: vec![],
+        register_defaults: HashMap::new(),
         value_register: Some("wrap_amount".to_string()),
         static_params: vec![],
         description: "Wrap ETH to WETH".to_string(),
@@ -309,6 +381,7 @@ fn default_web3_presets() -> HashMap<String, Web3Preset> {
         contract_register: None,
         function: "withdraw".to_string(),
         params_registers: vec!["unwrap_amount".to_string()],
+        register_defaults: HashMap::new(),
         value_register: None,
         static_params: vec![],
         description: "Unwrap WETH to ETH".to_string(),
@@ -320,6 +393,7 @@ fn default_web3_presets() -> HashMap<String, Web3Preset> {
         contract_register: Some("sell_token".to_string()),
         function: "approve".to_string(),
         params_registers: vec![],
+        register_defaults: HashMap::new(),
         value_register: None,
         static_params: vec![
             "0x000000000022D473030F116dDEE9F6B43aC78BA3".to_string(), // Permit2 address
@@ -334,6 +408,7 @@ fn default_web3_presets() -> HashMap<String, Web3Preset> {
         contract_register: Some("sell_token".to_string()),
         function: "allowance".to_string(),
         params_registers: vec!["wallet_address".to_string()],
+        register_defaults: HashMap::new(),
         value_register: None,
         static_params: vec![
             "0x000000000022D473030F116dDEE9F6B43aC78BA3".to_string(), // Permit2 address
@@ -347,6 +422,7 @@ fn default_web3_presets() -> HashMap<String, Web3Preset> {
         contract_register: Some("token_address".to_string()),
         function: "balanceOf".to_string(),
         params_registers: vec!["wallet_address".to_string()],
+        register_defaults: HashMap::new(),
         value_register: None,
         static_params: vec![],
         description: "Get ERC20 token balance. Set token_address register first.".to_string(),
@@ -358,6 +434,7 @@ fn default_web3_presets() -> HashMap<String, Web3Preset> {
         contract_register: Some("token_address".to_string()),
         function: "transfer".to_string(),
         params_registers: vec!["recipient_address".to_string(), "transfer_amount".to_string()],
+        register_defaults: HashMap::new(),
         value_register: None,
         static_params: vec![],
         description: "Transfer ERC20 tokens. Set token_address, recipient_address, transfer_amount registers first.".to_string(),
@@ -386,32 +463,80 @@ fn default_networks() -> HashMap<String, NetworkConfig> {
 
 /// Get chain ID for network (returns string for URL params)
 pub fn get_chain_id(network: &str) -> String {
-    get_networks()
-        .get(network)
-        .map(|n| n.chain_id.to_string())
-        .unwrap_or_else(|| "8453".to_string()) // default to base
+    get_chain_id_u64(network).to_string()
 }
 
 /// Get chain ID as u64 for network
 pub fn get_chain_id_u64(network: &str) -> u64 {
-    get_networks()
-        .get(network)
+    get_network(network)
         .map(|n| n.chain_id)
         .unwrap_or(8453) // default to base
 }
 
 /// Get network name (display name) for a network key
 pub fn get_network_name(network: &str) -> String {
-    get_networks()
-        .get(network)
-        .map(|n| n.name.clone())
+    get_network(network)
+        .map(|n| n.name)
         .unwrap_or_else(|| network.to_string())
 }
 
 /// Get explorer URL for a network
 pub fn get_explorer_url(network: &str) -> String {
-    get_networks()
-        .get(network)
-        .map(|n| n.explorer.clone())
+    get_network(network)
+        .map(|n| n.explorer)
         .unwrap_or_else(|| "https://basescan.org".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_config_dir() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join("config")
+    }
+
+    #[test]
+    fn test_shipped_config_validates() {
+        let config_dir = repo_config_dir();
+        let fetch: HashMap<String, FetchPreset> =
+            parse_file(&config_dir.join("x402_fetch_presets.ron")).unwrap().unwrap();
+        let web3: HashMap<String, Web3Preset> =
+            parse_file(&config_dir.join("web3_presets.ron")).unwrap().unwrap();
+        let networks: HashMap<String, NetworkConfig> =
+            parse_file(&config_dir.join("networks.ron")).unwrap().unwrap();
+        validate_fetch_presets(&fetch).unwrap();
+        validate_web3_presets(&web3, &crate::web3::default_abis_dir()).unwrap();
+        validate_networks(&networks).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_web3_preset_rejected() {
+        let mut presets = default_web3_presets();
+        let mut bad = presets["weth_deposit"].clone();
+        bad.contracts.insert("base".to_string(), "0xnot-an-address".to_string());
+        presets.insert("bad".to_string(), bad);
+        let err = validate_web3_presets(&presets, Path::new("/nonexistent")).unwrap_err();
+        assert!(err.contains("'bad'"), "{}", err);
+
+        let mut presets = default_web3_presets();
+        let mut no_contract = presets["erc20_transfer"].clone();
+        no_contract.contract_register = None;
+        presets.insert("no_contract".to_string(), no_contract);
+        assert!(validate_web3_presets(&presets, Path::new("/nonexistent")).is_err());
+
+        let mut presets = default_web3_presets();
+        let mut stray_default = presets["erc20_transfer"].clone();
+        stray_default.register_defaults.insert("aave_referral_code".to_string(), "0".to_string());
+        presets.insert("stray_default".to_string(), stray_default);
+        assert!(validate_web3_presets(&presets, Path::new("/nonexistent")).is_err());
+    }
+
+    #[test]
+    fn test_parse_error_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("networks.ron"), "{ \"base\": ( chain_id: ").unwrap();
+        let err = parse_file::<NetworkConfig>(&dir.path().join("networks.ron")).unwrap_err();
+        assert!(err.contains("Failed to parse"), "{}", err);
+        assert!(parse_file::<NetworkConfig>(&dir.path().join("missing.ron")).unwrap().is_none());
+    }
+}
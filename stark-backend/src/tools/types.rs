@@ -4,6 +4,7 @@ use crate::db::Database;
 use crate::execution::ProcessManager;
 use crate::gateway::events::EventBroadcaster;
 use crate::gateway::protocol::GatewayEvent;
+use crate::hooks::{HookContext, HookManager, HookResult};
 use crate::qmd_memory::MemoryStore;
 use crate::skills::SkillRegistry;
 use crate::tools::register::RegisterStore;
@@ -376,6 +377,8 @@ pub struct ToolContext {
     /// Runtime API key store (interior-mutable so install_api_key can write via &self)
     /// Keys are stored as UPPER_SNAKE_CASE names → values
     pub api_keys: Arc<RwLock<HashMap<String, String>>>,
+    /// Hook manager for lifecycle hooks fired by tools (commit, push, PR create)
+    pub hook_manager: Option<Arc<HookManager>>,
 }
 
 impl std::fmt::Debug for ToolContext {
@@ -401,6 +404,7 @@ impl std::fmt::Debug for ToolContext {
             .field("wallet_provider", &self.wallet_provider.is_some())
             .field("platform_chat_id", &self.platform_chat_id)
             .field("api_keys", &self.api_keys.read().ok().map(|m| m.len()))
+            .field("hook_manager", &self.hook_manager.is_some())
             .finish()
     }
 }
@@ -429,6 +433,7 @@ impl Default for ToolContext {
             wallet_provider: None,
             platform_chat_id: None,
             api_keys: Arc::new(RwLock::new(HashMap::new())),
+            hook_manager: None,
         }
     }
 }
@@ -644,9 +649,31 @@ impl ToolContext {
         self
     }
 
+    /// Add a HookManager to the context (for tools that fire lifecycle hooks)
+    pub fn with_hook_manager(mut self, hook_manager: Arc<HookManager>) -> Self {
+        self.hook_manager = Some(hook_manager);
+        self
+    }
+
+    /// Run hooks for the context's event, filling in channel/session/workspace.
+    /// Returns `Continue(None)` when no HookManager is attached.
+    pub async fn run_hooks(&self, hook_context: HookContext) -> HookResult {
+        let manager = match &self.hook_manager {
+            Some(m) => m,
+            None => return HookResult::Continue(None),
+        };
+        let mut hook_context = hook_context;
+        hook_context.channel_id = hook_context.channel_id.or(self.channel_id);
+        hook_context.session_id = hook_context.session_id.or(self.session_id);
+        if hook_context.workspace.is_none() {
+            hook_context.workspace = self.workspace_dir.clone();
+        }
+        manager.execute(hook_context.event, &mut hook_context).await
+    }
+
     /// Populate context bank with extracted terms from user input and broadcast update
     pub fn scan_and_set_context_bank(&mut self, text: &str) {
-        let items = crate::tools::scan_input(text);
+        let items = crate::tools::scan_input_with_contacts(text, self.database.as_deref());
         if !items.is_empty() {
             self.context_bank.add_all(items);
 
@@ -665,6 +692,15 @@ impl ToolContext {
         self.context_bank.format_for_agent()
     }
 
+    /// Channel and session that AI calls made by a tool are recorded under
+    pub fn usage_scope(&self) -> crate::ai::UsageScope<'static> {
+        crate::ai::UsageScope {
+            channel_id: self.channel_id,
+            session_id: self.session_id,
+            ..Default::default()
+        }
+    }
+
     /// Set a register value and broadcast the update to connected clients.
     /// This is the preferred way to set registers when you want real-time updates in the UI.
     pub fn set_register(&self, key: &str, value: Value, source_tool: &str) {
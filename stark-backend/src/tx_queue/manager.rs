@@ -84,6 +84,14 @@ impl TxQueueManager {
             .collect()
     }
 
+    /// Whether `from` has a transaction waiting to be broadcast. Queued transactions take
+    /// their nonce from the chain, so another one queued before it is broadcast would collide.
+    pub fn has_pending_from(&self, from: &str) -> bool {
+        self.transactions
+            .iter()
+            .any(|r| r.value().status == QueuedTxStatus::Pending && r.value().from.eq_ignore_ascii_case(from))
+    }
+
     /// List transactions by status
     pub fn list_by_status(&self, status: QueuedTxStatus) -> Vec<QueuedTxSummary> {
         self.transactions
@@ -285,6 +293,20 @@ mod tests {
         assert_eq!(retrieved.unwrap().uuid, "test-uuid-1");
     }
 
+    #[test]
+    fn test_has_pending_from() {
+        let manager = TxQueueManager::new();
+        assert!(!manager.has_pending_from("0x1234"));
+
+        manager.queue(create_test_tx("test-uuid-pending"));
+        assert!(manager.has_pending_from("0x1234"));
+        assert!(!manager.has_pending_from("0x5678"));
+
+        // Only transactions still waiting for broadcast count
+        manager.mark_broadcasting("test-uuid-pending");
+        assert!(!manager.has_pending_from("0x1234"));
+    }
+
     #[test]
     fn test_status_updates() {
         let manager = TxQueueManager::new();
@@ -0,0 +1,97 @@
+//! Contract wallet helpers: EIP-1271 signature checks and Safe owner lookups.
+//!
+//! Used by web login so smart-contract wallets (e.g. a team Safe) can sign in,
+//! and so owners of a configured Safe can be granted access.
+
+use crate::x402::X402EvmRpc;
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Address, H256};
+use ethers::utils::id;
+
+/// `bytes4(keccak256("isValidSignature(bytes32,bytes)"))`, returned on success
+pub const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+/// Calldata for EIP-1271 `isValidSignature(bytes32 hash, bytes signature)`
+pub fn encode_is_valid_signature(hash: H256, signature: &[u8]) -> Vec<u8> {
+    let mut data = id("isValidSignature(bytes32,bytes)").to_vec();
+    data.extend(abi::encode(&[
+        Token::FixedBytes(hash.as_bytes().to_vec()),
+        Token::Bytes(signature.to_vec()),
+    ]));
+    data
+}
+
+/// Whether `isValidSignature` return data carries the EIP-1271 magic value
+pub fn is_magic_value(data: &[u8]) -> bool {
+    data.len() >= 4 && data[..4] == EIP1271_MAGIC_VALUE
+}
+
+/// Calldata for Safe `getOwners()`
+pub fn encode_get_owners() -> Vec<u8> {
+    id("getOwners()").to_vec()
+}
+
+/// Decode the `address[]` returned by Safe `getOwners()`
+pub fn decode_get_owners(data: &[u8]) -> Result<Vec<Address>, String> {
+    let tokens = abi::decode(&[ParamType::Array(Box::new(ParamType::Address))], data)
+        .map_err(|e| format!("Failed to decode getOwners result: {}", e))?;
+    match tokens.into_iter().next() {
+        Some(Token::Array(items)) => Ok(items.into_iter().filter_map(|t| t.into_address()).collect()),
+        _ => Err("Unexpected getOwners result".to_string()),
+    }
+}
+
+/// Ask a contract wallet whether `signature` is valid for `hash` (EIP-1271).
+///
+/// Reverts and empty results (EOAs, contracts without the method) count as invalid.
+pub async fn is_valid_signature(
+    rpc: &X402EvmRpc,
+    wallet: Address,
+    hash: H256,
+    signature: &[u8],
+) -> Result<bool, String> {
+    match rpc.call(wallet, &encode_is_valid_signature(hash, signature)).await {
+        Ok(data) => Ok(is_magic_value(&data)),
+        Err(e) if e.contains("revert") => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Current owners of a Safe, read live from the chain
+pub async fn get_safe_owners(rpc: &X402EvmRpc, safe: Address) -> Result<Vec<Address>, String> {
+    let data = rpc.call(safe, &encode_get_owners()).await?;
+    decode_get_owners(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_signature_calldata() {
+        let hash = H256::repeat_byte(0xab);
+        let data = encode_is_valid_signature(hash, &[1, 2, 3]);
+        assert_eq!(&data[..4], &EIP1271_MAGIC_VALUE);
+        // selector + hash + offset + length + one padded word of signature
+        assert_eq!(data.len(), 4 + 32 * 4);
+        assert_eq!(&data[4..36], hash.as_bytes());
+    }
+
+    #[test]
+    fn test_magic_value() {
+        let mut ok = EIP1271_MAGIC_VALUE.to_vec();
+        ok.extend([0u8; 28]);
+        assert!(is_magic_value(&ok));
+        assert!(!is_magic_value(&[0xff; 32]));
+        assert!(!is_magic_value(&[]));
+    }
+
+    #[test]
+    fn test_decode_get_owners() {
+        let a = Address::repeat_byte(0x11);
+        let b = Address::repeat_byte(0x22);
+        let encoded = abi::encode(&[Token::Array(vec![Token::Address(a), Token::Address(b)])]);
+        assert_eq!(decode_get_owners(&encoded).unwrap(), vec![a, b]);
+        assert!(decode_get_owners(&[0u8; 3]).is_err());
+    }
+}
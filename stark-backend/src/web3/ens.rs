@@ -0,0 +1,138 @@
+//! ENS (Ethereum Name Service) resolution on Ethereum mainnet.
+//!
+//! Forward resolution goes registry → resolver → `addr(node)`. Reverse lookups read
+//! `name(node)` for `<addr>.addr.reverse` and only return the name if it resolves
+//! back to the same address (the reverse record is set by the address owner and is
+//! otherwise unauthenticated).
+
+use std::sync::Arc;
+
+use crate::db::Database;
+use crate::tools::rpc_config::resolve_rpc_config;
+use crate::wallet::WalletProvider;
+use crate::x402::X402EvmRpc;
+use ethers::abi::{self, ParamType};
+use ethers::providers::{namehash, reverse_address, ADDR_SELECTOR, ENS_ADDRESS, NAME_SELECTOR};
+use ethers::types::Address;
+use ethers::utils::id;
+
+/// Network ENS names are resolved on
+pub const ENS_NETWORK: &str = "mainnet";
+
+/// Whether `s` looks like an ENS name (`label.eth`, subdomains allowed)
+pub fn is_ens_name(s: &str) -> bool {
+    let s = s.trim();
+    let Some(labels) = s.to_lowercase().strip_suffix(".eth").map(str::to_string) else {
+        return false;
+    };
+    !labels.is_empty()
+        && labels.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// Lowercase and trim a name (ENS names are case-insensitive for ASCII)
+pub fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Build a mainnet RPC client using the configured RPC provider
+pub fn mainnet_rpc(
+    db: &Database,
+    wallet_provider: Option<&Arc<dyn WalletProvider>>,
+) -> Result<X402EvmRpc, String> {
+    let wallet_provider = wallet_provider.ok_or_else(|| "ENS resolution needs a wallet provider for RPC access".to_string())?;
+    let settings = db
+        .get_bot_settings()
+        .map_err(|e| format!("Failed to load bot settings: {}", e))?;
+    let (url, use_x402) = resolve_rpc_config(&settings.rpc_provider, settings.custom_rpc_endpoints.as_ref(), ENS_NETWORK)
+        .ok_or_else(|| format!("No RPC endpoint configured for {}", ENS_NETWORK))?;
+    X402EvmRpc::new_with_wallet_provider(wallet_provider.clone(), ENS_NETWORK, Some(url), use_x402)
+}
+
+fn decode_address(data: &[u8]) -> Option<Address> {
+    match abi::decode(&[ParamType::Address], data).ok()?.into_iter().next()? {
+        abi::Token::Address(addr) if !addr.is_zero() => Some(addr),
+        _ => None,
+    }
+}
+
+/// Resolver contract registered for `name` (None if the name has no resolver)
+async fn resolver(rpc: &X402EvmRpc, name: &str) -> Result<Option<Address>, String> {
+    let data = [&id("resolver(bytes32)")[..], namehash(name).as_bytes()].concat();
+    let result = rpc.call(ENS_ADDRESS, &data).await?;
+    Ok(decode_address(&result))
+}
+
+/// Resolve an ENS name to an address (None if the name is unregistered or has no address)
+pub async fn resolve_name(rpc: &X402EvmRpc, name: &str) -> Result<Option<Address>, String> {
+    let name = normalize_name(name);
+    if !is_ens_name(&name) {
+        return Err(format!("'{}' is not an ENS name", name));
+    }
+    let Some(resolver) = resolver(rpc, &name).await? else {
+        return Ok(None);
+    };
+    let data = [&ADDR_SELECTOR[..], namehash(&name).as_bytes()].concat();
+    let result = rpc.call(resolver, &data).await?;
+    Ok(decode_address(&result))
+}
+
+/// Primary ENS name of an address, verified by forward resolution
+pub async fn lookup_address(rpc: &X402EvmRpc, address: Address) -> Result<Option<String>, String> {
+    let reverse = reverse_address(address);
+    let Some(resolver) = resolver(rpc, &reverse).await? else {
+        return Ok(None);
+    };
+    let data = [&NAME_SELECTOR[..], namehash(&reverse).as_bytes()].concat();
+    let result = rpc.call(resolver, &data).await?;
+    let name = match abi::decode(&[ParamType::String], &result)
+        .ok()
+        .and_then(|tokens| tokens.into_iter().next())
+    {
+        Some(abi::Token::String(name)) if is_ens_name(&name) => name,
+        _ => return Ok(None),
+    };
+
+    match resolve_name(rpc, &name).await? {
+        Some(resolved) if resolved == address => Ok(Some(normalize_name(&name))),
+        _ => {
+            log::warn!("[ens] Reverse record {} for {:?} does not resolve back, ignoring", name, address);
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ens_name() {
+        assert!(is_ens_name("vitalik.eth"));
+        assert!(is_ens_name("Pay.Vitalik.ETH"));
+        assert!(is_ens_name("my-name_1.eth"));
+        assert!(!is_ens_name(".eth"));
+        assert!(!is_ens_name("eth"));
+        assert!(!is_ens_name("foo..eth"));
+        assert!(!is_ens_name("-foo.eth"));
+        assert!(!is_ens_name("foo.com"));
+        assert!(!is_ens_name("0x742d35Cc6634C0532925a3b844Bc9e7595f8FdF0"));
+    }
+
+    #[test]
+    fn test_namehash() {
+        // EIP-137 reference values
+        assert_eq!(
+            format!("{:?}", namehash("eth")),
+            "0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+        );
+        assert_eq!(
+            format!("{:?}", namehash("foo.eth")),
+            "0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+        );
+    }
+}
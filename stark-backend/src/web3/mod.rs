@@ -3,11 +3,16 @@
 //! Shared by `web3_function_call` (manual mode) and `web3_preset_function_call` (preset mode).
 //! Provides ABI loading, encoding/decoding, transaction signing, and call execution.
 
+pub mod contract_wallet;
+pub mod ens;
+pub mod multicall;
+pub mod safe;
+
 use crate::tools::builtin::cryptocurrency::verify_intent::{self, TransactionIntent};
 use crate::tools::builtin::cryptocurrency::web3_tx::parse_u256;
 use crate::tools::rpc_config::{resolve_rpc_from_context, Network, ResolvedRpcConfig};
 use crate::tools::types::{ToolContext, ToolResult};
-use crate::tx_queue::QueuedTransaction;
+use crate::tx_queue::{QueuedTransaction, TxQueueManager};
 use crate::wallet::WalletProvider;
 use crate::x402::X402EvmRpc;
 use ethers::abi::{Abi, Function, ParamType, Token};
@@ -19,7 +24,7 @@ use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
 // ---- Shared types and helpers (used by both manual and preset tools) ----
@@ -65,17 +70,49 @@ pub fn default_abis_dir() -> PathBuf {
     crate::config::repo_root().join("abis")
 }
 
-/// Load ABI from file
+/// ABIs bundled with installed skills: skill name -> (namespaced "skill/abi" name -> JSON)
+static SKILL_ABIS: RwLock<Option<HashMap<String, HashMap<String, String>>>> = RwLock::new(None);
+
+/// Register the ABIs shipped by a skill, replacing any it registered before.
+/// Keys must already be namespaced as `<skill>/<abi>`.
+pub fn register_skill_abis(skill: &str, abis: HashMap<String, String>) {
+    let mut guard = SKILL_ABIS.write().unwrap_or_else(|e| e.into_inner());
+    guard.get_or_insert_with(HashMap::new).insert(skill.to_string(), abis);
+}
+
+/// Remove every ABI registered by a skill
+pub fn unregister_skill_abis(skill: &str) {
+    let mut guard = SKILL_ABIS.write().unwrap_or_else(|e| e.into_inner());
+    if let Some(map) = guard.as_mut() {
+        map.remove(skill);
+    }
+}
+
+/// Look up a skill-bundled ABI by its namespaced name
+fn skill_abi_json(name: &str) -> Option<String> {
+    let skill = name.split_once('/')?.0;
+    let guard = SKILL_ABIS.read().unwrap_or_else(|e| e.into_inner());
+    guard.as_ref()?.get(skill)?.get(name).cloned()
+}
+
+/// Parse the contents of an ABI file
+pub fn parse_abi_file(name: &str, content: &str) -> Result<AbiFile, String> {
+    serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse ABI '{}': {}", name, e))
+}
+
+/// Load ABI by name: skill-bundled ABIs (`<skill>/<abi>`) first, then the abis directory
 pub fn load_abi(abis_dir: &PathBuf, name: &str) -> Result<AbiFile, String> {
+    if let Some(content) = skill_abi_json(name) {
+        return parse_abi_file(name, &content);
+    }
+
     let path = abis_dir.join(format!("{}.json", name));
 
     let content = std::fs::read_to_string(&path)
         .map_err(|e| format!("Failed to load ABI '{}': {}. Available ABIs are in the /abis folder.", name, e))?;
 
-    let abi_file: AbiFile = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse ABI '{}': {}", name, e))?;
-
-    Ok(abi_file)
+    parse_abi_file(name, &content)
 }
 
 /// Parse ethers Abi from our ABI file format
@@ -371,6 +408,147 @@ pub async fn sign_transaction_for_queue(
     })
 }
 
+/// Sign and queue a transaction outside a tool call (scheduler jobs such as automatic
+/// repays and redeems), labelled with `preset`. Returns the queue UUID.
+#[allow(clippy::too_many_arguments)]
+pub async fn queue_signed(
+    network: &str,
+    to: Address,
+    calldata: Vec<u8>,
+    value: U256,
+    preset: &str,
+    rpc_config: &ResolvedRpcConfig,
+    wallet_provider: &Arc<dyn WalletProvider>,
+    tx_queue: &TxQueueManager,
+) -> Result<String, String> {
+    let signed = sign_transaction_for_queue(network, to, calldata, value, rpc_config, wallet_provider).await?;
+    let queued_tx = QueuedTransaction::new(
+        Uuid::new_v4().to_string(),
+        signed.network,
+        signed.from,
+        signed.to,
+        signed.value,
+        signed.data,
+        signed.gas_limit,
+        signed.max_fee_per_gas,
+        signed.max_priority_fee_per_gas,
+        signed.nonce,
+        signed.signed_tx_hex,
+        None,
+    )
+    .with_preset(Some(preset));
+    Ok(tx_queue.queue(queued_tx))
+}
+
+/// Transactions from Discord/Telegram/Slack channels require Rogue Mode
+pub fn check_gateway_transactions_allowed(context: &ToolContext) -> Result<(), String> {
+    let is_gateway_channel = context.channel_type
+        .as_ref()
+        .map(|ct| {
+            let ct_lower = ct.to_lowercase();
+            ct_lower == "discord" || ct_lower == "telegram" || ct_lower == "slack"
+        })
+        .unwrap_or(false);
+
+    let is_rogue_mode = context.extra
+        .get("rogue_mode_enabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if is_gateway_channel && !is_rogue_mode {
+        return Err(
+            "Transactions cannot be executed in Discord/Telegram/Slack channels unless Rogue Mode is enabled. \
+            Please enable Rogue Mode in the bot settings to allow autonomous transactions from gateway channels."
+                .to_string()
+        );
+    }
+    Ok(())
+}
+
+/// Format a wei value for intent verification ("0.001000 ETH", "5 wei", "0 ETH")
+fn format_value_display(value: &str) -> String {
+    if let Ok(w) = value.parse::<u128>() {
+        let eth = w as f64 / 1e18;
+        if eth >= 0.0001 {
+            format!("{:.6} ETH", eth)
+        } else if w > 0 {
+            format!("{} wei", value)
+        } else {
+            "0 ETH".to_string()
+        }
+    } else {
+        format!("{} wei", value)
+    }
+}
+
+/// Sign, verify and queue a transaction whose calldata was built outside an ABI file
+/// (aggregator quotes, Universal Router commands). Returns the queue UUID and the signed tx.
+#[allow(clippy::too_many_arguments)]
+pub async fn queue_raw_transaction(
+    network: &Network,
+    to: Address,
+    calldata: Vec<u8>,
+    value: U256,
+    tx_type: &str,
+    description: String,
+    swap_sell: Option<verify_intent::SwapSellAmount>,
+    context: &ToolContext,
+) -> Result<(String, SignedTxForQueue), String> {
+    check_gateway_transactions_allowed(context)?;
+
+    let wallet_provider = context.wallet_provider.as_ref()
+        .ok_or_else(|| "Wallet not configured. Cannot execute web3 calls.".to_string())?;
+    let tx_queue = context.tx_queue.as_ref()
+        .ok_or_else(|| "Transaction queue not available. Contact administrator.".to_string())?;
+
+    let rpc_config = resolve_rpc_from_context(&context.extra, network.as_ref());
+    let signed = sign_transaction_for_queue(
+        network.as_ref(),
+        to,
+        calldata,
+        value,
+        &rpc_config,
+        wallet_provider,
+    ).await?;
+
+    let intent = TransactionIntent {
+        tx_type: tx_type.to_string(),
+        to: signed.to.clone(),
+        value: signed.value.clone(),
+        value_display: format_value_display(&signed.value),
+        network: signed.network.clone(),
+        function_name: None,
+        abi_name: None,
+        preset_name: None,
+        destination_chain: None,
+        calldata: Some(signed.data.clone()),
+        swap_sell,
+        description,
+    };
+    verify_intent::verify_intent(&intent, context, None).await?;
+
+    let uuid = Uuid::new_v4().to_string();
+    let queued_tx = QueuedTransaction::new(
+        uuid.clone(),
+        signed.network.clone(),
+        signed.from.clone(),
+        signed.to.clone(),
+        signed.value.clone(),
+        signed.data.clone(),
+        signed.gas_limit.clone(),
+        signed.max_fee_per_gas.clone(),
+        signed.max_priority_fee_per_gas.clone(),
+        signed.nonce,
+        signed.signed_tx_hex.clone(),
+        context.channel_id,
+    );
+    tx_queue.queue(queued_tx);
+
+    log::info!("[web3] {} transaction queued with UUID: {}", tx_type, uuid);
+
+    Ok((uuid, signed))
+}
+
 /// Shared execution logic: ABI loading, encoding, safety checks, call/sign/queue.
 /// Used by both `Web3FunctionCallTool` (manual) and `Web3PresetFunctionCallTool` (preset).
 pub async fn execute_resolved_call(
@@ -533,24 +711,8 @@ pub async fn execute_resolved_call(
         };
 
         // Check if we're in a gateway channel without rogue mode
-        let is_gateway_channel = context.channel_type
-            .as_ref()
-            .map(|ct| {
-                let ct_lower = ct.to_lowercase();
-                ct_lower == "discord" || ct_lower == "telegram" || ct_lower == "slack"
-            })
-            .unwrap_or(false);
-
-        let is_rogue_mode = context.extra
-            .get("rogue_mode_enabled")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-
-        if is_gateway_channel && !is_rogue_mode {
-            return ToolResult::error(
-                "Transactions cannot be executed in Discord/Telegram/Slack channels unless Rogue Mode is enabled. \
-                Please enable Rogue Mode in the bot settings to allow autonomous transactions from gateway channels."
-            );
+        if let Err(e) = check_gateway_transactions_allowed(context) {
+            return ToolResult::error(e);
         }
 
         // Check if tx_queue is available
@@ -570,18 +732,7 @@ pub async fn execute_resolved_call(
         ).await {
             Ok(signed) => {
                 // Verify intent before queueing
-                let value_display = if let Ok(w) = signed.value.parse::<u128>() {
-                    let eth = w as f64 / 1e18;
-                    if eth >= 0.0001 {
-                        format!("{:.6} ETH", eth)
-                    } else if w > 0 {
-                        format!("{} wei", signed.value)
-                    } else {
-                        "0 ETH".to_string()
-                    }
-                } else {
-                    format!("{} wei", signed.value)
-                };
+                let value_display = format_value_display(&signed.value);
 
                 let tx_type = if preset_name.is_some() {
                     "preset_call"
@@ -600,6 +751,7 @@ pub async fn execute_resolved_call(
                     preset_name: preset_name.map(|s| s.to_string()),
                     destination_chain: None,
                     calldata: Some(signed.data.clone()),
+                    swap_sell: None,
                     description: format!(
                         "Call {}::{}() on {}",
                         abi_name, function_name, signed.network,
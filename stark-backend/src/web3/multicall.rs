@@ -0,0 +1,224 @@
+//! Multicall3 helpers for batching read-only calls into a single eth_call.
+//!
+//! Multicall3 is deployed at the same address on every network we support,
+//! so batching balance reads costs one RPC request per network instead of
+//! one per token (which matters when the RPC provider is paid via x402).
+//! `aggregate3Value` is also used to send native currency to many recipients
+//! in one transaction (batch payouts).
+
+use crate::x402::X402EvmRpc;
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Address, U256};
+use ethers::utils::id;
+
+/// Canonical Multicall3 deployment address (same on all EVM chains)
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// Maximum sub-calls per aggregate3 request, to stay well under eth_call gas caps
+const MAX_CALLS_PER_BATCH: usize = 200;
+
+/// A single call inside an aggregate3 batch
+#[derive(Debug, Clone)]
+pub struct Call3 {
+    pub target: Address,
+    pub allow_failure: bool,
+    pub call_data: Vec<u8>,
+}
+
+/// Result of a single call inside an aggregate3 batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call3Result {
+    pub success: bool,
+    pub return_data: Vec<u8>,
+}
+
+impl Call3Result {
+    /// Decode the return data as a single uint256 (balances, allowances, ...)
+    pub fn as_u256(&self) -> Option<U256> {
+        if !self.success || self.return_data.len() < 32 {
+            return None;
+        }
+        Some(U256::from_big_endian(&self.return_data[..32]))
+    }
+}
+
+/// A single value-carrying call inside an aggregate3Value batch
+#[derive(Debug, Clone)]
+pub struct Call3Value {
+    pub target: Address,
+    pub allow_failure: bool,
+    pub value: U256,
+    pub call_data: Vec<u8>,
+}
+
+pub fn multicall3_address() -> Address {
+    MULTICALL3_ADDRESS.parse().expect("valid multicall3 address")
+}
+
+/// Calldata for ERC-20 `balanceOf(owner)`
+pub fn encode_balance_of(owner: Address) -> Vec<u8> {
+    let mut data = id("balanceOf(address)").to_vec();
+    data.extend(abi::encode(&[Token::Address(owner)]));
+    data
+}
+
+/// Build a Call3 reading the native balance of `owner` via Multicall3's `getEthBalance`
+pub fn native_balance_call(owner: Address) -> Call3 {
+    let mut data = id("getEthBalance(address)").to_vec();
+    data.extend(abi::encode(&[Token::Address(owner)]));
+    Call3 {
+        target: multicall3_address(),
+        allow_failure: true,
+        call_data: data,
+    }
+}
+
+/// Build a Call3 reading an ERC-20 balance
+pub fn erc20_balance_call(token: Address, owner: Address) -> Call3 {
+    Call3 {
+        target: token,
+        allow_failure: true,
+        call_data: encode_balance_of(owner),
+    }
+}
+
+/// Build a Call3 reading the ERC-20 allowance `owner` granted to `spender`
+pub fn erc20_allowance_call(token: Address, owner: Address, spender: Address) -> Call3 {
+    let mut data = id("allowance(address,address)").to_vec();
+    data.extend(abi::encode(&[Token::Address(owner), Token::Address(spender)]));
+    Call3 {
+        target: token,
+        allow_failure: true,
+        call_data: data,
+    }
+}
+
+/// Build a Call3 reading a no-argument ERC-20 getter such as `decimals()` or `symbol()`
+pub fn erc20_getter_call(token: Address, signature: &str) -> Call3 {
+    Call3 {
+        target: token,
+        allow_failure: true,
+        call_data: id(signature).to_vec(),
+    }
+}
+
+/// Encode `aggregate3((address,bool,bytes)[])` calldata
+pub fn encode_aggregate3(calls: &[Call3]) -> Vec<u8> {
+    let tuples: Vec<Token> = calls
+        .iter()
+        .map(|c| {
+            Token::Tuple(vec![
+                Token::Address(c.target),
+                Token::Bool(c.allow_failure),
+                Token::Bytes(c.call_data.clone()),
+            ])
+        })
+        .collect();
+
+    let mut data = id("aggregate3((address,bool,bytes)[])").to_vec();
+    data.extend(abi::encode(&[Token::Array(tuples)]));
+    data
+}
+
+/// Encode `aggregate3Value((address,bool,uint256,bytes)[])` calldata.
+/// The transaction value must equal the sum of the call values.
+pub fn encode_aggregate3_value(calls: &[Call3Value]) -> Vec<u8> {
+    let tuples: Vec<Token> = calls
+        .iter()
+        .map(|c| {
+            Token::Tuple(vec![
+                Token::Address(c.target),
+                Token::Bool(c.allow_failure),
+                Token::Uint(c.value),
+                Token::Bytes(c.call_data.clone()),
+            ])
+        })
+        .collect();
+
+    let mut data = id("aggregate3Value((address,bool,uint256,bytes)[])").to_vec();
+    data.extend(abi::encode(&[Token::Array(tuples)]));
+    data
+}
+
+/// Decode the `(bool,bytes)[]` return value of aggregate3
+pub fn decode_aggregate3(data: &[u8]) -> Result<Vec<Call3Result>, String> {
+    let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![
+        ParamType::Bool,
+        ParamType::Bytes,
+    ])));
+
+    let tokens = abi::decode(&[result_type], data)
+        .map_err(|e| format!("Failed to decode aggregate3 result: {}", e))?;
+
+    let items = match tokens.into_iter().next() {
+        Some(Token::Array(items)) => items,
+        _ => return Err("Unexpected aggregate3 result shape".to_string()),
+    };
+
+    items
+        .into_iter()
+        .map(|item| match item {
+            Token::Tuple(fields) => match (fields.first(), fields.get(1)) {
+                (Some(Token::Bool(success)), Some(Token::Bytes(bytes))) => Ok(Call3Result {
+                    success: *success,
+                    return_data: bytes.clone(),
+                }),
+                _ => Err("Unexpected aggregate3 tuple fields".to_string()),
+            },
+            _ => Err("Unexpected aggregate3 item".to_string()),
+        })
+        .collect()
+}
+
+/// Execute calls through Multicall3, splitting into batches as needed.
+/// Results are returned in the same order as `calls`.
+pub async fn aggregate3(rpc: &X402EvmRpc, calls: &[Call3]) -> Result<Vec<Call3Result>, String> {
+    let mut results = Vec::with_capacity(calls.len());
+    for batch in calls.chunks(MAX_CALLS_PER_BATCH) {
+        let data = encode_aggregate3(batch);
+        let raw = rpc.call(multicall3_address(), &data).await?;
+        let decoded = decode_aggregate3(&raw)?;
+        if decoded.len() != batch.len() {
+            return Err(format!(
+                "aggregate3 returned {} results for {} calls",
+                decoded.len(),
+                batch.len()
+            ));
+        }
+        results.extend(decoded);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(hex::encode(&encode_aggregate3(&[])[..4]), "82ad56cb");
+        assert_eq!(hex::encode(&encode_aggregate3_value(&[])[..4]), "174dea71");
+        assert_eq!(hex::encode(&encode_balance_of(Address::zero())[..4]), "70a08231");
+        assert_eq!(hex::encode(&native_balance_call(Address::zero()).call_data[..4]), "4d2301cc");
+        assert_eq!(
+            hex::encode(&erc20_allowance_call(Address::zero(), Address::zero(), Address::zero()).call_data[..4]),
+            "dd62ed3e"
+        );
+    }
+
+    #[test]
+    fn test_decode_aggregate3_roundtrip() {
+        let mut balance = [0u8; 32];
+        U256::from(1_500_000u64).to_big_endian(&mut balance);
+
+        let encoded = abi::encode(&[Token::Array(vec![
+            Token::Tuple(vec![Token::Bool(true), Token::Bytes(balance.to_vec())]),
+            Token::Tuple(vec![Token::Bool(false), Token::Bytes(vec![])]),
+        ])]);
+
+        let results = decode_aggregate3(&encoded).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_u256(), Some(U256::from(1_500_000u64)));
+        assert_eq!(results[1].as_u256(), None);
+    }
+}
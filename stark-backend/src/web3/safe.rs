@@ -0,0 +1,255 @@
+//! Safe transaction helpers: MultiSend batching, Safe tx hashes and execution.
+//!
+//! Signing follows the `safe_wallet` skill: owners approve the Safe tx hash
+//! on-chain (`approveHash`) and `execTransaction` is called with pre-validated
+//! signatures (`r = owner, s = 0, v = 1`). A pre-validated signature for the
+//! owner sending `execTransaction` needs no prior `approveHash`.
+
+use crate::x402::X402EvmRpc;
+use ethers::abi::{self, ParamType, Token};
+use ethers::types::{Address, H256, U256};
+use ethers::utils::id;
+
+/// MultiSendCallOnly v1.4.1 (same address on all chains). Only allows calls,
+/// so a delegatecall into it cannot change the Safe's own storage.
+pub const MULTI_SEND_CALL_ONLY_ADDRESS: &str = "0x9641d764fc13c8B624c04430C7356C1C7C8102e2";
+
+/// Safe operation type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Call = 0,
+    DelegateCall = 1,
+}
+
+/// A call inside a MultiSend batch
+#[derive(Debug, Clone)]
+pub struct MultiSendCall {
+    pub to: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+/// A Safe transaction without gas refund (safeTxGas, baseGas, gasPrice, gasToken
+/// and refundReceiver are all zero)
+#[derive(Debug, Clone)]
+pub struct SafeTx {
+    pub to: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+    pub operation: Operation,
+    pub nonce: U256,
+}
+
+pub fn multi_send_call_only_address() -> Address {
+    MULTI_SEND_CALL_ONLY_ADDRESS.parse().expect("valid MultiSendCallOnly address")
+}
+
+/// Encode `multiSend(bytes)` calldata. Each call is packed as
+/// `operation (1) | to (20) | value (32) | data length (32) | data`.
+pub fn encode_multi_send(calls: &[MultiSendCall]) -> Vec<u8> {
+    let mut packed = Vec::new();
+    for call in calls {
+        packed.push(Operation::Call as u8);
+        packed.extend_from_slice(call.to.as_bytes());
+        let mut word = [0u8; 32];
+        call.value.to_big_endian(&mut word);
+        packed.extend_from_slice(&word);
+        U256::from(call.data.len()).to_big_endian(&mut word);
+        packed.extend_from_slice(&word);
+        packed.extend_from_slice(&call.data);
+    }
+
+    let mut data = id("multiSend(bytes)").to_vec();
+    data.extend(abi::encode(&[Token::Bytes(packed)]));
+    data
+}
+
+/// Safe tx fields in `execTransaction` / `getTransactionHash` order, up to refundReceiver
+fn safe_tx_tokens(tx: &SafeTx) -> Vec<Token> {
+    vec![
+        Token::Address(tx.to),
+        Token::Uint(tx.value),
+        Token::Bytes(tx.data.clone()),
+        Token::Uint(U256::from(tx.operation as u8)),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+        Token::Address(Address::zero()),
+        Token::Address(Address::zero()),
+    ]
+}
+
+/// Calldata for Safe `getTransactionHash(...)`
+pub fn encode_get_transaction_hash(tx: &SafeTx) -> Vec<u8> {
+    let mut tokens = safe_tx_tokens(tx);
+    tokens.push(Token::Uint(tx.nonce));
+    let mut data =
+        id("getTransactionHash(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,uint256)").to_vec();
+    data.extend(abi::encode(&tokens));
+    data
+}
+
+/// Calldata for Safe `execTransaction(...)`
+pub fn encode_exec_transaction(tx: &SafeTx, signatures: &[u8]) -> Vec<u8> {
+    let mut tokens = safe_tx_tokens(tx);
+    tokens.push(Token::Bytes(signatures.to_vec()));
+    let mut data =
+        id("execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)").to_vec();
+    data.extend(abi::encode(&tokens));
+    data
+}
+
+/// Calldata for Safe `approveHash(bytes32)`
+pub fn encode_approve_hash(hash: H256) -> Vec<u8> {
+    let mut data = id("approveHash(bytes32)").to_vec();
+    data.extend(abi::encode(&[Token::FixedBytes(hash.as_bytes().to_vec())]));
+    data
+}
+
+/// Packed pre-validated signatures (`r = owner, s = 0, v = 1`), sorted by owner
+/// address as `execTransaction` requires
+pub fn approved_hash_signatures(owners: &[Address]) -> Vec<u8> {
+    let mut owners = owners.to_vec();
+    owners.sort();
+    owners.dedup();
+
+    let mut signatures = Vec::with_capacity(owners.len() * 65);
+    for owner in owners {
+        signatures.extend_from_slice(H256::from(owner).as_bytes());
+        signatures.extend_from_slice(&[0u8; 32]);
+        signatures.push(1);
+    }
+    signatures
+}
+
+async fn call_uint(rpc: &X402EvmRpc, safe: Address, signature: &str) -> Result<U256, String> {
+    let data = rpc.call(safe, &id(signature)).await?;
+    match abi::decode(&[ParamType::Uint(256)], &data) {
+        Ok(tokens) => tokens
+            .into_iter()
+            .next()
+            .and_then(|t| t.into_uint())
+            .ok_or_else(|| format!("Unexpected {} result", signature)),
+        Err(e) => Err(format!("Failed to decode {} result: {}", signature, e)),
+    }
+}
+
+/// What one owner sends to the Safe to move a Safe transaction forward
+#[derive(Debug, Clone)]
+pub struct OwnerCall {
+    /// Calldata for the Safe: `execTransaction` or `approveHash`
+    pub calldata: Vec<u8>,
+    pub safe_tx_hash: H256,
+    /// True when the call executes the Safe tx (threshold 1); false when it only
+    /// adds the owner's on-chain approval and other owners still need to confirm
+    pub executes: bool,
+}
+
+/// Build the owner's call for a Safe tx at the Safe's current nonce: an
+/// `execTransaction` with the owner's pre-validated signature when the Safe
+/// needs a single confirmation, otherwise `approveHash` of the Safe tx hash
+pub async fn prepare_owner_call(
+    rpc: &X402EvmRpc,
+    safe: Address,
+    owner: Address,
+    to: Address,
+    value: U256,
+    data: Vec<u8>,
+    operation: Operation,
+) -> Result<OwnerCall, String> {
+    let nonce = get_nonce(rpc, safe)
+        .await
+        .map_err(|e| format!("Failed to read Safe nonce: {}", e))?;
+    let threshold = get_threshold(rpc, safe)
+        .await
+        .map_err(|e| format!("Failed to read Safe threshold: {}", e))?;
+    let tx = SafeTx { to, value, data, operation, nonce };
+    let safe_tx_hash = get_transaction_hash(rpc, safe, &tx)
+        .await
+        .map_err(|e| format!("Failed to compute Safe tx hash: {}", e))?;
+
+    if threshold <= U256::one() {
+        Ok(OwnerCall {
+            calldata: encode_exec_transaction(&tx, &approved_hash_signatures(&[owner])),
+            safe_tx_hash,
+            executes: true,
+        })
+    } else {
+        Ok(OwnerCall {
+            calldata: encode_approve_hash(safe_tx_hash),
+            safe_tx_hash,
+            executes: false,
+        })
+    }
+}
+
+/// Number of owner confirmations the Safe requires
+pub async fn get_threshold(rpc: &X402EvmRpc, safe: Address) -> Result<U256, String> {
+    call_uint(rpc, safe, "getThreshold()").await
+}
+
+/// Nonce the next Safe transaction must use
+pub async fn get_nonce(rpc: &X402EvmRpc, safe: Address) -> Result<U256, String> {
+    call_uint(rpc, safe, "nonce()").await
+}
+
+/// Safe tx hash owners approve, computed by the Safe itself
+pub async fn get_transaction_hash(rpc: &X402EvmRpc, safe: Address, tx: &SafeTx) -> Result<H256, String> {
+    let data = rpc.call(safe, &encode_get_transaction_hash(tx)).await?;
+    if data.len() < 32 {
+        return Err("Unexpected getTransactionHash result".to_string());
+    }
+    Ok(H256::from_slice(&data[..32]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selectors() {
+        let tx = SafeTx {
+            to: Address::zero(),
+            value: U256::zero(),
+            data: vec![],
+            operation: Operation::Call,
+            nonce: U256::zero(),
+        };
+        assert_eq!(hex::encode(&encode_multi_send(&[])[..4]), "8d80ff0a");
+        assert_eq!(hex::encode(&encode_get_transaction_hash(&tx)[..4]), "d8d11f78");
+        assert_eq!(hex::encode(&encode_exec_transaction(&tx, &[])[..4]), "6a761202");
+        assert_eq!(hex::encode(&encode_approve_hash(H256::zero())[..4]), "d4d9bdcd");
+    }
+
+    #[test]
+    fn test_multi_send_packing() {
+        let to = Address::repeat_byte(0x11);
+        let calls = vec![
+            MultiSendCall { to, value: U256::from(5), data: vec![0xaa, 0xbb] },
+            MultiSendCall { to, value: U256::zero(), data: vec![] },
+        ];
+        let encoded = encode_multi_send(&calls);
+        let tokens = abi::decode(&[ParamType::Bytes], &encoded[4..]).unwrap();
+        let packed = tokens[0].clone().into_bytes().unwrap();
+
+        assert_eq!(packed.len(), (85 + 2) + 85);
+        assert_eq!(packed[0], 0);
+        assert_eq!(&packed[1..21], to.as_bytes());
+        assert_eq!(U256::from_big_endian(&packed[21..53]), U256::from(5));
+        assert_eq!(U256::from_big_endian(&packed[53..85]), U256::from(2));
+        assert_eq!(&packed[85..87], &[0xaa, 0xbb]);
+        assert_eq!(U256::from_big_endian(&packed[87 + 53..87 + 85]), U256::zero());
+    }
+
+    #[test]
+    fn test_approved_hash_signatures_sorted() {
+        let a = Address::repeat_byte(0x22);
+        let b = Address::repeat_byte(0x11);
+        let sigs = approved_hash_signatures(&[a, b, a]);
+        assert_eq!(sigs.len(), 130);
+        assert_eq!(&sigs[12..32], b.as_bytes());
+        assert_eq!(&sigs[32..64], &[0u8; 32]);
+        assert_eq!(sigs[64], 1);
+        assert_eq!(&sigs[65 + 12..65 + 32], a.as_bytes());
+    }
+}
@@ -0,0 +1,224 @@
+//! Spend budgets for x402-paid AI endpoints
+//!
+//! Limits are kept in micro-USDC (6 decimals, the unit x402 payments are made in): a daily
+//! total (UTC), a per-session total and a per-cron-job daily total. Before each AI call the
+//! dispatcher checks the tightest applicable limit against the estimated cost of the call;
+//! the remaining allowance is also handed to the x402 client so it refuses to sign more.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ai_endpoint_config::{self, AiEndpointPreset};
+
+/// `x402_payments.kind` of payments for AI calls, the only ones counted against budgets
+/// (tool and RPC payments are recorded as `tool`)
+pub const AI_PAYMENT_KIND: &str = "ai";
+
+/// Configured limits in micro-USDC (None = no limit)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiBudget {
+    pub daily_limit: Option<u64>,
+    pub session_limit: Option<u64>,
+    pub cron_job_limit: Option<u64>,
+    /// Switch to a cheaper endpoint preset instead of stopping when the configured one no longer fits
+    pub fallback_enabled: bool,
+}
+
+impl AiBudget {
+    pub fn is_unlimited(&self) -> bool {
+        self.daily_limit.is_none() && self.session_limit.is_none() && self.cron_job_limit.is_none()
+    }
+}
+
+/// USDC already spent in each budget scope (micro-USDC)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AiSpend {
+    pub today: u64,
+    pub session: u64,
+    pub cron_job: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    Daily,
+    Session,
+    CronJob,
+}
+
+impl BudgetScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BudgetScope::Daily => "daily",
+            BudgetScope::Session => "session",
+            BudgetScope::CronJob => "cron job",
+        }
+    }
+}
+
+/// Outcome of a pre-flight budget check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BudgetCheck {
+    /// No limit applies
+    Unlimited,
+    /// The call fits; `remaining` is the tightest allowance left
+    Allowed { remaining: u64 },
+    /// The call would exceed (or the scope has already used up) a limit
+    Exhausted {
+        scope: BudgetScope,
+        limit: u64,
+        spent: u64,
+        estimated_cost: Option<u64>,
+    },
+}
+
+impl BudgetCheck {
+    /// Message shown to the user when a budget stops the agent
+    pub fn exhausted_message(&self) -> Option<String> {
+        match self {
+            BudgetCheck::Exhausted { scope, limit, spent, estimated_cost } => Some(format!(
+                "AI spend budget exhausted: {} limit is {} USDC, {} USDC spent{}",
+                scope.label(),
+                format_usdc(*limit),
+                format_usdc(*spent),
+                estimated_cost
+                    .map(|c| format!(", next call costs ~{} USDC", format_usdc(c)))
+                    .unwrap_or_default()
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Check a call of `estimated_cost` against every applicable limit. The cron job limit only
+/// applies when the call belongs to a cron job.
+pub fn check(budget: &AiBudget, spend: &AiSpend, is_cron_job: bool, estimated_cost: Option<u64>) -> BudgetCheck {
+    let scopes = [
+        (BudgetScope::Daily, budget.daily_limit, spend.today),
+        (BudgetScope::Session, budget.session_limit, spend.session),
+        (
+            BudgetScope::CronJob,
+            budget.cron_job_limit.filter(|_| is_cron_job),
+            spend.cron_job,
+        ),
+    ];
+
+    let tightest = scopes
+        .into_iter()
+        .filter_map(|(scope, limit, spent)| limit.map(|l| (scope, l, spent)))
+        .min_by_key(|(_, limit, spent)| limit.saturating_sub(*spent));
+
+    let Some((scope, limit, spent)) = tightest else {
+        return BudgetCheck::Unlimited;
+    };
+
+    let remaining = limit.saturating_sub(spent);
+    if remaining == 0 || estimated_cost.is_some_and(|cost| cost > remaining) {
+        return BudgetCheck::Exhausted {
+            scope,
+            limit,
+            spent,
+            estimated_cost,
+        };
+    }
+    BudgetCheck::Allowed { remaining }
+}
+
+/// Per-call cost of an endpoint as declared by its `ai_endpoints.ron` preset
+pub fn preset_cost(endpoint: &str) -> Option<u64> {
+    ai_endpoint_config::list_ai_endpoints()
+        .into_iter()
+        .find(|(_, preset)| preset.endpoint == endpoint)
+        .and_then(|(_, preset)| preset.x402_cost)
+}
+
+/// The most expensive preset of the same archetype that is still cheaper than `current_cost`
+/// and fits into `remaining`
+pub fn cheaper_endpoint(
+    presets: &[(String, AiEndpointPreset)],
+    archetype: &str,
+    current_cost: u64,
+    remaining: u64,
+) -> Option<(String, AiEndpointPreset)> {
+    presets
+        .iter()
+        .filter(|(_, p)| p.model_archetype == archetype)
+        .filter_map(|(id, p)| p.x402_cost.map(|cost| (id, p, cost)))
+        .filter(|(_, _, cost)| *cost < current_cost && *cost <= remaining)
+        .max_by_key(|(_, _, cost)| *cost)
+        .map(|(id, p, _)| (id.clone(), p.clone()))
+}
+
+/// Micro-USDC as a decimal string (e.g. 2500 -> "0.0025")
+pub fn format_usdc(micro: u64) -> String {
+    let formatted = format!("{}.{:06}", micro / 1_000_000, micro % 1_000_000);
+    let trimmed = formatted.trim_end_matches('0');
+    trimmed.strip_suffix('.').unwrap_or(trimmed).to_string()
+}
+
+/// Decimal USDC to micro-USDC (rejects negative and non-finite amounts)
+pub fn parse_usdc(usdc: f64) -> Result<u64, String> {
+    if !usdc.is_finite() || usdc < 0.0 {
+        return Err(format!("Invalid USDC amount: {}", usdc));
+    }
+    Ok((usdc * 1_000_000.0).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(archetype: &str, cost: Option<u64>) -> AiEndpointPreset {
+        AiEndpointPreset {
+            display_name: String::new(),
+            endpoint: String::new(),
+            model_archetype: archetype.to_string(),
+            x402_cost: cost,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_check_uses_tightest_limit() {
+        let budget = AiBudget {
+            daily_limit: Some(1_000_000),
+            session_limit: Some(100_000),
+            cron_job_limit: Some(10_000),
+            fallback_enabled: false,
+        };
+        let spend = AiSpend { today: 500_000, session: 95_000, cron_job: 10_000 };
+
+        // Cron limit is ignored outside cron jobs
+        assert_eq!(check(&budget, &spend, false, Some(5_000)), BudgetCheck::Allowed { remaining: 5_000 });
+        assert!(matches!(
+            check(&budget, &spend, false, Some(5_001)),
+            BudgetCheck::Exhausted { scope: BudgetScope::Session, .. }
+        ));
+        assert!(matches!(
+            check(&budget, &spend, true, None),
+            BudgetCheck::Exhausted { scope: BudgetScope::CronJob, .. }
+        ));
+        assert_eq!(check(&AiBudget::default(), &spend, true, Some(1)), BudgetCheck::Unlimited);
+    }
+
+    #[test]
+    fn test_cheaper_endpoint() {
+        let presets = vec![
+            ("kimi".to_string(), preset("kimi", Some(5000))),
+            ("kimi-turbo".to_string(), preset("kimi", Some(2500))),
+            ("kimi-mini".to_string(), preset("kimi", Some(1000))),
+            ("llama".to_string(), preset("llama", Some(500))),
+        ];
+        let (id, _) = cheaper_endpoint(&presets, "kimi", 5000, 3000).unwrap();
+        assert_eq!(id, "kimi-turbo");
+        assert!(cheaper_endpoint(&presets, "kimi", 5000, 900).is_none());
+    }
+
+    #[test]
+    fn test_usdc_conversion() {
+        assert_eq!(format_usdc(2500), "0.0025");
+        assert_eq!(format_usdc(1_500_000), "1.5");
+        assert_eq!(format_usdc(0), "0");
+        assert_eq!(parse_usdc(0.25).unwrap(), 250_000);
+        assert!(parse_usdc(-1.0).is_err());
+    }
+}
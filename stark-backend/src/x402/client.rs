@@ -2,6 +2,7 @@
 
 use reqwest::{header, Client, Response};
 use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -19,6 +20,8 @@ pub struct X402Response {
 pub struct X402Client {
     client: Client,
     signer: Arc<X402Signer>,
+    /// Largest single payment (smallest asset unit) this client will sign; u64::MAX = no cap
+    spend_limit: AtomicU64,
 }
 
 impl X402Client {
@@ -36,6 +39,7 @@ impl X402Client {
         Ok(Self {
             client,
             signer: Arc::new(signer),
+            spend_limit: AtomicU64::new(u64::MAX),
         })
     }
 
@@ -53,9 +57,16 @@ impl X402Client {
         Ok(Self {
             client,
             signer: Arc::new(signer),
+            spend_limit: AtomicU64::new(u64::MAX),
         })
     }
 
+    /// Cap the amount of any single payment this client signs (None = no cap).
+    /// Used to keep AI calls within the remaining spend budget.
+    pub fn set_spend_limit(&self, limit: Option<u64>) {
+        self.spend_limit.store(limit.unwrap_or(u64::MAX), Ordering::SeqCst);
+    }
+
     /// Get the wallet address
     pub fn wallet_address(&self) -> String {
         self.signer.address()
@@ -149,6 +160,18 @@ impl X402Client {
         let requirements = payment_required.accepts.first()
             .ok_or_else(|| "No payment options in 402 response".to_string())?;
 
+        // Refuse to sign more than the remaining budget allows
+        let spend_limit = self.spend_limit.load(Ordering::SeqCst);
+        if spend_limit != u64::MAX {
+            let amount: u64 = requirements.max_amount_required.parse().unwrap_or(u64::MAX);
+            if amount > spend_limit {
+                return Err(format!(
+                    "x402 payment of {} exceeds the remaining AI spend budget ({})",
+                    requirements.max_amount_required, spend_limit
+                ));
+            }
+        }
+
         // Create payment info before signing
         let payment_info = X402PaymentInfo::from_requirements(requirements);
 
@@ -76,6 +76,18 @@ pub struct TxLog {
     pub data: Bytes,
 }
 
+/// A log entry from eth_getLogs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterLog {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+    pub block_number: Option<U64>,
+    pub transaction_hash: Option<H256>,
+    pub log_index: Option<U256>,
+}
+
 impl X402EvmRpc {
     /// Create a new X402 EVM RPC client with default settings (x402 enabled)
     pub fn new(private_key: &str, network: &str) -> Result<Self, String> {
@@ -322,6 +334,25 @@ impl X402EvmRpc {
             .map_err(|e| format!("Failed to parse nonce: {}", e))
     }
 
+    /// Get the latest block number
+    pub async fn block_number(&self) -> Result<u64, String> {
+        let result = self.rpc_call("eth_blockNumber", json!([])).await?;
+
+        let hex_str = result.as_str()
+            .ok_or_else(|| "Invalid blockNumber response".to_string())?;
+
+        u64::from_str_radix(hex_str.trim_start_matches("0x"), 16)
+            .map_err(|e| format!("Failed to parse block number: {}", e))
+    }
+
+    /// Get logs matching a filter (`address`, `topics`, `fromBlock`, `toBlock`)
+    pub async fn get_logs(&self, filter: Value) -> Result<Vec<FilterLog>, String> {
+        let result = self.rpc_call("eth_getLogs", json!([filter])).await?;
+
+        serde_json::from_value(result)
+            .map_err(|e| format!("Failed to parse logs: {}", e))
+    }
+
     /// Wait for a transaction receipt with polling
     pub async fn wait_for_receipt(
         &self,
@@ -16,8 +16,9 @@ mod client;
 mod signer;
 mod evm_rpc;
 pub mod erc20;
+pub mod budget;
 
 pub use types::*;
 pub use client::{X402Client, X402Response, is_x402_endpoint};
 pub use signer::X402Signer;
-pub use evm_rpc::{TxLog, X402EvmRpc};
+pub use evm_rpc::{FilterLog, TxLog, X402EvmRpc};
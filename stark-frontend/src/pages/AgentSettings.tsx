@@ -6,6 +6,7 @@ import Input from '@/components/ui/Input';
 import { getAgentSettings, updateAgentSettings, getBotSettings, updateBotSettings, getAiEndpointPresets, AiEndpointPreset } from '@/lib/api';
 
 type ModelArchetype = 'kimi' | 'llama' | 'claude' | 'openai';
+type ProviderKind = 'auto' | 'anthropic' | 'openai' | 'ollama';
 
 interface Settings {
   endpoint?: string;
@@ -13,6 +14,10 @@ interface Settings {
   max_response_tokens?: number;
   max_context_tokens?: number;
   has_secret_key?: boolean;
+  model?: string | null;
+  provider?: ProviderKind;
+  temperature?: number | null;
+  top_p?: number | null;
 }
 
 export default function AgentSettings() {
@@ -23,6 +28,10 @@ export default function AgentSettings() {
   const [maxResponseTokens, setMaxResponseTokens] = useState(40000);
   const [maxContextTokens, setMaxContextTokens] = useState(100000);
   const [secretKey, setSecretKey] = useState('');
+  const [model, setModel] = useState('');
+  const [provider, setProvider] = useState<ProviderKind>('auto');
+  const [temperature, setTemperature] = useState('');
+  const [topP, setTopP] = useState('');
   const [hasExistingSecretKey, setHasExistingSecretKey] = useState(false);
   const [maxToolIterations, setMaxToolIterations] = useState(50);
   const [isLoading, setIsLoading] = useState(true);
@@ -82,6 +91,12 @@ export default function AgentSettings() {
         setModelArchetype(data.model_archetype as ModelArchetype);
       }
 
+      // Set model selection and sampling parameters
+      setModel(data.model ?? '');
+      setProvider(data.provider ?? 'auto');
+      setTemperature(data.temperature != null ? String(data.temperature) : '');
+      setTopP(data.top_p != null ? String(data.top_p) : '');
+
       // Set token limits
       if (data.max_response_tokens && data.max_response_tokens > 0) {
         setMaxResponseTokens(data.max_response_tokens);
@@ -154,6 +169,10 @@ export default function AgentSettings() {
         max_response_tokens: number;
         max_context_tokens: number;
         secret_key?: string;
+        model?: string;
+        provider?: ProviderKind;
+        temperature?: number;
+        top_p?: number;
       } = {
         endpoint,
         model_archetype: archetype,
@@ -165,6 +184,14 @@ export default function AgentSettings() {
         payload.secret_key = secretKey;
       }
 
+      // Model selection is only configurable for custom endpoints
+      if (endpointOption === 'custom') {
+        if (model.trim()) payload.model = model.trim();
+        payload.provider = provider;
+        if (temperature.trim()) payload.temperature = parseFloat(temperature);
+        if (topP.trim()) payload.top_p = parseFloat(topP);
+      }
+
       await updateAgentSettings(payload);
       setMessage({ type: 'success', text: 'Endpoint settings saved successfully' });
 
@@ -174,7 +201,15 @@ export default function AgentSettings() {
         setSecretKey(''); // Clear the input after saving
       }
     } catch (err) {
-      setMessage({ type: 'error', text: 'Failed to save endpoint settings' });
+      // Validation errors come back as {"error": "..."}
+      let text = 'Failed to save endpoint settings';
+      try {
+        const parsed = JSON.parse(err instanceof Error ? err.message : '');
+        if (parsed?.error) text = parsed.error;
+      } catch {
+        // not a JSON error body
+      }
+      setMessage({ type: 'error', text });
     } finally {
       setIsSaving(false);
     }
@@ -286,6 +321,57 @@ export default function AgentSettings() {
                 </p>
               </div>
 
+              {endpointOption === 'custom' && (
+                <>
+                  <Input
+                    label="Model"
+                    value={model}
+                    onChange={(e) => setModel(e.target.value)}
+                    placeholder="Leave empty to use the archetype's default model"
+                  />
+                  <div>
+                    <label className="block text-sm font-medium text-slate-300 mb-2">
+                      Provider
+                    </label>
+                    <select
+                      value={provider}
+                      onChange={(e) => setProvider(e.target.value as ProviderKind)}
+                      className="w-full px-4 py-3 bg-slate-900/50 border border-slate-600 rounded-lg text-white focus:outline-none focus:ring-2 focus:ring-stark-500 focus:border-transparent"
+                    >
+                      <option value="auto">Auto (from archetype)</option>
+                      <option value="openai">OpenAI-compatible</option>
+                      <option value="anthropic">Anthropic</option>
+                      <option value="ollama">Ollama (native /api/chat)</option>
+                    </select>
+                    <p className="text-xs text-slate-500 mt-1">
+                      API flavour spoken by the endpoint. Auto uses Anthropic for Claude and OpenAI-compatible otherwise.
+                    </p>
+                  </div>
+                  <div className="grid grid-cols-2 gap-4">
+                    <Input
+                      label="Temperature"
+                      type="number"
+                      step="0.1"
+                      min={0}
+                      max={2}
+                      value={temperature}
+                      onChange={(e) => setTemperature(e.target.value)}
+                      placeholder="Provider default"
+                    />
+                    <Input
+                      label="Top P"
+                      type="number"
+                      step="0.05"
+                      min={0}
+                      max={1}
+                      value={topP}
+                      onChange={(e) => setTopP(e.target.value)}
+                      placeholder="Provider default"
+                    />
+                  </div>
+                </>
+              )}
+
               <div>
                 <label className="block text-sm font-medium text-slate-300 mb-2">
                   Max Response Tokens
@@ -1,8 +1,10 @@
 import { useState, useEffect } from 'react';
-import { DollarSign, Receipt, TrendingUp, ExternalLink, Clock } from 'lucide-react';
+import { DollarSign, Receipt, TrendingUp, ExternalLink, Clock, Gauge, Save } from 'lucide-react';
 import Card, { CardContent } from '@/components/ui/Card';
 import Button from '@/components/ui/Button';
+import Input from '@/components/ui/Input';
 import { useApi } from '@/hooks/useApi';
+import { apiFetch } from '@/lib/api';
 
 interface PaymentInfo {
   id: number;
@@ -39,10 +41,135 @@ interface SummaryResponse {
   error?: string;
 }
 
+interface BudgetLimits {
+  daily_limit_usdc: string | null;
+  session_limit_usdc: string | null;
+  cron_job_limit_usdc: string | null;
+  fallback_enabled: boolean;
+}
+
+interface BudgetResponse {
+  success: boolean;
+  budget?: BudgetLimits;
+  spent_today_usdc?: string;
+  remaining_today_usdc?: string | null;
+  cron_jobs?: { cron_job_id: string; spent_usdc: string; remaining_usdc: string | null }[];
+  endpoint?: string | null;
+  endpoint_cost_usdc?: string | null;
+  error?: string;
+}
+
+function BudgetCard({ data, onSaved }: { data: BudgetResponse | null; onSaved: () => void }) {
+  const [daily, setDaily] = useState('');
+  const [session, setSession] = useState('');
+  const [cronJob, setCronJob] = useState('');
+  const [fallback, setFallback] = useState(true);
+  const [isSaving, setIsSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const budget = data?.budget;
+  useEffect(() => {
+    if (!budget) return;
+    setDaily(budget.daily_limit_usdc ?? '');
+    setSession(budget.session_limit_usdc ?? '');
+    setCronJob(budget.cron_job_limit_usdc ?? '');
+    setFallback(budget.fallback_enabled);
+  }, [budget?.daily_limit_usdc, budget?.session_limit_usdc, budget?.cron_job_limit_usdc, budget?.fallback_enabled]);
+
+  const toLimit = (value: string) => (value.trim() ? parseFloat(value) : null);
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    setError(null);
+    try {
+      await apiFetch('/payments/budget', {
+        method: 'PUT',
+        body: JSON.stringify({
+          daily_limit_usdc: toLimit(daily),
+          session_limit_usdc: toLimit(session),
+          cron_job_limit_usdc: toLimit(cronJob),
+          fallback_enabled: fallback,
+        }),
+      });
+      onSaved();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to save budget');
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  const spentToday = parseFloat(data?.spent_today_usdc ?? '0');
+  const dailyLimit = budget?.daily_limit_usdc ? parseFloat(budget.daily_limit_usdc) : null;
+  const usedPct = dailyLimit ? Math.min(100, (spentToday / dailyLimit) * 100) : 0;
+
+  return (
+    <Card className="mb-8">
+      <CardContent>
+        <div className="flex items-center gap-3 mb-4">
+          <div className="p-3 rounded-lg bg-stark-500/20">
+            <Gauge className="w-6 h-6 text-stark-400" />
+          </div>
+          <div>
+            <h2 className="text-lg font-semibold text-white">AI Spend Budget</h2>
+            <p className="text-sm text-slate-400">
+              ${data?.spent_today_usdc ?? '0'} spent today
+              {dailyLimit !== null && ` of $${budget?.daily_limit_usdc}`}
+              {data?.endpoint_cost_usdc && ` · current endpoint ~$${data.endpoint_cost_usdc} per call`}
+            </p>
+          </div>
+        </div>
+
+        {dailyLimit !== null && (
+          <div className="h-2 bg-slate-700 rounded-full mb-4 overflow-hidden">
+            <div
+              className={`h-full ${usedPct >= 90 ? 'bg-red-500' : usedPct >= 70 ? 'bg-amber-500' : 'bg-green-500'}`}
+              style={{ width: `${usedPct}%` }}
+            />
+          </div>
+        )}
+
+        <div className="grid grid-cols-1 md:grid-cols-3 gap-4 mb-4">
+          <Input label="Daily limit (USDC)" type="number" step="0.01" min={0} value={daily} onChange={(e) => setDaily(e.target.value)} placeholder="No limit" />
+          <Input label="Per session (USDC)" type="number" step="0.01" min={0} value={session} onChange={(e) => setSession(e.target.value)} placeholder="No limit" />
+          <Input label="Per cron job per day (USDC)" type="number" step="0.01" min={0} value={cronJob} onChange={(e) => setCronJob(e.target.value)} placeholder="No limit" />
+        </div>
+
+        <label className="flex items-center gap-2 text-sm text-slate-300 mb-4">
+          <input type="checkbox" checked={fallback} onChange={(e) => setFallback(e.target.checked)} />
+          Fall back to a cheaper endpoint of the same archetype instead of stopping
+        </label>
+
+        {data?.cron_jobs && data.cron_jobs.length > 0 && (
+          <div className="mb-4 text-sm">
+            <p className="text-slate-400 mb-1">Cron jobs today</p>
+            {data.cron_jobs.map((job) => (
+              <div key={job.cron_job_id} className="flex justify-between text-slate-300">
+                <span className="font-mono">{job.cron_job_id}</span>
+                <span>
+                  ${job.spent_usdc}
+                  {job.remaining_usdc !== null && <span className="text-slate-500"> (${job.remaining_usdc} left)</span>}
+                </span>
+              </div>
+            ))}
+          </div>
+        )}
+
+        {error && <p className="text-sm text-red-400 mb-2">{error}</p>}
+        <Button size="sm" onClick={handleSave} isLoading={isSaving}>
+          <Save className="w-4 h-4 mr-2" />
+          Save Budget
+        </Button>
+      </CardContent>
+    </Card>
+  );
+}
+
 export default function Payments() {
   const [filter, setFilter] = useState<'all' | 'with_feedback' | 'without_feedback'>('all');
   const { data: paymentsData, isLoading: paymentsLoading, refetch: refetchPayments } = useApi<PaymentsResponse>('/payments');
   const { data: summaryData, isLoading: summaryLoading, refetch: refetchSummary } = useApi<SummaryResponse>('/payments/summary');
+  const { data: budgetData, refetch: refetchBudget } = useApi<BudgetResponse>('/payments/budget');
 
   // Poll every 5 seconds
   useEffect(() => {
@@ -173,6 +300,8 @@ export default function Payments() {
         </Card>
       </div>
 
+      <BudgetCard data={budgetData} onSaved={refetchBudget} />
+
       {/* Filter Tabs */}
       <div className="flex gap-2 mb-6">
         <Button
@@ -221,6 +221,12 @@ function CronJobsTab({ jobs, setJobs, showCreateForm, setShowCreateForm, setErro
         return `Every ${Math.round(ms / 1000)}s`;
       case 'cron':
         return job.schedule_value;
+      case 'event':
+        try {
+          return `On ${JSON.parse(job.schedule_value).kind}`;
+        } catch {
+          return job.schedule_value;
+        }
       default:
         return job.schedule_value;
     }
@@ -271,6 +277,7 @@ function CronJobsTab({ jobs, setJobs, showCreateForm, setShowCreateForm, setErro
                     <option value="every">Every (interval)</option>
                     <option value="at">At (one-time)</option>
                     <option value="cron">Cron Expression</option>
+                    <option value="event">Event Trigger</option>
                   </select>
                 </div>
               </div>
@@ -302,10 +309,22 @@ function CronJobsTab({ jobs, setJobs, showCreateForm, setShowCreateForm, setErro
                 </div>
               ) : (
                 <Input
-                  label={formData.schedule_type === 'cron' ? 'Cron Expression' : 'Run At (ISO date)'}
+                  label={
+                    formData.schedule_type === 'cron'
+                      ? 'Cron Expression'
+                      : formData.schedule_type === 'event'
+                        ? 'Trigger (JSON)'
+                        : 'Run At (ISO date)'
+                  }
                   value={formData.schedule_value}
                   onChange={(e) => setFormData({ ...formData, schedule_value: e.target.value })}
-                  placeholder={formData.schedule_type === 'cron' ? '0 0 * * *' : '2024-12-31T12:00:00Z'}
+                  placeholder={
+                    formData.schedule_type === 'cron'
+                      ? '0 0 * * *'
+                      : formData.schedule_type === 'event'
+                        ? '{"kind": "price", "token": "WETH", "above": 4000}'
+                        : '2024-12-31T12:00:00Z'
+                  }
                   required
                 />
               )}
@@ -257,7 +257,7 @@ export interface CronJob {
   job_id: string;
   name: string;
   description?: string;
-  schedule_type: 'at' | 'every' | 'cron';
+  schedule_type: 'at' | 'every' | 'cron' | 'event';
   schedule_value: string;
   timezone?: string;
   session_mode: 'main' | 'isolated';